@@ -40,6 +40,18 @@ impl JsonRpcBatch {
         Ok(())
     }
 
+    pub fn add_dry_run_transaction_request(
+        &mut self,
+        transaction: SignedTransaction,
+    ) -> Result<()> {
+        let txn_payload = hex::encode(lcs::to_bytes(&transaction)?);
+        self.add_request(
+            "dry_run_transaction".to_string(),
+            vec![Value::String(txn_payload)],
+        );
+        Ok(())
+    }
+
     pub fn add_get_account_state_request(&mut self, address: AccountAddress) {
         self.add_request(
             "get_account_state".to_string(),
@@ -47,6 +59,33 @@ impl JsonRpcBatch {
         );
     }
 
+    pub fn add_get_account_state_at_version_request(
+        &mut self,
+        address: AccountAddress,
+        version: u64,
+    ) {
+        self.add_request(
+            "get_account_state".to_string(),
+            vec![Value::String(address.to_string()), json!(version)],
+        );
+    }
+
+    pub fn add_get_accounts_request(
+        &mut self,
+        addresses: &[AccountAddress],
+        include_proofs: bool,
+        version: Option<u64>,
+    ) {
+        let addresses: Vec<_> = addresses
+            .iter()
+            .map(|address| address.to_string())
+            .collect();
+        self.add_request(
+            "get_accounts".to_string(),
+            vec![json!(addresses), json!(include_proofs), json!(version)],
+        );
+    }
+
     pub fn add_get_metadata_request(&mut self, version: Option<u64>) {
         self.add_request("get_metadata".to_string(), vec![json!(version)]);
     }
@@ -83,6 +122,61 @@ impl JsonRpcBatch {
         );
     }
 
+    /// Like `add_get_account_transaction_request`, but responded to with the transaction along
+    /// with the proof of it.
+    pub fn add_get_account_transaction_with_proof_request(
+        &mut self,
+        account: AccountAddress,
+        sequence: u64,
+        include_events: bool,
+    ) {
+        self.add_request(
+            "get_account_transaction_with_proof".to_string(),
+            vec![
+                json!(account.to_string()),
+                json!(sequence),
+                json!(include_events),
+            ],
+        );
+    }
+
+    /// Like `add_get_account_transaction_request`, but responded to with the transaction pending
+    /// in mempool if it's not committed yet.
+    pub fn add_get_account_transaction_or_pending_request(
+        &mut self,
+        account: AccountAddress,
+        sequence: u64,
+        include_events: bool,
+    ) {
+        self.add_request(
+            "get_account_transaction".to_string(),
+            vec![
+                json!(account.to_string()),
+                json!(sequence),
+                json!(include_events),
+                json!(true),
+            ],
+        );
+    }
+
+    pub fn add_get_mempool_transaction_status_request(
+        &mut self,
+        account: AccountAddress,
+        sequence: u64,
+    ) {
+        self.add_request(
+            "get_mempool_transaction_status".to_string(),
+            vec![json!(account.to_string()), json!(sequence)],
+        );
+    }
+
+    pub fn add_get_mempool_transaction_status_by_hash_request(&mut self, hash: String) {
+        self.add_request(
+            "get_mempool_transaction_status_by_hash".to_string(),
+            vec![json!(hash)],
+        );
+    }
+
     pub fn add_get_events_request(&mut self, event_key: String, start: u64, limit: u64) {
         self.add_request(
             "get_events".to_string(),
@@ -90,10 +184,49 @@ impl JsonRpcBatch {
         );
     }
 
+    pub fn add_get_events_with_proofs_request(
+        &mut self,
+        event_key: String,
+        start: u64,
+        limit: u64,
+    ) {
+        self.add_request(
+            "get_events_with_proofs".to_string(),
+            vec![json!(event_key), json!(start), json!(limit)],
+        );
+    }
+
+    pub fn add_get_events_page_request(
+        &mut self,
+        event_key: String,
+        cursor: Option<String>,
+        limit: u64,
+        start_version: Option<u64>,
+        end_version: Option<u64>,
+    ) {
+        self.add_request(
+            "get_events_page".to_string(),
+            vec![
+                json!(event_key),
+                json!(cursor),
+                json!(limit),
+                json!(start_version),
+                json!(end_version),
+            ],
+        );
+    }
+
     pub fn add_get_state_proof_request(&mut self, known_version: u64) {
         self.add_request("get_state_proof".to_string(), vec![json!(known_version)]);
     }
 
+    pub fn add_get_epoch_change_proof_request(&mut self, start_epoch: u64, end_epoch: u64) {
+        self.add_request(
+            "get_epoch_change_proof".to_string(),
+            vec![json!(start_epoch), json!(end_epoch)],
+        );
+    }
+
     pub fn add_get_account_state_with_proof_request(
         &mut self,
         account: AccountAddress,
@@ -2,8 +2,10 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::views::{
-    AccountStateWithProofView, AccountView, BlockMetadata, CurrencyInfoView, EventView,
-    StateProofView, TransactionView,
+    AccountStateWithProofView, AccountTransactionView, AccountView, AccountWithStateView,
+    BlockMetadata, CurrencyInfoView, DryRunResultView, EpochChangeProofView, EventView,
+    EventWithProofView, EventsPageView, MempoolTransactionStatusView, PendingTransactionView,
+    StateProofView, TransactionView, TransactionWithProofView,
 };
 use anyhow::{ensure, format_err, Error, Result};
 
@@ -14,11 +16,19 @@ use std::convert::TryFrom;
 #[derive(Clone, PartialEq, Debug)]
 pub enum JsonRpcResponse {
     SubmissionResponse,
+    DryRunResponse(DryRunResultView),
     AccountResponse(Option<AccountView>),
+    AccountsResponse(Vec<AccountWithStateView>),
     StateProofResponse(StateProofView),
+    EpochChangeProofResponse(EpochChangeProofView),
     AccountTransactionResponse(Option<TransactionView>),
+    AccountTransactionWithProofResponse(Option<TransactionWithProofView>),
+    PendingTransactionResponse(PendingTransactionView),
+    MempoolTransactionStatusResponse(Option<MempoolTransactionStatusView>),
     TransactionsResponse(Vec<TransactionView>),
     EventsResponse(Vec<EventView>),
+    EventsWithProofsResponse(Vec<EventWithProofView>),
+    EventsPageResponse(EventsPageView),
     BlockMetadataResponse(BlockMetadata),
     CurrenciesResponse(Vec<CurrencyInfoView>),
     AccountStateWithProofResponse(AccountStateWithProofView),
@@ -39,6 +49,10 @@ impl TryFrom<(String, Value)> for JsonRpcResponse {
                 );
                 Ok(JsonRpcResponse::SubmissionResponse)
             }
+            "dry_run_transaction" => {
+                let result: DryRunResultView = serde_json::from_value(value)?;
+                Ok(JsonRpcResponse::DryRunResponse(result))
+            }
             "get_account_state" => {
                 let account = match value {
                     Value::Null => None,
@@ -49,10 +63,22 @@ impl TryFrom<(String, Value)> for JsonRpcResponse {
                 };
                 Ok(JsonRpcResponse::AccountResponse(account))
             }
+            "get_accounts" => {
+                let accounts: Vec<AccountWithStateView> = serde_json::from_value(value)?;
+                Ok(JsonRpcResponse::AccountsResponse(accounts))
+            }
             "get_events" => {
                 let events: Vec<EventView> = serde_json::from_value(value)?;
                 Ok(JsonRpcResponse::EventsResponse(events))
             }
+            "get_events_with_proofs" => {
+                let events: Vec<EventWithProofView> = serde_json::from_value(value)?;
+                Ok(JsonRpcResponse::EventsWithProofsResponse(events))
+            }
+            "get_events_page" => {
+                let page: EventsPageView = serde_json::from_value(value)?;
+                Ok(JsonRpcResponse::EventsPageResponse(page))
+            }
             "get_metadata" => {
                 let metadata: BlockMetadata = serde_json::from_value(value)?;
                 Ok(JsonRpcResponse::BlockMetadataResponse(metadata))
@@ -71,15 +97,47 @@ impl TryFrom<(String, Value)> for JsonRpcResponse {
                 let state_proof: StateProofView = serde_json::from_value(value)?;
                 Ok(JsonRpcResponse::StateProofResponse(state_proof))
             }
+            "get_epoch_change_proof" => {
+                let proof: EpochChangeProofView = serde_json::from_value(value)?;
+                Ok(JsonRpcResponse::EpochChangeProofResponse(proof))
+            }
             "get_account_transaction" => {
                 let txn = match value {
                     Value::Null => None,
                     _ => {
-                        let txn: TransactionView = serde_json::from_value(value)?;
+                        let txn: AccountTransactionView = serde_json::from_value(value)?;
+                        Some(txn)
+                    }
+                };
+                match txn {
+                    Some(AccountTransactionView::Pending(txn)) => {
+                        Ok(JsonRpcResponse::PendingTransactionResponse(txn))
+                    }
+                    Some(AccountTransactionView::Committed(txn)) => {
+                        Ok(JsonRpcResponse::AccountTransactionResponse(Some(txn)))
+                    }
+                    None => Ok(JsonRpcResponse::AccountTransactionResponse(None)),
+                }
+            }
+            "get_account_transaction_with_proof" => {
+                let txn = match value {
+                    Value::Null => None,
+                    _ => {
+                        let txn: TransactionWithProofView = serde_json::from_value(value)?;
                         Some(txn)
                     }
                 };
-                Ok(JsonRpcResponse::AccountTransactionResponse(txn))
+                Ok(JsonRpcResponse::AccountTransactionWithProofResponse(txn))
+            }
+            "get_mempool_transaction_status" | "get_mempool_transaction_status_by_hash" => {
+                let status = match value {
+                    Value::Null => None,
+                    _ => {
+                        let status: MempoolTransactionStatusView = serde_json::from_value(value)?;
+                        Some(status)
+                    }
+                };
+                Ok(JsonRpcResponse::MempoolTransactionStatusResponse(status))
             }
             "get_transactions" => {
                 let txns: Vec<TransactionView> = serde_json::from_value(value)?;
@@ -116,6 +174,16 @@ pub trait ResponseAsView: Sized {
     }
 }
 
+impl ResponseAsView for DryRunResultView {
+    fn from_response(response: JsonRpcResponse) -> Result<Self> {
+        if let JsonRpcResponse::DryRunResponse(result) = response {
+            Ok(result)
+        } else {
+            Self::unexpected_response_error::<Self>(response)
+        }
+    }
+}
+
 impl ResponseAsView for AccountView {
     fn optional_from_response(response: JsonRpcResponse) -> Result<Option<Self>> {
         if let JsonRpcResponse::AccountResponse(view) = response {
@@ -126,6 +194,16 @@ impl ResponseAsView for AccountView {
     }
 }
 
+impl ResponseAsView for AccountWithStateView {
+    fn vec_from_response(response: JsonRpcResponse) -> Result<Vec<Self>> {
+        if let JsonRpcResponse::AccountsResponse(accounts) = response {
+            Ok(accounts)
+        } else {
+            Self::unexpected_response_error::<Vec<Self>>(response)
+        }
+    }
+}
+
 impl ResponseAsView for EventView {
     fn vec_from_response(response: JsonRpcResponse) -> Result<Vec<Self>> {
         if let JsonRpcResponse::EventsResponse(events) = response {
@@ -136,6 +214,26 @@ impl ResponseAsView for EventView {
     }
 }
 
+impl ResponseAsView for EventWithProofView {
+    fn vec_from_response(response: JsonRpcResponse) -> Result<Vec<Self>> {
+        if let JsonRpcResponse::EventsWithProofsResponse(events) = response {
+            Ok(events)
+        } else {
+            Self::unexpected_response_error::<Vec<Self>>(response)
+        }
+    }
+}
+
+impl ResponseAsView for EventsPageView {
+    fn from_response(response: JsonRpcResponse) -> Result<Self> {
+        if let JsonRpcResponse::EventsPageResponse(page) = response {
+            Ok(page)
+        } else {
+            Self::unexpected_response_error::<Self>(response)
+        }
+    }
+}
+
 impl ResponseAsView for BlockMetadata {
     fn from_response(response: JsonRpcResponse) -> Result<Self> {
         if let JsonRpcResponse::BlockMetadataResponse(metadata) = response {
@@ -174,6 +272,36 @@ impl ResponseAsView for TransactionView {
     }
 }
 
+impl ResponseAsView for TransactionWithProofView {
+    fn optional_from_response(response: JsonRpcResponse) -> Result<Option<Self>> {
+        if let JsonRpcResponse::AccountTransactionWithProofResponse(view) = response {
+            Ok(view)
+        } else {
+            Self::unexpected_response_error::<Option<Self>>(response)
+        }
+    }
+}
+
+impl ResponseAsView for PendingTransactionView {
+    fn from_response(response: JsonRpcResponse) -> Result<Self> {
+        if let JsonRpcResponse::PendingTransactionResponse(view) = response {
+            Ok(view)
+        } else {
+            Self::unexpected_response_error::<Self>(response)
+        }
+    }
+}
+
+impl ResponseAsView for MempoolTransactionStatusView {
+    fn optional_from_response(response: JsonRpcResponse) -> Result<Option<Self>> {
+        if let JsonRpcResponse::MempoolTransactionStatusResponse(view) = response {
+            Ok(view)
+        } else {
+            Self::unexpected_response_error::<Option<Self>>(response)
+        }
+    }
+}
+
 impl ResponseAsView for StateProofView {
     fn from_response(response: JsonRpcResponse) -> Result<Self> {
         if let JsonRpcResponse::StateProofResponse(view) = response {
@@ -184,6 +312,16 @@ impl ResponseAsView for StateProofView {
     }
 }
 
+impl ResponseAsView for EpochChangeProofView {
+    fn from_response(response: JsonRpcResponse) -> Result<Self> {
+        if let JsonRpcResponse::EpochChangeProofResponse(view) = response {
+            Ok(view)
+        } else {
+            Self::unexpected_response_error::<Self>(response)
+        }
+    }
+}
+
 impl ResponseAsView for AccountStateWithProofView {
     fn from_response(response: JsonRpcResponse) -> Result<Self> {
         if let JsonRpcResponse::AccountStateWithProofResponse(resp) = response {
@@ -0,0 +1,408 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use anyhow::{bail, ensure, Result};
+use libra_crypto::HashValue;
+use libra_json_rpc_client::{
+    views::{
+        AccountStateWithProofView, AccountView, BlockMetadata, EventView, EventWithProofView,
+        StateProofView, TransactionDataView, TransactionView, TransactionWithProofView,
+    },
+    JsonRpcAsyncClient, JsonRpcBatch, JsonRpcResponse, ResponseAsView,
+};
+use libra_retrier::{exp_retry_strategy, retry_async};
+use libra_types::{
+    account_address::AccountAddress,
+    account_config::AccountResource,
+    account_state_blob::{AccountStateBlob, AccountStateWithProof},
+    contract_event::EventWithProof,
+    epoch_change::EpochChangeProof,
+    event::EventKey,
+    ledger_info::LedgerInfoWithSignatures,
+    transaction::{SignedTransaction, TransactionWithProof},
+    trusted_state::{TrustedState, TrustedStateChange},
+    vm_status::StatusCode,
+    waypoint::Waypoint,
+};
+use move_core_types::identifier::Identifier;
+use reqwest::Url;
+use std::{
+    convert::TryFrom,
+    sync::{Mutex, MutexGuard},
+    time::{Duration, Instant},
+};
+
+/// How often `wait_for_transaction` checks whether the transaction is committed
+const WAIT_POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// How requests which fail to reach the node are retried: after `start_delay_ms`, then after
+/// twice as long each time, up to `max_delay_ms`, for at most `max_retries` times.
+#[derive(Clone, Copy, Debug)]
+pub struct RetryConfig {
+    pub start_delay_ms: u64,
+    pub max_delay_ms: u64,
+    pub max_retries: usize,
+}
+
+impl Default for RetryConfig {
+    fn default() -> Self {
+        Self {
+            start_delay_ms: 100,
+            max_delay_ms: 5_000,
+            max_retries: 5,
+        }
+    }
+}
+
+/// How waiting for a transaction to be committed ended
+#[allow(clippy::large_enum_variant)]
+#[derive(Clone, Debug, PartialEq)]
+pub enum TransactionOutcome {
+    /// The transaction is committed, and executed successfully.
+    Executed(TransactionView),
+    /// The transaction is committed, but failed executing: only its gas is charged.
+    VmFailure(TransactionView),
+    /// Another transaction with the same sender and sequence number is committed instead.
+    Replaced(TransactionView),
+    /// The expiration time of the transaction passed on chain before it was committed, so it
+    /// never will be.
+    Expired,
+    /// Timed out waiting, with transactions of the sender before this one still not committed.
+    SequenceNumberGap { account_sequence_number: u64 },
+    /// Timed out waiting, though nothing is known to keep the transaction from being committed.
+    TimedOut,
+}
+
+/// Client of a full node, verifying what it gets against its trusted state.
+///
+/// Every request comes with the latest ledger info of the node and the epoch changes leading to
+/// it, which are verified against the trusted state and ratchet it. The data of the response is
+/// checked to be what the ledger info commits to when the node returns a proof for it; otherwise
+/// it's checked to be consistent with the ledger info.
+pub struct Client {
+    rpc: JsonRpcAsyncClient,
+    trusted_state: Mutex<TrustedState>,
+    retry: RetryConfig,
+}
+
+/// The responses to the requests of a batch, along with the state proof they come with
+struct VerifiableResponses {
+    responses: Vec<Result<JsonRpcResponse>>,
+    ledger_info: LedgerInfoWithSignatures,
+    epoch_change_proof: EpochChangeProof,
+    /// The trusted state the state proof was asked relative to
+    trusted_state: TrustedState,
+}
+
+impl VerifiableResponses {
+    /// Takes the response to the first request of the batch not taken yet
+    fn next(&mut self) -> Result<JsonRpcResponse> {
+        ensure!(!self.responses.is_empty(), "Missing response in batch");
+        self.responses.remove(0)
+    }
+
+    /// Verifies the ledger info, returning the trusted state it ratchets to if it's newer
+    fn verify_ledger_info(&self) -> Result<Option<TrustedState>> {
+        let change = self
+            .trusted_state
+            .verify_and_ratchet(&self.ledger_info, &self.epoch_change_proof)?;
+        Ok(new_trusted_state(change))
+    }
+
+    fn ledger_version(&self) -> u64 {
+        self.ledger_info.ledger_info().version()
+    }
+}
+
+impl Client {
+    /// Creates a client of the JSON RPC endpoint at `url`, which trusts the ledger from `waypoint`
+    /// on
+    pub fn new(url: Url, waypoint: Waypoint) -> Self {
+        Self {
+            rpc: JsonRpcAsyncClient::new(url),
+            trusted_state: Mutex::new(TrustedState::from(waypoint)),
+            retry: RetryConfig::default(),
+        }
+    }
+
+    pub fn with_retry(mut self, retry: RetryConfig) -> Self {
+        self.retry = retry;
+        self
+    }
+
+    /// The latest ledger state verified so far
+    pub fn trusted_state(&self) -> TrustedState {
+        self.lock_trusted_state().clone()
+    }
+
+    /// Submits `txn` to the mempool of the node
+    pub async fn submit(&self, txn: SignedTransaction) -> Result<()> {
+        let mut batch = JsonRpcBatch::new();
+        batch.add_submit_request(txn)?;
+        let mut responses = self.execute(batch).await?;
+        let change = responses.verify_ledger_info()?;
+        match responses.next()? {
+            JsonRpcResponse::SubmissionResponse => (),
+            response => bail!("Received non-submit response payload: {:?}", response),
+        }
+        self.ratchet(change);
+        Ok(())
+    }
+
+    /// Waits for the transaction sent by `sender` with `sequence_number` and `hash` to be
+    /// committed, for at most `timeout`, or until it can't be anymore.
+    ///
+    /// The committed transaction and the account of the sender are verified with their proofs.
+    /// The expiration time of the transaction is learnt from mempool while it's pending there, to
+    /// tell once it's passed on chain.
+    pub async fn wait_for_transaction(
+        &self,
+        sender: AccountAddress,
+        sequence_number: u64,
+        hash: HashValue,
+        timeout: Duration,
+    ) -> Result<TransactionOutcome> {
+        let start = Instant::now();
+        let mut expiration_time_secs = None;
+        loop {
+            let mut batch = JsonRpcBatch::new();
+            batch.add_get_account_transaction_with_proof_request(sender, sequence_number, true);
+            batch.add_get_account_transaction_or_pending_request(sender, sequence_number, false);
+            batch.add_get_account_state_with_proof_request(sender, None, None);
+            let mut responses = self.execute(batch).await?;
+            let change = responses.verify_ledger_info()?;
+            let txn = TransactionWithProofView::optional_from_response(responses.next()?)?;
+            let pending = responses.next()?;
+            let account_state = AccountStateWithProof::try_from(
+                &AccountStateWithProofView::from_response(responses.next()?)?,
+            )?;
+            let ledger_info = responses.ledger_info.ledger_info();
+
+            account_state.verify(ledger_info, ledger_info.version(), sender)?;
+            let account_sequence_number = match &account_state.blob {
+                Some(blob) => AccountResource::try_from(blob)?.sequence_number(),
+                None => 0,
+            };
+            let outcome = match txn {
+                Some(txn) => {
+                    let txn = TransactionWithProof::try_from(&txn)?;
+                    ensure!(txn.events.is_some(), "Missing events of the transaction");
+                    txn.verify_user_txn(ledger_info, txn.version, sender, sequence_number)?;
+                    let txn = TransactionView::from(txn);
+                    Some(if txn.hash != hash.to_hex() {
+                        TransactionOutcome::Replaced(txn)
+                    } else if txn.vm_status == StatusCode::EXECUTED {
+                        TransactionOutcome::Executed(txn)
+                    } else {
+                        TransactionOutcome::VmFailure(txn)
+                    })
+                }
+                None => {
+                    match pending {
+                        JsonRpcResponse::PendingTransactionResponse(pending) => {
+                            if pending.hash == hash.to_hex() {
+                                if let TransactionDataView::UserTransaction {
+                                    expiration_time,
+                                    ..
+                                } = pending.transaction
+                                {
+                                    expiration_time_secs = Some(expiration_time);
+                                }
+                            }
+                        }
+                        // Only what's proven to be committed is taken as such.
+                        JsonRpcResponse::AccountTransactionResponse(_) => (),
+                        response => {
+                            bail!("Received non-transaction response payload: {:?}", response)
+                        }
+                    }
+                    None
+                }
+            };
+            let outcome = outcome.or_else(|| {
+                let ledger_time_secs = ledger_info.timestamp_usecs() / 1_000_000;
+                if expiration_time_secs.map_or(false, |expiration| ledger_time_secs >= expiration) {
+                    Some(TransactionOutcome::Expired)
+                } else if start.elapsed() < timeout {
+                    None
+                } else if account_sequence_number < sequence_number {
+                    Some(TransactionOutcome::SequenceNumberGap {
+                        account_sequence_number,
+                    })
+                } else {
+                    Some(TransactionOutcome::TimedOut)
+                }
+            });
+            self.ratchet(change);
+
+            match outcome {
+                Some(outcome) => return Ok(outcome),
+                None => tokio::time::delay_for(WAIT_POLL_INTERVAL).await,
+            }
+        }
+    }
+
+    /// Returns the account at `address` in the latest state, verified with its proof
+    pub async fn get_account(&self, address: AccountAddress) -> Result<Option<AccountView>> {
+        let mut batch = JsonRpcBatch::new();
+        batch.add_get_account_state_request(address);
+        batch.add_get_account_state_with_proof_request(address, None, None);
+        let mut responses = self.execute(batch).await?;
+        let account = AccountView::optional_from_response(responses.next()?)?;
+        let account_state = AccountStateWithProof::try_from(
+            &AccountStateWithProofView::from_response(responses.next()?)?,
+        )?;
+
+        ensure!(
+            account_state.version == responses.ledger_version(),
+            "Account state at version {}, instead of the ledger info version {}",
+            account_state.version,
+            responses.ledger_version(),
+        );
+        let change = responses.trusted_state.verify_account_state(
+            &responses.ledger_info,
+            &responses.epoch_change_proof,
+            &account_state,
+            account_state.version,
+            address,
+        )?;
+        let new_state = new_trusted_state(change);
+        verify_account(&account, &account_state.blob)?;
+        self.ratchet(new_state);
+        Ok(account)
+    }
+
+    /// Returns the events of the stream with `event_key`, in order, `limit` of them at most
+    /// starting from `start_seq_num`, each verified with its proof
+    pub async fn get_events(
+        &self,
+        event_key: &EventKey,
+        start_seq_num: u64,
+        limit: u64,
+    ) -> Result<Vec<EventView>> {
+        let mut batch = JsonRpcBatch::new();
+        batch.add_get_events_with_proofs_request(format!("{:x}", event_key), start_seq_num, limit);
+        let mut responses = self.execute(batch).await?;
+        let change = responses.verify_ledger_info()?;
+        let events = EventWithProofView::vec_from_response(responses.next()?)?;
+        ensure!(
+            events.len() as u64 <= limit,
+            "Received {} events, more than the {} asked for",
+            events.len(),
+            limit
+        );
+        let events = events
+            .iter()
+            .zip(start_seq_num..)
+            .map(|(event, seq_num)| {
+                let event = EventWithProof::try_from(event)?;
+                event.verify(
+                    responses.ledger_info.ledger_info(),
+                    event_key,
+                    seq_num,
+                    event.transaction_version,
+                    event.event_index,
+                )?;
+                Ok(EventView::from((event.transaction_version, event.event)))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        self.ratchet(change);
+        Ok(events)
+    }
+
+    /// Returns the version and timestamp of the latest ledger info
+    pub async fn get_metadata(&self) -> Result<BlockMetadata> {
+        let mut batch = JsonRpcBatch::new();
+        batch.add_get_metadata_request(None);
+        let mut responses = self.execute(batch).await?;
+        let change = responses.verify_ledger_info()?;
+        let metadata = BlockMetadata::from_response(responses.next()?)?;
+        let ledger_info = responses.ledger_info.ledger_info();
+        ensure!(
+            metadata.version == ledger_info.version()
+                && metadata.timestamp == ledger_info.timestamp_usecs(),
+            "Metadata {:?} doesn't match the ledger info",
+            metadata,
+        );
+        self.ratchet(change);
+        Ok(metadata)
+    }
+
+    /// Executes `batch` along with a state proof relative to the trusted state, retrying if the
+    /// node can't be reached
+    async fn execute(&self, mut batch: JsonRpcBatch) -> Result<VerifiableResponses> {
+        let trusted_state = self.trusted_state();
+        batch.add_get_state_proof_request(trusted_state.latest_version());
+
+        let rpc = &self.rpc;
+        let retry = exp_retry_strategy(
+            self.retry.start_delay_ms,
+            self.retry.max_delay_ms,
+            self.retry.max_retries,
+        );
+        let mut responses = retry_async(retry, || {
+            let batch = batch.clone();
+            Box::pin(async move { rpc.execute(batch).await })
+        })
+        .await?;
+
+        let state_proof = match responses.pop() {
+            Some(state_proof) => StateProofView::from_response(state_proof?)?,
+            None => bail!("Missing state proof in batch"),
+        };
+        Ok(VerifiableResponses {
+            responses,
+            ledger_info: lcs::from_bytes(&state_proof.ledger_info_with_signatures.into_bytes()?)?,
+            epoch_change_proof: lcs::from_bytes(&state_proof.epoch_change_proof.into_bytes()?)?,
+            trusted_state,
+        })
+    }
+
+    /// Moves the trusted state to the one verified, unless a more recent one was verified in the
+    /// meantime by a concurrent request
+    fn ratchet(&self, new_state: Option<TrustedState>) {
+        if let Some(new_state) = new_state {
+            let mut trusted_state = self.lock_trusted_state();
+            if new_state.latest_version() > trusted_state.latest_version() {
+                *trusted_state = new_state;
+            }
+        }
+    }
+
+    fn lock_trusted_state(&self) -> MutexGuard<TrustedState> {
+        self.trusted_state
+            .lock()
+            .expect("[client sdk] failed to acquire trusted state lock")
+    }
+}
+
+fn new_trusted_state(change: TrustedStateChange) -> Option<TrustedState> {
+    match change {
+        TrustedStateChange::Version { new_state } | TrustedStateChange::Epoch { new_state, .. } => {
+            Some(new_state)
+        }
+        TrustedStateChange::NoChange => None,
+    }
+}
+
+/// Checks that `account` is the account stored in `blob`, in the currencies it has balances in.
+fn verify_account(account: &Option<AccountView>, blob: &Option<AccountStateBlob>) -> Result<()> {
+    let expected = match blob {
+        Some(blob) => {
+            let currencies = account
+                .iter()
+                .flat_map(|account| &account.balances)
+                .map(|balance| Identifier::new(balance.currency.clone()))
+                .collect::<Result<Vec<_>>>()?;
+            AccountView::try_from_blob(blob, &currencies)?
+        }
+        None => None,
+    };
+    ensure!(
+        account == &expected,
+        "Account {:?} doesn't match the account state proven {:?}",
+        account,
+        expected
+    );
+    Ok(())
+}
@@ -0,0 +1,306 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{Client, TransactionOutcome};
+use libra_crypto::{
+    ed25519::Ed25519PrivateKey,
+    hash::{
+        CryptoHash, EventAccumulatorHasher, TransactionAccumulatorHasher,
+        SPARSE_MERKLE_PLACEHOLDER_HASH,
+    },
+    HashValue, PrivateKey, Uniform,
+};
+use libra_json_rpc_client::views::{
+    AccountStateWithProofView, EventView, EventWithProofView, StateProofView,
+    TransactionWithProofView,
+};
+use libra_types::{
+    account_address::AccountAddress,
+    account_state_blob::AccountStateWithProof,
+    block_info::BlockInfo,
+    contract_event::{ContractEvent, EventWithProof},
+    epoch_change::EpochChangeProof,
+    epoch_state::EpochState,
+    event::EventKey,
+    ledger_info::{LedgerInfo, LedgerInfoWithSignatures},
+    proof::{
+        accumulator::InMemoryAccumulator, AccountStateProof, AccumulatorConsistencyProof,
+        EventAccumulatorProof, EventProof, SparseMerkleProof, TransactionAccumulatorProof,
+        TransactionInfoWithProof,
+    },
+    test_helpers::transaction_test_helpers::get_test_signed_txn,
+    transaction::{Transaction, TransactionInfo, TransactionWithProof},
+    validator_signer::ValidatorSigner,
+    validator_verifier::ValidatorVerifier,
+    vm_status::StatusCode,
+    waypoint::Waypoint,
+};
+use move_core_types::language_storage::TypeTag;
+use reqwest::Url;
+use serde_json::{json, Value};
+use std::{collections::BTreeMap, convert::TryFrom, time::Duration};
+use tokio::runtime::Runtime;
+use warp::Filter;
+
+const GAS_USED: u64 = 100;
+
+/// How the mock node tampers with its responses
+#[derive(Clone, Copy, PartialEq)]
+enum Tamper {
+    Nothing,
+    /// Changes the data of the event, keeping its proof.
+    EventData,
+    /// Changes the gas used by the transaction, keeping the accumulator proof of its info.
+    GasUsed,
+}
+
+/// A ledger of two transactions: the genesis at version 0, which ends epoch 0, and a user
+/// transaction emitting an event at version 1, committed to by the validator of epoch 1.
+#[derive(Clone)]
+struct MockLedger {
+    waypoint: Waypoint,
+    epoch_change_li: LedgerInfoWithSignatures,
+    latest_li: LedgerInfoWithSignatures,
+    sender: AccountAddress,
+    txn: Transaction,
+    txn_info: TransactionInfo,
+    txn_info_proof: TransactionAccumulatorProof,
+    event: ContractEvent,
+}
+
+impl MockLedger {
+    fn new() -> Self {
+        let signer = ValidatorSigner::random(None);
+        let genesis_info = TransactionInfo::new(
+            HashValue::zero(),
+            *SPARSE_MERKLE_PLACEHOLDER_HASH,
+            HashValue::zero(),
+            0,
+            StatusCode::EXECUTED,
+        );
+        let epoch_change_li = LedgerInfo::new(
+            BlockInfo::new(
+                0,
+                0,
+                HashValue::zero(),
+                genesis_info.hash(),
+                0,
+                0,
+                Some(EpochState {
+                    epoch: 1,
+                    verifier: ValidatorVerifier::new_single(signer.author(), signer.public_key()),
+                }),
+            ),
+            HashValue::zero(),
+        );
+
+        let sender = AccountAddress::random();
+        let private_key = Ed25519PrivateKey::generate_for_testing();
+        let txn = Transaction::UserTransaction(get_test_signed_txn(
+            sender,
+            0,
+            &private_key,
+            private_key.public_key(),
+            None,
+        ));
+        let event = ContractEvent::new(
+            EventKey::new_from_address(&sender, 0),
+            0,
+            TypeTag::Bool,
+            vec![],
+        );
+        let txn_info = TransactionInfo::new(
+            txn.hash(),
+            // The state is left empty, so that the sender is proven not to exist.
+            *SPARSE_MERKLE_PLACEHOLDER_HASH,
+            InMemoryAccumulator::<EventAccumulatorHasher>::from_leaves(&[event.hash()]).root_hash(),
+            GAS_USED,
+            StatusCode::EXECUTED,
+        );
+        let latest_li = LedgerInfo::new(
+            BlockInfo::new(
+                1,
+                1,
+                HashValue::zero(),
+                InMemoryAccumulator::<TransactionAccumulatorHasher>::from_leaves(&[
+                    genesis_info.hash(),
+                    txn_info.hash(),
+                ])
+                .root_hash(),
+                1,
+                1_000_000,
+                None,
+            ),
+            HashValue::zero(),
+        );
+        let mut signatures = BTreeMap::new();
+        signatures.insert(signer.author(), signer.sign(&latest_li));
+
+        Self {
+            waypoint: Waypoint::new_epoch_boundary(&epoch_change_li).unwrap(),
+            epoch_change_li: LedgerInfoWithSignatures::new(epoch_change_li, BTreeMap::new()),
+            latest_li: LedgerInfoWithSignatures::new(latest_li, signatures),
+            sender,
+            txn,
+            txn_info,
+            txn_info_proof: TransactionAccumulatorProof::new(vec![genesis_info.hash()]),
+            event,
+        }
+    }
+
+    /// The result the node responds to a request for `method` with
+    fn result(&self, method: &str, tamper: Tamper) -> Value {
+        let txn_info_with_proof = || {
+            let txn_info = if tamper == Tamper::GasUsed {
+                TransactionInfo::new(
+                    self.txn_info.transaction_hash(),
+                    self.txn_info.state_root_hash(),
+                    self.txn_info.event_root_hash(),
+                    GAS_USED / 2,
+                    StatusCode::EXECUTED,
+                )
+            } else {
+                self.txn_info.clone()
+            };
+            TransactionInfoWithProof::new(self.txn_info_proof.clone(), txn_info)
+        };
+
+        match method {
+            "get_state_proof" => json!(StateProofView::try_from((
+                self.latest_li.clone(),
+                EpochChangeProof::new(vec![self.epoch_change_li.clone()], false),
+                AccumulatorConsistencyProof::new(vec![]),
+            ))
+            .unwrap()),
+            "get_events_with_proofs" => {
+                let event = if tamper == Tamper::EventData {
+                    ContractEvent::new(*self.event.key(), 0, TypeTag::Bool, vec![1])
+                } else {
+                    self.event.clone()
+                };
+                let event = EventWithProof::new(
+                    1,
+                    0,
+                    event,
+                    EventProof::new(txn_info_with_proof(), EventAccumulatorProof::new(vec![])),
+                );
+                json!(vec![EventWithProofView::try_from(&event).unwrap()])
+            }
+            "get_account_transaction_with_proof" => json!(TransactionWithProofView::try_from(
+                &TransactionWithProof::new(
+                    1,
+                    self.txn.clone(),
+                    Some(vec![self.event.clone()]),
+                    txn_info_with_proof(),
+                )
+            )
+            .unwrap()),
+            // Nothing is pending in mempool.
+            "get_account_transaction" => Value::Null,
+            "get_account_state_with_proof" => json!(AccountStateWithProofView::try_from(
+                AccountStateWithProof::new(
+                    1,
+                    None,
+                    AccountStateProof::new(
+                        TransactionInfoWithProof::new(
+                            self.txn_info_proof.clone(),
+                            self.txn_info.clone()
+                        ),
+                        SparseMerkleProof::new(None, vec![]),
+                    ),
+                )
+            )
+            .unwrap()),
+            method => panic!("Unexpected request for {}", method),
+        }
+    }
+}
+
+/// Serves `ledger` as a JSON RPC endpoint, returning its URL
+fn serve(runtime: &Runtime, ledger: MockLedger, tamper: Tamper) -> Url {
+    let route = warp::post()
+        .and(warp::body::json())
+        .map(move |requests: Vec<Value>| {
+            let responses = requests
+                .iter()
+                .map(|request| {
+                    json!({
+                        "jsonrpc": "2.0",
+                        "id": request["id"],
+                        "result": ledger.result(request["method"].as_str().unwrap(), tamper),
+                    })
+                })
+                .collect::<Vec<_>>();
+            warp::reply::json(&responses)
+        });
+    let (address, server) =
+        runtime.enter(move || warp::serve(route).bind_ephemeral(([127, 0, 0, 1], 0)));
+    runtime.spawn(server);
+    Url::parse(&format!("http://{}", address)).unwrap()
+}
+
+fn setup(tamper: Tamper) -> (Runtime, MockLedger, Client) {
+    let runtime = Runtime::new().unwrap();
+    let ledger = MockLedger::new();
+    let client = Client::new(serve(&runtime, ledger.clone(), tamper), ledger.waypoint);
+    (runtime, ledger, client)
+}
+
+#[test]
+fn test_get_events() {
+    let (mut runtime, ledger, client) = setup(Tamper::Nothing);
+
+    let events = runtime
+        .block_on(client.get_events(ledger.event.key(), 0, 10))
+        .unwrap();
+    assert_eq!(events, vec![EventView::from((1, ledger.event))]);
+    assert_eq!(client.trusted_state().latest_version(), 1);
+}
+
+#[test]
+fn test_get_events_tampered() {
+    let (mut runtime, ledger, client) = setup(Tamper::EventData);
+
+    assert!(runtime
+        .block_on(client.get_events(ledger.event.key(), 0, 10))
+        .is_err());
+    // Nothing is trusted from a response failing verification.
+    assert_eq!(client.trusted_state().latest_version(), 0);
+}
+
+#[test]
+fn test_wait_for_transaction() {
+    let (mut runtime, ledger, client) = setup(Tamper::Nothing);
+
+    let outcome = runtime
+        .block_on(client.wait_for_transaction(
+            ledger.sender,
+            0,
+            ledger.txn.hash(),
+            Duration::from_secs(1),
+        ))
+        .unwrap();
+    match outcome {
+        TransactionOutcome::Executed(txn) => {
+            assert_eq!(txn.version, 1);
+            assert_eq!(txn.gas_used, GAS_USED);
+            assert_eq!(txn.events, vec![EventView::from((1, ledger.event))]);
+        }
+        outcome => panic!("Unexpected outcome {:?}", outcome),
+    }
+}
+
+#[test]
+fn test_wait_for_transaction_tampered() {
+    let (mut runtime, ledger, client) = setup(Tamper::GasUsed);
+
+    assert!(runtime
+        .block_on(client.wait_for_transaction(
+            ledger.sender,
+            0,
+            ledger.txn.hash(),
+            Duration::from_secs(1),
+        ))
+        .is_err());
+    assert_eq!(client.trusted_state().latest_version(), 0);
+}
@@ -0,0 +1,18 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+#![forbid(unsafe_code)]
+
+//! Async client of the JSON RPC interface of full nodes, checking the responses against the
+//! ledger info it trusts.
+//!
+//! The `Client` starts from a waypoint, and each request it makes is batched with a state proof
+//! relative to its trusted state, which the response is verified and the trusted state ratcheted
+//! with. Requests failing to reach the node are retried with exponential backoff.
+
+mod client;
+#[cfg(test)]
+mod client_test;
+
+pub use client::{Client, RetryConfig, TransactionOutcome};
+pub use libra_json_rpc_client::views;
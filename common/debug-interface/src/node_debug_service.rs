@@ -4,9 +4,10 @@
 //! Debug interface to access information in a specific node.
 
 use libra_logger::json_log;
-use std::net::SocketAddr;
+use network::peer_reputation::PeerReputations;
+use std::{collections::HashMap, net::SocketAddr};
 use tokio::runtime::{Builder, Runtime};
-use warp::Filter;
+use warp::{Filter, Rejection, Reply};
 
 #[derive(Debug)]
 pub struct NodeDebugService {
@@ -14,7 +15,9 @@ pub struct NodeDebugService {
 }
 
 impl NodeDebugService {
-    pub fn new(address: SocketAddr) -> Self {
+    /// Serves the debug interface on `address`, including the reputations of the peers of each of
+    /// the `peer_reputations` networks.
+    pub fn new(address: SocketAddr, peer_reputations: Vec<PeerReputations>) -> Self {
         let runtime = Builder::new()
             .thread_name("nodedebug-")
             .threaded_scheduler()
@@ -22,14 +25,7 @@ impl NodeDebugService {
             .build()
             .expect("[rpc] failed to create runtime");
 
-        // GET /metrics
-        let metrics =
-            warp::path("metrics").map(|| warp::reply::json(&libra_metrics::get_all_metrics()));
-
-        // GET /evnets
-        let events = warp::path("events").map(|| warp::reply::json(&json_log::pop_last_entries()));
-
-        let routes = warp::get().and(metrics.or(events));
+        let routes = routes(peer_reputations);
 
         let server = runtime.enter(move || warp::serve(routes).bind(address));
         runtime.handle().spawn(server);
@@ -37,3 +33,69 @@ impl NodeDebugService {
         Self { runtime }
     }
 }
+
+fn routes(
+    peer_reputations: Vec<PeerReputations>,
+) -> impl Filter<Extract = impl Reply, Error = Rejection> + Clone {
+    // GET /metrics
+    let metrics =
+        warp::path("metrics").map(|| warp::reply::json(&libra_metrics::get_all_metrics()));
+
+    // GET /evnets
+    let events = warp::path("events").map(|| warp::reply::json(&json_log::pop_last_entries()));
+
+    // GET /peer_reputations
+    let peer_reputations = warp::path("peer_reputations").map(move || {
+        let reputations: HashMap<_, _> = peer_reputations
+            .iter()
+            .map(|reputations| (reputations.network_context().to_string(), reputations.all()))
+            .collect();
+        warp::reply::json(&reputations)
+    });
+
+    warp::get().and(metrics.or(events).or(peer_reputations))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use libra_config::{config::PeerReputationConfig, network_id::NetworkContext};
+    use libra_types::PeerId;
+    use network::peer_reputation::Misbehavior;
+
+    #[tokio::test]
+    async fn test_get_peer_reputations() {
+        let config = PeerReputationConfig {
+            enabled: true,
+            ..PeerReputationConfig::default()
+        };
+        let peer_reputations = PeerReputations::new(NetworkContext::mock(), config.clone());
+        let peer_id = PeerId::random();
+        peer_reputations.report(peer_id, Misbehavior::RpcTimeout);
+        peer_reputations.record_rpc_success(peer_id);
+        let idle_reputations = PeerReputations::new(NetworkContext::mock(), config);
+
+        let response = warp::test::request()
+            .path("/peer_reputations")
+            .reply(&routes(vec![
+                peer_reputations.clone(),
+                idle_reputations.clone(),
+            ]))
+            .await;
+        assert_eq!(response.status(), 200);
+
+        let reputations: serde_json::Value = serde_json::from_slice(response.body()).unwrap();
+        let network_reputations = &reputations[peer_reputations.network_context().to_string()];
+        assert_eq!(
+            network_reputations,
+            &serde_json::to_value(peer_reputations.all()).unwrap()
+        );
+        let reputation = &network_reputations[peer_id.to_string()];
+        assert_eq!(reputation["rpc_timeouts"], 1);
+        assert_eq!(reputation["rpc_successes"], 1);
+        assert_eq!(
+            reputations[idle_reputations.network_context().to_string()],
+            serde_json::json!({})
+        );
+    }
+}
@@ -54,6 +54,15 @@ pub fn fixed_retry_strategy(delay_ms: u64, tries: usize) -> impl Iterator<Item =
     FixedDelay::new(delay_ms).take(tries)
 }
 
+/// Delays starting from `start_ms`, doubled on each retry and capped at `limit_ms`.
+pub fn exp_retry_strategy(
+    start_ms: u64,
+    limit_ms: u64,
+    tries: usize,
+) -> impl Iterator<Item = Duration> {
+    ExponentWithLimitDelay::new(start_ms, limit_ms).take(tries)
+}
+
 /// An iterator which uses a fixed delay
 pub struct FixedDelay {
     duration: Duration,
@@ -76,6 +85,32 @@ impl Iterator for FixedDelay {
     }
 }
 
+/// An iterator which doubles the delay each time, up to a limit
+pub struct ExponentWithLimitDelay {
+    current: Duration,
+    limit: Duration,
+}
+
+impl ExponentWithLimitDelay {
+    /// Create a new `ExponentWithLimitDelay` using the given durations in milliseconds.
+    fn new(start_ms: u64, limit_ms: u64) -> Self {
+        ExponentWithLimitDelay {
+            current: Duration::from_millis(start_ms),
+            limit: Duration::from_millis(limit_ms),
+        }
+    }
+}
+
+impl Iterator for ExponentWithLimitDelay {
+    type Item = Duration;
+
+    fn next(&mut self) -> Option<Duration> {
+        let duration = std::cmp::min(self.current, self.limit);
+        self.current = duration * 2;
+        Some(duration)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -102,4 +137,15 @@ mod tests {
         });
         assert_eq!(result, Err("not 5"));
     }
+
+    #[test]
+    fn test_exp_retry_strategy() {
+        assert_eq!(
+            exp_retry_strategy(100, 500, 5).collect::<Vec<_>>(),
+            vec![100, 200, 400, 500, 500]
+                .into_iter()
+                .map(Duration::from_millis)
+                .collect::<Vec<_>>(),
+        );
+    }
 }
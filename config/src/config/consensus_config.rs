@@ -10,6 +10,8 @@ use std::{collections::HashMap, path::PathBuf};
 #[serde(default, deny_unknown_fields)]
 pub struct ConsensusConfig {
     pub contiguous_rounds: u32,
+    // number of past epochs whose blocks and quorum certs are kept in the consensus DB
+    pub consensusdb_retained_epochs: u64,
     pub max_block_size: u64,
     pub max_pruned_blocks_in_mem: usize,
     pub round_initial_timeout_ms: u64,
@@ -21,6 +23,7 @@ impl Default for ConsensusConfig {
     fn default() -> ConsensusConfig {
         ConsensusConfig {
             contiguous_rounds: 2,
+            consensusdb_retained_epochs: 1,
             max_block_size: 1000,
             max_pruned_blocks_in_mem: 10000,
             round_initial_timeout_ms: 1000,
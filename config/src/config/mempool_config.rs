@@ -7,10 +7,19 @@ use serde::{Deserialize, Serialize};
 #[serde(default, deny_unknown_fields)]
 pub struct MempoolConfig {
     pub capacity: usize,
+    // total size of transactions in Mempool, in bytes
+    pub capacity_bytes: usize,
     pub capacity_per_user: usize,
     pub max_broadcasts_per_peer: usize,
+    // how much higher, in percent, the gas price of a transaction replacing a pending one with
+    // the same sender and sequence number has to be
+    pub min_gas_price_bump_percentage: u64,
+    pub shared_mempool_ack_timeout_ms: u64,
     pub shared_mempool_backoff_interval_ms: u64,
+    // max number of broadcasts to a peer that haven't been ACK'ed for yet
+    pub shared_mempool_backpressure_window: usize,
     pub shared_mempool_batch_size: usize,
+    pub shared_mempool_batch_bytes: usize,
     pub shared_mempool_max_concurrent_inbound_syncs: usize,
     pub shared_mempool_tick_interval_ms: u64,
     pub system_transaction_timeout_secs: u64,
@@ -21,11 +30,16 @@ impl Default for MempoolConfig {
     fn default() -> MempoolConfig {
         MempoolConfig {
             shared_mempool_tick_interval_ms: 50,
+            shared_mempool_ack_timeout_ms: 2_000,
             shared_mempool_backoff_interval_ms: 30_000,
+            shared_mempool_backpressure_window: 8,
             shared_mempool_batch_size: 100,
+            shared_mempool_batch_bytes: 1_000_000,
             shared_mempool_max_concurrent_inbound_syncs: 100,
             max_broadcasts_per_peer: 25,
+            min_gas_price_bump_percentage: 10,
             capacity: 1_000_000,
+            capacity_bytes: 2_000_000_000,
             capacity_per_user: 100,
             system_transaction_timeout_secs: 86400,
             system_transaction_gc_interval_ms: 180_000,
@@ -2,13 +2,13 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
-    config::{Error, RoleType, SecureBackend},
+    config::{Error, RoleType, SecureBackend, TokenBucketConfig},
     keys::KeyPair,
     network_id::NetworkId,
     utils,
 };
-use libra_crypto::{x25519, Uniform};
-use libra_network_address::NetworkAddress;
+use libra_crypto::{ed25519::Ed25519PublicKey, x25519, Uniform};
+use libra_network_address::{parse_dns_tcp, parse_ip_tcp, NetworkAddress};
 use libra_secure_storage::{CryptoStorage, KVStorage, Storage};
 use libra_types::{transaction::authenticator::AuthenticationKey, PeerId};
 use rand::{
@@ -17,8 +17,9 @@ use rand::{
 };
 use serde::{Deserialize, Serialize};
 use std::{
-    collections::{HashMap, HashSet},
+    collections::{BTreeMap, HashMap, HashSet},
     convert::{TryFrom, TryInto},
+    path::PathBuf,
     string::ToString,
 };
 
@@ -56,6 +57,22 @@ pub struct NetworkConfig {
     // in case some peers don't have well defined addresses.
     pub seed_pubkeys: SeedPublicKeys,
     pub max_frame_size: usize,
+    // Scoring of peers on their behavior, to disconnect and ban the misbehaving ones.
+    pub peer_reputation: PeerReputationConfig,
+    // Limits on the bytes sent for each protocol over a connection, keyed by protocol name,
+    // e.g. "StateSynchronizerDirectSend": each byte takes a token. Protocols not listed aren't
+    // limited.
+    pub bandwidth_limits: BTreeMap<String, TokenBucketConfig>,
+    // Proxy to dial outbound connections through, for nodes which can't reach their peers
+    // directly, e.g. behind a corporate NAT.
+    pub proxy: Option<ProxyConfig>,
+    // The address other nodes can reach this node at, when it differs from the listen_address,
+    // e.g. the public address of the NAT forwarding connections to it. When set, discovery
+    // advertises it in place of the address of its own config.
+    pub advertised_address: Option<NetworkAddress>,
+    // A signed file of seed peers to periodically reload and merge with the other discovery
+    // sources, so operators can push peer lists without a reconfiguration transaction.
+    pub seed_file: Option<SeedFileConfig>,
 }
 
 impl Default for NetworkConfig {
@@ -76,6 +93,11 @@ impl NetworkConfig {
             seed_pubkeys: HashMap::default(),
             seed_addrs: HashMap::default(),
             max_frame_size: 8 * 1024 * 1024, // TODO use constant
+            peer_reputation: PeerReputationConfig::default(),
+            bandwidth_limits: BTreeMap::new(),
+            proxy: None,
+            advertised_address: None,
+            seed_file: None,
         };
         config.prepare_identity();
         config
@@ -96,6 +118,11 @@ impl NetworkConfig {
             seed_pubkeys: self.seed_pubkeys.clone(),
             seed_addrs: self.seed_addrs.clone(),
             max_frame_size: self.max_frame_size,
+            peer_reputation: self.peer_reputation.clone(),
+            bandwidth_limits: self.bandwidth_limits.clone(),
+            proxy: self.proxy.clone(),
+            advertised_address: self.advertised_address.clone(),
+            seed_file: self.seed_file.clone(),
         }
     }
 
@@ -208,6 +235,49 @@ impl NetworkConfig {
         }
         Ok(())
     }
+
+    /// Check that the proxy and advertised addresses are plain TCP addresses
+    pub fn verify_external_addrs(&self) -> Result<(), Error> {
+        if let Some(proxy) = &self.proxy {
+            crate::config::invariant(
+                is_tcp_addr(proxy.address()),
+                format!("Unexpected proxy address format: '{}'", proxy.address()),
+            )?;
+        }
+        if let Some(addr) = &self.advertised_address {
+            crate::config::invariant(
+                is_tcp_addr(addr),
+                format!("Unexpected advertised address format: '{}'", addr),
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Whether `addr` is an `/ip4`, `/ip6` or `/dns` address followed by `/tcp` and nothing else.
+fn is_tcp_addr(addr: &NetworkAddress) -> bool {
+    let protos = addr.as_slice();
+    parse_ip_tcp(protos)
+        .map(|(_, suffix)| suffix.is_empty())
+        .or_else(|| parse_dns_tcp(protos).map(|(_, suffix)| suffix.is_empty()))
+        .unwrap_or(false)
+}
+
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ProxyConfig {
+    // A SOCKS5 proxy accepting unauthenticated clients.
+    Socks5(NetworkAddress),
+    // An HTTP proxy supporting the CONNECT method.
+    HttpConnect(NetworkAddress),
+}
+
+impl ProxyConfig {
+    pub fn address(&self) -> &NetworkAddress {
+        match self {
+            ProxyConfig::Socks5(addr) | ProxyConfig::HttpConnect(addr) => addr,
+        }
+    }
 }
 
 #[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
@@ -244,6 +314,62 @@ pub struct GossipConfig {
     pub discovery_interval_ms: u64,
 }
 
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct SeedFileConfig {
+    pub location: SeedFileLocation,
+    // The key the seed peers must be signed with. Files with a missing or invalid signature are
+    // ignored, and the peers of the last valid file are kept.
+    pub signing_public_key: Ed25519PublicKey,
+    pub reload_interval_ms: u64,
+}
+
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SeedFileLocation {
+    Path(PathBuf),
+    // An http(s) URL to GET the file from.
+    Url(String),
+}
+
+/// Off by default. When enabled, every peer starts with `max_score`, which each misbehavior lowers
+/// by its penalty and each rpc answered in time raises back by `rpc_success_reward`. A peer is
+/// disconnected once its score is at or below `disconnect_threshold`, and also refused
+/// connections for `ban_duration_ms` once it is at or below `ban_threshold`.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct PeerReputationConfig {
+    pub enabled: bool,
+    pub max_score: i64,
+    // Penalty for a message that fails to deserialize.
+    pub invalid_message_penalty: i64,
+    // Penalty for a well-formed message the peer wasn't supposed to send.
+    pub protocol_violation_penalty: i64,
+    // Penalty for an outbound rpc the peer doesn't answer in time. Along with the reward, this
+    // makes the score drop for peers timing out on more than a third of the rpcs by default.
+    pub rpc_timeout_penalty: i64,
+    pub rpc_success_reward: i64,
+    pub disconnect_threshold: i64,
+    pub ban_threshold: i64,
+    pub ban_duration_ms: u64,
+}
+
+impl Default for PeerReputationConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            max_score: 100,
+            invalid_message_penalty: 20,
+            protocol_violation_penalty: 50,
+            rpc_timeout_penalty: 2,
+            rpc_success_reward: 1,
+            disconnect_threshold: 0,
+            ban_threshold: -100,
+            ban_duration_ms: 10 * 60 * 1000, /* 10 minutes */
+        }
+    }
+}
+
 #[cfg_attr(any(test, feature = "fuzzing"), derive(Clone, PartialEq))]
 #[derive(Debug, Deserialize, Serialize)]
 #[serde(rename_all = "snake_case", tag = "type")]
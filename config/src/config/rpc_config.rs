@@ -3,12 +3,22 @@
 
 use crate::utils;
 use serde::{Deserialize, Serialize};
-use std::net::SocketAddr;
+use std::{collections::BTreeMap, net::SocketAddr, path::PathBuf};
 
 #[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
 #[serde(default, deny_unknown_fields)]
 pub struct RpcConfig {
     pub address: SocketAddr,
+    /// Certificate and private key to serve JSON RPC over HTTPS with, instead of HTTP.
+    pub tls: Option<TlsConfig>,
+    pub cors: CorsConfig,
+    pub rate_limit: RateLimitConfig,
+    pub subscriptions: SubscriptionConfig,
+    pub response_cache: ResponseCacheConfig,
+    pub dry_run: DryRunConfig,
+    /// How far behind, in seconds, the latest ledger info can be for the node to be ready to
+    /// serve requests, as reported at `/-/ready`.
+    pub ready_max_sync_lag_secs: u64,
 }
 
 pub const DEFAULT_JSON_RPC_PORT: u16 = 8080;
@@ -19,6 +29,13 @@ impl Default for RpcConfig {
             address: format!("0.0.0.0:{}", DEFAULT_JSON_RPC_PORT)
                 .parse()
                 .unwrap(),
+            tls: None,
+            cors: CorsConfig::default(),
+            rate_limit: RateLimitConfig::default(),
+            subscriptions: SubscriptionConfig::default(),
+            response_cache: ResponseCacheConfig::default(),
+            dry_run: DryRunConfig::default(),
+            ready_max_sync_lag_secs: 60,
         }
     }
 }
@@ -28,3 +45,143 @@ impl RpcConfig {
         self.address.set_port(utils::get_available_port());
     }
 }
+
+/// PEM files of the certificate chain and private key of the JSON RPC server.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct TlsConfig {
+    pub cert_path: PathBuf,
+    pub key_path: PathBuf,
+}
+
+/// Cross-origin resource sharing policy, for browsers to allow web pages to make requests to
+/// JSON RPC.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct CorsConfig {
+    /// Origins of the web pages allowed, e.g. `http://localhost:3000`, or `*` for any. CORS is
+    /// disabled if empty.
+    pub allowed_origins: Vec<String>,
+    /// How long, in seconds, browsers can keep what's allowed for.
+    pub max_age_secs: u64,
+}
+
+impl Default for CorsConfig {
+    fn default() -> CorsConfig {
+        CorsConfig {
+            allowed_origins: Vec::new(),
+            max_age_secs: 600,
+        }
+    }
+}
+
+/// Limits on the rate of JSON RPC requests, tracked in a token bucket per client IP and method.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct RateLimitConfig {
+    pub enabled: bool,
+    /// Budget of each client for transaction submissions.
+    pub submit: TokenBucketConfig,
+    /// Budget of each client for each of the other methods.
+    pub read: TokenBucketConfig,
+    /// Budgets overriding the above for the given methods, including `ws_connect` for opening
+    /// WebSocket connections to subscribe over.
+    pub methods: BTreeMap<String, TokenBucketConfig>,
+    /// Maximum number of buckets kept track of, past which requests from new clients are
+    /// rejected until the buckets of idle clients are refilled and dropped.
+    pub max_tracked_buckets: usize,
+}
+
+impl Default for RateLimitConfig {
+    fn default() -> RateLimitConfig {
+        RateLimitConfig {
+            enabled: false,
+            submit: TokenBucketConfig {
+                capacity: 10,
+                refill_per_sec: 2,
+            },
+            read: TokenBucketConfig {
+                capacity: 200,
+                refill_per_sec: 100,
+            },
+            methods: BTreeMap::new(),
+            max_tracked_buckets: 100_000,
+        }
+    }
+}
+
+/// A bucket holding up to `capacity` tokens, refilled at `refill_per_sec` tokens a second. Each
+/// request takes a token, and is rejected if there's none left.
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct TokenBucketConfig {
+    pub capacity: u64,
+    pub refill_per_sec: u64,
+}
+
+/// Limits on the subscriptions served over WebSocket, which hold on to server resources for as
+/// long as they are open.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct SubscriptionConfig {
+    /// Maximum number of WebSocket connections open at a time, past which new ones are rejected.
+    pub max_connections: usize,
+    /// Maximum number of subscriptions open at a time, across all connections.
+    pub max_subscriptions: usize,
+    /// Maximum number of subscriptions open at a time on a single connection.
+    pub max_subscriptions_per_connection: usize,
+}
+
+impl Default for SubscriptionConfig {
+    fn default() -> SubscriptionConfig {
+        SubscriptionConfig {
+            max_connections: 1_000,
+            max_subscriptions: 10_000,
+            max_subscriptions_per_connection: 100,
+        }
+    }
+}
+
+/// In-memory cache of the responses which can't change once what they return is committed, such
+/// as transactions by version.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ResponseCacheConfig {
+    /// Maximum number of responses cached, least recently used ones being evicted first. 0
+    /// disables the cache.
+    pub capacity: usize,
+    /// How long, in seconds, HTTP caches are told they can keep these responses for.
+    pub max_age_secs: u64,
+}
+
+impl Default for ResponseCacheConfig {
+    fn default() -> ResponseCacheConfig {
+        ResponseCacheConfig {
+            capacity: 10_000,
+            max_age_secs: 86_400,
+        }
+    }
+}
+
+/// Limits on the execution of the transactions run by `dry_run_transaction`, which anyone can
+/// submit without paying for the gas, on top of the gas limits of the transactions themselves.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct DryRunConfig {
+    /// Maximum number of instructions interpreted.
+    pub max_instructions: u64,
+    /// Maximum number of nested calls to Move functions.
+    pub max_call_depth: usize,
+    /// Maximum abstract memory size of a value an instruction operates on.
+    pub max_value_size: u64,
+}
+
+impl Default for DryRunConfig {
+    fn default() -> DryRunConfig {
+        DryRunConfig {
+            max_instructions: 1_000_000,
+            max_call_depth: 256,
+            max_value_size: 1_000_000,
+        }
+    }
+}
@@ -2,14 +2,21 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
 
 #[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
 #[serde(default, deny_unknown_fields)]
 pub struct StateSyncConfig {
     // Size of chunk to request for state synchronization
     pub chunk_limit: u64,
+    // bootstraps an empty DB at the waypoint from backups, instead of replaying all transactions
+    // since genesis
+    pub fast_sync: Option<FastSyncConfig>,
     // default timeout used for long polling to remote peer
     pub long_poll_timeout_ms: u64,
+    // maximum number of chunk requests in flight at once, for consecutive chunks requested from
+    // different upstream peers
+    pub max_concurrent_chunk_requests: u64,
     // valid maximum chunk limit for sanity check
     pub max_chunk_limit: u64,
     // valid maximum timeout limit for sanity check
@@ -24,7 +31,9 @@ impl Default for StateSyncConfig {
     fn default() -> Self {
         Self {
             chunk_limit: 250,
+            fast_sync: None,
             long_poll_timeout_ms: 30000,
+            max_concurrent_chunk_requests: 4,
             max_chunk_limit: 1000,
             max_timeout_ms: 120_000,
             sync_request_timeout_ms: 60_000,
@@ -32,3 +41,39 @@ impl Default for StateSyncConfig {
         }
     }
 }
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct FastSyncConfig {
+    // backup storage holding the backups to bootstrap from
+    pub backup_storage: BackupStorageConfig,
+    // YAML config of the secure storage holding the key the backups are encrypted with, if they
+    // are
+    pub encryption_key_storage: Option<PathBuf>,
+    // name of the key in the secure storage
+    pub encryption_key_name: String,
+    // number of chunks to download and verify concurrently, ahead of writing them to the DB
+    pub concurrent_downloads: usize,
+}
+
+impl Default for FastSyncConfig {
+    fn default() -> Self {
+        Self {
+            backup_storage: BackupStorageConfig::CommandAdapter {
+                config: PathBuf::from("backup.toml"),
+            },
+            encryption_key_storage: None,
+            encryption_key_name: "backup_encryption_key".to_string(),
+            concurrent_downloads: 8,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case", tag = "type")]
+pub enum BackupStorageConfig {
+    // backups in a local directory
+    LocalFs { dir: PathBuf },
+    // backups accessed through the commands in a command adapter config file
+    CommandAdapter { config: PathBuf },
+}
@@ -15,6 +15,21 @@ pub struct StorageConfig {
     /// None disables pruning. The windows is in number of versions, consider system tps
     /// (transaction per second) when calculating proper window.
     pub prune_window: Option<u64>,
+    /// Like `prune_window`, but for events. None keeps all events.
+    pub event_prune_window: Option<u64>,
+    /// Like `prune_window`, but for transactions. None keeps all transactions.
+    pub transaction_prune_window: Option<u64>,
+    /// Reads slower than this are logged, with what's asked for. None logs no reads.
+    pub slow_read_threshold_ms: Option<u64>,
+    /// Where to move historical data to, e.g. a cheaper disk, keeping only the latest
+    /// `hot_window` versions under `dir`. None keeps everything under `dir`.
+    pub cold_storage_dir: Option<PathBuf>,
+    /// The window is in number of versions, like `prune_window`.
+    pub hot_window: u64,
+    /// Commits within this interval of the last one synced to disk are synced together with a
+    /// later one, saving fsyncs when committing many small chunks, e.g. during state sync. What's
+    /// not yet synced is lost if the machine crashes. None syncs every commit.
+    pub commit_interval_ms: Option<u64>,
     #[serde(skip)]
     data_dir: PathBuf,
 }
@@ -29,6 +44,13 @@ impl Default for StorageConfig {
             // At 100 tps on avg, we keep 4~5 days of history.
             // n.b. Validators have more aggressive override in the config builder.
             prune_window: Some(40_000_000),
+            event_prune_window: None,
+            transaction_prune_window: None,
+            slow_read_threshold_ms: Some(1_000),
+            cold_storage_dir: None,
+            // At 100 tps on avg, we keep about a day on fast disk.
+            hot_window: 10_000_000,
+            commit_interval_ms: None,
             data_dir: PathBuf::from("/opt/libra/data/common"),
         }
     }
@@ -43,6 +65,16 @@ impl StorageConfig {
         }
     }
 
+    pub fn cold_storage_dir(&self) -> Option<PathBuf> {
+        self.cold_storage_dir.as_ref().map(|dir| {
+            if dir.is_relative() {
+                self.data_dir.join(dir)
+            } else {
+                dir.clone()
+            }
+        })
+    }
+
     pub fn set_data_dir(&mut self, data_dir: PathBuf) {
         self.data_dir = data_dir;
     }
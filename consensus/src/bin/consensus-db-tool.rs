@@ -0,0 +1,135 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+#![forbid(unsafe_code)]
+
+use anyhow::Result;
+use consensus::consensusdb::ConsensusDB;
+use consensus_types::{block::Block, quorum_cert::QuorumCert};
+use serde::Serialize;
+use std::{
+    collections::BTreeMap,
+    fs::File,
+    io::{BufWriter, Write},
+    path::{Path, PathBuf},
+};
+use structopt::StructOpt;
+
+#[derive(Debug, StructOpt)]
+#[structopt(about = "Inspect, export and prune the blocks and quorum certs in a consensus DB.")]
+struct Opt {
+    #[structopt(
+        long,
+        parse(from_os_str),
+        help = "Storage directory of the node, holding the consensusdb directory."
+    )]
+    db_dir: PathBuf,
+
+    #[structopt(subcommand)]
+    cmd: Command,
+}
+
+#[derive(Debug, StructOpt)]
+enum Command {
+    #[structopt(about = "Summarize the blocks stored, by epoch.")]
+    List,
+    #[structopt(about = "Export the blocks of epochs before the given one as JSON lines.")]
+    Export {
+        #[structopt(long)]
+        before_epoch: u64,
+        #[structopt(long, parse(from_os_str))]
+        output: PathBuf,
+    },
+    #[structopt(about = "Delete the blocks of epochs before the given one.")]
+    Prune {
+        #[structopt(long)]
+        before_epoch: u64,
+        #[structopt(
+            long,
+            parse(from_os_str),
+            help = "File to export the blocks to before deleting them."
+        )]
+        archive: Option<PathBuf>,
+    },
+}
+
+#[derive(Serialize)]
+struct ArchivedBlock<'a> {
+    block: &'a Block,
+    quorum_cert: &'a Option<QuorumCert>,
+}
+
+fn list(db: &ConsensusDB) -> Result<()> {
+    let (last_vote, highest_timeout_certificate, blocks, quorum_certs) = db.get_data()?;
+    // epoch -> (number of blocks, lowest round, highest round)
+    let mut epochs = BTreeMap::new();
+    for block in blocks.iter() {
+        let entry = epochs
+            .entry(block.epoch())
+            .or_insert((0, block.round(), block.round()));
+        entry.0 += 1;
+        entry.1 = std::cmp::min(entry.1, block.round());
+        entry.2 = std::cmp::max(entry.2, block.round());
+    }
+    for (epoch, (num_blocks, lowest_round, highest_round)) in epochs {
+        println!(
+            "Epoch {}: {} blocks, rounds {} to {}.",
+            epoch, num_blocks, lowest_round, highest_round,
+        );
+    }
+    println!(
+        "{} blocks and {} quorum certs in total.",
+        blocks.len(),
+        quorum_certs.len(),
+    );
+    println!(
+        "Last vote: {}, highest timeout certificate: {}.",
+        if last_vote.is_some() { "yes" } else { "no" },
+        if highest_timeout_certificate.is_some() {
+            "yes"
+        } else {
+            "no"
+        },
+    );
+    Ok(())
+}
+
+fn export(db: &ConsensusDB, before_epoch: u64, output: &Path) -> Result<usize> {
+    let blocks = db.get_blocks_before_epoch(before_epoch)?;
+    let mut writer = BufWriter::new(File::create(output)?);
+    for (block, quorum_cert) in blocks.iter() {
+        serde_json::to_writer(&mut writer, &ArchivedBlock { block, quorum_cert })?;
+        writeln!(writer)?;
+    }
+    writer.flush()?;
+    Ok(blocks.len())
+}
+
+fn main() -> Result<()> {
+    let opt = Opt::from_args();
+    let db = ConsensusDB::new(&opt.db_dir);
+
+    match opt.cmd {
+        Command::List => list(&db)?,
+        Command::Export {
+            before_epoch,
+            output,
+        } => {
+            let num_blocks = export(&db, before_epoch, &output)?;
+            println!("Exported {} blocks to {:?}.", num_blocks, output);
+        }
+        Command::Prune {
+            before_epoch,
+            archive,
+        } => {
+            if let Some(archive) = archive {
+                let num_blocks = export(&db, before_epoch, &archive)?;
+                println!("Archived {} blocks to {:?}.", num_blocks, archive);
+            }
+            let num_blocks = db.prune_epochs_before(before_epoch)?;
+            println!("Pruned {} blocks.", num_blocks);
+        }
+    }
+
+    Ok(())
+}
@@ -4,6 +4,26 @@
 use super::*;
 use consensus_types::block::block_test_utils::certificate_for_genesis;
 use libra_temppath::TempPath;
+use libra_types::{block_info::BlockInfo, epoch_state::EpochState, ledger_info::LedgerInfo};
+
+/// The genesis block of the epoch after `epoch`, along with its quorum cert
+fn genesis_block_after_epoch(epoch: u64) -> (Block, QuorumCert) {
+    let ledger_info = LedgerInfo::new(
+        BlockInfo::new(
+            epoch,
+            0,
+            HashValue::zero(),
+            HashValue::zero(),
+            0,
+            0,
+            Some(EpochState::empty()),
+        ),
+        HashValue::zero(),
+    );
+    let block = Block::make_genesis_block_from_ledger_info(&ledger_info);
+    let qc = QuorumCert::certificate_for_genesis_from_ledger_info(&ledger_info, block.id());
+    (block, qc)
+}
 
 #[test]
 fn test_put_get() {
@@ -48,3 +68,29 @@ fn test_delete_block_and_qc() {
     assert_eq!(db.get_blocks().unwrap().len(), 0);
     assert_eq!(db.get_quorum_certificates().unwrap().len(), 0);
 }
+
+#[test]
+fn test_prune_epochs_before() {
+    let tmp_dir = TempPath::new();
+    let db = ConsensusDB::new(&tmp_dir);
+
+    let (blocks, qcs): (Vec<_>, Vec<_>) = (0..3).map(genesis_block_after_epoch).unzip();
+    db.save_blocks_and_quorum_certificates(blocks.clone(), qcs.clone())
+        .unwrap();
+
+    let old_blocks = db.get_blocks_before_epoch(2).unwrap();
+    assert_eq!(
+        old_blocks,
+        vec![
+            (blocks[0].clone(), Some(qcs[0].clone())),
+            (blocks[1].clone(), Some(qcs[1].clone()))
+        ]
+    );
+
+    assert_eq!(db.prune_epochs_before(2).unwrap(), 2);
+    assert_eq!(db.get_blocks().unwrap().len(), 1);
+    assert!(db.get_blocks().unwrap().contains_key(&blocks[2].id()));
+    assert_eq!(db.get_quorum_certificates().unwrap().len(), 1);
+    assert!(db.get_blocks_before_epoch(2).unwrap().is_empty());
+    assert_eq!(db.prune_epochs_before(2).unwrap(), 0);
+}
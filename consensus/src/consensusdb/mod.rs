@@ -158,6 +158,37 @@ impl ConsensusDB {
         self.commit(batch)
     }
 
+    /// Get the blocks from epochs before `epoch`, along with the quorum certs certifying them,
+    /// sorted by epoch and round, e.g. to archive them before they are pruned.
+    pub fn get_blocks_before_epoch(&self, epoch: u64) -> Result<Vec<(Block, Option<QuorumCert>)>> {
+        let mut quorum_certs = self.get_quorum_certificates()?;
+        let mut blocks = self
+            .get_blocks()?
+            .into_iter()
+            .filter(|(_block_id, block)| block.epoch() < epoch)
+            .map(|(block_id, block)| (block, quorum_certs.remove(&block_id)))
+            .collect::<Vec<_>>();
+        blocks.sort_by_key(|(block, _qc)| (block.epoch(), block.round()));
+        Ok(blocks)
+    }
+
+    /// Delete the blocks from epochs before `epoch` along with the quorum certs certifying them,
+    /// returning the number of blocks deleted. Blocks of past epochs are never needed for
+    /// recovery, as the root of the recovered block tree is in the current epoch.
+    pub fn prune_epochs_before(&self, epoch: u64) -> Result<usize> {
+        let block_ids = self
+            .get_blocks()?
+            .into_iter()
+            .filter(|(_block_id, block)| block.epoch() < epoch)
+            .map(|(block_id, _block)| block_id)
+            .collect::<Vec<_>>();
+        let num_blocks = block_ids.len();
+        if num_blocks > 0 {
+            self.delete_blocks_and_quorum_certificates(block_ids)?;
+        }
+        Ok(num_blocks)
+    }
+
     /// Get all consensus blocks.
     fn get_blocks(&self) -> Result<HashMap<HashValue, Block>> {
         let mut iter = self.db.iter::<BlockSchema>(ReadOptions::default())?;
@@ -117,6 +117,59 @@ pub static TIMEOUT_COUNT: Lazy<IntCounter> = Lazy::new(|| {
     register_int_counter!("libra_consensus_timeout_count", "Count the number of timeouts a node experienced since last restart (close to 0 in happy path).").unwrap()
 });
 
+/// Count of the timeout votes received from each validator since last restart.
+pub static TIMEOUT_VOTES_BY_PEER: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "libra_consensus_timeout_votes_by_peer",
+        "Count of the timeout votes received from each validator since last restart.",
+        &["peer"]
+    )
+    .unwrap()
+});
+
+/// Count of the rounds that timed out since last restart, by the proposer of the round.
+pub static TIMEOUT_ROUNDS_BY_PROPOSER: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "libra_consensus_timeout_rounds_by_proposer",
+        "Count of the rounds that timed out since last restart, by the proposer of the round.",
+        &["proposer"]
+    )
+    .unwrap()
+});
+
+/// Duration between the start of a round and the reception of its proposal.
+pub static PROPOSAL_LATENCY_S: Lazy<DurationHistogram> = Lazy::new(|| {
+    DurationHistogram::new(
+        register_histogram!(
+            "libra_consensus_proposal_latency_s",
+            "Duration between the start of a round and the reception of its proposal."
+        )
+        .unwrap(),
+    )
+});
+
+/// Duration between the first vote received in a round and the QC formed for the round.
+pub static VOTE_COLLECTION_S: Lazy<DurationHistogram> = Lazy::new(|| {
+    DurationHistogram::new(
+        register_histogram!(
+            "libra_consensus_vote_collection_s",
+            "Duration between the first vote received in a round and the QC formed for the round."
+        )
+        .unwrap(),
+    )
+});
+
+/// Duration between the start of a round and the QC formed for the round.
+pub static QC_FORMATION_S: Lazy<DurationHistogram> = Lazy::new(|| {
+    DurationHistogram::new(
+        register_histogram!(
+            "libra_consensus_qc_formation_s",
+            "Duration between the start of a round and the QC formed for the round."
+        )
+        .unwrap(),
+    )
+});
+
 /// The timeout of the current round.
 pub static ROUND_TIMEOUT_MS: Lazy<IntGauge> = Lazy::new(|| {
     register_int_gauge!(
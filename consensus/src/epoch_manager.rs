@@ -27,14 +27,18 @@ use consensus_types::{
     epoch_retrieval::EpochRetrievalRequest,
 };
 use futures::{select, StreamExt};
-use libra_config::config::{ConsensusConfig, ConsensusProposerType, NodeConfig};
+use libra_config::config::{
+    ConsensusConfig, ConsensusProposerType, LeaderReputationConfig, NodeConfig,
+};
 use libra_logger::prelude::*;
 use libra_metrics::monitor;
 use libra_types::{
     account_address::AccountAddress,
     epoch_change::EpochChangeProof,
     epoch_state::EpochState,
-    on_chain_config::{OnChainConfigPayload, ValidatorSet},
+    on_chain_config::{
+        OnChainConfigPayload, OnChainConsensusConfig, ProposerElectionType, ValidatorSet,
+    },
 };
 use network::protocols::network::Event;
 use safety_rules::SafetyRulesManager;
@@ -69,6 +73,8 @@ impl LivenessStorageData {
 pub struct EpochManager {
     author: Author,
     config: ConsensusConfig,
+    // proposer election of the current epoch, the on-chain one if published or the local one
+    proposer_type: ConsensusProposerType,
     time_service: Arc<dyn TimeService>,
     self_sender: channel::Sender<anyhow::Result<Event<ConsensusMsg>>>,
     network_sender: ConsensusNetworkSender,
@@ -93,11 +99,13 @@ impl EpochManager {
     ) -> Self {
         let author = node_config.validator_network.as_ref().unwrap().peer_id();
         let config = node_config.consensus.clone();
+        let proposer_type = config.proposer_type.clone();
         let sr_config = &mut node_config.consensus.safety_rules;
         let safety_rules_manager = SafetyRulesManager::new(sr_config);
         Self {
             author,
             config,
+            proposer_type,
             time_service,
             self_sender,
             network_sender,
@@ -149,7 +157,7 @@ impl EpochManager {
             .verifier
             .get_ordered_account_addresses_iter()
             .collect::<Vec<_>>();
-        match &self.config.proposer_type {
+        match &self.proposer_type {
             ConsensusProposerType::RotatingProposer => Box::new(RotatingProposer::new(
                 proposers,
                 self.config.contiguous_rounds,
@@ -358,6 +366,20 @@ impl EpochManager {
             epoch: payload.epoch(),
             verifier: (&validator_set).into(),
         };
+        self.proposer_type = match payload.get_optional::<OnChainConsensusConfig>() {
+            Ok(Some(consensus_config)) => {
+                local_proposer_type(consensus_config.proposer_election_type)
+            }
+            Ok(None) => self.config.proposer_type.clone(),
+            Err(e) => {
+                crit!(
+                    "Invalid on-chain consensus config in epoch {}, using the local one: {:?}",
+                    payload.epoch(),
+                    e
+                );
+                self.config.proposer_type.clone()
+            }
+        };
 
         match self.storage.start() {
             LivenessStorageData::RecoveryData(initial_data) => {
@@ -528,3 +550,18 @@ impl EpochManager {
         }
     }
 }
+
+/// Converts the proposer election set on chain into its local config counterpart
+fn local_proposer_type(proposer_election_type: ProposerElectionType) -> ConsensusProposerType {
+    match proposer_election_type {
+        ProposerElectionType::FixedProposer => ConsensusProposerType::FixedProposer,
+        ProposerElectionType::RotatingProposer => ConsensusProposerType::RotatingProposer,
+        ProposerElectionType::LeaderReputation {
+            active_weights,
+            inactive_weights,
+        } => ConsensusProposerType::LeaderReputation(LeaderReputationConfig {
+            active_weights,
+            inactive_weights,
+        }),
+    }
+}
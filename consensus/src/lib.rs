@@ -13,7 +13,6 @@
 #![recursion_limit = "512"]
 
 mod block_storage;
-mod consensusdb;
 mod counters;
 mod epoch_manager;
 mod liveness;
@@ -35,6 +34,10 @@ mod util;
 
 /// LibraBFT implementation
 pub mod consensus_provider;
+/// Persistence of the blocks and quorum certs consensus recovers from, public for
+/// consensus-db-tool.
+#[allow(missing_docs)]
+pub mod consensusdb;
 /// LibraNet interface.
 pub mod network_interface;
 
@@ -6,9 +6,14 @@ use crate::{
     pending_votes::{PendingVotes, VoteReceptionResult},
     util::time_service::{SendTask, TimeService},
 };
-use consensus_types::{common::Round, sync_info::SyncInfo, vote::Vote};
+use consensus_types::{
+    common::{Author, Round},
+    sync_info::SyncInfo,
+    vote::Vote,
+};
 use libra_logger::prelude::*;
 use libra_types::validator_verifier::ValidatorVerifier;
+use serde::Serialize;
 use std::{fmt, sync::Arc, time::Duration};
 
 /// A reason for starting a new round: introduced for monitoring / debug purposes.
@@ -48,6 +53,21 @@ impl fmt::Display for NewRoundEvent {
     }
 }
 
+/// A snapshot of the state of the current round, for operators to tell what a round is waiting
+/// for. Durations are measured since the start of the round.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+pub struct RoundStateSnapshot {
+    pub round: Round,
+    pub highest_committed_round: Round,
+    pub elapsed: Duration,
+    pub timeout: Duration,
+    pub proposal_received: Option<Duration>,
+    pub first_vote_received: Option<Duration>,
+    pub num_votes: usize,
+    pub timeout_voters: Vec<Author>,
+    pub vote_sent: bool,
+}
+
 /// Determines the maximum round duration based on the round difference between the current
 /// round and the committed round
 pub trait RoundTimeInterval: Send + Sync + 'static {
@@ -149,6 +169,12 @@ pub struct RoundState {
     pending_votes: PendingVotes,
     // Vote sent locally for the current round.
     vote_sent: Option<Vote>,
+    // When the current round started, as Duration since UNIX_EPOCH.
+    current_round_start: Duration,
+    // When the proposal of the current round was received, since the round started.
+    proposal_received: Option<Duration>,
+    // When the first vote of the current round was received, since the round started.
+    first_vote_received: Option<Duration>,
 }
 
 // this is required by structured log
@@ -185,15 +211,19 @@ impl RoundState {
         counters::TIMEOUT_ROUNDS_COUNT.get();
         counters::TIMEOUT_COUNT.get();
 
+        let now = time_service.get_current_timestamp();
         Self {
             time_interval,
             highest_committed_round: 0,
             current_round: 0,
-            current_round_deadline: time_service.get_current_timestamp(),
+            current_round_deadline: now,
             time_service,
             timeout_sender,
             pending_votes: PendingVotes::new(),
             vote_sent: None,
+            current_round_start: now,
+            proposal_received: None,
+            first_vote_received: None,
         }
     }
 
@@ -227,10 +257,17 @@ impl RoundState {
         }
         let new_round = sync_info.highest_round() + 1;
         if new_round > self.current_round {
+            if self.current_round > 0 {
+                send_struct_log!(StructuredLogEntry::new_named("consensus", "round_end")
+                    .data("round_state", self.snapshot()));
+            }
             // Start a new round.
             self.current_round = new_round;
             self.pending_votes = PendingVotes::new();
             self.vote_sent = None;
+            self.current_round_start = self.time_service.get_current_timestamp();
+            self.proposal_received = None;
+            self.first_vote_received = None;
             let timeout = self.setup_timeout();
             // The new round reason is QCReady in case both QC and TC are equal
             let new_round_reason = if sync_info.highest_timeout_certificate().is_none() {
@@ -255,7 +292,28 @@ impl RoundState {
         verifier: &ValidatorVerifier,
     ) -> VoteReceptionResult {
         if vote.vote_data().proposed().round() == self.current_round {
-            self.pending_votes.insert_vote(vote, verifier)
+            let since_round_start = self.since_round_start();
+            let result = self.pending_votes.insert_vote(vote, verifier);
+            match &result {
+                VoteReceptionResult::VoteAdded(_)
+                | VoteReceptionResult::NewQuorumCertificate(_)
+                | VoteReceptionResult::NewTimeoutCertificate(_) => {
+                    let first_vote_received =
+                        *self.first_vote_received.get_or_insert(since_round_start);
+                    if vote.is_timeout() {
+                        counters::TIMEOUT_VOTES_BY_PEER
+                            .with_label_values(&[&vote.author().short_str()])
+                            .inc();
+                    }
+                    if let VoteReceptionResult::NewQuorumCertificate(_) = result {
+                        counters::QC_FORMATION_S.observe_duration(since_round_start);
+                        counters::VOTE_COLLECTION_S
+                            .observe_duration(since_round_start - first_vote_received);
+                    }
+                }
+                _ => (),
+            }
+            result
         } else {
             VoteReceptionResult::UnexpectedRound(
                 vote.vote_data().proposed().round(),
@@ -274,6 +332,40 @@ impl RoundState {
         self.vote_sent.clone()
     }
 
+    /// Records the reception of the proposal for `round`, if it's the current round
+    pub fn record_proposal(&mut self, round: Round) {
+        if round == self.current_round && self.proposal_received.is_none() {
+            let since_round_start = self.since_round_start();
+            counters::PROPOSAL_LATENCY_S.observe_duration(since_round_start);
+            self.proposal_received = Some(since_round_start);
+        }
+    }
+
+    /// Returns a snapshot of the state of the current round
+    pub fn snapshot(&self) -> RoundStateSnapshot {
+        RoundStateSnapshot {
+            round: self.current_round,
+            highest_committed_round: self.highest_committed_round,
+            elapsed: self.since_round_start(),
+            timeout: self
+                .current_round_deadline
+                .checked_sub(self.current_round_start)
+                .unwrap_or_default(),
+            proposal_received: self.proposal_received,
+            first_vote_received: self.first_vote_received,
+            num_votes: self.pending_votes.num_votes(),
+            timeout_voters: self.pending_votes.timeout_voters(),
+            vote_sent: self.vote_sent.is_some(),
+        }
+    }
+
+    fn since_round_start(&self) -> Duration {
+        self.time_service
+            .get_current_timestamp()
+            .checked_sub(self.current_round_start)
+            .unwrap_or_default()
+    }
+
     /// Setup the timeout task and return the duration of the current timeout
     fn setup_timeout(&mut self) -> Duration {
         let timeout_sender = self.timeout_sender.clone();
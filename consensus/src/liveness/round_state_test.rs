@@ -3,7 +3,8 @@
 
 use crate::{
     liveness::round_state::{
-        ExponentialTimeInterval, NewRoundEvent, NewRoundReason, RoundState, RoundTimeInterval,
+        ExponentialTimeInterval, NewRoundEvent, NewRoundReason, RoundState, RoundStateSnapshot,
+        RoundTimeInterval,
     },
     util::mock_time_service::SimulatedTimeService,
 };
@@ -78,6 +79,38 @@ fn test_round_event_generation() {
     );
 }
 
+#[test]
+fn test_round_state_snapshot() {
+    let (mut pm, _) = make_round_state();
+    pm.process_certificates(generate_sync_info(Some(1), None, None));
+    // A proposal for another round isn't recorded
+    pm.record_proposal(1);
+    let snapshot = pm.snapshot();
+    assert_eq!(snapshot.round, 2);
+    assert_eq!(snapshot.proposal_received, None);
+
+    pm.record_proposal(2);
+    let proposal_received = pm.snapshot().proposal_received;
+    assert!(proposal_received.is_some());
+    // Only the first proposal of the round is recorded
+    pm.record_proposal(2);
+    let snapshot = pm.snapshot();
+    assert_eq!(snapshot.proposal_received, proposal_received);
+    assert_eq!(snapshot.num_votes, 0);
+    assert!(snapshot.timeout_voters.is_empty());
+    assert!(!snapshot.vote_sent);
+
+    // A new round starts over
+    pm.process_certificates(generate_sync_info(Some(2), None, None));
+    let RoundStateSnapshot {
+        round,
+        proposal_received,
+        ..
+    } = pm.snapshot();
+    assert_eq!(round, 3);
+    assert_eq!(proposal_received, None);
+}
+
 fn make_round_state() -> (RoundState, channel::Receiver<Round>) {
     let time_interval = Box::new(ExponentialTimeInterval::fixed(Duration::from_millis(2)));
     let simulated_time = SimulatedTimeService::auto_advance_until(Duration::from_millis(4));
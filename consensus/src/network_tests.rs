@@ -50,7 +50,8 @@ pub struct TwinId {
 /// `NetworkPlayground` mocks the network implementation and provides convenience
 /// methods for testing. Test clients can use `wait_for_messages` or
 /// `deliver_messages` to inspect the direct-send messages sent between peers.
-/// They can also configure network messages to be dropped between specific peers.
+/// They can also configure network messages to be dropped between specific peers,
+/// or to be held back between specific peers until the delay is lifted.
 ///
 /// Currently, RPC messages are delivered immediately and are not controlled by
 /// `wait_for_messages` or `deliver_messages` for delivery. They are also not
@@ -72,6 +73,11 @@ pub struct NetworkPlayground {
     outbound_msgs_rx: mpsc::Receiver<(TwinId, PeerManagerRequest)>,
     /// Allow test code to drop direct-send messages between peers.
     drop_config: Arc<RwLock<DropConfig>>,
+    /// Allow test code to delay direct-send messages between peers.
+    delay_config: Arc<RwLock<DropConfig>>,
+    /// Messages held back by the delay config, along with whether they count
+    /// towards `wait_for_messages` once delivered.
+    delayed_msgs: Vec<(TwinId, TwinId, PeerManagerNotification, bool)>,
     /// An executor for spawning node outbound network event handlers
     executor: Handle,
     // Maps authors to twins IDs
@@ -88,6 +94,8 @@ impl NetworkPlayground {
             outbound_msgs_tx,
             outbound_msgs_rx,
             drop_config: Arc::new(RwLock::new(DropConfig(HashMap::new()))),
+            delay_config: Arc::new(RwLock::new(DropConfig(HashMap::new()))),
+            delayed_msgs: vec![],
             executor,
             author_to_twin_ids: Arc::new(RwLock::new(AuthorToTwinIds(HashMap::new()))),
         }
@@ -188,6 +196,7 @@ impl NetworkPlayground {
             .unwrap()
             .insert(twin_id, consensus_tx);
         self.drop_config.write().unwrap().add_node(twin_id);
+        self.delay_config.write().unwrap().add_node(twin_id);
 
         self.extend_author_to_twin_ids(twin_id.author, twin_id);
 
@@ -244,6 +253,8 @@ impl NetworkPlayground {
     /// copy of all messages for verification.
     /// While all the sent messages are delivered, only the messages that satisfy the given
     /// msg inspector are counted.
+    /// Delayed messages are delivered first once their delay is lifted, in the order they
+    /// were sent.
     pub async fn wait_for_messages<F>(
         &mut self,
         num_messages: usize,
@@ -254,6 +265,18 @@ impl NetworkPlayground {
     {
         let mut msg_copies = vec![];
         while msg_copies.len() < num_messages {
+            if let Some((src_twin_id, dst_twin_id, msg_notif, counted)) =
+                self.take_released_message()
+            {
+                let msg_copy = self
+                    .deliver_message(src_twin_id, dst_twin_id, msg_notif)
+                    .await;
+                if counted && msg_inspector(&msg_copy) {
+                    msg_copies.push(msg_copy);
+                }
+                continue;
+            }
+
             // Take the next queued message
             let (src_twin_id, net_req) = self.outbound_msgs_rx.next().await
                 .expect("[network playground] waiting for messages, but message queue has shutdown unexpectedly");
@@ -279,8 +302,18 @@ impl NetworkPlayground {
                 let msg_notif =
                     PeerManagerNotification::RecvMessage(src_twin_id.author, msg.clone());
 
-                // Deliver and copy message it if it's not dropped
-                if !self.is_message_dropped(&src_twin_id_copy, &dst_twin_id_copy) {
+                // Deliver and copy message it if it's neither dropped nor delayed
+                if self.is_message_dropped(&src_twin_id_copy, &dst_twin_id_copy) {
+                    continue;
+                }
+                if self.is_message_delayed(&src_twin_id_copy, &dst_twin_id_copy) {
+                    self.delayed_msgs.push((
+                        src_twin_id_copy,
+                        dst_twin_id_copy,
+                        msg_notif,
+                        idx == 0,
+                    ));
+                } else {
                     let msg_copy = self
                         .deliver_message(src_twin_id_copy, dst_twin_id_copy, msg_notif)
                         .await;
@@ -373,6 +406,38 @@ impl NetworkPlayground {
             .stop_drop_message_for(src, dst)
     }
 
+    fn is_message_delayed(&self, src_twin_id: &TwinId, dst_twin_id: &TwinId) -> bool {
+        self.delay_config
+            .read()
+            .unwrap()
+            .is_message_dropped(src_twin_id, dst_twin_id)
+    }
+
+    /// Holds back the messages from `src` to `dst` until `stop_delay_message_for` is called.
+    pub fn delay_message_for(&mut self, src: &TwinId, dst: &TwinId) -> bool {
+        self.delay_config
+            .write()
+            .unwrap()
+            .drop_message_for(src, dst)
+    }
+
+    pub fn stop_delay_message_for(&mut self, src: &TwinId, dst: &TwinId) -> bool {
+        self.delay_config
+            .write()
+            .unwrap()
+            .stop_drop_message_for(src, dst)
+    }
+
+    /// Takes the oldest delayed message whose delay has been lifted
+    fn take_released_message(&mut self) -> Option<(TwinId, TwinId, PeerManagerNotification, bool)> {
+        let delay_config = self.delay_config.read().unwrap();
+        let idx = self
+            .delayed_msgs
+            .iter()
+            .position(|(src, dst, _, _)| !delay_config.is_message_dropped(src, dst))?;
+        Some(self.delayed_msgs.remove(idx))
+    }
+
     pub async fn start(mut self) {
         // Take the next queued message
         while let Some((src_twin_id, net_req)) = self.outbound_msgs_rx.next().await {
@@ -187,6 +187,20 @@ impl PendingVotes {
 
         VoteReceptionResult::VoteAdded(voting_power)
     }
+
+    /// Number of validators that voted in this round
+    pub fn num_votes(&self) -> usize {
+        self.author_to_vote.len()
+    }
+
+    /// Validators that sent a timeout vote in this round
+    pub fn timeout_voters(&self) -> Vec<Author> {
+        self.maybe_partial_tc
+            .as_ref()
+            .map_or_else(Vec::new, |partial_tc| {
+                partial_tc.signatures().keys().cloned().collect()
+            })
+    }
 }
 
 //
@@ -274,12 +274,33 @@ impl RecoveryData {
 pub struct StorageWriteProxy {
     db: Arc<ConsensusDB>,
     libra_db: Arc<dyn DbReader>,
+    retained_epochs: u64,
 }
 
 impl StorageWriteProxy {
     pub fn new(config: &NodeConfig, libra_db: Arc<dyn DbReader>) -> Self {
         let db = Arc::new(ConsensusDB::new(config.storage.dir()));
-        StorageWriteProxy { db, libra_db }
+        StorageWriteProxy {
+            db,
+            libra_db,
+            retained_epochs: config.consensus.consensusdb_retained_epochs,
+        }
+    }
+
+    /// Deletes the blocks of the epochs before the retained ones, counting back from `epoch`.
+    /// Failing to is only logged, as those blocks are never needed for recovery.
+    fn prune_past_epochs(&self, epoch: u64) {
+        match self
+            .db
+            .prune_epochs_before(epoch.saturating_sub(self.retained_epochs))
+        {
+            Ok(0) => (),
+            Ok(num_pruned_blocks) => info!(
+                "Pruned {} blocks of past epochs from ConsensusDB",
+                num_pruned_blocks
+            ),
+            Err(e) => error!("Unable to prune blocks of past epochs: {:?}", e),
+        }
     }
 }
 
@@ -358,6 +379,13 @@ impl PersistentLivenessStorage for StorageWriteProxy {
             .ledger_frozen_subtree_hashes
             .clone();
         let root_executed_trees = ExecutedTrees::from(startup_info.committed_tree_state);
+        // this runs on every epoch change too, whether or not the recovery data can be constructed
+        self.prune_past_epochs(
+            startup_info
+                .latest_ledger_info
+                .ledger_info()
+                .next_block_epoch(),
+        );
         match RecoveryData::new(
             last_vote,
             ledger_recovery_data.clone(),
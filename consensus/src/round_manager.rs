@@ -238,6 +238,13 @@ impl RoundManager {
             }
             NewRoundReason::Timeout => {
                 counters::TIMEOUT_ROUNDS_COUNT.inc();
+                // the proposer of the round that timed out, which may be what slowed it down
+                let timed_out_proposer = self
+                    .proposer_election
+                    .get_valid_proposer(new_round_event.round - 1);
+                counters::TIMEOUT_ROUNDS_BY_PROPOSER
+                    .with_label_values(&[&timed_out_proposer.short_str()])
+                    .inc();
             }
         };
         if self
@@ -455,6 +462,7 @@ impl RoundManager {
                 .expect("Proposal should be verified having an author"),
             proposal,
         );
+        self.round_state.record_proposal(proposal.round());
 
         let block_time_since_epoch = Duration::from_micros(proposal.timestamp_usecs());
 
@@ -36,7 +36,11 @@ use network::{
     peer_manager::{conn_notifs_channel, ConnectionRequestSender, PeerManagerRequestSender},
     protocols::network::{NewNetworkEvents, NewNetworkSender},
 };
-use std::{collections::HashMap, num::NonZeroUsize, sync::Arc};
+use std::{
+    collections::{hash_map::Entry, HashMap},
+    num::NonZeroUsize,
+    sync::Arc,
+};
 use tokio::runtime::{Builder, Runtime};
 
 /// Auxiliary struct that is preparing SMR for the test
@@ -198,6 +202,35 @@ impl SMRNode {
         }
         (smr_nodes, node_authors)
     }
+
+    /// Drains the commits the node made since the last call
+    fn drain_commits(&mut self) -> Vec<LedgerInfoWithSignatures> {
+        let mut commits = vec![];
+        while let Ok(Some(commit)) = self.commit_cb_receiver.try_next() {
+            commits.push(commit);
+        }
+        commits
+    }
+}
+
+/// Checks the safety invariant: no two nodes (twins included) commit different blocks
+/// for the same round.
+fn assert_safety(commits: &[Vec<LedgerInfoWithSignatures>]) {
+    let mut committed_ids = HashMap::new();
+    for commit in commits.iter().flatten() {
+        let commit_info = commit.ledger_info().commit_info();
+        match committed_ids.entry(commit_info.round()) {
+            Entry::Occupied(entry) => assert_eq!(
+                *entry.get(),
+                commit_info.id(),
+                "Conflicting commits for round {}",
+                commit_info.round()
+            ),
+            Entry::Vacant(entry) => {
+                entry.insert(commit_info.id());
+            }
+        }
+    }
 }
 
 #[test]
@@ -376,3 +409,114 @@ fn twins_vote_dedup_test() {
         assert!(!commit_seen);
     });
 }
+
+#[test]
+/// This test checks that the safety invariant holds when
+/// a node and its twin equivocate.
+///
+/// Setup:
+///
+/// 4 honest nodes (n0, n1, n2, n3), and 1 twin (twin0).
+/// There are no partitions: when n0 is the proposer, both n0
+/// and twin0 send a proposal for the round, and both vote for
+/// whatever proposal they receive first.
+///
+/// Test:
+///
+/// Extract enough votes to potentially form commits. Check that
+/// no two nodes commit different blocks for the same round.
+///
+/// Run the test:
+/// cargo xtest -p consensus twins_equivocation_safety_test -- --nocapture
+fn twins_equivocation_safety_test() {
+    let mut runtime = consensus_runtime();
+    let mut playground = NetworkPlayground::new(runtime.handle().clone());
+    let num_nodes = 4;
+    let num_twins = 1;
+    let (mut nodes, _) = SMRNode::start_num_nodes_with_twins(
+        num_nodes,
+        num_twins,
+        &mut playground,
+        RotatingProposer,
+    );
+
+    timed_block_on(&mut runtime, async {
+        playground
+            .wait_for_messages(1, NetworkPlayground::proposals_only)
+            .await;
+
+        // Pull enough votes to get a few commits.
+        playground
+            .wait_for_messages(50, NetworkPlayground::votes_only)
+            .await;
+
+        let commits: Vec<_> = nodes.iter_mut().map(SMRNode::drain_commits).collect();
+        assert_safety(&commits);
+    });
+}
+
+#[test]
+/// This test checks that delayed messages do not prevent a quorum
+/// from making progress, and are delivered once the delay is lifted.
+///
+/// Setup:
+///
+/// 4 honest nodes (n0, n1, n2, n3), and 0 twins.
+/// Delay all the messages n3 sends and receives.
+///
+/// Test:
+///
+/// Extract enough votes to potentially form commits. Check that
+/// n0, n1 and n2 commit while n3 doesn't. Lift the delay and check
+/// that the safety invariant holds for all the nodes.
+///
+/// Run the test:
+/// cargo xtest -p consensus delayed_messages_test -- --nocapture
+fn delayed_messages_test() {
+    let mut runtime = consensus_runtime();
+    let mut playground = NetworkPlayground::new(runtime.handle().clone());
+    let num_nodes = 4;
+    let num_twins = 0;
+    let (mut nodes, node_authors) = SMRNode::start_num_nodes_with_twins(
+        num_nodes,
+        num_twins,
+        &mut playground,
+        RotatingProposer,
+    );
+
+    let twin_ids: Vec<_> = node_authors
+        .iter()
+        .map(|author| *playground.get_twin_ids(*author).get(0).unwrap())
+        .collect();
+    for twin_id in &twin_ids[0..3] {
+        assert!(playground.delay_message_for(&twin_ids[3], twin_id));
+        assert!(playground.delay_message_for(twin_id, &twin_ids[3]));
+    }
+
+    timed_block_on(&mut runtime, async {
+        // Pull enough votes to get a few commits, including the rounds
+        // n3 should have proposed which time out.
+        playground
+            .wait_for_messages(50, NetworkPlayground::votes_only)
+            .await;
+
+        let mut commits: Vec<_> = nodes.iter_mut().map(SMRNode::drain_commits).collect();
+        assert!(commits[0..3]
+            .iter()
+            .any(|node_commits| !node_commits.is_empty()));
+        assert!(commits[3].is_empty());
+
+        for twin_id in &twin_ids[0..3] {
+            assert!(playground.stop_delay_message_for(&twin_ids[3], twin_id));
+            assert!(playground.stop_delay_message_for(twin_id, &twin_ids[3]));
+        }
+        playground
+            .wait_for_messages(20, NetworkPlayground::votes_only)
+            .await;
+
+        for (node_commits, node) in commits.iter_mut().zip(nodes.iter_mut()) {
+            node_commits.extend(node.drain_commits());
+        }
+        assert_safety(&commits);
+    });
+}
@@ -4,7 +4,9 @@
 use channel::libra_channel::Receiver;
 use libra_types::{
     account_config::NewEpochEvent,
-    on_chain_config::{OnChainConfigPayload, ON_CHAIN_CONFIG_REGISTRY},
+    on_chain_config::{
+        OnChainConfigPayload, ON_CHAIN_CONFIG_REGISTRY, OPTIONAL_ON_CHAIN_CONFIG_REGISTRY,
+    },
 };
 use subscription_service::ReconfigSubscription;
 
@@ -12,7 +14,11 @@ use subscription_service::ReconfigSubscription;
 pub fn gen_consensus_reconfig_subscription(
 ) -> (ReconfigSubscription, Receiver<(), OnChainConfigPayload>) {
     ReconfigSubscription::subscribe_all(
-        ON_CHAIN_CONFIG_REGISTRY.to_vec(),
+        ON_CHAIN_CONFIG_REGISTRY
+            .iter()
+            .chain(OPTIONAL_ON_CHAIN_CONFIG_REGISTRY)
+            .cloned()
+            .collect(),
         vec![NewEpochEvent::event_key()],
     )
 }
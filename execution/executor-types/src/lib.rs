@@ -27,6 +27,8 @@ use storage_interface::TreeState;
 pub trait ChunkExecutor: Send {
     /// Verifies the transactions based on the provided proofs and ledger info. If the transactions
     /// are valid, executes them and commits immediately if execution results match the proofs.
+    /// If the chunk ends an epoch part-way, only the transactions up to the end of the epoch are
+    /// committed, and the rest are left to executing the chunk again, which skips those committed.
     /// Returns a vector of reconfiguration events in the chunk
     fn execute_and_commit_chunk(
         &mut self,
@@ -37,6 +39,9 @@ pub trait ChunkExecutor: Send {
         // carrying any epoch change LI.
         epoch_change_li: Option<LedgerInfoWithSignatures>,
     ) -> Result<Vec<ContractEvent>>;
+
+    /// The trees of what's been synced, including the chunks committed.
+    fn synced_trees(&self) -> &ExecutedTrees;
 }
 
 pub trait BlockExecutor: Send {
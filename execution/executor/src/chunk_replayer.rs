@@ -0,0 +1,145 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+#![forbid(unsafe_code)]
+
+//! Replays chunks of transactions committed elsewhere, for state sync and backup restore alike,
+//! reporting the progress made to callbacks.
+
+use anyhow::{ensure, Result};
+use executor_types::{ChunkExecutor, ExecutedTrees};
+use libra_types::{
+    contract_event::ContractEvent,
+    ledger_info::LedgerInfoWithSignatures,
+    transaction::{TransactionListWithProof, Version},
+};
+
+/// The progress made on a chunk: the transactions from `first_version` to `last_version` are all
+/// synced. Some of them might have been synced by consensus instead, when state sync and
+/// consensus race.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct ReplayProgress {
+    pub first_version: Version,
+    pub last_version: Version,
+}
+
+impl ReplayProgress {
+    pub fn num_txns(&self) -> usize {
+        (self.last_version - self.first_version + 1) as usize
+    }
+}
+
+type ProgressCallback = Box<dyn FnMut(&ReplayProgress) + Send>;
+
+/// Wraps the executor to replay chunks of transactions, calling back with the progress made after
+/// each chunk. Chunks that end an epoch part-way are replayed in several goes, each reported.
+pub struct ChunkReplayer<E> {
+    executor: E,
+    progress_callbacks: Vec<ProgressCallback>,
+}
+
+impl<E> ChunkReplayer<E> {
+    pub fn new(executor: E) -> Self {
+        Self {
+            executor,
+            progress_callbacks: vec![],
+        }
+    }
+
+    /// Adds a callback, called with the progress made after each chunk replayed.
+    pub fn add_progress_callback<F>(&mut self, callback: F)
+    where
+        F: FnMut(&ReplayProgress) + Send + 'static,
+    {
+        self.progress_callbacks.push(Box::new(callback));
+    }
+
+    fn report_progress(&mut self, progress: ReplayProgress) {
+        for callback in self.progress_callbacks.iter_mut() {
+            callback(&progress);
+        }
+    }
+}
+
+impl<E: ChunkExecutor> ChunkReplayer<E> {
+    /// Executes and commits the chunk like `execute_and_commit_chunk`, once for each epoch it goes
+    /// through: those ending part-way are committed with the LedgerInfo `epoch_change_li` returns
+    /// for the first version in them, which must have been verified independently like the
+    /// target. Returns the number of transactions committed.
+    pub fn replay<F>(
+        &mut self,
+        txn_list_with_proof: TransactionListWithProof,
+        verified_target_li: LedgerInfoWithSignatures,
+        mut epoch_change_li: F,
+    ) -> Result<usize>
+    where
+        F: FnMut(Version) -> Option<LedgerInfoWithSignatures>,
+    {
+        let last_version = match txn_list_with_proof.first_transaction_version {
+            Some(first_version) if !txn_list_with_proof.transactions.is_empty() => {
+                first_version + txn_list_with_proof.transactions.len() as Version - 1
+            }
+            _ => return Ok(0),
+        };
+
+        let mut num_replayed = 0;
+        loop {
+            let next_version = self.next_version();
+            if next_version > last_version {
+                return Ok(num_replayed);
+            }
+            let epoch_change_li = epoch_change_li(next_version)
+                .filter(|li| li.ledger_info().version() <= last_version);
+            self.execute_and_commit_chunk(
+                txn_list_with_proof.clone(),
+                verified_target_li.clone(),
+                epoch_change_li,
+            )?;
+            let num_committed = self.next_version().saturating_sub(next_version);
+            ensure!(
+                num_committed > 0,
+                "No transactions committed replaying from version {}.",
+                next_version,
+            );
+            num_replayed += num_committed as usize;
+        }
+    }
+
+    fn next_version(&self) -> Version {
+        self.executor.synced_trees().txn_accumulator().num_leaves()
+    }
+}
+
+impl<E: ChunkExecutor> ChunkExecutor for ChunkReplayer<E> {
+    fn execute_and_commit_chunk(
+        &mut self,
+        txn_list_with_proof: TransactionListWithProof,
+        verified_target_li: LedgerInfoWithSignatures,
+        epoch_change_li: Option<LedgerInfoWithSignatures>,
+    ) -> Result<Vec<ContractEvent>> {
+        // Transactions synced already, and those after the end of the epoch, are not committed.
+        let first_version = txn_list_with_proof
+            .first_transaction_version
+            .map(|first_version| first_version.max(self.next_version()));
+        let reconfig_events = self.executor.execute_and_commit_chunk(
+            txn_list_with_proof,
+            verified_target_li,
+            epoch_change_li,
+        )?;
+        let next_version = self.next_version();
+        match first_version {
+            Some(first_version) if next_version > first_version => {
+                self.report_progress(ReplayProgress {
+                    first_version,
+                    last_version: next_version - 1,
+                })
+            }
+            _ => (),
+        }
+        Ok(reconfig_events)
+    }
+
+    fn synced_trees(&self) -> &ExecutedTrees {
+        self.executor.synced_trees()
+    }
+}
@@ -3,6 +3,7 @@
 
 use super::*;
 use crate::{
+    chunk_replayer::{ChunkReplayer, ReplayProgress},
     db_bootstrapper::bootstrap_db_if_empty,
     mock_vm::{
         encode_mint_transaction, encode_reconfiguration_transaction, encode_transfer_transaction,
@@ -21,7 +22,10 @@ use libra_types::{
 use libradb::LibraDB;
 use proptest::prelude::*;
 use rand::Rng;
-use std::collections::BTreeMap;
+use std::{
+    collections::BTreeMap,
+    sync::{Arc, Mutex},
+};
 
 fn build_test_config() -> (NodeConfig, Ed25519PrivateKey) {
     let mut validator_config = config_builder::ValidatorConfig::new();
@@ -352,6 +356,180 @@ fn test_executor_execute_and_commit_chunk() {
     assert_eq!(li, ledger_info);
 }
 
+/// Creates the chunk of transactions [1, 10], along with the LedgerInfo ending the epoch at version
+/// 6, part-way through it, and the one at version 10 it's proven against.
+fn create_chunk_across_epochs() -> (
+    TransactionListWithProof,
+    LedgerInfoWithSignatures,
+    LedgerInfoWithSignatures,
+) {
+    let (config, _) = build_test_config();
+    let db = create_storage(&config);
+    let mut executor = Executor::<MockVM>::new(db.clone());
+    let mut txns: Vec<_> = (1..6)
+        .map(|i| encode_mint_transaction(gen_address(i), 100))
+        .collect();
+    txns.push(encode_reconfiguration_transaction(gen_address(6)));
+    let id = gen_block_id(1);
+    let output = executor
+        .execute_block((id, txns), executor.committed_block_id())
+        .unwrap();
+    let epoch_change_li = LedgerInfoWithSignatures::new(
+        LedgerInfo::new(
+            BlockInfo::new(
+                1,
+                0,
+                id,
+                output.root_hash(),
+                6,
+                6,
+                output.epoch_state().clone(),
+            ),
+            HashValue::zero(),
+        ),
+        BTreeMap::new(),
+    );
+    executor
+        .commit_blocks(vec![id], epoch_change_li.clone())
+        .unwrap();
+    let block = TestBlock::new(7..11, 100, gen_block_id(2));
+    let output = executor
+        .execute_block((block.id, block.txns), executor.committed_block_id())
+        .unwrap();
+    let ledger_info = gen_ledger_info(10, output.root_hash(), block.id, 10);
+    executor
+        .commit_blocks(vec![block.id], ledger_info.clone())
+        .unwrap();
+    let chunk = db.reader.get_transactions(1, 10, 10, false).unwrap();
+    (chunk, epoch_change_li, ledger_info)
+}
+
+fn records_progress<E>(replayer: &mut ChunkReplayer<E>) -> Arc<Mutex<Vec<ReplayProgress>>> {
+    let progress = Arc::new(Mutex::new(vec![]));
+    let recorded = Arc::clone(&progress);
+    replayer.add_progress_callback(move |progress| recorded.lock().unwrap().push(*progress));
+    progress
+}
+
+#[test]
+fn test_chunk_replayer_execute_and_commit_chunk() {
+    let (chunks, ledger_info) = create_transaction_chunks(vec![1..31, 31..71]);
+
+    let (config, _) = build_test_config();
+    let db = create_storage(&config);
+    let mut replayer = ChunkReplayer::new(Executor::<MockVM>::new(db.clone()));
+    let progress = records_progress(&mut replayer);
+
+    replayer
+        .execute_and_commit_chunk(chunks[0].clone(), ledger_info.clone(), None)
+        .unwrap();
+    // An empty chunk makes no progress.
+    replayer
+        .execute_and_commit_chunk(
+            TransactionListWithProof::new_empty(),
+            ledger_info.clone(),
+            None,
+        )
+        .unwrap();
+    replayer
+        .execute_and_commit_chunk(chunks[1].clone(), ledger_info.clone(), None)
+        .unwrap();
+
+    assert_eq!(db.reader.get_latest_ledger_info().unwrap(), ledger_info);
+    assert_eq!(
+        *progress.lock().unwrap(),
+        vec![
+            ReplayProgress {
+                first_version: 1,
+                last_version: 30,
+            },
+            ReplayProgress {
+                first_version: 31,
+                last_version: 70,
+            },
+        ]
+    );
+}
+
+#[test]
+fn test_chunk_replayer_replay() {
+    let (chunks, ledger_info) = create_transaction_chunks(vec![1..31, 21..71]);
+
+    let (config, _) = build_test_config();
+    let db = create_storage(&config);
+    let mut replayer = ChunkReplayer::new(Executor::<MockVM>::new(db.clone()));
+    let progress = records_progress(&mut replayer);
+
+    // The second chunk overlaps with the first one, whose transactions are skipped.
+    for (chunk, num_replayed) in chunks.iter().zip(&[30, 40]) {
+        assert_eq!(
+            replayer
+                .replay(chunk.clone(), ledger_info.clone(), |_| None)
+                .unwrap(),
+            *num_replayed
+        );
+    }
+    assert_eq!(db.reader.get_latest_ledger_info().unwrap(), ledger_info);
+    assert_eq!(
+        *progress.lock().unwrap(),
+        vec![
+            ReplayProgress {
+                first_version: 1,
+                last_version: 30,
+            },
+            ReplayProgress {
+                first_version: 31,
+                last_version: 70,
+            },
+        ]
+    );
+
+    // Replaying past the synced transactions fails.
+    let (config, _) = build_test_config();
+    let mut replayer = ChunkReplayer::new(Executor::<MockVM>::new(create_storage(&config)));
+    assert!(replayer
+        .replay(chunks[1].clone(), ledger_info, |_| None)
+        .is_err());
+}
+
+#[test]
+fn test_chunk_replayer_replay_across_epochs() {
+    let (chunk, epoch_change_li, ledger_info) = create_chunk_across_epochs();
+
+    let (config, _) = build_test_config();
+    let db = create_storage(&config);
+    let mut replayer = ChunkReplayer::new(Executor::<MockVM>::new(db.clone()));
+    let progress = records_progress(&mut replayer);
+    // Without the LedgerInfo ending the epoch, the transactions up to it can't be committed.
+    assert!(replayer
+        .replay(chunk.clone(), ledger_info.clone(), |_| None)
+        .is_err());
+    assert_eq!(
+        replayer
+            .replay(chunk, ledger_info.clone(), |version| if version <= 6 {
+                Some(epoch_change_li.clone())
+            } else {
+                None
+            })
+            .unwrap(),
+        10
+    );
+    assert_eq!(db.reader.get_latest_ledger_info().unwrap(), ledger_info);
+    assert_eq!(
+        *progress.lock().unwrap(),
+        vec![
+            ReplayProgress {
+                first_version: 1,
+                last_version: 6,
+            },
+            ReplayProgress {
+                first_version: 7,
+                last_version: 10,
+            },
+        ]
+    );
+}
+
 #[test]
 fn test_executor_execute_and_commit_chunk_restart() {
     let first_batch_size = 30;
@@ -431,6 +609,59 @@ fn test_executor_execute_and_commit_chunk_local_result_mismatch() {
         .is_err());
 }
 
+#[test]
+fn test_executor_execute_and_commit_chunk_across_epochs() {
+    let (chunk, epoch_change_li, ledger_info) = create_chunk_across_epochs();
+
+    let (config, _) = build_test_config();
+    let db = create_storage(&config);
+    let mut executor = Executor::<MockVM>::new(db.clone());
+
+    // Without the LedgerInfo ending the epoch, the transactions up to it can't be committed.
+    assert!(executor
+        .execute_and_commit_chunk(chunk.clone(), ledger_info.clone(), None)
+        .is_err());
+    assert_eq!(
+        db.reader
+            .get_latest_ledger_info()
+            .unwrap()
+            .ledger_info()
+            .version(),
+        0
+    );
+
+    // The transactions after the end of the epoch are retried, so only those up to it are
+    // committed, and executing the chunk again commits the rest.
+    executor
+        .execute_and_commit_chunk(
+            chunk.clone(),
+            ledger_info.clone(),
+            Some(epoch_change_li.clone()),
+        )
+        .unwrap();
+    assert_eq!(db.reader.get_latest_ledger_info().unwrap(), epoch_change_li);
+    executor
+        .execute_and_commit_chunk(chunk, ledger_info.clone(), None)
+        .unwrap();
+    assert_eq!(db.reader.get_latest_ledger_info().unwrap(), ledger_info);
+}
+
+#[test]
+fn test_executor_execute_and_commit_chunk_unbootstrapped_db() {
+    let (chunks, ledger_info) = create_transaction_chunks(vec![1..11]);
+
+    let (config, _) = build_test_config();
+    let db = DbReaderWriter::new(LibraDB::new_for_test(config.storage.dir()));
+    let tree_state = db.reader.get_latest_tree_state().unwrap();
+    let mut executor = Executor::<MockVM>::new_on_unbootstrapped_db(db.clone(), tree_state);
+
+    let err = executor
+        .execute_and_commit_chunk(chunks[0].clone(), ledger_info, None)
+        .unwrap_err();
+    assert!(err.to_string().contains("DB not bootstrapped"));
+    assert!(db.reader.get_startup_info().unwrap().is_none());
+}
+
 #[test]
 fn test_noop_block_after_reconfiguration() {
     let mut executor = TestExecutor::new();
@@ -10,6 +10,7 @@ mod mock_vm;
 mod speculation_cache;
 mod types;
 
+pub mod chunk_replayer;
 pub mod db_bootstrapper;
 
 use crate::{
@@ -60,6 +61,9 @@ static OP_COUNTERS: Lazy<libra_metrics::OpMetrics> =
 pub struct Executor<V> {
     db: DbReaderWriter,
     cache: SpeculationCache,
+    /// Whether the DB was restored from backups. Such a DB might have no LedgerInfo to reset the
+    /// cache to, in which case chunks are executed on top of the trees the executor goes by.
+    restored_db: bool,
     phantom: PhantomData<V>,
 }
 
@@ -82,10 +86,20 @@ where
         Self {
             db,
             cache: SpeculationCache::new_with_startup_info(startup_info),
+            restored_db: false,
             phantom: PhantomData,
         }
     }
 
+    /// Constructs an `Executor` to replay transactions on a DB restored from backups, which might
+    /// have no LedgerInfo to start from at `tree_state`, where the restored transactions end.
+    pub fn new_for_replay(db: DbReaderWriter, tree_state: TreeState) -> Self {
+        Self {
+            restored_db: true,
+            ..Self::new_on_unbootstrapped_db(db, tree_state)
+        }
+    }
+
     fn reset_cache(&mut self) -> Result<(), Error> {
         let startup_info = self
             .db
@@ -96,10 +110,20 @@ where
         Ok(())
     }
 
+    /// Resets the cache like `reset_cache`, unless the DB was restored from backups and has no
+    /// LedgerInfo yet, in which case the synced trees are already those of the DB.
+    fn reset_cache_for_chunk(&mut self) -> Result<(), Error> {
+        if self.restored_db && self.db.reader.get_startup_info()?.is_none() {
+            return Ok(());
+        }
+        self.reset_cache()
+    }
+
     fn new_on_unbootstrapped_db(db: DbReaderWriter, tree_state: TreeState) -> Self {
         Self {
             db,
             cache: SpeculationCache::new_for_db_bootstrapping(tree_state),
+            restored_db: false,
             phantom: PhantomData,
         }
     }
@@ -356,7 +380,9 @@ where
                             bail!("Write set should be a subset of read set.")
                         }
                         Transaction::UserTransaction(txn) => match txn.payload() {
-                            TransactionPayload::Module(_) | TransactionPayload::Script(_) => {
+                            TransactionPayload::Module(_)
+                            | TransactionPayload::Script(_)
+                            | TransactionPayload::ScriptFunction(_) => {
                                 bail!("Write set should be a subset of read set.")
                             }
                             TransactionPayload::WriteSet(_) => (),
@@ -448,7 +474,7 @@ impl<V: VMExecutor> ChunkExecutor for Executor<V> {
         epoch_change_li: Option<LedgerInfoWithSignatures>,
     ) -> Result<Vec<ContractEvent>> {
         // Update the cache in executor to be consistent with latest synced state.
-        self.reset_cache()?;
+        self.reset_cache_for_chunk()?;
 
         info!(
             "Local synced version: {}. First transaction version in request: {:?}. \
@@ -546,6 +572,10 @@ impl<V: VMExecutor> ChunkExecutor for Executor<V> {
             itertools::zip_eq(transactions, output.transaction_data()),
             transaction_infos.iter().enumerate(),
         ) {
+            // The transactions after the one ending the epoch are left for the next epoch.
+            if let TransactionStatus::Retry = txn_data.status() {
+                break;
+            }
             let generated_txn_info = &TransactionInfo::new(
                 txn.hash(),
                 txn_data.state_root_hash(),
@@ -603,6 +633,10 @@ impl<V: VMExecutor> ChunkExecutor for Executor<V> {
         );
         Ok(reconfig_events)
     }
+
+    fn synced_trees(&self) -> &ExecutedTrees {
+        self.cache.synced_trees()
+    }
 }
 
 impl<V: VMExecutor> BlockExecutor for Executor<V> {
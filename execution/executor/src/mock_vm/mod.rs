@@ -376,5 +376,8 @@ fn decode_transaction(txn: &SignedTransaction) -> MockVMTransaction {
         TransactionPayload::Module(_) => {
             unimplemented!("MockVM does not support Module transaction payload.")
         }
+        TransactionPayload::ScriptFunction(_) => {
+            unimplemented!("MockVM does not support ScriptFunction transaction payload.")
+        }
     }
 }
@@ -0,0 +1,147 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Cache of the responses to requests for what can't change once committed, so that requests for
+//! the same historical data don't each go to the DB:
+//!   - `get_transactions` for a range of versions that's entirely committed,
+//!   - `get_epoch_change_proof` for epochs that have all ended.
+
+use crate::methods::JsonRpcRequest;
+use libra_config::config::ResponseCacheConfig;
+use serde_json::Value;
+use std::{
+    collections::{BTreeMap, HashMap},
+    hash::Hash,
+    sync::Mutex,
+};
+
+/// The method name along with its serialized params
+pub(crate) type CacheKey = (String, String);
+
+pub(crate) struct ResponseCache {
+    enabled: bool,
+    responses: Mutex<LruCache<CacheKey, Value>>,
+    max_age_secs: u64,
+}
+
+impl ResponseCache {
+    pub fn new(config: &ResponseCacheConfig) -> Self {
+        Self {
+            enabled: config.capacity > 0,
+            responses: Mutex::new(LruCache::new(config.capacity)),
+            max_age_secs: config.max_age_secs,
+        }
+    }
+
+    /// Returns the key to cache the response to `request` with, if the response can't change
+    pub fn key(&self, method: &str, request: &JsonRpcRequest) -> Option<CacheKey> {
+        if !self.enabled || !is_immutable(method, request) {
+            return None;
+        }
+        Some((
+            method.to_string(),
+            serde_json::to_string(&request.params).ok()?,
+        ))
+    }
+
+    pub fn get(&self, key: &CacheKey) -> Option<Value> {
+        self.lock().get(key).cloned()
+    }
+
+    pub fn insert(&self, key: CacheKey, result: Value) {
+        self.lock().insert(key, result);
+    }
+
+    /// The `Cache-Control` header of HTTP responses which only carry results that can't change
+    pub fn cache_control(&self) -> String {
+        format!("public, max-age={}", self.max_age_secs)
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<LruCache<CacheKey, Value>> {
+        self.responses
+            .lock()
+            .expect("[json-rpc] failed to acquire response cache lock")
+    }
+}
+
+/// Returns whether the result of a request is the same at any ledger version from the one it's
+/// made at on. Requests with invalid params are never cached, as they fail.
+fn is_immutable(method: &str, request: &JsonRpcRequest) -> bool {
+    let param = |index| serde_json::from_value::<u64>(request.get_param(index)).ok();
+    let ledger_info = request.ledger_info.ledger_info();
+    match method {
+        "get_transactions" => match (param(0), param(1)) {
+            (Some(start_version), Some(limit)) => start_version
+                .checked_add(limit)
+                .map_or(false, |end_version| {
+                    end_version <= ledger_info.version() + 1
+                }),
+            _ => false,
+        },
+        "get_epoch_change_proof" => param(1).map_or(false, |end_epoch| {
+            end_epoch <= ledger_info.next_block_epoch()
+        }),
+        _ => false,
+    }
+}
+
+/// A map of at most `capacity` entries, evicting the least recently used one to make room
+pub(crate) struct LruCache<K, V> {
+    capacity: usize,
+    /// Incremented on each access, to order the entries by when they were last used
+    next_tick: u64,
+    data: HashMap<K, (V, u64)>,
+    lru_index: BTreeMap<u64, K>,
+}
+
+impl<K, V> LruCache<K, V>
+where
+    K: Eq + Hash + Clone,
+{
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            next_tick: 0,
+            data: HashMap::new(),
+            lru_index: BTreeMap::new(),
+        }
+    }
+
+    pub fn get(&mut self, key: &K) -> Option<&V> {
+        let tick = self.tick();
+        let (value, last_used) = self.data.get_mut(key)?;
+        self.lru_index.remove(last_used);
+        self.lru_index.insert(tick, key.clone());
+        *last_used = tick;
+        Some(value)
+    }
+
+    pub fn insert(&mut self, key: K, value: V) {
+        if self.capacity == 0 {
+            return;
+        }
+        match self.data.get(&key) {
+            Some((_, last_used)) => {
+                self.lru_index.remove(last_used);
+            }
+            None => {
+                if self.data.len() == self.capacity {
+                    let least_recently_used = self.lru_index.keys().next().cloned();
+                    if let Some(tick) = least_recently_used {
+                        if let Some(key) = self.lru_index.remove(&tick) {
+                            self.data.remove(&key);
+                        }
+                    }
+                }
+            }
+        }
+        let tick = self.tick();
+        self.lru_index.insert(tick, key.clone());
+        self.data.insert(key, (value, tick));
+    }
+
+    fn tick(&mut self) -> u64 {
+        self.next_tick += 1;
+        self.next_tick
+    }
+}
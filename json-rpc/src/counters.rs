@@ -23,7 +23,31 @@ pub static INVALID_REQUESTS: Lazy<IntGaugeVec> = Lazy::new(|| {
         "libra_client_service_invalid_requests_count",
         "Cumulative number of invalid requests that JSON RPC client service receives",
         &[
-            "type", // categories of invalid requests: "invalid_format", "invalid_params", "invalid_method", "method_not_found"
+            "type", // categories of invalid requests: "invalid_format", "invalid_params", "invalid_method", "method_not_found", "rate_limited"
+        ]
+    )
+    .unwrap()
+});
+
+/// Cumulative number of requests served from the cache of responses which can't change
+pub static CACHED_RESPONSES: Lazy<IntGaugeVec> = Lazy::new(|| {
+    register_int_gauge_vec!(
+        "libra_client_service_cached_responses_count",
+        "Cumulative number of requests that JSON RPC client service serves from its response cache",
+        &[
+            "type", // matches JSON RPC method name (e.g. "get_transactions")
+        ]
+    )
+    .unwrap()
+});
+
+/// Number of WebSocket subscriptions that the JSON RPC client service is serving
+pub static ACTIVE_SUBSCRIPTIONS: Lazy<IntGaugeVec> = Lazy::new(|| {
+    register_int_gauge_vec!(
+        "libra_client_service_active_subscriptions",
+        "Number of WebSocket subscriptions that JSON RPC client service is serving",
+        &[
+            "type", // matches the subscription method name (e.g. "subscribe_to_events")
         ]
     )
     .unwrap()
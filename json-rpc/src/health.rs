@@ -0,0 +1,86 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Health checks of the node for load balancers:
+//!   - `/-/healthy` reports whether the node is up and its storage can be read,
+//!   - `/-/ready` reports whether it's in sync and able to accept transactions, so that nodes
+//!     which fall behind can be taken out of rotation.
+//! Both return a JSON body with the details, with status 200 if the check passes and 503 if not.
+
+use crate::methods::JsonRpcService;
+use anyhow::Result;
+use libra_types::{chain_id::ChainId, transaction::Version};
+use serde::Serialize;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use warp::{http::StatusCode, Filter};
+
+#[derive(Serialize)]
+struct HealthView {
+    chain_id: u8,
+    /// Version of the latest ledger info in storage
+    latest_ledger_version: Version,
+    latest_ledger_timestamp_usecs: u64,
+    /// Version of the latest transaction committed to storage, which the latest ledger info can
+    /// lag behind while syncing
+    latest_committed_version: Version,
+    /// How long ago the latest ledger info was agreed on
+    sync_lag_secs: u64,
+    /// Whether mempool accepts transactions submitted through JSON RPC
+    mempool_healthy: bool,
+}
+
+/// Builds the warp filter serving the health checks at `/-/healthy` and `/-/ready`
+pub(crate) fn health_routes(
+    service: JsonRpcService,
+    chain_id: ChainId,
+    ready_max_sync_lag: Duration,
+) -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone {
+    let healthy_service = service.clone();
+    let healthy = warp::path!("-" / "healthy").and(warp::get()).map(move || {
+        match health(&healthy_service, chain_id) {
+            Ok(health) => warp::reply::with_status(warp::reply::json(&health), StatusCode::OK),
+            Err(err) => unavailable(err),
+        }
+    });
+    let ready =
+        warp::path!("-" / "ready")
+            .and(warp::get())
+            .map(move || match health(&service, chain_id) {
+                Ok(health) => {
+                    let status = if health.mempool_healthy
+                        && health.sync_lag_secs <= ready_max_sync_lag.as_secs()
+                    {
+                        StatusCode::OK
+                    } else {
+                        StatusCode::SERVICE_UNAVAILABLE
+                    };
+                    warp::reply::with_status(warp::reply::json(&health), status)
+                }
+                Err(err) => unavailable(err),
+            });
+    healthy.or(ready)
+}
+
+fn health(service: &JsonRpcService, chain_id: ChainId) -> Result<HealthView> {
+    let ledger_info = service.get_latest_ledger_info()?;
+    let (latest_committed_version, _) = service.db.get_latest_state_root()?;
+    let timestamp = Duration::from_micros(ledger_info.ledger_info().timestamp_usecs());
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("System time is before the Unix epoch");
+    Ok(HealthView {
+        chain_id: chain_id.id(),
+        latest_ledger_version: ledger_info.ledger_info().version(),
+        latest_ledger_timestamp_usecs: ledger_info.ledger_info().timestamp_usecs(),
+        latest_committed_version,
+        sync_lag_secs: now.checked_sub(timestamp).unwrap_or_default().as_secs(),
+        mempool_healthy: service.mempool_available(),
+    })
+}
+
+fn unavailable(err: anyhow::Error) -> warp::reply::WithStatus<warp::reply::Json> {
+    warp::reply::with_status(
+        warp::reply::json(&serde_json::json!({ "error": err.to_string() })),
+        StatusCode::SERVICE_UNAVAILABLE,
+    )
+}
@@ -9,16 +9,24 @@
 //! Protocol specification: https://www.jsonrpc.org/specification
 //!
 //! Module organization:
+//! ├── cache.rs          # LRU cache of the responses which can't change once committed
+//! ├── health.rs         # health and readiness checks for load balancers
 //! ├── methods.rs        # contains all available JSON RPC method handlers
+//! ├── rate_limiter.rs   # token bucket rate limiting of requests per client and method
 //! ├── runtime.rs        # implementation of JSON RPC protocol over HTTP
+//! ├── subscriptions.rs  # WebSocket endpoint pushing transactions and events as they commit
 //! ├── tests.rs          # tests
 
 #[macro_use]
 mod util;
 
+mod cache;
 mod counters;
+mod health;
 mod methods;
+mod rate_limiter;
 mod runtime;
+mod subscriptions;
 
 pub use libra_json_rpc_types::{errors, views};
 
@@ -5,52 +5,84 @@
 use crate::{
     errors::JsonRpcError,
     views::{
-        AccountStateWithProofView, AccountView, BlockMetadata, CurrencyInfoView, EventView,
-        StateProofView, TransactionView,
+        AccountStateWithProofView, AccountTransactionView, AccountView, AccountWithStateView,
+        BlockMetadata, CurrencyInfoView, DryRunResultView, EpochChangeProofView, EventView,
+        EventWithProofView, EventsPageView, MempoolTransactionStatusView, MoveAbortView,
+        PendingTransactionView, RejectedTransactionView, StateProofView, TransactionView,
+        TransactionWithProofView, WriteOpView,
     },
 };
 use anyhow::{ensure, format_err, Error, Result};
 use core::future::Future;
 use futures::{channel::oneshot, SinkExt};
-use libra_config::config::RoleType;
-use libra_crypto::hash::CryptoHash;
-use libra_mempool::MempoolClientSender;
+use libra_config::config::{DryRunConfig, RoleType};
+use libra_crypto::{hash::CryptoHash, HashValue};
+use libra_mempool::{
+    MempoolClientRequest, MempoolClientSender, PendingTransaction, TransactionQuery,
+    TransactionStatus,
+};
+use libra_state_view::StateViewId;
 use libra_trace::prelude::*;
 use libra_types::{
     account_address::AccountAddress,
     account_config::{from_currency_code_string, CurrencyInfoResource},
-    account_state::AccountState,
     event::EventKey,
     ledger_info::LedgerInfoWithSignatures,
     mempool_status::MempoolStatusCode,
     move_resource::MoveStorage,
     on_chain_config::{OnChainConfig, RegisteredCurrencies},
-    transaction::SignedTransaction,
+    transaction::{SignedTransaction, Transaction, TransactionWithProof, Version},
 };
+use libra_vm::LibraVM;
+use move_core_types::identifier::Identifier;
+use move_vm_types::gas_schedule::ExecutionLimits;
 use network::counters;
+use scratchpad::SparseMerkleTree;
 use serde_json::Value;
 use std::{collections::HashMap, convert::TryFrom, ops::Deref, pin::Pin, str::FromStr, sync::Arc};
-use storage_interface::DbReader;
+use storage_interface::{state_view::VerifiedStateView, DbReader};
+
+/// Maximum number of accounts that can be queried by a single `get_accounts` request
+const MAX_ACCOUNTS_PER_REQUEST: usize = 100;
+/// Maximum number of events read to serve a single `get_events_page` request, so that skipping
+/// the events before the requested version range is done over several requests
+const MAX_EVENTS_SCANNED_PER_PAGE: u64 = 10_000;
 
 #[derive(Clone)]
 pub(crate) struct JsonRpcService {
-    db: Arc<dyn DbReader>,
+    pub(crate) db: Arc<dyn DbReader>,
     mempool_sender: MempoolClientSender,
     role: RoleType,
+    dry_run_limits: ExecutionLimits,
 }
 
 impl JsonRpcService {
-    pub fn new(db: Arc<dyn DbReader>, mempool_sender: MempoolClientSender, role: RoleType) -> Self {
+    pub fn new(
+        db: Arc<dyn DbReader>,
+        mempool_sender: MempoolClientSender,
+        role: RoleType,
+        dry_run_config: &DryRunConfig,
+    ) -> Self {
         Self {
             db,
             mempool_sender,
             role,
+            dry_run_limits: ExecutionLimits {
+                max_instructions: dry_run_config.max_instructions,
+                max_call_depth: dry_run_config.max_call_depth,
+                max_value_size: dry_run_config.max_value_size,
+            },
         }
     }
 
     pub fn get_latest_ledger_info(&self) -> Result<LedgerInfoWithSignatures> {
         self.db.get_latest_ledger_info()
     }
+
+    /// Returns whether mempool is still around to take the transactions submitted
+    pub fn mempool_available(&self) -> bool {
+        !self.mempool_sender.is_closed()
+    }
 }
 
 type RpcHandler =
@@ -64,10 +96,10 @@ pub(crate) struct JsonRpcRequest {
 }
 
 impl JsonRpcRequest {
-    /// Returns the request parameter at the given index. Note: this method should not panic with
-    /// an out of bounds error, as the number of request parameters has already been checked.
+    /// Returns the request parameter at the given index. The number of request parameters has
+    /// already been checked, so this is only null for optional parameters not given.
     fn get_param(&self, index: usize) -> Value {
-        self.params[index].clone()
+        self.params.get(index).cloned().unwrap_or(Value::Null)
     }
 
     fn version(&self) -> u64 {
@@ -84,7 +116,10 @@ async fn submit(mut service: JsonRpcService, request: JsonRpcRequest) -> Result<
     let (req_sender, callback) = oneshot::channel();
     service
         .mempool_sender
-        .send((transaction, req_sender))
+        .send(MempoolClientRequest::SubmitTransaction(
+            transaction,
+            req_sender,
+        ))
         .await?;
     let (mempool_status, vm_status_opt) = callback.await??;
 
@@ -97,41 +132,166 @@ async fn submit(mut service: JsonRpcService, request: JsonRpcRequest) -> Result<
     }
 }
 
-/// Returns account state (AccountView) by given address
+/// Executes a transaction against the latest state without submitting it, returning what the
+/// transaction would do. The signature isn't checked, so that transactions can be tried out
+/// before being signed.
+async fn dry_run_transaction(
+    service: JsonRpcService,
+    request: JsonRpcRequest,
+) -> Result<DryRunResultView> {
+    let txn_payload: String = serde_json::from_value(request.get_param(0))?;
+    let transaction: SignedTransaction = lcs::from_bytes(&hex::decode(txn_payload)?)?;
+
+    // Executing the transaction can take a while, so it's kept off the threads serving requests.
+    let db = Arc::clone(&service.db);
+    let execution_limits = service.dry_run_limits;
+    let (version, output) = tokio::task::spawn_blocking(move || -> Result<_> {
+        let (version, state_root) = db.get_latest_state_root()?;
+        let smt = SparseMerkleTree::new(state_root);
+        let state_view = VerifiedStateView::new(
+            StateViewId::Miscellaneous,
+            db,
+            Some(version),
+            state_root,
+            &smt,
+        );
+        let output = LibraVM::simulate_user_transaction(
+            &state_view,
+            transaction,
+            false,
+            Some(execution_limits),
+        );
+        Ok((version, output))
+    })
+    .await??;
+
+    let status = output.status().vm_status();
+    // the version the transaction would be committed at if it were next
+    let txn_version = version + 1;
+    Ok(DryRunResultView {
+        vm_status: status.status_code(),
+        discarded: output.status().is_discarded(),
+        gas_used: output.gas_used(),
+        move_abort: MoveAbortView::from_vm_status(&status),
+        events: output
+            .events()
+            .iter()
+            .map(|event| (txn_version, event.clone()).into())
+            .collect(),
+        write_set: output
+            .write_set()
+            .iter()
+            .map(|(access_path, write_op)| (access_path, write_op).into())
+            .collect(),
+    })
+}
+
+/// Returns account state (AccountView) by given address, as of the version given by the optional
+/// `version` parameter if any
 async fn get_account_state(
     service: JsonRpcService,
     request: JsonRpcRequest,
 ) -> Result<Option<AccountView>> {
     let address: String = serde_json::from_value(request.get_param(0))?;
     let account_address = AccountAddress::from_str(&address)?;
+    let version = optional_version_param(&request, 1)?;
     let response = service
         .db
-        .get_account_state_with_proof_by_version(account_address, request.version())?
+        .get_account_state_with_proof_by_version(account_address, version)?
         .0;
-    let currency_info = currencies_info(service, request).await?;
-    let currencies: Vec<_> = currency_info
+    let currencies = currency_codes(service, request).await?;
+    match response {
+        Some(blob) => AccountView::try_from_blob(&blob, &currencies),
+        None => Ok(None),
+    }
+}
+
+/// Returns account states (AccountView) of many accounts at the same version, along with their
+/// blobs and proofs if requested. The version is given by the optional `version` parameter,
+/// defaulting to the latest
+async fn get_accounts(
+    service: JsonRpcService,
+    request: JsonRpcRequest,
+) -> Result<Vec<AccountWithStateView>> {
+    let addresses: Vec<String> = serde_json::from_value(request.get_param(0))?;
+    let include_proofs: bool = serde_json::from_value(request.get_param(1))?;
+    let version = optional_version_param(&request, 2)?;
+
+    ensure!(
+        !addresses.is_empty() && addresses.len() <= MAX_ACCOUNTS_PER_REQUEST,
+        "number of addresses must be between 1 and {}",
+        MAX_ACCOUNTS_PER_REQUEST
+    );
+    let account_addresses = addresses
+        .iter()
+        .map(|address| AccountAddress::from_str(address))
+        .collect::<Result<Vec<_>>>()?;
+
+    let (blobs, states_with_proof) = if include_proofs {
+        let mut blobs = vec![];
+        let mut states_with_proof = vec![];
+        for address in &account_addresses {
+            let account_state_with_proof =
+                service
+                    .db
+                    .get_account_state_with_proof(*address, version, request.version())?;
+            blobs.push(account_state_with_proof.blob.clone());
+            states_with_proof.push(Some(AccountStateWithProofView::try_from(
+                account_state_with_proof,
+            )?));
+        }
+        (blobs, states_with_proof)
+    } else {
+        let blobs = service
+            .db
+            .get_account_states_batch(&account_addresses, version)?;
+        let num_blobs = blobs.len();
+        (blobs, vec![None; num_blobs])
+    };
+    ensure!(
+        blobs.len() == account_addresses.len(),
+        "Storage layer returned {} account states for {} addresses",
+        blobs.len(),
+        account_addresses.len()
+    );
+
+    let currencies = currency_codes(service, request).await?;
+    addresses
         .into_iter()
-        .map(|info| from_currency_code_string(&info.code))
-        .collect::<Result<_, _>>()?;
-    if let Some(blob) = response {
-        let account_state = AccountState::try_from(&blob)?;
-        if let Some(account) = account_state.get_account_resource()? {
-            let balances = account_state.get_balance_resources(&currencies)?;
-            if let Some(account_role) = account_state.get_account_role()? {
-                if let Some(freezing_bit) = account_state.get_freezing_bit()? {
-                    return Ok(Some(AccountView::new(
-                        &account,
-                        balances,
-                        account_role,
-                        freezing_bit,
-                    )));
-                }
-            }
+        .zip(blobs)
+        .zip(states_with_proof)
+        .map(|((address, blob), state_with_proof)| {
+            let account = match blob {
+                Some(blob) => AccountView::try_from_blob(&blob, &currencies)?,
+                None => None,
+            };
+            Ok(AccountWithStateView {
+                address,
+                account,
+                state_with_proof,
+            })
+        })
+        .collect()
+}
+
+/// Returns the version given by the optional parameter at `index`, defaulting to the version of the
+/// request. Versions whose state is pruned can't be queried, which storage reports.
+fn optional_version_param(request: &JsonRpcRequest, index: usize) -> Result<Version> {
+    match serde_json::from_value::<Option<Version>>(request.get_param(index))? {
+        Some(version) => {
+            ensure!(
+                version <= request.version(),
+                "version {} is greater than the latest ledger version {}",
+                version,
+                request.version()
+            );
+            Ok(version)
         }
+        None => Ok(request.version()),
     }
-    Ok(None)
 }
 
+/// Builds the view of an account from its state, none if it's missing any of the resources shown
 /// Returns the blockchain metadata for a specified version. If no version is specified, default to
 /// returning the current blockchain metadata
 /// Can be used to verify that target Full Node is up-to-date
@@ -207,46 +367,152 @@ async fn get_transactions(
 }
 
 /// Returns account transaction by account and sequence_number
+/// If asked for with the optional `include_pending` parameter, returns the transaction pending in
+/// mempool if it's not committed
 async fn get_account_transaction(
-    service: JsonRpcService,
+    mut service: JsonRpcService,
     request: JsonRpcRequest,
-) -> Result<Option<TransactionView>> {
+) -> Result<Option<AccountTransactionView>> {
     let p_account: String = serde_json::from_value(request.get_param(0))?;
     let sequence: u64 = serde_json::from_value(request.get_param(1))?;
     let include_events: bool = serde_json::from_value(request.get_param(2))?;
+    let include_pending = serde_json::from_value::<bool>(request.get_param(3)).unwrap_or(false);
 
     let account = AccountAddress::try_from(p_account)?;
 
     let tx = service
         .db
         .get_txn_by_account(account, sequence, request.version(), include_events)?;
-
     if let Some(tx) = tx {
-        if include_events {
-            ensure!(
-                tx.events.is_some(),
-                "Storage layer didn't return events when requested!"
-            );
+        return Ok(Some(AccountTransactionView::Committed(
+            account_transaction_view(tx, include_events)?,
+        )));
+    }
+    if !include_pending {
+        return Ok(None);
+    }
+
+    let (req_sender, callback) = oneshot::channel();
+    service
+        .mempool_sender
+        .send(MempoolClientRequest::GetPendingTransaction(
+            account, sequence, req_sender,
+        ))
+        .await?;
+    Ok(callback
+        .await??
+        .map(|pending_txn| AccountTransactionView::Pending(pending_transaction_view(pending_txn))))
+}
+
+fn pending_transaction_view(pending_txn: PendingTransaction) -> PendingTransactionView {
+    let txn = Transaction::UserTransaction(pending_txn.txn);
+    PendingTransactionView {
+        hash: txn.hash().to_hex(),
+        transaction: txn.into(),
+        ranking_score: pending_txn.ranking_score,
+        mempool_expiration_time: pending_txn.expiration_time.as_secs(),
+    }
+}
+
+/// Returns the status in mempool of the transaction sent by an account with a sequence number
+async fn get_mempool_transaction_status(
+    service: JsonRpcService,
+    request: JsonRpcRequest,
+) -> Result<Option<MempoolTransactionStatusView>> {
+    let p_account: String = serde_json::from_value(request.get_param(0))?;
+    let sequence: u64 = serde_json::from_value(request.get_param(1))?;
+    let account = AccountAddress::try_from(p_account)?;
+
+    mempool_transaction_status(
+        service,
+        TransactionQuery::BySequenceNumber(account, sequence),
+    )
+    .await
+}
+
+/// Returns the status in mempool of the transaction with a hash
+async fn get_mempool_transaction_status_by_hash(
+    service: JsonRpcService,
+    request: JsonRpcRequest,
+) -> Result<Option<MempoolTransactionStatusView>> {
+    let p_hash: String = serde_json::from_value(request.get_param(0))?;
+    let hash = HashValue::from_hex(&p_hash)?;
+
+    mempool_transaction_status(service, TransactionQuery::ByHash(hash)).await
+}
+
+async fn mempool_transaction_status(
+    mut service: JsonRpcService,
+    query: TransactionQuery,
+) -> Result<Option<MempoolTransactionStatusView>> {
+    let (req_sender, callback) = oneshot::channel();
+    service
+        .mempool_sender
+        .send(MempoolClientRequest::GetTransactionStatus(
+            query, req_sender,
+        ))
+        .await?;
+    let status = match callback.await?? {
+        Some(status) => status,
+        None => return Ok(None),
+    };
+    Ok(Some(match status {
+        TransactionStatus::Validated(pending_txn) => {
+            MempoolTransactionStatusView::Validated(pending_transaction_view(pending_txn))
         }
-        let tx_version = tx.version;
-
-        let events = tx
-            .events
-            .unwrap_or_default()
-            .into_iter()
-            .map(|x| ((tx_version, x).into()))
-            .collect();
-
-        Ok(Some(TransactionView {
-            version: tx_version,
-            hash: tx.transaction.hash().to_hex(),
-            transaction: tx.transaction.into(),
-            events,
-            vm_status: tx.proof.transaction_info().major_status(),
-            gas_used: tx.proof.transaction_info().gas_used(),
-        }))
-    } else {
-        Ok(None)
+        TransactionStatus::WaitingOnSequenceGap(pending_txn) => {
+            MempoolTransactionStatusView::WaitingOnSequenceGap(pending_transaction_view(
+                pending_txn,
+            ))
+        }
+        TransactionStatus::ReadyForBroadcast(pending_txn) => {
+            MempoolTransactionStatusView::ReadyForBroadcast(pending_transaction_view(pending_txn))
+        }
+        TransactionStatus::Rejected(hash, (mempool_status, vm_status_opt)) => {
+            let error = match vm_status_opt {
+                Some(vm_status) => JsonRpcError::vm_status(vm_status),
+                None => JsonRpcError::mempool_error(mempool_status)?,
+            };
+            MempoolTransactionStatusView::Rejected(RejectedTransactionView {
+                hash: hash.to_hex(),
+                error,
+            })
+        }
+    }))
+}
+
+/// Builds the view of a transaction looked up by account and sequence number
+pub(crate) fn account_transaction_view(
+    tx: TransactionWithProof,
+    include_events: bool,
+) -> Result<TransactionView> {
+    if include_events {
+        ensure!(
+            tx.events.is_some(),
+            "Storage layer didn't return events when requested!"
+        );
+    }
+    Ok(tx.into())
+}
+
+/// Returns account transaction by account and sequence_number, with the proof of it relative to
+/// the ledger info of the request
+async fn get_account_transaction_with_proof(
+    service: JsonRpcService,
+    request: JsonRpcRequest,
+) -> Result<Option<TransactionWithProofView>> {
+    let p_account: String = serde_json::from_value(request.get_param(0))?;
+    let sequence: u64 = serde_json::from_value(request.get_param(1))?;
+    let include_events: bool = serde_json::from_value(request.get_param(2))?;
+
+    let account = AccountAddress::try_from(p_account)?;
+
+    let tx = service
+        .db
+        .get_txn_by_account(account, sequence, request.version(), include_events)?;
+    match tx {
+        Some(tx) => Ok(Some(TransactionWithProofView::try_from(&tx)?)),
+        None => Ok(None),
     }
 }
 
@@ -268,6 +534,108 @@ async fn get_events(service: JsonRpcService, request: JsonRpcRequest) -> Result<
     Ok(events)
 }
 
+/// Returns events by given access path, with the proofs of them relative to the ledger info of
+/// the request
+async fn get_events_with_proofs(
+    service: JsonRpcService,
+    request: JsonRpcRequest,
+) -> Result<Vec<EventWithProofView>> {
+    let raw_event_key: String = serde_json::from_value(request.get_param(0))?;
+    let start: u64 = serde_json::from_value(request.get_param(1))?;
+    let limit: u64 = serde_json::from_value(request.get_param(2))?;
+
+    let event_key = EventKey::try_from(&hex::decode(raw_event_key)?[..])?;
+    service
+        .db
+        .get_events_with_proofs(&event_key, start, limit, request.version())?
+        .iter()
+        .map(EventWithProofView::try_from)
+        .collect()
+}
+
+/// Returns a page of events by given access path, with versions in the given range if any, along
+/// with a cursor to get the next page with
+async fn get_events_page(
+    service: JsonRpcService,
+    request: JsonRpcRequest,
+) -> Result<EventsPageView> {
+    let raw_event_key: String = serde_json::from_value(request.get_param(0))?;
+    let cursor: Option<String> = serde_json::from_value(request.get_param(1))?;
+    let limit: u64 = serde_json::from_value(request.get_param(2))?;
+    let start_version: Option<u64> = serde_json::from_value(request.get_param(3))?;
+    let end_version: Option<u64> = serde_json::from_value(request.get_param(4))?;
+
+    ensure!(
+        limit > 0 && limit <= 1000,
+        "limit must be smaller than 1000"
+    );
+    let event_key = EventKey::try_from(&hex::decode(raw_event_key)?[..])?;
+    let mut next_seq_num = match cursor {
+        Some(cursor) => decode_events_cursor(&cursor, &event_key)?,
+        None => 0,
+    };
+    let start_version = start_version.unwrap_or(0);
+    let req_version = request.version();
+
+    let mut events = vec![];
+    let mut num_scanned = 0;
+    'scan: while num_scanned < MAX_EVENTS_SCANNED_PER_PAGE {
+        let batch = service
+            .db
+            .get_events(&event_key, next_seq_num, true, limit)?;
+        let batch_size = batch.len() as u64;
+        for (version, event) in batch {
+            if end_version.map_or(false, |end_version| version > end_version) {
+                // past the end of the range, no more events to page through
+                return Ok(EventsPageView {
+                    events,
+                    next_cursor: None,
+                });
+            }
+            // committed after the requested version, to be returned by a later request
+            if version > req_version {
+                break 'scan;
+            }
+            num_scanned += 1;
+            next_seq_num = event.sequence_number() + 1;
+            if version >= start_version {
+                events.push((version, event).into());
+                if events.len() as u64 == limit {
+                    break 'scan;
+                }
+            }
+        }
+        if batch_size < limit {
+            break;
+        }
+    }
+    Ok(EventsPageView {
+        events,
+        next_cursor: Some(encode_events_cursor(&event_key, next_seq_num)),
+    })
+}
+
+/// Encodes the position in an event stream to continue paging from
+fn encode_events_cursor(event_key: &EventKey, next_seq_num: u64) -> String {
+    let mut bytes = event_key.as_bytes().to_vec();
+    bytes.extend_from_slice(&next_seq_num.to_be_bytes());
+    hex::encode(bytes)
+}
+
+/// Decodes the position in the given event stream to continue paging from
+fn decode_events_cursor(cursor: &str, event_key: &EventKey) -> Result<u64> {
+    let bytes = hex::decode(cursor)?;
+    ensure!(
+        bytes.len() == EventKey::LENGTH + 8
+            && bytes[..EventKey::LENGTH] == event_key.as_bytes()[..],
+        "invalid cursor for event key {}",
+        event_key
+    );
+    let mut seq_num_bytes = [0u8; 8];
+    seq_num_bytes.copy_from_slice(&bytes[EventKey::LENGTH..]);
+    Ok(u64::from_be_bytes(seq_num_bytes))
+}
+
 /// Returns meta information about supported currencies
 async fn currencies_info(
     service: JsonRpcService,
@@ -297,6 +665,18 @@ async fn currencies_info(
     Ok(currencies)
 }
 
+/// Returns the codes of the supported currencies
+async fn currency_codes(
+    service: JsonRpcService,
+    request: JsonRpcRequest,
+) -> Result<Vec<Identifier>> {
+    currencies_info(service, request)
+        .await?
+        .into_iter()
+        .map(|info| from_currency_code_string(&info.code))
+        .collect()
+}
+
 /// Returns proof of new state relative to version known to client
 async fn get_state_proof(
     service: JsonRpcService,
@@ -309,6 +689,26 @@ async fn get_state_proof(
     StateProofView::try_from((request.ledger_info, proofs.0, proofs.1))
 }
 
+/// Returns the ledger infos ending the epochs in [`start_epoch`, `end_epoch`), which all have to
+/// have ended. If there are too many of them, only the first ones are returned, with `more` set.
+async fn get_epoch_change_proof(
+    service: JsonRpcService,
+    request: JsonRpcRequest,
+) -> Result<EpochChangeProofView> {
+    let start_epoch: u64 = serde_json::from_value(request.get_param(0))?;
+    let end_epoch: u64 = serde_json::from_value(request.get_param(1))?;
+    ensure!(
+        end_epoch <= request.ledger_info.ledger_info().next_block_epoch(),
+        "end_epoch {} is past the latest ended epoch {}",
+        end_epoch,
+        request.ledger_info.ledger_info().next_block_epoch() - 1,
+    );
+    let proof = service
+        .db
+        .get_epoch_ending_ledger_infos(start_epoch, end_epoch)?;
+    EpochChangeProofView::try_from(&proof)
+}
+
 /// Returns the account state to the client, alongside a proof relative to the version and
 /// ledger_version specified by the client. If version or ledger_version are not specified,
 /// the latest known versions will be used.
@@ -347,19 +747,53 @@ async fn get_network_status(service: JsonRpcService, _request: JsonRpcRequest) -
 pub(crate) fn build_registry() -> RpcRegistry {
     let mut registry = RpcRegistry::new();
     register_rpc_method!(registry, "submit", submit, 1);
+    register_rpc_method!(registry, "dry_run_transaction", dry_run_transaction, 1);
     register_rpc_method!(registry, "get_metadata", get_metadata, 1);
-    register_rpc_method!(registry, "get_account_state", get_account_state, 1);
+    register_rpc_method!(registry, "get_account_state", get_account_state, 1, 1);
+    register_rpc_method!(registry, "get_accounts", get_accounts, 2, 1);
     register_rpc_method!(registry, "get_transactions", get_transactions, 3);
     register_rpc_method!(
         registry,
         "get_account_transaction",
         get_account_transaction,
+        3,
+        1
+    );
+    register_rpc_method!(
+        registry,
+        "get_account_transaction_with_proof",
+        get_account_transaction_with_proof,
         3
     );
+    register_rpc_method!(
+        registry,
+        "get_mempool_transaction_status",
+        get_mempool_transaction_status,
+        2
+    );
+    register_rpc_method!(
+        registry,
+        "get_mempool_transaction_status_by_hash",
+        get_mempool_transaction_status_by_hash,
+        1
+    );
     register_rpc_method!(registry, "get_events", get_events, 3);
+    register_rpc_method!(
+        registry,
+        "get_events_with_proofs",
+        get_events_with_proofs,
+        3
+    );
+    register_rpc_method!(registry, "get_events_page", get_events_page, 5);
     register_rpc_method!(registry, "get_currencies", currencies_info, 0);
 
     register_rpc_method!(registry, "get_state_proof", get_state_proof, 1);
+    register_rpc_method!(
+        registry,
+        "get_epoch_change_proof",
+        get_epoch_change_proof,
+        2
+    );
     register_rpc_method!(
         registry,
         "get_account_state_with_proof",
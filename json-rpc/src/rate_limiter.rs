@@ -0,0 +1,97 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Token bucket rate limiting of JSON RPC requests, per client IP and method.
+
+use libra_config::config::{RateLimitConfig, TokenBucketConfig};
+use std::{
+    collections::HashMap,
+    net::IpAddr,
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+const SUBMIT_METHOD: &str = "submit";
+
+pub(crate) struct RateLimiter {
+    config: RateLimitConfig,
+    buckets: Mutex<HashMap<(IpAddr, String), TokenBucket>>,
+}
+
+impl RateLimiter {
+    pub fn new(config: RateLimitConfig) -> Self {
+        Self {
+            config,
+            buckets: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Takes a token from the bucket of `ip` for `method`, returning false if there's none left
+    /// and the request should be rejected
+    pub fn try_acquire(&self, ip: IpAddr, method: &str, now: Instant) -> bool {
+        if !self.config.enabled {
+            return true;
+        }
+        let bucket_config = self.bucket_config(method);
+        let mut buckets = self
+            .buckets
+            .lock()
+            .expect("[json-rpc] failed to acquire rate limiter lock");
+
+        let key = (ip, method.to_string());
+        if !buckets.contains_key(&key) && buckets.len() >= self.config.max_tracked_buckets {
+            // buckets refilled to capacity are the same as new ones, so they can be dropped
+            buckets.retain(|(_, method), bucket| !bucket.refill(&self.bucket_config(method), now));
+            if buckets.len() >= self.config.max_tracked_buckets {
+                return false;
+            }
+        }
+        buckets
+            .entry(key)
+            .or_insert_with(|| TokenBucket::full(&bucket_config, now))
+            .try_take(&bucket_config, now)
+    }
+
+    fn bucket_config(&self, method: &str) -> TokenBucketConfig {
+        match self.config.methods.get(method) {
+            Some(bucket_config) => *bucket_config,
+            None if method == SUBMIT_METHOD => self.config.submit,
+            None => self.config.read,
+        }
+    }
+}
+
+struct TokenBucket {
+    tokens: f64,
+    last_refill: Instant,
+}
+
+impl TokenBucket {
+    fn full(config: &TokenBucketConfig, now: Instant) -> Self {
+        Self {
+            tokens: config.capacity as f64,
+            last_refill: now,
+        }
+    }
+
+    /// Adds the tokens refilled since the last refill, returning whether the bucket is full
+    fn refill(&mut self, config: &TokenBucketConfig, now: Instant) -> bool {
+        let elapsed = now
+            .checked_duration_since(self.last_refill)
+            .unwrap_or_else(|| Duration::from_secs(0));
+        self.tokens = (self.tokens + elapsed.as_secs_f64() * config.refill_per_sec as f64)
+            .min(config.capacity as f64);
+        self.last_refill = std::cmp::max(self.last_refill, now);
+        self.tokens >= config.capacity as f64
+    }
+
+    fn try_take(&mut self, config: &TokenBucketConfig, now: Instant) -> bool {
+        self.refill(config, now);
+        if self.tokens >= 1.0 {
+            self.tokens -= 1.0;
+            true
+        } else {
+            false
+        }
+    }
+}
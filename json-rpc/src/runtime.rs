@@ -2,19 +2,27 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
+    cache::ResponseCache,
     counters,
     errors::JsonRpcError,
+    health::health_routes,
     methods::{build_registry, JsonRpcRequest, JsonRpcService, RpcRegistry},
+    rate_limiter::RateLimiter,
+    subscriptions::ws_route,
 };
 use futures::future::join_all;
-use libra_config::config::{NodeConfig, RoleType};
+use libra_config::config::{CorsConfig, NodeConfig, RoleType, RpcConfig};
 use libra_json_rpc_types::views::{
     JSONRPC_LIBRA_LEDGER_TIMESTAMPUSECS, JSONRPC_LIBRA_LEDGER_VERSION,
 };
 use libra_mempool::MempoolClientSender;
-use libra_types::ledger_info::LedgerInfoWithSignatures;
+use libra_types::{chain_id::ChainId, ledger_info::LedgerInfoWithSignatures};
 use serde_json::{map::Map, Value};
-use std::{net::SocketAddr, sync::Arc};
+use std::{
+    net::SocketAddr,
+    sync::Arc,
+    time::{Duration, Instant},
+};
 use storage_interface::DbReader;
 use tokio::runtime::{Builder, Runtime};
 use warp::{
@@ -28,15 +36,17 @@ const LABEL_INVALID_FORMAT: &str = "invalid_format";
 const LABEL_INVALID_METHOD: &str = "invalid_method";
 const LABEL_INVALID_PARAMS: &str = "invalid_params";
 const LABEL_MISSING_METHOD: &str = "method_not_found";
+const LABEL_RATE_LIMITED: &str = "rate_limited";
 const LABEL_SUCCESS: &str = "success";
 
 /// Creates HTTP server (warp-based) that serves JSON RPC requests
 /// Returns handle to corresponding Tokio runtime
 pub fn bootstrap(
-    address: SocketAddr,
+    config: &RpcConfig,
     libra_db: Arc<dyn DbReader>,
     mp_sender: MempoolClientSender,
     role: RoleType,
+    chain_id: ChainId,
 ) -> Runtime {
     let runtime = Builder::new()
         .thread_name("rpc-")
@@ -46,17 +56,39 @@ pub fn bootstrap(
         .expect("[rpc] failed to create runtime");
 
     let registry = Arc::new(build_registry());
-    let service = JsonRpcService::new(libra_db, mp_sender, role);
+    let rate_limiter = Arc::new(RateLimiter::new(config.rate_limit.clone()));
+    let cache = Arc::new(ResponseCache::new(&config.response_cache));
+    let service = JsonRpcService::new(libra_db, mp_sender, role, &config.dry_run);
 
+    let subscriptions = ws_route(
+        service.clone(),
+        Arc::clone(&rate_limiter),
+        &config.subscriptions,
+    );
+    let health = health_routes(
+        service.clone(),
+        chain_id,
+        Duration::from_secs(config.ready_max_sync_lag_secs),
+    );
     let handler = warp::any()
         .and(warp::path::end())
         .and(warp::post())
         .and(warp::header::exact("content-type", "application/json"))
         .and(warp::body::json())
+        .and(warp::addr::remote())
         .and(warp::any().map(move || service.clone()))
         .and(warp::any().map(move || Arc::clone(&registry)))
+        .and(warp::any().map(move || Arc::clone(&rate_limiter)))
+        .and(warp::any().map(move || Arc::clone(&cache)))
         .and_then(rpc_endpoint);
 
+    let routes = health.or(subscriptions).or(handler);
+    let routes = if config.cors.allowed_origins.is_empty() {
+        routes.map(boxed_reply).boxed()
+    } else {
+        routes.with(cors(&config.cors)).map(boxed_reply).boxed()
+    };
+
     // Ensure that we actually bind to the socket first before spawning the
     // server tasks. This helps in tests to prevent races where a client attempts
     // to make a request before the server task is actually listening on the
@@ -64,18 +96,56 @@ pub fn bootstrap(
     //
     // Note: we need to enter the runtime context first to actually bind, since
     //       tokio TcpListener can only be bound inside a tokio context.
-    let server = runtime.enter(move || warp::serve(handler).bind(address));
-    runtime.handle().spawn(server);
+    let address = config.address;
+    match config.tls.clone() {
+        Some(tls) => {
+            let server = runtime.enter(move || {
+                warp::serve(routes)
+                    .tls()
+                    .cert_path(tls.cert_path)
+                    .key_path(tls.key_path)
+                    .bind(address)
+            });
+            runtime.handle().spawn(server);
+        }
+        None => {
+            let server = runtime.enter(move || warp::serve(routes).bind(address));
+            runtime.handle().spawn(server);
+        }
+    }
     runtime
 }
 
+fn boxed_reply(reply: impl warp::Reply + 'static) -> Box<dyn warp::Reply> {
+    Box::new(reply)
+}
+
+/// Builds the CORS policy allowing browsers on `config.allowed_origins` to make requests
+fn cors(config: &CorsConfig) -> warp::filters::cors::Builder {
+    let cors = warp::cors()
+        .allow_methods(vec!["GET", "POST"])
+        .allow_header("content-type")
+        .max_age(Duration::from_secs(config.max_age_secs));
+    if config.allowed_origins.iter().any(|origin| origin == "*") {
+        cors.allow_any_origin()
+    } else {
+        cors.allow_origins(config.allowed_origins.iter().map(String::as_str))
+    }
+}
+
 /// Creates JSON RPC endpoint by given node config
 pub fn bootstrap_from_config(
     config: &NodeConfig,
     libra_db: Arc<dyn DbReader>,
     mp_sender: MempoolClientSender,
 ) -> Runtime {
-    bootstrap(config.rpc.address, libra_db, mp_sender, config.base.role)
+    bootstrap(
+        &config.rpc,
+        libra_db,
+        mp_sender,
+        config.base.role,
+        config.base.chain_id,
+    )
 }
 
 /// JSON RPC entry point
@@ -83,44 +153,75 @@ pub fn bootstrap_from_config(
 /// Performs routing based on methods defined in `registry`
 async fn rpc_endpoint(
     data: Value,
+    remote: Option<SocketAddr>,
     service: JsonRpcService,
     registry: Arc<RpcRegistry>,
+    rate_limiter: Arc<RateLimiter>,
+    cache: Arc<ResponseCache>,
 ) -> Result<Box<dyn warp::Reply>, warp::Rejection> {
     // take snapshot of latest version of DB to be used across all requests, especially for batched requests
     let ledger_info = service
         .get_latest_ledger_info()
         .map_err(|_| reject::custom(DatabaseError))?;
 
-    let resp = Ok(if let Value::Array(requests) = data {
+    let (resp, immutable) = if let Value::Array(requests) = data {
         // batch API call
         let futures = requests.into_iter().map(|req| {
             rpc_request_handler(
                 req,
+                remote,
                 service.clone(),
                 Arc::clone(&registry),
+                Arc::clone(&rate_limiter),
+                Arc::clone(&cache),
                 ledger_info.clone(),
             )
         });
-        let responses = join_all(futures).await;
-        warp::reply::json(&Value::Array(responses))
+        let (responses, immutable): (Vec<_>, Vec<_>) = join_all(futures).await.into_iter().unzip();
+        let all_immutable = !immutable.is_empty() && immutable.into_iter().all(|i| i);
+        (warp::reply::json(&Value::Array(responses)), all_immutable)
     } else {
         // single API call
-        let resp = rpc_request_handler(data, service, registry, ledger_info).await;
-        warp::reply::json(&resp)
-    });
+        let (resp, immutable) = rpc_request_handler(
+            data,
+            remote,
+            service,
+            registry,
+            rate_limiter,
+            Arc::clone(&cache),
+            ledger_info,
+        )
+        .await;
+        (warp::reply::json(&resp), immutable)
+    };
 
-    Ok(Box::new(resp) as Box<dyn warp::Reply>)
+    // results which can't change can be kept by HTTP caches, though the ledger version and
+    // timestamp they come with are the ones they're first served at
+    if immutable {
+        Ok(Box::new(warp::reply::with_header(
+            resp,
+            "cache-control",
+            cache.cache_control(),
+        )) as Box<dyn warp::Reply>)
+    } else {
+        Ok(Box::new(resp) as Box<dyn warp::Reply>)
+    }
 }
 
 /// Handler of single RPC request
-/// Performs validation and executes corresponding rpc handler
+/// Performs validation and executes corresponding rpc handler, serving the result from `cache` if
+/// it can't change. Returns the response along with whether its result can't change.
 async fn rpc_request_handler(
     req: Value,
+    remote: Option<SocketAddr>,
     service: JsonRpcService,
     registry: Arc<RpcRegistry>,
+    rate_limiter: Arc<RateLimiter>,
+    cache: Arc<ResponseCache>,
     ledger_info: LedgerInfoWithSignatures,
-) -> Value {
+) -> (Value, bool) {
     let request: Map<String, Value>;
+    let mut immutable = false;
     let mut response = Map::new();
     let version = ledger_info.ledger_info().version();
     let timestamp = ledger_info.ledger_info().timestamp_usecs();
@@ -147,7 +248,7 @@ async fn rpc_request_handler(
                 JsonRpcError::invalid_request(),
                 Some(LABEL_INVALID_FORMAT),
             );
-            return Value::Object(response);
+            return (Value::Object(response), false);
         }
     }
 
@@ -158,14 +259,14 @@ async fn rpc_request_handler(
         }
         Err(err) => {
             set_response_error(&mut response, err, Some(LABEL_INVALID_FORMAT));
-            return Value::Object(response);
+            return (Value::Object(response), false);
         }
     };
 
     // verify protocol version
     if let Err(err) = verify_protocol(&request) {
         set_response_error(&mut response, err, Some(LABEL_INVALID_FORMAT));
-        return Value::Object(response);
+        return (Value::Object(response), false);
     }
 
     // parse parameters
@@ -180,7 +281,7 @@ async fn rpc_request_handler(
                 JsonRpcError::invalid_params(),
                 Some(LABEL_INVALID_PARAMS),
             );
-            return Value::Object(response);
+            return (Value::Object(response), false);
         }
     }
 
@@ -191,29 +292,55 @@ async fn rpc_request_handler(
     // get rpc handler
     match request.get("method") {
         Some(Value::String(name)) => match registry.get(name) {
-            Some(handler) => match handler(service, request_params).await {
-                Ok(result) => {
-                    response.insert("result".to_string(), result);
-                    counters::REQUESTS
-                        .with_label_values(&[name, LABEL_SUCCESS])
-                        .inc();
+            Some(_)
+                if remote.map_or(false, |remote| {
+                    !rate_limiter.try_acquire(remote.ip(), name, Instant::now())
+                }) =>
+            {
+                set_response_error(
+                    &mut response,
+                    JsonRpcError::rate_limit_exceeded(name),
+                    Some(LABEL_RATE_LIMITED),
+                );
+            }
+            Some(handler) => {
+                let cache_key = cache.key(name, &request_params);
+                let cached = cache_key.as_ref().and_then(|key| cache.get(key));
+                if cached.is_some() {
+                    counters::CACHED_RESPONSES.with_label_values(&[name]).inc();
                 }
-                Err(err) => {
-                    // check for custom error
-                    if let Some(custom_error) = err.downcast_ref::<JsonRpcError>() {
-                        set_response_error(&mut response, custom_error.clone(), None);
-                    } else {
-                        set_response_error(
-                            &mut response,
-                            JsonRpcError::internal_error(err.to_string()),
-                            None,
-                        );
+                let result = match cached {
+                    Some(result) => Ok(result),
+                    None => handler(service, request_params).await,
+                };
+                match result {
+                    Ok(result) => {
+                        if let Some(key) = cache_key {
+                            cache.insert(key, result.clone());
+                            immutable = true;
+                        }
+                        response.insert("result".to_string(), result);
+                        counters::REQUESTS
+                            .with_label_values(&[name, LABEL_SUCCESS])
+                            .inc();
+                    }
+                    Err(err) => {
+                        // check for custom error
+                        if let Some(custom_error) = err.downcast_ref::<JsonRpcError>() {
+                            set_response_error(&mut response, custom_error.clone(), None);
+                        } else {
+                            set_response_error(
+                                &mut response,
+                                JsonRpcError::internal_error(err.to_string()),
+                                None,
+                            );
+                        }
+                        counters::REQUESTS
+                            .with_label_values(&[name, LABEL_FAIL])
+                            .inc();
                     }
-                    counters::REQUESTS
-                        .with_label_values(&[name, LABEL_FAIL])
-                        .inc();
                 }
-            },
+            }
             None => {
                 set_response_error(
                     &mut response,
@@ -231,12 +358,16 @@ async fn rpc_request_handler(
         }
     }
 
-    Value::Object(response)
+    (Value::Object(response), immutable)
 }
 
 // Sets the JSON RPC error value for a given response.
 // If a counter label is supplied, also increments the invalid request counter using the label,
-fn set_response_error(response: &mut Map<String, Value>, error: JsonRpcError, label: Option<&str>) {
+pub(crate) fn set_response_error(
+    response: &mut Map<String, Value>,
+    error: JsonRpcError,
+    label: Option<&str>,
+) {
     response.insert("error".to_string(), error.serialize());
 
     if let Some(label) = label {
@@ -244,7 +375,7 @@ fn set_response_error(response: &mut Map<String, Value>, error: JsonRpcError, la
     }
 }
 
-fn parse_request_id(request: &Map<String, Value>) -> Result<Value, JsonRpcError> {
+pub(crate) fn parse_request_id(request: &Map<String, Value>) -> Result<Value, JsonRpcError> {
     match request.get("id") {
         Some(req_id) => {
             if req_id.is_string() || req_id.is_number() || req_id.is_null() {
@@ -257,7 +388,7 @@ fn parse_request_id(request: &Map<String, Value>) -> Result<Value, JsonRpcError>
     }
 }
 
-fn verify_protocol(request: &Map<String, Value>) -> Result<(), JsonRpcError> {
+pub(crate) fn verify_protocol(request: &Map<String, Value>) -> Result<(), JsonRpcError> {
     if let Some(Value::String(protocol)) = request.get("jsonrpc") {
         if protocol == "2.0" {
             return Ok(());
@@ -0,0 +1,448 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! WebSocket endpoint streaming committed transactions and events to subscribers
+//!
+//! Clients connect to `/ws` and send JSON RPC requests over the socket to subscribe:
+//!   - `subscribe_to_events(event_key, start_seq_num)`
+//!   - `subscribe_to_transactions(account, start_seq_num, include_events)`
+//! Each subscription is acknowledged with a response carrying the request id, after which
+//! everything from `start_seq_num` on is pushed as `subscription` notifications carrying the same
+//! id: what's already committed first, then the rest as it commits.
+//!
+//! Opening connections and subscribing are rate limited per client IP like other requests, and the
+//! number of connections and subscriptions open at a time is capped.
+
+use crate::{
+    counters,
+    errors::JsonRpcError,
+    methods::{account_transaction_view, JsonRpcService},
+    rate_limiter::RateLimiter,
+    runtime::{parse_request_id, set_response_error, verify_protocol},
+    views::EventView,
+};
+use anyhow::Result;
+use futures::{channel::mpsc, future, SinkExt, StreamExt};
+use libra_config::config::SubscriptionConfig;
+use libra_json_rpc_types::views::JSONRPC_LIBRA_LEDGER_VERSION;
+use libra_types::{account_address::AccountAddress, event::EventKey, transaction::Version};
+use serde::Serialize;
+use serde_json::{map::Map, Value};
+use std::{
+    convert::TryFrom,
+    net::SocketAddr,
+    str::FromStr,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+    time::{Duration, Instant},
+};
+use warp::{
+    http::StatusCode,
+    ws::{Message, WebSocket, Ws},
+    Filter,
+};
+
+/// How often subscriptions check the DB for newly committed transactions and events
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+/// Number of events read from the DB at a time while catching up
+const EVENTS_PAGE_SIZE: u64 = 100;
+/// Method name opening connections is rate limited by
+const CONNECT_METHOD: &str = "ws_connect";
+/// Number of notifications buffered for a connection, before its subscriptions wait for the
+/// client to catch up
+const CONNECTION_BUFFER_SIZE: usize = 1024;
+
+/// Numbers of connections and subscriptions open, against the limits on them
+struct Limits {
+    config: SubscriptionConfig,
+    num_connections: AtomicUsize,
+    num_subscriptions: AtomicUsize,
+}
+
+#[derive(Clone, Copy)]
+enum SlotKind {
+    Connection,
+    Subscription,
+}
+
+impl Limits {
+    fn count(&self, kind: SlotKind) -> &AtomicUsize {
+        match kind {
+            SlotKind::Connection => &self.num_connections,
+            SlotKind::Subscription => &self.num_subscriptions,
+        }
+    }
+
+    fn max(&self, kind: SlotKind) -> usize {
+        match kind {
+            SlotKind::Connection => self.config.max_connections,
+            SlotKind::Subscription => self.config.max_subscriptions,
+        }
+    }
+}
+
+/// One of the limited number of connections or subscriptions, given back when dropped
+struct Slot {
+    limits: Arc<Limits>,
+    kind: SlotKind,
+}
+
+impl Slot {
+    fn take(limits: &Arc<Limits>, kind: SlotKind) -> Option<Self> {
+        let count = limits.count(kind);
+        if count.fetch_add(1, Ordering::SeqCst) < limits.max(kind) {
+            Some(Slot {
+                limits: Arc::clone(limits),
+                kind,
+            })
+        } else {
+            count.fetch_sub(1, Ordering::SeqCst);
+            None
+        }
+    }
+}
+
+impl Drop for Slot {
+    fn drop(&mut self) {
+        self.limits.count(self.kind).fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+/// Builds the warp filter serving subscriptions over WebSocket at `/ws`
+pub(crate) fn ws_route(
+    service: JsonRpcService,
+    rate_limiter: Arc<RateLimiter>,
+    config: &SubscriptionConfig,
+) -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone {
+    let limits = Arc::new(Limits {
+        config: config.clone(),
+        num_connections: AtomicUsize::new(0),
+        num_subscriptions: AtomicUsize::new(0),
+    });
+    warp::path("ws")
+        .and(warp::path::end())
+        .and(warp::ws())
+        .and(warp::addr::remote())
+        .and(warp::any().map(move || service.clone()))
+        .and(warp::any().map(move || Arc::clone(&rate_limiter)))
+        .and(warp::any().map(move || Arc::clone(&limits)))
+        .map(
+            |ws: Ws,
+             remote: Option<SocketAddr>,
+             service: JsonRpcService,
+             rate_limiter: Arc<RateLimiter>,
+             limits: Arc<Limits>| {
+                if is_rate_limited(&rate_limiter, remote, CONNECT_METHOD) {
+                    return Box::new(warp::reply::with_status(
+                        "rate limit exceeded",
+                        StatusCode::TOO_MANY_REQUESTS,
+                    )) as Box<dyn warp::Reply>;
+                }
+                let connection = match Slot::take(&limits, SlotKind::Connection) {
+                    Some(connection) => connection,
+                    None => {
+                        return Box::new(warp::reply::with_status(
+                            "too many connections",
+                            StatusCode::SERVICE_UNAVAILABLE,
+                        ))
+                    }
+                };
+                Box::new(ws.on_upgrade(move |socket| {
+                    serve_connection(socket, remote, service, rate_limiter, connection)
+                }))
+            },
+        )
+}
+
+fn is_rate_limited(rate_limiter: &RateLimiter, remote: Option<SocketAddr>, method: &str) -> bool {
+    remote.map_or(false, |remote| {
+        !rate_limiter.try_acquire(remote.ip(), method, Instant::now())
+    })
+}
+
+/// Serves the subscriptions requested on a connection until either side closes it
+async fn serve_connection(
+    socket: WebSocket,
+    remote: Option<SocketAddr>,
+    service: JsonRpcService,
+    rate_limiter: Arc<RateLimiter>,
+    connection: Slot,
+) {
+    let limits = Arc::clone(&connection.limits);
+    let (mut ws_sink, mut ws_stream) = socket.split();
+    let (sender, mut receiver) = mpsc::channel::<Value>(CONNECTION_BUFFER_SIZE);
+
+    let forward = async move {
+        while let Some(msg) = receiver.next().await {
+            if ws_sink.send(Message::text(msg.to_string())).await.is_err() {
+                break;
+            }
+        }
+    };
+    let read = async move {
+        let mut num_subscriptions = 0;
+        while let Some(Ok(msg)) = ws_stream.next().await {
+            if msg.is_close() {
+                break;
+            }
+            // pings and binary messages are not requests
+            let text = match msg.to_str() {
+                Ok(text) => text,
+                Err(_) => continue,
+            };
+            let mut sender = sender.clone();
+            let response = match parse_request(text) {
+                Ok((id, subscription)) => {
+                    let method = subscription.method_name();
+                    let max_per_connection = limits.config.max_subscriptions_per_connection;
+                    if is_rate_limited(&rate_limiter, remote, method) {
+                        response(id, Err(JsonRpcError::rate_limit_exceeded(method)))
+                    } else if num_subscriptions >= max_per_connection {
+                        response(
+                            id,
+                            Err(JsonRpcError::internal_error(format!(
+                                "too many subscriptions on the connection, at most {} allowed",
+                                max_per_connection
+                            ))),
+                        )
+                    } else if let Some(slot) = Slot::take(&limits, SlotKind::Subscription) {
+                        num_subscriptions += 1;
+                        tokio::spawn(subscription.run(
+                            service.clone(),
+                            id.clone(),
+                            sender.clone(),
+                            slot,
+                        ));
+                        response(id, Ok(Value::Null))
+                    } else {
+                        response(
+                            id,
+                            Err(JsonRpcError::internal_error(format!(
+                                "too many subscriptions on the server, at most {} allowed",
+                                limits.config.max_subscriptions
+                            ))),
+                        )
+                    }
+                }
+                Err((id, err)) => response(id, Err(err)),
+            };
+            if sender.send(response).await.is_err() {
+                break;
+            }
+        }
+    };
+
+    futures::pin_mut!(forward, read);
+    future::select(forward, read).await;
+    drop(connection);
+}
+
+/// Parses a subscription request, returning the request id along with the error if it's invalid
+fn parse_request(text: &str) -> Result<(Value, Subscription), (Value, JsonRpcError)> {
+    let request = match serde_json::from_str(text) {
+        Ok(Value::Object(request)) => request,
+        _ => return Err((Value::Null, JsonRpcError::invalid_request())),
+    };
+    let id = parse_request_id(&request).map_err(|err| (Value::Null, err))?;
+    verify_protocol(&request).map_err(|err| (id.clone(), err))?;
+
+    let params = match request.get("params") {
+        Some(Value::Array(params)) => params,
+        _ => return Err((id, JsonRpcError::invalid_params())),
+    };
+    let subscription = match request.get("method") {
+        Some(Value::String(method)) => Subscription::parse(method, params),
+        _ => Err(JsonRpcError::invalid_request()),
+    }
+    .map_err(|err| (id.clone(), err))?;
+
+    Ok((id, subscription))
+}
+
+fn response(id: Value, result: Result<Value, JsonRpcError>) -> Value {
+    let mut response = Map::new();
+    response.insert("jsonrpc".to_string(), Value::String("2.0".to_string()));
+    response.insert("id".to_string(), id);
+    match result {
+        Ok(result) => {
+            response.insert("result".to_string(), result);
+        }
+        Err(err) => set_response_error(&mut response, err, None),
+    }
+    Value::Object(response)
+}
+
+fn notification(id: &Value, version: Version, result: Value) -> Value {
+    let mut params = Map::new();
+    params.insert("id".to_string(), id.clone());
+    params.insert("result".to_string(), result);
+
+    let mut notification = Map::new();
+    notification.insert("jsonrpc".to_string(), Value::String("2.0".to_string()));
+    notification.insert(
+        "method".to_string(),
+        Value::String("subscription".to_string()),
+    );
+    notification.insert("params".to_string(), Value::Object(params));
+    notification.insert(
+        JSONRPC_LIBRA_LEDGER_VERSION.to_string(),
+        Value::Number(version.into()),
+    );
+    Value::Object(notification)
+}
+
+enum Subscription {
+    Events {
+        event_key: EventKey,
+        next_seq_num: u64,
+    },
+    Transactions {
+        account: AccountAddress,
+        next_seq_num: u64,
+        include_events: bool,
+    },
+}
+
+impl Subscription {
+    fn parse(method: &str, params: &[Value]) -> Result<Self, JsonRpcError> {
+        let invalid_params = |_| JsonRpcError::invalid_params();
+        match (method, params) {
+            ("subscribe_to_events", [event_key, start_seq_num]) => {
+                let event_key: String =
+                    serde_json::from_value(event_key.clone()).map_err(invalid_params)?;
+                let event_key = hex::decode(event_key)
+                    .ok()
+                    .and_then(|bytes| EventKey::try_from(&bytes[..]).ok())
+                    .ok_or_else(JsonRpcError::invalid_params)?;
+                Ok(Subscription::Events {
+                    event_key,
+                    next_seq_num: serde_json::from_value(start_seq_num.clone())
+                        .map_err(invalid_params)?,
+                })
+            }
+            ("subscribe_to_transactions", [account, start_seq_num, include_events]) => {
+                let account: String =
+                    serde_json::from_value(account.clone()).map_err(invalid_params)?;
+                Ok(Subscription::Transactions {
+                    account: AccountAddress::from_str(&account)
+                        .map_err(|_| JsonRpcError::invalid_params())?,
+                    next_seq_num: serde_json::from_value(start_seq_num.clone())
+                        .map_err(invalid_params)?,
+                    include_events: serde_json::from_value(include_events.clone())
+                        .map_err(invalid_params)?,
+                })
+            }
+            ("subscribe_to_events", _) | ("subscribe_to_transactions", _) => {
+                Err(JsonRpcError::invalid_params())
+            }
+            _ => Err(JsonRpcError::method_not_found()),
+        }
+    }
+
+    fn method_name(&self) -> &'static str {
+        match self {
+            Subscription::Events { .. } => "subscribe_to_events",
+            Subscription::Transactions { .. } => "subscribe_to_transactions",
+        }
+    }
+
+    /// Pushes what's committed to `sender` as notifications, until the connection is closed or
+    /// reading the DB fails, holding on to `_slot` until then
+    async fn run(
+        mut self,
+        service: JsonRpcService,
+        id: Value,
+        mut sender: mpsc::Sender<Value>,
+        _slot: Slot,
+    ) {
+        let gauge = counters::ACTIVE_SUBSCRIPTIONS.with_label_values(&[self.method_name()]);
+        gauge.inc();
+
+        let mut interval = tokio::time::interval(POLL_INTERVAL);
+        loop {
+            interval.tick().await;
+            if sender.is_closed() {
+                break;
+            }
+            match self.push_committed(&service, &id, &mut sender).await {
+                Ok(true) => (),
+                Ok(false) => break,
+                Err(err) => {
+                    let error = JsonRpcError::internal_error(err.to_string());
+                    let _ = sender.send(response(id, Err(error))).await;
+                    break;
+                }
+            }
+        }
+
+        gauge.dec();
+    }
+
+    /// Pushes everything committed since the last call, returning false if the connection is
+    /// closed
+    async fn push_committed(
+        &mut self,
+        service: &JsonRpcService,
+        id: &Value,
+        sender: &mut mpsc::Sender<Value>,
+    ) -> Result<bool> {
+        let version = service.get_latest_ledger_info()?.ledger_info().version();
+        match self {
+            Subscription::Events {
+                event_key,
+                next_seq_num,
+            } => loop {
+                let events =
+                    service
+                        .db
+                        .get_events(event_key, *next_seq_num, true, EVENTS_PAGE_SIZE)?;
+                let num_events = events.len() as u64;
+                for (txn_version, event) in events {
+                    // committed after the ledger info read above, to be pushed next time
+                    if txn_version > version {
+                        return Ok(true);
+                    }
+                    *next_seq_num = event.sequence_number() + 1;
+                    let view = EventView::from((txn_version, event));
+                    if !notify(sender, id, version, view).await? {
+                        return Ok(false);
+                    }
+                }
+                if num_events < EVENTS_PAGE_SIZE {
+                    return Ok(true);
+                }
+            },
+            Subscription::Transactions {
+                account,
+                next_seq_num,
+                include_events,
+            } => {
+                while let Some(txn) = service.db.get_txn_by_account(
+                    *account,
+                    *next_seq_num,
+                    version,
+                    *include_events,
+                )? {
+                    *next_seq_num += 1;
+                    let view = account_transaction_view(txn, *include_events)?;
+                    if !notify(sender, id, version, view).await? {
+                        return Ok(false);
+                    }
+                }
+                Ok(true)
+            }
+        }
+    }
+}
+
+async fn notify(
+    sender: &mut mpsc::Sender<Value>,
+    id: &Value,
+    version: Version,
+    result: impl Serialize,
+) -> Result<bool> {
+    let notification = notification(id, version, serde_json::to_value(result)?);
+    Ok(sender.send(notification).await.is_ok())
+}
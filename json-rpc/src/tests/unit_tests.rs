@@ -2,44 +2,65 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
+    cache::LruCache,
     errors::{JsonRpcError, ServerCode},
-    tests::utils::{test_bootstrap, MockLibraDB},
+    methods::JsonRpcService,
+    rate_limiter::RateLimiter,
+    subscriptions::ws_route,
+    tests::utils::{test_bootstrap, test_bootstrap_with_config, MockLibraDB},
 };
 use futures::{channel::mpsc::channel, StreamExt};
-use libra_config::utils;
+use libra_config::{
+    config::{
+        CorsConfig, DryRunConfig, RateLimitConfig, ResponseCacheConfig, RoleType, RpcConfig,
+        SubscriptionConfig, TokenBucketConfig,
+    },
+    utils,
+};
 use libra_crypto::{ed25519::Ed25519PrivateKey, hash::CryptoHash, HashValue, PrivateKey, Uniform};
 use libra_json_rpc_client::{
     views::{
-        AccountStateWithProofView, BlockMetadata, BytesView, EventView, StateProofView,
-        TransactionDataView, TransactionView,
+        AccountStateWithProofView, AccountView, AccountWithStateView, BlockMetadata, BytesView,
+        EventView, EventWithProofView, EventsPageView, MempoolTransactionStatusView,
+        PendingTransactionView, StateProofView, TransactionDataView, TransactionView,
+        TransactionWithProofView,
     },
     JsonRpcAsyncClient, JsonRpcBatch, JsonRpcResponse, ResponseAsView,
 };
 use libra_json_rpc_types::views::{
     JSONRPC_LIBRA_LEDGER_TIMESTAMPUSECS, JSONRPC_LIBRA_LEDGER_VERSION,
 };
+use libra_mempool::{
+    MempoolClientRequest, PendingTransaction, TransactionQuery, TransactionStatus,
+};
 use libra_proptest_helpers::ValueGenerator;
 use libra_types::{
     account_address::AccountAddress,
-    account_config::AccountResource,
+    account_config::{AccountResource, CORE_CODE_ADDRESS},
     account_state_blob::{AccountStateBlob, AccountStateWithProof},
-    contract_event::ContractEvent,
+    chain_id::ChainId,
+    contract_event::{ContractEvent, EventWithProof},
     event::EventKey,
     ledger_info::LedgerInfoWithSignatures,
     mempool_status::{MempoolStatus, MempoolStatusCode},
     proof::{SparseMerkleProof, TransactionAccumulatorProof, TransactionInfoWithProof},
     test_helpers::transaction_test_helpers::get_test_signed_txn,
-    transaction::{Transaction, TransactionInfo, TransactionPayload},
-    vm_status::{StatusCode, VMStatus},
+    transaction::{Transaction, TransactionInfo, TransactionPayload, TransactionWithProof},
+    vm_status::{AbortLocation, StatusCode, VMStatus},
 };
 use libradb::test_helper::arb_blocks_to_commit;
-use move_core_types::language_storage::TypeTag;
+use move_core_types::{
+    identifier::Identifier,
+    language_storage::{ModuleId, TypeTag},
+};
 use proptest::prelude::*;
 use std::{
     collections::{BTreeMap, HashMap},
     convert::TryFrom,
+    net::IpAddr,
     str::FromStr,
     sync::Arc,
+    time::{Duration, Instant},
 };
 use storage_interface::DbReader;
 use tokio::runtime::Runtime;
@@ -178,6 +199,248 @@ fn test_json_rpc_protocol() {
     assert!(data.get(JSONRPC_LIBRA_LEDGER_TIMESTAMPUSECS).is_some());
 }
 
+#[test]
+fn test_rate_limiter() {
+    let bucket = |capacity, refill_per_sec| TokenBucketConfig {
+        capacity,
+        refill_per_sec,
+    };
+    let mut config = RateLimitConfig {
+        enabled: true,
+        submit: bucket(1, 1),
+        read: bucket(2, 10),
+        max_tracked_buckets: 5,
+        ..RateLimitConfig::default()
+    };
+    config
+        .methods
+        .insert("get_transactions".to_string(), bucket(0, 0));
+    let rate_limiter = RateLimiter::new(config);
+    let ip = IpAddr::from([1, 2, 3, 4]);
+    let other_ip = IpAddr::from([5, 6, 7, 8]);
+    let start = Instant::now();
+
+    // buckets start full and are refilled over time
+    assert!(rate_limiter.try_acquire(ip, "get_account_state", start));
+    assert!(rate_limiter.try_acquire(ip, "get_account_state", start));
+    assert!(!rate_limiter.try_acquire(ip, "get_account_state", start));
+    let later = start + Duration::from_millis(100);
+    assert!(rate_limiter.try_acquire(ip, "get_account_state", later));
+    assert!(!rate_limiter.try_acquire(ip, "get_account_state", later));
+
+    // each client and method has its own bucket
+    assert!(rate_limiter.try_acquire(other_ip, "get_account_state", start));
+    assert!(rate_limiter.try_acquire(ip, "get_events", start));
+    assert!(rate_limiter.try_acquire(ip, "submit", start));
+    assert!(!rate_limiter.try_acquire(ip, "submit", start));
+    assert!(!rate_limiter.try_acquire(ip, "get_transactions", start));
+
+    // past the maximum number of buckets, new clients are rejected until idle buckets are full
+    assert!(rate_limiter.try_acquire(other_ip, "get_events", start));
+    assert!(!rate_limiter.try_acquire(other_ip, "submit", start));
+    let much_later = start + Duration::from_secs(10);
+    assert!(rate_limiter.try_acquire(other_ip, "submit", much_later));
+
+    // nothing is limited when disabled
+    let rate_limiter = RateLimiter::new(RateLimitConfig {
+        submit: bucket(0, 0),
+        ..RateLimitConfig::default()
+    });
+    assert!(rate_limiter.try_acquire(ip, "submit", start));
+}
+
+#[test]
+fn test_rate_limited_requests() {
+    let address = format!("127.0.0.1:{}", utils::get_available_port());
+    let config = RpcConfig {
+        address: address.parse().unwrap(),
+        rate_limit: RateLimitConfig {
+            enabled: true,
+            read: TokenBucketConfig {
+                capacity: 1,
+                refill_per_sec: 0,
+            },
+            ..RateLimitConfig::default()
+        },
+        ..RpcConfig::default()
+    };
+    let _runtime = test_bootstrap_with_config(&config, Arc::new(mock_db()), channel(1024).0);
+    let client = reqwest::blocking::Client::new();
+    let url = format!("http://{}", address);
+
+    let request = serde_json::json!({"jsonrpc": "2.0", "method": "get_account_state", "params": [1, 2], "id": 1});
+    let resp = client.post(&url).json(&request).send().unwrap();
+    assert_eq!(fetch_error(resp), -32000);
+    let resp = client.post(&url).json(&request).send().unwrap();
+    assert_eq!(fetch_error(resp), ServerCode::RateLimitExceeded as i16);
+
+    // submissions have their own budget
+    let request =
+        serde_json::json!({"jsonrpc": "2.0", "method": "submit", "params": ["invalid"], "id": 1});
+    let resp = client.post(&url).json(&request).send().unwrap();
+    assert_eq!(fetch_error(resp), -32000);
+}
+
+#[test]
+fn test_cors() {
+    let address = format!("127.0.0.1:{}", utils::get_available_port());
+    let origin = "http://localhost:3000";
+    let config = RpcConfig {
+        address: address.parse().unwrap(),
+        cors: CorsConfig {
+            allowed_origins: vec![origin.to_string()],
+            ..CorsConfig::default()
+        },
+        ..RpcConfig::default()
+    };
+    let _runtime = test_bootstrap_with_config(&config, Arc::new(mock_db()), channel(1024).0);
+    let client = reqwest::blocking::Client::new();
+    let url = format!("http://{}", address);
+
+    let preflight = client
+        .request(reqwest::Method::OPTIONS, &url)
+        .header("origin", origin)
+        .header("access-control-request-method", "POST")
+        .header("access-control-request-headers", "content-type")
+        .send()
+        .unwrap();
+    assert_eq!(preflight.status(), reqwest::StatusCode::OK);
+    assert_eq!(preflight.headers()["access-control-allow-origin"], origin);
+
+    let request =
+        serde_json::json!({"jsonrpc": "2.0", "method": "get_currencies", "params": [], "id": 1});
+    let resp = client
+        .post(&url)
+        .header("origin", origin)
+        .json(&request)
+        .send()
+        .unwrap();
+    assert_eq!(resp.status(), reqwest::StatusCode::OK);
+    assert_eq!(resp.headers()["access-control-allow-origin"], origin);
+
+    let resp = client
+        .post(&url)
+        .header("origin", "http://elsewhere.com")
+        .json(&request)
+        .send()
+        .unwrap();
+    assert_eq!(resp.status(), reqwest::StatusCode::FORBIDDEN);
+}
+
+#[test]
+fn test_lru_cache() {
+    let mut cache = LruCache::new(2);
+    cache.insert(1, "a");
+    cache.insert(2, "b");
+    assert_eq!(cache.get(&1), Some(&"a"));
+    // 2 is the least recently used
+    cache.insert(3, "c");
+    assert_eq!(cache.get(&2), None);
+    assert_eq!(cache.get(&1), Some(&"a"));
+    assert_eq!(cache.get(&3), Some(&"c"));
+    cache.insert(3, "d");
+    cache.insert(4, "e");
+    assert_eq!(cache.get(&1), None);
+    assert_eq!(cache.get(&3), Some(&"d"));
+
+    let mut disabled = LruCache::new(0);
+    disabled.insert(1, "a");
+    assert_eq!(disabled.get(&1), None);
+}
+
+#[test]
+fn test_cache_control() {
+    let mock_db = mock_db();
+    let version = mock_db.version;
+    let address = format!("127.0.0.1:{}", utils::get_available_port());
+    let _runtime = test_bootstrap(address.parse().unwrap(), Arc::new(mock_db), channel(1024).0);
+    let client = reqwest::blocking::Client::new();
+    let url = format!("http://{}", address);
+    let cache_control = |request: serde_json::Value| {
+        let resp = client.post(&url).json(&request).send().unwrap();
+        resp.headers()
+            .get("cache-control")
+            .map(|value| value.to_str().unwrap().to_string())
+    };
+
+    let committed = serde_json::json!({"jsonrpc": "2.0", "method": "get_transactions", "params": [0, version + 1, false], "id": 1});
+    let expected = Some(format!(
+        "public, max-age={}",
+        ResponseCacheConfig::default().max_age_secs
+    ));
+    assert_eq!(cache_control(committed.clone()), expected);
+    // served from the cache
+    assert_eq!(cache_control(committed.clone()), expected);
+    let proof = serde_json::json!({"jsonrpc": "2.0", "method": "get_epoch_change_proof", "params": [0, 0], "id": 2});
+    assert_eq!(
+        cache_control(serde_json::json!([committed, proof])),
+        expected
+    );
+
+    // not all committed yet
+    let pending = serde_json::json!({"jsonrpc": "2.0", "method": "get_transactions", "params": [0, version + 2, false], "id": 1});
+    assert_eq!(cache_control(pending), None);
+    let latest =
+        serde_json::json!({"jsonrpc": "2.0", "method": "get_currencies", "params": [], "id": 1});
+    assert_eq!(cache_control(latest.clone()), None);
+    assert_eq!(cache_control(serde_json::json!([latest, proof])), None);
+    let failed = serde_json::json!({"jsonrpc": "2.0", "method": "get_epoch_change_proof", "params": [0, u64::max_value()], "id": 1});
+    assert_eq!(cache_control(failed), None);
+}
+
+#[test]
+fn test_health_checks() {
+    let address = format!("127.0.0.1:{}", utils::get_available_port());
+    let config = RpcConfig {
+        address: address.parse().unwrap(),
+        ready_max_sync_lag_secs: u64::max_value(),
+        ..RpcConfig::default()
+    };
+    let mock_db = mock_db();
+    let version = mock_db.version;
+    let (mp_sender, _mp_events) = channel(1024);
+    let _runtime = test_bootstrap_with_config(&config, Arc::new(mock_db), mp_sender);
+    let client = reqwest::blocking::Client::new();
+
+    for path in &["-/healthy", "-/ready"] {
+        let resp = client
+            .get(&format!("http://{}/{}", address, path))
+            .send()
+            .unwrap();
+        assert_eq!(resp.status(), reqwest::StatusCode::OK);
+        let health: serde_json::Value = resp.json().unwrap();
+        assert_eq!(health["chain_id"], ChainId::test().id());
+        assert_eq!(health["latest_ledger_version"], version);
+        assert_eq!(health["latest_committed_version"], version);
+        assert_eq!(health["mempool_healthy"], true);
+    }
+}
+
+#[test]
+fn test_not_ready_without_mempool() {
+    let address = format!("127.0.0.1:{}", utils::get_available_port());
+    let config = RpcConfig {
+        address: address.parse().unwrap(),
+        ready_max_sync_lag_secs: u64::max_value(),
+        ..RpcConfig::default()
+    };
+    let _runtime = test_bootstrap_with_config(&config, Arc::new(mock_db()), channel(1024).0);
+    let client = reqwest::blocking::Client::new();
+
+    let resp = client
+        .get(&format!("http://{}/-/healthy", address))
+        .send()
+        .unwrap();
+    assert_eq!(resp.status(), reqwest::StatusCode::OK);
+    let resp = client
+        .get(&format!("http://{}/-/ready", address))
+        .send()
+        .unwrap();
+    assert_eq!(resp.status(), reqwest::StatusCode::SERVICE_UNAVAILABLE);
+    let health: serde_json::Value = resp.json().unwrap();
+    assert_eq!(health["mempool_healthy"], false);
+}
+
 #[test]
 fn test_transaction_submission() {
     let (mp_sender, mut mp_events) = channel(1);
@@ -193,7 +456,7 @@ fn test_transaction_submission() {
     // future that mocks shared mempool execution
     runtime.spawn(async move {
         let validator = MockVMValidator;
-        while let Some((txn, cb)) = mp_events.next().await {
+        while let Some(MempoolClientRequest::SubmitTransaction(txn, cb)) = mp_events.next().await {
             let vm_status = validator.validate_transaction(txn).unwrap().status();
             let result = if vm_status.is_some() {
                 (MempoolStatus::new(MempoolStatusCode::VmError), vm_status)
@@ -224,12 +487,15 @@ fn test_transaction_submission() {
     if let Err(e) = response {
         if let Some(error) = e.downcast_ref::<JsonRpcError>() {
             assert_eq!(error.code, ServerCode::VmValidationError as i16);
-            let vm_status: VMStatus =
-                serde_json::from_value(error.data.as_ref().unwrap().clone()).unwrap();
+            let data = error.get_vm_status_data().unwrap();
             assert_eq!(
-                vm_status.status_code(),
+                data.vm_status.status_code(),
                 StatusCode::SENDING_ACCOUNT_DOES_NOT_EXIST
             );
+            assert_eq!(data.status_code, StatusCode::SENDING_ACCOUNT_DOES_NOT_EXIST);
+            assert_eq!(data.status_code_name, "SENDING_ACCOUNT_DOES_NOT_EXIST");
+            assert_eq!(data.status_type, "Validation");
+            assert_eq!(data.move_abort, None);
         } else {
             panic!("unexpected error format");
         }
@@ -238,6 +504,27 @@ fn test_transaction_submission() {
     }
 }
 
+#[test]
+fn test_vm_status_error_explains_move_abort() {
+    let module_id = ModuleId::new(CORE_CODE_ADDRESS, Identifier::new("LibraAccount").unwrap());
+    let error = JsonRpcError::vm_status(VMStatus::MoveAbort(AbortLocation::Module(module_id), 5));
+    assert_eq!(error.code, ServerCode::VmExecutionError as i16);
+
+    let data = error.get_vm_status_data().unwrap();
+    assert_eq!(data.status_code_name, "ABORTED");
+    assert_eq!(data.status_type, "Execution");
+    let move_abort = data.move_abort.unwrap();
+    assert_eq!(move_abort.abort_code, 5);
+    assert_eq!(move_abort.reason.as_deref(), Some("EINSUFFICIENT_BALANCE"));
+    assert!(error.message.contains("EINSUFFICIENT_BALANCE"));
+
+    // aborts from scripts can't be explained
+    let error = JsonRpcError::vm_status(VMStatus::MoveAbort(AbortLocation::Script, 5));
+    let move_abort = error.get_vm_status_data().unwrap().move_abort.unwrap();
+    assert_eq!(move_abort.location, "Script");
+    assert_eq!(move_abort.reason, None);
+}
+
 // TODO: Once account configs are published in the mock DB this test can be turned back on
 //#[test]
 //fn test_get_account_state() {
@@ -367,6 +654,100 @@ fn test_get_events() {
     );
 }
 
+#[test]
+fn test_get_events_with_proofs() {
+    let (mock_db, client, mut runtime) = create_database_client_and_runtime(1);
+
+    let (first_event_version, first_event) = mock_db.events[0].clone();
+    let event_key = hex::encode(first_event.key().as_bytes());
+
+    let mut batch = JsonRpcBatch::default();
+    batch.add_get_events_with_proofs_request(event_key, first_event.sequence_number(), 10);
+    let result = execute_batch_and_get_first_response(&client, &mut runtime, batch);
+
+    let events = EventWithProofView::vec_from_response(result).unwrap();
+    let fetched_event = EventWithProof::try_from(&events[0]).unwrap();
+    assert_eq!(fetched_event.event, first_event);
+    assert_eq!(fetched_event.transaction_version, first_event_version);
+}
+
+#[test]
+fn test_get_events_page() {
+    let mut mock_db = mock_db();
+    // an event stream of 4 events, 2 at version 0 and 2 at version 1
+    let event_key = EventKey::new_from_address(&AccountAddress::random(), 0);
+    mock_db.events = (0..4)
+        .map(|seq_num| {
+            let event = ContractEvent::new(event_key, seq_num, TypeTag::Bool, vec![]);
+            (seq_num / 2, event)
+        })
+        .collect();
+    let port = utils::get_available_port();
+    let address = format!("0.0.0.0:{}", port);
+    let mp_sender = channel(1).0;
+    let mut runtime = test_bootstrap(address.parse().unwrap(), Arc::new(mock_db), mp_sender);
+    let client = JsonRpcAsyncClient::new(
+        reqwest::Url::from_str(format!("http://{}:{}", "127.0.0.1", port).as_str())
+            .expect("invalid url"),
+    );
+    let raw_event_key = hex::encode(event_key.as_bytes());
+    let mut get_page = |cursor, limit, start_version, end_version| {
+        let mut batch = JsonRpcBatch::default();
+        batch.add_get_events_page_request(
+            raw_event_key.clone(),
+            cursor,
+            limit,
+            start_version,
+            end_version,
+        );
+        let result = execute_batch_and_get_first_response(&client, &mut runtime, batch);
+        EventsPageView::from_response(result).unwrap()
+    };
+    let seq_nums = |page: &EventsPageView| -> Vec<u64> {
+        page.events.iter().map(|e| e.sequence_number).collect()
+    };
+
+    // paging through the whole stream, one event at a time
+    let mut cursor = None;
+    for seq_num in 0..4 {
+        let page = get_page(cursor, 1, None, None);
+        assert_eq!(seq_nums(&page), vec![seq_num]);
+        cursor = page.next_cursor;
+        assert!(cursor.is_some());
+    }
+    // nothing left for now, but the cursor can be used to get what's emitted later
+    let page = get_page(cursor.clone(), 1, None, None);
+    assert!(page.events.is_empty());
+    assert_eq!(page.next_cursor, cursor);
+
+    // filtering by version range
+    let page = get_page(None, 10, Some(1), None);
+    assert_eq!(seq_nums(&page), vec![2, 3]);
+    assert!(page.next_cursor.is_some());
+    let page = get_page(None, 10, None, Some(0));
+    assert_eq!(seq_nums(&page), vec![0, 1]);
+    assert_eq!(page.next_cursor, None);
+    let page = get_page(None, 1, None, Some(0));
+    assert_eq!(seq_nums(&page), vec![0]);
+    let page = get_page(page.next_cursor, 1, None, Some(0));
+    assert_eq!(seq_nums(&page), vec![1]);
+    let page = get_page(page.next_cursor, 1, None, Some(0));
+    assert!(page.events.is_empty());
+    assert_eq!(page.next_cursor, None);
+
+    // a cursor is only valid for the event stream it's returned for
+    let mut batch = JsonRpcBatch::default();
+    batch.add_get_events_page_request(
+        hex::encode(EventKey::new_from_address(&AccountAddress::random(), 0).as_bytes()),
+        cursor,
+        1,
+        None,
+        None,
+    );
+    let responses = runtime.block_on(client.execute(batch)).unwrap();
+    assert!(responses[0].is_err());
+}
+
 #[test]
 fn test_get_transactions() {
     let (mock_db, client, mut runtime) = create_database_client_and_runtime(1);
@@ -521,6 +902,184 @@ fn test_get_account_transaction() {
     }
 }
 
+#[test]
+fn test_get_account_transaction_with_proof() {
+    let (mock_db, client, mut runtime) = create_database_client_and_runtime(1);
+
+    let (version, (expected_tx, _)) = mock_db
+        .all_txns
+        .iter()
+        .enumerate()
+        .find(|(_, (t, _))| t.as_signed_user_txn().is_ok())
+        .expect("Couldn't find user transaction");
+    let signed_tx = expected_tx.as_signed_user_txn().unwrap();
+
+    let mut batch = JsonRpcBatch::default();
+    batch.add_get_account_transaction_with_proof_request(
+        signed_tx.sender(),
+        signed_tx.sequence_number(),
+        true,
+    );
+    let result = execute_batch_and_get_first_response(&client, &mut runtime, batch);
+    let view = TransactionWithProofView::optional_from_response(result)
+        .unwrap()
+        .expect("Transaction didn't exist!");
+    let tx = TransactionWithProof::try_from(&view).unwrap();
+
+    assert_eq!(tx.version, version as u64);
+    assert_eq!(&tx.transaction, expected_tx);
+    let expected_events = mock_db
+        .events
+        .iter()
+        .filter(|(ev, _)| *ev == tx.version)
+        .map(|(_, e)| e.clone())
+        .collect::<Vec<_>>();
+    assert_eq!(tx.events, Some(expected_events));
+}
+
+#[test]
+fn test_get_account_transaction_pending() {
+    let (mp_sender, mut mp_events) = channel(1);
+    let mock_db = mock_db();
+    let port = utils::get_available_port();
+    let address = format!("0.0.0.0:{}", port);
+    let mut runtime = test_bootstrap(address.parse().unwrap(), Arc::new(mock_db), mp_sender);
+    let client = JsonRpcAsyncClient::new(
+        reqwest::Url::from_str(format!("http://{}:{}", "127.0.0.1", port).as_str())
+            .expect("invalid url"),
+    );
+
+    // future that mocks shared mempool, holding a single pending transaction
+    let sender = AccountAddress::new([9; AccountAddress::LENGTH]);
+    let privkey = Ed25519PrivateKey::generate_for_testing();
+    let txn = get_test_signed_txn(sender, 0, &privkey, privkey.public_key(), None);
+    let pending_txn = PendingTransaction {
+        txn: txn.clone(),
+        ranking_score: 10,
+        expiration_time: Duration::from_secs(100),
+    };
+    runtime.spawn(async move {
+        while let Some(request) = mp_events.next().await {
+            if let MempoolClientRequest::GetPendingTransaction(account, seq, cb) = request {
+                let result = if account == pending_txn.txn.sender()
+                    && seq == pending_txn.txn.sequence_number()
+                {
+                    Some(pending_txn.clone())
+                } else {
+                    None
+                };
+                cb.send(Ok(result)).unwrap();
+            }
+        }
+    });
+
+    let mut batch = JsonRpcBatch::default();
+    batch.add_get_account_transaction_or_pending_request(sender, 0, false);
+    let result = execute_batch_and_get_first_response(&client, &mut runtime, batch);
+    let view = PendingTransactionView::from_response(result).unwrap();
+    assert_eq!(view.hash, Transaction::UserTransaction(txn).hash().to_hex());
+    assert_eq!(view.ranking_score, 10);
+    assert_eq!(view.mempool_expiration_time, 100);
+
+    // not in mempool either
+    let mut batch = JsonRpcBatch::default();
+    batch.add_get_account_transaction_or_pending_request(sender, 1, false);
+    let result = execute_batch_and_get_first_response(&client, &mut runtime, batch);
+    assert_eq!(
+        TransactionView::optional_from_response(result).unwrap(),
+        None
+    );
+
+    // mempool isn't consulted unless asked to
+    let mut batch = JsonRpcBatch::default();
+    batch.add_get_account_transaction_request(sender, 0, false);
+    let result = execute_batch_and_get_first_response(&client, &mut runtime, batch);
+    assert_eq!(
+        TransactionView::optional_from_response(result).unwrap(),
+        None
+    );
+}
+
+#[test]
+fn test_get_mempool_transaction_status() {
+    let (mp_sender, mut mp_events) = channel(1);
+    let mock_db = mock_db();
+    let port = utils::get_available_port();
+    let address = format!("0.0.0.0:{}", port);
+    let mut runtime = test_bootstrap(address.parse().unwrap(), Arc::new(mock_db), mp_sender);
+    let client = JsonRpcAsyncClient::new(
+        reqwest::Url::from_str(format!("http://{}:{}", "127.0.0.1", port).as_str())
+            .expect("invalid url"),
+    );
+
+    // future that mocks shared mempool, holding a pending transaction and a rejected one
+    let sender = AccountAddress::new([9; AccountAddress::LENGTH]);
+    let privkey = Ed25519PrivateKey::generate_for_testing();
+    let txn = get_test_signed_txn(sender, 0, &privkey, privkey.public_key(), None);
+    let pending_txn = PendingTransaction {
+        txn: txn.clone(),
+        ranking_score: 10,
+        expiration_time: Duration::from_secs(100),
+    };
+    let rejected_hash = HashValue::random();
+    runtime.spawn(async move {
+        while let Some(request) = mp_events.next().await {
+            if let MempoolClientRequest::GetTransactionStatus(query, cb) = request {
+                let result = match query {
+                    TransactionQuery::BySequenceNumber(account, 0) if account == sender => {
+                        Some(TransactionStatus::ReadyForBroadcast(pending_txn.clone()))
+                    }
+                    TransactionQuery::ByHash(hash) if hash == rejected_hash => {
+                        Some(TransactionStatus::Rejected(
+                            hash,
+                            (
+                                MempoolStatus::new(MempoolStatusCode::GasPriceBumpTooLow),
+                                None,
+                            ),
+                        ))
+                    }
+                    _ => None,
+                };
+                cb.send(Ok(result)).unwrap();
+            }
+        }
+    });
+
+    let mut batch = JsonRpcBatch::default();
+    batch.add_get_mempool_transaction_status_request(sender, 0);
+    let result = execute_batch_and_get_first_response(&client, &mut runtime, batch);
+    match MempoolTransactionStatusView::optional_from_response(result).unwrap() {
+        Some(MempoolTransactionStatusView::ReadyForBroadcast(view)) => {
+            assert_eq!(view.hash, Transaction::UserTransaction(txn).hash().to_hex());
+            assert_eq!(view.ranking_score, 10);
+        }
+        status => panic!("unexpected status: {:?}", status),
+    }
+
+    let mut batch = JsonRpcBatch::default();
+    batch.add_get_mempool_transaction_status_by_hash_request(rejected_hash.to_hex());
+    let result = execute_batch_and_get_first_response(&client, &mut runtime, batch);
+    match MempoolTransactionStatusView::optional_from_response(result).unwrap() {
+        Some(MempoolTransactionStatusView::Rejected(view)) => {
+            assert_eq!(view.hash, rejected_hash.to_hex());
+            assert_eq!(
+                view.error.code,
+                ServerCode::MempoolGasPriceBumpTooLow as i16
+            );
+        }
+        status => panic!("unexpected status: {:?}", status),
+    }
+
+    // unknown to mempool
+    let mut batch = JsonRpcBatch::default();
+    batch.add_get_mempool_transaction_status_request(sender, 1);
+    let result = execute_batch_and_get_first_response(&client, &mut runtime, batch);
+    assert_eq!(
+        MempoolTransactionStatusView::optional_from_response(result).unwrap(),
+        None
+    );
+}
+
 #[test]
 // Check that if version and ledger_version parameters are None, then the server returns the latest
 // known state.
@@ -540,6 +1099,67 @@ fn test_get_account_state_with_proof_null_versions() {
     assert_eq!(received_proof.version, expected_proof.version);
 }
 
+#[test]
+fn test_get_accounts() {
+    let (mock_db, client, mut runtime) = create_database_client_and_runtime(1);
+
+    let addresses: Vec<_> = mock_db.all_accounts.keys().cloned().collect();
+    let mut batch = JsonRpcBatch::default();
+    for address in &addresses {
+        batch.add_get_account_state_request(*address);
+    }
+    let expected_accounts: Vec<_> = runtime
+        .block_on(client.execute(batch))
+        .unwrap()
+        .into_iter()
+        .map(|response| AccountView::optional_from_response(response.unwrap()).unwrap())
+        .collect();
+
+    for &include_proofs in &[false, true] {
+        let mut batch = JsonRpcBatch::default();
+        batch.add_get_accounts_request(&addresses, include_proofs, None);
+        let result = execute_batch_and_get_first_response(&client, &mut runtime, batch);
+
+        let accounts = AccountWithStateView::vec_from_response(result).unwrap();
+        assert_eq!(accounts.len(), addresses.len());
+        for ((account, address), expected_account) in
+            accounts.iter().zip(&addresses).zip(&expected_accounts)
+        {
+            assert_eq!(account.address, address.to_string());
+            assert_eq!(&account.account, expected_account);
+            assert_eq!(account.state_with_proof.is_some(), include_proofs);
+        }
+    }
+
+    // too many addresses
+    let mut batch = JsonRpcBatch::default();
+    batch.add_get_accounts_request(&[addresses[0]; 101], false, None);
+    let responses = runtime.block_on(client.execute(batch)).unwrap();
+    assert!(responses[0].is_err());
+}
+
+#[test]
+fn test_get_accounts_at_version() {
+    let (mock_db, client, mut runtime) = create_database_client_and_runtime(1);
+    let address = get_first_account_from_mock_db(&mock_db);
+    let latest_version = mock_db.get_latest_version().unwrap();
+
+    let mut batch = JsonRpcBatch::default();
+    batch.add_get_accounts_request(&[address], false, Some(latest_version - 1));
+    batch.add_get_account_state_at_version_request(address, latest_version - 1);
+    // versions later than the ledger version can't be queried
+    batch.add_get_accounts_request(&[address], false, Some(latest_version + 1));
+    batch.add_get_account_state_at_version_request(address, latest_version + 1);
+    let responses = runtime.block_on(client.execute(batch)).unwrap();
+
+    let accounts = AccountWithStateView::vec_from_response(responses[0].clone().unwrap()).unwrap();
+    assert_eq!(accounts.len(), 1);
+    assert_eq!(accounts[0].address, address.to_string());
+    assert!(responses[1].is_ok());
+    assert!(responses[2].is_err());
+    assert!(responses[3].is_err());
+}
+
 #[test]
 fn test_get_account_state_with_proof() {
     let (mock_db, client, mut runtime) = create_database_client_and_runtime(1);
@@ -619,6 +1239,210 @@ fn test_get_network_status() {
     }
 }
 
+#[test]
+fn test_subscribe_to_events() {
+    let mock_db = mock_db();
+    let (_, first_event) = mock_db.events[0].clone();
+    let expected_events: Vec<_> = mock_db
+        .events
+        .iter()
+        .filter(|(_, e)| e.key() == first_event.key())
+        .cloned()
+        .collect();
+    let request = serde_json::json!({
+        "jsonrpc": "2.0",
+        "method": "subscribe_to_events",
+        "params": [hex::encode(first_event.key().as_bytes()), first_event.sequence_number()],
+        "id": 1,
+    });
+
+    let notifications = subscribe(mock_db, request, expected_events.len());
+    for ((version, event), notification) in expected_events.iter().zip(notifications) {
+        let view: EventView = serde_json::from_value(notification).unwrap();
+        assert_eq!(view.transaction_version, *version);
+        assert_eq!(view.sequence_number, event.sequence_number());
+    }
+}
+
+#[test]
+fn test_subscribe_to_transactions() {
+    let mock_db = mock_db();
+    let (txn, sender, sequence_number) = mock_db
+        .all_txns
+        .iter()
+        .find_map(|(t, _)| {
+            t.as_signed_user_txn()
+                .ok()
+                .map(|x| (t.clone(), x.sender(), x.sequence_number()))
+        })
+        .expect("mock DB missing user transaction");
+    let request = serde_json::json!({
+        "jsonrpc": "2.0",
+        "method": "subscribe_to_transactions",
+        "params": [sender.to_string(), sequence_number, true],
+        "id": 1,
+    });
+
+    let notifications = subscribe(mock_db, request, 1);
+    let view: TransactionView = serde_json::from_value(notifications[0].clone()).unwrap();
+    assert_eq!(view.hash, txn.hash().to_hex());
+    match view.transaction {
+        TransactionDataView::UserTransaction {
+            sender: view_sender,
+            sequence_number: view_sequence_number,
+            ..
+        } => {
+            assert_eq!(view_sender, sender.to_string());
+            assert_eq!(view_sequence_number, sequence_number);
+        }
+        _ => panic!("wrong type"),
+    }
+}
+
+#[test]
+fn test_subscribe_invalid_request() {
+    let mut runtime = Runtime::new().unwrap();
+    let service = JsonRpcService::new(
+        Arc::new(mock_db()),
+        channel(1).0,
+        RoleType::Validator,
+        &DryRunConfig::default(),
+    );
+    runtime.block_on(async move {
+        let mut client = warp::test::ws()
+            .path("/ws")
+            .handshake(test_ws_route(service, &SubscriptionConfig::default()))
+            .await
+            .unwrap();
+        let requests = vec![
+            (
+                serde_json::json!({"jsonrpc": "2.0", "method": "get_events", "params": [], "id": 1}),
+                -32601,
+            ),
+            (
+                serde_json::json!({"jsonrpc": "2.0", "method": "subscribe_to_events", "params": ["00", 0], "id": 2}),
+                -32602,
+            ),
+            (
+                serde_json::json!({"jsonrpc": "1.0", "method": "subscribe_to_events", "params": [], "id": 3}),
+                -32600,
+            ),
+        ];
+        for (request, expected_code) in requests {
+            client.send_text(request.to_string()).await;
+            let response = recv_json(&mut client).await;
+            assert_eq!(response["id"], request["id"]);
+            assert_eq!(response["error"]["code"], expected_code);
+        }
+    });
+}
+
+#[test]
+fn test_subscription_limits() {
+    let mut runtime = Runtime::new().unwrap();
+    let service = JsonRpcService::new(
+        Arc::new(mock_db()),
+        channel(1).0,
+        RoleType::Validator,
+        &DryRunConfig::default(),
+    );
+    let route = test_ws_route(
+        service,
+        &SubscriptionConfig {
+            max_connections: 1,
+            max_subscriptions: 1,
+            max_subscriptions_per_connection: 2,
+        },
+    );
+    runtime.block_on(async move {
+        let mut client = warp::test::ws()
+            .path("/ws")
+            .handshake(route.clone())
+            .await
+            .unwrap();
+        // A second connection is rejected while the first is open.
+        assert!(warp::test::ws()
+            .path("/ws")
+            .handshake(route.clone())
+            .await
+            .is_err());
+
+        // Subscriptions to an account without transactions, so that nothing is pushed.
+        let account = AccountAddress::random().to_string();
+        for id in 1..=2 {
+            let request = serde_json::json!({
+                "jsonrpc": "2.0",
+                "method": "subscribe_to_transactions",
+                "params": [account, 0, false],
+                "id": id,
+            });
+            client.send_text(request.to_string()).await;
+            let response = recv_json(&mut client).await;
+            assert_eq!(response["id"], id);
+            if id == 1 {
+                assert_eq!(response["result"], serde_json::Value::Null);
+            } else {
+                // Over the cap across all connections, though not over that of the connection.
+                assert_eq!(response["error"]["code"], -32000);
+            }
+        }
+    });
+}
+
+/// Serves subscriptions for `service` with rate limiting disabled.
+fn test_ws_route(
+    service: JsonRpcService,
+    config: &SubscriptionConfig,
+) -> impl warp::Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone {
+    ws_route(
+        service,
+        Arc::new(RateLimiter::new(RateLimitConfig::default())),
+        config,
+    )
+}
+
+/// Subscribes with the given request over a WebSocket connection to a service serving the given
+/// mock DB, and returns the results of the first `num_notifications` notifications pushed.
+fn subscribe(
+    mock_db: MockLibraDB,
+    request: serde_json::Value,
+    num_notifications: usize,
+) -> Vec<serde_json::Value> {
+    let mut runtime = Runtime::new().unwrap();
+    let service = JsonRpcService::new(
+        Arc::new(mock_db),
+        channel(1).0,
+        RoleType::Validator,
+        &DryRunConfig::default(),
+    );
+    runtime.block_on(async move {
+        let mut client = warp::test::ws()
+            .path("/ws")
+            .handshake(test_ws_route(service, &SubscriptionConfig::default()))
+            .await
+            .unwrap();
+        client.send_text(request.to_string()).await;
+
+        let ack = recv_json(&mut client).await;
+        assert_eq!(ack["id"], request["id"]);
+        assert_eq!(ack["result"], serde_json::Value::Null);
+
+        let mut results = vec![];
+        for _ in 0..num_notifications {
+            let notification = recv_json(&mut client).await;
+            assert_eq!(notification["method"], "subscription");
+            assert_eq!(notification["params"]["id"], request["id"]);
+            results.push(notification["params"]["result"].clone());
+        }
+        results
+    })
+}
+
+async fn recv_json(client: &mut warp::test::WsClient) -> serde_json::Value {
+    let msg = client.recv().await.unwrap();
+    serde_json::from_str(msg.to_str().unwrap()).unwrap()
+}
+
 /// Creates and returns a MockLibraDB, JsonRpcAsyncClient and corresponding server Runtime tuple for
 /// testing. The given channel_buffer specifies the buffer size of the mempool client sender channel.
 fn create_database_client_and_runtime(
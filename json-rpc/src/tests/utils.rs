@@ -2,20 +2,22 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use anyhow::{Error, Result};
-use libra_config::config::RoleType;
+use libra_config::config::{RoleType, RpcConfig};
 use libra_crypto::HashValue;
 use libra_mempool::MempoolClientSender;
 use libra_types::{
     account_address::AccountAddress,
     account_state_blob::{AccountStateBlob, AccountStateWithProof},
     block_info::BlockInfo,
-    contract_event::ContractEvent,
+    chain_id::ChainId,
+    contract_event::{ContractEvent, EventWithProof},
     epoch_change::EpochChangeProof,
     event::EventKey,
     ledger_info::{LedgerInfo, LedgerInfoWithSignatures},
     proof::{
-        AccumulatorConsistencyProof, AccumulatorRangeProof, SparseMerkleProof,
-        TransactionAccumulatorProof, TransactionInfoWithProof, TransactionListProof,
+        AccumulatorConsistencyProof, AccumulatorRangeProof, EventAccumulatorProof, EventProof,
+        SparseMerkleProof, TransactionAccumulatorProof, TransactionInfoWithProof,
+        TransactionListProof,
     },
     transaction::{
         Transaction, TransactionInfo, TransactionListWithProof, TransactionWithProof, Version,
@@ -33,7 +35,27 @@ pub fn test_bootstrap(
     libra_db: Arc<dyn DbReader>,
     mp_sender: MempoolClientSender,
 ) -> Runtime {
-    crate::bootstrap(address, libra_db, mp_sender, RoleType::Validator)
+    let config = RpcConfig {
+        address,
+        ..RpcConfig::default()
+    };
+    test_bootstrap_with_config(&config, libra_db, mp_sender)
+}
+
+/// Creates JSON RPC server for a Validator node with the given config
+/// Should only be used for unit-tests
+pub fn test_bootstrap_with_config(
+    config: &RpcConfig,
+    libra_db: Arc<dyn DbReader>,
+    mp_sender: MempoolClientSender,
+) -> Runtime {
+    crate::bootstrap(
+        config,
+        libra_db,
+        mp_sender,
+        RoleType::Validator,
+        ChainId::test(),
+    )
 }
 
 /// Lightweight mock of LibraDB
@@ -192,6 +214,47 @@ impl DbReader for MockLibraDB {
         Ok(events)
     }
 
+    fn get_events_with_proofs(
+        &self,
+        key: &EventKey,
+        start: u64,
+        limit: u64,
+        ledger_version: u64,
+    ) -> Result<Vec<EventWithProof>> {
+        let events = self
+            .get_events(key, start, true, limit)?
+            .into_iter()
+            .filter(|(version, _)| *version <= ledger_version)
+            .map(|(version, event)| {
+                let event_index = self
+                    .events
+                    .iter()
+                    .filter(|(v, _)| *v == version)
+                    .position(|(_, e)| *e == event)
+                    .unwrap_or(0);
+                EventWithProof::new(
+                    version,
+                    event_index as u64,
+                    event,
+                    EventProof::new(
+                        TransactionInfoWithProof::new(
+                            TransactionAccumulatorProof::new(vec![]),
+                            TransactionInfo::new(
+                                Default::default(),
+                                Default::default(),
+                                Default::default(),
+                                0,
+                                StatusCode::EXECUTED,
+                            ),
+                        ),
+                        EventAccumulatorProof::new(vec![]),
+                    ),
+                )
+            })
+            .collect();
+        Ok(events)
+    }
+
     fn get_state_proof(
         &self,
         known_version: u64,
@@ -245,7 +308,7 @@ impl DbReader for MockLibraDB {
     }
 
     fn get_latest_state_root(&self) -> Result<(u64, HashValue)> {
-        unimplemented!()
+        Ok((self.version, HashValue::zero()))
     }
 
     fn get_latest_tree_state(&self) -> Result<TreeState> {
@@ -257,7 +320,7 @@ impl DbReader for MockLibraDB {
         _start_epoch: u64,
         _end_epoch: u64,
     ) -> Result<EpochChangeProof> {
-        unimplemented!()
+        Ok(EpochChangeProof::new(vec![], false))
     }
 
     fn get_epoch_ending_ledger_info(&self, _: u64) -> Result<LedgerInfoWithSignatures> {
@@ -6,14 +6,19 @@
 /// `name`  - name for the rpc method
 /// `method` - method name of new rpc method
 /// `num_args` - number of method arguments
+/// `num_opt_args` - number of optional method arguments following them, none if not given
 macro_rules! register_rpc_method {
     ($registry:expr, $name: expr, $method: expr, $num_args: expr) => {
+        register_rpc_method!($registry, $name, $method, $num_args, 0)
+    };
+    ($registry:expr, $name: expr, $method: expr, $num_args: expr, $num_opt_args: expr) => {
         $registry.insert(
             $name.to_string(),
             Box::new(move |service, request| {
                 Box::pin(async move {
                     ensure!(
-                        request.params.len() == $num_args,
+                        request.params.len() >= $num_args
+                            && request.params.len() <= $num_args + $num_opt_args,
                         "Invalid number of arguments"
                     );
                     Ok(serde_json::to_value($method(service, request).await?)?)
@@ -1,10 +1,11 @@
 // Copyright (c) The Libra Core Contributors
 // SPDX-License-Identifier: Apache-2.0
 
+use crate::views::MoveAbortView;
 use anyhow::Result;
 use libra_types::{
     mempool_status::{MempoolStatus, MempoolStatusCode},
-    vm_status::{StatusType, VMStatus},
+    vm_status::{StatusCode, StatusType, VMStatus},
 };
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
@@ -29,15 +30,46 @@ pub enum ServerCode {
     MempoolInvalidUpdate = -32010,
     MempoolVmError = -32011,
     MempoolUnknownError = -32012,
+
+    RateLimitExceeded = -32013,
+
+    MempoolGasPriceBumpTooLow = -32014,
 }
 
-#[derive(Clone, Debug, Deserialize, Serialize)]
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
 pub struct JsonRpcError {
     pub code: i16,
     pub message: String,
     pub data: Option<Value>,
 }
 
+/// The `data` of the errors returned for VM statuses, so that clients don't need to know what
+/// each status code stands for
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct VMStatusErrorData {
+    pub vm_status: VMStatus,
+    pub status_code: StatusCode,
+    /// Name of the status code, e.g. `SEQUENCE_NUMBER_TOO_OLD`
+    pub status_code_name: String,
+    /// Category of the status code, e.g. `Validation`
+    pub status_type: String,
+    /// Where and why Move code aborted, if the status is an abort
+    pub move_abort: Option<MoveAbortView>,
+}
+
+impl From<VMStatus> for VMStatusErrorData {
+    fn from(vm_status: VMStatus) -> Self {
+        let status_code = vm_status.status_code();
+        Self {
+            status_code,
+            status_code_name: format!("{:?}", status_code),
+            status_type: vm_status.status_type().to_string(),
+            move_abort: MoveAbortView::from_vm_status(&vm_status),
+            vm_status,
+        }
+    }
+}
+
 impl std::error::Error for JsonRpcError {}
 
 impl std::fmt::Display for JsonRpcError {
@@ -83,6 +115,14 @@ impl JsonRpcError {
         }
     }
 
+    pub fn rate_limit_exceeded(method: &str) -> Self {
+        Self {
+            code: ServerCode::RateLimitExceeded as i16,
+            message: format!("Server error: rate limit exceeded for {}", method),
+            data: None,
+        }
+    }
+
     pub fn mempool_error(error: MempoolStatus) -> Result<Self> {
         let code = match error.code {
             MempoolStatusCode::InvalidSeqNumber => ServerCode::MempoolInvalidSeqNumber,
@@ -91,6 +131,7 @@ impl JsonRpcError {
             MempoolStatusCode::InvalidUpdate => ServerCode::MempoolInvalidUpdate,
             MempoolStatusCode::VmError => ServerCode::MempoolVmError,
             MempoolStatusCode::UnknownStatus => ServerCode::MempoolUnknownError,
+            MempoolStatusCode::GasPriceBumpTooLow => ServerCode::MempoolGasPriceBumpTooLow,
             MempoolStatusCode::Accepted => {
                 return Err(anyhow::format_err!(
                     "[JSON RPC] cannot create mempool error for mempool accepted status"
@@ -120,19 +161,31 @@ impl JsonRpcError {
             StatusType::Unknown => ServerCode::VmUnknownError,
         };
 
+        let data = VMStatusErrorData::from(error);
+        let message = match &data.move_abort {
+            Some(move_abort) => {
+                format!("Server error: VM {} error: {}", vm_status_type, move_abort)
+            }
+            None => format!(
+                "Server error: VM {} error: {}",
+                vm_status_type, data.status_code_name
+            ),
+        };
+
         Self {
             code: code as i16,
-            message: format!("Server error: VM {} error: {:?}", vm_status_type, error),
-            data: Some(serde_json::json!(error)),
+            message,
+            data: Some(serde_json::json!(data)),
         }
     }
 
+    pub fn get_vm_status_data(&self) -> Option<VMStatusErrorData> {
+        self.data
+            .as_ref()
+            .and_then(|data| serde_json::from_value(data.clone()).ok())
+    }
+
     pub fn get_vm_status(&self) -> Option<VMStatus> {
-        if let Some(data) = &self.data {
-            if let Ok(vm_status) = serde_json::from_value::<VMStatus>(data.clone()) {
-                return Some(vm_status);
-            }
-        }
-        None
+        self.get_vm_status_data().map(|data| data.vm_status)
     }
 }
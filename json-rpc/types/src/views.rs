@@ -1,28 +1,37 @@
 // Copyright (c) The Libra Core Contributors
 // SPDX-License-Identifier: Apache-2.0
 
+use crate::errors::JsonRpcError;
 use anyhow::{format_err, Error, Result};
-use libra_crypto::HashValue;
+use compiled_stdlib::{errmap::explain_abort, script_registry::StdlibScriptRegistry};
+use libra_crypto::{hash::CryptoHash, HashValue};
 use libra_types::{
+    access_path::AccessPath,
     account_config::{
         AccountResource, AccountRole, BalanceResource, BurnEvent, CancelBurnEvent,
         CurrencyInfoResource, FreezingBit, MintEvent, NewBlockEvent, NewEpochEvent, PreburnEvent,
         ReceivedPaymentEvent, SentPaymentEvent, ToLBRExchangeRateUpdateEvent, UpgradeEvent,
     },
-    account_state_blob::AccountStateWithProof,
-    contract_event::ContractEvent,
+    account_state::AccountState,
+    account_state_blob::{AccountStateBlob, AccountStateWithProof},
+    contract_event::{ContractEvent, EventWithProof},
     epoch_change::EpochChangeProof,
     ledger_info::LedgerInfoWithSignatures,
-    proof::{AccountStateProof, AccumulatorConsistencyProof},
-    transaction::{Transaction, TransactionArgument, TransactionPayload},
-    vm_status::StatusCode,
+    proof::{AccountStateProof, AccumulatorConsistencyProof, TransactionInfoWithProof},
+    transaction::{
+        Script, Transaction, TransactionArgument, TransactionPayload, TransactionWithProof,
+    },
+    vm_status::{AbortLocation, StatusCode, VMStatus},
+    write_set::WriteOp,
 };
 use move_core_types::{
     identifier::Identifier,
     language_storage::{StructTag, TypeTag},
     move_resource::MoveResource,
 };
+use once_cell::sync::Lazy;
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
 use std::{collections::BTreeMap, convert::TryFrom};
 use transaction_builder::get_transaction_name;
 
@@ -104,6 +113,29 @@ impl AccountView {
             role: AccountRoleView::from(account_role),
         }
     }
+
+    /// Returns the view of the account stored in `blob`, with its balances in `currencies`, if
+    /// it's a valid account
+    pub fn try_from_blob(
+        blob: &AccountStateBlob,
+        currencies: &[Identifier],
+    ) -> Result<Option<Self>> {
+        let account_state = AccountState::try_from(blob)?;
+        if let Some(account) = account_state.get_account_resource()? {
+            let balances = account_state.get_balance_resources(currencies)?;
+            if let Some(account_role) = account_state.get_account_role()? {
+                if let Some(freezing_bit) = account_state.get_freezing_bit()? {
+                    return Ok(Some(AccountView::new(
+                        &account,
+                        balances,
+                        account_role,
+                        freezing_bit,
+                    )));
+                }
+            }
+        }
+        Ok(None)
+    }
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
@@ -334,6 +366,176 @@ pub struct TransactionView {
     pub gas_used: u64,
 }
 
+impl From<TransactionWithProof> for TransactionView {
+    fn from(txn: TransactionWithProof) -> TransactionView {
+        let version = txn.version;
+        TransactionView {
+            version,
+            hash: txn.transaction.hash().to_hex(),
+            transaction: txn.transaction.into(),
+            events: txn
+                .events
+                .unwrap_or_default()
+                .into_iter()
+                .map(|event| (version, event).into())
+                .collect(),
+            vm_status: txn.proof.transaction_info().major_status(),
+            gas_used: txn.proof.transaction_info().gas_used(),
+        }
+    }
+}
+
+/// What executing a transaction against the latest state would produce, as returned by
+/// `dry_run_transaction`. Nothing of it is committed.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct DryRunResultView {
+    pub vm_status: StatusCode,
+    /// Whether the transaction would be discarded, in which case it has no effects
+    pub discarded: bool,
+    pub gas_used: u64,
+    /// Where and why the transaction would abort, if it would
+    pub move_abort: Option<MoveAbortView>,
+    pub events: Vec<EventView>,
+    pub write_set: Vec<WriteOpView>,
+}
+
+/// An abort raised by Move code, explained through the error map of the stdlib when raised by a
+/// stdlib module
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct MoveAbortView {
+    /// The module the abort is raised by, or "Script"
+    pub location: String,
+    pub abort_code: u64,
+    /// Name of the error category the abort code encodes, e.g. `INVALID_ARGUMENT`
+    pub category: Option<String>,
+    pub category_description: Option<String>,
+    /// Name of the error constant the abort code stands for, e.g. `EINSUFFICIENT_BALANCE`
+    pub reason: Option<String>,
+    pub reason_description: Option<String>,
+}
+
+impl MoveAbortView {
+    /// Returns the view of the abort if `vm_status` is one
+    pub fn from_vm_status(vm_status: &VMStatus) -> Option<Self> {
+        let (location, abort_code) = match vm_status {
+            VMStatus::MoveAbort(location, abort_code) => (location, *abort_code),
+            VMStatus::Executed | VMStatus::Error(_) => return None,
+        };
+        let context = match location {
+            AbortLocation::Module(module_id) => explain_abort(module_id, abort_code),
+            AbortLocation::Script => None,
+        };
+        let category = context
+            .as_ref()
+            .and_then(|context| context.category.as_ref());
+        let reason = context.as_ref().map(|context| &context.reason);
+        Some(Self {
+            location: format!("{:?}", location),
+            abort_code,
+            category: category.map(|category| category.code_name.clone()),
+            category_description: category.map(|category| category.code_description.clone()),
+            reason: reason.map(|reason| reason.code_name.clone()),
+            reason_description: reason.map(|reason| reason.code_description.clone()),
+        })
+    }
+}
+
+impl std::fmt::Display for MoveAbortView {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(
+            f,
+            "aborted in {} with code {}",
+            self.location, self.abort_code
+        )?;
+        if let Some(reason) = &self.reason {
+            write!(f, ": ")?;
+            if let Some(category) = &self.category {
+                write!(f, "{}, ", category)?;
+            }
+            write!(f, "{}", reason)?;
+            if let Some(description) = self.reason_description.as_ref().filter(|d| !d.is_empty()) {
+                write!(f, " ({})", description)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+#[serde(tag = "type")]
+pub enum WriteOpView {
+    #[serde(rename = "value")]
+    Value {
+        address: BytesView,
+        path: BytesView,
+        value: BytesView,
+    },
+    #[serde(rename = "deletion")]
+    Deletion { address: BytesView, path: BytesView },
+}
+
+impl From<(&AccessPath, &WriteOp)> for WriteOpView {
+    fn from((access_path, write_op): (&AccessPath, &WriteOp)) -> WriteOpView {
+        let address = BytesView::from(access_path.address.as_ref());
+        let path = BytesView::from(&access_path.path);
+        match write_op {
+            WriteOp::Value(value) => WriteOpView::Value {
+                address,
+                path,
+                value: BytesView::from(value),
+            },
+            WriteOp::Deletion => WriteOpView::Deletion { address, path },
+        }
+    }
+}
+
+/// A transaction pending in mempool, not committed yet
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct PendingTransactionView {
+    pub transaction: TransactionDataView,
+    pub hash: String,
+    pub ranking_score: u64,
+    /// Time, in seconds since the unix epoch, by which mempool drops the transaction
+    pub mempool_expiration_time: u64,
+}
+
+/// Response to `get_account_transaction`: the committed transaction, or if asked for, the
+/// transaction pending in mempool
+#[allow(clippy::large_enum_variant)]
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+#[serde(untagged)]
+pub enum AccountTransactionView {
+    Committed(TransactionView),
+    Pending(PendingTransactionView),
+}
+
+/// Response to `get_mempool_transaction_status` and `get_mempool_transaction_status_by_hash`:
+/// where a transaction is in mempool, or why it was rejected on submission
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+#[serde(tag = "type")]
+pub enum MempoolTransactionStatusView {
+    /// Ready to be included in a block, but not broadcast by this node, e.g. because it was
+    /// received from another node
+    #[serde(rename = "validated")]
+    Validated(PendingTransactionView),
+    /// Waiting on transactions with lower sequence numbers from the same sender
+    #[serde(rename = "waiting_on_sequence_gap")]
+    WaitingOnSequenceGap(PendingTransactionView),
+    /// Ready to be broadcast and included in a block
+    #[serde(rename = "ready_for_broadcast")]
+    ReadyForBroadcast(PendingTransactionView),
+    #[serde(rename = "rejected")]
+    Rejected(RejectedTransactionView),
+}
+
+/// A transaction recently rejected by mempool on submission
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct RejectedTransactionView {
+    pub hash: String,
+    /// The error `submit` responded with
+    pub error: JsonRpcError,
+}
+
 #[allow(clippy::large_enum_variant)]
 #[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
 #[serde(tag = "type")]
@@ -356,6 +558,7 @@ pub enum TransactionDataView {
         expiration_time: u64,
         script_hash: String,
         script: ScriptView,
+        decoded_script: Option<DecodedScriptView>,
     },
     #[serde(rename = "unknown")]
     UnknownTransaction {},
@@ -388,6 +591,57 @@ impl ScriptView {
     // TODO cover all script types
 }
 
+/// ABIs of the scripts decoded in `TransactionDataView`s
+static SCRIPT_REGISTRY: Lazy<StdlibScriptRegistry> = Lazy::new(StdlibScriptRegistry::builtin);
+
+/// A script call decoded with the ABI of the script, with its arguments named
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct DecodedScriptView {
+    pub name: String,
+    pub type_arguments: Vec<NamedValueView>,
+    pub arguments: Vec<NamedValueView>,
+}
+
+/// Type arguments are shown as their type tags, and arguments as JSON values: numbers except for
+/// u128s, which are shown as strings, and hex-encoded strings for addresses and byte arrays
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct NamedValueView {
+    pub name: String,
+    pub value: Value,
+}
+
+impl DecodedScriptView {
+    /// Decodes `script` with the ABIs of the standard library scripts, returning none if it isn't a
+    /// call to one of them
+    pub fn decode(script: &Script) -> Option<Self> {
+        let (abi, (ty_args, args)) = SCRIPT_REGISTRY.decode_script(script)?;
+        Some(Self {
+            name: abi.name().to_string(),
+            type_arguments: ty_args
+                .into_iter()
+                .map(|(name, type_tag)| NamedValueView {
+                    name,
+                    value: Value::String(type_tag.to_string()),
+                })
+                .collect(),
+            arguments: args
+                .into_iter()
+                .map(|(name, arg)| NamedValueView {
+                    name,
+                    value: match arg {
+                        TransactionArgument::U8(value) => Value::from(value),
+                        TransactionArgument::U64(value) => Value::from(value),
+                        TransactionArgument::U128(value) => Value::String(value.to_string()),
+                        TransactionArgument::Address(address) => Value::String(address.to_string()),
+                        TransactionArgument::U8Vector(bytes) => Value::String(hex::encode(bytes)),
+                        TransactionArgument::Bool(value) => Value::Bool(value),
+                    },
+                })
+                .collect(),
+        })
+    }
+}
+
 impl From<Transaction> for TransactionDataView {
     fn from(tx: Transaction) -> Self {
         let x = match tx {
@@ -403,6 +657,10 @@ impl From<Transaction> for TransactionDataView {
                     _ => HashValue::zero(),
                 }
                 .to_hex();
+                let decoded_script = match t.payload() {
+                    TransactionPayload::Script(s) => DecodedScriptView::decode(s),
+                    _ => None,
+                };
 
                 Ok(TransactionDataView::UserTransaction {
                     sender: t.sender().to_string(),
@@ -417,6 +675,7 @@ impl From<Transaction> for TransactionDataView {
                     expiration_time: t.expiration_time().as_secs(),
                     script_hash,
                     script: t.into_raw_transaction().into_payload().into(),
+                    decoded_script,
                 })
             }
         };
@@ -472,6 +731,9 @@ impl From<TransactionPayload> for ScriptView {
             TransactionPayload::Module(_) => {
                 ("module publishing".to_string(), empty_vec, empty_ty_vec)
             }
+            TransactionPayload::ScriptFunction(_) => {
+                ("script function".to_string(), empty_vec, empty_ty_vec)
+            }
         };
 
         let res = match code.as_str() {
@@ -575,6 +837,46 @@ impl
     }
 }
 
+/// The ledger infos ending the epochs asked for with `get_epoch_change_proof`
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct EpochChangeProofView {
+    pub ledger_info_with_signatures: Vec<BytesView>,
+    /// Whether there are more epochs in the range asked for, to be fetched from the epoch after
+    /// the last one returned
+    pub more: bool,
+}
+
+impl TryFrom<&EpochChangeProof> for EpochChangeProofView {
+    type Error = Error;
+
+    fn try_from(proof: &EpochChangeProof) -> Result<EpochChangeProofView, Self::Error> {
+        Ok(EpochChangeProofView {
+            ledger_info_with_signatures: proof
+                .ledger_info_with_sigs
+                .iter()
+                .map(|ledger_info| Ok(BytesView::from(&lcs::to_bytes(ledger_info)?)))
+                .collect::<Result<_>>()?,
+            more: proof.more,
+        })
+    }
+}
+
+/// An account in the response to `get_accounts`, with its blob and proof if requested
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct AccountWithStateView {
+    pub address: String,
+    pub account: Option<AccountView>,
+    pub state_with_proof: Option<AccountStateWithProofView>,
+}
+
+/// A page of events in the response to `get_events_page`
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct EventsPageView {
+    pub events: Vec<EventView>,
+    /// Token to get the next page with, none if there are no more events in the version range
+    pub next_cursor: Option<String>,
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
 pub struct AccountStateWithProofView {
     pub version: u64,
@@ -601,6 +903,22 @@ impl TryFrom<AccountStateWithProof> for AccountStateWithProofView {
     }
 }
 
+impl TryFrom<&AccountStateWithProofView> for AccountStateWithProof {
+    type Error = Error;
+
+    fn try_from(view: &AccountStateWithProofView) -> Result<AccountStateWithProof, Error> {
+        let blob = match &view.blob {
+            Some(blob) => Some(lcs::from_bytes(&blob.clone().into_bytes()?)?),
+            None => None,
+        };
+        Ok(AccountStateWithProof::new(
+            view.version,
+            blob,
+            AccountStateProof::try_from(&view.proof)?,
+        ))
+    }
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
 pub struct AccountStateProofView {
     pub ledger_info_to_transaction_info_proof: BytesView,
@@ -629,3 +947,99 @@ impl TryFrom<AccountStateProof> for AccountStateProofView {
         })
     }
 }
+
+impl TryFrom<&AccountStateProofView> for AccountStateProof {
+    type Error = Error;
+
+    fn try_from(view: &AccountStateProofView) -> Result<AccountStateProof, Error> {
+        let decode = |bytes: &BytesView| bytes.clone().into_bytes();
+        Ok(AccountStateProof::new(
+            TransactionInfoWithProof::new(
+                lcs::from_bytes(&decode(&view.ledger_info_to_transaction_info_proof)?)?,
+                lcs::from_bytes(&decode(&view.transaction_info)?)?,
+            ),
+            lcs::from_bytes(&decode(&view.transaction_info_to_account_proof)?)?,
+        ))
+    }
+}
+
+/// An event in the response to `get_events_with_proofs`, with the proof that it's in the ledger
+/// at the version of the response
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct EventWithProofView {
+    pub transaction_version: u64,
+    pub event_index: u64,
+    pub event: BytesView,
+    pub proof: BytesView,
+}
+
+impl TryFrom<&EventWithProof> for EventWithProofView {
+    type Error = Error;
+
+    fn try_from(event: &EventWithProof) -> Result<EventWithProofView, Error> {
+        Ok(EventWithProofView {
+            transaction_version: event.transaction_version,
+            event_index: event.event_index,
+            event: BytesView::from(&lcs::to_bytes(&event.event)?),
+            proof: BytesView::from(&lcs::to_bytes(&event.proof)?),
+        })
+    }
+}
+
+impl TryFrom<&EventWithProofView> for EventWithProof {
+    type Error = Error;
+
+    fn try_from(view: &EventWithProofView) -> Result<EventWithProof, Error> {
+        Ok(EventWithProof::new(
+            view.transaction_version,
+            view.event_index,
+            lcs::from_bytes(&view.event.clone().into_bytes()?)?,
+            lcs::from_bytes(&view.proof.clone().into_bytes()?)?,
+        ))
+    }
+}
+
+/// A transaction in the response to `get_account_transaction_with_proof`, with the proof that
+/// it's in the ledger at the version of the response
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct TransactionWithProofView {
+    pub version: u64,
+    pub transaction: BytesView,
+    /// The events the transaction emitted, if asked for
+    pub events: Option<BytesView>,
+    pub proof: BytesView,
+}
+
+impl TryFrom<&TransactionWithProof> for TransactionWithProofView {
+    type Error = Error;
+
+    fn try_from(txn: &TransactionWithProof) -> Result<TransactionWithProofView, Error> {
+        let events = match &txn.events {
+            Some(events) => Some(BytesView::from(&lcs::to_bytes(events)?)),
+            None => None,
+        };
+        Ok(TransactionWithProofView {
+            version: txn.version,
+            transaction: BytesView::from(&lcs::to_bytes(&txn.transaction)?),
+            events,
+            proof: BytesView::from(&lcs::to_bytes(&txn.proof)?),
+        })
+    }
+}
+
+impl TryFrom<&TransactionWithProofView> for TransactionWithProof {
+    type Error = Error;
+
+    fn try_from(view: &TransactionWithProofView) -> Result<TransactionWithProof, Error> {
+        let events = match &view.events {
+            Some(events) => Some(lcs::from_bytes(&events.clone().into_bytes()?)?),
+            None => None,
+        };
+        Ok(TransactionWithProof::new(
+            view.version,
+            lcs::from_bytes(&view.transaction.clone().into_bytes()?)?,
+            events,
+            lcs::from_bytes(&view.proof.clone().into_bytes()?)?,
+        ))
+    }
+}
@@ -1,9 +1,9 @@
 // Copyright (c) The Libra Core Contributors
 // SPDX-License-Identifier: Apache-2.0
 
-use criterion::{criterion_group, criterion_main, Criterion};
+use criterion::{criterion_group, criterion_main, BatchSize, Criterion, Throughput};
 use language_benchmarks::{move_vm::bench, transactions::TransactionBencher};
-use language_e2e_tests::account_universe::P2PTransferGen;
+use language_e2e_tests::{account_universe::P2PTransferGen, benchmark::ScriptWorkload};
 use proptest::prelude::*;
 
 //
@@ -19,6 +19,33 @@ fn peer_to_peer(c: &mut Criterion) {
 
 criterion_group!(txn_benches, peer_to_peer);
 
+//
+// Stdlib script benchmarks
+//
+
+const SCRIPT_BLOCK_SIZE: usize = 100;
+
+fn stdlib_scripts(c: &mut Criterion) {
+    let mut group = c.benchmark_group("stdlib_scripts");
+    group.throughput(Throughput::Elements(SCRIPT_BLOCK_SIZE as u64));
+    for workload in ScriptWorkload::all().iter() {
+        group.bench_function(workload.name(), |b| {
+            b.iter_batched(
+                || workload.setup(SCRIPT_BLOCK_SIZE),
+                |(executor, txns)| {
+                    executor
+                        .execute_block(txns)
+                        .expect("VM should not fail to start")
+                },
+                BatchSize::LargeInput,
+            )
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(script_benches, stdlib_scripts);
+
 //
 // MoveVM benchmarks
 //
@@ -33,4 +60,4 @@ fn call(c: &mut Criterion) {
 
 criterion_group!(vm_benches, arith, call);
 
-criterion_main!(vm_benches);
+criterion_main!(vm_benches, script_benches);
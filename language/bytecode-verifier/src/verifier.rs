@@ -53,7 +53,15 @@ pub fn verify_main_signature(script: &CompiledScript) -> VMResult<()> {
 }
 
 fn verify_main_signature_impl(script: &CompiledScript) -> PartialVMResult<()> {
-    fn is_valid_arg_type(idx: usize, arg_type: &SignatureToken) -> bool {
+    fn is_signer_reference(arg_type: &SignatureToken) -> bool {
+        use SignatureToken as S;
+        match arg_type {
+            S::Reference(inner) => matches!(&**inner, S::Signer),
+            _ => false,
+        }
+    }
+
+    fn is_valid_arg_type(in_signer_prefix: bool, arg_type: &SignatureToken) -> bool {
         use SignatureToken as S;
         match arg_type {
             S::Bool | S::U8 | S::U64 | S::U128 | S::Address => true,
@@ -73,8 +81,9 @@ fn verify_main_signature_impl(script: &CompiledScript) -> PartialVMResult<()> {
             },
 
             // &signer is a type that can only be populated by the Move VM. And its value is filled
-            // based on the sender of the transaction
-            S::Reference(inner) => idx == 0 && matches!(&**inner, S::Signer),
+            // based on the signers of the transaction: the sender first, then the secondary
+            // signers of a multi-agent transaction. So signers can only lead the arguments.
+            S::Reference(inner) => in_signer_prefix && matches!(&**inner, S::Signer),
 
             S::Signer
             | S::Struct(_)
@@ -85,12 +94,14 @@ fn verify_main_signature_impl(script: &CompiledScript) -> PartialVMResult<()> {
     }
 
     let arguments = script.signature_at(script.as_inner().parameters);
-    for (idx, arg_type) in arguments.0.iter().enumerate() {
-        if !is_valid_arg_type(idx, arg_type) {
+    let mut in_signer_prefix = true;
+    for arg_type in &arguments.0 {
+        if !is_valid_arg_type(in_signer_prefix, arg_type) {
             return Err(PartialVMError::new(
                 StatusCode::INVALID_MAIN_FUNCTION_SIGNATURE,
             ));
         }
+        in_signer_prefix &= is_signer_reference(arg_type);
     }
     Ok(())
 }
@@ -5,18 +5,20 @@
 
 use crate::{gas_costs, keygen::KeyGen};
 use anyhow::{Error, Result};
-use libra_crypto::ed25519::*;
+use libra_crypto::{ed25519::*, traits::SigningKey};
 use libra_types::{
     access_path::AccessPath,
     account_address::AccountAddress,
     account_config::{
         self, from_currency_code_string, type_tag_for_currency_code, AccountResource,
-        BalanceResource, RoleId, COIN1_NAME, COIN2_NAME, LBR_NAME,
+        BalanceResource, ChildVASP, DesignatedDealer, ParentVASP, RoleId, COIN1_NAME, COIN2_NAME,
+        LBR_NAME,
     },
     chain_id::ChainId,
     event::EventHandle,
     transaction::{
-        authenticator::AuthenticationKey, ChangeSet, Module, RawTransaction, Script,
+        authenticator::{AccountAuthenticator, AuthenticationKey},
+        ChangeSet, Module, RawTransaction, RawTransactionWithData, Script, ScriptFunction,
         SignedTransaction, TransactionArgument, TransactionPayload,
     },
     write_set::{WriteOp, WriteSet, WriteSetMut},
@@ -46,6 +48,15 @@ pub fn coin2_currency_code() -> Identifier {
     from_currency_code_string(COIN2_NAME).unwrap()
 }
 
+/// Returns the currency codes of all the currencies registered at genesis.
+pub fn all_currency_codes() -> Vec<Identifier> {
+    vec![
+        lbr_currency_code(),
+        coin1_currency_code(),
+        coin2_currency_code(),
+    ]
+}
+
 /// Details about a Libra account.
 ///
 /// Tests will typically create a set of `Account` instances to run transactions on. This type
@@ -232,6 +243,18 @@ impl Account {
                 Duration::from_secs(DEFAULT_EXPIRATION_TIME),
                 ChainId::test(),
             ),
+            TransactionPayload::ScriptFunction(script_function) => {
+                RawTransaction::new_script_function(
+                    address,
+                    sequence_number,
+                    script_function,
+                    max_gas_amount,
+                    gas_unit_price,
+                    gas_currency_code,
+                    Duration::from_secs(DEFAULT_EXPIRATION_TIME),
+                    ChainId::test(),
+                )
+            }
         }
     }
 
@@ -359,6 +382,24 @@ impl Account {
         )
     }
 
+    /// Create a transaction that applies `change_set` directly, signed by `sender`.
+    ///
+    /// Only the libra root account may send write set transactions.
+    pub fn signed_writeset_txn(
+        &self,
+        change_set: ChangeSet,
+        sequence_number: u64,
+    ) -> SignedTransaction {
+        self.create_signed_txn_impl(
+            *self.address(),
+            TransactionPayload::WriteSet(change_set),
+            sequence_number,
+            0, // max gas amount is ignored for write sets
+            0, // gas price
+            LBR_NAME.to_owned(),
+        )
+    }
+
     pub fn create_raw_txn_impl(
         sender: AccountAddress,
         program: TransactionPayload,
@@ -380,6 +421,11 @@ impl Account {
         )
     }
 
+    /// Signs `message` as one of the signers of a multi-agent transaction.
+    pub fn sign_multi_agent(&self, message: &RawTransactionWithData) -> AccountAuthenticator {
+        AccountAuthenticator::ed25519(self.pubkey.clone(), self.privkey.sign(message))
+    }
+
     pub fn transaction(&self) -> TransactionBuilder {
         TransactionBuilder::new(self.clone())
     }
@@ -399,6 +445,7 @@ pub struct TransactionBuilder {
     pub gas_unit_price: Option<u64>,
     pub gas_currency_code: Option<String>,
     pub ttl: Option<Duration>,
+    pub secondary_signers: Vec<Account>,
 }
 
 impl TransactionBuilder {
@@ -411,6 +458,7 @@ impl TransactionBuilder {
             gas_unit_price: None,
             gas_currency_code: None,
             ttl: None,
+            secondary_signers: vec![],
         }
     }
 
@@ -424,6 +472,16 @@ impl TransactionBuilder {
         self
     }
 
+    pub fn script_function(mut self, f: ScriptFunction) -> Self {
+        self.program = Some(TransactionPayload::ScriptFunction(f));
+        self
+    }
+
+    pub fn payload(mut self, payload: TransactionPayload) -> Self {
+        self.program = Some(payload);
+        self
+    }
+
     pub fn module(mut self, m: Module) -> Self {
         self.program = Some(TransactionPayload::Module(m));
         self
@@ -454,23 +512,57 @@ impl TransactionBuilder {
         self
     }
 
-    pub fn sign(self) -> SignedTransaction {
+    /// Makes this a multi-agent transaction signed by the sender and by `secondary_signers`, in
+    /// order. The script receives one `&signer` per account, starting with the sender.
+    pub fn secondary_signers(mut self, secondary_signers: Vec<Account>) -> Self {
+        self.secondary_signers = secondary_signers;
+        self
+    }
+
+    pub fn raw(&self) -> RawTransaction {
         RawTransaction::new(
             *self.sender.address(),
             self.sequence_number.expect("sequence number not set"),
-            self.program.expect("transaction payload not set"),
+            self.program.clone().expect("transaction payload not set"),
             self.max_gas_amount
                 .unwrap_or_else(|| gas_costs::TXN_RESERVED * 2),
             self.gas_unit_price.unwrap_or(0),
             self.gas_currency_code
+                .clone()
                 .unwrap_or_else(|| LBR_NAME.to_owned()),
             self.ttl
                 .unwrap_or_else(|| Duration::from_secs(DEFAULT_EXPIRATION_TIME)),
             ChainId::test(),
         )
-        .sign(&self.sender.privkey, self.sender.pubkey)
-        .unwrap()
-        .into_inner()
+    }
+
+    pub fn sign(self) -> SignedTransaction {
+        let raw_txn = self.raw();
+        if self.secondary_signers.is_empty() {
+            return raw_txn
+                .sign(&self.sender.privkey, self.sender.pubkey)
+                .unwrap()
+                .into_inner();
+        }
+
+        let secondary_signer_addresses: Vec<_> = self
+            .secondary_signers
+            .iter()
+            .map(|signer| *signer.address())
+            .collect();
+        let message = RawTransactionWithData::new_multi_agent(
+            raw_txn.clone(),
+            secondary_signer_addresses.clone(),
+        );
+        SignedTransaction::new_multi_agent(
+            raw_txn,
+            self.sender.sign_multi_agent(&message),
+            secondary_signer_addresses,
+            self.secondary_signers
+                .iter()
+                .map(|signer| signer.sign_multi_agent(&message))
+                .collect(),
+        )
     }
 }
 
@@ -544,6 +636,18 @@ impl AccountRoleSpecifier {
         Value::struct_(Struct::pack(vec![Value::u64(self.id())], true))
     }
 
+    /// Returns whether accounts with this role are allowed to hold balances.
+    pub fn can_hold_balance(&self) -> bool {
+        match self {
+            Self::DesignatedDealer | Self::ParentVASP | Self::ChildVASP => true,
+            Self::LibraRoot
+            | Self::TreasuryCompliance
+            | Self::Validator
+            | Self::ValidatorOperator
+            | Self::Unhosted => false,
+        }
+    }
+
     pub fn role_id_struct_tag() -> StructTag {
         StructTag {
             address: account_config::CORE_CODE_ADDRESS,
@@ -648,6 +752,7 @@ pub struct AccountData {
     balances: BTreeMap<Identifier, Balance>,
     event_generator: EventHandleGenerator,
     account_role: AccountRole,
+    role_resources: RoleResources,
 }
 
 fn new_event_handle(count: u64) -> EventHandle {
@@ -668,6 +773,20 @@ impl AccountData {
         )
     }
 
+    /// Creates a new `AccountData` with a new account that holds a balance in each of the given
+    /// currencies.
+    pub fn new_with_balances(
+        balances: impl IntoIterator<Item = (Identifier, u64)>,
+        sequence_number: u64,
+    ) -> Self {
+        let mut account_data = Self::new(0, sequence_number);
+        account_data.balances = balances
+            .into_iter()
+            .map(|(code, amount)| (code, Balance::new(amount)))
+            .collect();
+        account_data
+    }
+
     pub fn new_libra_root() -> Self {
         Self::with_account(
             Account::new(),
@@ -748,12 +867,26 @@ impl AccountData {
             sequence_number,
             sent_events: new_event_handle(sent_events_count),
             received_events: new_event_handle(received_events_count),
+            role_resources: RoleResources::None,
         }
     }
 
+    /// Returns an [`AccountDataBuilder`] for creating accounts with roles other than a plain
+    /// ParentVASP, along with the resources those roles require.
+    pub fn builder() -> AccountDataBuilder {
+        AccountDataBuilder::new()
+    }
+
     /// Adds the balance held by this account to the one represented as balance_currency_code
     pub fn add_balance_currency(&mut self, balance_currency_code: Identifier) {
-        self.balances.insert(balance_currency_code, Balance::new(0));
+        self.add_balance(balance_currency_code, 0)
+    }
+
+    /// Sets the balance held by this account in `balance_currency_code` to `amount`, adding the
+    /// currency if the account does not already hold it.
+    pub fn add_balance(&mut self, balance_currency_code: Identifier, amount: u64) {
+        self.balances
+            .insert(balance_currency_code, Balance::new(amount));
     }
 
     /// Changes the keys for this account to the provided ones.
@@ -913,6 +1046,14 @@ impl AccountData {
             self.make_event_generator_access_path(),
             WriteOp::Value(event_generator),
         ));
+        write_set.extend(
+            self.role_resources.to_writeset_entries(
+                &self.account,
+                self.balances
+                    .iter()
+                    .map(|(code, balance)| (code, balance.coin())),
+            ),
+        );
         WriteSetMut::new(write_set).freeze().unwrap()
     }
 
@@ -939,6 +1080,13 @@ impl AccountData {
         self.balances.get(currency_code).unwrap().coin()
     }
 
+    /// Returns the initial balances of every currency held by this account.
+    pub fn balances(&self) -> impl Iterator<Item = (&IdentStr, u64)> {
+        self.balances
+            .iter()
+            .map(|(code, balance)| (code.as_ident_str(), balance.coin()))
+    }
+
     /// Returns the initial sequence number.
     pub fn sequence_number(&self) -> u64 {
         self.sequence_number
@@ -965,6 +1113,355 @@ impl AccountData {
     }
 }
 
+//---------------------------------------------------------------------------
+// Account data builder
+//---------------------------------------------------------------------------
+
+/// Builder for [`AccountData`] instances with an arbitrary account role.
+///
+/// The resulting account has the resources the Move standard library would have published had
+/// the account been created through the corresponding `create_*` transaction script, so it can be
+/// added to a [`FakeExecutor`][crate::executor::FakeExecutor] directly.
+///
+/// ```ignore
+/// let parent = AccountData::builder()
+///     .role(AccountRoleSpecifier::ParentVASP)
+///     .balance(coin1_currency_code(), 1_000_000)
+///     .build();
+/// let child = AccountData::builder()
+///     .role(AccountRoleSpecifier::ChildVASP)
+///     .parent_vasp(*parent.address())
+///     .build();
+/// ```
+#[derive(Clone, Debug)]
+pub struct AccountDataBuilder {
+    account: Option<Account>,
+    role: AccountRoleSpecifier,
+    balances: BTreeMap<Identifier, u64>,
+    sequence_number: u64,
+    parent_vasp: Option<AccountAddress>,
+    human_name: Vec<u8>,
+    base_url: Vec<u8>,
+    compliance_public_key: Option<Vec<u8>>,
+}
+
+impl AccountDataBuilder {
+    pub fn new() -> Self {
+        Self {
+            account: None,
+            role: AccountRoleSpecifier::default(),
+            balances: BTreeMap::new(),
+            sequence_number: 0,
+            parent_vasp: None,
+            human_name: vec![],
+            base_url: vec![],
+            compliance_public_key: None,
+        }
+    }
+
+    /// Uses `account` instead of a freshly generated one.
+    pub fn account(mut self, account: Account) -> Self {
+        self.account = Some(account);
+        self
+    }
+
+    pub fn role(mut self, role: AccountRoleSpecifier) -> Self {
+        self.role = role;
+        self
+    }
+
+    /// Adds a balance of `amount` in `currency_code`. May be called once per currency.
+    ///
+    /// Roles that can hold funds default to a zero LBR balance if no balance is added.
+    pub fn balance(mut self, currency_code: Identifier, amount: u64) -> Self {
+        self.balances.insert(currency_code, amount);
+        self
+    }
+
+    pub fn sequence_number(mut self, sequence_number: u64) -> Self {
+        self.sequence_number = sequence_number;
+        self
+    }
+
+    /// Sets the parent of a ChildVASP account. Required for that role.
+    ///
+    /// The parent must hold the account limit windows for every currency the child holds, which
+    /// is the case for ParentVASP accounts created by this builder with the same currencies.
+    pub fn parent_vasp(mut self, parent_vasp: AccountAddress) -> Self {
+        self.parent_vasp = Some(parent_vasp);
+        self
+    }
+
+    pub fn human_name(mut self, human_name: &[u8]) -> Self {
+        self.human_name = human_name.to_vec();
+        self
+    }
+
+    pub fn base_url(mut self, base_url: &[u8]) -> Self {
+        self.base_url = base_url.to_vec();
+        self
+    }
+
+    /// Sets the compliance key published in the account's `DualAttestation::Credential`. Defaults
+    /// to the account's own public key.
+    pub fn compliance_public_key(mut self, compliance_public_key: &Ed25519PublicKey) -> Self {
+        self.compliance_public_key = Some(compliance_public_key.to_bytes().to_vec());
+        self
+    }
+
+    pub fn build(self) -> AccountData {
+        let account = self.account.unwrap_or_else(Account::new);
+        let credential = || Credential {
+            human_name: self.human_name.clone(),
+            base_url: self.base_url.clone(),
+            compliance_public_key: self
+                .compliance_public_key
+                .clone()
+                .unwrap_or_else(|| account.pubkey.to_bytes().to_vec()),
+        };
+        let role_resources = match self.role {
+            AccountRoleSpecifier::ParentVASP => RoleResources::ParentVASP {
+                credential: credential(),
+            },
+            AccountRoleSpecifier::ChildVASP => RoleResources::ChildVASP {
+                parent_vasp_addr: self
+                    .parent_vasp
+                    .expect("a ChildVASP account requires a parent VASP"),
+            },
+            AccountRoleSpecifier::DesignatedDealer => RoleResources::DesignatedDealer {
+                credential: credential(),
+                mint_events: new_event_handle(0),
+            },
+            AccountRoleSpecifier::TreasuryCompliance => RoleResources::TreasuryCompliance,
+            AccountRoleSpecifier::Validator => RoleResources::Validator,
+            // Operators are only created with the resources common to all accounts.
+            AccountRoleSpecifier::ValidatorOperator
+            | AccountRoleSpecifier::LibraRoot
+            | AccountRoleSpecifier::Unhosted => RoleResources::None,
+        };
+
+        let mut balances: BTreeMap<_, _> = self
+            .balances
+            .into_iter()
+            .map(|(code, amount)| (code, Balance::new(amount)))
+            .collect();
+        if balances.is_empty() && self.role.can_hold_balance() {
+            balances.insert(lbr_currency_code(), Balance::new(0));
+        }
+        assert!(
+            balances.is_empty() || self.role.can_hold_balance(),
+            "accounts with role {:?} cannot hold balances",
+            self.role
+        );
+
+        AccountData {
+            account_role: AccountRole::new(*account.address(), self.role),
+            event_generator: EventHandleGenerator::new_with_event_count(*account.address(), 2),
+            withdrawal_capability: Some(WithdrawCapability::new(*account.address())),
+            key_rotation_capability: Some(KeyRotationCapability::new(*account.address())),
+            account,
+            balances,
+            sequence_number: self.sequence_number,
+            sent_events: new_event_handle(0),
+            received_events: new_event_handle(0),
+            role_resources,
+        }
+    }
+}
+
+impl Default for AccountDataBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+//---------------------------------------------------------------------------
+// Role resource represenation
+//---------------------------------------------------------------------------
+
+/// The role-specific resources published under an account, on top of the `RoleId`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+enum RoleResources {
+    None,
+    ParentVASP {
+        credential: Credential,
+    },
+    ChildVASP {
+        parent_vasp_addr: AccountAddress,
+    },
+    DesignatedDealer {
+        credential: Credential,
+        mint_events: EventHandle,
+    },
+    TreasuryCompliance,
+    /// An empty `ValidatorConfig`, without config nor operator.
+    Validator,
+}
+
+impl RoleResources {
+    /// Returns the write set entries for the role resources of `account`, which holds `balances`.
+    fn to_writeset_entries<'a>(
+        &self,
+        account: &Account,
+        balances: impl Iterator<Item = (&'a Identifier, u64)>,
+    ) -> Vec<(AccessPath, WriteOp)> {
+        use MoveStructLayout as S;
+        use MoveTypeLayout as T;
+
+        let mut resources = vec![];
+        match self {
+            Self::None => (),
+            Self::ParentVASP { credential } => {
+                resources.push((
+                    ParentVASP::struct_tag(),
+                    Struct::pack(vec![Value::u64(0)], true),
+                    S::new(vec![T::U64]),
+                ));
+                resources.push(credential.to_resource());
+                // Parent VASPs track the limits of all the accounts of the VASP, including its
+                // children.
+                for (code, amount) in balances {
+                    resources.push((
+                        core_struct_tag("AccountLimits", "Window", Some(code)),
+                        Struct::pack(
+                            vec![
+                                Value::u64(0),
+                                Value::u64(0),
+                                Value::u64(0),
+                                Value::u64(amount),
+                                Value::address(account_config::libra_root_address()),
+                            ],
+                            true,
+                        ),
+                        S::new(vec![T::U64, T::U64, T::U64, T::U64, T::Address]),
+                    ));
+                }
+            }
+            Self::ChildVASP { parent_vasp_addr } => resources.push((
+                ChildVASP::struct_tag(),
+                Struct::pack(vec![Value::address(*parent_vasp_addr)], true),
+                S::new(vec![T::Address]),
+            )),
+            Self::DesignatedDealer {
+                credential,
+                mint_events,
+            } => {
+                resources.push((
+                    DesignatedDealer::struct_tag(),
+                    Struct::pack(
+                        vec![Value::struct_(Struct::pack(
+                            vec![
+                                Value::u64(mint_events.count()),
+                                Value::vector_u8(mint_events.key().to_vec()),
+                            ],
+                            true,
+                        ))],
+                        true,
+                    ),
+                    S::new(vec![T::Struct(AccountData::event_handle_layout())]),
+                ));
+                resources.push(credential.to_resource());
+                for (code, _) in balances {
+                    resources.push((
+                        core_struct_tag("DesignatedDealer", "TierInfo", Some(code)),
+                        Struct::pack(
+                            vec![
+                                Value::u64(0),
+                                Value::u64(0),
+                                Value::vector_u64(DEFAULT_DD_TIERS.iter().copied()),
+                            ],
+                            true,
+                        ),
+                        S::new(vec![T::U64, T::U64, T::Vector(Box::new(T::U64))]),
+                    ));
+                    resources.push((
+                        core_struct_tag("Libra", "Preburn", Some(code)),
+                        Struct::pack(
+                            vec![Value::struct_(Struct::pack(vec![Value::u64(0)], true))],
+                            true,
+                        ),
+                        S::new(vec![T::Struct(S::new(vec![T::U64]))]),
+                    ));
+                }
+            }
+            Self::TreasuryCompliance => resources.push((
+                core_struct_tag("SlidingNonce", "SlidingNonce", None),
+                Struct::pack(vec![Value::u64(0), Value::u128(0)], true),
+                S::new(vec![T::U64, T::U128]),
+            )),
+            Self::Validator => {
+                let config_layout = S::new(vec![T::Vector(Box::new(T::U8)); 5]);
+                let option_none = |elements| Value::struct_(Struct::pack(vec![elements], false));
+                resources.push((
+                    core_struct_tag("ValidatorConfig", "ValidatorConfig", None),
+                    Struct::pack(
+                        vec![
+                            option_none(Value::vector_resource_for_testing_only(vec![])),
+                            option_none(Value::vector_address(vec![])),
+                        ],
+                        true,
+                    ),
+                    S::new(vec![
+                        T::Struct(S::new(vec![T::Vector(Box::new(T::Struct(config_layout)))])),
+                        T::Struct(S::new(vec![T::Vector(Box::new(T::Address))])),
+                    ]),
+                ))
+            }
+        }
+
+        resources
+            .into_iter()
+            .map(|(tag, value, layout)| {
+                (
+                    account.make_access_path(tag),
+                    WriteOp::Value(value.simple_serialize(&layout).unwrap()),
+                )
+            })
+            .collect()
+    }
+}
+
+/// Default tier upper bounds given to a designated dealer for each currency.
+const DEFAULT_DD_TIERS: [u64; 4] = [500_000, 5_000_000, 50_000_000, 500_000_000];
+
+fn core_struct_tag(module: &str, name: &str, currency_code: Option<&Identifier>) -> StructTag {
+    StructTag {
+        address: account_config::CORE_CODE_ADDRESS,
+        module: Identifier::new(module).unwrap(),
+        name: Identifier::new(name).unwrap(),
+        type_params: currency_code
+            .map(|code| vec![type_tag_for_currency_code(code.clone())])
+            .unwrap_or_default(),
+    }
+}
+
+/// Struct that represents a `DualAttestation::Credential` resource for tests.
+#[derive(Clone, Debug, Eq, PartialEq)]
+struct Credential {
+    human_name: Vec<u8>,
+    base_url: Vec<u8>,
+    compliance_public_key: Vec<u8>,
+}
+
+impl Credential {
+    fn to_resource(&self) -> (StructTag, Struct, MoveStructLayout) {
+        let bytes = || MoveTypeLayout::Vector(Box::new(MoveTypeLayout::U8));
+        (
+            account_config::Credential::struct_tag(),
+            Struct::pack(
+                vec![
+                    Value::vector_u8(self.human_name.clone()),
+                    Value::vector_u8(self.base_url.clone()),
+                    Value::vector_u8(self.compliance_public_key.clone()),
+                    Value::u64(std::u64::MAX),
+                ],
+                true,
+            ),
+            MoveStructLayout::new(vec![bytes(), bytes(), bytes(), MoveTypeLayout::U64]),
+        )
+    }
+}
+
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct WithdrawCapability {
     account_address: AccountAddress,
@@ -0,0 +1,183 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Throughput and latency measurements of stdlib scripts run through the [`FakeExecutor`].
+//!
+//! Each [`ScriptWorkload`] sets up an executor and a block of transactions running one stdlib
+//! script. [`run`] measures the throughput of executing the block at once and the latency of
+//! executing each transaction on its own, and returns a [`BenchmarkResult`] that can be written
+//! out as JSON to track regressions over time. The criterion benchmarks in `language/benchmarks`
+//! execute the same workloads.
+
+use crate::{
+    account::{Account, AccountData},
+    common_transactions::{create_account_txn, peer_to_peer_txn, rotate_key_txn},
+    executor::FakeExecutor,
+    gas_costs::TXN_RESERVED,
+};
+use libra_crypto::{ed25519::Ed25519PrivateKey, PrivateKey, Uniform};
+use libra_types::{
+    account_config,
+    transaction::{authenticator::AuthenticationKey, SignedTransaction},
+};
+use serde_json::json;
+use std::time::{Duration, Instant};
+
+/// A block of transactions that all run the same stdlib script.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ScriptWorkload {
+    /// Payments from one account to another.
+    PeerToPeer,
+    /// Parent VASP accounts created by the Libra root account.
+    CreateAccount,
+    /// Authentication key rotations, one per account.
+    RotateKey,
+}
+
+impl ScriptWorkload {
+    /// Every workload, in a fixed order.
+    pub fn all() -> [ScriptWorkload; 3] {
+        [
+            ScriptWorkload::PeerToPeer,
+            ScriptWorkload::CreateAccount,
+            ScriptWorkload::RotateKey,
+        ]
+    }
+
+    /// The name the workload is reported under.
+    pub fn name(self) -> &'static str {
+        match self {
+            ScriptWorkload::PeerToPeer => "peer_to_peer",
+            ScriptWorkload::CreateAccount => "create_account",
+            ScriptWorkload::RotateKey => "rotate_key",
+        }
+    }
+
+    /// Creates an executor and `num_transactions` transactions of this workload that execute
+    /// successfully against it, in order.
+    pub fn setup(self, num_transactions: usize) -> (FakeExecutor, Vec<SignedTransaction>) {
+        let mut executor = FakeExecutor::from_genesis_file();
+        let num_transactions = num_transactions as u64;
+        let txns = match self {
+            ScriptWorkload::PeerToPeer => {
+                let sender = AccountData::new(TXN_RESERVED * num_transactions * 2, 0);
+                let receiver = AccountData::new(0, 0);
+                executor.add_account_data(&sender);
+                executor.add_account_data(&receiver);
+                (0..num_transactions)
+                    .map(|seq_num| {
+                        peer_to_peer_txn(sender.account(), receiver.account(), seq_num, 1)
+                    })
+                    .collect()
+            }
+            ScriptWorkload::CreateAccount => {
+                let libra_root = Account::new_libra_root();
+                // The genesis transaction used sequence number 0.
+                (1..=num_transactions)
+                    .map(|seq_num| {
+                        create_account_txn(
+                            &libra_root,
+                            &Account::new(),
+                            seq_num,
+                            0,
+                            account_config::lbr_type_tag(),
+                        )
+                    })
+                    .collect()
+            }
+            ScriptWorkload::RotateKey => (0..num_transactions)
+                .map(|_| {
+                    let sender = AccountData::new(TXN_RESERVED, 0);
+                    executor.add_account_data(&sender);
+                    let pubkey = Ed25519PrivateKey::generate_for_testing().public_key();
+                    let new_key_hash = AuthenticationKey::ed25519(&pubkey).to_vec();
+                    rotate_key_txn(sender.account(), new_key_hash, 0)
+                })
+                .collect(),
+        };
+        (executor, txns)
+    }
+}
+
+/// The measurements taken by [`run`].
+#[derive(Clone, Debug)]
+pub struct BenchmarkResult {
+    pub workload: ScriptWorkload,
+    pub num_transactions: usize,
+    /// The time taken to execute all transactions as one block.
+    pub block_time: Duration,
+    /// The time taken to execute and apply each transaction on its own, in order.
+    pub latencies: Vec<Duration>,
+}
+
+impl BenchmarkResult {
+    /// Transactions per second when executing the whole block at once.
+    pub fn tps(&self) -> f64 {
+        self.num_transactions as f64 / self.block_time.as_secs_f64()
+    }
+
+    /// The `percentile`th percentile of the per-transaction latencies, for `percentile` between
+    /// 0 and 100.
+    pub fn latency_percentile(&self, percentile: f64) -> Duration {
+        let mut latencies = self.latencies.clone();
+        latencies.sort();
+        let rank = (percentile / 100.0 * (latencies.len() - 1) as f64).round() as usize;
+        latencies[rank]
+    }
+
+    /// Returns the result as a JSON object. Durations are in microseconds.
+    pub fn to_json(&self) -> serde_json::Value {
+        json!({
+            "workload": self.workload.name(),
+            "num_transactions": self.num_transactions,
+            "block_time_us": self.block_time.as_micros() as u64,
+            "tps": self.tps(),
+            "latency_us": {
+                "p50": self.latency_percentile(50.0).as_micros() as u64,
+                "p90": self.latency_percentile(90.0).as_micros() as u64,
+                "p99": self.latency_percentile(99.0).as_micros() as u64,
+                "max": self.latency_percentile(100.0).as_micros() as u64,
+            },
+        })
+    }
+}
+
+/// Measures `workload` with a block of `num_transactions` transactions.
+///
+/// Panics if `num_transactions` is zero or any transaction fails to execute.
+pub fn run(workload: ScriptWorkload, num_transactions: usize) -> BenchmarkResult {
+    assert!(num_transactions > 0, "a benchmark needs transactions");
+    let (mut executor, txns) = workload.setup(num_transactions);
+
+    let start = Instant::now();
+    executor
+        .execute_block(txns.clone())
+        .expect("VM should not fail to start");
+    let block_time = start.elapsed();
+
+    let latencies = txns
+        .into_iter()
+        .map(|txn| {
+            let start = Instant::now();
+            executor.execute_and_apply(txn);
+            start.elapsed()
+        })
+        .collect();
+
+    BenchmarkResult {
+        workload,
+        num_transactions,
+        block_time,
+        latencies,
+    }
+}
+
+/// Measures every workload and returns the results as a JSON array.
+pub fn run_all(num_transactions: usize) -> serde_json::Value {
+    serde_json::Value::Array(
+        ScriptWorkload::all()
+            .iter()
+            .map(|workload| run(*workload, num_transactions).to_json())
+            .collect(),
+    )
+}
@@ -0,0 +1,224 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Scenario helpers for compliance tests: account limits and dual attestation.
+//!
+//! Dual attestation credentials are set up with
+//! [`AccountDataBuilder::compliance_public_key`][crate::account::AccountDataBuilder::compliance_public_key]
+//! and payment metadata is signed with
+//! [`transaction_builder::dual_attestation::sign_dual_attestation_message`].
+//!
+//! Setting up limits through transactions takes a publish script from the limited account, a
+//! window update and a definition update from treasury compliance, each with its own sequence
+//! number and sliding nonce. The helpers here write the resources directly instead, so a test
+//! can focus on the transactions it checks:
+//!
+//! ```ignore
+//! let limits = LimitsDefinition {
+//!     max_inflow: 100,
+//!     time_period: 1_000_000,
+//!     ..LimitsDefinition::unrestricted()
+//! };
+//! executor.limit_account(vasp.address(), &coin1, limits);
+//! // ... payments into `vasp` until the inflow limit is hit ...
+//! executor.advance_past_limits_window(vasp.address(), &coin1);
+//! ```
+//!
+//! Transactions signed with the default expiration time expire after 40,000 seconds, so tests
+//! that advance the clock should use a limits `time_period` well below that.
+
+use crate::executor::FakeExecutor;
+use libra_types::{
+    access_path::AccessPath,
+    account_address::AccountAddress,
+    account_config::{self, Limit},
+    write_set::{WriteOp, WriteSetMut},
+};
+use move_core_types::{
+    identifier::{IdentStr, Identifier},
+    language_storage::StructTag,
+};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+
+/// The time period of the limits published by `AccountLimits::publish_unrestricted_limits`, in
+/// microseconds.
+pub const ONE_DAY_MICROS: u64 = 86_400_000_000;
+
+/// An `AccountLimits::LimitsDefinition<CoinType>` resource.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct LimitsDefinition {
+    pub max_inflow: u64,
+    pub max_outflow: u64,
+    /// The length of a limits window, in microseconds.
+    pub time_period: u64,
+    pub max_holding: u64,
+}
+
+impl LimitsDefinition {
+    /// The limits published by `AccountLimits::publish_unrestricted_limits`.
+    pub fn unrestricted() -> Self {
+        Self {
+            max_inflow: std::u64::MAX,
+            max_outflow: std::u64::MAX,
+            time_period: ONE_DAY_MICROS,
+            max_holding: std::u64::MAX,
+        }
+    }
+}
+
+/// An `AccountLimits::Window<CoinType>` resource.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct Window {
+    /// The on-chain time the window started at, in microseconds.
+    pub window_start: u64,
+    pub window_inflow: u64,
+    pub window_outflow: u64,
+    pub tracked_balance: u64,
+    /// The address of the `LimitsDefinition` the window is checked against.
+    pub limit_address: AccountAddress,
+}
+
+impl Window {
+    /// Returns an empty window checked against the limits at `limit_address`.
+    pub fn new(limit_address: AccountAddress, tracked_balance: u64) -> Self {
+        Self {
+            window_start: 0,
+            window_inflow: 0,
+            window_outflow: 0,
+            tracked_balance,
+            limit_address,
+        }
+    }
+}
+
+fn currency_struct_tag(module: &str, name: &str, currency_code: &IdentStr) -> StructTag {
+    StructTag {
+        address: account_config::CORE_CODE_ADDRESS,
+        module: Identifier::new(module).unwrap(),
+        name: Identifier::new(name).unwrap(),
+        type_params: vec![account_config::type_tag_for_currency_code(
+            currency_code.to_owned(),
+        )],
+    }
+}
+
+fn limits_definition_path(address: &AccountAddress, currency_code: &IdentStr) -> AccessPath {
+    let tag = currency_struct_tag("AccountLimits", "LimitsDefinition", currency_code);
+    AccessPath::new(*address, AccessPath::resource_access_vec(&tag))
+}
+
+fn window_path(address: &AccountAddress, currency_code: &IdentStr) -> AccessPath {
+    let tag = currency_struct_tag("AccountLimits", "Window", currency_code);
+    AccessPath::new(*address, AccessPath::resource_access_vec(&tag))
+}
+
+impl FakeExecutor {
+    /// Publishes `limits` for `currency_code` at `address`, replacing any limits already there.
+    ///
+    /// Only the windows pointing at `address` are checked against these limits.
+    pub fn set_limits_definition(
+        &mut self,
+        address: &AccountAddress,
+        currency_code: &IdentStr,
+        limits: LimitsDefinition,
+    ) {
+        self.write_value(limits_definition_path(address, currency_code), &limits);
+    }
+
+    pub fn read_limits_definition(
+        &self,
+        address: &AccountAddress,
+        currency_code: &IdentStr,
+    ) -> Option<LimitsDefinition> {
+        self.read_value(&limits_definition_path(address, currency_code))
+    }
+
+    /// Publishes `window` for `currency_code` at `address`, replacing any window already there.
+    pub fn set_limits_window(
+        &mut self,
+        address: &AccountAddress,
+        currency_code: &IdentStr,
+        window: Window,
+    ) {
+        self.write_value(window_path(address, currency_code), &window);
+    }
+
+    pub fn read_limits_window(
+        &self,
+        address: &AccountAddress,
+        currency_code: &IdentStr,
+    ) -> Option<Window> {
+        self.read_value(&window_path(address, currency_code))
+    }
+
+    /// Limits the parent VASP at `address` to `limits` for `currency_code`.
+    ///
+    /// The limits are published at `address` and its window is pointed at them, restarting it at
+    /// the current time. The balance tracked by the window is kept, so the account must already
+    /// hold a window for `currency_code`, as parent VASPs created by `AccountData` do.
+    pub fn limit_account(
+        &mut self,
+        address: &AccountAddress,
+        currency_code: &IdentStr,
+        limits: LimitsDefinition,
+    ) {
+        let window = self
+            .read_limits_window(address, currency_code)
+            .expect("a limited account must hold a limits window");
+        self.set_limits_definition(address, currency_code, limits);
+        self.set_limits_window(
+            address,
+            currency_code,
+            Window {
+                window_start: self.get_block_time(),
+                ..Window::new(*address, window.tracked_balance)
+            },
+        );
+    }
+
+    /// Advances the clock until the limits window of `address` for `currency_code` has expired,
+    /// so the next transfer starts a new window.
+    pub fn advance_past_limits_window(
+        &mut self,
+        address: &AccountAddress,
+        currency_code: &IdentStr,
+    ) {
+        let window = self
+            .read_limits_window(address, currency_code)
+            .expect("account must hold a limits window");
+        let limits = self
+            .read_limits_definition(&window.limit_address, currency_code)
+            .expect("window must point at a limits definition");
+        // A window is reset once the current time is strictly past its end.
+        let window_end = window.window_start + limits.time_period;
+        let now = self.get_block_time();
+        self.advance_time(window_end.saturating_sub(now) + 1);
+    }
+
+    /// Sets the amount, in micro LBR, from which payments between VASPs require dual attestation.
+    pub fn set_dual_attestation_limit(&mut self, micro_lbr_limit: u64) {
+        self.write_resource(
+            &account_config::libra_root_address(),
+            &Limit { micro_lbr_limit },
+        );
+    }
+
+    pub fn dual_attestation_limit(&self) -> u64 {
+        self.read_resource::<Limit>(&account_config::libra_root_address())
+            .expect("dual attestation limit must be published")
+            .micro_lbr_limit
+    }
+
+    fn read_value<T: DeserializeOwned>(&self, path: &AccessPath) -> Option<T> {
+        self.read_from_access_path(path)
+            .map(|blob| lcs::from_bytes(&blob).expect("resource must deserialize"))
+    }
+
+    fn write_value<T: Serialize>(&mut self, path: AccessPath, value: &T) {
+        let blob = lcs::to_bytes(value).expect("resource must serialize");
+        let write_set = WriteSetMut::new(vec![(path, WriteOp::Value(blob))])
+            .freeze()
+            .expect("write set must be valid");
+        self.apply_write_set(&write_set);
+    }
+}
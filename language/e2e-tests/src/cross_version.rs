@@ -0,0 +1,171 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Replay of the same transactions against two versions of the stdlib.
+//!
+//! A [`CrossVersionReplay`] keeps a baseline and a candidate executor in lock step: every block
+//! is executed by both, the outputs are compared, and on agreement both apply them. Any difference
+//! in status, gas used, events or write set is a change in behavior that validators running
+//! different versions would disagree on, so it is reported as a [`ReplayError::Divergence`].
+//!
+//! The baseline is usually the stdlib released in `compiled-stdlib` and the candidate the one
+//! freshly built from source, see [`CrossVersionReplay::compiled_vs_fresh`]. Both executors link
+//! the same VM, so only the stdlib differs between them.
+
+use crate::{account::AccountData, executor::FakeExecutor};
+use libra_types::{
+    on_chain_config::VMPublishingOption,
+    transaction::{SignedTransaction, TransactionOutput, TransactionStatus},
+    vm_status::VMStatus,
+};
+use std::fmt;
+use vm::CompiledModule;
+
+/// Which side of a [`CrossVersionReplay`] an error came from.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ReplaySide {
+    Baseline,
+    Candidate,
+}
+
+/// Error returned by [`CrossVersionReplay::execute_block`].
+#[derive(Debug)]
+pub enum ReplayError {
+    /// The block failed to execute on one side.
+    VMStatus { side: ReplaySide, status: VMStatus },
+    /// The output at `index` differs between the two versions.
+    Divergence {
+        index: usize,
+        baseline: TransactionOutput,
+        candidate: TransactionOutput,
+    },
+}
+
+impl fmt::Display for ReplayError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ReplayError::VMStatus { side, status } => {
+                write!(f, "{:?} failed to execute the block: {:?}", side, status)
+            }
+            ReplayError::Divergence {
+                index,
+                baseline,
+                candidate,
+            } => write!(
+                f,
+                "transaction {} diverged: baseline {:?} != candidate {:?}",
+                index, baseline, candidate
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ReplayError {}
+
+/// Two executors that replay the same blocks and must produce the same outputs.
+#[derive(Debug)]
+pub struct CrossVersionReplay {
+    baseline: FakeExecutor,
+    candidate: FakeExecutor,
+}
+
+impl CrossVersionReplay {
+    /// Creates a replay between two executors.
+    ///
+    /// Both executors should hold the same accounts, so that the same transactions are valid on
+    /// either side.
+    pub fn new(baseline: FakeExecutor, candidate: FakeExecutor) -> Self {
+        Self {
+            baseline,
+            candidate,
+        }
+    }
+
+    /// Creates a replay of the released stdlib against the stdlib built from source.
+    pub fn compiled_vs_fresh() -> Self {
+        Self::new(
+            FakeExecutor::from_genesis_file(),
+            FakeExecutor::from_fresh_genesis(),
+        )
+    }
+
+    /// Creates a replay between two sets of stdlib modules, each used to build a fresh genesis
+    /// with the given publishing options.
+    pub fn from_stdlibs(
+        baseline_modules: Vec<CompiledModule>,
+        candidate_modules: Vec<CompiledModule>,
+        publishing_options: VMPublishingOption,
+    ) -> Self {
+        Self::new(
+            FakeExecutor::custom_genesis(baseline_modules, None, publishing_options.clone()),
+            FakeExecutor::custom_genesis(candidate_modules, None, publishing_options),
+        )
+    }
+
+    /// Returns the executor running the baseline version.
+    pub fn baseline(&self) -> &FakeExecutor {
+        &self.baseline
+    }
+
+    /// Returns the executor running the candidate version.
+    pub fn candidate(&self) -> &FakeExecutor {
+        &self.candidate
+    }
+
+    /// Adds an account to both executors.
+    pub fn add_account_data(&mut self, account_data: &AccountData) {
+        self.baseline.add_account_data(account_data);
+        self.candidate.add_account_data(account_data);
+    }
+
+    /// Executes `txn_block` on both versions and compares the outputs.
+    ///
+    /// If they agree, the outputs are applied on both sides and returned. Otherwise neither side
+    /// is changed and the first divergence is returned.
+    pub fn execute_block(
+        &mut self,
+        txn_block: Vec<SignedTransaction>,
+    ) -> Result<Vec<TransactionOutput>, ReplayError> {
+        let baseline_outputs =
+            self.baseline
+                .execute_block(txn_block.clone())
+                .map_err(|status| ReplayError::VMStatus {
+                    side: ReplaySide::Baseline,
+                    status,
+                })?;
+        let candidate_outputs =
+            self.candidate
+                .execute_block(txn_block)
+                .map_err(|status| ReplayError::VMStatus {
+                    side: ReplaySide::Candidate,
+                    status,
+                })?;
+        assert_eq!(
+            baseline_outputs.len(),
+            candidate_outputs.len(),
+            "the two versions returned different numbers of outputs"
+        );
+
+        for (index, (baseline, candidate)) in baseline_outputs
+            .iter()
+            .zip(candidate_outputs.into_iter())
+            .enumerate()
+        {
+            if baseline != &candidate {
+                return Err(ReplayError::Divergence {
+                    index,
+                    baseline: baseline.clone(),
+                    candidate,
+                });
+            }
+        }
+
+        for output in &baseline_outputs {
+            if let TransactionStatus::Keep(_) = output.status() {
+                self.baseline.apply_write_set(output.write_set());
+                self.candidate.apply_write_set(output.write_set());
+            }
+        }
+        Ok(baseline_outputs)
+    }
+}
@@ -20,7 +20,7 @@ use move_core_types::{
 };
 use move_vm_runtime::data_cache::RemoteCache;
 use once_cell::sync::Lazy;
-use std::collections::HashMap;
+use std::{collections::HashMap, sync::Mutex};
 use vm::{errors::*, CompiledModule};
 use vm_genesis::generate_genesis_change_set_for_testing;
 
@@ -35,7 +35,7 @@ pub static GENESIS_CHANGE_SET_FRESH: Lazy<ChangeSet> =
 ///
 /// Tests use this to set up state, and pass in a reference to the cache whenever a `StateView` or
 /// `RemoteCache` is needed.
-#[derive(Debug, Default)]
+#[derive(Clone, Debug, Default)]
 pub struct FakeDataStore {
     data: HashMap<AccessPath, Vec<u8>>,
 }
@@ -129,3 +129,41 @@ impl RemoteCache for FakeDataStore {
         RemoteStorage::new(self).get_resource(address, tag)
     }
 }
+
+/// A [`StateView`] over a [`FakeDataStore`] that records every access path read through it, in
+/// the order they were first read.
+pub(crate) struct ReadRecordingView<'a> {
+    state: &'a FakeDataStore,
+    reads: Mutex<Vec<AccessPath>>,
+}
+
+impl<'a> ReadRecordingView<'a> {
+    pub(crate) fn new(state: &'a FakeDataStore) -> Self {
+        Self {
+            state,
+            reads: Mutex::new(vec![]),
+        }
+    }
+
+    pub(crate) fn into_reads(self) -> Vec<AccessPath> {
+        self.reads.into_inner().expect("read set lock poisoned")
+    }
+}
+
+impl<'a> StateView for ReadRecordingView<'a> {
+    fn get(&self, access_path: &AccessPath) -> Result<Option<Vec<u8>>> {
+        let mut reads = self.reads.lock().expect("read set lock poisoned");
+        if !reads.contains(access_path) {
+            reads.push(access_path.clone());
+        }
+        StateView::get(self.state, access_path)
+    }
+
+    fn multi_get(&self, access_paths: &[AccessPath]) -> Result<Vec<Option<Vec<u8>>>> {
+        access_paths.iter().map(|path| self.get(path)).collect()
+    }
+
+    fn is_genesis(&self) -> bool {
+        self.state.is_genesis()
+    }
+}
@@ -0,0 +1,119 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Assertions over the contract events emitted by a transaction.
+//!
+//! Events are matched by their Move type tag, optionally by the key of the stream they were
+//! emitted on, and by their LCS-decoded payload:
+//!
+//! ```ignore
+//! output.assert_emits::<SentPaymentEvent>(sender.sent_events_key(), |event| {
+//!     event.amount() == 1_000
+//! });
+//! ```
+
+use libra_types::{
+    contract_event::ContractEvent,
+    transaction::{TransactionOutput, TransactionStatus},
+};
+use move_core_types::{language_storage::TypeTag, move_resource::MoveResource};
+use serde::de::DeserializeOwned;
+use std::fmt::Debug;
+
+/// Extension trait for matching the events emitted by a transaction.
+pub trait EventAssert {
+    /// Returns the events emitted by the transaction.
+    fn emitted_events(&self) -> &[ContractEvent];
+
+    /// Returns the payloads of all events of type `T`, in emission order.
+    ///
+    /// Panics if an event has the type tag of `T` but its payload fails to decode.
+    fn events_of_type<T: MoveResource + DeserializeOwned>(&self) -> Vec<T> {
+        decode_events(
+            self.emitted_events()
+                .iter()
+                .filter(|event| has_type::<T>(event)),
+        )
+    }
+
+    /// Returns the payloads of all events of type `T` emitted on the stream with key `key`, in
+    /// emission order.
+    fn events_of_type_with_key<T: MoveResource + DeserializeOwned>(&self, key: &[u8]) -> Vec<T> {
+        decode_events(
+            self.emitted_events()
+                .iter()
+                .filter(|event| has_type::<T>(event) && event.key().as_bytes() == key),
+        )
+    }
+
+    /// Asserts that an event of type `T` matching `predicate` was emitted on the stream with key
+    /// `key`, and returns its payload.
+    fn assert_emits<T, F>(&self, key: &[u8], predicate: F) -> T
+    where
+        T: MoveResource + DeserializeOwned + Debug,
+        F: Fn(&T) -> bool,
+    {
+        let candidates = self.events_of_type_with_key::<T>(key);
+        let all_events = format!("{:?}", candidates);
+        candidates
+            .into_iter()
+            .find(|event| predicate(event))
+            .unwrap_or_else(|| {
+                panic!(
+                    "no {} event on stream {:?} matched; found {} (all events: {:?})",
+                    T::STRUCT_NAME,
+                    key,
+                    all_events,
+                    self.emitted_events(),
+                )
+            })
+    }
+
+    /// Asserts that no event of type `T` was emitted.
+    fn assert_emits_none<T: MoveResource + DeserializeOwned + Debug>(&self) {
+        let events = self.events_of_type::<T>();
+        assert!(
+            events.is_empty(),
+            "expected no {} events, found {:?}",
+            T::STRUCT_NAME,
+            events
+        );
+    }
+}
+
+impl EventAssert for TransactionOutput {
+    fn emitted_events(&self) -> &[ContractEvent] {
+        match self.status() {
+            TransactionStatus::Keep(_) => self.events(),
+            // Discarded and retried transactions never make their events visible.
+            TransactionStatus::Discard(_) | TransactionStatus::Retry => &[],
+        }
+    }
+}
+
+impl EventAssert for [ContractEvent] {
+    fn emitted_events(&self) -> &[ContractEvent] {
+        self
+    }
+}
+
+fn has_type<T: MoveResource>(event: &ContractEvent) -> bool {
+    event.type_tag() == &TypeTag::Struct(T::struct_tag())
+}
+
+fn decode_events<'a, T: MoveResource + DeserializeOwned>(
+    events: impl Iterator<Item = &'a ContractEvent>,
+) -> Vec<T> {
+    events
+        .map(|event| {
+            lcs::from_bytes(event.event_data()).unwrap_or_else(|err| {
+                panic!(
+                    "failed to decode {} event {:?}: {}",
+                    T::STRUCT_NAME,
+                    event,
+                    err
+                )
+            })
+        })
+        .collect()
+}
@@ -0,0 +1,90 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Execution traces for debugging failing tests.
+//!
+//! When tracing is enabled with [`FakeExecutor::set_tracing`][crate::executor::FakeExecutor::set_tracing],
+//! every block the executor runs, and every transaction it verifies, records the Move functions
+//! called with their arguments (in debug builds only), the access paths read from the data store
+//! and the access paths written. The trace of the most recent run on the current thread is kept, and
+//! [`assert_prologue_parity!`][crate::assert_prologue_parity] prints it when it fails:
+//!
+//! ```text
+//! calls:
+//!   0x1::LibraAccount::prologue<Struct(4)>(...)
+//!     0x1::LibraAccount::balance<Struct(4)>(...)
+//! reads:
+//!   AccessPath { address: ..., type: Resource, ... }
+//! writes:
+//! ```
+
+use libra_types::access_path::AccessPath;
+use move_vm_runtime::TracedCall;
+use std::{cell::RefCell, fmt, thread};
+
+/// The Move function calls and state accesses made while executing or verifying transactions.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct ExecutionTrace {
+    /// The Move functions called, in call order.
+    pub calls: Vec<TracedCall>,
+    /// The access paths read from the data store, in the order they were first read.
+    pub reads: Vec<AccessPath>,
+    /// The access paths in the write sets of the outputs.
+    pub writes: Vec<AccessPath>,
+}
+
+impl fmt::Display for ExecutionTrace {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "calls:")?;
+        for call in &self.calls {
+            write!(
+                f,
+                "{:indent$}{}",
+                "",
+                call.function,
+                indent = 2 * (call.depth + 1)
+            )?;
+            if !call.ty_args.is_empty() {
+                write!(f, "<{}>", call.ty_args.join(", "))?;
+            }
+            writeln!(f, "({})", call.args.join(", "))?;
+        }
+        writeln!(f, "reads:")?;
+        for access_path in &self.reads {
+            writeln!(f, "  {}", access_path)?;
+        }
+        writeln!(f, "writes:")?;
+        for access_path in &self.writes {
+            writeln!(f, "  {}", access_path)?;
+        }
+        Ok(())
+    }
+}
+
+thread_local! {
+    static LAST_TRACE: RefCell<Option<ExecutionTrace>> = RefCell::new(None);
+}
+
+/// Returns the trace of the most recent traced run on this thread, if any.
+pub fn last_trace() -> Option<ExecutionTrace> {
+    LAST_TRACE.with(|trace| trace.borrow().clone())
+}
+
+pub(crate) fn set_last_trace(trace: ExecutionTrace) {
+    LAST_TRACE.with(|last_trace| *last_trace.borrow_mut() = Some(trace));
+}
+
+/// Prints the [`last_trace`] to stderr if it is dropped while the thread is panicking.
+///
+/// Assertion macros hold one of these while they check their conditions.
+pub struct PrintTraceOnPanic;
+
+impl Drop for PrintTraceOnPanic {
+    fn drop(&mut self) {
+        if thread::panicking() {
+            if let Some(trace) = last_trace() {
+                eprintln!("execution trace of the last traced run:\n{}", trace);
+            }
+        }
+    }
+}
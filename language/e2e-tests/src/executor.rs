@@ -5,40 +5,51 @@
 
 use crate::{
     account::{Account, AccountData},
-    data_store::{FakeDataStore, GENESIS_CHANGE_SET, GENESIS_CHANGE_SET_FRESH},
+    data_store::{FakeDataStore, ReadRecordingView, GENESIS_CHANGE_SET, GENESIS_CHANGE_SET_FRESH},
+    execution_trace::{self, ExecutionTrace},
+    genesis_builder::{GenesisBuilder, DEFAULT_VALIDATOR_COUNT},
+    golden_outputs::GoldenOutputs,
+    parallel_executor::{self, ParallelExecutionError},
 };
 use compiled_stdlib::{stdlib_modules, transaction_scripts::StdlibScript, StdLibOptions};
-use libra_config::generator;
 use libra_crypto::HashValue;
 use libra_state_view::StateView;
 use libra_types::{
     access_path::AccessPath,
-    account_config::{AccountResource, BalanceResource, CORE_CODE_ADDRESS},
+    account_config::{libra_root_address, AccountResource, BalanceResource, CORE_CODE_ADDRESS},
     block_metadata::{new_block_event_key, BlockMetadata, NewBlockEvent},
-    on_chain_config::{OnChainConfig, ScriptPublishingOption, VMPublishingOption, ValidatorSet},
+    contract_event::ContractEvent,
+    libra_timestamp::{LibraTimestamp, LibraTimestampResource},
+    on_chain_config::{
+        config_address, ConfigurationResource, OnChainConfig, ScriptPublishingOption,
+        VMPublishingOption, ValidatorSet,
+    },
     transaction::{
-        SignedTransaction, Transaction, TransactionOutput, TransactionStatus, VMValidatorResult,
+        ChangeSet, SignedTransaction, Transaction, TransactionOutput, TransactionStatus,
+        VMValidatorResult,
     },
     vm_status::{StatusCode, VMStatus},
     write_set::WriteSet,
 };
 use libra_vm::{
     data_cache::RemoteStorage, txn_effects_to_writeset_and_events, LibraVM, LibraVMValidator,
-    VMExecutor, VMValidator,
+    VMExecutor, VMValidator, ValidationMode,
 };
 use move_core_types::{
     account_address::AccountAddress,
     gas_schedule::{GasAlgebra, GasUnits},
     identifier::Identifier,
     language_storage::{ModuleId, TypeTag},
+    move_resource::MoveResource,
 };
 use move_vm_runtime::move_vm::MoveVM;
 use move_vm_types::{
-    gas_schedule::{zero_cost_schedule, CostStrategy},
+    gas_schedule::{zero_cost_schedule, CostStrategy, ExecutionLimits, GasProfile},
     values::Value,
 };
+use serde::{de::DeserializeOwned, Serialize};
+use std::iter;
 use vm::CompiledModule;
-use vm_genesis::GENESIS_KEYPAIR;
 
 /// Provides an environment to run a VM instance.
 ///
@@ -47,6 +58,18 @@ use vm_genesis::GENESIS_KEYPAIR;
 pub struct FakeExecutor {
     data_store: FakeDataStore,
     block_time: u64,
+    golden_outputs: Option<GoldenOutputs>,
+    tracing: bool,
+    validation_mode: ValidationMode,
+}
+
+/// A copy of the state of a [`FakeExecutor`] at some point in time.
+///
+/// Created by [`FakeExecutor::snapshot`] and consumed by [`FakeExecutor::restore`].
+#[derive(Clone, Debug)]
+pub struct ExecutorSnapshot {
+    data_store: FakeDataStore,
+    block_time: u64,
 }
 
 impl FakeExecutor {
@@ -55,6 +78,9 @@ impl FakeExecutor {
         let mut executor = FakeExecutor {
             data_store: FakeDataStore::default(),
             block_time: 0,
+            golden_outputs: None,
+            tracing: false,
+            validation_mode: ValidationMode::default(),
         };
         executor.apply_write_set(write_set);
         executor
@@ -98,29 +124,25 @@ impl FakeExecutor {
         FakeExecutor {
             data_store: FakeDataStore::default(),
             block_time: 0,
+            golden_outputs: None,
+            tracing: false,
+            validation_mode: ValidationMode::default(),
         }
     }
 
     /// Creates fresh genesis from the stdlib modules passed in.
+    ///
+    /// See [`GenesisBuilder`] for more ways to customize genesis.
     pub fn custom_genesis(
         genesis_modules: Vec<CompiledModule>,
         validator_accounts: Option<usize>,
         publishing_options: VMPublishingOption,
     ) -> Self {
-        let genesis_change_set = {
-            let validator_count = validator_accounts.map_or(10, |s| s);
-            let swarm = generator::validator_swarm_for_testing(validator_count);
-
-            vm_genesis::encode_genesis_change_set(
-                &GENESIS_KEYPAIR.1,
-                &vm_genesis::operator_assignments(&swarm.nodes),
-                &vm_genesis::operator_registrations(&swarm.nodes),
-                &genesis_modules,
-                publishing_options,
-            )
-            .0
-        };
-        Self::from_genesis(genesis_change_set.write_set())
+        GenesisBuilder::new()
+            .stdlib_modules(genesis_modules)
+            .validator_count(validator_accounts.unwrap_or(DEFAULT_VALIDATOR_COUNT))
+            .publishing_options(publishing_options)
+            .build()
     }
 
     /// Creates a number of [`Account`] instances all with the same balance and sequence number,
@@ -135,6 +157,45 @@ impl FakeExecutor {
         accounts
     }
 
+    /// Captures the current state of this executor.
+    ///
+    /// Tests can explore one execution path, then [`restore`][FakeExecutor::restore] the snapshot
+    /// to try another from the same starting state without rebuilding genesis.
+    pub fn snapshot(&self) -> ExecutorSnapshot {
+        ExecutorSnapshot {
+            data_store: self.data_store.clone(),
+            block_time: self.block_time,
+        }
+    }
+
+    /// Rolls this executor back to the state captured in `snapshot`.
+    pub fn restore(&mut self, snapshot: ExecutorSnapshot) {
+        self.data_store = snapshot.data_store;
+        self.block_time = snapshot.block_time;
+    }
+
+    /// Records the outputs of every block executed from now on, to be checked against the golden
+    /// file `name` when this executor is dropped. See [`golden_outputs`][crate::golden_outputs]
+    /// for how golden files are created and updated.
+    ///
+    /// Accounts whose outputs are recorded must be deterministic, e.g. generated from a seeded
+    /// [`KeyGen`][crate::keygen::KeyGen] and created on chain rather than added to the data store.
+    pub fn set_golden_file(&mut self, name: &str) {
+        self.golden_outputs = Some(GoldenOutputs::new(name));
+    }
+
+    /// Enables or disables tracing of the transactions this executor executes and verifies.
+    ///
+    /// See [`execution_trace`] for what is recorded and where it is reported.
+    pub fn set_tracing(&mut self, enabled: bool) {
+        self.tracing = enabled;
+    }
+
+    /// Sets how thoroughly [`FakeExecutor::verify_transaction`] validates transactions.
+    pub fn set_validation_mode(&mut self, mode: ValidationMode) {
+        self.validation_mode = mode;
+    }
+
     /// Applies a [`WriteSet`] to this executor's data store.
     pub fn apply_write_set(&mut self, write_set: &WriteSet) {
         self.data_store.add_write_set(write_set);
@@ -176,6 +237,39 @@ impl FakeExecutor {
             })
     }
 
+    /// Reads the resource of type `T` published under `address` from this executor's data store,
+    /// or `None` if there is none.
+    pub fn read_resource<T: MoveResource + DeserializeOwned>(
+        &self,
+        address: &AccountAddress,
+    ) -> Option<T> {
+        let ap = AccessPath::new(*address, T::resource_path());
+        self.read_from_access_path(&ap).map(|data_blob| {
+            lcs::from_bytes(data_blob.as_slice()).unwrap_or_else(|err| {
+                panic!(
+                    "Failure decoding {} resource at {}: {}",
+                    T::STRUCT_NAME,
+                    address,
+                    err
+                )
+            })
+        })
+    }
+
+    /// Publishes `resource` under `address` in this executor's data store, replacing any resource
+    /// of the same type already there.
+    ///
+    /// Like [`FakeExecutor::add_account_data`], this bypasses the VM entirely.
+    pub fn write_resource<T: MoveResource + Serialize>(
+        &mut self,
+        address: &AccountAddress,
+        resource: &T,
+    ) {
+        let ap = AccessPath::new(*address, T::resource_path());
+        let data_blob = lcs::to_bytes(resource).expect("resource must serialize");
+        self.data_store.set(ap, data_blob);
+    }
+
     /// Executes the given block of transactions.
     ///
     /// Typical tests will call this method and check that the output matches what was expected.
@@ -184,15 +278,50 @@ impl FakeExecutor {
         &self,
         txn_block: Vec<SignedTransaction>,
     ) -> Result<Vec<TransactionOutput>, VMStatus> {
-        LibraVM::execute_block(
+        self.execute_transaction_block(
             txn_block
                 .into_iter()
                 .map(Transaction::UserTransaction)
                 .collect(),
-            &self.data_store,
         )
     }
 
+    /// Executes a block of transactions through the optimistic parallel execution path in
+    /// [`parallel_executor`] and cross-checks the outputs against sequential execution.
+    ///
+    /// Returns the outputs if both paths agree, or the first divergence otherwise. Like
+    /// [`FakeExecutor::execute_block`], the outputs are not applied to the data store.
+    pub fn execute_block_parallel(
+        &self,
+        txn_block: Vec<SignedTransaction>,
+    ) -> Result<Vec<TransactionOutput>, ParallelExecutionError> {
+        let parallel_outputs = parallel_executor::execute_block(&self.data_store, &txn_block)
+            .map_err(ParallelExecutionError::VMStatus)?;
+        let sequential_outputs = self
+            .execute_block(txn_block)
+            .map_err(ParallelExecutionError::VMStatus)?;
+        assert_eq!(
+            sequential_outputs.len(),
+            parallel_outputs.len(),
+            "parallel execution returned the wrong number of outputs"
+        );
+
+        for (index, (sequential, parallel)) in sequential_outputs
+            .iter()
+            .zip(parallel_outputs.into_iter())
+            .enumerate()
+        {
+            if sequential != &parallel {
+                return Err(ParallelExecutionError::Divergence {
+                    index,
+                    sequential: sequential.clone(),
+                    parallel,
+                });
+            }
+        }
+        Ok(sequential_outputs)
+    }
+
     /// Executes the transaction as a singleton block and applies the resulting write set to the
     /// data store. Panics if execution fails
     pub fn execute_and_apply(&mut self, transaction: SignedTransaction) -> TransactionOutput {
@@ -214,11 +343,110 @@ impl FakeExecutor {
         }
     }
 
+    /// Signs `change_set` as a write set transaction from `sender` at its current sequence number,
+    /// then executes it as a singleton block.
+    ///
+    /// The resulting write set is applied to the data store if the transaction is kept. Returns
+    /// the validator's verdict on the transaction along with the output, so tests can check that
+    /// both agree.
+    pub fn execute_writeset(
+        &mut self,
+        sender: &Account,
+        change_set: ChangeSet,
+    ) -> (Option<VMStatus>, TransactionOutput) {
+        let sequence_number = self
+            .read_account_resource(sender)
+            .expect("write set sender must exist")
+            .sequence_number();
+        let txn = sender.signed_writeset_txn(change_set, sequence_number);
+        let validation_status = self.verify_transaction(txn.clone()).status();
+        let output = self.execute_transaction(txn);
+        if let TransactionStatus::Keep(_) = output.status() {
+            self.apply_write_set(output.write_set());
+        }
+        (validation_status, output)
+    }
+
     pub fn execute_transaction_block(
         &self,
         txn_block: Vec<Transaction>,
     ) -> Result<Vec<TransactionOutput>, VMStatus> {
-        LibraVM::execute_block(txn_block, &self.data_store)
+        let outputs = if self.tracing {
+            self.execute_block_traced(txn_block)?
+        } else {
+            LibraVM::execute_block(txn_block, &self.data_store)?
+        };
+        if let Some(golden_outputs) = &self.golden_outputs {
+            golden_outputs.record(&outputs);
+        }
+        Ok(outputs)
+    }
+
+    fn execute_block_traced(
+        &self,
+        txn_block: Vec<Transaction>,
+    ) -> Result<Vec<TransactionOutput>, VMStatus> {
+        let view = ReadRecordingView::new(&self.data_store);
+        let (result, calls) =
+            move_vm_runtime::record_calls(|| LibraVM::execute_block(txn_block, &view));
+        let writes = result
+            .as_ref()
+            .map(|outputs| {
+                outputs
+                    .iter()
+                    .flat_map(|output| output.write_set().iter())
+                    .map(|(access_path, _)| access_path.clone())
+                    .collect()
+            })
+            .unwrap_or_default();
+        execution_trace::set_last_trace(ExecutionTrace {
+            calls,
+            reads: view.into_reads(),
+            writes,
+        });
+        result
+    }
+
+    /// Executes the given block of transactions behind `block_metadata` and applies the resulting
+    /// write sets to the data store.
+    ///
+    /// If a transaction in the block triggers a reconfiguration, the VM returns `Retry` for the
+    /// rest of the block. Those transactions are then executed in a new block proposed by a member
+    /// of the updated validator set, the same way consensus would carry them across the epoch
+    /// boundary. The returned outputs line up one-to-one with `txn_block`.
+    pub fn execute_block_with_metadata(
+        &mut self,
+        block_metadata: BlockMetadata,
+        txn_block: Vec<SignedTransaction>,
+    ) -> Result<Vec<TransactionOutput>, VMStatus> {
+        let mut outputs = Vec::with_capacity(txn_block.len());
+        let mut block_metadata = block_metadata;
+        let mut pending = txn_block;
+        loop {
+            self.block_time = block_metadata.timestamp_usec();
+            let block = iter::once(Transaction::BlockMetadata(block_metadata))
+                .chain(pending.iter().cloned().map(Transaction::UserTransaction))
+                .collect();
+            let mut block_outputs = self.execute_transaction_block(block)?;
+            let prologue_output = block_outputs.remove(0);
+            self.apply_write_set(prologue_output.write_set());
+
+            let retry_from = block_outputs
+                .iter()
+                .position(|output| output.status() == &TransactionStatus::Retry)
+                .unwrap_or_else(|| block_outputs.len());
+            for output in block_outputs.drain(..retry_from) {
+                if !output.status().is_discarded() {
+                    self.apply_write_set(output.write_set());
+                }
+                outputs.push(output);
+            }
+            if retry_from == pending.len() {
+                return Ok(outputs);
+            }
+            pending = pending.split_off(retry_from);
+            block_metadata = self.new_block_metadata();
+        }
     }
 
     pub fn execute_transaction(&self, txn: SignedTransaction) -> TransactionOutput {
@@ -231,6 +459,37 @@ impl FakeExecutor {
             .expect("A block with one transaction should have one output")
     }
 
+    /// Executes the transaction against the current state and returns its output together with a
+    /// breakdown of the gas charged per instruction category and native function.
+    ///
+    /// Like [`FakeExecutor::execute_transaction`], this doesn't apply the output to the data store.
+    pub fn execute_transaction_with_gas_profile(
+        &self,
+        txn: SignedTransaction,
+    ) -> (TransactionOutput, GasProfile) {
+        LibraVM::execute_user_transaction_with_gas_profile(&self.data_store, txn)
+    }
+
+    /// Simulates the transaction against the current state, optionally without checking its
+    /// signature, and returns its output. The output is not applied to the data store.
+    pub fn simulate_transaction(
+        &self,
+        txn: SignedTransaction,
+        check_signature: bool,
+    ) -> TransactionOutput {
+        LibraVM::simulate_user_transaction(&self.data_store, txn, check_signature, None)
+    }
+
+    /// Like [`FakeExecutor::simulate_transaction`], but fails the transaction once its code
+    /// exceeds `limits`.
+    pub fn simulate_transaction_with_limits(
+        &self,
+        txn: SignedTransaction,
+        limits: ExecutionLimits,
+    ) -> TransactionOutput {
+        LibraVM::simulate_user_transaction(&self.data_store, txn, true, Some(limits))
+    }
+
     /// Get the blob for the associated AccessPath
     pub fn read_from_access_path(&self, path: &AccessPath) -> Option<Vec<u8>> {
         StateView::get(&self.data_store, path).unwrap()
@@ -238,25 +497,54 @@ impl FakeExecutor {
 
     /// Verifies the given transaction by running it through the VM verifier.
     pub fn verify_transaction(&self, txn: SignedTransaction) -> VMValidatorResult {
-        let vm = LibraVMValidator::new(self.get_state_view());
-        vm.validate_transaction(txn, &self.data_store)
+        let vm =
+            LibraVMValidator::new(self.get_state_view()).with_validation_mode(self.validation_mode);
+        if !self.tracing {
+            return vm.validate_transaction(txn, &self.data_store);
+        }
+
+        let view = ReadRecordingView::new(&self.data_store);
+        let (result, calls) = move_vm_runtime::record_calls(|| vm.validate_transaction(txn, &view));
+        execution_trace::set_last_trace(ExecutionTrace {
+            calls,
+            reads: view.into_reads(),
+            writes: vec![],
+        });
+        result
     }
 
     pub fn get_state_view(&self) -> &FakeDataStore {
         &self.data_store
     }
 
-    pub fn new_block(&mut self) {
+    /// Returns the current epoch, as recorded in the on-chain `LibraConfig::Configuration` resource.
+    pub fn epoch(&self) -> u64 {
+        self.read_resource::<ConfigurationResource>(&config_address())
+            .expect("Configuration resource must exist in data store")
+            .epoch()
+    }
+
+    /// Returns the metadata for the block following the current one, proposed by the first
+    /// validator in the current validator set.
+    pub fn new_block_metadata(&self) -> BlockMetadata {
+        self.block_metadata_at(self.block_time + 1)
+    }
+
+    fn block_metadata_at(&self, timestamp_usecs: u64) -> BlockMetadata {
         let validator_set = ValidatorSet::fetch_config(&self.data_store)
             .expect("Unable to retrieve the validator set from storage");
-        self.block_time += 1;
-        let new_block = BlockMetadata::new(
+        BlockMetadata::new(
             HashValue::zero(),
             0,
-            self.block_time,
+            timestamp_usecs,
             vec![],
             *validator_set.payload()[0].account_address(),
-        );
+        )
+    }
+
+    pub fn new_block(&mut self) {
+        let new_block = self.new_block_metadata();
+        self.block_time = new_block.timestamp_usec();
         let output = self
             .execute_transaction_block(vec![Transaction::BlockMetadata(new_block)])
             .expect("Executing block prologue should succeed")
@@ -277,11 +565,41 @@ impl FakeExecutor {
         Identifier::new(name).unwrap()
     }
 
+    /// Sets the on-chain time, as seen by `LibraTimestamp::now_microseconds`, to `new_block_time`
+    /// microseconds.
+    ///
+    /// This overwrites the `LibraTimestamp::CurrentTimeMicroseconds` resource directly, so time can
+    /// also be moved backwards. No block is executed; use [`FakeExecutor::advance_time`] to move
+    /// time forward through the block prologue instead.
     pub fn set_block_time(&mut self, new_block_time: u64) {
         self.block_time = new_block_time;
+        let timestamp = LibraTimestampResource {
+            libra_timestamp: LibraTimestamp {
+                microseconds: new_block_time,
+            },
+        };
+        self.data_store.set(
+            AccessPath::new(
+                libra_root_address(),
+                LibraTimestampResource::resource_path(),
+            ),
+            lcs::to_bytes(&timestamp).expect("Failure encoding timestamp resource"),
+        );
+    }
+
+    /// Moves the on-chain time forward by `duration_usecs` microseconds by executing an empty
+    /// block with the new timestamp.
+    pub fn advance_time(&mut self, duration_usecs: u64) {
+        assert!(
+            duration_usecs > 0,
+            "time must strictly advance between blocks"
+        );
+        let block_metadata = self.block_metadata_at(self.block_time + duration_usecs);
+        self.execute_block_with_metadata(block_metadata, vec![])
+            .expect("Executing block prologue should succeed");
     }
 
-    pub fn get_block_time(&mut self) -> u64 {
+    pub fn get_block_time(&self) -> u64 {
         self.block_time
     }
 
@@ -293,7 +611,19 @@ impl FakeExecutor {
         args: Vec<Value>,
         sender: &AccountAddress,
     ) {
-        let write_set = {
+        self.exec_with_events(module_name, function_name, type_params, args, sender);
+    }
+
+    /// Like [`FakeExecutor::exec`], but also returns the events emitted by the function.
+    pub fn exec_with_events(
+        &mut self,
+        module_name: &str,
+        function_name: &str,
+        type_params: Vec<TypeTag>,
+        args: Vec<Value>,
+        sender: &AccountAddress,
+    ) -> Vec<ContractEvent> {
+        let (write_set, events) = {
             let cost_table = zero_cost_schedule();
             let mut cost_strategy = CostStrategy::system(&cost_table, GasUnits::new(100_000_000));
             let vm = MoveVM::new();
@@ -312,10 +642,9 @@ impl FakeExecutor {
                     panic!("Error calling {}.{}: {}", module_name, function_name, e)
                 });
             let effects = session.finish().expect("Failed to generate txn effects");
-            let (writeset, _events) =
-                txn_effects_to_writeset_and_events(effects).expect("Failed to generate writeset");
-            writeset
+            txn_effects_to_writeset_and_events(effects).expect("Failed to generate writeset")
         };
         self.data_store.add_write_set(&write_set);
+        events
     }
 }
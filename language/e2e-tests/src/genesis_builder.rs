@@ -0,0 +1,137 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Construction of custom genesis states for tests.
+//!
+//! ```ignore
+//! let executor = GenesisBuilder::new()
+//!     .validator_count(4)
+//!     .publishing_options(VMPublishingOption::open())
+//!     .instruction_cost(&Bytecode::Add, GasCost::new(10, 1))
+//!     .build();
+//! ```
+
+use crate::executor::FakeExecutor;
+use compiled_stdlib::{stdlib_modules, StdLibOptions};
+use libra_config::generator;
+use libra_types::{on_chain_config::VMPublishingOption, transaction::ChangeSet};
+use move_core_types::gas_schedule::GasCost;
+use move_vm_types::gas_schedule::NativeCostIndex;
+use vm::{file_format::Bytecode, file_format_common::instruction_key, CompiledModule};
+use vm_genesis::GENESIS_KEYPAIR;
+
+/// The number of validators in the genesis built by default.
+pub const DEFAULT_VALIDATOR_COUNT: usize = 10;
+
+/// Builds the genesis [`ChangeSet`] a [`FakeExecutor`] starts from.
+///
+/// By default this is the same genesis as [`FakeExecutor::from_genesis_file`]: the compiled
+/// stdlib, [`DEFAULT_VALIDATOR_COUNT`] validators, open publishing and the initial gas schedule.
+#[derive(Clone, Debug)]
+pub struct GenesisBuilder {
+    validator_count: usize,
+    stdlib_modules: Vec<CompiledModule>,
+    extra_modules: Vec<CompiledModule>,
+    publishing_options: VMPublishingOption,
+    instruction_table: Vec<GasCost>,
+    native_table: Vec<GasCost>,
+}
+
+impl GenesisBuilder {
+    pub fn new() -> Self {
+        let (instruction_table, native_table) = vm_genesis::initial_cost_tables();
+        Self {
+            validator_count: DEFAULT_VALIDATOR_COUNT,
+            stdlib_modules: stdlib_modules(StdLibOptions::Compiled).to_vec(),
+            extra_modules: vec![],
+            publishing_options: VMPublishingOption::open(),
+            instruction_table,
+            native_table,
+        }
+    }
+
+    /// Sets the number of validators in the genesis validator set.
+    pub fn validator_count(mut self, validator_count: usize) -> Self {
+        self.validator_count = validator_count;
+        self
+    }
+
+    /// Replaces the stdlib modules genesis is built from. Later modules may depend on earlier
+    /// ones.
+    pub fn stdlib_modules(mut self, modules: Vec<CompiledModule>) -> Self {
+        self.stdlib_modules = modules;
+        self
+    }
+
+    /// Publishes `module` at genesis, after the stdlib and any modules added before it.
+    pub fn add_module(mut self, module: CompiledModule) -> Self {
+        self.extra_modules.push(module);
+        self
+    }
+
+    /// Sets the script and module publishing options.
+    pub fn publishing_options(mut self, publishing_options: VMPublishingOption) -> Self {
+        self.publishing_options = publishing_options;
+        self
+    }
+
+    /// Replaces the whole gas schedule. Both tables must be ordered the way the VM expects them,
+    /// as in [`vm_genesis::initial_cost_tables`].
+    pub fn gas_schedule(
+        mut self,
+        instruction_table: Vec<GasCost>,
+        native_table: Vec<GasCost>,
+    ) -> Self {
+        self.instruction_table = instruction_table;
+        self.native_table = native_table;
+        self
+    }
+
+    /// Sets the cost of a single instruction. Only the opcode of `instr` matters, not its
+    /// operands.
+    pub fn instruction_cost(mut self, instr: &Bytecode, cost: GasCost) -> Self {
+        // Instruction keys start at 1.
+        let index = instruction_key(instr) as usize - 1;
+        self.instruction_table[index] = cost;
+        self
+    }
+
+    /// Sets the cost of a single native function.
+    pub fn native_cost(mut self, native: NativeCostIndex, cost: GasCost) -> Self {
+        self.native_table[native as usize] = cost;
+        self
+    }
+
+    /// Encodes the genesis change set.
+    pub fn build_change_set(&self) -> ChangeSet {
+        let swarm = generator::validator_swarm_for_testing(self.validator_count);
+        let modules: Vec<_> = self
+            .stdlib_modules
+            .iter()
+            .chain(self.extra_modules.iter())
+            .cloned()
+            .collect();
+
+        vm_genesis::encode_genesis_change_set_with_gas_schedule(
+            &GENESIS_KEYPAIR.1,
+            &vm_genesis::operator_assignments(&swarm.nodes),
+            &vm_genesis::operator_registrations(&swarm.nodes),
+            &modules,
+            self.publishing_options.clone(),
+            &self.instruction_table,
+            &self.native_table,
+        )
+        .0
+    }
+
+    /// Creates an executor starting from this genesis.
+    pub fn build(&self) -> FakeExecutor {
+        FakeExecutor::from_genesis(self.build_change_set().write_set())
+    }
+}
+
+impl Default for GenesisBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
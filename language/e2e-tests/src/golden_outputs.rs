@@ -0,0 +1,196 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Golden-file recording of transaction outputs.
+//!
+//! A test opts in with [`FakeExecutor::set_golden_file`][crate::executor::FakeExecutor::set_golden_file].
+//! Every output the executor produces from then on is rendered as text -- its status, gas used,
+//! events and a digest of its write set -- and when the executor is dropped the rendering is
+//! compared against `goldens/<name>.exp` in this crate. Any difference fails the test, which
+//! catches unintended changes in behavior of the VM or the stdlib.
+//!
+//! Golden files are committed along with the tests. A missing golden file fails the test too; to
+//! create it, or to accept the new outputs after an intended change, rerun the test with
+//! `REGENERATE_GOLDENFILES=1`.
+
+use anyhow::{bail, Result};
+use libra_crypto::HashValue;
+use libra_types::{
+    transaction::{TransactionOutput, TransactionStatus},
+    vm_status::VMStatus,
+};
+use std::{
+    fmt::Write,
+    fs,
+    path::{Path, PathBuf},
+    sync::Mutex,
+    thread,
+};
+
+/// The version of the golden file format, recorded on the first line of every golden file. Bump
+/// it whenever the rendering of outputs changes.
+pub const GOLDEN_FORMAT_VERSION: u32 = 1;
+
+/// The environment variable that, when set to `1`, makes golden files be (re)generated instead of
+/// checked.
+pub const REGENERATE_GOLDEN_ENV_VAR: &str = "REGENERATE_GOLDENFILES";
+
+const GOLDEN_DIR: &str = "goldens";
+const GOLDEN_EXTENSION: &str = "exp";
+
+/// Transaction outputs recorded for comparison against a golden file.
+#[derive(Debug)]
+pub struct GoldenOutputs {
+    path: PathBuf,
+    entries: Mutex<Vec<String>>,
+}
+
+impl GoldenOutputs {
+    /// Creates an empty recording that is checked against the golden file `name`.
+    pub fn new(name: &str) -> Self {
+        let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join(GOLDEN_DIR)
+            .join(name)
+            .with_extension(GOLDEN_EXTENSION);
+        Self {
+            path,
+            entries: Mutex::new(vec![]),
+        }
+    }
+
+    /// Returns the path of the golden file this recording is checked against.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Records the outputs of one block.
+    pub fn record(&self, outputs: &[TransactionOutput]) {
+        let mut entries = self.entries.lock().expect("golden outputs lock poisoned");
+        let block = entries.len();
+        let mut entry = format!("block {}\n", block);
+        for (index, output) in outputs.iter().enumerate() {
+            render_output(&mut entry, index, output);
+        }
+        entries.push(entry);
+    }
+
+    /// Returns the contents of a golden file holding the outputs recorded so far.
+    pub fn render(&self) -> String {
+        let entries = self.entries.lock().expect("golden outputs lock poisoned");
+        let mut contents = format!("golden outputs v{}\n", GOLDEN_FORMAT_VERSION);
+        for entry in entries.iter() {
+            contents.push('\n');
+            contents.push_str(entry);
+        }
+        contents
+    }
+
+    /// Checks the recorded outputs against the golden file, or writes the golden file if
+    /// regenerating was requested.
+    pub fn verify(&self) -> Result<()> {
+        let actual = self.render();
+        let regenerate =
+            std::env::var(REGENERATE_GOLDEN_ENV_VAR).map_or(false, |value| value == "1");
+        if regenerate {
+            if let Some(dir) = self.path.parent() {
+                fs::create_dir_all(dir)?;
+            }
+            fs::write(&self.path, actual)?;
+            return Ok(());
+        }
+        if !self.path.exists() {
+            bail!(
+                "golden file {} does not exist; run with {}=1 to generate it",
+                self.path.display(),
+                REGENERATE_GOLDEN_ENV_VAR,
+            );
+        }
+
+        let expected = fs::read_to_string(&self.path)?;
+        if expected == actual {
+            return Ok(());
+        }
+        let expected_header = expected.lines().next().unwrap_or_default();
+        let actual_header = actual.lines().next().unwrap_or_default();
+        if expected_header != actual_header {
+            bail!(
+                "golden file {} has format `{}` but `{}` was expected; rerun with {}=1 to \
+                 regenerate it",
+                self.path.display(),
+                expected_header,
+                actual_header,
+                REGENERATE_GOLDEN_ENV_VAR,
+            );
+        }
+        let (line, expected_line, actual_line) = expected
+            .lines()
+            .chain(std::iter::repeat(""))
+            .zip(actual.lines().chain(std::iter::repeat("")))
+            .enumerate()
+            .find(|(_, (expected, actual))| expected != actual)
+            .map(|(line, (expected, actual))| (line + 1, expected, actual))
+            .expect("contents differ, so some line must differ");
+        bail!(
+            "outputs differ from golden file {} at line {}:\n  expected: {}\n    actual: {}\n\
+             rerun with {}=1 to accept the new outputs",
+            self.path.display(),
+            line,
+            expected_line,
+            actual_line,
+            REGENERATE_GOLDEN_ENV_VAR,
+        )
+    }
+}
+
+impl Drop for GoldenOutputs {
+    fn drop(&mut self) {
+        // Don't turn a failing test into a double panic.
+        if thread::panicking() {
+            return;
+        }
+        if let Err(err) = self.verify() {
+            panic!("{}", err);
+        }
+    }
+}
+
+fn render_output(entry: &mut String, index: usize, output: &TransactionOutput) {
+    // Writing to a `String` cannot fail.
+    writeln!(entry, "  txn {}", index).unwrap();
+    writeln!(entry, "    status: {}", render_status(output.status())).unwrap();
+    writeln!(entry, "    gas used: {}", output.gas_used()).unwrap();
+    let write_set_bytes = lcs::to_bytes(output.write_set()).expect("write set must serialize");
+    writeln!(
+        entry,
+        "    write set: {:x}",
+        HashValue::sha3_256_of(&write_set_bytes)
+    )
+    .unwrap();
+    for event in output.events() {
+        writeln!(
+            entry,
+            "    event {:x} #{} {}: {}",
+            event.key(),
+            event.sequence_number(),
+            event.type_tag(),
+            hex::encode(event.event_data())
+        )
+        .unwrap();
+    }
+}
+
+fn render_status(status: &TransactionStatus) -> String {
+    match status {
+        TransactionStatus::Keep(status) => format!("Keep({})", render_vm_status(status)),
+        TransactionStatus::Discard(status) => format!("Discard({})", render_vm_status(status)),
+        TransactionStatus::Retry => "Retry".to_string(),
+    }
+}
+
+/// Renders the parts of a `VMStatus` tests compare on; see [`assert_status_eq`][crate::assert_status_eq].
+fn render_vm_status(status: &VMStatus) -> String {
+    match status.move_abort_code() {
+        Some(code) => format!("{:?} {}", status.status_code(), code),
+        None => format!("{:?}", status.status_code()),
+    }
+}
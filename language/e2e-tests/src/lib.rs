@@ -14,14 +14,24 @@ mod tests;
 
 pub mod account;
 pub mod account_universe;
+pub mod benchmark;
 pub mod common_transactions;
 pub mod compile;
+pub mod compliance;
+pub mod cross_version;
 pub mod data_store;
+pub mod event_assert;
 pub mod execution_strategies;
+pub mod execution_trace;
 pub mod executor;
 pub mod gas_costs;
+pub mod genesis_builder;
+pub mod golden_outputs;
 pub mod keygen;
+pub mod on_chain_configs;
+pub mod parallel_executor;
 mod proptest_types;
+pub mod txn_generator;
 
 pub fn assert_status_eq(s1: &VMStatus, s2: &VMStatus) -> bool {
     // TODO(tmn) After providing real abort locations, use normal equality
@@ -40,10 +50,11 @@ pub fn transaction_status_eq(t1: &TransactionStatus, t2: &TransactionStatus) ->
 
 #[macro_export]
 macro_rules! assert_prologue_parity {
-    ($e1:expr, $e2:expr, $e3:expr) => {
+    ($e1:expr, $e2:expr, $e3:expr) => {{
+        let _guard = $crate::execution_trace::PrintTraceOnPanic;
         assert_status_eq(&$e1.unwrap(), &$e3);
         assert!(transaction_status_eq($e2, &TransactionStatus::Discard($e3)));
-    };
+    }};
 }
 
 #[macro_export]
@@ -0,0 +1,158 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Helpers for reading and changing on-chain configs in tests.
+//!
+//! Changing a config through its update script needs the right signer, sequence number and, for
+//! some configs, a reconfiguration the script triggers itself. The setters here write the
+//! `LibraConfig::LibraConfig<T>` resources directly instead. Like any direct write, they do not
+//! emit a new epoch event; call [`FakeExecutor::reconfigure`] afterwards to start a new epoch
+//! with the changed configs, as state sync would observe it:
+//!
+//! ```ignore
+//! executor.set_libra_version(LibraVersion { major: 2 });
+//! let event = executor.reconfigure();
+//! assert_eq!(event.epoch(), executor.epoch());
+//! ```
+
+use crate::executor::FakeExecutor;
+use libra_types::{
+    account_config::{libra_root_address, NewEpochEvent},
+    contract_event::ContractEvent,
+    on_chain_config::{
+        config_address, new_epoch_event_key, ConfigurationResource, LibraVersion, OnChainConfig,
+        RegisteredCurrencies, VMConfig, VMPublishingOption, ValidatorSet,
+    },
+    write_set::{WriteOp, WriteSetMut},
+};
+use move_core_types::gas_schedule::GasConstants;
+use move_vm_types::values::Value;
+use serde::{Deserialize, Serialize};
+use std::convert::TryFrom;
+
+/// The on-chain layout of `LibraVMConfig`, where the publishing option and the cost tables are
+/// stored as LCS blobs.
+#[derive(Deserialize, Serialize)]
+struct OnChainVMConfig {
+    publishing_option: Vec<u8>,
+    gas_schedule: OnChainCostTable,
+}
+
+#[derive(Deserialize, Serialize)]
+struct OnChainCostTable {
+    instruction_table: Vec<u8>,
+    native_table: Vec<u8>,
+    gas_constants: GasConstants,
+}
+
+/// Returns the new epoch events among `events`, in order.
+pub fn new_epoch_events(events: &[ContractEvent]) -> Vec<NewEpochEvent> {
+    events
+        .iter()
+        .filter(|event| event.key() == &new_epoch_event_key())
+        .map(|event| NewEpochEvent::try_from(event).expect("new epoch event must deserialize"))
+        .collect()
+}
+
+impl FakeExecutor {
+    /// Returns the current value of the on-chain config `T`, if it is published.
+    pub fn read_config<T: OnChainConfig>(&self) -> Option<T> {
+        T::fetch_config(self.get_state_view())
+    }
+
+    pub fn libra_version(&self) -> LibraVersion {
+        self.read_config()
+            .expect("LibraVersion config must be published")
+    }
+
+    pub fn set_libra_version(&mut self, version: LibraVersion) {
+        self.write_config(&version);
+    }
+
+    pub fn publishing_option(&self) -> VMPublishingOption {
+        self.read_config::<VMConfig>()
+            .expect("LibraVMConfig config must be published")
+            .publishing_option
+    }
+
+    /// Replaces the publishing option of the VM config, keeping its gas schedule.
+    pub fn set_publishing_option(&mut self, publishing_option: VMPublishingOption) {
+        let access_path = VMConfig::CONFIG_ID.access_path();
+        let blob = self
+            .read_from_access_path(&access_path)
+            .expect("LibraVMConfig config must be published");
+        let mut vm_config: OnChainVMConfig =
+            lcs::from_bytes(&blob).expect("LibraVMConfig config must deserialize");
+        vm_config.publishing_option =
+            lcs::to_bytes(&publishing_option).expect("publishing option must serialize");
+        self.write_config_blob::<VMConfig>(
+            lcs::to_bytes(&vm_config).expect("LibraVMConfig config must serialize"),
+        );
+    }
+
+    pub fn registered_currencies(&self) -> RegisteredCurrencies {
+        self.read_config()
+            .expect("RegisteredCurrencies config must be published")
+    }
+
+    /// Replaces the list of registered currency codes. This does not publish or remove any
+    /// `CurrencyInfo` resources.
+    pub fn set_registered_currencies(&mut self, currencies: RegisteredCurrencies) {
+        self.write_config(&currencies);
+    }
+
+    pub fn validator_set(&self) -> ValidatorSet {
+        self.read_config()
+            .expect("LibraSystem config must be published")
+    }
+
+    /// Replaces the validator set. The new set must not be empty, since blocks are proposed by
+    /// its first validator.
+    pub fn set_validator_set(&mut self, validator_set: ValidatorSet) {
+        assert!(
+            !validator_set.payload().is_empty(),
+            "the validator set must not be empty"
+        );
+        self.write_config(&validator_set);
+    }
+
+    /// Returns the on-chain time of the last reconfiguration, in microseconds.
+    pub fn last_reconfiguration_time(&self) -> u64 {
+        self.read_resource::<ConfigurationResource>(&config_address())
+            .expect("Configuration resource must exist in data store")
+            .last_reconfiguration_time()
+    }
+
+    /// Starts a new epoch by calling `LibraConfig::reconfigure` as the Libra root account, and
+    /// returns the new epoch event it emitted.
+    ///
+    /// At most one reconfiguration can happen at a given on-chain time, so a new block is
+    /// executed first to move the clock forward.
+    pub fn reconfigure(&mut self) -> NewEpochEvent {
+        self.new_block();
+        let events = self.exec_with_events(
+            "LibraConfig",
+            "reconfigure",
+            vec![],
+            vec![Value::transaction_argument_signer_reference(
+                libra_root_address(),
+            )],
+            &libra_root_address(),
+        );
+        new_epoch_events(&events)
+            .pop()
+            .expect("reconfiguration must emit a new epoch event")
+    }
+
+    fn write_config<T: OnChainConfig + Serialize>(&mut self, config: &T) {
+        // `LibraConfig<T>` has the config as its only field, so it serializes the same way.
+        self.write_config_blob::<T>(lcs::to_bytes(config).expect("config must serialize"));
+    }
+
+    fn write_config_blob<T: OnChainConfig>(&mut self, blob: Vec<u8>) {
+        let write_set = WriteSetMut::new(vec![(T::CONFIG_ID.access_path(), WriteOp::Value(blob))])
+            .freeze()
+            .expect("write set must be valid");
+        self.apply_write_set(&write_set);
+    }
+}
@@ -0,0 +1,126 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Optimistic parallel execution of a block of transactions.
+//!
+//! The VM has no concurrent execution path of its own yet, so this module provides one for
+//! testing: every pending transaction of the block is executed concurrently against the state
+//! committed so far, recording the access paths it reads. The speculative outputs are then
+//! committed in block order until one is found that read a location written by a transaction
+//! committed earlier in the same round; the rest of the block is re-executed in the next round.
+//! The result must match sequential execution exactly.
+
+use crate::data_store::{FakeDataStore, ReadRecordingView};
+use libra_types::{
+    access_path::AccessPath,
+    on_chain_config::new_epoch_event_key,
+    transaction::{SignedTransaction, Transaction, TransactionOutput, TransactionStatus},
+    vm_status::VMStatus,
+    write_set::WriteSet,
+};
+use libra_vm::{LibraVM, VMExecutor};
+use rayon::prelude::*;
+use std::{collections::BTreeSet, fmt};
+
+/// Error returned by [`FakeExecutor::execute_block_parallel`][crate::executor::FakeExecutor::execute_block_parallel].
+#[derive(Debug)]
+pub enum ParallelExecutionError {
+    /// The block failed to execute sequentially or in parallel.
+    VMStatus(VMStatus),
+    /// The output at `index` differs between sequential and parallel execution.
+    Divergence {
+        index: usize,
+        sequential: TransactionOutput,
+        parallel: TransactionOutput,
+    },
+}
+
+impl fmt::Display for ParallelExecutionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParallelExecutionError::VMStatus(status) => write!(f, "{:?}", status),
+            ParallelExecutionError::Divergence {
+                index,
+                sequential,
+                parallel,
+            } => write!(
+                f,
+                "transaction {} diverged: sequential {:?} != parallel {:?}",
+                index, sequential, parallel
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ParallelExecutionError {}
+
+/// Executes `txn_block` on top of `state` using optimistic parallel execution. `state` is left
+/// untouched.
+pub fn execute_block(
+    state: &FakeDataStore,
+    txn_block: &[SignedTransaction],
+) -> Result<Vec<TransactionOutput>, VMStatus> {
+    let mut state = state.clone();
+    let mut outputs = Vec::with_capacity(txn_block.len());
+
+    while outputs.len() < txn_block.len() {
+        let pending = &txn_block[outputs.len()..];
+        let speculative: Vec<_> = pending
+            .par_iter()
+            .map(|txn| execute_speculatively(&state, txn.clone()))
+            .collect();
+
+        let mut written = BTreeSet::new();
+        for (position, result) in speculative.into_iter().enumerate() {
+            let (output, reads) = match result {
+                Ok(result) => result,
+                // The first transaction of a round saw exactly the committed state.
+                Err(status) if position == 0 => return Err(status),
+                Err(_) => break,
+            };
+            if !reads.is_disjoint(&written) {
+                break;
+            }
+
+            if let TransactionStatus::Keep(_) = output.status() {
+                written.extend(output.write_set().iter().map(|(path, _)| path.clone()));
+                state.add_write_set(output.write_set());
+            }
+            let new_epoch_event_key = new_epoch_event_key();
+            let reconfigured = output
+                .events()
+                .iter()
+                .any(|event| *event.key() == new_epoch_event_key);
+            outputs.push(output);
+
+            // Like the VM, leave the rest of the block to be retried in the new epoch.
+            if reconfigured {
+                outputs.resize(
+                    txn_block.len(),
+                    TransactionOutput::new(
+                        WriteSet::default(),
+                        vec![],
+                        0,
+                        TransactionStatus::Retry,
+                    ),
+                );
+                break;
+            }
+        }
+    }
+    Ok(outputs)
+}
+
+/// Executes `txn` as a singleton block against `state`, returning its output along with the
+/// access paths it read.
+fn execute_speculatively(
+    state: &FakeDataStore,
+    txn: SignedTransaction,
+) -> Result<(TransactionOutput, BTreeSet<AccessPath>), VMStatus> {
+    let view = ReadRecordingView::new(state);
+    let mut outputs = LibraVM::execute_block(vec![Transaction::UserTransaction(txn)], &view)?;
+    let output = outputs
+        .pop()
+        .expect("a singleton block must have one output");
+    Ok((output, view.into_reads().into_iter().collect()))
+}
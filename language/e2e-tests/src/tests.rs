@@ -9,18 +9,33 @@
 //! separate binary. The linker ends up repeating a lot of work for each binary to not much
 //! benefit.
 
+mod account_roles;
 mod account_universe;
+mod benchmark;
+mod block_time;
+mod compliance;
 mod create_account;
+mod cross_version;
 mod data_store;
+mod events;
 mod execution_strategies;
+mod execution_trace;
 mod failed_transaction_tests;
+mod gas_profile;
 mod genesis;
 mod mint;
 mod module_publishing;
+mod multi_agent;
+mod multi_currency;
 mod on_chain_configs;
+mod parallel_execution;
 mod peer_to_peer;
+mod reconfiguration;
 mod rotate_key;
+mod script_functions;
 mod scripts;
+mod simulation;
+mod snapshot;
 mod transaction_builder;
 mod transaction_fees;
 mod validator_set_management;
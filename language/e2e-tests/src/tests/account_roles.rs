@@ -0,0 +1,156 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    account::{self, Account, AccountData, AccountRoleSpecifier},
+    common_transactions::peer_to_peer_txn,
+    executor::FakeExecutor,
+};
+use libra_types::{
+    account_config::{self, ChildVASP},
+    validator_config::ValidatorConfigResource,
+};
+use transaction_builder::*;
+
+#[test]
+fn peer_to_peer_between_parent_vasps() {
+    let mut executor = FakeExecutor::from_genesis_file();
+    let sender = AccountData::builder()
+        .role(AccountRoleSpecifier::ParentVASP)
+        .balance(account::lbr_currency_code(), 1_000_000)
+        .sequence_number(10)
+        .build();
+    let receiver = AccountData::builder()
+        .role(AccountRoleSpecifier::ParentVASP)
+        .balance(account::lbr_currency_code(), 100_000)
+        .build();
+    executor.add_account_data(&sender);
+    executor.add_account_data(&receiver);
+
+    executor.execute_and_apply(peer_to_peer_txn(
+        sender.account(),
+        receiver.account(),
+        10,
+        1_000,
+    ));
+    let receiver_balance = executor
+        .read_balance_resource(receiver.account(), account::lbr_currency_code())
+        .expect("receiver balance must exist");
+    assert_eq!(101_000, receiver_balance.coin());
+}
+
+#[test]
+fn parent_vasp_creates_child() {
+    let mut executor = FakeExecutor::from_genesis_file();
+    let parent = AccountData::builder()
+        .role(AccountRoleSpecifier::ParentVASP)
+        .balance(account::coin1_currency_code(), 1_000_000)
+        .build();
+    executor.add_account_data(&parent);
+
+    let child = Account::new();
+    executor.execute_and_apply(parent.account().signed_script_txn(
+        encode_create_child_vasp_account_script(
+            account_config::coin1_tag(),
+            *child.address(),
+            child.auth_key_prefix(),
+            false,
+            100,
+        ),
+        0,
+    ));
+    let child_balance = executor
+        .read_balance_resource(&child, account::coin1_currency_code())
+        .expect("child balance must exist");
+    assert_eq!(100, child_balance.coin());
+}
+
+#[test]
+fn child_vasp_sends_to_sibling() {
+    let mut executor = FakeExecutor::from_genesis_file();
+    let parent = AccountData::builder()
+        .role(AccountRoleSpecifier::ParentVASP)
+        .balance(account::lbr_currency_code(), 0)
+        .build();
+    let sender = AccountData::builder()
+        .role(AccountRoleSpecifier::ChildVASP)
+        .parent_vasp(*parent.address())
+        .balance(account::lbr_currency_code(), 10_000)
+        .build();
+    let receiver = AccountData::builder()
+        .role(AccountRoleSpecifier::ChildVASP)
+        .parent_vasp(*parent.address())
+        .build();
+    executor.add_account_data(&parent);
+    executor.add_account_data(&sender);
+    executor.add_account_data(&receiver);
+
+    let child_vasp = executor
+        .read_resource::<ChildVASP>(receiver.address())
+        .expect("child VASP resource must exist");
+    assert_eq!(*parent.address(), child_vasp.parent_vasp_addr());
+
+    executor.execute_and_apply(peer_to_peer_txn(
+        sender.account(),
+        receiver.account(),
+        0,
+        1_000,
+    ));
+}
+
+#[test]
+fn tiered_mint_to_built_designated_dealer() {
+    let mut executor = FakeExecutor::from_genesis_file();
+    let dd = AccountData::builder()
+        .role(AccountRoleSpecifier::DesignatedDealer)
+        .balance(account::coin1_currency_code(), 0)
+        .build();
+    executor.add_account_data(&dd);
+
+    executor.execute_and_apply(Account::new_blessed_tc().signed_script_txn(
+        encode_tiered_mint_script(account_config::coin1_tag(), 0, *dd.address(), 1_000, 0),
+        0,
+    ));
+    let dd_balance = executor
+        .read_balance_resource(dd.account(), account::coin1_currency_code())
+        .expect("dd balance must exist");
+    assert_eq!(1_000, dd_balance.coin());
+}
+
+#[test]
+fn built_validator_sets_built_operator() {
+    let mut executor = FakeExecutor::from_genesis_file();
+    let validator = AccountData::builder()
+        .role(AccountRoleSpecifier::Validator)
+        .build();
+    let operator = AccountData::builder()
+        .role(AccountRoleSpecifier::ValidatorOperator)
+        .build();
+    executor.add_account_data(&validator);
+    executor.add_account_data(&operator);
+
+    let config = executor
+        .read_resource::<ValidatorConfigResource>(validator.address())
+        .expect("validator config must exist");
+    assert!(config.validator_config.is_none());
+    assert!(config.delegated_account.is_none());
+
+    executor.execute_and_apply(
+        validator
+            .account()
+            .signed_script_txn(encode_set_validator_operator_script(*operator.address()), 0),
+    );
+    let config = executor
+        .read_resource::<ValidatorConfigResource>(validator.address())
+        .expect("validator config must exist");
+    assert_eq!(Some(*operator.address()), config.delegated_account);
+}
+
+#[test]
+#[should_panic]
+fn admin_roles_cannot_hold_balances() {
+    AccountData::builder()
+        .role(AccountRoleSpecifier::TreasuryCompliance)
+        .balance(account::lbr_currency_code(), 1)
+        .build();
+}
@@ -0,0 +1,26 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::benchmark::{run, run_all, ScriptWorkload};
+
+#[test]
+fn workloads_execute_successfully() {
+    for workload in ScriptWorkload::all().iter() {
+        // `run` applies every transaction with `execute_and_apply`, which checks that it executed.
+        let result = run(*workload, 3);
+        assert_eq!(result.latencies.len(), 3);
+        assert!(result.tps() > 0.0);
+    }
+}
+
+#[test]
+fn results_are_machine_readable() {
+    let results = run_all(2);
+    let results = results.as_array().expect("results must be an array");
+    assert_eq!(results.len(), ScriptWorkload::all().len());
+    for (result, workload) in results.iter().zip(ScriptWorkload::all().iter()) {
+        assert_eq!(result["workload"], workload.name());
+        assert_eq!(result["num_transactions"], 2);
+        assert!(result["latency_us"]["p50"].is_u64());
+    }
+}
@@ -0,0 +1,79 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{account::AccountData, executor::FakeExecutor, transaction_status_eq};
+use libra_types::{
+    account_config::{lbr_type_tag, libra_root_address},
+    libra_timestamp::LibraTimestampResource,
+    transaction::TransactionStatus,
+    vm_status::{StatusCode, VMStatus},
+};
+use transaction_builder::encode_peer_to_peer_with_metadata_script;
+
+fn on_chain_time(executor: &FakeExecutor) -> u64 {
+    executor
+        .read_resource::<LibraTimestampResource>(&libra_root_address())
+        .expect("timestamp resource must exist")
+        .libra_timestamp
+        .microseconds
+}
+
+#[test]
+fn advance_time_runs_block_prologue() {
+    let mut executor = FakeExecutor::from_genesis_file();
+    let start = executor.get_block_time();
+
+    executor.advance_time(5_000_000);
+    assert_eq!(executor.get_block_time(), start + 5_000_000);
+    assert_eq!(on_chain_time(&executor), start + 5_000_000);
+
+    executor.new_block();
+    assert_eq!(on_chain_time(&executor), start + 5_000_001);
+}
+
+#[test]
+fn set_block_time_can_rewind() {
+    let mut executor = FakeExecutor::from_genesis_file();
+    executor.advance_time(10_000_000);
+
+    executor.set_block_time(1_000);
+    assert_eq!(executor.get_block_time(), 1_000);
+    assert_eq!(on_chain_time(&executor), 1_000);
+}
+
+#[test]
+fn transaction_expires_after_advancing_time() {
+    let mut executor = FakeExecutor::from_genesis_file();
+    let sender = AccountData::new(1_000_000, 10);
+    let receiver = AccountData::new(100_000, 10);
+    executor.add_account_data(&sender);
+    executor.add_account_data(&receiver);
+
+    let ttl_secs = 10;
+    let txn = sender
+        .account()
+        .transaction()
+        .script(encode_peer_to_peer_with_metadata_script(
+            lbr_type_tag(),
+            *receiver.address(),
+            1_000,
+            vec![],
+            vec![],
+        ))
+        .sequence_number(10)
+        .ttl(ttl_secs)
+        .sign();
+
+    let output = executor.execute_transaction(txn.clone());
+    assert!(transaction_status_eq(
+        output.status(),
+        &TransactionStatus::Keep(VMStatus::Executed)
+    ));
+
+    executor.advance_time(ttl_secs * 1_000_000 + 1);
+    let output = executor.execute_transaction(txn);
+    assert!(transaction_status_eq(
+        output.status(),
+        &TransactionStatus::Discard(VMStatus::Error(StatusCode::TRANSACTION_EXPIRED))
+    ));
+}
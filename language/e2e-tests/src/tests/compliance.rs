@@ -0,0 +1,140 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    account::{self, AccountData, AccountRoleSpecifier},
+    compliance::LimitsDefinition,
+    executor::FakeExecutor,
+    keygen::KeyGen,
+};
+use libra_types::{
+    account_config::{self, Credential},
+    transaction::{SignedTransaction, TransactionOutput},
+    vm_status::StatusCode,
+};
+use transaction_builder::{dual_attestation, encode_peer_to_peer_with_metadata_script};
+
+const EDEPOSIT_EXCEEDS_LIMITS: u64 = 3;
+const EMALFORMED_METADATA_SIGNATURE: u64 = 6;
+
+fn parent_vasp(coin1_balance: u64) -> AccountData {
+    AccountData::builder()
+        .role(AccountRoleSpecifier::ParentVASP)
+        .balance(account::lbr_currency_code(), 0)
+        .balance(account::coin1_currency_code(), coin1_balance)
+        .build()
+}
+
+fn coin1_payment(
+    sender: &AccountData,
+    receiver: &AccountData,
+    amount: u64,
+    metadata: Vec<u8>,
+    metadata_signature: Vec<u8>,
+    sequence_number: u64,
+) -> SignedTransaction {
+    sender.account().signed_script_txn(
+        encode_peer_to_peer_with_metadata_script(
+            account_config::coin1_tag(),
+            *receiver.address(),
+            amount,
+            metadata,
+            metadata_signature,
+        ),
+        sequence_number,
+    )
+}
+
+fn assert_aborted(output: &TransactionOutput, abort_code: u64) {
+    assert_eq!(
+        output.status().vm_status().status_code(),
+        StatusCode::ABORTED
+    );
+    assert_eq!(
+        output.status().vm_status().move_abort_code(),
+        Some(abort_code)
+    );
+}
+
+#[test]
+fn inflow_limit_resets_with_window() {
+    let mut executor = FakeExecutor::from_genesis_file();
+    let coin1 = account::coin1_currency_code();
+    let sender = parent_vasp(1_000);
+    let receiver = parent_vasp(0);
+    executor.add_account_data(&sender);
+    executor.add_account_data(&receiver);
+
+    executor.limit_account(
+        receiver.address(),
+        &coin1,
+        LimitsDefinition {
+            max_inflow: 100,
+            time_period: 1_000_000,
+            ..LimitsDefinition::unrestricted()
+        },
+    );
+
+    executor.execute_and_apply(coin1_payment(&sender, &receiver, 100, vec![], vec![], 0));
+    let window = executor
+        .read_limits_window(receiver.address(), &coin1)
+        .expect("receiver must hold a window");
+    assert_eq!(window.window_inflow, 100);
+    assert_eq!(window.tracked_balance, 100);
+
+    let output =
+        executor.execute_transaction(coin1_payment(&sender, &receiver, 1, vec![], vec![], 1));
+    assert_aborted(&output, EDEPOSIT_EXCEEDS_LIMITS);
+
+    executor.advance_past_limits_window(receiver.address(), &coin1);
+    executor.execute_and_apply(coin1_payment(&sender, &receiver, 1, vec![], vec![], 1));
+    let window = executor
+        .read_limits_window(receiver.address(), &coin1)
+        .expect("receiver must hold a window");
+    assert_eq!(window.window_inflow, 1);
+    assert_eq!(window.tracked_balance, 101);
+}
+
+#[test]
+fn payments_above_dual_attestation_limit_need_signature() {
+    let mut executor = FakeExecutor::from_genesis_file();
+    let mut keygen = KeyGen::from_seed([9u8; 32]);
+    let (receiver_compliance_key, receiver_compliance_public_key) = keygen.generate_keypair();
+    let sender = parent_vasp(1_000_000);
+    let receiver = AccountData::builder()
+        .role(AccountRoleSpecifier::ParentVASP)
+        .balance(account::lbr_currency_code(), 0)
+        .balance(account::coin1_currency_code(), 0)
+        .compliance_public_key(&receiver_compliance_public_key)
+        .build();
+    executor.add_account_data(&sender);
+    executor.add_account_data(&receiver);
+    executor.set_dual_attestation_limit(1_000);
+    assert_eq!(executor.dual_attestation_limit(), 1_000);
+
+    // Small payments don't need dual attestation.
+    executor.execute_and_apply(coin1_payment(&sender, &receiver, 10, vec![], vec![], 0));
+
+    let amount = 100_000;
+    let output =
+        executor.execute_transaction(coin1_payment(&sender, &receiver, amount, vec![], vec![], 1));
+    assert_aborted(&output, EMALFORMED_METADATA_SIGNATURE);
+
+    let ref_id = lcs::to_bytes(&7777u64).unwrap();
+    let signature = dual_attestation::sign_dual_attestation_message(
+        &receiver_compliance_key,
+        &ref_id,
+        sender.address(),
+        amount,
+    );
+    executor.execute_and_apply(coin1_payment(
+        &sender, &receiver, amount, ref_id, signature, 1,
+    ));
+    let credential = executor
+        .read_resource::<Credential>(receiver.address())
+        .expect("receiver must hold a credential");
+    assert_eq!(
+        credential.compliance_public_key(),
+        &receiver_compliance_public_key.to_bytes()[..]
+    );
+}
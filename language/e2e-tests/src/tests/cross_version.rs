@@ -0,0 +1,75 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    account::{lbr_currency_code, Account, AccountData, AccountRoleSpecifier},
+    common_transactions::peer_to_peer_txn,
+    cross_version::{CrossVersionReplay, ReplayError},
+    executor::FakeExecutor,
+};
+
+#[test]
+fn compiled_and_fresh_stdlib_agree_on_payments() {
+    let mut replay = CrossVersionReplay::compiled_vs_fresh();
+    let sender = AccountData::new(1_000_000, 10);
+    let receiver = AccountData::new(100_000, 10);
+    replay.add_account_data(&sender);
+    replay.add_account_data(&receiver);
+
+    let txns = (0..5)
+        .map(|i| peer_to_peer_txn(sender.account(), receiver.account(), 10 + i, 1_000))
+        .collect();
+    let outputs = replay.execute_block(txns).expect("versions must agree");
+    assert_eq!(outputs.len(), 5);
+
+    // Both sides applied the block, so the next one builds on it.
+    let txn = peer_to_peer_txn(sender.account(), receiver.account(), 15, 1_000);
+    replay
+        .execute_block(vec![txn])
+        .expect("versions must agree");
+    let sender_resource = replay
+        .candidate()
+        .read_account_resource(sender.account())
+        .expect("sender must exist");
+    assert_eq!(sender_resource.sequence_number(), 16);
+}
+
+#[test]
+fn divergence_is_reported() {
+    let sender = AccountData::new(1_000_000, 10);
+    let receiver = Account::new();
+    let mut baseline = FakeExecutor::from_genesis_file();
+    let mut candidate = FakeExecutor::from_genesis_file();
+    baseline.add_account_data(&sender);
+    // The same account with a different balance stands in for a change in behavior.
+    candidate.add_account_data(&AccountData::with_account(
+        sender.account().clone(),
+        2_000_000,
+        lbr_currency_code(),
+        10,
+        AccountRoleSpecifier::ParentVASP,
+    ));
+    let receiver_data = AccountData::with_account(
+        receiver.clone(),
+        0,
+        lbr_currency_code(),
+        0,
+        AccountRoleSpecifier::ParentVASP,
+    );
+    baseline.add_account_data(&receiver_data);
+    candidate.add_account_data(&receiver_data);
+    let mut replay = CrossVersionReplay::new(baseline, candidate);
+
+    let txn = peer_to_peer_txn(sender.account(), &receiver, 10, 1_000);
+    match replay.execute_block(vec![txn]) {
+        Err(ReplayError::Divergence { index, .. }) => assert_eq!(index, 0),
+        other => panic!("expected a divergence, got {:?}", other),
+    }
+
+    // Neither side applied the diverging block.
+    let sender_resource = replay
+        .baseline()
+        .read_account_resource(sender.account())
+        .expect("sender must exist");
+    assert_eq!(sender_resource.sequence_number(), 10);
+}
@@ -1,11 +1,15 @@
 // Copyright (c) The Libra Core Contributors
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::{account::AccountData, compile::compile_script_with_address, executor::FakeExecutor};
+use crate::{
+    account::{Account, AccountData},
+    compile::compile_script_with_address,
+    executor::FakeExecutor,
+};
 use bytecode_verifier::verify_module;
 use compiler::Compiler;
 use libra_types::{
-    account_config::LBR_NAME,
+    account_config::{AccountResource, LBR_NAME},
     transaction::{Module, SignedTransaction, Transaction, TransactionPayload, TransactionStatus},
     vm_status::{StatusCode, VMStatus},
 };
@@ -190,6 +194,28 @@ fn change_after_move() {
     executor.apply_write_set(output.write_set());
 }
 
+#[test]
+fn read_and_write_resource() {
+    let mut executor = FakeExecutor::from_genesis_file();
+    let sender = AccountData::new(1_000_000, 10);
+    executor.add_account_data(&sender);
+
+    let resource = executor
+        .read_resource::<AccountResource>(sender.address())
+        .expect("sender must exist");
+    assert_eq!(resource.sequence_number(), 10);
+
+    let other = Account::new();
+    assert!(executor
+        .read_resource::<AccountResource>(other.address())
+        .is_none());
+    executor.write_resource(other.address(), &resource);
+    let copied = executor
+        .read_resource::<AccountResource>(other.address())
+        .expect("resource must have been written");
+    assert_eq!(copied.sequence_number(), 10);
+}
+
 fn add_module_txn(sender: &AccountData, seq_num: u64) -> (CompiledModule, SignedTransaction) {
     let module_code = String::from(
         "
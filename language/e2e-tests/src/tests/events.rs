@@ -0,0 +1,199 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    account::{Account, AccountData},
+    common_transactions::peer_to_peer_txn,
+    compile::compile_script_with_address,
+    event_assert::EventAssert,
+    executor::FakeExecutor,
+    gas_costs::TXN_RESERVED,
+};
+use compiled_stdlib::transaction_scripts::StdlibScript;
+use libra_types::{
+    account_config::{MintEvent, ReceivedPaymentEvent, SentPaymentEvent, LBR_NAME},
+    on_chain_config::config_address,
+    transaction::{SignedTransaction, TransactionArgument, TransactionStatus},
+    vm_status::{StatusCode, VMStatus},
+};
+
+#[test]
+fn peer_to_peer_emits_payment_events() {
+    let mut executor = FakeExecutor::from_genesis_file();
+    let sender = AccountData::new(1_000_000, 10);
+    let receiver = AccountData::new(100_000, 10);
+    executor.add_account_data(&sender);
+    executor.add_account_data(&receiver);
+
+    let output = executor.execute_and_apply(peer_to_peer_txn(
+        sender.account(),
+        receiver.account(),
+        10,
+        1_000,
+    ));
+
+    let sent = output.assert_emits::<SentPaymentEvent, _>(sender.sent_events_key(), |event| {
+        event.amount() == 1_000
+    });
+    assert_eq!(*receiver.address(), sent.receiver());
+    let received = output
+        .assert_emits::<ReceivedPaymentEvent, _>(receiver.received_events_key(), |event| {
+            event.amount() == 1_000
+        });
+    assert_eq!(*sender.address(), received.sender());
+
+    assert_eq!(1, output.events_of_type::<SentPaymentEvent>().len());
+    assert!(output
+        .events_of_type_with_key::<SentPaymentEvent>(receiver.received_events_key())
+        .is_empty());
+    output.assert_emits_none::<MintEvent>();
+}
+
+#[test]
+#[should_panic]
+fn assert_emits_fails_on_payload_mismatch() {
+    let mut executor = FakeExecutor::from_genesis_file();
+    let sender = AccountData::new(1_000_000, 10);
+    let receiver = AccountData::new(100_000, 10);
+    executor.add_account_data(&sender);
+    executor.add_account_data(&receiver);
+
+    let output = executor.execute_and_apply(peer_to_peer_txn(
+        sender.account(),
+        receiver.account(),
+        10,
+        1_000,
+    ));
+    output.assert_emits::<SentPaymentEvent, _>(sender.sent_events_key(), |event| {
+        event.amount() == 2_000
+    });
+}
+
+fn emit_events_txn(sender: &AccountData, seq_num: u64, count: u64) -> SignedTransaction {
+    let program = format!(
+        "
+            import 0x1.Event;
+
+            main(account: &signer) {{
+                let handle: Event.EventHandle<u64>;
+                let i: u64;
+                handle = Event.new_event_handle<u64>(move(account));
+                i = 0;
+                while (copy(i) < {}) {{
+                    Event.emit_event<u64>(&mut handle, copy(i));
+                    i = move(i) + 1;
+                }}
+                Event.destroy_handle<u64>(move(handle));
+                return;
+            }}
+        ",
+        count,
+    );
+    let script = compile_script_with_address(sender.address(), "file_name", &program, vec![]);
+    sender.account().create_signed_txn_impl(
+        *sender.address(),
+        script,
+        seq_num,
+        1_000_000,
+        0,
+        LBR_NAME.to_owned(),
+    )
+}
+
+/// Updates the Libra version to the first one bounding write sets.
+fn enable_write_set_limits(executor: &mut FakeExecutor) {
+    let account = Account::new_genesis_account(config_address());
+    let txn = account.create_signed_txn_with_args(
+        StdlibScript::UpdateLibraVersion.compiled_bytes().into_vec(),
+        vec![],
+        vec![TransactionArgument::U64(2)],
+        1,
+        TXN_RESERVED,
+        0,
+        LBR_NAME.to_owned(),
+    );
+    executor.new_block();
+    let output = executor.execute_and_apply(txn);
+    assert_eq!(
+        output.status(),
+        &TransactionStatus::Keep(VMStatus::Executed)
+    );
+}
+
+#[test]
+fn events_unbounded_before_write_set_limits() {
+    let mut executor = FakeExecutor::from_genesis_file();
+    let sender = AccountData::new(1_000_000, 10);
+    executor.add_account_data(&sender);
+
+    let output = executor.execute_transaction(emit_events_txn(&sender, 10, 1025));
+    assert_eq!(
+        output.status(),
+        &TransactionStatus::Keep(VMStatus::Executed)
+    );
+}
+
+#[test]
+fn too_many_events() {
+    let mut executor = FakeExecutor::from_genesis_file();
+    let sender = AccountData::new(1_000_000, 10);
+    executor.add_account_data(&sender);
+    enable_write_set_limits(&mut executor);
+
+    let output = executor.execute_and_apply(emit_events_txn(&sender, 10, 1024));
+    assert_eq!(
+        output.status(),
+        &TransactionStatus::Keep(VMStatus::Executed)
+    );
+
+    // Past the limit, the transaction fails whatever the gas left, and its events are dropped.
+    let output = executor.execute_transaction(emit_events_txn(&sender, 11, 1025));
+    assert_eq!(
+        output.status().vm_status().status_code(),
+        StatusCode::EVENTS_LIMIT_REACHED
+    );
+    assert!(output.events().len() < 1025);
+}
+
+#[test]
+fn events_bounded_by_published_write_set_limits() {
+    let mut executor = FakeExecutor::from_genesis_file();
+    let sender = AccountData::new(1_000_000, 10);
+    executor.add_account_data(&sender);
+    enable_write_set_limits(&mut executor);
+
+    let program = "
+        import 0x1.LibraWriteSetLimits;
+
+        main(account: &signer) {
+            LibraWriteSetLimits.initialize(move(account), 1024, 1048576, 10);
+            return;
+        }
+    ";
+    let script = compile_script_with_address(&config_address(), "file_name", program, vec![]);
+    let txn = Account::new_genesis_account(config_address()).create_signed_txn_impl(
+        config_address(),
+        script,
+        2,
+        TXN_RESERVED,
+        0,
+        LBR_NAME.to_owned(),
+    );
+    let output = executor.execute_and_apply(txn);
+    assert_eq!(
+        output.status(),
+        &TransactionStatus::Keep(VMStatus::Executed)
+    );
+
+    // The VM now goes by the published limit rather than its own default of 1024 events.
+    let output = executor.execute_and_apply(emit_events_txn(&sender, 10, 10));
+    assert_eq!(
+        output.status(),
+        &TransactionStatus::Keep(VMStatus::Executed)
+    );
+    let output = executor.execute_transaction(emit_events_txn(&sender, 11, 11));
+    assert_eq!(
+        output.status().vm_status().status_code(),
+        StatusCode::EVENTS_LIMIT_REACHED
+    );
+}
@@ -0,0 +1,202 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    account::{Account, AccountData},
+    common_transactions::peer_to_peer_txn,
+    execution_trace,
+    executor::FakeExecutor,
+};
+
+#[test]
+fn traced_execution_records_calls_and_accesses() {
+    let mut executor = FakeExecutor::from_genesis_file();
+    executor.set_tracing(true);
+    let sender = AccountData::new(1_000_000, 10);
+    let receiver = AccountData::new(100_000, 10);
+    executor.add_account_data(&sender);
+    executor.add_account_data(&receiver);
+
+    executor.execute_and_apply(peer_to_peer_txn(
+        sender.account(),
+        receiver.account(),
+        10,
+        1_000,
+    ));
+    let trace = execution_trace::last_trace().expect("execution must be traced");
+
+    // Calls are only recorded in debug builds.
+    #[cfg(debug_assertions)]
+    {
+        let prologue = trace
+            .calls
+            .iter()
+            .find(|call| call.function.ends_with("::LibraAccount::prologue"))
+            .expect("prologue must be called");
+        assert_eq!(prologue.depth, 0);
+        assert!(trace
+            .calls
+            .iter()
+            .any(|call| call.function.ends_with("::LibraAccount::pay_from") && call.depth > 0));
+    }
+    assert!(trace.reads.contains(&sender.make_account_access_path()));
+    assert!(trace.writes.contains(&receiver.make_account_access_path()));
+}
+
+#[test]
+fn traced_verification_records_calls() {
+    let mut executor = FakeExecutor::from_genesis_file();
+    executor.set_tracing(true);
+    let sender = AccountData::new(1_000_000, 10);
+    executor.add_account_data(&sender);
+
+    // Signed with the wrong key, so the prologue rejects it.
+    let txn = peer_to_peer_txn(&Account::new(), sender.account(), 10, 1_000);
+    assert!(executor.verify_transaction(txn).status().is_some());
+    let trace = execution_trace::last_trace().expect("verification must be traced");
+    assert!(trace.writes.is_empty());
+    assert!(!trace.reads.is_empty());
+}
+
+#[test]
+fn untraced_execution_records_nothing() {
+    let mut executor = FakeExecutor::from_genesis_file();
+    let sender = AccountData::new(1_000_000, 10);
+    let receiver = AccountData::new(100_000, 10);
+    executor.add_account_data(&sender);
+    executor.add_account_data(&receiver);
+
+    executor.execute_and_apply(peer_to_peer_txn(
+        sender.account(),
+        receiver.account(),
+        10,
+        1_000,
+    ));
+    assert!(execution_trace::last_trace().is_none());
+}
+
+// The hooks are compiled out of release builds.
+#[cfg(debug_assertions)]
+#[test]
+fn trace_callback_follows_execution() {
+    use move_vm_runtime::{trace_with, TraceCallback, TracedCall};
+    use std::{cell::RefCell, rc::Rc};
+    use vm::file_format::Bytecode;
+
+    #[derive(Default)]
+    struct TraceRecorder {
+        entries: Vec<(usize, String)>,
+        exits: usize,
+        instructions: usize,
+        aborts: Vec<(String, u64)>,
+    }
+
+    impl TraceCallback for TraceRecorder {
+        fn on_function_entry(&mut self, call: &TracedCall) {
+            self.entries.push((call.depth, call.function.clone()));
+        }
+
+        fn on_function_exit(&mut self, _depth: usize, _function: &str) {
+            self.exits += 1;
+        }
+
+        fn on_instruction(&mut self, _function: &str, _pc: u16, _instruction: &Bytecode) {
+            self.instructions += 1;
+        }
+
+        fn on_abort(&mut self, function: &str, abort_code: u64) {
+            self.aborts.push((function.to_string(), abort_code));
+        }
+    }
+
+    let mut executor = FakeExecutor::from_genesis_file();
+    let sender = AccountData::new(1_000_000, 10);
+    let receiver = AccountData::new(100_000, 10);
+    executor.add_account_data(&sender);
+    executor.add_account_data(&receiver);
+
+    let recorder = Rc::new(RefCell::new(TraceRecorder::default()));
+    trace_with(recorder.clone(), || {
+        executor.execute_transaction(peer_to_peer_txn(
+            sender.account(),
+            receiver.account(),
+            10,
+            1_000,
+        ))
+    });
+    {
+        let recorder = recorder.borrow();
+        assert!(
+            recorder
+                .entries
+                .iter()
+                .any(|(depth, function)| *depth == 0
+                    && function.ends_with("::LibraAccount::prologue"))
+        );
+        assert_eq!(recorder.entries.len(), recorder.exits);
+        assert!(recorder.instructions > recorder.entries.len());
+        assert!(recorder.aborts.is_empty());
+    }
+
+    // Paying more than the balance aborts.
+    trace_with(recorder.clone(), || {
+        executor.execute_transaction(peer_to_peer_txn(
+            sender.account(),
+            receiver.account(),
+            10,
+            10_000_000,
+        ))
+    });
+    let num_entries = recorder.borrow().entries.len();
+    assert!(num_entries > recorder.borrow().exits);
+    assert!(!recorder.borrow().aborts.is_empty());
+
+    // Nothing is traced outside of `trace_with`.
+    executor.execute_transaction(peer_to_peer_txn(
+        sender.account(),
+        receiver.account(),
+        10,
+        1_000,
+    ));
+    assert_eq!(recorder.borrow().entries.len(), num_entries);
+}
+
+#[cfg(debug_assertions)]
+#[test]
+fn trace_callback_removed_on_panic() {
+    use move_vm_runtime::{trace_with, TraceCallback};
+    use std::{
+        cell::RefCell,
+        panic::{self, AssertUnwindSafe},
+        rc::Rc,
+    };
+    use vm::file_format::Bytecode;
+
+    #[derive(Default)]
+    struct InstructionCounter(usize);
+
+    impl TraceCallback for InstructionCounter {
+        fn on_instruction(&mut self, _function: &str, _pc: u16, _instruction: &Bytecode) {
+            self.0 += 1;
+        }
+    }
+
+    let counter = Rc::new(RefCell::new(InstructionCounter::default()));
+    let result = panic::catch_unwind(AssertUnwindSafe(|| {
+        trace_with(counter.clone(), || panic!("traced code panics"))
+    }));
+    assert!(result.is_err());
+
+    let mut executor = FakeExecutor::from_genesis_file();
+    let sender = AccountData::new(1_000_000, 10);
+    let receiver = AccountData::new(100_000, 10);
+    executor.add_account_data(&sender);
+    executor.add_account_data(&receiver);
+    executor.execute_transaction(peer_to_peer_txn(
+        sender.account(),
+        receiver.account(),
+        10,
+        1_000,
+    ));
+    assert_eq!(counter.borrow().0, 0);
+}
@@ -0,0 +1,48 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{account::AccountData, common_transactions::peer_to_peer_txn, executor::FakeExecutor};
+use libra_types::{transaction::TransactionStatus, vm_status::VMStatus};
+use move_vm_types::gas_schedule::InstructionCategory;
+
+#[test]
+fn peer_to_peer_gas_profile() {
+    let mut executor = FakeExecutor::from_genesis_file();
+    let sender = AccountData::new(1_000_000, 10);
+    let receiver = AccountData::new(100_000, 10);
+    executor.add_account_data(&sender);
+    executor.add_account_data(&receiver);
+
+    let txn = peer_to_peer_txn(sender.account(), receiver.account(), 10, 1_000);
+    let (output, profile) = executor.execute_transaction_with_gas_profile(txn.clone());
+    assert_eq!(
+        output.status(),
+        &TransactionStatus::Keep(VMStatus::Executed)
+    );
+
+    // Profiling must not change the outcome of the transaction.
+    assert_eq!(output, executor.execute_transaction(txn));
+
+    assert!(profile.intrinsic() > 0);
+    assert!(profile
+        .instructions()
+        .contains_key(&InstructionCategory::Call));
+    assert!(profile
+        .instructions()
+        .contains_key(&InstructionCategory::GlobalStorage));
+    assert!(!profile.natives().is_empty());
+    assert!(profile.total() >= profile.intrinsic());
+}
+
+#[test]
+fn discarded_transaction_has_empty_profile() {
+    let executor = FakeExecutor::from_genesis_file();
+    // The sender was never published, so the transaction is discarded in the prologue.
+    let sender = AccountData::new(1_000_000, 10);
+    let receiver = AccountData::new(100_000, 10);
+
+    let txn = peer_to_peer_txn(sender.account(), receiver.account(), 10, 1_000);
+    let (output, profile) = executor.execute_transaction_with_gas_profile(txn);
+    assert!(output.status().is_discarded());
+    assert_eq!(profile.total(), 0);
+}
@@ -3,9 +3,17 @@
 
 use crate::{
     account::AccountData, common_transactions::peer_to_peer_txn, data_store::GENESIS_CHANGE_SET,
-    executor::FakeExecutor,
+    executor::FakeExecutor, genesis_builder::GenesisBuilder,
 };
-use libra_types::transaction::{Transaction, TransactionStatus};
+use compiler::Compiler;
+use libra_types::{
+    access_path::AccessPath,
+    account_address::AccountAddress,
+    on_chain_config::{OnChainConfig, ValidatorSet},
+    transaction::{Transaction, TransactionStatus},
+};
+use move_core_types::gas_schedule::GasCost;
+use vm::file_format::Bytecode;
 
 #[test]
 fn execute_genesis_write_set() {
@@ -35,3 +43,55 @@ fn execute_genesis_and_drop_other_transaction() {
     assert_eq!(output.len(), 2);
     assert_eq!(output.pop().unwrap().status(), &TransactionStatus::Retry)
 }
+
+#[test]
+fn default_genesis_builder_matches_genesis_file() {
+    assert_eq!(
+        GenesisBuilder::new().build_change_set(),
+        *GENESIS_CHANGE_SET
+    );
+}
+
+#[test]
+fn genesis_builder_validator_count() {
+    let executor = GenesisBuilder::new().validator_count(4).build();
+    let validator_set =
+        ValidatorSet::fetch_config(executor.get_state_view()).expect("validator set must exist");
+    assert_eq!(validator_set.payload().len(), 4);
+}
+
+#[test]
+fn genesis_builder_gas_schedule() {
+    let sender = AccountData::new(1_000_000, 10);
+    let receiver = AccountData::new(100_000, 10);
+    let txn = peer_to_peer_txn(sender.account(), receiver.account(), 10, 1_000);
+    let gas_used = |builder: GenesisBuilder| {
+        let mut executor = builder.build();
+        executor.add_account_data(&sender);
+        executor.add_account_data(&receiver);
+        executor.execute_and_apply(txn.clone()).gas_used()
+    };
+
+    let default_gas_used = gas_used(GenesisBuilder::new());
+    let cheap_ret_gas_used =
+        gas_used(GenesisBuilder::new().instruction_cost(&Bytecode::Ret, GasCost::new(1, 1)));
+    assert!(cheap_ret_gas_used < default_gas_used);
+}
+
+#[test]
+fn genesis_builder_publishes_extra_modules() {
+    let address = AccountAddress::random();
+    let compiler = Compiler {
+        address,
+        ..Compiler::default()
+    };
+    let module = compiler
+        .into_compiled_module("file_name", "module M { public f() { return; } }")
+        .expect("module must compile");
+    let module_id = module.self_id();
+
+    let executor = GenesisBuilder::new().add_module(module).build();
+    assert!(executor
+        .read_from_access_path(&AccessPath::code_access_path(&module_id))
+        .is_some());
+}
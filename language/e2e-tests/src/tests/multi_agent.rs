@@ -0,0 +1,212 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    account::{Account, AccountData},
+    executor::FakeExecutor,
+};
+use compiler::Compiler;
+use libra_types::{
+    account_address::AccountAddress,
+    on_chain_config::VMPublishingOption,
+    transaction::{
+        RawTransactionWithData, Script, SignedTransaction, TransactionArgument, TransactionStatus,
+    },
+    vm_status::{AbortLocation, StatusCode, VMStatus},
+};
+
+/// A script taking the sender and one secondary signer, which aborts unless the secondary signer
+/// is `expected`.
+fn two_signer_script(expected: AccountAddress) -> Script {
+    let code = "
+    import 0x1.Signer;
+
+    main(sender: &signer, secondary: &signer, expected: address) {
+        _ = move(sender);
+        assert(Signer.address_of(move(secondary)) == move(expected), 42);
+        return;
+    }
+    ";
+    Script::new(
+        Compiler::default()
+            .into_script_blob("file_name", code)
+            .expect("script must compile"),
+        vec![],
+        vec![TransactionArgument::Address(expected)],
+    )
+}
+
+fn setup() -> (FakeExecutor, AccountData, AccountData) {
+    let mut executor =
+        FakeExecutor::from_genesis_with_options(VMPublishingOption::custom_scripts());
+    let sender = AccountData::new(1_000_000, 10);
+    let secondary = AccountData::new(0, 0);
+    executor.add_account_data(&sender);
+    executor.add_account_data(&secondary);
+    (executor, sender, secondary)
+}
+
+#[test]
+fn multi_agent_script_receives_every_signer() {
+    let (mut executor, sender, secondary) = setup();
+
+    let txn = sender
+        .account()
+        .transaction()
+        .script(two_signer_script(*secondary.address()))
+        .sequence_number(10)
+        .secondary_signers(vec![secondary.account().clone()])
+        .sign();
+    assert_eq!(executor.verify_transaction(txn.clone()).status(), None);
+    let output = executor.execute_transaction(txn);
+    assert_eq!(
+        output.status(),
+        &TransactionStatus::Keep(VMStatus::Executed)
+    );
+
+    // The script is given the secondary signers in order, so swapping in another account
+    // aborts.
+    let other = AccountData::new(0, 0);
+    executor.add_account_data(&other);
+    let txn = sender
+        .account()
+        .transaction()
+        .script(two_signer_script(*secondary.address()))
+        .sequence_number(10)
+        .secondary_signers(vec![other.account().clone()])
+        .sign();
+    let output = executor.execute_transaction(txn);
+    assert_eq!(
+        output.status(),
+        &TransactionStatus::Keep(VMStatus::MoveAbort(AbortLocation::Script, 42))
+    );
+}
+
+#[test]
+fn missing_secondary_signature() {
+    let (mut executor, sender, secondary) = setup();
+
+    let raw_txn = sender
+        .account()
+        .transaction()
+        .script(two_signer_script(*secondary.address()))
+        .sequence_number(10)
+        .raw();
+    let message =
+        RawTransactionWithData::new_multi_agent(raw_txn.clone(), vec![*secondary.address()]);
+    let txn = SignedTransaction::new_multi_agent(
+        raw_txn,
+        sender.account().sign_multi_agent(&message),
+        vec![*secondary.address()],
+        vec![],
+    );
+    assert_eq!(
+        executor.verify_transaction(txn.clone()).status(),
+        Some(VMStatus::Error(StatusCode::INVALID_SIGNATURE))
+    );
+    assert_eq!(
+        executor.execute_transaction(txn).status(),
+        &TransactionStatus::Discard(VMStatus::Error(StatusCode::INVALID_SIGNATURE))
+    );
+}
+
+#[test]
+fn secondary_signature_over_different_signers() {
+    let (mut executor, sender, secondary) = setup();
+
+    // The secondary signer signs a message without itself in the signer list, so the signature
+    // does not cover the transaction it is attached to.
+    let raw_txn = sender
+        .account()
+        .transaction()
+        .script(two_signer_script(*secondary.address()))
+        .sequence_number(10)
+        .raw();
+    let message =
+        RawTransactionWithData::new_multi_agent(raw_txn.clone(), vec![*secondary.address()]);
+    let other_message = RawTransactionWithData::new_multi_agent(raw_txn.clone(), vec![]);
+    let txn = SignedTransaction::new_multi_agent(
+        raw_txn,
+        sender.account().sign_multi_agent(&message),
+        vec![*secondary.address()],
+        vec![secondary.account().sign_multi_agent(&other_message)],
+    );
+    assert_eq!(
+        executor.verify_transaction(txn.clone()).status(),
+        Some(VMStatus::Error(StatusCode::INVALID_SIGNATURE))
+    );
+    assert_eq!(
+        executor.execute_transaction(txn).status(),
+        &TransactionStatus::Discard(VMStatus::Error(StatusCode::INVALID_SIGNATURE))
+    );
+}
+
+#[test]
+fn secondary_signed_with_wrong_key() {
+    let (mut executor, sender, secondary) = setup();
+
+    // A valid signature for the secondary address, but by a key that does not own the account.
+    let impostor = Account::new();
+    let raw_txn = sender
+        .account()
+        .transaction()
+        .script(two_signer_script(*secondary.address()))
+        .sequence_number(10)
+        .raw();
+    let message =
+        RawTransactionWithData::new_multi_agent(raw_txn.clone(), vec![*secondary.address()]);
+    let txn = SignedTransaction::new_multi_agent(
+        raw_txn,
+        sender.account().sign_multi_agent(&message),
+        vec![*secondary.address()],
+        vec![impostor.sign_multi_agent(&message)],
+    );
+    assert_eq!(
+        executor.verify_transaction(txn.clone()).status(),
+        Some(VMStatus::Error(StatusCode::INVALID_AUTH_KEY))
+    );
+    assert_eq!(
+        executor.execute_transaction(txn).status(),
+        &TransactionStatus::Discard(VMStatus::Error(StatusCode::INVALID_AUTH_KEY))
+    );
+}
+
+#[test]
+fn secondary_signer_without_account() {
+    let (mut executor, sender, _) = setup();
+
+    let missing = Account::new();
+    let txn = sender
+        .account()
+        .transaction()
+        .script(two_signer_script(*missing.address()))
+        .sequence_number(10)
+        .secondary_signers(vec![missing])
+        .sign();
+    assert_eq!(
+        executor.verify_transaction(txn.clone()).status(),
+        Some(VMStatus::Error(StatusCode::SENDING_ACCOUNT_DOES_NOT_EXIST))
+    );
+    assert_eq!(
+        executor.execute_transaction(txn).status(),
+        &TransactionStatus::Discard(VMStatus::Error(StatusCode::SENDING_ACCOUNT_DOES_NOT_EXIST))
+    );
+}
+
+#[test]
+fn script_signer_count_must_match_transaction() {
+    let (mut executor, sender, secondary) = setup();
+
+    // A single-signer transaction running a script that expects a secondary signer.
+    let txn = sender
+        .account()
+        .transaction()
+        .script(two_signer_script(*secondary.address()))
+        .sequence_number(10)
+        .sign();
+    let output = executor.execute_transaction(txn);
+    assert_eq!(
+        output.status(),
+        &TransactionStatus::Keep(VMStatus::Error(StatusCode::TYPE_MISMATCH))
+    );
+}
@@ -0,0 +1,72 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    account::{self, AccountData},
+    executor::FakeExecutor,
+    gas_costs,
+};
+use libra_types::account_config::{self, COIN1_NAME};
+use transaction_builder::encode_peer_to_peer_with_metadata_script;
+
+#[test]
+fn add_account_data_publishes_all_balances() {
+    let mut executor = FakeExecutor::from_genesis_file();
+    let account = AccountData::new_with_balances(
+        account::all_currency_codes()
+            .into_iter()
+            .zip(vec![10, 20, 30]),
+        0,
+    );
+    executor.add_account_data(&account);
+
+    for (code, amount) in account.balances() {
+        let balance = executor
+            .read_balance_resource(account.account(), code.to_owned())
+            .expect("balance must exist");
+        assert_eq!(amount, balance.coin());
+    }
+}
+
+#[test]
+fn pay_gas_in_non_lbr_currency() {
+    let mut executor = FakeExecutor::from_genesis_file();
+    let sender = AccountData::new_with_balances(
+        vec![
+            (account::lbr_currency_code(), 1_000_000),
+            (account::coin1_currency_code(), 1_000_000),
+        ],
+        0,
+    );
+    let mut receiver = AccountData::new(0, 0);
+    receiver.add_balance(account::coin1_currency_code(), 0);
+    executor.add_account_data(&sender);
+    executor.add_account_data(&receiver);
+
+    let txn = sender
+        .account()
+        .transaction()
+        .script(encode_peer_to_peer_with_metadata_script(
+            account_config::coin1_tag(),
+            *receiver.address(),
+            1_000,
+            vec![],
+            vec![],
+        ))
+        .sequence_number(0)
+        .max_gas_amount(gas_costs::TXN_RESERVED)
+        .gas_unit_price(1)
+        .gas_currency_code(COIN1_NAME)
+        .sign();
+    let output = executor.execute_and_apply(txn);
+    assert!(output.gas_used() > 0);
+
+    let coin1_balance = executor
+        .read_balance_resource(sender.account(), account::coin1_currency_code())
+        .expect("sender coin1 balance must exist");
+    assert_eq!(1_000_000 - 1_000 - output.gas_used(), coin1_balance.coin());
+    let lbr_balance = executor
+        .read_balance_resource(sender.account(), account::lbr_currency_code())
+        .expect("sender lbr balance must exist");
+    assert_eq!(1_000_000, lbr_balance.coin());
+}
@@ -11,9 +11,9 @@ use crate::{
 use compiled_stdlib::transaction_scripts::StdlibScript;
 use libra_types::{
     account_config::LBR_NAME,
-    on_chain_config::LibraVersion,
+    on_chain_config::{LibraVersion, RegisteredCurrencies, VMPublishingOption, ValidatorSet},
     transaction::{TransactionArgument, TransactionStatus},
-    vm_status::VMStatus,
+    vm_status::{StatusCode, VMStatus},
 };
 use libra_vm::LibraVM;
 use transaction_builder::encode_update_dual_attestation_limit_script;
@@ -118,3 +118,84 @@ fn updated_limit_allows_txn() {
     assert_eq!(3_999_990, sender_balance.coin());
     assert_eq!(1_000_010, receiver_balance.coin());
 }
+
+#[test]
+fn set_libra_version_takes_effect_after_reconfiguration() {
+    let mut executor = FakeExecutor::from_genesis_file();
+    let initial_epoch = executor.epoch();
+    let initial_reconfiguration_time = executor.last_reconfiguration_time();
+
+    executor.set_libra_version(LibraVersion { major: 7 });
+    assert_eq!(executor.libra_version(), LibraVersion { major: 7 });
+    assert_eq!(executor.epoch(), initial_epoch);
+
+    let event = executor.reconfigure();
+    assert_eq!(event.epoch(), initial_epoch + 1);
+    assert_eq!(executor.epoch(), initial_epoch + 1);
+    assert!(executor.last_reconfiguration_time() > initial_reconfiguration_time);
+
+    let vm = LibraVM::new(executor.get_state_view());
+    assert_eq!(
+        vm.internals().libra_version().unwrap(),
+        LibraVersion { major: 7 }
+    );
+}
+
+#[test]
+fn set_publishing_option_locks_scripts() {
+    let mut executor = FakeExecutor::from_genesis_file();
+    let sender = AccountData::new(1_000_000, 10);
+    executor.add_account_data(&sender);
+    let txn = sender.account().create_signed_txn_with_args(
+        vec![],
+        vec![],
+        vec![],
+        10,
+        TXN_RESERVED,
+        0,
+        LBR_NAME.to_owned(),
+    );
+    assert_eq!(executor.verify_transaction(txn.clone()).status(), None);
+
+    let locked = VMPublishingOption::locked(StdlibScript::whitelist());
+    executor.set_publishing_option(locked.clone());
+    assert_eq!(executor.publishing_option(), locked);
+    assert_eq!(
+        executor.verify_transaction(txn).status(),
+        Some(VMStatus::Error(StatusCode::UNKNOWN_SCRIPT))
+    );
+}
+
+#[test]
+fn whitelist_update_script_locks_scripts() {
+    let mut executor = FakeExecutor::from_genesis_file();
+    let current = LibraVersion { major: 1 };
+    let locked = StdlibScript::publishing_option_for_version(&current);
+    assert_ne!(executor.publishing_option(), locked);
+
+    let libra_root = Account::new_libra_root();
+    executor.execute_and_apply(
+        libra_root.signed_script_txn(StdlibScript::encode_whitelist_update_script(&current), 1),
+    );
+    assert_eq!(executor.publishing_option(), locked);
+}
+
+#[test]
+fn set_registered_currencies_and_validator_set() {
+    let mut executor = FakeExecutor::from_genesis_file();
+
+    let currencies = RegisteredCurrencies::new(vec![account::lbr_currency_code()]);
+    executor.set_registered_currencies(currencies.clone());
+    assert_eq!(executor.registered_currencies(), currencies);
+
+    let validator_set = executor.validator_set();
+    assert!(!validator_set.payload().is_empty());
+    let first_validator = validator_set.payload()[0].clone();
+    executor.set_validator_set(ValidatorSet::new(vec![first_validator.clone()]));
+    assert_eq!(executor.validator_set().payload(), &[first_validator]);
+
+    // Blocks are still proposed and the new set is carried into the next epoch.
+    let event = executor.reconfigure();
+    assert_eq!(event.epoch(), executor.epoch());
+    assert_eq!(executor.validator_set().payload().len(), 1);
+}
@@ -0,0 +1,62 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{account::AccountData, common_transactions::peer_to_peer_txn, executor::FakeExecutor};
+use libra_types::{transaction::TransactionStatus, vm_status::VMStatus};
+
+#[test]
+fn parallel_matches_sequential_for_independent_transfers() {
+    let mut executor = FakeExecutor::from_genesis_file();
+    let mut txns = vec![];
+    for _ in 0..8 {
+        let sender = AccountData::new(1_000_000, 0);
+        let receiver = AccountData::new(0, 0);
+        executor.add_account_data(&sender);
+        executor.add_account_data(&receiver);
+        txns.push(peer_to_peer_txn(
+            sender.account(),
+            receiver.account(),
+            0,
+            1_000,
+        ));
+    }
+
+    let outputs = executor
+        .execute_block_parallel(txns)
+        .expect("parallel execution must match sequential execution");
+    for output in outputs {
+        assert_eq!(
+            output.status(),
+            &TransactionStatus::Keep(VMStatus::Executed)
+        );
+    }
+}
+
+#[test]
+fn parallel_matches_sequential_for_dependent_transfers() {
+    let mut executor = FakeExecutor::from_genesis_file();
+    let sender = AccountData::new(1_000_000, 10);
+    let receiver = AccountData::new(0, 0);
+    executor.add_account_data(&sender);
+    executor.add_account_data(&receiver);
+
+    // Each transaction depends on the sequence number and balance written by the previous one,
+    // and the last one is a replay that must be discarded.
+    let mut txns: Vec<_> = (10..14)
+        .map(|seq_num| peer_to_peer_txn(sender.account(), receiver.account(), seq_num, 1_000))
+        .collect();
+    txns.push(peer_to_peer_txn(
+        sender.account(),
+        receiver.account(),
+        10,
+        1_000,
+    ));
+
+    let outputs = executor
+        .execute_block_parallel(txns)
+        .expect("parallel execution must match sequential execution");
+    assert!(matches!(
+        outputs.last().unwrap().status(),
+        TransactionStatus::Discard(_)
+    ));
+}
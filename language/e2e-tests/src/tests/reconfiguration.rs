@@ -0,0 +1,80 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    account::Account, executor::FakeExecutor, gas_costs::TXN_RESERVED, transaction_status_eq,
+};
+use compiled_stdlib::transaction_scripts::StdlibScript;
+use libra_types::{
+    account_config::LBR_NAME,
+    on_chain_config::{config_address, new_epoch_event_key, LibraVersion},
+    transaction::{SignedTransaction, TransactionArgument, TransactionStatus},
+    vm_status::VMStatus,
+};
+use libra_vm::LibraVM;
+
+fn update_libra_version_txn(sender: &Account, seq_num: u64, major: u64) -> SignedTransaction {
+    sender.create_signed_txn_with_args(
+        StdlibScript::UpdateLibraVersion.compiled_bytes().into_vec(),
+        vec![],
+        vec![TransactionArgument::U64(major)],
+        seq_num,
+        TXN_RESERVED,
+        0,
+        LBR_NAME.to_owned(),
+    )
+}
+
+#[test]
+fn block_with_metadata_spans_epochs() {
+    let mut executor = FakeExecutor::from_genesis_file();
+    let account = Account::new_genesis_account(config_address());
+    let initial_epoch = executor.epoch();
+
+    // Each transaction reconfigures, so the second one has to be carried over into a new block.
+    let block_metadata = executor.new_block_metadata();
+    let outputs = executor
+        .execute_block_with_metadata(
+            block_metadata,
+            vec![
+                update_libra_version_txn(&account, 1, 2),
+                update_libra_version_txn(&account, 2, 3),
+            ],
+        )
+        .unwrap();
+
+    assert_eq!(outputs.len(), 2);
+    for output in &outputs {
+        assert!(transaction_status_eq(
+            output.status(),
+            &TransactionStatus::Keep(VMStatus::Executed)
+        ));
+        assert!(output
+            .events()
+            .iter()
+            .any(|event| event.key() == &new_epoch_event_key()));
+    }
+    assert_eq!(executor.epoch(), initial_epoch + 2);
+
+    let vm = LibraVM::new(executor.get_state_view());
+    assert_eq!(
+        vm.internals().libra_version().unwrap(),
+        LibraVersion { major: 3 }
+    );
+}
+
+#[test]
+fn block_with_metadata_without_reconfiguration() {
+    let mut executor = FakeExecutor::from_genesis_file();
+    let initial_epoch = executor.epoch();
+    let initial_time = executor.get_block_time();
+
+    let block_metadata = executor.new_block_metadata();
+    let outputs = executor
+        .execute_block_with_metadata(block_metadata, vec![])
+        .unwrap();
+
+    assert!(outputs.is_empty());
+    assert_eq!(executor.epoch(), initial_epoch);
+    assert_eq!(executor.get_block_time(), initial_time + 1);
+}
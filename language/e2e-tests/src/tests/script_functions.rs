@@ -0,0 +1,146 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    account::{self, Account},
+    executor::FakeExecutor,
+    keygen::KeyGen,
+};
+use compiled_stdlib::{script_functions::ScriptFunctionCall, transaction_scripts::StdlibScript};
+use libra_types::{
+    account_config,
+    on_chain_config::VMPublishingOption,
+    transaction::{ScriptFunction, TransactionArgument, TransactionPayload, TransactionStatus},
+    vm_status::{StatusCode, VMStatus},
+};
+use move_core_types::{identifier::Identifier, language_storage::ModuleId};
+use transaction_builder::encode_create_parent_vasp_account_script;
+
+fn create_parent_vasp(executor: &mut FakeExecutor, vasp: &Account) {
+    let mut keygen = KeyGen::from_seed([9u8; 32]);
+    let (_, compliance_public_key) = keygen.generate_keypair();
+    executor.execute_and_apply(
+        Account::new_libra_root()
+            .transaction()
+            .script(encode_create_parent_vasp_account_script(
+                account_config::coin1_tag(),
+                *vasp.address(),
+                vasp.auth_key_prefix(),
+                vec![],
+                vec![],
+                compliance_public_key.to_bytes().to_vec(),
+                false,
+            ))
+            .sequence_number(1)
+            .sign(),
+    );
+}
+
+#[test]
+fn add_currency_with_script_function() {
+    let mut executor = FakeExecutor::from_genesis_file();
+    executor.set_publishing_option(VMPublishingOption::open());
+    let vasp = Account::new();
+    create_parent_vasp(&mut executor, &vasp);
+    assert!(executor
+        .read_balance_resource(&vasp, account::coin2_currency_code())
+        .is_none());
+
+    executor.execute_and_apply(
+        vasp.transaction()
+            .payload(
+                ScriptFunctionCall::AddCurrency {
+                    currency: account_config::type_tag_for_currency_code(
+                        account::coin2_currency_code(),
+                    ),
+                }
+                .encode(),
+            )
+            .sequence_number(0)
+            .sign(),
+    );
+    let balance = executor
+        .read_balance_resource(&vasp, account::coin2_currency_code())
+        .expect("the new balance must be published");
+    assert_eq!(balance.coin(), 0);
+}
+
+#[test]
+fn script_functions_require_open_scripts() {
+    let mut executor = FakeExecutor::from_genesis_file();
+    executor.set_publishing_option(VMPublishingOption::locked(StdlibScript::whitelist()));
+    let vasp = Account::new();
+    create_parent_vasp(&mut executor, &vasp);
+
+    let txn =
+        vasp.transaction()
+            .payload(
+                ScriptFunctionCall::AddCurrency {
+                    currency: account_config::type_tag_for_currency_code(
+                        account::coin2_currency_code(),
+                    ),
+                }
+                .encode(),
+            )
+            .sequence_number(0)
+            .sign();
+    assert_eq!(
+        executor.verify_transaction(txn).status(),
+        Some(VMStatus::Error(StatusCode::UNKNOWN_SCRIPT))
+    );
+}
+
+fn rotate_base_url(args: Vec<TransactionArgument>) -> TransactionPayload {
+    TransactionPayload::ScriptFunction(ScriptFunction::new(
+        ModuleId::new(
+            account_config::CORE_CODE_ADDRESS,
+            Identifier::new("DualAttestation").unwrap(),
+        ),
+        Identifier::new("rotate_base_url").unwrap(),
+        vec![],
+        args,
+    ))
+}
+
+#[test]
+fn script_function_arguments_must_match_parameters() {
+    let mut executor = FakeExecutor::from_genesis_file();
+    executor.set_publishing_option(VMPublishingOption::open());
+    let vasp = Account::new();
+    create_parent_vasp(&mut executor, &vasp);
+
+    let bad_args = vec![
+        // Missing argument.
+        vec![],
+        // Extra argument.
+        vec![
+            TransactionArgument::U8Vector(b"https://libra.org".to_vec()),
+            TransactionArgument::U64(0),
+        ],
+        // Wrong argument type.
+        vec![TransactionArgument::U64(0)],
+    ];
+    for (sequence_number, args) in bad_args.into_iter().enumerate() {
+        let output = executor.execute_transaction(
+            vasp.transaction()
+                .payload(rotate_base_url(args))
+                .sequence_number(sequence_number as u64)
+                .sign(),
+        );
+        assert_eq!(
+            output.status(),
+            &TransactionStatus::Keep(VMStatus::Error(StatusCode::TYPE_MISMATCH))
+        );
+        executor.apply_write_set(output.write_set());
+    }
+
+    // A well formed call goes through.
+    executor.execute_and_apply(
+        vasp.transaction()
+            .payload(rotate_base_url(vec![TransactionArgument::U8Vector(
+                b"https://libra.org".to_vec(),
+            )]))
+            .sequence_number(3)
+            .sign(),
+    );
+}
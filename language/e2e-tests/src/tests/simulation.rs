@@ -0,0 +1,106 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{account::AccountData, common_transactions::peer_to_peer_txn, executor::FakeExecutor};
+use libra_crypto::{ed25519::Ed25519PrivateKey, PrivateKey, SigningKey, Uniform};
+use libra_types::{
+    transaction::{SignedTransaction, TransactionStatus},
+    vm_status::{StatusCode, VMStatus},
+};
+use move_vm_types::gas_schedule::ExecutionLimits;
+
+#[test]
+fn simulate_peer_to_peer() {
+    let mut executor = FakeExecutor::from_genesis_file();
+    let sender = AccountData::new(1_000_000, 10);
+    let receiver = AccountData::new(100_000, 10);
+    executor.add_account_data(&sender);
+    executor.add_account_data(&receiver);
+
+    let txn = peer_to_peer_txn(sender.account(), receiver.account(), 10, 1_000);
+    let output = executor.simulate_transaction(txn.clone(), true);
+    assert_eq!(
+        output.status(),
+        &TransactionStatus::Keep(VMStatus::Executed)
+    );
+    assert_eq!(output, executor.execute_transaction(txn.clone()));
+
+    // A transaction signed by the wrong key is simulated the same when not checking signatures.
+    let raw_txn = txn.into_raw_transaction();
+    let signature = Ed25519PrivateKey::generate_for_testing().sign(&raw_txn);
+    let wrongly_signed_txn =
+        SignedTransaction::new(raw_txn, sender.account().pubkey.clone(), signature);
+    assert_eq!(
+        executor.simulate_transaction(wrongly_signed_txn.clone(), false),
+        output
+    );
+    assert_eq!(
+        executor
+            .simulate_transaction(wrongly_signed_txn, true)
+            .status(),
+        &TransactionStatus::Discard(VMStatus::Error(StatusCode::INVALID_SIGNATURE))
+    );
+
+    // The public key is still checked against the sender's authentication key.
+    let raw_txn =
+        peer_to_peer_txn(sender.account(), receiver.account(), 10, 1_000).into_raw_transaction();
+    let private_key = Ed25519PrivateKey::generate_for_testing();
+    let signature = private_key.sign(&raw_txn);
+    let unknown_key_txn = SignedTransaction::new(raw_txn, private_key.public_key(), signature);
+    assert_eq!(
+        executor
+            .simulate_transaction(unknown_key_txn, false)
+            .status(),
+        &TransactionStatus::Discard(VMStatus::Error(StatusCode::INVALID_AUTH_KEY))
+    );
+}
+
+#[test]
+fn simulate_with_execution_limits() {
+    let mut executor = FakeExecutor::from_genesis_file();
+    let sender = AccountData::new(1_000_000, 10);
+    let receiver = AccountData::new(100_000, 10);
+    executor.add_account_data(&sender);
+    executor.add_account_data(&receiver);
+
+    let no_limits = ExecutionLimits {
+        max_instructions: u64::max_value(),
+        max_call_depth: usize::max_value(),
+        max_value_size: u64::max_value(),
+    };
+    let txn = peer_to_peer_txn(sender.account(), receiver.account(), 10, 1_000);
+    assert_eq!(
+        executor.simulate_transaction_with_limits(txn.clone(), no_limits),
+        executor.simulate_transaction(txn.clone(), true)
+    );
+
+    // Each limit fails the transaction with its own status, whatever the gas left.
+    let limited_status = |limits| {
+        executor
+            .simulate_transaction_with_limits(txn.clone(), limits)
+            .status()
+            .vm_status()
+            .status_code()
+    };
+    assert_eq!(
+        limited_status(ExecutionLimits {
+            max_instructions: 1,
+            ..no_limits
+        }),
+        StatusCode::EXECUTION_INSTRUCTION_LIMIT_REACHED
+    );
+    assert_eq!(
+        limited_status(ExecutionLimits {
+            max_call_depth: 0,
+            ..no_limits
+        }),
+        StatusCode::CALL_DEPTH_LIMIT_REACHED
+    );
+    assert_eq!(
+        limited_status(ExecutionLimits {
+            max_value_size: 0,
+            ..no_limits
+        }),
+        StatusCode::VALUE_SIZE_LIMIT_REACHED
+    );
+}
@@ -0,0 +1,57 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    account::{self, AccountData},
+    common_transactions::peer_to_peer_txn,
+    executor::FakeExecutor,
+};
+
+#[test]
+fn restore_rolls_back_applied_transactions() {
+    let mut executor = FakeExecutor::from_genesis_file();
+    let sender = AccountData::new(1_000_000, 10);
+    let receiver = AccountData::new(100_000, 10);
+    executor.add_account_data(&sender);
+    executor.add_account_data(&receiver);
+
+    let balance = |executor: &FakeExecutor, account: &AccountData| {
+        executor
+            .read_balance_resource(account.account(), account::lbr_currency_code())
+            .expect("balance must exist")
+            .coin()
+    };
+
+    let snapshot = executor.snapshot();
+    let block_time = executor.get_block_time();
+
+    // First path: a small transfer in a new block.
+    executor.new_block();
+    executor.execute_and_apply(peer_to_peer_txn(
+        sender.account(),
+        receiver.account(),
+        10,
+        1_000,
+    ));
+    assert_eq!(balance(&executor, &sender), 999_000);
+    assert_eq!(balance(&executor, &receiver), 101_000);
+
+    // Second path from the same baseline: the same sequence number is usable again.
+    executor.restore(snapshot.clone());
+    assert_eq!(executor.get_block_time(), block_time);
+    assert_eq!(balance(&executor, &sender), 1_000_000);
+    assert_eq!(balance(&executor, &receiver), 100_000);
+
+    executor.execute_and_apply(peer_to_peer_txn(
+        sender.account(),
+        receiver.account(),
+        10,
+        50_000,
+    ));
+    assert_eq!(balance(&executor, &sender), 950_000);
+    assert_eq!(balance(&executor, &receiver), 150_000);
+
+    // A snapshot can be restored more than once.
+    executor.restore(snapshot);
+    assert_eq!(balance(&executor, &sender), 1_000_000);
+}
@@ -1650,3 +1650,199 @@ fn add_child_currencies() {
             .sign(),
     );
 }
+
+#[test]
+fn typed_builder_checks_arguments() {
+    let payee = *Account::new().address();
+    let builder = PeerToPeerWithMetadataBuilder {
+        currency: account_config::coin1_tag(),
+        payee,
+        amount: 100,
+        metadata: vec![],
+        metadata_signature: vec![],
+    };
+    assert_eq!(
+        builder.clone().build(),
+        Ok(encode_peer_to_peer_with_metadata_script(
+            account_config::coin1_tag(),
+            payee,
+            100,
+            vec![],
+            vec![],
+        ))
+    );
+
+    let zero_amount = PeerToPeerWithMetadataBuilder {
+        amount: 0,
+        ..builder.clone()
+    };
+    assert_eq!(zero_amount.build(), Err(BuilderError::ZeroAmount("amount")));
+
+    let short_signature = PeerToPeerWithMetadataBuilder {
+        metadata_signature: vec![0; SIGNATURE_LENGTH - 1],
+        ..builder.clone()
+    };
+    assert_eq!(
+        short_signature.build(),
+        Err(BuilderError::InvalidSignatureLength(
+            "metadata_signature",
+            SIGNATURE_LENGTH - 1
+        ))
+    );
+
+    let signed = PeerToPeerWithMetadataBuilder {
+        metadata_signature: vec![0; SIGNATURE_LENGTH],
+        ..builder
+    };
+    assert!(signed.build().is_ok());
+}
+
+#[test]
+fn transaction_factory_signs_with_next_sequence_number() {
+    use libra_types::chain_id::ChainId;
+    use transaction_builder::transaction_factory::{LocalSequenceNumbers, TransactionFactory};
+
+    let mut executor = FakeExecutor::from_genesis_file();
+    let sender = AccountData::new(1_000_000, 10);
+    let receiver = AccountData::new(100_000, 10);
+    executor.add_account_data(&sender);
+    executor.add_account_data(&receiver);
+
+    let factory = TransactionFactory::new(ChainId::test());
+    let sequence_numbers = LocalSequenceNumbers::new();
+    sequence_numbers.set(*sender.address(), 10);
+    for expected_sequence_number in 10..12 {
+        let txn = factory
+            .signed_script(
+                *sender.address(),
+                encode_peer_to_peer_with_metadata_script(
+                    account_config::lbr_type_tag(),
+                    *receiver.address(),
+                    100,
+                    vec![],
+                    vec![],
+                ),
+                &sequence_numbers,
+                &sender.account().privkey,
+            )
+            .unwrap();
+        assert_eq!(txn.sequence_number(), expected_sequence_number);
+        assert_eq!(txn.chain_id(), ChainId::test());
+        executor.execute_and_apply(txn);
+    }
+
+    let receiver_balance = executor
+        .read_balance_resource(receiver.account(), account::lbr_currency_code())
+        .expect("receiver balance must exist");
+    assert_eq!(receiver_balance.coin(), 100_200);
+    assert!(factory
+        .signed_script(
+            *receiver.address(),
+            encode_rotate_authentication_key_script(vec![]),
+            &sequence_numbers,
+            &receiver.account().privkey,
+        )
+        .is_err());
+}
+
+#[test]
+fn dual_attestation_helpers_sign_travel_rule_payment() {
+    let mut executor = FakeExecutor::from_genesis_file();
+    let payment_receiver = Account::new();
+    let payment_sender = Account::new();
+    let libra_root = Account::new_libra_root();
+    let dd = Account::new_genesis_account(account_config::testnet_dd_account_address());
+    let mut keygen = KeyGen::from_seed([9u8; 32]);
+    let (sender_vasp_compliance_private_key, sender_vasp_compliance_public_key) =
+        keygen.generate_keypair();
+    let (receiver_vasp_compliance_private_key, receiver_vasp_compliance_public_key) =
+        keygen.generate_keypair();
+
+    for (seq_num, (vasp, compliance_public_key)) in vec![
+        (&payment_sender, &sender_vasp_compliance_public_key),
+        (&payment_receiver, &receiver_vasp_compliance_public_key),
+    ]
+    .into_iter()
+    .enumerate()
+    {
+        executor.execute_and_apply(libra_root.signed_script_txn(
+            encode_create_parent_vasp_account_script(
+                account_config::coin1_tag(),
+                *vasp.address(),
+                vasp.auth_key_prefix(),
+                vec![],
+                vec![],
+                compliance_public_key.to_bytes().to_vec(),
+                false,
+            ),
+            seq_num as u64 + 1,
+        ));
+    }
+    // DD -> VASP payments are subject to the travel rule too, so mint below the threshold.
+    for seq_num in 0..2 {
+        executor.execute_and_apply(dd.signed_script_txn(
+            encode_testnet_mint_script(
+                account_config::coin1_tag(),
+                *payment_sender.address(),
+                COIN1_THRESHOLD - 1,
+            ),
+            seq_num,
+        ));
+    }
+
+    let metadata = dual_attestation::travel_rule_metadata("off-chain-reference-id");
+    let signature = dual_attestation::sign_dual_attestation_message(
+        &receiver_vasp_compliance_private_key,
+        &metadata,
+        payment_sender.address(),
+        COIN1_THRESHOLD,
+    );
+    assert!(dual_attestation::verify_dual_attestation_signature(
+        &receiver_vasp_compliance_public_key,
+        &signature,
+        &metadata,
+        payment_sender.address(),
+        COIN1_THRESHOLD,
+    )
+    .is_ok());
+    // The signature covers the amount and must come from the payee's compliance key.
+    assert!(dual_attestation::verify_dual_attestation_signature(
+        &receiver_vasp_compliance_public_key,
+        &signature,
+        &metadata,
+        payment_sender.address(),
+        COIN1_THRESHOLD + 1,
+    )
+    .is_err());
+    assert!(dual_attestation::verify_dual_attestation_signature(
+        &receiver_vasp_compliance_public_key,
+        &dual_attestation::sign_dual_attestation_message(
+            &sender_vasp_compliance_private_key,
+            &metadata,
+            payment_sender.address(),
+            COIN1_THRESHOLD,
+        ),
+        &metadata,
+        payment_sender.address(),
+        COIN1_THRESHOLD,
+    )
+    .is_err());
+
+    executor.execute_and_apply(payment_sender.signed_script_txn(
+        encode_peer_to_peer_with_metadata_script(
+            account_config::coin1_tag(),
+            *payment_receiver.address(),
+            COIN1_THRESHOLD,
+            metadata,
+            signature,
+        ),
+        0,
+    ));
+    assert_eq!(
+        executor
+            .read_balance_resource(&payment_receiver, account::coin1_currency_code())
+            .unwrap()
+            .coin(),
+        COIN1_THRESHOLD
+    );
+}
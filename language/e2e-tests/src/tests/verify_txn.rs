@@ -7,6 +7,7 @@ use crate::{
     compile::compile_module_with_address,
     executor::FakeExecutor,
     transaction_status_eq,
+    txn_generator::TxnGenerator,
 };
 use compiled_stdlib::transaction_scripts::StdlibScript;
 use compiler::Compiler;
@@ -21,7 +22,9 @@ use libra_types::{
     },
     vm_status::{StatusCode, StatusType, VMStatus},
 };
+use libra_vm::ValidationMode;
 use move_core_types::gas_schedule::{GasAlgebra, GasConstants};
+use proptest::{prelude::*, test_runner::TestRunner};
 use transaction_builder::encode_peer_to_peer_with_metadata_script;
 
 #[test]
@@ -185,8 +188,7 @@ fn verify_simple_payment() {
 
     // XXX TZ: TransactionExpired
 
-    // RejectedWriteSet is tested in `verify_rejected_write_set`
-    // InvalidWriteSet is tested in genesis.rs
+    // Write set prologue failures and InvalidWriteSet are tested in write_set.rs
 
     // Create a new transaction from a bogus account that doesn't exist
     let bogus_account = AccountData::new(100_000, 10);
@@ -205,8 +207,7 @@ fn verify_simple_payment() {
         VMStatus::Error(StatusCode::SENDING_ACCOUNT_DOES_NOT_EXIST)
     );
 
-    // RejectedWriteSet is tested in `verify_rejected_write_set`
-    // InvalidWriteSet is tested in genesis.rs
+    // Write set prologue failures and InvalidWriteSet are tested in write_set.rs
 
     // The next couple tests test transaction size, and bounds on gas price and the number of
     // gas units that can be submitted with a transaction.
@@ -392,6 +393,11 @@ pub fn test_arbitrary_script_execution() {
     );
 
     assert_eq!(executor.verify_transaction(txn.clone()).status(), None);
+    executor.set_validation_mode(ValidationMode::Full);
+    assert_eq!(
+        executor.verify_transaction(txn.clone()).status(),
+        Some(VMStatus::Error(StatusCode::CODE_DESERIALIZATION_ERROR))
+    );
     let status = executor.execute_transaction(txn).status().clone();
     assert!(!status.is_discarded());
     assert_eq!(
@@ -569,6 +575,8 @@ pub fn test_open_publishing() {
         .account()
         .create_user_txn(random_module, 10, 100_000, 1, LBR_NAME.to_owned());
     assert_eq!(executor.verify_transaction(txn.clone()).status(), None);
+    executor.set_validation_mode(ValidationMode::Full);
+    assert_eq!(executor.verify_transaction(txn.clone()).status(), None);
     assert_eq!(
         executor.execute_transaction(txn).status(),
         &TransactionStatus::Keep(VMStatus::Executed)
@@ -632,8 +640,14 @@ fn test_dependency_fails_verification() {
         1,
         LBR_NAME.to_owned(),
     );
-    // As of now, we don't verify dependencies in verify_transaction.
+    // By default, we don't verify dependencies in verify_transaction.
     assert_eq!(executor.verify_transaction(txn.clone()).status(), None);
+    executor.set_validation_mode(ValidationMode::Full);
+    let status = executor
+        .verify_transaction(txn.clone())
+        .status()
+        .expect("full validation must check dependencies");
+    assert_eq!(status.status_code(), StatusCode::INVALID_RESOURCE_FIELD);
     match executor.execute_transaction(txn).status() {
         TransactionStatus::Keep(status) => {
             assert!(status.status_type() == StatusType::Verification);
@@ -642,3 +656,38 @@ fn test_dependency_fails_verification() {
         _ => panic!("Failed to find missing dependency in bytecode verifier"),
     }
 }
+
+#[test]
+fn generated_transactions_prologue_parity() {
+    let mut executor = FakeExecutor::from_genesis_file();
+    let sender = AccountData::new(900_000, 10);
+    let receiver = AccountData::new(100_000, 10);
+    executor.add_account_data(&sender);
+    executor.add_account_data(&receiver);
+    let generator = TxnGenerator::new(&sender, *receiver.address());
+
+    let mut runner = TestRunner::new(ProptestConfig::with_cases(64));
+    runner
+        .run(&generator.strategy(), |(variant, txn)| {
+            let validation_status = executor.verify_transaction(txn.clone()).status();
+            let output = executor.execute_transaction(txn);
+            match variant.expected_status() {
+                None => {
+                    prop_assert_eq!(validation_status, None);
+                    prop_assert_eq!(
+                        output.status(),
+                        &TransactionStatus::Keep(VMStatus::Executed)
+                    );
+                }
+                Some(status_code) => {
+                    assert_prologue_parity!(
+                        validation_status,
+                        output.status(),
+                        VMStatus::Error(status_code)
+                    );
+                }
+            }
+            Ok(())
+        })
+        .unwrap();
+}
@@ -304,3 +304,79 @@ fn transfer_and_execute_writeset() {
 
     executor.apply_write_set(output.write_set());
 }
+
+#[test]
+fn execute_writeset_applies_change_set() {
+    let mut executor = FakeExecutor::from_genesis_file();
+    executor.new_block();
+
+    let new_account_data = AccountData::new(0, 10);
+    let (validation_status, output) = executor.execute_writeset(
+        &Account::new_libra_root(),
+        ChangeSet::new(new_account_data.to_writeset(), vec![]),
+    );
+    assert_eq!(validation_status, None);
+    assert_eq!(
+        output.status(),
+        &TransactionStatus::Keep(VMStatus::Executed)
+    );
+
+    let new_account = executor
+        .read_account_resource(new_account_data.account())
+        .expect("account must exist");
+    assert_eq!(10, new_account.sequence_number());
+}
+
+#[test]
+fn writeset_prologue_failures_match_validator() {
+    let mut executor = FakeExecutor::from_genesis_file();
+    executor.new_block();
+    let sender = AccountData::new(1000, 10);
+    executor.add_account_data(&sender);
+
+    // Only the libra root account may send write sets.
+    let (validation_status, output) = executor.execute_writeset(
+        sender.account(),
+        ChangeSet::new(AccountData::new(0, 0).to_writeset(), vec![]),
+    );
+    let validation_status = validation_status.expect("validator must reject the write set");
+    assert_eq!(validation_status.status_code(), StatusCode::ABORTED);
+    assert_eq!(validation_status.move_abort_code(), Some(33));
+    assert!(output.status().is_discarded());
+    assert_eq!(output.status().vm_status().move_abort_code(), Some(33));
+}
+
+#[test]
+fn invalid_writesets_pass_validation() {
+    let mut executor = FakeExecutor::from_genesis_file();
+    let libra_root = Account::new_libra_root();
+    executor.new_block();
+
+    // The validator only runs the write set prologue, so write sets that conflict with the effects
+    // of the epilogue are accepted by it and only discarded at execution.
+    let write_set = WriteSetMut::new(vec![(
+        libra_root.make_account_access_path(),
+        WriteOp::Value(vec![]),
+    )])
+    .freeze()
+    .unwrap();
+    let (validation_status, output) =
+        executor.execute_writeset(&libra_root, ChangeSet::new(write_set, vec![]));
+    assert_eq!(validation_status, None);
+    assert_eq!(
+        output.status(),
+        &TransactionStatus::Discard(VMStatus::Error(StatusCode::INVALID_WRITE_SET))
+    );
+
+    // The same holds for write sets that emit their own reconfiguration event.
+    let event = ContractEvent::new(new_epoch_event_key(), 0, lbr_type_tag(), vec![]);
+    let (validation_status, output) = executor.execute_writeset(
+        &libra_root,
+        ChangeSet::new(AccountData::new(0, 0).to_writeset(), vec![event]),
+    );
+    assert_eq!(validation_status, None);
+    assert_eq!(
+        output.status(),
+        &TransactionStatus::Discard(VMStatus::Error(StatusCode::INVALID_WRITE_SET))
+    );
+}
@@ -0,0 +1,166 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Proptest strategies for generating well-typed transactions, both valid ones and ones the
+//! prologue is expected to reject.
+
+use crate::{
+    account::{lbr_currency_code, Account, AccountData},
+    gas_costs,
+};
+use libra_types::{
+    account_address::AccountAddress,
+    account_config::{lbr_type_tag, LBR_NAME},
+    transaction::{SignedTransaction, TransactionPayload, MAX_TRANSACTION_SIZE_IN_BYTES},
+    vm_status::StatusCode,
+};
+use proptest::prelude::*;
+use proptest_derive::Arbitrary;
+use transaction_builder::encode_peer_to_peer_with_metadata_script;
+
+/// The kinds of transactions a [`TxnGenerator`] can produce.
+#[derive(Arbitrary, Clone, Copy, Debug, Eq, PartialEq)]
+pub enum TxnVariant {
+    /// A peer-to-peer payment the sender can afford.
+    Valid,
+    /// Signed by a key other than the sender's.
+    BadAuthKey,
+    /// Uses a sequence number the sender has already used.
+    StaleSequenceNumber,
+    /// Carries metadata larger than the maximum transaction size.
+    OversizedPayload,
+    /// Pays for gas with a currency code that is not a valid identifier.
+    WrongGasCurrency,
+}
+
+impl TxnVariant {
+    /// Returns the status that both the validator and the executor reject a transaction of this
+    /// variant with, or `None` if the transaction passes the prologue.
+    pub fn expected_status(self) -> Option<StatusCode> {
+        match self {
+            TxnVariant::Valid => None,
+            TxnVariant::BadAuthKey => Some(StatusCode::INVALID_AUTH_KEY),
+            TxnVariant::StaleSequenceNumber => Some(StatusCode::SEQUENCE_NUMBER_TOO_OLD),
+            TxnVariant::OversizedPayload => Some(StatusCode::EXCEEDED_MAX_TRANSACTION_SIZE),
+            TxnVariant::WrongGasCurrency => Some(StatusCode::INVALID_GAS_SPECIFIER),
+        }
+    }
+}
+
+/// Generates peer-to-peer transactions from a sender to a receiver whose state is known.
+///
+/// Both accounts must have been added to the executor the transactions are run against, and
+/// neither may have sent a transaction since.
+#[derive(Clone, Debug)]
+pub struct TxnGenerator {
+    sender: Account,
+    receiver: AccountAddress,
+    sequence_number: u64,
+    balance: u64,
+}
+
+impl TxnGenerator {
+    /// Creates a generator for transactions sent by `sender` to `receiver`.
+    ///
+    /// `sender` must hold LBR and have a non-zero sequence number, so that both affordable and
+    /// stale transactions exist.
+    pub fn new(sender: &AccountData, receiver: AccountAddress) -> Self {
+        let balance = sender.balance(&lbr_currency_code());
+        assert!(balance > 0, "sender must hold LBR");
+        assert!(
+            sender.sequence_number() > 0,
+            "sender must have a non-zero sequence number"
+        );
+        Self {
+            sender: sender.account().clone(),
+            receiver,
+            sequence_number: sender.sequence_number(),
+            balance,
+        }
+    }
+
+    /// Returns a strategy that generates transactions of any variant, paired with the variant.
+    pub fn strategy(&self) -> impl Strategy<Value = (TxnVariant, SignedTransaction)> {
+        let generator = self.clone();
+        any::<TxnVariant>()
+            .prop_flat_map(move |variant| (Just(variant), generator.variant_strategy(variant)))
+    }
+
+    /// Returns a strategy that generates transactions of the given variant.
+    pub fn variant_strategy(&self, variant: TxnVariant) -> BoxedStrategy<SignedTransaction> {
+        let generator = self.clone();
+        let amount = 1..=self.balance;
+        match variant {
+            TxnVariant::Valid => amount
+                .prop_map(move |amount| {
+                    generator.p2p_txn(
+                        &generator.sender,
+                        generator.sequence_number,
+                        amount,
+                        vec![],
+                        LBR_NAME,
+                    )
+                })
+                .boxed(),
+            TxnVariant::BadAuthKey => (any::<Account>(), amount)
+                .prop_map(move |(signer, amount)| {
+                    generator.p2p_txn(&signer, generator.sequence_number, amount, vec![], LBR_NAME)
+                })
+                .boxed(),
+            TxnVariant::StaleSequenceNumber => (0..self.sequence_number, amount)
+                .prop_map(move |(sequence_number, amount)| {
+                    generator.p2p_txn(&generator.sender, sequence_number, amount, vec![], LBR_NAME)
+                })
+                .boxed(),
+            TxnVariant::OversizedPayload => (
+                MAX_TRANSACTION_SIZE_IN_BYTES..2 * MAX_TRANSACTION_SIZE_IN_BYTES,
+                amount,
+            )
+                .prop_map(move |(metadata_len, amount)| {
+                    generator.p2p_txn(
+                        &generator.sender,
+                        generator.sequence_number,
+                        amount,
+                        vec![0; metadata_len],
+                        LBR_NAME,
+                    )
+                })
+                .boxed(),
+            TxnVariant::WrongGasCurrency => ("[0-9][A-Za-z0-9]{0,7}", amount)
+                .prop_map(move |(gas_currency_code, amount)| {
+                    generator.p2p_txn(
+                        &generator.sender,
+                        generator.sequence_number,
+                        amount,
+                        vec![],
+                        &gas_currency_code,
+                    )
+                })
+                .boxed(),
+        }
+    }
+
+    fn p2p_txn(
+        &self,
+        signer: &Account,
+        sequence_number: u64,
+        amount: u64,
+        metadata: Vec<u8>,
+        gas_currency_code: &str,
+    ) -> SignedTransaction {
+        signer.create_signed_txn_impl(
+            *self.sender.address(),
+            TransactionPayload::Script(encode_peer_to_peer_with_metadata_script(
+                lbr_type_tag(),
+                self.receiver,
+                amount,
+                metadata,
+                vec![],
+            )),
+            sequence_number,
+            gas_costs::TXN_RESERVED,
+            0,
+            gas_currency_code.to_owned(),
+        )
+    }
+}
@@ -118,7 +118,8 @@ pub mod libra_transaction_validator;
 pub mod system_module_names;
 
 pub use crate::{
-    libra_transaction_executor::LibraVM, libra_transaction_validator::LibraVMValidator,
+    libra_transaction_executor::LibraVM,
+    libra_transaction_validator::{LibraVMValidator, ValidationMode},
     libra_vm::txn_effects_to_writeset_and_events,
 };
 
@@ -19,8 +19,8 @@ use libra_types::{
     account_config,
     block_metadata::BlockMetadata,
     transaction::{
-        ChangeSet, Module, Script, SignatureCheckedTransaction, Transaction, TransactionArgument,
-        TransactionOutput, TransactionPayload, TransactionStatus,
+        ChangeSet, Module, Script, ScriptFunction, SignatureCheckedTransaction, SignedTransaction,
+        Transaction, TransactionArgument, TransactionOutput, TransactionPayload, TransactionStatus,
     },
     vm_status::{StatusCode, VMStatus},
     write_set::{WriteSet, WriteSetMut},
@@ -32,7 +32,7 @@ use move_core_types::{
 use move_vm_runtime::{data_cache::RemoteCache, session::Session};
 
 use move_vm_types::{
-    gas_schedule::{zero_cost_schedule, CostStrategy},
+    gas_schedule::{zero_cost_schedule, CostStrategy, ExecutionLimits, GasProfile},
     values::Value,
 };
 use rayon::prelude::*;
@@ -131,6 +131,7 @@ impl LibraVM {
                 &txn_data,
                 account_currency_symbol,
             )?;
+            self.0.check_secondary_signers(txn_data, remote_cache)?;
         }
 
         // Run the execution logic
@@ -141,16 +142,81 @@ impl LibraVM {
                 .charge_intrinsic_gas(txn_data.transaction_size())
                 .map_err(|e| e.into_vm_status())?;
             session
-                .execute_script(
+                .execute_script_with_signers(
                     script.code().to_vec(),
                     script.ty_args().to_vec(),
                     convert_txn_args(script.args()),
-                    txn_data.sender(),
+                    txn_data.signers(),
                     cost_strategy,
                 )
                 .map_err(|e| e.into_vm_status())?;
 
-            charge_global_write_gas_usage(cost_strategy, &session)?;
+            charge_global_write_gas_usage(
+                cost_strategy,
+                &mut session,
+                self.0.get_write_set_limits()?.as_ref(),
+            )?;
+
+            cost_strategy.disable_metering();
+            self.success_transaction_cleanup(
+                session,
+                gas_schedule,
+                cost_strategy.remaining_gas(),
+                txn_data,
+                account_currency_symbol,
+            )
+        }
+    }
+
+    fn execute_script_function(
+        &self,
+        remote_cache: &StateViewCache<'_>,
+        cost_strategy: &mut CostStrategy,
+        txn_data: &TransactionMetadata,
+        script_function: &ScriptFunction,
+        account_currency_symbol: &IdentStr,
+    ) -> Result<TransactionOutput, VMStatus> {
+        let gas_schedule = self.0.get_gas_schedule()?;
+        let mut session = self.0.new_session(remote_cache);
+
+        // Run the validation logic
+        {
+            cost_strategy.disable_metering();
+            let _timer = TXN_VERIFICATION_SECONDS.start_timer();
+            self.0.check_gas(txn_data)?;
+            self.0.is_allowed_script_function(script_function)?;
+            self.0.run_prologue(
+                &mut session,
+                cost_strategy,
+                &txn_data,
+                account_currency_symbol,
+            )?;
+            self.0.check_secondary_signers(txn_data, remote_cache)?;
+        }
+
+        // Run the execution logic
+        {
+            let _timer = TXN_EXECUTION_SECONDS.start_timer();
+            cost_strategy.enable_metering();
+            cost_strategy
+                .charge_intrinsic_gas(txn_data.transaction_size())
+                .map_err(|e| e.into_vm_status())?;
+            session
+                .execute_script_function(
+                    script_function.module(),
+                    script_function.function(),
+                    script_function.ty_args().to_vec(),
+                    script_function.args().to_vec(),
+                    txn_data.signers(),
+                    cost_strategy,
+                )
+                .map_err(|e| e.into_vm_status())?;
+
+            charge_global_write_gas_usage(
+                cost_strategy,
+                &mut session,
+                self.0.get_write_set_limits()?.as_ref(),
+            )?;
 
             cost_strategy.disable_metering();
             self.success_transaction_cleanup(
@@ -184,6 +250,7 @@ impl LibraVM {
             txn_data,
             account_currency_symbol,
         )?;
+        self.0.check_secondary_signers(txn_data, remote_cache)?;
 
         // Publish the module
         let module_address = if self.0.on_chain_config()?.publishing_option.is_open_module() {
@@ -200,7 +267,11 @@ impl LibraVM {
             .publish_module(module.code().to_vec(), module_address, cost_strategy)
             .map_err(|e| e.into_vm_status())?;
 
-        charge_global_write_gas_usage(cost_strategy, &session)?;
+        charge_global_write_gas_usage(
+            cost_strategy,
+            &mut session,
+            self.0.get_write_set_limits()?.as_ref(),
+        )?;
 
         self.success_transaction_cleanup(
             session,
@@ -216,11 +287,22 @@ impl LibraVM {
         remote_cache: &StateViewCache<'_>,
         txn: &SignatureCheckedTransaction,
     ) -> TransactionOutput {
+        self.execute_user_transaction_impl(remote_cache, txn, false, None)
+            .0
+    }
+
+    fn execute_user_transaction_impl(
+        &mut self,
+        remote_cache: &StateViewCache<'_>,
+        txn: &SignatureCheckedTransaction,
+        profile_gas: bool,
+        execution_limits: Option<ExecutionLimits>,
+    ) -> (TransactionOutput, Option<GasProfile>) {
         macro_rules! unwrap_or_discard {
             ($res: expr) => {
                 match $res {
                     Ok(s) => s,
-                    Err(e) => return discard_error_output(e),
+                    Err(e) => return (discard_error_output(e), None),
                 }
             };
         }
@@ -228,6 +310,12 @@ impl LibraVM {
         let gas_schedule = unwrap_or_discard!(self.0.get_gas_schedule());
         let txn_data = TransactionMetadata::new(txn);
         let mut cost_strategy = CostStrategy::system(gas_schedule, txn_data.max_gas_amount());
+        if profile_gas {
+            cost_strategy.enable_profiling();
+        }
+        if let Some(limits) = execution_limits {
+            cost_strategy.enable_execution_limits(limits);
+        }
         let account_currency_symbol = unwrap_or_discard!(
             account_config::from_currency_code_string(txn.gas_currency_code())
                 .map_err(|_| VMStatus::Error(StatusCode::INVALID_GAS_SPECIFIER))
@@ -240,6 +328,13 @@ impl LibraVM {
                 s,
                 account_currency_symbol.as_ident_str(),
             ),
+            TransactionPayload::ScriptFunction(f) => self.execute_script_function(
+                remote_cache,
+                &mut cost_strategy,
+                &txn_data,
+                f,
+                account_currency_symbol.as_ident_str(),
+            ),
             TransactionPayload::Module(m) => self.execute_module(
                 remote_cache,
                 &mut cost_strategy,
@@ -248,7 +343,10 @@ impl LibraVM {
                 account_currency_symbol.as_ident_str(),
             ),
             TransactionPayload::WriteSet(_) => {
-                return discard_error_output(VMStatus::Error(StatusCode::UNREACHABLE))
+                return (
+                    discard_error_output(VMStatus::Error(StatusCode::UNREACHABLE)),
+                    None,
+                )
             }
         };
 
@@ -257,8 +355,9 @@ impl LibraVM {
             .sub(cost_strategy.remaining_gas())
             .get();
         TXN_GAS_USAGE.observe(gas_usage as f64);
+        let gas_profile = cost_strategy.take_gas_profile();
 
-        match result {
+        let output = match result {
             Ok(output) => output,
             Err(err) => {
                 let txn_status = TransactionStatus::from(err.clone());
@@ -275,7 +374,67 @@ impl LibraVM {
                     )
                 }
             }
-        }
+        };
+        (output, gas_profile)
+    }
+
+    /// Executes a single user transaction against `state_view` and returns its output together
+    /// with a breakdown of the gas it was charged. The output is not applied anywhere.
+    ///
+    /// This is only meant for tests and tooling; block execution should go through
+    /// `VMExecutor::execute_block`.
+    pub fn execute_user_transaction_with_gas_profile(
+        state_view: &dyn StateView,
+        txn: SignedTransaction,
+    ) -> (TransactionOutput, GasProfile) {
+        let checked_txn = match txn.check_signature() {
+            Ok(checked_txn) => checked_txn,
+            Err(_) => {
+                return (
+                    discard_error_output(VMStatus::Error(StatusCode::INVALID_SIGNATURE)),
+                    GasProfile::default(),
+                )
+            }
+        };
+        let state_view_cache = StateViewCache::new(state_view);
+        let mut vm = LibraVM::new(&state_view_cache);
+        let (output, gas_profile) =
+            vm.execute_user_transaction_impl(&state_view_cache, &checked_txn, true, None);
+        (output, gas_profile.unwrap_or_default())
+    }
+
+    /// Executes a single user transaction against `state_view` and returns its output, which is
+    /// not applied anywhere, to preview what the transaction would do before submitting it.
+    ///
+    /// If `check_signature` is false, the transaction is executed whatever its signature, though
+    /// the prologue still checks the public key against the sender's authentication key. If
+    /// `execution_limits` are given, the transaction fails with a distinct status once its code
+    /// exceeds them, whatever the gas left.
+    pub fn simulate_user_transaction(
+        state_view: &dyn StateView,
+        txn: SignedTransaction,
+        check_signature: bool,
+        execution_limits: Option<ExecutionLimits>,
+    ) -> TransactionOutput {
+        let checked_txn = if check_signature {
+            match txn.check_signature() {
+                Ok(checked_txn) => checked_txn,
+                Err(_) => {
+                    return discard_error_output(VMStatus::Error(StatusCode::INVALID_SIGNATURE))
+                }
+            }
+        } else {
+            txn.skip_signature_check()
+        };
+        let state_view_cache = StateViewCache::new(state_view);
+        let mut vm = LibraVM::new(&state_view_cache);
+        let (output, _) = vm.execute_user_transaction_impl(
+            &state_view_cache,
+            &checked_txn,
+            false,
+            execution_limits,
+        );
+        output
     }
 
     fn read_writeset(
@@ -27,16 +27,46 @@ use move_vm_types::gas_schedule::CostStrategy;
 /// other transactions.
 const PRIORITIZED_TRANSACTION_ROLE_CUTOFF: u64 = 5;
 
+/// How thoroughly the validator checks a transaction before accepting it.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ValidationMode {
+    /// Check the signature, gas and publishing options, then run the prologue.
+    Prologue,
+    /// Additionally verify the script and link it against its dependencies, or verify the module,
+    /// so that transactions failing verification at execution time are rejected up front instead
+    /// of being charged gas for the failure.
+    Full,
+}
+
+impl Default for ValidationMode {
+    fn default() -> Self {
+        ValidationMode::Prologue
+    }
+}
+
 #[derive(Clone)]
-pub struct LibraVMValidator(LibraVMImpl);
+pub struct LibraVMValidator(LibraVMImpl, ValidationMode);
 
 impl LibraVMValidator {
     pub fn new<S: StateView>(state: &S) -> Self {
-        Self(LibraVMImpl::new(state))
+        Self(LibraVMImpl::new(state), ValidationMode::default())
     }
 
     pub fn init_with_config(version: LibraVersion, on_chain_config: VMConfig) -> Self {
-        LibraVMValidator(LibraVMImpl::init_with_config(version, on_chain_config))
+        LibraVMValidator(
+            LibraVMImpl::init_with_config(version, on_chain_config),
+            ValidationMode::default(),
+        )
+    }
+
+    /// Sets how thoroughly transactions are validated.
+    pub fn with_validation_mode(mut self, mode: ValidationMode) -> Self {
+        self.1 = mode;
+        self
+    }
+
+    pub fn validation_mode(&self) -> ValidationMode {
+        self.1
     }
 
     fn verify_transaction_impl(
@@ -57,9 +87,27 @@ impl LibraVMValidator {
                     &mut cost_strategy,
                     &txn_data,
                     account_currency_symbol,
-                )
+                )?;
+                self.0.check_secondary_signers(&txn_data, remote_cache)?;
+                if self.1 == ValidationMode::Full {
+                    session
+                        .verify_script(script.code(), script.ty_args())
+                        .map_err(|e| e.into_vm_status())?;
+                }
+                Ok(())
             }
-            TransactionPayload::Module(_module) => {
+            TransactionPayload::ScriptFunction(script_function) => {
+                self.0.check_gas(&txn_data)?;
+                self.0.is_allowed_script_function(script_function)?;
+                self.0.run_prologue(
+                    &mut session,
+                    &mut cost_strategy,
+                    &txn_data,
+                    account_currency_symbol,
+                )?;
+                self.0.check_secondary_signers(&txn_data, remote_cache)
+            }
+            TransactionPayload::Module(module) => {
                 self.0.check_gas(&txn_data)?;
                 self.0.is_allowed_module(&txn_data, remote_cache)?;
                 self.0.run_prologue(
@@ -67,7 +115,14 @@ impl LibraVMValidator {
                     &mut cost_strategy,
                     &txn_data,
                     account_currency_symbol,
-                )
+                )?;
+                self.0.check_secondary_signers(&txn_data, remote_cache)?;
+                if self.1 == ValidationMode::Full {
+                    session
+                        .verify_module(module.code())
+                        .map_err(|e| e.into_vm_status())?;
+                }
+                Ok(())
             }
             TransactionPayload::WriteSet(_cs) => {
                 self.0.run_writeset_prologue(&mut session, &txn_data)
@@ -86,6 +141,9 @@ impl VMValidator for LibraVMValidator {
     /// 2. The script to be executed is under given specific configuration.
     /// 3. Invokes `LibraAccount.prologue`, which checks properties such as the transaction has the
     /// right sequence number and the sender has enough balance to pay for the gas.
+    /// 4. The secondary signers of a multi-agent transaction exist and their authentication keys
+    ///    match the keys that signed the transaction.
+    /// 5. With `ValidationMode::Full`, verifies the script and its dependencies, or the module.
     /// TBD:
     /// 1. Transaction arguments matches the main function's type signature.
     ///    We don't check this item for now and would execute the check at execution time.
@@ -4,6 +4,7 @@
 use crate::{
     access_path_cache::AccessPathCache,
     counters::*,
+    create_access_path,
     data_cache::{RemoteStorage, StateViewCache},
     system_module_names::*,
     transaction_metadata::TransactionMetadata,
@@ -12,11 +13,13 @@ use crate::{
 use libra_logger::prelude::*;
 use libra_state_view::StateView;
 use libra_types::{
-    account_config,
+    account_config::{self, AccountResource},
     contract_event::ContractEvent,
     event::EventKey,
-    on_chain_config::{ConfigStorage, LibraVersion, OnChainConfig, VMConfig},
-    transaction::{ChangeSet, Script, TransactionOutput, TransactionStatus},
+    on_chain_config::{
+        ConfigStorage, LibraVersion, OnChainConfig, OnChainWriteSetLimits, VMConfig,
+    },
+    transaction::{ChangeSet, Script, ScriptFunction, TransactionOutput, TransactionStatus},
     vm_status::{convert_prologue_runtime_error, StatusCode, VMStatus},
     write_set::{WriteOp, WriteSet, WriteSetMut},
 };
@@ -24,12 +27,13 @@ use move_core_types::{
     gas_schedule::{CostTable, GasAlgebra, GasUnits},
     identifier::IdentStr,
     language_storage::TypeTag,
+    move_resource::MoveResource,
 };
 
 use move_vm_runtime::{
     data_cache::{RemoteCache, TransactionEffects},
     move_vm::MoveVM,
-    session::Session,
+    session::{Session, WriteSetLimits},
 };
 use move_vm_types::{
     gas_schedule::{calculate_intrinsic_gas, zero_cost_schedule, CostStrategy},
@@ -38,12 +42,17 @@ use move_vm_types::{
 use std::{convert::TryFrom, sync::Arc};
 use vm::errors::Location;
 
+/// The Libra version from which the write sets of transactions are bounded by `WriteSetLimits`
+/// and charged per byte written, rather than per account mutated.
+const WRITE_SET_LIMITS_LIBRA_VERSION: u64 = 2;
+
 #[derive(Clone)]
 /// A wrapper to make VMRuntime standalone and thread safe.
 pub struct LibraVMImpl {
     move_vm: Arc<MoveVM>,
     on_chain_config: Option<VMConfig>,
     version: Option<LibraVersion>,
+    write_set_limits: Option<OnChainWriteSetLimits>,
 }
 
 impl LibraVMImpl {
@@ -54,6 +63,7 @@ impl LibraVMImpl {
             move_vm: Arc::new(inner),
             on_chain_config: None,
             version: None,
+            write_set_limits: None,
         };
         vm.load_configs_impl(&RemoteStorage::new(state));
         vm
@@ -65,6 +75,7 @@ impl LibraVMImpl {
             move_vm: Arc::new(inner),
             on_chain_config: Some(on_chain_config),
             version: Some(version),
+            write_set_limits: None,
         }
     }
 
@@ -83,6 +94,7 @@ impl LibraVMImpl {
     fn load_configs_impl<S: ConfigStorage>(&mut self, data_cache: &S) {
         self.on_chain_config = VMConfig::fetch_config(data_cache);
         self.version = LibraVersion::fetch_config(data_cache);
+        self.write_set_limits = OnChainWriteSetLimits::fetch_config(data_cache);
     }
 
     pub fn get_gas_schedule(&self) -> Result<&CostTable, VMStatus> {
@@ -102,6 +114,22 @@ impl LibraVMImpl {
         })
     }
 
+    /// Returns the bounds on the write sets of transactions, those published on chain if any, or
+    /// `None` if the Libra version predates them.
+    pub(crate) fn get_write_set_limits(&self) -> Result<Option<WriteSetLimits>, VMStatus> {
+        if self.get_libra_version()?.major < WRITE_SET_LIMITS_LIBRA_VERSION {
+            return Ok(None);
+        }
+        Ok(Some(match &self.write_set_limits {
+            Some(limits) => WriteSetLimits {
+                max_write_ops: limits.max_write_ops,
+                max_write_bytes: limits.max_write_bytes,
+                max_events: limits.max_events,
+            },
+            None => WriteSetLimits::default(),
+        }))
+    }
+
     pub fn check_gas(&self, txn_data: &TransactionMetadata) -> Result<(), VMStatus> {
         let gas_constants = &self.get_gas_schedule()?.gas_constants;
         let raw_bytes_len = txn_data.transaction_size;
@@ -188,6 +216,24 @@ impl LibraVMImpl {
         }
     }
 
+    /// Script functions carry no code to check against the script whitelist, but can call any
+    /// public function, so they are only allowed where custom scripts are.
+    pub(crate) fn is_allowed_script_function(
+        &self,
+        script_function: &ScriptFunction,
+    ) -> Result<(), VMStatus> {
+        if !self.on_chain_config()?.publishing_option.is_open_script() {
+            warn!(
+                "[VM] Script functions not allowed: {}::{}",
+                script_function.module().name(),
+                script_function.function()
+            );
+            Err(VMStatus::Error(StatusCode::UNKNOWN_SCRIPT))
+        } else {
+            Ok(())
+        }
+    }
+
     pub(crate) fn is_allowed_module(
         &self,
         txn_data: &TransactionMetadata,
@@ -205,6 +251,34 @@ impl LibraVMImpl {
         }
     }
 
+    /// Checks that every secondary signer of a multi-agent transaction has an account whose
+    /// authentication key matches the key that signed for it. The prologue only checks the sender.
+    pub(crate) fn check_secondary_signers(
+        &self,
+        txn_data: &TransactionMetadata,
+        remote_cache: &StateViewCache,
+    ) -> Result<(), VMStatus> {
+        for (signer, authentication_key) in txn_data
+            .secondary_signers
+            .iter()
+            .zip(&txn_data.secondary_authentication_keys)
+        {
+            let account_access_path = create_access_path(*signer, AccountResource::struct_tag());
+            let account = match remote_cache
+                .get(&account_access_path)
+                .map_err(|_| VMStatus::Error(StatusCode::STORAGE_ERROR))?
+            {
+                Some(blob) => lcs::from_bytes::<AccountResource>(&blob)
+                    .map_err(|_| VMStatus::Error(StatusCode::UNKNOWN_INVARIANT_VIOLATION_ERROR))?,
+                None => return Err(VMStatus::Error(StatusCode::SENDING_ACCOUNT_DOES_NOT_EXIST)),
+            };
+            if account.authentication_key() != authentication_key.as_slice() {
+                return Err(VMStatus::Error(StatusCode::INVALID_AUTH_KEY));
+            }
+        }
+        Ok(())
+    }
+
     /// Run the prologue of a transaction by calling into `PROLOGUE_NAME` function stored
     /// in the `ACCOUNT_MODULE` on chain.
     pub(crate) fn run_prologue<R: RemoteCache>(
@@ -419,21 +493,15 @@ pub fn txn_effects_to_writeset_and_events_cached<C: AccessPathCache>(
     let mut ops = vec![];
 
     for (addr, vals) in effects.resources {
-        for (ty_tag, val_opt) in vals {
+        for (ty_tag, blob_opt) in vals {
             let struct_tag = match ty_tag {
                 TypeTag::Struct(struct_tag) => struct_tag,
                 _ => return Err(VMStatus::Error(StatusCode::VALUE_SERIALIZATION_ERROR)),
             };
             let ap = ap_cache.get_resource_path(addr, struct_tag);
-            let op = match val_opt {
+            let op = match blob_opt {
                 None => WriteOp::Deletion,
-                Some((ty_layout, val)) => {
-                    let blob = val
-                        .simple_serialize(&ty_layout)
-                        .ok_or_else(|| VMStatus::Error(StatusCode::VALUE_SERIALIZATION_ERROR))?;
-
-                    WriteOp::Value(blob)
-                }
+                Some(blob) => WriteOp::Value(blob),
             };
             ops.push((ap, op))
         }
@@ -463,10 +531,18 @@ pub fn txn_effects_to_writeset_and_events_cached<C: AccessPathCache>(
     Ok((ws, events))
 }
 
+/// Charges for what the session writes, bounded by `limits` if any, and otherwise charged for
+/// `default_account_size` bytes per account mutated.
 pub(crate) fn charge_global_write_gas_usage<R: RemoteCache>(
     cost_strategy: &mut CostStrategy,
-    session: &Session<R>,
+    session: &mut Session<R>,
+    limits: Option<&WriteSetLimits>,
 ) -> Result<(), VMStatus> {
+    if let Some(limits) = limits {
+        return session
+            .charge_write_set(limits, cost_strategy)
+            .map_err(|e| e.into_vm_status());
+    }
     let total_cost = session.num_mutated_accounts()
         * cost_strategy
             .cost_table()
@@ -14,6 +14,8 @@ use std::{convert::TryFrom, time::Duration};
 pub struct TransactionMetadata {
     pub sender: AccountAddress,
     pub authentication_key_preimage: Vec<u8>,
+    pub secondary_signers: Vec<AccountAddress>,
+    pub secondary_authentication_keys: Vec<Vec<u8>>,
     pub sequence_number: u64,
     pub max_gas_amount: GasUnits<GasCarrier>,
     pub gas_unit_price: GasPrice<GasCarrier>,
@@ -29,6 +31,13 @@ impl TransactionMetadata {
                 .authenticator()
                 .authentication_key_preimage()
                 .into_vec(),
+            secondary_signers: txn.authenticator().secondary_signer_addresses(),
+            secondary_authentication_keys: txn
+                .authenticator()
+                .secondary_signers()
+                .iter()
+                .map(|signer| signer.authentication_key().to_vec())
+                .collect(),
             sequence_number: txn.sequence_number(),
             max_gas_amount: GasUnits::new(txn.max_gas_amount()),
             gas_unit_price: GasPrice::new(txn.gas_unit_price()),
@@ -53,6 +62,17 @@ impl TransactionMetadata {
         &self.authentication_key_preimage
     }
 
+    pub fn secondary_signers(&self) -> &[AccountAddress] {
+        &self.secondary_signers
+    }
+
+    /// The sender followed by the secondary signers, in the order they are passed to the script.
+    pub fn signers(&self) -> Vec<AccountAddress> {
+        let mut signers = vec![self.sender];
+        signers.extend_from_slice(&self.secondary_signers);
+        signers
+    }
+
     pub fn sequence_number(&self) -> u64 {
         self.sequence_number
     }
@@ -74,6 +94,8 @@ impl Default for TransactionMetadata {
         TransactionMetadata {
             sender: AccountAddress::ZERO,
             authentication_key_preimage: AuthenticationKeyPreimage::ed25519(&public_key).into_vec(),
+            secondary_signers: vec![],
+            secondary_authentication_keys: vec![],
             sequence_number: 0,
             max_gas_amount: GasUnits::new(100_000_000),
             gas_unit_price: GasPrice::new(0),
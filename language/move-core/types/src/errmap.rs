@@ -0,0 +1,87 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Mapping of Move abort codes to the error constants they were raised with.
+//!
+//! A module aborts with a plain `u64`. The `ErrorMapping` of a set of modules records, for every
+//! module, the name and documentation of the error constant behind each code, so that an abort
+//! can be reported as `LibraAccount::EINSUFFICIENT_BALANCE` rather than as code 5.
+
+use crate::language_storage::ModuleId;
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeMap, fmt};
+
+/// A named error code.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct ErrorDescription {
+    /// The name of the constant defining the code, e.g. `EINSUFFICIENT_BALANCE`.
+    pub code_name: String,
+    /// The doc comment of the constant, or an empty string if it has none.
+    pub code_description: String,
+}
+
+/// The explanation of an abort code raised by a module.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ErrorContext {
+    /// The category of the error, if the abort code encodes one.
+    pub category: Option<ErrorDescription>,
+    /// The reason for the error.
+    pub reason: ErrorDescription,
+}
+
+impl fmt::Display for ErrorContext {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if let Some(category) = &self.category {
+            write!(f, "{}: ", category.code_name)?;
+        }
+        write!(f, "{}", self.reason.code_name)?;
+        if !self.reason.code_description.is_empty() {
+            write!(f, " ({})", self.reason.code_description)?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+pub struct ErrorMapping {
+    /// The error categories, by category code. When this is not empty, the lowest byte of an
+    /// abort code is its category and the remaining bytes are its reason. Otherwise the whole
+    /// abort code is the reason.
+    pub error_categories: BTreeMap<u64, ErrorDescription>,
+    /// The reasons each module aborts with, by reason code.
+    pub module_error_maps: BTreeMap<ModuleId, BTreeMap<u64, ErrorDescription>>,
+}
+
+impl ErrorMapping {
+    /// Record `description` as the reason `code` of `module`, returning the description it
+    /// replaced, if any.
+    pub fn add_module_error(
+        &mut self,
+        module: ModuleId,
+        code: u64,
+        description: ErrorDescription,
+    ) -> Option<ErrorDescription> {
+        self.module_error_maps
+            .entry(module)
+            .or_default()
+            .insert(code, description)
+    }
+
+    /// Return the explanation of the abort code `code` raised by `module`, or `None` if the
+    /// module or its reason is unknown. An unknown category is left out of the explanation.
+    pub fn get_explanation(&self, module: &ModuleId, code: u64) -> Option<ErrorContext> {
+        let (category, reason_code) = if self.error_categories.is_empty() {
+            (None, code)
+        } else {
+            (
+                self.error_categories.get(&(code & 0xff)).cloned(),
+                code >> 8,
+            )
+        };
+        let reason = self.module_error_maps.get(module)?.get(&reason_code)?;
+        Some(ErrorContext {
+            category,
+            reason: reason.clone(),
+        })
+    }
+}
@@ -271,3 +271,19 @@ pub fn words_in(size: AbstractMemorySize<GasCarrier>) -> AbstractMemorySize<GasC
         (size + (word_size - 1)) / word_size
     })
 }
+
+/// Calculate the intrinsic gas for the transaction based upon its size in bytes/words.
+pub fn calculate_intrinsic_gas(
+    transaction_size: AbstractMemorySize<GasCarrier>,
+    gas_constants: &GasConstants,
+) -> GasUnits<GasCarrier> {
+    precondition!(transaction_size.get() <= gas_constants.max_transaction_size_in_bytes);
+    let min_transaction_fee = gas_constants.min_transaction_gas_units;
+
+    if transaction_size.get() > gas_constants.large_transaction_cutoff.get() {
+        let excess = words_in(transaction_size.sub(gas_constants.large_transaction_cutoff));
+        min_transaction_fee.add(gas_constants.instrinsic_gas_per_byte.mul(excess))
+    } else {
+        min_transaction_fee.unitary_cast()
+    }
+}
@@ -4,6 +4,7 @@
 //! Core types for Move.
 
 pub mod account_address;
+pub mod errmap;
 pub mod gas_schedule;
 pub mod identifier;
 pub mod language_storage;
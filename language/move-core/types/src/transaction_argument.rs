@@ -1,7 +1,7 @@
 // Copyright (c) The Libra Core Contributors
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::account_address::AccountAddress;
+use crate::{account_address::AccountAddress, language_storage::TypeTag};
 use serde::{Deserialize, Serialize};
 use std::fmt;
 
@@ -29,3 +29,16 @@ impl fmt::Debug for TransactionArgument {
         }
     }
 }
+
+/// Returns whether `arg` is a value of the Move type `type_tag`.
+pub fn is_argument_of_type(arg: &TransactionArgument, type_tag: &TypeTag) -> bool {
+    match (arg, type_tag) {
+        (TransactionArgument::U8(_), TypeTag::U8)
+        | (TransactionArgument::U64(_), TypeTag::U64)
+        | (TransactionArgument::U128(_), TypeTag::U128)
+        | (TransactionArgument::Address(_), TypeTag::Address)
+        | (TransactionArgument::Bool(_), TypeTag::Bool) => true,
+        (TransactionArgument::U8Vector(_), TypeTag::Vector(inner)) => **inner == TypeTag::U8,
+        _ => false,
+    }
+}
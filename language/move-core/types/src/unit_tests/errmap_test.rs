@@ -0,0 +1,69 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    errmap::{ErrorDescription, ErrorMapping},
+    identifier::Identifier,
+    language_storage::{ModuleId, CORE_CODE_ADDRESS},
+};
+
+fn description(code_name: &str) -> ErrorDescription {
+    ErrorDescription {
+        code_name: code_name.to_string(),
+        code_description: format!("{} happened", code_name),
+    }
+}
+
+fn module(name: &str) -> ModuleId {
+    ModuleId::new(CORE_CODE_ADDRESS, Identifier::new(name).unwrap())
+}
+
+#[test]
+fn test_explanation_without_categories() {
+    let mut mapping = ErrorMapping::default();
+    mapping.add_module_error(
+        module("LibraAccount"),
+        5,
+        description("EINSUFFICIENT_BALANCE"),
+    );
+
+    let context = mapping.get_explanation(&module("LibraAccount"), 5).unwrap();
+    assert_eq!(context.category, None);
+    assert_eq!(context.reason, description("EINSUFFICIENT_BALANCE"));
+    assert_eq!(
+        context.to_string(),
+        "EINSUFFICIENT_BALANCE (EINSUFFICIENT_BALANCE happened)"
+    );
+    assert_eq!(mapping.get_explanation(&module("LibraAccount"), 6), None);
+    assert_eq!(mapping.get_explanation(&module("Libra"), 5), None);
+}
+
+#[test]
+fn test_explanation_with_categories() {
+    let mut mapping = ErrorMapping::default();
+    mapping
+        .error_categories
+        .insert(8, description("LIMIT_EXCEEDED"));
+    mapping.add_module_error(
+        module("LibraAccount"),
+        5,
+        description("EINSUFFICIENT_BALANCE"),
+    );
+
+    let context = mapping
+        .get_explanation(&module("LibraAccount"), 5 << 8 | 8)
+        .unwrap();
+    assert_eq!(context.category, Some(description("LIMIT_EXCEEDED")));
+    assert_eq!(context.reason, description("EINSUFFICIENT_BALANCE"));
+    assert_eq!(
+        context.to_string(),
+        "LIMIT_EXCEEDED: EINSUFFICIENT_BALANCE (EINSUFFICIENT_BALANCE happened)"
+    );
+
+    // An unknown category still explains the reason.
+    let context = mapping
+        .get_explanation(&module("LibraAccount"), 5 << 8 | 1)
+        .unwrap();
+    assert_eq!(context.category, None);
+    assert_eq!(mapping.get_explanation(&module("LibraAccount"), 5), None);
+}
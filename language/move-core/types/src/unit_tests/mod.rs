@@ -2,5 +2,6 @@
 // SPDX-License-Identifier: Apache-2.0
 
 mod address_test;
+mod errmap_test;
 mod identifier_test;
 mod language_storage_test;
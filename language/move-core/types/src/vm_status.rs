@@ -493,6 +493,16 @@ pub enum StatusCode {
     GAS_SCHEDULE_ERROR = 4023,
     VM_MAX_TYPE_DEPTH_REACHED = 4024,
     VM_MAX_VALUE_DEPTH_REACHED = 4025,
+    // Execution limits, enforced independently from gas when enabled, were exceeded: too many
+    // instructions were interpreted, calls were nested too deep, or a value was too large.
+    EXECUTION_INSTRUCTION_LIMIT_REACHED = 4026,
+    CALL_DEPTH_LIMIT_REACHED = 4027,
+    VALUE_SIZE_LIMIT_REACHED = 4028,
+    // A transaction wrote or deleted too many resources and modules, wrote too many bytes, or
+    // emitted too many events.
+    WRITE_OPS_LIMIT_REACHED = 4029,
+    WRITE_BYTES_LIMIT_REACHED = 4030,
+    EVENTS_LIMIT_REACHED = 4031,
 
     // A reserved status to represent an unknown vm status.
     // this is std::u64::MAX, but we can't pattern match on that, so put the hardcoded value in
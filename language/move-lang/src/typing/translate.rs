@@ -101,7 +101,14 @@ fn script(context: &mut Context, nscript: N::Script) -> T::Script {
     }
 }
 
-fn check_primitive_script_arg(context: &mut Context, mloc: Loc, idx: usize, ty: &Type) {
+/// Checks the type of a script parameter, returning whether it is a '&signer'. '&signer'
+/// parameters are only allowed while `in_signer_prefix`, that is before all other parameters.
+fn check_primitive_script_arg(
+    context: &mut Context,
+    mloc: Loc,
+    in_signer_prefix: bool,
+    ty: &Type,
+) -> bool {
     let loc = ty.loc;
 
     let signer_ref = sp(loc, Type_::Ref(false, Box::new(Type_::signer(loc))));
@@ -131,21 +138,22 @@ fn check_primitive_script_arg(context: &mut Context, mloc: Loc, idx: usize, ty:
             format_comma(tys),
         );
         context.error(vec![(mloc, mmsg), (loc, tmsg)]);
-        return;
+        return false;
     }
 
-    if idx != 0 && subtype_no_report(context, ty.clone(), signer_ref.clone()).is_ok() {
+    let is_signer_ref = subtype_no_report(context, ty.clone(), signer_ref.clone()).is_ok();
+    if !in_signer_prefix && is_signer_ref {
         let mmsg = format!(
             "Invalid parameter for script function '{}'",
             context.current_function.as_ref().unwrap()
         );
         let tmsg = format!(
-            "{} must be the first argument to a script",
+            "{} arguments must come before all other arguments to a script",
             core::error_format(&signer_ref, &Subst::empty()),
         );
         context.error(vec![(mloc, mmsg), (loc, tmsg)]);
-        return;
     }
+    is_signer_ref
 }
 
 //**************************************************************************************************
@@ -171,8 +179,10 @@ fn function(
 
     function_signature(context, &signature);
     if is_script {
-        for (idx, (_, param_ty)) in signature.parameters.iter().enumerate() {
-            check_primitive_script_arg(context, loc, idx, param_ty);
+        let mut in_signer_prefix = true;
+        for (_, param_ty) in &signature.parameters {
+            in_signer_prefix &=
+                check_primitive_script_arg(context, loc, in_signer_prefix, param_ty);
         }
         subtype(
             context,
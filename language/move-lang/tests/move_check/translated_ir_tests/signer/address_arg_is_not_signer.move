@@ -24,4 +24,3 @@ script {
 script {
     fun t3(s: &signer, s2: &signer) { }
 }
-// check: INVALID_MAIN_FUNCTION_SIGNATURE
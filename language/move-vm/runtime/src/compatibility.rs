@@ -0,0 +1,329 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Checks whether a module can replace a published one.
+//!
+//! A replacement is *layout compatible* if every struct of the published module is still defined,
+//! with the same kind, type parameters and fields, so that the values already in storage can be
+//! read back. It is *linking compatible* if, in addition, every public function of the published
+//! module is still public with the same signature, so that the modules and scripts calling it
+//! still link. Public functions are the only ones callable from other modules: there is no friend
+//! visibility to check.
+
+use move_core_types::{
+    identifier::{IdentStr, Identifier},
+    language_storage::ModuleId,
+};
+use vm::{
+    access::ModuleAccess,
+    file_format::{
+        CompiledModule, FunctionDefinition, Kind, SignatureIndex, SignatureToken, StructDefinition,
+        StructFieldInformation, StructHandleIndex,
+    },
+};
+
+/// A reason why a module can't replace a published one.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Incompatibility {
+    /// The replacement has a different address or name.
+    ModuleIdChanged(ModuleId),
+    /// A struct of the published module is no longer defined.
+    StructMissing(Identifier),
+    /// A struct is no longer (or is now) a resource, or its type parameters changed.
+    StructKindChanged(Identifier),
+    /// The fields of a struct, or their order, names or types, changed.
+    StructLayoutChanged(Identifier),
+    /// A public function of the published module is no longer defined, or no longer public.
+    PublicFunctionMissing(Identifier),
+    /// The parameters, return types or type parameters of a public function changed.
+    PublicFunctionSignatureChanged(Identifier),
+}
+
+impl Incompatibility {
+    /// Whether values of the published module in storage may no longer be readable.
+    pub fn breaks_layout(&self) -> bool {
+        match self {
+            Incompatibility::ModuleIdChanged(_)
+            | Incompatibility::StructMissing(_)
+            | Incompatibility::StructKindChanged(_)
+            | Incompatibility::StructLayoutChanged(_) => true,
+            Incompatibility::PublicFunctionMissing(_)
+            | Incompatibility::PublicFunctionSignatureChanged(_) => false,
+        }
+    }
+}
+
+/// The incompatibilities found between a published module and its replacement.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct CompatibilityReport {
+    pub incompatibilities: Vec<Incompatibility>,
+}
+
+impl CompatibilityReport {
+    /// Whether the values in storage can be read back with the replacement.
+    pub fn is_layout_compatible(&self) -> bool {
+        !self
+            .incompatibilities
+            .iter()
+            .any(Incompatibility::breaks_layout)
+    }
+
+    /// Whether, in addition, the code calling the published module still links.
+    pub fn is_linking_compatible(&self) -> bool {
+        self.incompatibilities.is_empty()
+    }
+}
+
+/// Checks whether `new_module` can replace `old_module`.
+pub fn check_compatibility(
+    old_module: &CompiledModule,
+    new_module: &CompiledModule,
+) -> CompatibilityReport {
+    let mut incompatibilities = vec![];
+    if old_module.self_id() != new_module.self_id() {
+        incompatibilities.push(Incompatibility::ModuleIdChanged(new_module.self_id()));
+    }
+
+    for old_struct in old_module.struct_defs() {
+        let name = struct_name(old_module, old_struct);
+        let new_struct = match new_module
+            .struct_defs()
+            .iter()
+            .find(|def| struct_name(new_module, def) == name)
+        {
+            Some(new_struct) => new_struct,
+            None => {
+                incompatibilities.push(Incompatibility::StructMissing(name.to_owned()));
+                continue;
+            }
+        };
+
+        let old_handle = old_module.struct_handle_at(old_struct.struct_handle);
+        let new_handle = new_module.struct_handle_at(new_struct.struct_handle);
+        if old_handle.is_nominal_resource != new_handle.is_nominal_resource
+            || old_handle.type_parameters != new_handle.type_parameters
+        {
+            incompatibilities.push(Incompatibility::StructKindChanged(name.to_owned()));
+        } else if fields(old_module, old_struct) != fields(new_module, new_struct) {
+            incompatibilities.push(Incompatibility::StructLayoutChanged(name.to_owned()));
+        }
+    }
+
+    for old_function in old_module
+        .function_defs()
+        .iter()
+        .filter(|def| def.is_public())
+    {
+        let name = function_name(old_module, old_function);
+        match new_module
+            .function_defs()
+            .iter()
+            .find(|def| def.is_public() && function_name(new_module, def) == name)
+        {
+            Some(new_function) => {
+                if signature(old_module, old_function) != signature(new_module, new_function) {
+                    incompatibilities.push(Incompatibility::PublicFunctionSignatureChanged(
+                        name.to_owned(),
+                    ));
+                }
+            }
+            None => incompatibilities.push(Incompatibility::PublicFunctionMissing(name.to_owned())),
+        }
+    }
+
+    CompatibilityReport { incompatibilities }
+}
+
+/// A `SignatureToken` with its struct handles resolved, so that it can be compared with the
+/// tokens of another module.
+#[derive(Debug, Eq, PartialEq)]
+enum Type<'a> {
+    Bool,
+    U8,
+    U64,
+    U128,
+    Address,
+    Signer,
+    Vector(Box<Type<'a>>),
+    Struct {
+        module: ModuleId,
+        name: &'a IdentStr,
+        type_args: Vec<Type<'a>>,
+    },
+    Reference(Box<Type<'a>>),
+    MutableReference(Box<Type<'a>>),
+    TypeParameter(u16),
+}
+
+impl<'a> Type<'a> {
+    fn new(module: &'a CompiledModule, token: &SignatureToken) -> Self {
+        match token {
+            SignatureToken::Bool => Type::Bool,
+            SignatureToken::U8 => Type::U8,
+            SignatureToken::U64 => Type::U64,
+            SignatureToken::U128 => Type::U128,
+            SignatureToken::Address => Type::Address,
+            SignatureToken::Signer => Type::Signer,
+            SignatureToken::Vector(ty) => Type::Vector(Box::new(Type::new(module, ty))),
+            SignatureToken::Struct(idx) => Type::new_struct(module, *idx, &[]),
+            SignatureToken::StructInstantiation(idx, type_args) => {
+                Type::new_struct(module, *idx, type_args)
+            }
+            SignatureToken::Reference(ty) => Type::Reference(Box::new(Type::new(module, ty))),
+            SignatureToken::MutableReference(ty) => {
+                Type::MutableReference(Box::new(Type::new(module, ty)))
+            }
+            SignatureToken::TypeParameter(idx) => Type::TypeParameter(*idx),
+        }
+    }
+
+    fn new_struct(
+        module: &'a CompiledModule,
+        idx: StructHandleIndex,
+        type_args: &[SignatureToken],
+    ) -> Self {
+        let handle = module.struct_handle_at(idx);
+        Type::Struct {
+            module: module.module_id_for_handle(module.module_handle_at(handle.module)),
+            name: module.identifier_at(handle.name),
+            type_args: type_args.iter().map(|ty| Type::new(module, ty)).collect(),
+        }
+    }
+}
+
+fn struct_name<'a>(module: &'a CompiledModule, def: &StructDefinition) -> &'a IdentStr {
+    module.identifier_at(module.struct_handle_at(def.struct_handle).name)
+}
+
+fn function_name<'a>(module: &'a CompiledModule, def: &FunctionDefinition) -> &'a IdentStr {
+    module.identifier_at(module.function_handle_at(def.function).name)
+}
+
+/// The names and types of the fields of a struct, or `None` if it is native.
+fn fields<'a>(
+    module: &'a CompiledModule,
+    def: &'a StructDefinition,
+) -> Option<Vec<(&'a IdentStr, Type<'a>)>> {
+    match &def.field_information {
+        StructFieldInformation::Native => None,
+        StructFieldInformation::Declared(fields) => Some(
+            fields
+                .iter()
+                .map(|field| {
+                    (
+                        module.identifier_at(field.name),
+                        Type::new(module, &field.signature.0),
+                    )
+                })
+                .collect(),
+        ),
+    }
+}
+
+/// The parameters, return types and type parameters of a function.
+fn signature<'a>(
+    module: &'a CompiledModule,
+    def: &FunctionDefinition,
+) -> (Vec<Type<'a>>, Vec<Type<'a>>, &'a [Kind]) {
+    let handle = module.function_handle_at(def.function);
+    let types = |idx: SignatureIndex| -> Vec<Type<'a>> {
+        module
+            .signature_at(idx)
+            .0
+            .iter()
+            .map(|ty| Type::new(module, ty))
+            .collect()
+    };
+    (
+        types(handle.parameters),
+        types(handle.return_),
+        &handle.type_parameters,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use compiler::Compiler;
+
+    fn compile(code: &str) -> CompiledModule {
+        Compiler {
+            skip_stdlib_deps: true,
+            ..Compiler::default()
+        }
+        .into_compiled_module("file_name", code)
+        .expect("Module compilation failed")
+    }
+
+    const OLD: &str = "
+        module M {
+            resource R { f: u64 }
+            struct S { a: u64, b: bool }
+            public f(x: u64): u64 { return move(x); }
+            g() { return; }
+        }
+    ";
+
+    #[test]
+    fn compatible_upgrade() {
+        let old = compile(OLD);
+        // New structs and functions, and changes to private functions, don't break anything.
+        let new = compile(
+            "
+            module M {
+                resource R { f: u64 }
+                struct S { a: u64, b: bool }
+                struct T { c: address }
+                public f(x: u64): u64 { return move(x) + 1; }
+                public h() { return; }
+            }
+            ",
+        );
+        let report = check_compatibility(&old, &new);
+        assert_eq!(report, CompatibilityReport::default());
+        assert!(report.is_linking_compatible());
+    }
+
+    #[test]
+    fn incompatible_upgrade() {
+        let old = compile(OLD);
+        let new = compile(
+            "
+            module M {
+                struct R { f: u64 }
+                struct S { b: bool, a: u64 }
+                public f(x: u64): bool { return move(x) == 0; }
+            }
+            ",
+        );
+        let report = check_compatibility(&old, &new);
+        assert_eq!(
+            report.incompatibilities,
+            vec![
+                Incompatibility::StructKindChanged(Identifier::new("R").unwrap()),
+                Incompatibility::StructLayoutChanged(Identifier::new("S").unwrap()),
+                Incompatibility::PublicFunctionSignatureChanged(Identifier::new("f").unwrap()),
+            ]
+        );
+        assert!(!report.is_layout_compatible());
+
+        // Removing a public function only breaks linking.
+        let new = compile(
+            "
+            module M {
+                resource R { f: u64 }
+                struct S { a: u64, b: bool }
+            }
+            ",
+        );
+        let report = check_compatibility(&old, &new);
+        assert_eq!(
+            report.incompatibilities,
+            vec![Incompatibility::PublicFunctionMissing(
+                Identifier::new("f").unwrap()
+            )]
+        );
+        assert!(report.is_layout_compatible());
+        assert!(!report.is_linking_compatible());
+    }
+}
@@ -32,6 +32,9 @@ pub trait RemoteCache {
 pub struct AccountDataCache {
     data_map: BTreeMap<Type, Option<GlobalValue>>,
     module_map: BTreeMap<ModuleId, Vec<u8>>,
+    /// Dirty resources serialized by `write_set_size`, until they're accessed again, so that
+    /// `into_effects` doesn't serialize them a second time.
+    serialized: BTreeMap<Type, Vec<u8>>,
 }
 
 impl AccountDataCache {
@@ -39,6 +42,7 @@ impl AccountDataCache {
         Self {
             data_map: BTreeMap::new(),
             module_map: BTreeMap::new(),
+            serialized: BTreeMap::new(),
         }
     }
 }
@@ -64,10 +68,8 @@ pub(crate) struct TransactionDataCache<'r, 'l, R> {
 }
 
 pub struct TransactionEffects {
-    pub resources: Vec<(
-        AccountAddress,
-        Vec<(TypeTag, Option<(MoveTypeLayout, Value)>)>,
-    )>,
+    /// The serialized resources written, or `None` for those deleted.
+    pub resources: Vec<(AccountAddress, Vec<(TypeTag, Option<Vec<u8>>)>)>,
     pub modules: Vec<(ModuleId, Vec<u8>)>,
     pub events: Vec<(Vec<u8>, u64, TypeTag, MoveTypeLayout, Value)>,
 }
@@ -91,7 +93,7 @@ impl<'r, 'l, R: RemoteCache> TransactionDataCache<'r, 'l, R> {
     pub(crate) fn into_effects(self) -> PartialVMResult<TransactionEffects> {
         let mut modules = vec![];
         let mut resources = vec![];
-        for (addr, account_cache) in self.account_map {
+        for (addr, mut account_cache) in self.account_map {
             let mut vals = vec![];
             for (ty, gv_opt) in account_cache.data_map {
                 match gv_opt {
@@ -102,9 +104,11 @@ impl<'r, 'l, R: RemoteCache> TransactionDataCache<'r, 'l, R> {
                     Some(gv) => {
                         if gv.is_dirty()? {
                             let ty_tag = self.loader.type_to_type_tag(&ty)?;
-                            let ty_layout = self.loader.type_to_type_layout(&ty)?;
-                            let val = Value::struct_(gv.into_owned_struct()?);
-                            vals.push((ty_tag, Some((ty_layout, val))));
+                            let blob = match account_cache.serialized.remove(&ty) {
+                                Some(blob) => blob,
+                                None => serialize_resource(self.loader, &ty, &gv)?,
+                            };
+                            vals.push((ty_tag, Some(blob)));
                         }
                     }
                 };
@@ -138,6 +142,45 @@ impl<'r, 'l, R: RemoteCache> TransactionDataCache<'r, 'l, R> {
         self.account_map.keys().len() as u64
     }
 
+    /// Return the number of write ops `into_effects` would make, i.e. of deleted and dirty
+    /// resources and published modules, and the bytes they write: serialized resources and
+    /// module blobs.
+    pub(crate) fn write_set_size(&mut self) -> PartialVMResult<(u64, u64)> {
+        let mut write_ops = 0;
+        let mut write_bytes = 0;
+        for account_cache in self.account_map.values_mut() {
+            for (ty, gv_opt) in &account_cache.data_map {
+                match gv_opt {
+                    None => write_ops += 1,
+                    Some(gv) => {
+                        if gv.is_dirty()? {
+                            let len = match account_cache.serialized.get(ty) {
+                                Some(blob) => blob.len(),
+                                None => {
+                                    let blob = serialize_resource(self.loader, ty, gv)?;
+                                    let len = blob.len();
+                                    account_cache.serialized.insert(ty.clone(), blob);
+                                    len
+                                }
+                            };
+                            write_ops += 1;
+                            write_bytes += len as u64;
+                        }
+                    }
+                }
+            }
+            for blob in account_cache.module_map.values() {
+                write_ops += 1;
+                write_bytes += blob.len() as u64;
+            }
+        }
+        Ok((write_ops, write_bytes))
+    }
+
+    pub(crate) fn num_events(&self) -> u64 {
+        self.event_data.len() as u64
+    }
+
     fn get_mut_or_insert_with<'a, K, V, F>(map: &'a mut BTreeMap<K, V>, k: &K, gen: F) -> &'a mut V
     where
         F: FnOnce() -> (K, V),
@@ -162,6 +205,8 @@ impl<'r, 'l, R: RemoteCache> TransactionDataCache<'r, 'l, R> {
             (addr, AccountDataCache::new())
         });
 
+        // What's accessed may be changed, so it's serialized again if it's written.
+        account_cache.serialized.remove(ty);
         if !account_cache.data_map.contains_key(ty) {
             let ty_tag = self.loader.type_to_type_tag(ty)?;
 
@@ -182,6 +227,14 @@ impl<'r, 'l, R: RemoteCache> TransactionDataCache<'r, 'l, R> {
     }
 }
 
+fn serialize_resource(loader: &Loader, ty: &Type, gv: &GlobalValue) -> PartialVMResult<Vec<u8>> {
+    match loader.type_to_type_layout(ty)? {
+        MoveTypeLayout::Struct(layout) => gv.simple_serialize(&layout),
+        _ => None,
+    }
+    .ok_or_else(|| PartialVMError::new(StatusCode::VALUE_SERIALIZATION_ERROR))
+}
+
 // `DataStore` implementation for the `TransactionDataCache`
 impl<'r, 'l, C: RemoteCache> DataStore for TransactionDataCache<'r, 'l, C> {
     fn publish_resource(
@@ -194,6 +247,7 @@ impl<'r, 'l, C: RemoteCache> DataStore for TransactionDataCache<'r, 'l, C> {
             (addr, AccountDataCache::new())
         });
 
+        account_cache.serialized.remove(&ty);
         account_cache.data_map.insert(ty, Some(g));
 
         Ok(())
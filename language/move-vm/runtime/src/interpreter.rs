@@ -5,7 +5,7 @@ use crate::{
     data_operations::{borrow_global, move_resource_from, move_resource_to, resource_exists},
     loader::{Function, Loader, Resolver},
     native_functions::FunctionContext,
-    trace,
+    trace, trace_callback,
 };
 use libra_logger::prelude::*;
 use move_core_types::{
@@ -114,6 +114,12 @@ impl Interpreter {
         args: Vec<Value>,
     ) -> VMResult<()> {
         verify_args(function.parameters(), &ty_args, &args).map_err(|e| self.set_location(e))?;
+        trace_callback!(callback => callback.on_function_entry(&traced_call(
+            0,
+            &function,
+            &ty_args,
+            args.iter(),
+        )));
         let mut locals = Locals::new(function.local_count());
         for (i, value) in args.into_iter().enumerate() {
             locals
@@ -134,6 +140,10 @@ impl Interpreter {
                         .locals
                         .check_resources_for_return()
                         .map_err(|e| self.set_location(e))?;
+                    trace_callback!(callback => callback.on_function_exit(
+                        self.call_stack.0.len(),
+                        &current_frame.function.pretty_string(),
+                    ));
                     if let Some(frame) = self.call_stack.pop() {
                         current_frame = frame;
                     } else {
@@ -152,6 +162,9 @@ impl Interpreter {
                         self.call_native(&resolver, data_store, cost_strategy, func, vec![])?;
                         continue;
                     }
+                    cost_strategy
+                        .check_call_depth(self.call_stack.0.len() + 1)
+                        .map_err(|e| self.set_location(e))?;
                     let frame = self
                         .make_call_frame(func, vec![])
                         .or_else(|err| Err(self.maybe_core_dump(err, &current_frame)))?;
@@ -180,6 +193,9 @@ impl Interpreter {
                         self.call_native(&resolver, data_store, cost_strategy, func, ty_args)?;
                         continue;
                     }
+                    cost_strategy
+                        .check_call_depth(self.call_stack.0.len() + 1)
+                        .map_err(|e| self.set_location(e))?;
                     let frame = self
                         .make_call_frame(func, ty_args)
                         .or_else(|err| Err(self.maybe_core_dump(err, &current_frame)))?;
@@ -202,6 +218,16 @@ impl Interpreter {
     fn make_call_frame(&mut self, func: Arc<Function>, ty_args: Vec<Type>) -> VMResult<Frame> {
         let mut locals = Locals::new(func.local_count());
         let arg_count = func.arg_count();
+        trace_callback!(callback => {
+            let args =
+                &self.operand_stack.0[self.operand_stack.0.len().saturating_sub(arg_count)..];
+            callback.on_function_entry(&traced_call(
+                self.call_stack.0.len() + 1,
+                &func,
+                &ty_args,
+                args.iter(),
+            ))
+        });
         for i in 0..arg_count {
             locals
                 .store_loc(
@@ -253,10 +279,22 @@ impl Interpreter {
         for _ in 0..expected_args {
             arguments.push_front(self.operand_stack.pop()?);
         }
+        trace_callback!(callback => callback.on_function_entry(&traced_call(
+            self.call_stack.0.len() + 1,
+            &function,
+            &ty_args,
+            arguments.iter(),
+        )));
         let mut native_context = FunctionContext::new(self, data_store, cost_strategy, resolver);
         let native_function = function.get_native()?;
         let result = native_function.dispatch(&mut native_context, ty_args, arguments)?;
-        cost_strategy.deduct_gas(result.cost)?;
+        cost_strategy.deduct_native_gas(function.module_id(), function.name(), result.cost)?;
+        trace_callback!(callback => match result.result {
+            Ok(_) => {
+                callback.on_function_exit(self.call_stack.0.len() + 1, &function.pretty_string())
+            }
+            Err(code) => callback.on_abort(&function.pretty_string(), code),
+        });
         let values = result
             .result
             .map_err(|code| PartialVMError::new(StatusCode::ABORTED).with_sub_status(code))?;
@@ -524,6 +562,24 @@ impl Interpreter {
 const OPERAND_STACK_SIZE_LIMIT: usize = 1024;
 const CALL_STACK_SIZE_LIMIT: usize = 1024;
 
+/// Describes a call to `function` for [`TraceCallback::on_function_entry`].
+///
+/// [`TraceCallback::on_function_entry`]: crate::tracing::TraceCallback::on_function_entry
+#[cfg(debug_assertions)]
+fn traced_call<'a>(
+    depth: usize,
+    function: &Function,
+    ty_args: &[Type],
+    args: impl Iterator<Item = &'a Value>,
+) -> crate::tracing::TracedCall {
+    crate::tracing::TracedCall {
+        depth,
+        function: function.pretty_string(),
+        ty_args: ty_args.iter().map(|ty| format!("{:?}", ty)).collect(),
+        args: args.map(|arg| arg.to_string()).collect(),
+    }
+}
+
 /// The operand stack.
 struct Stack(Vec<Value>);
 
@@ -657,7 +713,13 @@ impl Frame {
         loop {
             for instruction in &code[self.pc as usize..] {
                 trace!(self.function.pretty_string(), self.pc, instruction);
+                trace_callback!(callback => callback.on_instruction(
+                    &self.function.pretty_string(),
+                    self.pc,
+                    instruction,
+                ));
                 self.pc += 1;
+                cost_strategy.count_instruction()?;
 
                 match instruction {
                     Bytecode::Pop => {
@@ -948,6 +1010,8 @@ impl Frame {
                     Bytecode::Abort => {
                         cost_strategy.charge_instr(Opcodes::ABORT)?;
                         let error_code = interpreter.operand_stack.pop_as::<u64>()?;
+                        trace_callback!(callback => callback
+                            .on_abort(&self.function.pretty_string(), error_code));
                         return Err(PartialVMError::new(StatusCode::ABORTED)
                             .with_sub_status(error_code)
                             .with_message(format!(
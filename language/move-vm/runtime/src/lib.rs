@@ -12,13 +12,16 @@
 #[macro_use]
 extern crate mirai_annotations;
 
+pub mod compatibility;
 pub mod data_cache;
 mod data_operations;
 mod interpreter;
 mod loader;
 pub mod move_vm;
-mod native_functions;
+pub mod native_functions;
 mod runtime;
 pub mod session;
 #[macro_use]
 mod tracing;
+
+pub use tracing::{record_calls, trace_with, TraceCallback, TracedCall};
@@ -1,7 +1,7 @@
 // Copyright (c) The Libra Core Contributors
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::native_functions::NativeFunction;
+use crate::native_functions::{NativeFunction, NativeFunctionRegistry};
 use bytecode_verifier::{
     constants, instantiation_loops::InstantiationLoopChecker, verify_main_signature,
     CodeUnitVerifier, DependencyChecker, DuplicationChecker, InstructionConsistency,
@@ -145,14 +145,19 @@ impl ModuleCache {
     // The VM is pretty much stopped waiting for this to finish
     //
 
-    fn insert(&mut self, id: ModuleId, module: CompiledModule) -> VMResult<Arc<Module>> {
+    fn insert(
+        &mut self,
+        natives: &NativeFunctionRegistry,
+        id: ModuleId,
+        module: CompiledModule,
+    ) -> VMResult<Arc<Module>> {
         if let Some(module) = self.module_at(&id) {
             return Ok(module);
         }
 
         // we need this operation to be transactional, if an error occurs we must
         // leave a clean state
-        self.add_module(&module)?;
+        self.add_module(natives, &module)?;
         match Module::new(module, self) {
             Ok(module) => Ok(Arc::clone(self.modules.insert(id, module))),
             Err((err, module)) => {
@@ -167,7 +172,11 @@ impl ModuleCache {
         }
     }
 
-    fn add_module(&mut self, module: &CompiledModule) -> VMResult<()> {
+    fn add_module(
+        &mut self,
+        natives: &NativeFunctionRegistry,
+        module: &CompiledModule,
+    ) -> VMResult<()> {
         let starting_idx = self.structs.len();
         for (idx, struct_def) in module.struct_defs().iter().enumerate() {
             let st = self.make_struct_type(module, struct_def, StructDefinitionIndex(idx as u16));
@@ -179,7 +188,7 @@ impl ModuleCache {
             Err(err.finish(Location::Undefined))
         })?;
         for func in module.function_defs() {
-            let function = Function::new(func, module);
+            let function = Function::new(natives, func, module);
             self.functions.push(Arc::new(function));
         }
         Ok(())
@@ -405,15 +414,17 @@ pub(crate) struct Loader {
     scripts: Mutex<ScriptCache>,
     module_cache: Mutex<ModuleCache>,
     type_cache: Mutex<TypeCache>,
+    natives: NativeFunctionRegistry,
 }
 
 impl Loader {
-    pub(crate) fn new() -> Self {
+    pub(crate) fn new(natives: NativeFunctionRegistry) -> Self {
         //println!("new loader");
         Self {
             scripts: Mutex::new(ScriptCache::new()),
             module_cache: Mutex::new(ModuleCache::new()),
             type_cache: Mutex::new(TypeCache::new()),
+            natives,
         }
     }
 
@@ -574,7 +585,7 @@ impl Loader {
         RecursiveStructDefChecker::verify_module(&module)?;
         InstantiationLoopChecker::verify_module(&module)?;
         CodeUnitVerifier::verify_module(&module)?;
-        Self::check_natives(&module)
+        self.check_natives(&module)
     }
 
     // The process of loading is recursive, and module are cached by the loader as soon as
@@ -636,8 +647,11 @@ impl Loader {
     }
 
     // All native functions must be known to the loader
-    fn check_natives(module: &CompiledModule) -> VMResult<()> {
-        fn check_natives_impl(module: &CompiledModule) -> PartialVMResult<()> {
+    fn check_natives(&self, module: &CompiledModule) -> VMResult<()> {
+        fn check_natives_impl(
+            natives: &NativeFunctionRegistry,
+            module: &CompiledModule,
+        ) -> PartialVMResult<()> {
             for (idx, native_function) in module
                 .function_defs()
                 .iter()
@@ -647,6 +661,7 @@ impl Loader {
                 let fh = module.function_handle_at(native_function.function);
                 let mh = module.module_handle_at(fh.module);
                 NativeFunction::resolve(
+                    natives,
                     module.address_identifier_at(mh.address),
                     module.identifier_at(mh.name).as_str(),
                     module.identifier_at(fh.name).as_str(),
@@ -672,7 +687,8 @@ impl Loader {
             }
             Ok(())
         }
-        check_natives_impl(module).map_err(|e| e.finish(Location::Module(module.self_id())))
+        check_natives_impl(&self.natives, module)
+            .map_err(|e| e.finish(Location::Module(module.self_id())))
     }
 
     //
@@ -718,7 +734,10 @@ impl Loader {
             return Ok(module);
         }
         let module = self.deserialize_and_verify_module(id, data_store)?;
-        self.module_cache.lock().unwrap().insert(id.clone(), module)
+        self.module_cache
+            .lock()
+            .unwrap()
+            .insert(&self.natives, id.clone(), module)
     }
 
     // Verify the kind (constraints) of an instantiation.
@@ -1365,6 +1384,7 @@ impl Script {
             locals,
             type_parameters,
             native,
+            is_public: true,
             scope,
             name,
         });
@@ -1407,17 +1427,23 @@ pub(crate) struct Function {
     locals: Signature,
     type_parameters: Vec<Kind>,
     native: Option<NativeFunction>,
+    is_public: bool,
     scope: Scope,
     name: Identifier,
 }
 
 impl Function {
-    fn new(def: &FunctionDefinition, module: &CompiledModule) -> Self {
+    fn new(
+        natives: &NativeFunctionRegistry,
+        def: &FunctionDefinition,
+        module: &CompiledModule,
+    ) -> Self {
         let handle = module.function_handle_at(def.function);
         let name = module.identifier_at(handle.name).to_owned();
         let module_id = module.self_id();
         let native = if def.is_native() {
             NativeFunction::resolve(
+                natives,
                 module_id.address(),
                 module_id.name().as_str(),
                 name.as_str(),
@@ -1451,6 +1477,7 @@ impl Function {
             locals,
             type_parameters,
             native,
+            is_public: def.is_public(),
             scope,
             name,
         }
@@ -1520,8 +1547,12 @@ impl Function {
         self.native.is_some()
     }
 
+    pub(crate) fn is_public(&self) -> bool {
+        self.is_public
+    }
+
     pub(crate) fn get_native(&self) -> PartialVMResult<NativeFunction> {
-        self.native.ok_or_else(|| {
+        self.native.clone().ok_or_else(|| {
             PartialVMError::new(StatusCode::UNREACHABLE)
                 .with_message("Missing Native Function".to_string())
         })
@@ -1,7 +1,10 @@
 // Copyright (c) The Libra Core Contributors
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::{data_cache::RemoteCache, runtime::VMRuntime, session::Session};
+use crate::{
+    data_cache::RemoteCache, native_functions::NativeFunctionRegistry, runtime::VMRuntime,
+    session::Session,
+};
 
 pub struct MoveVM {
     runtime: VMRuntime,
@@ -9,8 +12,14 @@ pub struct MoveVM {
 
 impl MoveVM {
     pub fn new() -> Self {
+        Self::new_with_natives(NativeFunctionRegistry::new())
+    }
+
+    /// Creates a VM which also supports the natives of `natives`. See [`NativeFunctionRegistry`]
+    /// for why it must not be used by validators.
+    pub fn new_with_natives(natives: NativeFunctionRegistry) -> Self {
         Self {
-            runtime: VMRuntime::new(),
+            runtime: VMRuntime::new(natives),
         }
     }
 
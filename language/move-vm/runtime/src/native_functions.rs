@@ -3,7 +3,12 @@
 
 use crate::{interpreter::Interpreter, loader::Resolver};
 use libra_types::account_config::CORE_CODE_ADDRESS;
-use move_core_types::{account_address::AccountAddress, gas_schedule::CostTable};
+use move_core_types::{
+    account_address::AccountAddress,
+    gas_schedule::{CostTable, GasCost},
+    identifier::{IdentStr, Identifier},
+    language_storage::ModuleId,
+};
 use move_vm_natives::{account, debug, event, hash, lcs, signature, signer, vector};
 use move_vm_types::{
     data_store::DataStore,
@@ -12,7 +17,11 @@ use move_vm_types::{
     natives::function::{NativeContext, NativeResult},
     values::Value,
 };
-use std::{collections::VecDeque, fmt::Write};
+use std::{
+    collections::{BTreeMap, VecDeque},
+    fmt::{self, Write},
+    sync::Arc,
+};
 use vm::errors::PartialVMResult;
 
 // The set of native functions the VM supports.
@@ -21,7 +30,8 @@ use vm::errors::PartialVMResult;
 // - `resolve` which given a function unique name ModuleAddress::ModuleName::FunctionName
 // returns a `NativeFunction`
 // - `dispatch` which given a `NativeFunction` invokes the native
-#[derive(Debug, Clone, Copy)]
+// Natives registered with a `NativeFunctionRegistry` are resolved after these.
+#[derive(Debug, Clone)]
 pub(crate) enum NativeFunction {
     HashSha2_256,
     HashSha3_256,
@@ -42,10 +52,12 @@ pub(crate) enum NativeFunction {
     SignerBorrowAddress,
     CreateSigner,
     DestroySigner,
+    Extension(Arc<NativeExtension>),
 }
 
 impl NativeFunction {
     pub(crate) fn resolve(
+        natives: &NativeFunctionRegistry,
         module_address: &AccountAddress,
         module_name: &str,
         function_name: &str,
@@ -73,7 +85,11 @@ impl NativeFunction {
             (&CORE_CODE_ADDRESS, "Debug", "print") => DebugPrint,
             (&CORE_CODE_ADDRESS, "Debug", "print_stack_trace") => DebugPrintStackTrace,
             (&CORE_CODE_ADDRESS, "Signer", "borrow_address") => SignerBorrowAddress,
-            _ => return None,
+            _ => Extension(Arc::clone(natives.get(
+                module_address,
+                module_name,
+                function_name,
+            )?)),
         })
     }
 
@@ -105,10 +121,88 @@ impl NativeFunction {
             Self::SignerBorrowAddress => signer::native_borrow_address(ctx, t, v),
             Self::CreateSigner => account::native_create_signer(ctx, t, v),
             Self::DestroySigner => account::native_destroy_signer(ctx, t, v),
+            Self::Extension(extension) => (extension.function)(&extension.cost, t, v),
         }
     }
 }
 
+/// The implementation of a native function registered with a `NativeFunctionRegistry`. It is
+/// given the gas entry it was registered with to compute the cost it returns, as the `CostTable`
+/// of the VM has no entry for it.
+pub type NativeFunctionImpl =
+    dyn Fn(&GasCost, Vec<Type>, VecDeque<Value>) -> PartialVMResult<NativeResult> + Send + Sync;
+
+pub(crate) struct NativeExtension {
+    cost: GasCost,
+    function: Box<NativeFunctionImpl>,
+}
+
+impl fmt::Debug for NativeExtension {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("NativeExtension")
+            .field("cost", &self.cost)
+            .finish()
+    }
+}
+
+/// Native functions to add to the ones the VM supports, so that new natives can be tried without
+/// forking the VM.
+///
+/// Modules declaring these natives only load in a VM created with the registry, and the gas they
+/// charge is not part of the on-chain gas schedule: this is meant for tests and private chains,
+/// never for a VM taking part in consensus with VMs that don't have the same registry.
+#[derive(Clone, Debug, Default)]
+pub struct NativeFunctionRegistry {
+    functions: BTreeMap<(ModuleId, Identifier), Arc<NativeExtension>>,
+}
+
+impl NativeFunctionRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers `function` as the implementation of the native `function_name` of `module`,
+    /// replacing any implementation registered before.
+    ///
+    /// The natives the VM supports can't be replaced: they are resolved first.
+    pub fn register<F>(
+        &mut self,
+        module: ModuleId,
+        function_name: Identifier,
+        cost: GasCost,
+        function: F,
+    ) -> &mut Self
+    where
+        F: Fn(&GasCost, Vec<Type>, VecDeque<Value>) -> PartialVMResult<NativeResult>
+            + Send
+            + Sync
+            + 'static,
+    {
+        self.functions.insert(
+            (module, function_name),
+            Arc::new(NativeExtension {
+                cost,
+                function: Box::new(function),
+            }),
+        );
+        self
+    }
+
+    fn get(
+        &self,
+        module_address: &AccountAddress,
+        module_name: &str,
+        function_name: &str,
+    ) -> Option<&Arc<NativeExtension>> {
+        let module_name = IdentStr::new(module_name).ok()?;
+        let function_name = IdentStr::new(function_name).ok()?;
+        self.functions.get(&(
+            ModuleId::new(*module_address, module_name.to_owned()),
+            function_name.to_owned(),
+        ))
+    }
+}
+
 pub(crate) struct FunctionContext<'a> {
     interpreter: &'a mut Interpreter,
     data_store: &'a mut dyn DataStore,
@@ -163,3 +257,97 @@ impl<'a> NativeContext for FunctionContext<'a> {
         self.resolver.is_resource(ty)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{data_cache::RemoteCache, move_vm::MoveVM};
+    use compiler::Compiler;
+    use move_core_types::{
+        gas_schedule::{GasAlgebra, GasUnits},
+        language_storage::TypeTag,
+        vm_status::StatusCode,
+    };
+    use move_vm_types::gas_schedule::{zero_cost_schedule, CostStrategy};
+    use vm::errors::{PartialVMResult, VMResult};
+
+    struct Modules(BTreeMap<ModuleId, Vec<u8>>);
+
+    impl RemoteCache for Modules {
+        fn get_module(&self, module_id: &ModuleId) -> VMResult<Option<Vec<u8>>> {
+            Ok(self.0.get(module_id).cloned())
+        }
+
+        fn get_resource(
+            &self,
+            _address: &AccountAddress,
+            _tag: &TypeTag,
+        ) -> PartialVMResult<Option<Vec<u8>>> {
+            Ok(None)
+        }
+    }
+
+    #[test]
+    fn registered_native() {
+        let module = Compiler {
+            skip_stdlib_deps: true,
+            ..Compiler::default()
+        }
+        .into_compiled_module(
+            "file_name",
+            "
+            module M {
+                native public double(x: u64): u64;
+                public check(x: u64) {
+                    if (Self.double(move(x)) != 42) {
+                        abort 1;
+                    }
+                    return;
+                }
+            }
+            ",
+        )
+        .expect("Module compilation failed");
+        let module_id = module.self_id();
+        let mut blob = vec![];
+        module.serialize(&mut blob).unwrap();
+        let mut modules = BTreeMap::new();
+        modules.insert(module_id.clone(), blob);
+        let remote = Modules(modules);
+
+        let cost_table = zero_cost_schedule();
+        let check = |vm: &MoveVM, x: u64| {
+            let mut cost_strategy = CostStrategy::system(&cost_table, GasUnits::new(0));
+            vm.new_session(&remote).execute_function(
+                &module_id,
+                IdentStr::new("check").unwrap(),
+                vec![],
+                vec![Value::u64(x)],
+                AccountAddress::ZERO,
+                &mut cost_strategy,
+            )
+        };
+
+        let mut natives = NativeFunctionRegistry::new();
+        natives.register(
+            module_id.clone(),
+            Identifier::new("double").unwrap(),
+            GasCost::new(1, 1),
+            |cost, _ty_args, mut args| {
+                let x = args.pop_back().unwrap().value_as::<u64>()?;
+                Ok(NativeResult::ok(cost.total(), vec![Value::u64(x * 2)]))
+            },
+        );
+        let vm = MoveVM::new_with_natives(natives);
+        assert!(check(&vm, 21).is_ok());
+        let err = check(&vm, 20).unwrap_err();
+        assert_eq!(err.major_status(), StatusCode::ABORTED);
+        assert_eq!(err.sub_status(), Some(1));
+
+        // Without the registry, the module doesn't load.
+        assert_eq!(
+            check(&MoveVM::new(), 21).unwrap_err().major_status(),
+            StatusCode::MISSING_DEPENDENCY
+        );
+    }
+}
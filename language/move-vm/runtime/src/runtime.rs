@@ -5,6 +5,7 @@ use crate::{
     data_cache::{RemoteCache, TransactionDataCache},
     interpreter::Interpreter,
     loader::Loader,
+    native_functions::NativeFunctionRegistry,
     session::Session,
 };
 use libra_logger::prelude::*;
@@ -12,13 +13,14 @@ use move_core_types::{
     account_address::AccountAddress,
     identifier::IdentStr,
     language_storage::{ModuleId, TypeTag},
+    transaction_argument::{is_argument_of_type, TransactionArgument},
     vm_status::StatusCode,
 };
 use move_vm_types::{data_store::DataStore, gas_schedule::CostStrategy, values::Value};
 use vm::{
     access::ModuleAccess,
     errors::{verification_error, Location, PartialVMError, PartialVMResult, VMResult},
-    file_format::SignatureToken,
+    file_format::{Signature, SignatureToken},
     CompiledModule, IndexKind,
 };
 
@@ -28,9 +30,9 @@ pub(crate) struct VMRuntime {
 }
 
 impl VMRuntime {
-    pub(crate) fn new() -> Self {
+    pub(crate) fn new(natives: NativeFunctionRegistry) -> Self {
         VMRuntime {
-            loader: Loader::new(),
+            loader: Loader::new(natives),
         }
     }
 
@@ -90,27 +92,16 @@ impl VMRuntime {
         script: Vec<u8>,
         ty_args: Vec<TypeTag>,
         mut args: Vec<Value>,
-        sender: AccountAddress,
+        senders: Vec<AccountAddress>,
         data_store: &mut impl DataStore,
         cost_strategy: &mut CostStrategy,
     ) -> VMResult<()> {
-        // signer helper closure
-        fn is_signer_reference(s: &SignatureToken) -> bool {
-            use SignatureToken as S;
-            match s {
-                S::Reference(inner) => matches!(&**inner, S::Signer),
-                _ => false,
-            }
-        }
-
         // load the script, perform verification
         let (main, type_params) = self.loader.load_script(&script, &ty_args, data_store)?;
 
         // build the arguments list for the main and check the arguments are of restricted types
-        let first_param_opt = main.parameters().0.get(0);
-        if first_param_opt.map_or(false, |sig| is_signer_reference(sig)) {
-            args.insert(0, Value::transaction_argument_signer_reference(sender))
-        }
+        bind_signers(main.parameters(), senders, &mut args)
+            .map_err(|e| e.finish(Location::Script))?;
         check_args(&args).map_err(|e| e.finish(Location::Script))?;
 
         // run the script
@@ -124,6 +115,69 @@ impl VMRuntime {
         )
     }
 
+    pub(crate) fn execute_script_function(
+        &self,
+        module: &ModuleId,
+        function_name: &IdentStr,
+        ty_args: Vec<TypeTag>,
+        args: Vec<TransactionArgument>,
+        senders: Vec<AccountAddress>,
+        data_store: &mut impl DataStore,
+        cost_strategy: &mut CostStrategy,
+    ) -> VMResult<()> {
+        let (func, type_params) =
+            self.loader
+                .load_function(function_name, module, &ty_args, data_store)?;
+
+        // only public functions can be called from a transaction
+        if !func.is_public() {
+            return Err(PartialVMError::new(StatusCode::VISIBILITY_MISMATCH)
+                .with_message(format!("{} is not public", func.pretty_string()))
+                .finish(Location::Module(module.clone())));
+        }
+
+        // check the arguments against the parameters after the signers, then build the arguments
+        // list the same way as for a script main
+        check_script_function_args(func.parameters(), &ty_args, &args)
+            .map_err(|e| e.finish(Location::Module(module.clone())))?;
+        let mut args = args.iter().map(transaction_argument_value).collect();
+        bind_signers(func.parameters(), senders, &mut args)
+            .map_err(|e| e.finish(Location::Module(module.clone())))?;
+        check_args(&args).map_err(|e| e.finish(Location::Module(module.clone())))?;
+
+        Interpreter::entrypoint(
+            func,
+            type_params,
+            args,
+            data_store,
+            cost_strategy,
+            &self.loader,
+        )
+    }
+
+    pub(crate) fn verify_script(
+        &self,
+        script: &[u8],
+        ty_args: &[TypeTag],
+        data_store: &mut impl DataStore,
+    ) -> VMResult<()> {
+        // loading verifies the script, its type arguments and all of its dependencies
+        self.loader.load_script(script, ty_args, data_store)?;
+        Ok(())
+    }
+
+    pub(crate) fn verify_module(&self, module: &[u8]) -> VMResult<()> {
+        let compiled_module = match CompiledModule::deserialize(module) {
+            Ok(module) => module,
+            Err(err) => {
+                warn!("[VM] module deserialization failed {:?}", err);
+                return Err(err.finish(Location::Undefined));
+            }
+        };
+        // same bytecode verification as publishing, dependencies are checked when loaded
+        self.loader.verify_module(&compiled_module)
+    }
+
     pub(crate) fn execute_function(
         &self,
         module: &ModuleId,
@@ -154,6 +208,109 @@ impl VMRuntime {
     }
 }
 
+/// Bind the transaction senders to the leading `&signer` parameters of an entry point by
+/// prepending them to `args`. A single sender may be passed to an entry point without a `&signer`
+/// parameter, otherwise there must be exactly one leading `&signer` parameter per sender.
+fn bind_signers(
+    parameters: &Signature,
+    senders: Vec<AccountAddress>,
+    args: &mut Vec<Value>,
+) -> PartialVMResult<()> {
+    let num_signer_params = num_signer_params(parameters);
+    if senders.len() != num_signer_params && !(senders.len() == 1 && num_signer_params == 0) {
+        return Err(
+            PartialVMError::new(StatusCode::TYPE_MISMATCH).with_message(format!(
+                "entry point expects {} signers but the transaction has {}",
+                num_signer_params,
+                senders.len()
+            )),
+        );
+    }
+    let signers = senders
+        .into_iter()
+        .take(num_signer_params)
+        .map(Value::transaction_argument_signer_reference);
+    args.splice(0..0, signers);
+    Ok(())
+}
+
+fn num_signer_params(parameters: &Signature) -> usize {
+    fn is_signer_reference(s: &SignatureToken) -> bool {
+        use SignatureToken as S;
+        match s {
+            S::Reference(inner) => matches!(&**inner, S::Signer),
+            _ => false,
+        }
+    }
+
+    parameters
+        .0
+        .iter()
+        .take_while(|sig| is_signer_reference(sig))
+        .count()
+}
+
+/// Check that a transaction passes exactly one argument of the right type to each parameter of a
+/// script function after its leading `&signer` parameters.
+fn check_script_function_args(
+    parameters: &Signature,
+    ty_args: &[TypeTag],
+    args: &[TransactionArgument],
+) -> PartialVMResult<()> {
+    let params = &parameters.0[num_signer_params(parameters)..];
+    if params.len() != args.len() {
+        return Err(
+            PartialVMError::new(StatusCode::TYPE_MISMATCH).with_message(format!(
+                "argument length mismatch: expected {} got {}",
+                params.len(),
+                args.len()
+            )),
+        );
+    }
+    for (param, arg) in params.iter().zip(args) {
+        let matches = argument_type_tag(param, ty_args)
+            .map_or(false, |type_tag| is_argument_of_type(arg, &type_tag));
+        if !matches {
+            return Err(PartialVMError::new(StatusCode::TYPE_MISMATCH)
+                .with_message(format!("unexpected type: {:?}, arg: {:?}", param, arg)));
+        }
+    }
+    Ok(())
+}
+
+/// The type of a parameter that can be passed as a transaction argument, with type parameters
+/// substituted by `ty_args`. Returns `None` for the types that transactions cannot pass.
+fn argument_type_tag(param: &SignatureToken, ty_args: &[TypeTag]) -> Option<TypeTag> {
+    use SignatureToken as S;
+    match param {
+        S::Bool => Some(TypeTag::Bool),
+        S::U8 => Some(TypeTag::U8),
+        S::U64 => Some(TypeTag::U64),
+        S::U128 => Some(TypeTag::U128),
+        S::Address => Some(TypeTag::Address),
+        S::Vector(inner) => {
+            argument_type_tag(inner, ty_args).map(|inner| TypeTag::Vector(Box::new(inner)))
+        }
+        S::TypeParameter(idx) => ty_args.get(*idx as usize).cloned(),
+        S::Signer
+        | S::Struct(_)
+        | S::StructInstantiation(_, _)
+        | S::Reference(_)
+        | S::MutableReference(_) => None,
+    }
+}
+
+fn transaction_argument_value(arg: &TransactionArgument) -> Value {
+    match arg {
+        TransactionArgument::U8(i) => Value::u8(*i),
+        TransactionArgument::U64(i) => Value::u64(*i),
+        TransactionArgument::U128(i) => Value::u128(*i),
+        TransactionArgument::Address(a) => Value::address(*a),
+        TransactionArgument::Bool(b) => Value::bool(*b),
+        TransactionArgument::U8Vector(v) => Value::vector_u8(v.clone()),
+    }
+}
+
 /// Check that the transaction arguments are acceptable by the VM.
 /// Constants are the only arguments allowed.
 fn check_args(args: &[Value]) -> PartialVMResult<()> {
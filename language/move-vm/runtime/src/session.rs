@@ -7,12 +7,37 @@ use crate::{
 };
 use move_core_types::{
     account_address::AccountAddress,
+    gas_schedule::{GasAlgebra, GasUnits},
     identifier::IdentStr,
     language_storage::{ModuleId, TypeTag},
+    transaction_argument::TransactionArgument,
+    vm_status::StatusCode,
 };
 use move_vm_types::{gas_schedule::CostStrategy, values::Value};
 use vm::errors::*;
 
+/// Bounds on what a transaction writes to storage, so that one transaction can't inflate it
+/// without bound, whatever the gas it pays.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct WriteSetLimits {
+    /// Maximum number of resources and modules written or deleted.
+    pub max_write_ops: u64,
+    /// Maximum number of bytes written, counting serialized resources and module blobs.
+    pub max_write_bytes: u64,
+    /// Maximum number of events emitted.
+    pub max_events: u64,
+}
+
+impl Default for WriteSetLimits {
+    fn default() -> Self {
+        Self {
+            max_write_ops: 1024,
+            max_write_bytes: 1 << 20,
+            max_events: 1024,
+        }
+    }
+}
+
 pub struct Session<'r, 'l, R> {
     pub(crate) runtime: &'l VMRuntime,
     pub(crate) data_cache: TransactionDataCache<'r, 'l, R>,
@@ -45,12 +70,48 @@ impl<'r, 'l, R: RemoteCache> Session<'r, 'l, R> {
         args: Vec<Value>,
         sender: AccountAddress,
         cost_strategy: &mut CostStrategy,
+    ) -> VMResult<()> {
+        self.execute_script_with_signers(script, ty_args, args, vec![sender], cost_strategy)
+    }
+
+    /// Executes a script signed by several accounts. `senders` are bound, in order, to the leading
+    /// `&signer` parameters of the script's main, which must have one per sender.
+    pub fn execute_script_with_signers(
+        &mut self,
+        script: Vec<u8>,
+        ty_args: Vec<TypeTag>,
+        args: Vec<Value>,
+        senders: Vec<AccountAddress>,
+        cost_strategy: &mut CostStrategy,
     ) -> VMResult<()> {
         self.runtime.execute_script(
             script,
             ty_args,
             args,
-            sender,
+            senders,
+            &mut self.data_cache,
+            cost_strategy,
+        )
+    }
+
+    /// Executes the public function `function_name` of `module` on behalf of `senders`, which
+    /// are bound to its leading `&signer` parameters the same way as for a script. `args` must
+    /// match the remaining parameters in number and type.
+    pub fn execute_script_function(
+        &mut self,
+        module: &ModuleId,
+        function_name: &IdentStr,
+        ty_args: Vec<TypeTag>,
+        args: Vec<TransactionArgument>,
+        senders: Vec<AccountAddress>,
+        cost_strategy: &mut CostStrategy,
+    ) -> VMResult<()> {
+        self.runtime.execute_script_function(
+            module,
+            function_name,
+            ty_args,
+            args,
+            senders,
             &mut self.data_cache,
             cost_strategy,
         )
@@ -66,10 +127,65 @@ impl<'r, 'l, R: RemoteCache> Session<'r, 'l, R> {
             .publish_module(module, sender, &mut self.data_cache, cost_strategy)
     }
 
+    /// Loads and verifies `script` and the modules it depends on, as `execute_script` would,
+    /// without running it.
+    pub fn verify_script(&mut self, script: &[u8], ty_args: &[TypeTag]) -> VMResult<()> {
+        self.runtime
+            .verify_script(script, ty_args, &mut self.data_cache)
+    }
+
+    /// Verifies `module` the same way `publish_module` does, without publishing it.
+    pub fn verify_module(&self, module: &[u8]) -> VMResult<()> {
+        self.runtime.verify_module(module)
+    }
+
     pub fn num_mutated_accounts(&self) -> u64 {
         self.data_cache.num_mutated_accounts()
     }
 
+    /// Fails if the writes and events of the session so far exceed `limits`, and charges for the
+    /// bytes written, at `global_memory_per_byte_write_cost`. At least `default_account_size`
+    /// bytes are charged for each mutated account.
+    pub fn charge_write_set(
+        &mut self,
+        limits: &WriteSetLimits,
+        cost_strategy: &mut CostStrategy,
+    ) -> VMResult<()> {
+        let (write_ops, write_bytes) = self
+            .data_cache
+            .write_set_size()
+            .map_err(|e| e.finish(Location::Undefined))?;
+        let num_events = self.data_cache.num_events();
+        let limit_error = if write_ops > limits.max_write_ops {
+            Some((StatusCode::WRITE_OPS_LIMIT_REACHED, "write ops", write_ops))
+        } else if write_bytes > limits.max_write_bytes {
+            Some((
+                StatusCode::WRITE_BYTES_LIMIT_REACHED,
+                "bytes written",
+                write_bytes,
+            ))
+        } else if num_events > limits.max_events {
+            Some((StatusCode::EVENTS_LIMIT_REACHED, "events", num_events))
+        } else {
+            None
+        };
+        if let Some((status, what, count)) = limit_error {
+            return Err(PartialVMError::new(status)
+                .with_message(format!("{} {}", count, what))
+                .finish(Location::Undefined));
+        }
+
+        let gas_constants = &cost_strategy.cost_table().gas_constants;
+        let charged_bytes = std::cmp::max(
+            write_bytes,
+            self.num_mutated_accounts() * gas_constants.default_account_size.get(),
+        );
+        let total_cost = gas_constants.global_memory_per_byte_write_cost.get() * charged_bytes;
+        cost_strategy
+            .deduct_gas(GasUnits::new(total_cost))
+            .map_err(|e| e.finish(Location::Undefined))
+    }
+
     pub fn finish(self) -> VMResult<TransactionEffects> {
         self.data_cache
             .into_effects()
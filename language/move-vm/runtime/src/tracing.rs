@@ -1,6 +1,9 @@
 // Copyright (c) The Libra Core Contributors
 // SPDX-License-Identifier: Apache-2.0
 
+use std::{cell::RefCell, rc::Rc};
+use vm::file_format::Bytecode;
+
 #[cfg(debug_assertions)]
 use once_cell::sync::Lazy;
 #[cfg(debug_assertions)]
@@ -10,8 +13,6 @@ use std::{
     io::Write,
     sync::Mutex,
 };
-#[cfg(debug_assertions)]
-use vm::file_format::Bytecode;
 
 #[cfg(debug_assertions)]
 const MOVE_VM_TRACING_ENV_VAR_NAME: &str = "MOVE_VM_TRACE";
@@ -53,3 +54,97 @@ macro_rules! trace {
         crate::tracing::trace(&$function_desc, $pc, &$instr)
     };
 }
+
+/// A call to a Move or native function, as passed to [`TraceCallback::on_function_entry`].
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TracedCall {
+    /// The depth of the call. Entry points called by the adapter are at depth 0.
+    pub depth: usize,
+    /// The fully qualified name of the function, e.g. `0x1::LibraAccount::pay_from`.
+    pub function: String,
+    /// The type arguments of the call.
+    pub ty_args: Vec<String>,
+    /// The values of the arguments of the call.
+    pub args: Vec<String>,
+}
+
+/// Hooks into the interpreter, e.g. for a Move debugger. Functions are identified by their fully
+/// qualified name, e.g. `0x1::LibraAccount::pay_from`, and calls by their depth: entry points
+/// called by the adapter are at depth 0.
+///
+/// The hooks are only invoked in debug builds: they are compiled out of release builds.
+pub trait TraceCallback {
+    /// A Move or native function is called.
+    fn on_function_entry(&mut self, _call: &TracedCall) {}
+
+    /// A function returns. Aborted functions don't return.
+    fn on_function_exit(&mut self, _depth: usize, _function: &str) {}
+
+    /// The instruction at `pc` in a Move function is about to be executed.
+    fn on_instruction(&mut self, _function: &str, _pc: u16, _instruction: &Bytecode) {}
+
+    /// A function aborts with `abort_code`, either on an `Abort` instruction or in a native.
+    fn on_abort(&mut self, _function: &str, _abort_code: u64) {}
+}
+
+thread_local! {
+    static TRACE_CALLBACKS: RefCell<Vec<Rc<RefCell<dyn TraceCallback>>>> = RefCell::new(vec![]);
+}
+
+/// Runs `f`, invoking `callback` as the interpreter runs on this thread in the meantime, along with
+/// the callbacks of any enclosing `trace_with`.
+pub fn trace_with<R>(callback: Rc<RefCell<dyn TraceCallback>>, f: impl FnOnce() -> R) -> R {
+    TRACE_CALLBACKS.with(|callbacks| callbacks.borrow_mut().push(callback));
+    let _pop = PopCallback;
+    f()
+}
+
+/// Pops the callback pushed by `trace_with` when dropped, even if `f` panics.
+struct PopCallback;
+
+impl Drop for PopCallback {
+    fn drop(&mut self) {
+        TRACE_CALLBACKS.with(|callbacks| callbacks.borrow_mut().pop());
+    }
+}
+
+/// Invokes `hook` on the callbacks of `trace_with`, innermost first.
+#[cfg(debug_assertions)]
+pub(crate) fn with_callback(mut hook: impl FnMut(&mut dyn TraceCallback)) {
+    TRACE_CALLBACKS.with(|callbacks| {
+        for callback in callbacks.borrow().iter().rev() {
+            hook(&mut *callback.borrow_mut())
+        }
+    })
+}
+
+#[macro_export]
+macro_rules! trace_callback {
+    ($callback:ident => $hook:expr) => {
+        // Only include this code in debug releases
+        #[cfg(debug_assertions)]
+        crate::tracing::with_callback(|$callback| $hook)
+    };
+}
+
+/// Records the calls passed to [`TraceCallback::on_function_entry`].
+#[derive(Default)]
+struct CallRecorder {
+    calls: Vec<TracedCall>,
+}
+
+impl TraceCallback for CallRecorder {
+    fn on_function_entry(&mut self, call: &TracedCall) {
+        self.calls.push(call.clone());
+    }
+}
+
+/// Runs `f`, recording every Move and native function called on this thread while it runs.
+///
+/// Like the other hooks, this only records calls in debug builds: release builds return no calls.
+pub fn record_calls<R>(f: impl FnOnce() -> R) -> (R, Vec<TracedCall>) {
+    let recorder = Rc::new(RefCell::new(CallRecorder::default()));
+    let result = trace_with(recorder.clone(), f);
+    let calls = std::mem::take(&mut recorder.borrow_mut().calls);
+    (result, calls)
+}
@@ -6,12 +6,15 @@
 //! It is important to note that the cost schedule defined in this file does not track hashing
 //! operations or other native operations; the cost of each native operation will be returned by the
 //! native function itself.
-use libra_types::{transaction::MAX_TRANSACTION_SIZE_IN_BYTES, vm_status::StatusCode};
-use mirai_annotations::*;
-use move_core_types::gas_schedule::{
-    words_in, AbstractMemorySize, CostTable, GasAlgebra, GasCarrier, GasConstants, GasCost,
-    GasUnits,
+use libra_types::vm_status::StatusCode;
+pub use move_core_types::gas_schedule::calculate_intrinsic_gas;
+use move_core_types::{
+    gas_schedule::{
+        AbstractMemorySize, CostTable, GasAlgebra, GasCarrier, GasConstants, GasCost, GasUnits,
+    },
+    language_storage::ModuleId,
 };
+use std::collections::BTreeMap;
 use vm::{
     errors::{Location, PartialVMError, PartialVMResult, VMResult},
     file_format::{
@@ -32,6 +35,25 @@ pub struct CostStrategy<'a> {
     cost_table: &'a CostTable,
     gas_left: GasUnits<GasCarrier>,
     charge: bool,
+    profile: Option<GasProfile>,
+    limits: Option<ExecutionLimits>,
+    instructions_executed: u64,
+}
+
+/// Bounds on the execution of untrusted code, enforced independently from gas so that off-chain
+/// tools, e.g. dry-run endpoints and fuzzers, can run scripts safely with tight limits.
+///
+/// Like gas, the limits only apply while metering is enabled, i.e. not to system code such as the
+/// prologue and epilogue.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct ExecutionLimits {
+    /// Maximum number of instructions interpreted.
+    pub max_instructions: u64,
+    /// Maximum number of nested calls to Move functions.
+    pub max_call_depth: usize,
+    /// Maximum abstract memory size of a value copied, moved, stored, packed, read or written by
+    /// an instruction.
+    pub max_value_size: GasCarrier,
 }
 
 impl<'a> CostStrategy<'a> {
@@ -44,6 +66,9 @@ impl<'a> CostStrategy<'a> {
             gas_left: gas_left.map(|x| x * cost_table.gas_constants.gas_unit_scaling_factor),
             cost_table,
             charge: true,
+            profile: None,
+            limits: None,
+            instructions_executed: 0,
         }
     }
 
@@ -56,6 +81,9 @@ impl<'a> CostStrategy<'a> {
             gas_left: gas_left.map(|x| x * cost_table.gas_constants.gas_unit_scaling_factor),
             cost_table,
             charge: false,
+            profile: None,
+            limits: None,
+            instructions_executed: 0,
         }
     }
 
@@ -74,6 +102,24 @@ impl<'a> CostStrategy<'a> {
 
     /// Charge a given amount of gas and fail if not enough gas units are left.
     pub fn deduct_gas(&mut self, amount: GasUnits<GasCarrier>) -> PartialVMResult<()> {
+        self.deduct_gas_for(amount, GasUsage::Other)
+    }
+
+    /// Charge the gas returned by a native function and fail if not enough gas units are left.
+    pub fn deduct_native_gas(
+        &mut self,
+        module_id: Option<&ModuleId>,
+        function_name: &str,
+        amount: GasUnits<GasCarrier>,
+    ) -> PartialVMResult<()> {
+        self.deduct_gas_for(amount, GasUsage::Native(module_id, function_name))
+    }
+
+    fn deduct_gas_for(
+        &mut self,
+        amount: GasUnits<GasCarrier>,
+        usage: GasUsage,
+    ) -> PartialVMResult<()> {
         if !self.charge {
             return Ok(());
         }
@@ -82,6 +128,9 @@ impl<'a> CostStrategy<'a> {
             .app(&amount, |curr_gas, gas_amt| curr_gas >= gas_amt)
         {
             self.gas_left = self.gas_left.sub(amount);
+            if let Some(profile) = &mut self.profile {
+                profile.record(usage, amount.get());
+            }
             Ok(())
         } else {
             // Zero out the internal gas state
@@ -96,17 +145,22 @@ impl<'a> CostStrategy<'a> {
         opcode: Opcodes,
         size: AbstractMemorySize<GasCarrier>,
     ) -> PartialVMResult<()> {
-        self.deduct_gas(
+        self.check_value_size(opcode, size)?;
+        self.deduct_gas_for(
             self.cost_table
                 .instruction_cost(opcode as u8)
                 .total()
                 .mul(size),
+            GasUsage::Instruction(opcode),
         )
     }
 
     /// Charge an instruction and fail if not enough gas units are left.
     pub fn charge_instr(&mut self, opcode: Opcodes) -> PartialVMResult<()> {
-        self.deduct_gas(self.cost_table.instruction_cost(opcode as u8).total())
+        self.deduct_gas_for(
+            self.cost_table.instruction_cost(opcode as u8).total(),
+            GasUsage::Instruction(opcode),
+        )
     }
 
     /// Charge gas related to the overall size of a transaction and fail if not enough
@@ -116,7 +170,7 @@ impl<'a> CostStrategy<'a> {
         instrinsic_cost: AbstractMemorySize<GasCarrier>,
     ) -> VMResult<()> {
         let cost = calculate_intrinsic_gas(instrinsic_cost, &self.cost_table.gas_constants);
-        self.deduct_gas(cost)
+        self.deduct_gas_for(cost, GasUsage::Intrinsic)
             .map_err(|e| e.finish(Location::Undefined))
     }
 
@@ -126,6 +180,197 @@ impl<'a> CostStrategy<'a> {
     pub fn enable_metering(&mut self) {
         self.charge = true
     }
+
+    /// Start recording a breakdown of all metered gas charges in a `GasProfile`.
+    pub fn enable_profiling(&mut self) {
+        self.profile = Some(GasProfile::default())
+    }
+
+    /// Return the `GasProfile` recorded so far, if profiling was enabled.
+    pub fn take_gas_profile(&mut self) -> Option<GasProfile> {
+        self.profile.take()
+    }
+
+    /// Bound the execution of metered code by `limits`, on top of the gas left.
+    pub fn enable_execution_limits(&mut self, limits: ExecutionLimits) {
+        self.limits = Some(limits)
+    }
+
+    /// Return the number of instructions interpreted with metering enabled.
+    pub fn instructions_executed(&self) -> u64 {
+        self.instructions_executed
+    }
+
+    /// Count an interpreted instruction and fail if the instruction limit is exceeded.
+    pub fn count_instruction(&mut self) -> PartialVMResult<()> {
+        if !self.charge {
+            return Ok(());
+        }
+        self.instructions_executed += 1;
+        match &self.limits {
+            Some(limits) if self.instructions_executed > limits.max_instructions => Err(
+                PartialVMError::new(StatusCode::EXECUTION_INSTRUCTION_LIMIT_REACHED),
+            ),
+            _ => Ok(()),
+        }
+    }
+
+    /// Fail if a call `depth` calls deep exceeds the call depth limit.
+    pub fn check_call_depth(&self, depth: usize) -> PartialVMResult<()> {
+        match &self.limits {
+            Some(limits) if self.charge && depth > limits.max_call_depth => {
+                Err(PartialVMError::new(StatusCode::CALL_DEPTH_LIMIT_REACHED)
+                    .with_message(format!("call depth {}", depth)))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    fn check_value_size(
+        &self,
+        opcode: Opcodes,
+        size: AbstractMemorySize<GasCarrier>,
+    ) -> PartialVMResult<()> {
+        match &self.limits {
+            Some(limits) if self.charge && size.get() > limits.max_value_size => {
+                Err(PartialVMError::new(StatusCode::VALUE_SIZE_LIMIT_REACHED)
+                    .with_message(format!("{:?} over a value of size {}", opcode, size.get())))
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+/// What a gas charge is being made for. Only used to attribute charges in a `GasProfile`.
+enum GasUsage<'a> {
+    Intrinsic,
+    Instruction(Opcodes),
+    Native(Option<&'a ModuleId>, &'a str),
+    Other,
+}
+
+/// Coarse grouping of bytecode instructions used to report gas usage.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq, PartialOrd, Ord)]
+pub enum InstructionCategory {
+    /// Loading constants onto the stack.
+    Constant,
+    /// Copying, moving and storing locals, and popping the stack.
+    Local,
+    /// Arithmetic, bitwise, logical operations and casts.
+    Arithmetic,
+    /// Equality and ordering comparisons.
+    Comparison,
+    /// Branches, returns, aborts and no-ops.
+    ControlFlow,
+    /// Calls to Move or native functions.
+    Call,
+    /// Packing and unpacking structs.
+    Struct,
+    /// Borrowing locals and fields, and reading or writing through references.
+    Reference,
+    /// Operations on global storage.
+    GlobalStorage,
+}
+
+impl From<Opcodes> for InstructionCategory {
+    fn from(opcode: Opcodes) -> Self {
+        use InstructionCategory as C;
+        use Opcodes::*;
+
+        match opcode {
+            LD_U8 | LD_U64 | LD_U128 | LD_CONST | LD_TRUE | LD_FALSE => C::Constant,
+            POP | COPY_LOC | MOVE_LOC | ST_LOC => C::Local,
+            ADD | SUB | MUL | MOD | DIV | BIT_OR | BIT_AND | XOR | OR | AND | NOT | SHL | SHR
+            | CAST_U8 | CAST_U64 | CAST_U128 => C::Arithmetic,
+            EQ | NEQ | LT | GT | LE | GE => C::Comparison,
+            RET | BR_TRUE | BR_FALSE | BRANCH | ABORT | NOP => C::ControlFlow,
+            CALL | CALL_GENERIC => C::Call,
+            PACK | PACK_GENERIC | UNPACK | UNPACK_GENERIC => C::Struct,
+            MUT_BORROW_LOC
+            | IMM_BORROW_LOC
+            | MUT_BORROW_FIELD
+            | MUT_BORROW_FIELD_GENERIC
+            | IMM_BORROW_FIELD
+            | IMM_BORROW_FIELD_GENERIC
+            | READ_REF
+            | WRITE_REF
+            | FREEZE_REF => C::Reference,
+            EXISTS
+            | EXISTS_GENERIC
+            | MUT_BORROW_GLOBAL
+            | MUT_BORROW_GLOBAL_GENERIC
+            | IMM_BORROW_GLOBAL
+            | IMM_BORROW_GLOBAL_GENERIC
+            | MOVE_FROM
+            | MOVE_FROM_GENERIC
+            | MOVE_TO
+            | MOVE_TO_GENERIC => C::GlobalStorage,
+        }
+    }
+}
+
+/// A breakdown of the gas charged through a `CostStrategy` while profiling was enabled.
+///
+/// All amounts are in internal gas units, i.e. before dividing by the gas unit scaling factor.
+/// Charges made while metering is disabled are not recorded.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct GasProfile {
+    intrinsic: GasCarrier,
+    instructions: BTreeMap<InstructionCategory, GasCarrier>,
+    natives: BTreeMap<String, GasCarrier>,
+    other: GasCarrier,
+}
+
+impl GasProfile {
+    fn record(&mut self, usage: GasUsage, amount: GasCarrier) {
+        match usage {
+            GasUsage::Intrinsic => self.intrinsic += amount,
+            GasUsage::Instruction(opcode) => {
+                *self
+                    .instructions
+                    .entry(InstructionCategory::from(opcode))
+                    .or_insert(0) += amount
+            }
+            GasUsage::Native(module_id, name) => {
+                let key = match module_id {
+                    Some(module_id) => {
+                        format!("{}::{}::{}", module_id.address(), module_id.name(), name)
+                    }
+                    None => name.to_string(),
+                };
+                *self.natives.entry(key).or_insert(0) += amount
+            }
+            GasUsage::Other => self.other += amount,
+        }
+    }
+
+    /// Gas charged for the size of the transaction.
+    pub fn intrinsic(&self) -> GasCarrier {
+        self.intrinsic
+    }
+
+    /// Gas charged per instruction category.
+    pub fn instructions(&self) -> &BTreeMap<InstructionCategory, GasCarrier> {
+        &self.instructions
+    }
+
+    /// Gas charged per native function, keyed by `address::Module::function`.
+    pub fn natives(&self) -> &BTreeMap<String, GasCarrier> {
+        &self.natives
+    }
+
+    /// Gas charged outside of instructions and natives, e.g. for global writes.
+    pub fn other(&self) -> GasCarrier {
+        self.other
+    }
+
+    /// Total gas charged across all categories.
+    pub fn total(&self) -> GasCarrier {
+        self.intrinsic
+            + self.instructions.values().sum::<GasCarrier>()
+            + self.natives.values().sum::<GasCarrier>()
+            + self.other
+    }
 }
 
 pub fn new_from_instructions(
@@ -273,22 +518,6 @@ pub fn zero_cost_schedule() -> CostTable {
     new_from_instructions(instrs, native_table)
 }
 
-/// Calculate the intrinsic gas for the transaction based upon its size in bytes/words.
-pub fn calculate_intrinsic_gas(
-    transaction_size: AbstractMemorySize<GasCarrier>,
-    gas_constants: &GasConstants,
-) -> GasUnits<GasCarrier> {
-    precondition!(transaction_size.get() <= MAX_TRANSACTION_SIZE_IN_BYTES as GasCarrier);
-    let min_transaction_fee = gas_constants.min_transaction_gas_units;
-
-    if transaction_size.get() > gas_constants.large_transaction_cutoff.get() {
-        let excess = words_in(transaction_size.sub(gas_constants.large_transaction_cutoff));
-        min_transaction_fee.add(gas_constants.instrinsic_gas_per_byte.mul(excess))
-    } else {
-        min_transaction_fee.unitary_cast()
-    }
-}
-
 #[allow(non_camel_case_types)]
 #[derive(Clone, Copy, Debug, Eq, Hash, PartialEq, PartialOrd, Ord)]
 #[repr(u8)]
@@ -2148,6 +2148,16 @@ impl GlobalValue {
     pub fn into_owned_struct(self) -> PartialVMResult<Struct> {
         Ok(Struct(take_unique_ownership(self.container)?))
     }
+
+    /// Serializes the resource without taking ownership of it, e.g. to measure what it would
+    /// write back to storage before it's written.
+    pub fn simple_serialize(&self, layout: &MoveStructLayout) -> Option<Vec<u8>> {
+        lcs::to_bytes(&AnnotatedValue {
+            layout,
+            val: &*self.container.borrow(),
+        })
+        .ok()
+    }
 }
 
 /***************************************************************************************
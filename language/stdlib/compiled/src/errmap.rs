@@ -0,0 +1,74 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Explanations of the abort codes raised by the standard library modules.
+//!
+//! The error map is generated from the error constants of the modules, together with the
+//! compiled stdlib, so that clients can report which constant a transaction aborted with:
+//!
+//! ```ignore
+//! if let VMStatus::MoveAbort(AbortLocation::Module(module_id), code) = status {
+//!     if let Some(context) = explain_abort(&module_id, code) {
+//!         println!("{}", context.reason.code_name); // e.g. EINSUFFICIENT_BALANCE
+//!     }
+//! }
+//! ```
+
+use move_core_types::{
+    errmap::{ErrorContext, ErrorMapping},
+    language_storage::ModuleId,
+};
+use once_cell::sync::Lazy;
+
+// This needs to be a string literal due to restrictions imposed by include_bytes.
+/// The error map of the compiled stdlib, as generated next to it.
+pub const COMPILED_ERROR_MAP_BYTES: &[u8] =
+    std::include_bytes!("../error_descriptions/error_descriptions.errmap");
+
+static COMPILED_ERROR_MAP: Lazy<ErrorMapping> = Lazy::new(|| {
+    lcs::from_bytes(COMPILED_ERROR_MAP_BYTES).expect("Failed to deserialize the stdlib error map")
+});
+
+/// Returns the error map of the compiled stdlib.
+pub fn error_map() -> &'static ErrorMapping {
+    &*COMPILED_ERROR_MAP
+}
+
+/// Returns the explanation of the abort code `code` raised by the stdlib module `module_id`, or
+/// `None` if the module does not define an error constant for it.
+pub fn explain_abort(module_id: &ModuleId, code: u64) -> Option<ErrorContext> {
+    error_map().get_explanation(module_id, code)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use libra_types::account_config::CORE_CODE_ADDRESS;
+    use move_core_types::identifier::Identifier;
+
+    fn stdlib_module(name: &str) -> ModuleId {
+        ModuleId::new(CORE_CODE_ADDRESS, Identifier::new(name).unwrap())
+    }
+
+    #[test]
+    fn test_explain_abort() {
+        let context = explain_abort(&stdlib_module("LibraAccount"), 5).unwrap();
+        assert_eq!(context.category, None);
+        assert_eq!(context.reason.code_name, "EINSUFFICIENT_BALANCE");
+
+        let context = explain_abort(&stdlib_module("LibraAccount"), 17).unwrap();
+        assert_eq!(context.reason.code_name, "EPAYEE_DOES_NOT_EXIST");
+        assert_eq!(
+            context.reason.code_description,
+            "Attempting to send funds to an account that does not exist"
+        );
+
+        assert_eq!(explain_abort(&stdlib_module("LibraAccount"), 1000), None);
+        assert_eq!(explain_abort(&stdlib_module("NotAModule"), 0), None);
+    }
+
+    #[test]
+    fn test_compiled_error_map_is_up_to_date() {
+        assert_eq!(error_map(), &stdlib::build_error_code_map());
+    }
+}
@@ -3,6 +3,11 @@
 
 #![forbid(unsafe_code)]
 
+pub mod errmap;
+pub mod package;
+pub mod script_decoder;
+pub mod script_functions;
+pub mod script_registry;
 pub mod transaction_scripts;
 
 use bytecode_verifier::{verify_module, DependencyChecker};
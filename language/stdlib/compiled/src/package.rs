@@ -0,0 +1,359 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! A versioned snapshot of the standard library modules and transaction scripts.
+//!
+//! Release tooling compares the package being released against the one on chain. The same
+//! `StdlibDiff` produces both the upgrade change set and the changelog:
+//!
+//! ```ignore
+//! let old = StdlibPackage::compiled();
+//! let new = StdlibPackage::new(StdlibVersion::new(1, 1, 0), &new_modules, new_scripts);
+//! let changes = diff(&old, &new);
+//! println!("{}", changes);
+//! let change_set = new.upgrade_change_set(&old);
+//! ```
+
+use crate::{stdlib_modules, transaction_scripts::StdlibScript, StdLibOptions};
+use anyhow::{anyhow, Error, Result};
+use libra_crypto::HashValue;
+use libra_types::{
+    access_path::AccessPath,
+    transaction::ChangeSet,
+    write_set::{WriteOp, WriteSetMut},
+};
+use move_core_types::language_storage::ModuleId;
+use std::{collections::BTreeMap, fmt, str::FromStr};
+use vm::CompiledModule;
+
+/// The version of the compiled stdlib shipped in this crate.
+pub const COMPILED_STDLIB_VERSION: StdlibVersion = StdlibVersion::new(1, 0, 0);
+
+/// A semantic version of the standard library. A new major version may break the modules or
+/// scripts that transactions and other modules depend on; minor and patch versions may not.
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct StdlibVersion {
+    pub major: u64,
+    pub minor: u64,
+    pub patch: u64,
+}
+
+impl StdlibVersion {
+    pub const fn new(major: u64, minor: u64, patch: u64) -> Self {
+        Self {
+            major,
+            minor,
+            patch,
+        }
+    }
+
+    /// Whether users of `self` can upgrade to `other` without breaking changes.
+    pub fn is_compatible_with(&self, other: &StdlibVersion) -> bool {
+        self.major == other.major && self <= other
+    }
+}
+
+impl fmt::Display for StdlibVersion {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)
+    }
+}
+
+impl FromStr for StdlibVersion {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let parts = s
+            .split('.')
+            .map(u64::from_str)
+            .collect::<std::result::Result<Vec<_>, _>>()
+            .map_err(|err| anyhow!("Invalid stdlib version {}: {}", s, err))?;
+        match parts.as_slice() {
+            [major, minor, patch] => Ok(Self::new(*major, *minor, *patch)),
+            _ => Err(anyhow!(
+                "Invalid stdlib version {}: expected major.minor.patch",
+                s
+            )),
+        }
+    }
+}
+
+/// The modules and scripts of a stdlib release, as serialized bytes.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct StdlibPackage {
+    version: StdlibVersion,
+    modules: BTreeMap<ModuleId, Vec<u8>>,
+    scripts: BTreeMap<String, Vec<u8>>,
+}
+
+impl StdlibPackage {
+    /// Build a package from compiled modules and `(name, bytecode)` scripts.
+    pub fn new(
+        version: StdlibVersion,
+        modules: &[CompiledModule],
+        scripts: impl IntoIterator<Item = (String, Vec<u8>)>,
+    ) -> Self {
+        let modules = modules
+            .iter()
+            .map(|module| {
+                let mut bytes = vec![];
+                module
+                    .serialize(&mut bytes)
+                    .expect("Failed to serialize module");
+                (module.self_id(), bytes)
+            })
+            .collect();
+        Self {
+            version,
+            modules,
+            scripts: scripts.into_iter().collect(),
+        }
+    }
+
+    /// Return the package of the compiled stdlib and scripts shipped in this crate.
+    pub fn compiled() -> Self {
+        Self::new(
+            COMPILED_STDLIB_VERSION,
+            stdlib_modules(StdLibOptions::Compiled),
+            StdlibScript::all()
+                .into_iter()
+                .map(|script| (script.name(), script.code().to_vec())),
+        )
+    }
+
+    pub fn version(&self) -> StdlibVersion {
+        self.version
+    }
+
+    /// Return the serialized modules, by module id.
+    pub fn modules(&self) -> &BTreeMap<ModuleId, Vec<u8>> {
+        &self.modules
+    }
+
+    /// Return the script bytecode, by script name.
+    pub fn scripts(&self) -> &BTreeMap<String, Vec<u8>> {
+        &self.scripts
+    }
+
+    /// Return the sha3-256 hash of the modules and scripts of the package. The version is not
+    /// part of the hash, so two packages with the same content have the same hash.
+    pub fn hash(&self) -> HashValue {
+        let bytes = lcs::to_bytes(&(&self.modules, &self.scripts))
+            .expect("Failed to serialize stdlib package");
+        HashValue::sha3_256_of(&bytes)
+    }
+
+    /// Build the change set upgrading the modules of `old` to the modules of this package: added
+    /// and changed modules are written and removed modules are deleted.
+    pub fn upgrade_change_set(&self, old: &StdlibPackage) -> ChangeSet {
+        let changes = diff(old, self);
+        let mut write_set = WriteSetMut::new(vec![]);
+        for module_id in changes
+            .added_modules
+            .iter()
+            .chain(changes.changed_modules.iter())
+        {
+            write_set.push((
+                AccessPath::code_access_path(module_id),
+                WriteOp::Value(self.modules[module_id].clone()),
+            ));
+        }
+        for module_id in &changes.removed_modules {
+            write_set.push((AccessPath::code_access_path(module_id), WriteOp::Deletion));
+        }
+        ChangeSet::new(
+            write_set.freeze().expect("Failed to create writeset"),
+            vec![],
+        )
+    }
+}
+
+/// The differences between two stdlib packages. Every list is sorted.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct StdlibDiff {
+    pub old_version: StdlibVersion,
+    pub new_version: StdlibVersion,
+    pub added_modules: Vec<ModuleId>,
+    pub removed_modules: Vec<ModuleId>,
+    pub changed_modules: Vec<ModuleId>,
+    pub added_scripts: Vec<String>,
+    pub removed_scripts: Vec<String>,
+    pub changed_scripts: Vec<String>,
+}
+
+impl StdlibDiff {
+    /// Whether the packages have the same modules and scripts.
+    pub fn is_empty(&self) -> bool {
+        self.added_modules.is_empty()
+            && self.removed_modules.is_empty()
+            && self.changed_modules.is_empty()
+            && self.added_scripts.is_empty()
+            && self.removed_scripts.is_empty()
+            && self.changed_scripts.is_empty()
+    }
+}
+
+/// Displays the diff as a changelog, listing only the kinds of changes that occur.
+impl fmt::Display for StdlibDiff {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "Stdlib {} -> {}", self.old_version, self.new_version)?;
+        if self.is_empty() {
+            return writeln!(f, "No changes");
+        }
+        fn section<T: fmt::Display>(
+            f: &mut fmt::Formatter,
+            title: &str,
+            items: &[T],
+        ) -> fmt::Result {
+            if items.is_empty() {
+                return Ok(());
+            }
+            writeln!(f, "{}:", title)?;
+            for item in items {
+                writeln!(f, "  {}", item)?;
+            }
+            Ok(())
+        }
+        section(f, "Added modules", &self.added_modules)?;
+        section(f, "Removed modules", &self.removed_modules)?;
+        section(f, "Changed modules", &self.changed_modules)?;
+        section(f, "Added scripts", &self.added_scripts)?;
+        section(f, "Removed scripts", &self.removed_scripts)?;
+        section(f, "Changed scripts", &self.changed_scripts)
+    }
+}
+
+/// Return the modules and scripts added, removed and changed from `old` to `new`.
+pub fn diff(old: &StdlibPackage, new: &StdlibPackage) -> StdlibDiff {
+    let (added_modules, removed_modules, changed_modules) = diff_maps(&old.modules, &new.modules);
+    let (added_scripts, removed_scripts, changed_scripts) = diff_maps(&old.scripts, &new.scripts);
+    StdlibDiff {
+        old_version: old.version,
+        new_version: new.version,
+        added_modules,
+        removed_modules,
+        changed_modules,
+        added_scripts,
+        removed_scripts,
+        changed_scripts,
+    }
+}
+
+/// Return the keys only in `new`, only in `old`, and in both with different values.
+fn diff_maps<K: Clone + Ord>(
+    old: &BTreeMap<K, Vec<u8>>,
+    new: &BTreeMap<K, Vec<u8>>,
+) -> (Vec<K>, Vec<K>, Vec<K>) {
+    let added = new
+        .keys()
+        .filter(|key| !old.contains_key(key))
+        .cloned()
+        .collect();
+    let removed = old
+        .keys()
+        .filter(|key| !new.contains_key(key))
+        .cloned()
+        .collect();
+    let changed = new
+        .iter()
+        .filter(|(key, bytes)| old.get(key).map_or(false, |old_bytes| old_bytes != *bytes))
+        .map(|(key, _)| key.clone())
+        .collect();
+    (added, removed, changed)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn modified_package() -> (StdlibPackage, ModuleId, ModuleId) {
+        let mut package = StdlibPackage::compiled();
+        package.version = StdlibVersion::new(1, 1, 0);
+        let mut module_ids = package.modules.keys().cloned();
+        let removed = module_ids.next().unwrap();
+        let changed = module_ids.next().unwrap();
+        package.modules.remove(&removed);
+        package.modules.get_mut(&changed).unwrap().push(0);
+        package
+            .scripts
+            .insert("new_script".to_string(), vec![0xA1, 0x1C, 0xEB, 0x0B]);
+        package.scripts.remove("burn");
+        package
+            .scripts
+            .get_mut("peer_to_peer_with_metadata")
+            .unwrap()
+            .push(0);
+        (package, removed, changed)
+    }
+
+    #[test]
+    fn test_version() {
+        let version: StdlibVersion = "1.2.3".parse().unwrap();
+        assert_eq!(version, StdlibVersion::new(1, 2, 3));
+        assert_eq!(version.to_string(), "1.2.3");
+        assert!("1.2".parse::<StdlibVersion>().is_err());
+        assert!("1.2.x".parse::<StdlibVersion>().is_err());
+
+        assert!(version.is_compatible_with(&StdlibVersion::new(1, 3, 0)));
+        assert!(!version.is_compatible_with(&StdlibVersion::new(1, 2, 2)));
+        assert!(!version.is_compatible_with(&StdlibVersion::new(2, 0, 0)));
+    }
+
+    #[test]
+    fn test_diff_of_same_package_is_empty() {
+        let package = StdlibPackage::compiled();
+        let changes = diff(&package, &package);
+        assert!(changes.is_empty());
+        assert_eq!(changes.to_string(), "Stdlib 1.0.0 -> 1.0.0\nNo changes\n");
+        assert!(package.upgrade_change_set(&package).write_set().is_empty());
+    }
+
+    #[test]
+    fn test_diff() {
+        let old = StdlibPackage::compiled();
+        let (new, removed, changed) = modified_package();
+        assert_ne!(old.hash(), new.hash());
+
+        let changes = diff(&old, &new);
+        assert_eq!(changes.new_version, StdlibVersion::new(1, 1, 0));
+        assert!(changes.added_modules.is_empty());
+        assert_eq!(changes.removed_modules, vec![removed.clone()]);
+        assert_eq!(changes.changed_modules, vec![changed.clone()]);
+        assert_eq!(changes.added_scripts, vec!["new_script".to_string()]);
+        assert_eq!(changes.removed_scripts, vec!["burn".to_string()]);
+        assert_eq!(
+            changes.changed_scripts,
+            vec!["peer_to_peer_with_metadata".to_string()]
+        );
+        assert_eq!(
+            changes.to_string(),
+            format!(
+                "Stdlib 1.0.0 -> 1.1.0\nRemoved modules:\n  {}\nChanged modules:\n  {}\n\
+                 Added scripts:\n  new_script\nRemoved scripts:\n  burn\n\
+                 Changed scripts:\n  peer_to_peer_with_metadata\n",
+                removed, changed
+            )
+        );
+
+        let change_set = new.upgrade_change_set(&old);
+        let writes = change_set.write_set().iter().cloned().collect::<Vec<_>>();
+        assert_eq!(
+            writes,
+            vec![
+                (
+                    AccessPath::code_access_path(&changed),
+                    WriteOp::Value(new.modules()[&changed].clone())
+                ),
+                (AccessPath::code_access_path(&removed), WriteOp::Deletion),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_hash_ignores_version() {
+        let package = StdlibPackage::compiled();
+        let mut bumped = package.clone();
+        bumped.version = StdlibVersion::new(1, 0, 1);
+        assert_eq!(package.hash(), bumped.hash());
+    }
+}
@@ -0,0 +1,167 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Decoding of transaction scripts into calls of named standard library scripts.
+//!
+//! A `Script` only carries bytecode and positional arguments. Matching the bytecode against the
+//! standard library scripts gives its ABI, which names every argument:
+//!
+//! ```ignore
+//! let call = decode_script(&script).expect("not a valid stdlib script call");
+//! assert_eq!(call.script(), StdlibScript::PeerToPeerWithMetadata);
+//! let payee = call.arg("payee");
+//! let currency = call.ty_arg("currency");
+//! ```
+
+use crate::transaction_scripts::StdlibScript;
+use libra_types::transaction::{Script, ScriptABI, TransactionArgument};
+use move_core_types::{language_storage::TypeTag, transaction_argument::is_argument_of_type};
+use std::convert::TryFrom;
+
+/// The type arguments and arguments of a script call, in order, named per the script's ABI.
+pub type NamedArguments = (Vec<(String, TypeTag)>, Vec<(String, TransactionArgument)>);
+
+/// A call of a standard library script, with its arguments named per the script's ABI.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct DecodedScriptCall {
+    script: StdlibScript,
+    ty_args: Vec<(String, TypeTag)>,
+    args: Vec<(String, TransactionArgument)>,
+}
+
+impl DecodedScriptCall {
+    pub fn script(&self) -> StdlibScript {
+        self.script
+    }
+
+    /// Return the type arguments with their names, in order.
+    pub fn ty_args(&self) -> &[(String, TypeTag)] {
+        &self.ty_args
+    }
+
+    /// Return the arguments with their names, in order.
+    pub fn args(&self) -> &[(String, TransactionArgument)] {
+        &self.args
+    }
+
+    /// Return the type argument named `name`.
+    pub fn ty_arg(&self, name: &str) -> Option<&TypeTag> {
+        self.ty_args
+            .iter()
+            .find(|(ty_arg_name, _)| ty_arg_name == name)
+            .map(|(_, ty_arg)| ty_arg)
+    }
+
+    /// Return the argument named `name`.
+    pub fn arg(&self, name: &str) -> Option<&TransactionArgument> {
+        self.args
+            .iter()
+            .find(|(arg_name, _)| arg_name == name)
+            .map(|(_, arg)| arg)
+    }
+}
+
+/// Decode `script` as a call of a standard library script. Returns `None` if the code is not a
+/// standard library script, or if the number of type arguments or the number and types of the
+/// arguments do not match its ABI.
+pub fn decode_script(script: &Script) -> Option<DecodedScriptCall> {
+    let stdlib_script = StdlibScript::try_from(script.code()).ok()?;
+    let (ty_args, args) = name_arguments(stdlib_script.abi_ref(), script)?;
+    Some(DecodedScriptCall {
+        script: stdlib_script,
+        ty_args,
+        args,
+    })
+}
+
+/// Name the type arguments and arguments of `script` per `abi`. Returns `None` if the number of
+/// type arguments or the number and types of the arguments do not match the ABI. The code of
+/// `script` is not checked against the ABI.
+pub fn name_arguments(abi: &ScriptABI, script: &Script) -> Option<NamedArguments> {
+    if abi.ty_args().len() != script.ty_args().len() || abi.args().len() != script.args().len() {
+        return None;
+    }
+    let ty_args = abi
+        .ty_args()
+        .iter()
+        .zip(script.ty_args())
+        .map(|(ty_arg_abi, ty_arg)| (ty_arg_abi.name().to_string(), ty_arg.clone()))
+        .collect();
+    let args = abi
+        .args()
+        .iter()
+        .zip(script.args())
+        .map(|(arg_abi, arg)| {
+            if is_argument_of_type(arg, arg_abi.type_tag()) {
+                Some((arg_abi.name().to_string(), arg.clone()))
+            } else {
+                None
+            }
+        })
+        .collect::<Option<_>>()?;
+    Some((ty_args, args))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use libra_types::account_address::AccountAddress;
+
+    fn peer_to_peer_script(args: Vec<TransactionArgument>) -> Script {
+        Script::new(
+            StdlibScript::PeerToPeerWithMetadata
+                .compiled_bytes()
+                .into_vec(),
+            vec![TypeTag::Bool],
+            args,
+        )
+    }
+
+    #[test]
+    fn test_decode_peer_to_peer() {
+        let payee = AccountAddress::random();
+        let script = peer_to_peer_script(vec![
+            TransactionArgument::Address(payee),
+            TransactionArgument::U64(100),
+            TransactionArgument::U8Vector(vec![1]),
+            TransactionArgument::U8Vector(vec![]),
+        ]);
+        let call = decode_script(&script).unwrap();
+        assert!(call.script() == StdlibScript::PeerToPeerWithMetadata);
+        assert_eq!(call.ty_arg("currency"), Some(&TypeTag::Bool));
+        assert_eq!(
+            call.arg("payee"),
+            Some(&TransactionArgument::Address(payee))
+        );
+        assert_eq!(call.arg("amount"), Some(&TransactionArgument::U64(100)));
+        assert_eq!(
+            call.arg("metadata"),
+            Some(&TransactionArgument::U8Vector(vec![1]))
+        );
+        assert_eq!(
+            call.arg("metadata_signature"),
+            Some(&TransactionArgument::U8Vector(vec![]))
+        );
+        assert_eq!(call.arg("missing"), None);
+    }
+
+    #[test]
+    fn test_decode_rejects_mismatches() {
+        // Wrong argument type.
+        let script = peer_to_peer_script(vec![
+            TransactionArgument::U64(1),
+            TransactionArgument::U64(100),
+            TransactionArgument::U8Vector(vec![]),
+            TransactionArgument::U8Vector(vec![]),
+        ]);
+        assert_eq!(decode_script(&script), None);
+
+        // Wrong number of arguments.
+        let script = peer_to_peer_script(vec![]);
+        assert_eq!(decode_script(&script), None);
+
+        // Unknown code.
+        let script = Script::new(vec![], vec![], vec![]);
+        assert_eq!(decode_script(&script), None);
+    }
+}
@@ -0,0 +1,282 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Calls of the standard library functions that a transaction can invoke by name.
+//!
+//! A script function payload names a public function of a published module instead of carrying
+//! script bytecode, so the transaction does not depend on the exact compiled script:
+//!
+//! ```ignore
+//! let payload = ScriptFunctionCall::AddCurrency { currency: lbr_type_tag() }.encode();
+//! assert_eq!(
+//!     ScriptFunctionCall::decode(&payload),
+//!     Some(ScriptFunctionCall::AddCurrency { currency: lbr_type_tag() })
+//! );
+//! ```
+
+use include_dir::{include_dir, Dir};
+use libra_types::{
+    account_config::CORE_CODE_ADDRESS,
+    transaction::{ScriptFunction, ScriptFunctionABI, TransactionArgument, TransactionPayload},
+};
+use move_core_types::{
+    account_address::AccountAddress,
+    identifier::Identifier,
+    language_storage::{ModuleId, TypeTag},
+};
+use once_cell::sync::Lazy;
+
+// This includes the script function ABIs as binaries, for the same reason as the script ABIs.
+const SCRIPT_FUNCTIONS_ABI_DIR: Dir = include_dir!("script_functions/abi");
+
+/// The ABI of every script function, ordered by module and function name.
+static SCRIPT_FUNCTION_ABIS: Lazy<Vec<ScriptFunctionABI>> = Lazy::new(|| {
+    let mut files = SCRIPT_FUNCTIONS_ABI_DIR.files().to_vec();
+    files.sort_by_key(|file| file.path().to_path_buf());
+    files
+        .iter()
+        .map(|file| {
+            lcs::from_bytes(file.contents()).unwrap_or_else(|err| {
+                panic!("Failed to deserialize ABI file {:?}: {}", file.path(), err)
+            })
+        })
+        .collect()
+});
+
+/// Return the ABIs of all standard library script functions.
+pub fn script_function_abis() -> &'static [ScriptFunctionABI] {
+    &SCRIPT_FUNCTION_ABIS
+}
+
+/// A call of a standard library script function. The signer of the transaction is passed as the
+/// `&signer` argument of the function.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum ScriptFunctionCall {
+    /// Add a balance of `currency` to the sending account.
+    AddCurrency { currency: TypeTag },
+    /// Rotate the dual attestation base URL of the sending account.
+    RotateBaseUrl { new_url: Vec<u8> },
+    /// Rotate the dual attestation compliance public key of the sending account.
+    RotateCompliancePublicKey { new_key: Vec<u8> },
+    /// Rotate the shared ed25519 public key of the sending account, and its authentication key.
+    RotateSharedEd25519PublicKey { new_public_key: Vec<u8> },
+    /// Set the operator of the validator config of the sending account.
+    SetValidatorOperator { operator_account: AccountAddress },
+    /// Remove the operator of the validator config of the sending account.
+    RemoveValidatorOperator,
+    /// Update the on-chain `LibraVersion`. The sender must be the Libra root account.
+    SetLibraVersion { major: u64 },
+}
+
+impl ScriptFunctionCall {
+    /// Return the names of the module and function called.
+    pub fn names(&self) -> (&'static str, &'static str) {
+        use ScriptFunctionCall::*;
+        match self {
+            AddCurrency { .. } => ("LibraAccount", "add_currency"),
+            RotateBaseUrl { .. } => ("DualAttestation", "rotate_base_url"),
+            RotateCompliancePublicKey { .. } => ("DualAttestation", "rotate_compliance_public_key"),
+            RotateSharedEd25519PublicKey { .. } => ("SharedEd25519PublicKey", "rotate_key"),
+            SetValidatorOperator { .. } => ("ValidatorConfig", "set_operator"),
+            RemoveValidatorOperator => ("ValidatorConfig", "remove_operator"),
+            SetLibraVersion { .. } => ("LibraVersion", "set"),
+        }
+    }
+
+    /// Return the ABI of the function called.
+    pub fn abi(&self) -> &'static ScriptFunctionABI {
+        let (module_name, function_name) = self.names();
+        script_function_abis()
+            .iter()
+            .find(|abi| {
+                abi.module_name().name().as_str() == module_name && abi.name() == function_name
+            })
+            .unwrap_or_else(|| panic!("No ABI for {}::{}", module_name, function_name))
+    }
+
+    /// Build the transaction payload of the call.
+    pub fn encode(self) -> TransactionPayload {
+        use ScriptFunctionCall::*;
+        let (module_name, function_name) = self.names();
+        let (ty_args, args) = match self {
+            AddCurrency { currency } => (vec![currency], vec![]),
+            RotateBaseUrl { new_url } => (vec![], vec![TransactionArgument::U8Vector(new_url)]),
+            RotateCompliancePublicKey { new_key } => {
+                (vec![], vec![TransactionArgument::U8Vector(new_key)])
+            }
+            RotateSharedEd25519PublicKey { new_public_key } => {
+                (vec![], vec![TransactionArgument::U8Vector(new_public_key)])
+            }
+            SetValidatorOperator { operator_account } => {
+                (vec![], vec![TransactionArgument::Address(operator_account)])
+            }
+            RemoveValidatorOperator => (vec![], vec![]),
+            SetLibraVersion { major } => (vec![], vec![TransactionArgument::U64(major)]),
+        };
+        TransactionPayload::ScriptFunction(ScriptFunction::new(
+            ModuleId::new(
+                CORE_CODE_ADDRESS,
+                Identifier::new(module_name).expect("module name must be a valid identifier"),
+            ),
+            Identifier::new(function_name).expect("function name must be a valid identifier"),
+            ty_args,
+            args,
+        ))
+    }
+
+    /// Decode `payload` as a call of a standard library script function. Returns `None` if the
+    /// payload is not a script function, if the function is not a known script function, or if
+    /// its type arguments or arguments do not match the function.
+    pub fn decode(payload: &TransactionPayload) -> Option<ScriptFunctionCall> {
+        use ScriptFunctionCall::*;
+        let function = match payload {
+            TransactionPayload::ScriptFunction(function) => function,
+            _ => return None,
+        };
+        if function.module().address() != &CORE_CODE_ADDRESS {
+            return None;
+        }
+        match (
+            function.module().name().as_str(),
+            function.function().as_str(),
+            function.ty_args(),
+            function.args(),
+        ) {
+            ("LibraAccount", "add_currency", [currency], []) => Some(AddCurrency {
+                currency: currency.clone(),
+            }),
+            (
+                "DualAttestation",
+                "rotate_base_url",
+                [],
+                [TransactionArgument::U8Vector(new_url)],
+            ) => Some(RotateBaseUrl {
+                new_url: new_url.clone(),
+            }),
+            (
+                "DualAttestation",
+                "rotate_compliance_public_key",
+                [],
+                [TransactionArgument::U8Vector(new_key)],
+            ) => Some(RotateCompliancePublicKey {
+                new_key: new_key.clone(),
+            }),
+            (
+                "SharedEd25519PublicKey",
+                "rotate_key",
+                [],
+                [TransactionArgument::U8Vector(new_public_key)],
+            ) => Some(RotateSharedEd25519PublicKey {
+                new_public_key: new_public_key.clone(),
+            }),
+            (
+                "ValidatorConfig",
+                "set_operator",
+                [],
+                [TransactionArgument::Address(operator_account)],
+            ) => Some(SetValidatorOperator {
+                operator_account: *operator_account,
+            }),
+            ("ValidatorConfig", "remove_operator", [], []) => Some(RemoveValidatorOperator),
+            ("LibraVersion", "set", [], [TransactionArgument::U64(major)]) => {
+                Some(SetLibraVersion { major: *major })
+            }
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use move_core_types::transaction_argument::is_argument_of_type;
+
+    fn all_calls() -> Vec<ScriptFunctionCall> {
+        use ScriptFunctionCall::*;
+        vec![
+            AddCurrency {
+                currency: TypeTag::U64,
+            },
+            RotateBaseUrl {
+                new_url: b"https://libra.org".to_vec(),
+            },
+            RotateCompliancePublicKey {
+                new_key: vec![1; 32],
+            },
+            RotateSharedEd25519PublicKey {
+                new_public_key: vec![2; 32],
+            },
+            SetValidatorOperator {
+                operator_account: AccountAddress::random(),
+            },
+            RemoveValidatorOperator,
+            SetLibraVersion { major: 2 },
+        ]
+    }
+
+    #[test]
+    fn test_encode_decode_roundtrip() {
+        for call in all_calls() {
+            let payload = call.clone().encode();
+            assert_eq!(ScriptFunctionCall::decode(&payload), Some(call));
+        }
+    }
+
+    #[test]
+    fn test_encoding_matches_abi() {
+        let calls = all_calls();
+        assert_eq!(calls.len(), script_function_abis().len());
+        for call in calls {
+            let abi = call.abi();
+            let function = match call.clone().encode() {
+                TransactionPayload::ScriptFunction(function) => function,
+                _ => unreachable!(),
+            };
+            assert_eq!(function.module(), abi.module_name());
+            assert_eq!(function.function().as_str(), abi.name());
+            assert_eq!(function.ty_args().len(), abi.ty_args().len());
+            assert_eq!(function.args().len(), abi.args().len());
+            for (arg, arg_abi) in function.args().iter().zip(abi.args()) {
+                assert!(is_argument_of_type(arg, arg_abi.type_tag()));
+            }
+        }
+    }
+
+    #[test]
+    fn test_decode_rejects_mismatches() {
+        let function = |module: &str, name: &str, args| {
+            TransactionPayload::ScriptFunction(ScriptFunction::new(
+                ModuleId::new(CORE_CODE_ADDRESS, Identifier::new(module).unwrap()),
+                Identifier::new(name).unwrap(),
+                vec![],
+                args,
+            ))
+        };
+        // Wrong argument type.
+        let payload = function(
+            "LibraVersion",
+            "set",
+            vec![TransactionArgument::Address(AccountAddress::random())],
+        );
+        assert_eq!(ScriptFunctionCall::decode(&payload), None);
+        // Unknown function.
+        let payload = function("LibraVersion", "initialize", vec![]);
+        assert_eq!(ScriptFunctionCall::decode(&payload), None);
+        // Not a script function.
+        let payload = TransactionPayload::Script(libra_types::transaction::Script::new(
+            vec![],
+            vec![],
+            vec![],
+        ));
+        assert_eq!(ScriptFunctionCall::decode(&payload), None);
+    }
+
+    #[test]
+    fn test_compiled_abis_are_up_to_date() {
+        let mut abis = stdlib::build_script_function_abis();
+        abis.sort_by(|a, b| {
+            (a.module_name().name(), a.name()).cmp(&(b.module_name().name(), b.name()))
+        });
+        assert_eq!(script_function_abis(), abis.as_slice());
+    }
+}
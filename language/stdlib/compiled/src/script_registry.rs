@@ -0,0 +1,224 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! A set of transaction script ABIs that can be extended at runtime.
+//!
+//! `StdlibScript` only knows the scripts compiled into this crate. A chain running a modified
+//! whitelist can describe its scripts with ABI files instead, and load them into a
+//! `StdlibScriptRegistry` next to (or in place of) the built-in ones.
+
+use crate::{
+    script_decoder::{name_arguments, NamedArguments},
+    transaction_scripts::StdlibScript,
+};
+use anyhow::{Context, Result};
+use libra_crypto::HashValue;
+use libra_types::transaction::{Script, ScriptABI, SCRIPT_HASH_LENGTH};
+use std::{collections::BTreeMap, fs, path::Path};
+
+/// Script ABIs, by script name. Loading an ABI with the name of a script already in the registry
+/// replaces it.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct StdlibScriptRegistry {
+    abis: BTreeMap<String, ScriptABI>,
+}
+
+impl StdlibScriptRegistry {
+    /// Return an empty registry.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Return a registry holding the ABIs of all built-in `StdlibScript`s.
+    pub fn builtin() -> Self {
+        let mut registry = Self::new();
+        for script in StdlibScript::all() {
+            registry.insert(script.abi());
+        }
+        registry
+    }
+
+    /// Add `abi`, returning the ABI it replaced, if any.
+    pub fn insert(&mut self, abi: ScriptABI) -> Option<ScriptABI> {
+        self.abis.insert(abi.name().to_string(), abi)
+    }
+
+    /// Add every `.abi` file in the directory at `path`. Subdirectories and other files are
+    /// ignored. Nothing is added if any ABI file fails to load.
+    pub fn load_dir<P: AsRef<Path>>(&mut self, path: P) -> Result<()> {
+        let path = path.as_ref();
+        let mut blobs = BTreeMap::new();
+        for entry in fs::read_dir(path)
+            .with_context(|| format!("Failed to read ABI directory {}", path.display()))?
+        {
+            let file = entry?.path();
+            if file.is_dir() || file.extension().map_or(true, |ext| ext != "abi") {
+                continue;
+            }
+            let blob = fs::read(&file)
+                .with_context(|| format!("Failed to read ABI file {}", file.display()))?;
+            blobs.insert(file.display().to_string(), blob);
+        }
+        self.load_bytes(blobs)
+    }
+
+    /// Add the LCS-serialized ABIs in `blobs`, keyed by a name used in error messages, such as
+    /// the file they were read from. Nothing is added if any blob fails to deserialize.
+    pub fn load_bytes<K, V>(&mut self, blobs: impl IntoIterator<Item = (K, V)>) -> Result<()>
+    where
+        K: AsRef<str>,
+        V: AsRef<[u8]>,
+    {
+        let abis = blobs
+            .into_iter()
+            .map(|(name, blob)| {
+                lcs::from_bytes::<ScriptABI>(blob.as_ref())
+                    .with_context(|| format!("Failed to deserialize ABI {}", name.as_ref()))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        for abi in abis {
+            self.insert(abi);
+        }
+        Ok(())
+    }
+
+    /// Return the ABI of the script named `name`.
+    pub fn get(&self, name: &str) -> Option<&ScriptABI> {
+        self.abis.get(name)
+    }
+
+    /// Return the ABI of the script whose bytecode is `code`.
+    pub fn find_by_code(&self, code: &[u8]) -> Option<&ScriptABI> {
+        self.abis.values().find(|abi| abi.code() == code)
+    }
+
+    /// Decode `script` as a call of a script in the registry, returning its ABI along with the
+    /// arguments named per the ABI. Returns `None` if the code is not in the registry, or if the
+    /// arguments do not match the ABI.
+    pub fn decode_script(&self, script: &Script) -> Option<(&ScriptABI, NamedArguments)> {
+        let abi = self.find_by_code(script.code())?;
+        Some((abi, name_arguments(abi, script)?))
+    }
+
+    /// Return all ABIs, in alphabetical order of script name.
+    pub fn abis(&self) -> impl Iterator<Item = &ScriptABI> {
+        self.abis.values()
+    }
+
+    pub fn len(&self) -> usize {
+        self.abis.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.abis.is_empty()
+    }
+
+    /// Construct the whitelist of script hashes for the scripts in the registry.
+    pub fn whitelist(&self) -> Vec<[u8; SCRIPT_HASH_LENGTH]> {
+        self.abis()
+            .map(|abi| *HashValue::sha3_256_of(abi.code()).as_ref())
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use libra_temppath::TempPath;
+    use libra_types::transaction::TransactionArgument;
+
+    fn custom_abi(name: &str) -> ScriptABI {
+        ScriptABI::new(
+            name.to_string(),
+            "A custom script".to_string(),
+            vec![0xA1, 0x1C, 0xEB, 0x0B],
+            vec![],
+            vec![],
+        )
+    }
+
+    #[test]
+    fn test_builtin_matches_stdlib_scripts() {
+        let registry = StdlibScriptRegistry::builtin();
+        assert_eq!(registry.len(), StdlibScript::all().len());
+        let mut whitelist = StdlibScript::whitelist();
+        let mut registry_whitelist = registry.whitelist();
+        whitelist.sort();
+        registry_whitelist.sort();
+        assert_eq!(registry_whitelist, whitelist);
+
+        let script = StdlibScript::PeerToPeerWithMetadata;
+        assert_eq!(registry.get(&script.name()), Some(&script.abi()));
+        assert_eq!(
+            registry.find_by_code(&script.compiled_bytes().into_vec()),
+            Some(&script.abi())
+        );
+    }
+
+    #[test]
+    fn test_decode_script() {
+        let mut registry = StdlibScriptRegistry::new();
+        let abi = custom_abi("custom_script");
+        registry.insert(abi.clone());
+
+        let script = Script::new(abi.code().to_vec(), vec![], vec![]);
+        assert_eq!(
+            registry.decode_script(&script),
+            Some((&abi, (vec![], vec![])))
+        );
+        // Arguments the ABI doesn't have.
+        let script = Script::new(
+            abi.code().to_vec(),
+            vec![],
+            vec![TransactionArgument::U64(1)],
+        );
+        assert_eq!(registry.decode_script(&script), None);
+        // Code not in the registry.
+        let script = Script::new(vec![], vec![], vec![]);
+        assert_eq!(registry.decode_script(&script), None);
+    }
+
+    #[test]
+    fn test_load_bytes_replaces_by_name() {
+        let mut registry = StdlibScriptRegistry::builtin();
+        let replacement = custom_abi("peer_to_peer_with_metadata");
+        registry
+            .load_bytes(vec![("replacement", lcs::to_bytes(&replacement).unwrap())])
+            .unwrap();
+        assert_eq!(registry.len(), StdlibScript::all().len());
+        assert_eq!(
+            registry.get("peer_to_peer_with_metadata"),
+            Some(&replacement)
+        );
+
+        // A bad blob leaves the registry unchanged.
+        let before = registry.clone();
+        assert!(registry
+            .load_bytes(vec![
+                ("good", lcs::to_bytes(&custom_abi("good")).unwrap()),
+                ("bad", vec![0xFF]),
+            ])
+            .is_err());
+        assert_eq!(registry, before);
+    }
+
+    #[test]
+    fn test_load_dir() {
+        let dir = TempPath::new();
+        dir.create_as_dir().unwrap();
+        let abi = custom_abi("custom_script");
+        fs::write(
+            dir.path().join("custom_script.abi"),
+            lcs::to_bytes(&abi).unwrap(),
+        )
+        .unwrap();
+        fs::write(dir.path().join("README.md"), b"not an ABI").unwrap();
+
+        let mut registry = StdlibScriptRegistry::new();
+        registry.load_dir(dir.path()).unwrap();
+        assert_eq!(registry.abis().collect::<Vec<_>>(), vec![&abi]);
+        assert!(StdlibScriptRegistry::new()
+            .load_dir(dir.path().join("missing"))
+            .is_err());
+    }
+}
@@ -10,16 +10,49 @@
 use anyhow::{anyhow, Error, Result};
 use include_dir::{include_dir, Dir};
 use libra_crypto::HashValue;
-use libra_types::transaction::{ScriptABI, SCRIPT_HASH_LENGTH};
-use std::{convert::TryFrom, fmt, path::PathBuf};
+use libra_types::{
+    on_chain_config::{LibraVersion, VMPublishingOption},
+    transaction::{Script, ScriptABI, TransactionArgument, SCRIPT_HASH_LENGTH},
+};
+use once_cell::sync::Lazy;
+use std::{collections::HashMap, convert::TryFrom, fmt, path::PathBuf};
 
 // This includes the script ABIs as binaries. We must use this hack to work around
 // a problem with Docker, which does not copy over the Move source files that would be be used to
 // produce these binaries at runtime.
 const TXN_SCRIPTS_ABI_DIR: Dir = include_dir!("transaction_scripts/abi");
 
+/// The on-chain `LibraVersion` the chain starts at in genesis.
+pub const INITIAL_LIBRA_VERSION: u64 = 1;
+
+/// The ABI of every script and the hash of its bytecode, deserialized and computed on first use.
+static SCRIPT_ABIS: Lazy<HashMap<StdlibScript, (ScriptABI, HashValue)>> = Lazy::new(|| {
+    StdlibScript::all()
+        .into_iter()
+        .map(|script| {
+            let abi = script.load_abi();
+            let hash = CompiledBytes::hash_bytes(abi.code());
+            (script, (abi, hash))
+        })
+        .collect()
+});
+
+static SCRIPTS_BY_HASH: Lazy<HashMap<HashValue, StdlibScript>> = Lazy::new(|| {
+    StdlibScript::all()
+        .into_iter()
+        .map(|script| (script.hash(), script))
+        .collect()
+});
+
+static WHITELIST: Lazy<Vec<[u8; SCRIPT_HASH_LENGTH]>> = Lazy::new(|| {
+    StdlibScript::all()
+        .iter()
+        .map(|script| *script.hash().as_ref())
+        .collect()
+});
+
 /// All of the Move transaction scripts that can be executed on the Libra blockchain
-#[derive(Clone, Copy, Eq, PartialEq)]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
 pub enum StdlibScript {
     AddCurrencyToAccount,
     AddRecoveryRotationCapability,
@@ -115,17 +148,71 @@ impl StdlibScript {
     /// Construct the whitelist of script hashes used to determine whether a transaction script can
     /// be executed on the Libra blockchain
     pub fn whitelist() -> Vec<[u8; SCRIPT_HASH_LENGTH]> {
+        WHITELIST.clone()
+    }
+
+    /// Construct the whitelist of the scripts that are active at the on-chain `version`. Clients
+    /// should submit only these scripts, since any other will be rejected with `UNKNOWN_SCRIPT`.
+    pub fn whitelist_for_version(version: &LibraVersion) -> Vec<[u8; SCRIPT_HASH_LENGTH]> {
         StdlibScript::all()
             .iter()
-            .map(|script| *script.compiled_bytes().hash().as_ref())
+            .filter(|script| script.is_active_in(version))
+            .map(|script| *script.hash().as_ref())
             .collect()
     }
 
+    /// Return the publishing option that only allows the scripts active at the on-chain
+    /// `version`.
+    pub fn publishing_option_for_version(version: &LibraVersion) -> VMPublishingOption {
+        VMPublishingOption::locked(Self::whitelist_for_version(version))
+    }
+
+    /// Build the `modify_publishing_option` script that sets the on-chain whitelist to the
+    /// scripts active at `version`. It must be sent by the Libra root account.
+    pub fn encode_whitelist_update_script(version: &LibraVersion) -> Script {
+        let publishing_option = lcs::to_bytes(&Self::publishing_option_for_version(version))
+            .expect("Failed to serialize publishing option");
+        Script::new(
+            StdlibScript::ModifyPublishingOption.code().to_vec(),
+            vec![],
+            vec![TransactionArgument::U8Vector(publishing_option)],
+        )
+    }
+
+    /// Return the on-chain `LibraVersion` from which this script is on the whitelist.
+    pub fn introduced_in(self) -> u64 {
+        // Every script so far shipped with the genesis stdlib. Scripts added by a later stdlib
+        // release should be matched here with the version that release is activated at.
+        INITIAL_LIBRA_VERSION
+    }
+
+    /// Return the on-chain `LibraVersion` from which this script is no longer on the whitelist,
+    /// if it has been deprecated.
+    pub fn deprecated_in(self) -> Option<u64> {
+        // No script has been deprecated yet. Deprecated scripts should be matched here with the
+        // version they are removed from the whitelist at, and kept in the enum until then.
+        None
+    }
+
+    /// Return true if this script is on the whitelist at the on-chain `version`.
+    pub fn is_active_in(self, version: &LibraVersion) -> bool {
+        self.introduced_in() <= version.major
+            && self
+                .deprecated_in()
+                .map_or(true, |deprecated| version.major < deprecated)
+    }
+
     /// Return a lowercase-underscore style name for this script
     pub fn name(self) -> String {
         self.to_string()
     }
 
+    /// Return the script whose bytecode has the sha3-256 `hash`, such as the script hash recorded
+    /// for a transaction, if it is one of the standard library scripts.
+    pub fn try_from_hash(hash: &HashValue) -> Option<Self> {
+        SCRIPTS_BY_HASH.get(hash).copied()
+    }
+
     /// Return true if `code_bytes` is the bytecode of one of the standard library scripts
     pub fn is(code_bytes: &[u8]) -> bool {
         Self::try_from(code_bytes).is_ok()
@@ -133,11 +220,26 @@ impl StdlibScript {
 
     /// Return the Move bytecode that was produced by compiling this script.
     pub fn compiled_bytes(self) -> CompiledBytes {
-        CompiledBytes(self.abi().code().to_vec())
+        CompiledBytes(self.code().to_vec())
+    }
+
+    /// Return the Move bytecode of this script without copying it.
+    pub fn code(self) -> &'static [u8] {
+        self.abi_ref().code()
     }
 
     /// Return the ABI of the script (including the bytecode).
     pub fn abi(self) -> ScriptABI {
+        self.abi_ref().clone()
+    }
+
+    /// Return the ABI of the script without copying it.
+    pub fn abi_ref(self) -> &'static ScriptABI {
+        &SCRIPT_ABIS[&self].0
+    }
+
+    /// Deserialize the ABI of the script from the embedded ABI directory.
+    fn load_abi(self) -> ScriptABI {
         let mut path = PathBuf::from(self.name());
         path.set_extension("abi");
         let content = TXN_SCRIPTS_ABI_DIR
@@ -150,7 +252,7 @@ impl StdlibScript {
 
     /// Return the sha3-256 hash of the compiled script bytes.
     pub fn hash(self) -> HashValue {
-        self.compiled_bytes().hash()
+        SCRIPT_ABIS[&self].1
     }
 }
 
@@ -181,11 +283,7 @@ impl TryFrom<&[u8]> for StdlibScript {
     /// Return `Some(<script_name>)` if  `code_bytes` is the bytecode of one of the standard library
     /// scripts, None otherwise.
     fn try_from(code_bytes: &[u8]) -> Result<Self> {
-        let hash = CompiledBytes::hash_bytes(code_bytes);
-        Self::all()
-            .iter()
-            .find(|script| script.hash() == hash)
-            .cloned()
+        Self::try_from_hash(&CompiledBytes::hash_bytes(code_bytes))
             .ok_or_else(|| anyhow!("Could not create standard library script from bytes"))
     }
 }
@@ -289,6 +387,71 @@ mod test {
         }
     }
 
+    #[test]
+    fn test_whitelist_for_version() {
+        // Nothing is active before genesis, and everything known is active at the current version.
+        let before_genesis = LibraVersion {
+            major: INITIAL_LIBRA_VERSION - 1,
+        };
+        assert!(StdlibScript::whitelist_for_version(&before_genesis).is_empty());
+        let current = LibraVersion {
+            major: INITIAL_LIBRA_VERSION,
+        };
+        assert_eq!(
+            StdlibScript::whitelist_for_version(&current),
+            StdlibScript::whitelist()
+        );
+        for script in StdlibScript::all() {
+            assert!(
+                script
+                    .deprecated_in()
+                    .map_or(true, |v| v > script.introduced_in()),
+                "{} is deprecated before it is introduced",
+                script
+            );
+        }
+    }
+
+    #[test]
+    fn test_whitelist_update_script() {
+        let current = LibraVersion {
+            major: INITIAL_LIBRA_VERSION,
+        };
+        let script = StdlibScript::encode_whitelist_update_script(&current);
+        assert!(
+            StdlibScript::try_from(script.code()).ok()
+                == Some(StdlibScript::ModifyPublishingOption)
+        );
+        assert!(script.ty_args().is_empty());
+        match script.args() {
+            [TransactionArgument::U8Vector(bytes)] => assert_eq!(
+                lcs::from_bytes::<VMPublishingOption>(bytes).unwrap(),
+                VMPublishingOption::locked(StdlibScript::whitelist())
+            ),
+            args => panic!("Unexpected arguments {:?}", args),
+        }
+    }
+
+    #[test]
+    fn test_cache_matches_abi_files() {
+        for script in StdlibScript::all() {
+            let abi = script.load_abi();
+            assert_eq!(script.abi_ref(), &abi);
+            assert_eq!(script.code(), abi.code());
+            assert_eq!(script.hash(), HashValue::sha3_256_of(abi.code()));
+            // The cache is built once, so every call returns the same ABI.
+            assert!(std::ptr::eq(script.abi_ref(), script.abi_ref()));
+        }
+    }
+
+    #[test]
+    fn test_try_from_hash() {
+        for script in StdlibScript::all() {
+            assert!(StdlibScript::try_from_hash(&script.hash()) == Some(script));
+        }
+        assert!(StdlibScript::try_from_hash(&HashValue::zero()).is_none());
+    }
+
     #[test]
     fn test_docs() {
         // Make sure that scripts have non-empty documentation.
@@ -0,0 +1,38 @@
+address 0x1 {
+
+module LibraConsensusConfig {
+    use 0x1::CoreAddresses;
+    use 0x1::LibraConfig;
+    use 0x1::Signer;
+
+    // The consensus config taking over the validators' local one once published.
+    // * proposer_election_type: The LCS encoding of how the proposer of each round is elected.
+    struct LibraConsensusConfig {
+        proposer_election_type: vector<u8>,
+    }
+
+    const EINVALID_SINGLETON_ADDRESS: u64 = 0;
+
+    // Publish the config under the libra root account. This doesn't trigger a reconfiguration,
+    // the config is picked up from the next one on, e.g. that of a later `set`.
+    public fun initialize(
+        lr_account: &signer,
+        proposer_election_type: vector<u8>,
+    ) {
+        assert(Signer::address_of(lr_account) == CoreAddresses::LIBRA_ROOT_ADDRESS(), EINVALID_SINGLETON_ADDRESS);
+
+        LibraConfig::publish_new_config<LibraConsensusConfig>(
+            lr_account,
+            LibraConsensusConfig { proposer_election_type },
+        );
+    }
+
+    public fun set(account: &signer, proposer_election_type: vector<u8>) {
+        LibraConfig::set<LibraConsensusConfig>(
+            account,
+            LibraConsensusConfig { proposer_election_type }
+        );
+    }
+}
+
+}
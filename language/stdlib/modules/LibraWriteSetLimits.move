@@ -0,0 +1,45 @@
+address 0x1 {
+
+module LibraWriteSetLimits {
+    use 0x1::CoreAddresses;
+    use 0x1::LibraConfig;
+    use 0x1::Signer;
+
+    // The bounds on what a single transaction can write to storage, enforced by the VM from
+    // LibraVersion 2 on, which falls back to its own defaults until they're published.
+    // * max_write_ops: The number of resources and modules written or deleted.
+    // * max_write_bytes: The number of bytes written, counting serialized resources and modules.
+    // * max_events: The number of events emitted.
+    struct LibraWriteSetLimits {
+        max_write_ops: u64,
+        max_write_bytes: u64,
+        max_events: u64,
+    }
+
+    const EINVALID_SINGLETON_ADDRESS: u64 = 0;
+
+    // Publish the limits under the libra root account. This doesn't trigger a reconfiguration,
+    // the VM picks them up from the next one on, e.g. that of a later `set`.
+    public fun initialize(
+        lr_account: &signer,
+        max_write_ops: u64,
+        max_write_bytes: u64,
+        max_events: u64,
+    ) {
+        assert(Signer::address_of(lr_account) == CoreAddresses::LIBRA_ROOT_ADDRESS(), EINVALID_SINGLETON_ADDRESS);
+
+        LibraConfig::publish_new_config<LibraWriteSetLimits>(
+            lr_account,
+            LibraWriteSetLimits { max_write_ops, max_write_bytes, max_events },
+        );
+    }
+
+    public fun set(account: &signer, max_write_ops: u64, max_write_bytes: u64, max_events: u64) {
+        LibraConfig::set<LibraWriteSetLimits>(
+            account,
+            LibraWriteSetLimits { max_write_ops, max_write_bytes, max_events }
+        );
+    }
+}
+
+}
@@ -4,8 +4,23 @@
 #![forbid(unsafe_code)]
 
 use bytecode_verifier::{verify_module, DependencyChecker};
+use libra_types::transaction::{ArgumentABI, ScriptFunctionABI, TypeArgumentABI};
 use log::LevelFilter;
-use move_lang::{compiled_unit::CompiledUnit, move_compile, shared::Address};
+use move_core_types::{
+    account_address::AccountAddress,
+    errmap::{ErrorDescription, ErrorMapping},
+    identifier::Identifier,
+    language_storage::{ModuleId, TypeTag},
+};
+use move_ir_types::location::Loc;
+use move_lang::{
+    compiled_unit::CompiledUnit,
+    expansion::ast::{Exp_, ModuleAccess_, Program, Type, Type_, Value_},
+    move_compile, move_compile_to_expansion_no_report,
+    parser::ast::ModuleIdent,
+    shared::Address,
+    CommentMap,
+};
 use sha2::{Digest, Sha256};
 use std::{
     fs::File,
@@ -37,6 +52,29 @@ pub const COMPILED_TRANSACTION_SCRIPTS_DIR: &str = "compiled/transaction_scripts
 /// The output path for transaction script ABIs.
 pub const COMPILED_TRANSACTION_SCRIPTS_ABI_DIR: &str = "compiled/transaction_scripts/abi";
 
+/// The output path for the error descriptions of the stdlib modules.
+pub const ERROR_DESCRIPTIONS_DIR: &str = "compiled/error_descriptions";
+/// The file name for the error descriptions
+pub const ERROR_DESCRIPTIONS_NAME: &str = "error_descriptions";
+/// The extension for error description files
+pub const ERROR_DESCRIPTIONS_EXTENSION: &str = "errmap";
+
+/// The output path for script function ABIs.
+pub const COMPILED_SCRIPT_FUNCTIONS_ABI_DIR: &str = "compiled/script_functions/abi";
+/// The extension for ABI files
+pub const ABI_EXTENSION: &str = "abi";
+
+/// The stdlib functions that can be called by name from a transaction, as (module, function).
+pub const SCRIPT_FUNCTIONS: &[(&str, &str)] = &[
+    ("DualAttestation", "rotate_base_url"),
+    ("DualAttestation", "rotate_compliance_public_key"),
+    ("LibraAccount", "add_currency"),
+    ("LibraVersion", "set"),
+    ("SharedEd25519PublicKey", "rotate_key"),
+    ("ValidatorConfig", "remove_operator"),
+    ("ValidatorConfig", "set_operator"),
+];
+
 /// Where to write generated transaction builders.
 pub const TRANSACTION_BUILDERS_GENERATED_SOURCE_PATH: &str =
     "../transaction-builder/src/generated.rs";
@@ -161,6 +199,175 @@ fn build_abi(output_path: &str, sources: &[String], dep_path: &str, compiled_scr
     move_prover::run_move_prover_errors_to_stderr(options).unwrap();
 }
 
+/// Builds the error map of the stdlib modules from their error constants: the `u64` constants
+/// whose names start with `E`, described by their doc comments. When a module declares two error
+/// constants with the same value, the first one declared describes the code.
+pub fn build_error_code_map() -> ErrorMapping {
+    let (program, comment_map) = expand_stdlib();
+    let mut error_map = ErrorMapping::default();
+    for (ident, module) in program.modules.iter() {
+        if !module.is_source_module {
+            continue;
+        }
+        let module_id = module_id(&ident);
+        let mut constants = module
+            .constants
+            .iter()
+            .filter(|(name, _)| name.0.value.starts_with('E'))
+            .filter_map(|(name, constant)| {
+                let code = match &constant.value.value {
+                    Exp_::Value(value) => match value.value {
+                        Value_::U64(code) => code,
+                        _ => return None,
+                    },
+                    Exp_::InferredNum(code) if *code <= u128::from(std::u64::MAX) => *code as u64,
+                    _ => return None,
+                };
+                Some((constant.loc, name.0.value, code))
+            })
+            .collect::<Vec<_>>();
+        constants.sort_by_key(|(loc, _, _)| loc.span().start());
+        for (loc, code_name, code) in constants {
+            let code_description = doc_at(&comment_map, loc);
+            let known = error_map
+                .module_error_maps
+                .get(&module_id)
+                .map_or(false, |errors| errors.contains_key(&code));
+            if !known {
+                error_map.add_module_error(
+                    module_id.clone(),
+                    code,
+                    ErrorDescription {
+                        code_name,
+                        code_description,
+                    },
+                );
+            }
+        }
+    }
+    error_map
+}
+
+/// Builds the ABIs of the `SCRIPT_FUNCTIONS`. The leading `&signer` parameters of a function are
+/// bound to the transaction signers, so they are not part of its ABI arguments.
+pub fn build_script_function_abis() -> Vec<ScriptFunctionABI> {
+    let (program, comment_map) = expand_stdlib();
+    SCRIPT_FUNCTIONS
+        .iter()
+        .map(|(module_name, function_name)| {
+            let (ident, module) = program
+                .modules
+                .iter()
+                .find(|(ident, _)| ident.0.value.name.0.value == *module_name)
+                .unwrap_or_else(|| panic!("Script function module {} not found", module_name));
+            let (_, function) = module
+                .functions
+                .iter()
+                .find(|(name, _)| name.0.value == *function_name)
+                .unwrap_or_else(|| {
+                    panic!(
+                        "Script function {}::{} not found",
+                        module_name, function_name
+                    )
+                });
+            let ty_args = function
+                .signature
+                .type_parameters
+                .iter()
+                .map(|(name, _)| TypeArgumentABI::new(name.value.clone()))
+                .collect();
+            let args = function
+                .signature
+                .parameters
+                .iter()
+                .skip_while(|(_, ty)| is_signer_reference(ty))
+                .map(|(var, ty)| {
+                    let type_tag = argument_type_tag(ty).unwrap_or_else(|| {
+                        panic!(
+                            "Script function {}::{} has an argument of unsupported type",
+                            module_name, function_name
+                        )
+                    });
+                    ArgumentABI::new(var.0.value.clone(), type_tag)
+                })
+                .collect();
+            ScriptFunctionABI::new(
+                function_name.to_string(),
+                module_id(&ident),
+                doc_at(&comment_map, function.loc),
+                ty_args,
+                args,
+            )
+        })
+        .collect()
+}
+
+pub fn build_script_function_abi_files() {
+    for abi in build_script_function_abis() {
+        let mut path = PathBuf::from(COMPILED_SCRIPT_FUNCTIONS_ABI_DIR);
+        path.push(format!("{}_{}", abi.module_name().name(), abi.name()));
+        path.set_extension(ABI_EXTENSION);
+        save_binary(&path, &lcs::to_bytes(&abi).unwrap());
+    }
+}
+
+fn expand_stdlib() -> (Program, CommentMap) {
+    let (_, res) =
+        move_compile_to_expansion_no_report(&stdlib_files(), &[], Some(Address::LIBRA_CORE))
+            .expect("Failed to read stdlib modules");
+    res.expect("stdlib modules failed to compile")
+}
+
+fn module_id(ident: &ModuleIdent) -> ModuleId {
+    ModuleId::new(
+        AccountAddress::new(ident.0.value.address.to_u8()),
+        Identifier::new(ident.0.value.name.0.value.as_str())
+            .expect("module name must be a valid identifier"),
+    )
+}
+
+/// Returns the doc comment of the item at `loc` on a single line, or an empty string.
+fn doc_at(comment_map: &CommentMap, loc: Loc) -> String {
+    comment_map
+        .get(loc.file())
+        .and_then(|comments| comments.get(&loc.span().start()))
+        .map_or("", |doc| doc.as_str())
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn builtin_type_name(ty: &Type) -> Option<(&str, &[Type])> {
+    match &ty.value {
+        Type_::Apply(access, ty_args) => match &access.value {
+            ModuleAccess_::Name(name) => Some((name.value.as_str(), ty_args.as_slice())),
+            ModuleAccess_::ModuleAccess(..) => None,
+        },
+        _ => None,
+    }
+}
+
+fn is_signer_reference(ty: &Type) -> bool {
+    match &ty.value {
+        Type_::Ref(false, inner) => matches!(builtin_type_name(inner), Some(("signer", []))),
+        _ => false,
+    }
+}
+
+fn argument_type_tag(ty: &Type) -> Option<TypeTag> {
+    match builtin_type_name(ty)? {
+        ("u8", []) => Some(TypeTag::U8),
+        ("u64", []) => Some(TypeTag::U64),
+        ("u128", []) => Some(TypeTag::U128),
+        ("bool", []) => Some(TypeTag::Bool),
+        ("address", []) => Some(TypeTag::Address),
+        ("vector", [inner]) => Some(TypeTag::Vector(Box::new(argument_type_tag(inner)?))),
+        _ => None,
+    }
+}
+
 pub fn generate_rust_transaction_builders() {
     let abis = transaction_builder_generator::read_abis(COMPILED_TRANSACTION_SCRIPTS_ABI_DIR)
         .expect("Failed to read generated ABIs");
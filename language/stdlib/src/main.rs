@@ -11,11 +11,12 @@ use std::{
     time::Instant,
 };
 use stdlib::{
-    build_stdlib, build_stdlib_doc, build_transaction_script_abi, build_transaction_script_doc,
-    compile_script, filter_move_files, generate_rust_transaction_builders, save_binary,
-    COMPILED_EXTENSION, COMPILED_OUTPUT_PATH, COMPILED_STDLIB_NAME,
-    COMPILED_TRANSACTION_SCRIPTS_ABI_DIR, COMPILED_TRANSACTION_SCRIPTS_DIR, STD_LIB_DOC_DIR,
-    TRANSACTION_SCRIPTS, TRANSACTION_SCRIPTS_DOC_DIR,
+    build_error_code_map, build_script_function_abi_files, build_stdlib, build_stdlib_doc,
+    build_transaction_script_abi, build_transaction_script_doc, compile_script, filter_move_files,
+    generate_rust_transaction_builders, save_binary, COMPILED_EXTENSION, COMPILED_OUTPUT_PATH,
+    COMPILED_SCRIPT_FUNCTIONS_ABI_DIR, COMPILED_STDLIB_NAME, COMPILED_TRANSACTION_SCRIPTS_ABI_DIR,
+    COMPILED_TRANSACTION_SCRIPTS_DIR, ERROR_DESCRIPTIONS_DIR, ERROR_DESCRIPTIONS_EXTENSION,
+    ERROR_DESCRIPTIONS_NAME, STD_LIB_DOC_DIR, TRANSACTION_SCRIPTS, TRANSACTION_SCRIPTS_DOC_DIR,
 };
 
 // Generates the compiled stdlib and transaction scripts. Until this is run changes to the source
@@ -36,6 +37,11 @@ fn main() {
                 .requires("no-compiler")
                 .help("do not generate script ABIs"),
         )
+        .arg(
+            Arg::with_name("no-errmap")
+                .long("no-errmap")
+                .help("do not generate error explanations"),
+        )
         .arg(
             Arg::with_name("no-script-builder")
                 .long("no-script-builer")
@@ -49,6 +55,7 @@ fn main() {
     let matches = cli.get_matches();
     let no_doc = matches.is_present("no-doc");
     let no_script_abi = matches.is_present("no-script-abi");
+    let no_errmap = matches.is_present("no-errmap");
     let no_script_builder = matches.is_present("no-script-builder");
     let no_compiler = matches.is_present("no-compiler");
 
@@ -124,6 +131,23 @@ fn main() {
                 .par_iter()
                 .for_each(|txn_file| build_transaction_script_abi(txn_file.clone()));
         });
+        time_it("Generating script function ABIs", || {
+            std::fs::remove_dir_all(&COMPILED_SCRIPT_FUNCTIONS_ABI_DIR).unwrap_or(());
+            std::fs::create_dir_all(&COMPILED_SCRIPT_FUNCTIONS_ABI_DIR).unwrap();
+            build_script_function_abi_files();
+        });
+    }
+
+    // Generate the error map
+    if !no_errmap {
+        time_it("Generating error explanations", || {
+            std::fs::create_dir_all(ERROR_DESCRIPTIONS_DIR).unwrap();
+            let mut errmap_path = PathBuf::from(ERROR_DESCRIPTIONS_DIR);
+            errmap_path.push(ERROR_DESCRIPTIONS_NAME);
+            errmap_path.set_extension(ERROR_DESCRIPTIONS_EXTENSION);
+            let bytes = lcs::to_bytes(&build_error_code_map()).unwrap();
+            save_binary(&errmap_path, &bytes);
+        });
     }
 
     // Generate script builders in Rust
@@ -16,6 +16,18 @@ use vm::{
 };
 
 pub(crate) static INITIAL_GAS_SCHEDULE: Lazy<(Vec<u8>, Vec<u8>)> = Lazy::new(|| {
+    let (raw_instruction_table, raw_native_table) = initial_cost_tables();
+    (
+        lcs::to_bytes(&raw_instruction_table)
+            .expect("Unable to serialize genesis gas schedule for instructions"),
+        lcs::to_bytes(&raw_native_table)
+            .expect("Unable to serialize genesis gas schedule for instructions"),
+    )
+});
+
+/// Returns the instruction and native cost tables published at genesis, each ordered the way the
+/// LibraVM expects it.
+pub fn initial_cost_tables() -> (Vec<GasCost>, Vec<GasCost>) {
     use Bytecode::*;
     let mut instrs = vec![
         (MoveTo(StructDefinitionIndex::new(0)), GasCost::new(13, 1)),
@@ -150,10 +162,5 @@ pub(crate) static INITIAL_GAS_SCHEDULE: Lazy<(Vec<u8>, Vec<u8>)> = Lazy::new(||
         .map(|(_, cost)| cost)
         .collect::<Vec<_>>();
 
-    (
-        lcs::to_bytes(&raw_instruction_table)
-            .expect("Unable to serialize genesis gas schedule for instructions"),
-        lcs::to_bytes(&raw_native_table)
-            .expect("Unable to serialize genesis gas schedule for instructions"),
-    )
-});
+    (raw_instruction_table, raw_native_table)
+}
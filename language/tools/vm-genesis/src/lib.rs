@@ -8,6 +8,7 @@ mod genesis_gas_schedule;
 
 use crate::{genesis_context::GenesisStateView, genesis_gas_schedule::INITIAL_GAS_SCHEDULE};
 use compiled_stdlib::{stdlib_modules, transaction_scripts::StdlibScript, StdLibOptions};
+pub use genesis_gas_schedule::initial_cost_tables;
 use libra_config::config::{NodeConfig, HANDSHAKE_VERSION};
 use libra_crypto::{
     ed25519::{Ed25519PrivateKey, Ed25519PublicKey},
@@ -30,7 +31,7 @@ use libra_types::{
 use libra_vm::{data_cache::StateViewCache, txn_effects_to_writeset_and_events};
 use move_core_types::{
     account_address::AccountAddress,
-    gas_schedule::{CostTable, GasAlgebra, GasUnits},
+    gas_schedule::{CostTable, GasAlgebra, GasCost, GasUnits},
     identifier::Identifier,
     language_storage::{ModuleId, StructTag, TypeTag},
 };
@@ -97,6 +98,50 @@ pub fn encode_genesis_change_set(
     operator_registrations: &[OperatorRegistration],
     stdlib_modules: &[CompiledModule],
     vm_publishing_option: VMPublishingOption,
+) -> (ChangeSet, BTreeMap<Vec<u8>, StructTag>) {
+    encode_genesis_change_set_impl(
+        public_key,
+        operator_assignments,
+        operator_registrations,
+        stdlib_modules,
+        vm_publishing_option,
+        &INITIAL_GAS_SCHEDULE,
+    )
+}
+
+/// Like [`encode_genesis_change_set`], but publishes the given instruction and native cost tables
+/// instead of the [initial ones](initial_cost_tables). Both tables must be ordered the way the
+/// LibraVM expects them.
+pub fn encode_genesis_change_set_with_gas_schedule(
+    public_key: &Ed25519PublicKey,
+    operator_assignments: &[OperatorAssignment],
+    operator_registrations: &[OperatorRegistration],
+    stdlib_modules: &[CompiledModule],
+    vm_publishing_option: VMPublishingOption,
+    instruction_table: &[GasCost],
+    native_table: &[GasCost],
+) -> (ChangeSet, BTreeMap<Vec<u8>, StructTag>) {
+    let gas_schedule = (
+        lcs::to_bytes(instruction_table).expect("Unable to serialize instruction gas schedule"),
+        lcs::to_bytes(native_table).expect("Unable to serialize native gas schedule"),
+    );
+    encode_genesis_change_set_impl(
+        public_key,
+        operator_assignments,
+        operator_registrations,
+        stdlib_modules,
+        vm_publishing_option,
+        &gas_schedule,
+    )
+}
+
+fn encode_genesis_change_set_impl(
+    public_key: &Ed25519PublicKey,
+    operator_assignments: &[OperatorAssignment],
+    operator_registrations: &[OperatorRegistration],
+    stdlib_modules: &[CompiledModule],
+    vm_publishing_option: VMPublishingOption,
+    gas_schedule: &(Vec<u8>, Vec<u8>),
 ) -> (ChangeSet, BTreeMap<Vec<u8>, StructTag>) {
     // create a data view for move_vm
     let mut state_view = GenesisStateView::new();
@@ -117,7 +162,13 @@ pub fn encode_genesis_change_set(
     });
 
     // generate the genesis WriteSet
-    create_and_initialize_main_accounts(&mut session, &public_key, vm_publishing_option, &lbr_ty);
+    create_and_initialize_main_accounts(
+        &mut session,
+        &public_key,
+        vm_publishing_option,
+        gas_schedule,
+        &lbr_ty,
+    );
     create_and_initialize_owners_operators(
         &mut session,
         &operator_assignments,
@@ -209,6 +260,7 @@ fn create_and_initialize_main_accounts(
     session: &mut Session<StateViewCache>,
     public_key: &Ed25519PublicKey,
     publishing_option: VMPublishingOption,
+    gas_schedule: &(Vec<u8>, Vec<u8>),
     lbr_ty: &TypeTag,
 ) {
     let genesis_auth_key = AuthenticationKey::ed25519(public_key);
@@ -231,8 +283,8 @@ fn create_and_initialize_main_accounts(
             Value::address(tc_account_address),
             Value::vector_u8(genesis_auth_key.to_vec()),
             Value::vector_u8(option_bytes),
-            Value::vector_u8(INITIAL_GAS_SCHEDULE.0.clone()),
-            Value::vector_u8(INITIAL_GAS_SCHEDULE.1.clone()),
+            Value::vector_u8(gas_schedule.0.clone()),
+            Value::vector_u8(gas_schedule.1.clone()),
         ],
     );
 
@@ -1,6 +1,7 @@
 // Copyright (c) The Libra Core Contributors
 // SPDX-License-Identifier: Apache-2.0
 
+use libra_types::transaction::ArgumentABI;
 use move_core_types::language_storage::TypeTag;
 
 /// Useful error message.
@@ -22,3 +23,32 @@ pub(crate) fn prepare_doc_string(doc: &str) -> String {
         doc = doc2;
     }
 }
+
+/// Convert a snake-case script name such as `peer_to_peer_with_metadata` to camel case.
+pub(crate) fn to_camel_case(name: &str) -> String {
+    name.split('_')
+        .map(|word| {
+            let mut chars = word.chars();
+            match chars.next() {
+                Some(first) => first.to_uppercase().chain(chars).collect::<String>(),
+                None => String::new(),
+            }
+        })
+        .collect()
+}
+
+/// Whether the argument is an amount of coins, which scripts never accept to be zero.
+pub(crate) fn is_amount_argument(arg: &ArgumentABI) -> bool {
+    let name = arg.name();
+    arg.type_tag() == &TypeTag::U64
+        && (name == "amount" || name.starts_with("amount_") || name.ends_with("_amount"))
+}
+
+/// Whether the argument is an optional ed25519 signature, which is either empty or
+/// `SIGNATURE_LENGTH` bytes long.
+pub(crate) fn is_signature_argument(arg: &ArgumentABI) -> bool {
+    arg.type_tag() == &TypeTag::Vector(Box::new(TypeTag::U8)) && arg.name().ends_with("signature")
+}
+
+/// The length of an ed25519 signature.
+pub(crate) const SIGNATURE_LENGTH: usize = 64;
@@ -1,7 +1,9 @@
 // Copyright (c) The Libra Core Contributors
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::common::type_not_allowed;
+use crate::common::{
+    is_amount_argument, is_signature_argument, to_camel_case, type_not_allowed, SIGNATURE_LENGTH,
+};
 use libra_types::transaction::{ArgumentABI, ScriptABI, TypeArgumentABI};
 use move_core_types::language_storage::TypeTag;
 
@@ -15,8 +17,10 @@ use std::{
 /// rather than using serde-generated, standalone definitions.
 pub fn output(out: &mut dyn Write, abis: &[ScriptABI], local_types: bool) -> Result<()> {
     output_preamble(out, local_types)?;
+    output_builder_error(out)?;
     for abi in abis {
         output_builder(out, abi, local_types)?;
+        output_builder_struct(out, abi)?;
     }
     Ok(())
 }
@@ -86,6 +90,101 @@ fn output_builder(out: &mut dyn Write, abi: &ScriptABI, local_types: bool) -> Re
     Ok(())
 }
 
+fn output_builder_error(out: &mut dyn Write) -> Result<()> {
+    writeln!(
+        out,
+        r#"
+/// The length of an ed25519 signature.
+pub const SIGNATURE_LENGTH: usize = {};
+
+/// The reason a script builder rejected its arguments.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum BuilderError {{
+    /// The named amount argument is zero.
+    ZeroAmount(&'static str),
+    /// The named signature argument is neither empty nor `SIGNATURE_LENGTH` bytes long.
+    InvalidSignatureLength(&'static str, usize),
+}}
+
+impl std::fmt::Display for BuilderError {{
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {{
+        match self {{
+            BuilderError::ZeroAmount(name) => write!(f, "Argument {{}} must not be zero", name),
+            BuilderError::InvalidSignatureLength(name, length) => write!(
+                f,
+                "Argument {{}} must be empty or {{}} bytes long, found {{}} bytes",
+                name, SIGNATURE_LENGTH, length
+            ),
+        }}
+    }}
+}}
+
+impl std::error::Error for BuilderError {{}}"#,
+        SIGNATURE_LENGTH
+    )
+}
+
+/// Output a struct holding the arguments of the script, whose `build` method checks them
+/// before calling the `encode_*_script` function.
+fn output_builder_struct(out: &mut dyn Write, abi: &ScriptABI) -> Result<()> {
+    let name = format!("{}Builder", to_camel_case(abi.name()));
+    writeln!(
+        out,
+        "\n/// Typed arguments of `encode_{}_script`, checked by `build`.",
+        abi.name()
+    )?;
+    writeln!(out, "#[derive(Clone, Debug, Eq, PartialEq)]")?;
+    writeln!(out, "pub struct {} {{", name)?;
+    for field in [
+        quote_type_parameters(abi.ty_args()),
+        quote_parameters(abi.args()),
+    ]
+    .concat()
+    {
+        writeln!(out, "    pub {},", field)?;
+    }
+    writeln!(out, "}}\n")?;
+    writeln!(out, "impl {} {{", name)?;
+    writeln!(
+        out,
+        "    pub fn build(self) -> std::result::Result<Script, BuilderError> {{"
+    )?;
+    for arg in abi.args() {
+        if is_amount_argument(arg) {
+            writeln!(
+                out,
+                r#"        if self.{0} == 0 {{
+            return Err(BuilderError::ZeroAmount("{0}"));
+        }}"#,
+                arg.name()
+            )?;
+        }
+        if is_signature_argument(arg) {
+            writeln!(
+                out,
+                r#"        if !self.{0}.is_empty() && self.{0}.len() != SIGNATURE_LENGTH {{
+            return Err(BuilderError::InvalidSignatureLength("{0}", self.{0}.len()));
+        }}"#,
+                arg.name()
+            )?;
+        }
+    }
+    writeln!(
+        out,
+        "        Ok(encode_{}_script({}))",
+        abi.name(),
+        abi.ty_args()
+            .iter()
+            .map(|ty_arg| ty_arg.name())
+            .chain(abi.args().iter().map(|arg| arg.name()))
+            .map(|name| format!("self.{}", name))
+            .collect::<Vec<_>>()
+            .join(", ")
+    )?;
+    writeln!(out, "    }}")?;
+    writeln!(out, "}}")
+}
+
 fn quote_doc(doc: &str) -> String {
     let doc = crate::common::prepare_doc_string(doc);
     let text = textwrap::fill(&doc, 86);
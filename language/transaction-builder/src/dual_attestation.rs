@@ -0,0 +1,95 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Off-chain construction of the `metadata` and `metadata_signature` arguments of
+//! `peer_to_peer_with_metadata` for payments subject to dual attestation.
+//!
+//! The payee's VASP signs the travel rule metadata agreed upon off-chain together with the payer
+//! and the amount, and the payer includes both in the payment:
+//!
+//! ```ignore
+//! let metadata = travel_rule_metadata("reference-id");
+//! // Computed by the payee's VASP with its compliance key.
+//! let signature = sign_dual_attestation_message(&compliance_key, &metadata, &payer, amount);
+//! let script = encode_peer_to_peer_with_metadata_script(
+//!     coin1_tag(),
+//!     payee,
+//!     amount,
+//!     metadata,
+//!     signature,
+//! );
+//! ```
+
+use anyhow::Result;
+use libra_crypto::{
+    ed25519::{Ed25519PrivateKey, Ed25519PublicKey, Ed25519Signature},
+    traits::Signature,
+};
+use libra_types::{
+    account_address::AccountAddress,
+    transaction::metadata::{Metadata, TravelRuleMetadata, TravelRuleMetadataV0},
+};
+use std::convert::TryFrom;
+#[cfg(feature = "vanilla")]
+use vanilla_ed25519_dalek as ed25519_dalek;
+
+/// The domain separator appended to dual attestation messages, `@@$$LIBRA_ATTEST$$@@`, as
+/// defined in `DualAttestation.move`.
+pub const DUAL_ATTESTATION_DOMAIN_SEPARATOR: &[u8] = b"@@$$LIBRA_ATTEST$$@@";
+
+/// Return the serialized travel rule metadata of a payment with the off-chain reference ID
+/// `off_chain_reference_id`.
+pub fn travel_rule_metadata(off_chain_reference_id: &str) -> Vec<u8> {
+    let metadata = Metadata::TravelRuleMetadata(TravelRuleMetadata::TravelRuleMetadataVersion0(
+        TravelRuleMetadataV0::new(Some(off_chain_reference_id.to_string())),
+    ));
+    lcs::to_bytes(&metadata).expect("metadata must serialize")
+}
+
+/// Return the message signed by the payee's compliance key for a payment of `amount` from
+/// `payer` with `metadata`: `metadata | lcs(payer) | lcs(amount) | DUAL_ATTESTATION_DOMAIN_SEPARATOR`.
+pub fn dual_attestation_message(metadata: &[u8], payer: &AccountAddress, amount: u64) -> Vec<u8> {
+    let mut message = metadata.to_vec();
+    message.extend(lcs::to_bytes(payer).expect("address must serialize"));
+    message.extend(lcs::to_bytes(&amount).expect("amount must serialize"));
+    message.extend_from_slice(DUAL_ATTESTATION_DOMAIN_SEPARATOR);
+    message
+}
+
+/// Return the `metadata_signature` of a payment of `amount` from `payer` with `metadata`, signed
+/// with the payee's `compliance_key`.
+pub fn sign_dual_attestation_message(
+    compliance_key: &Ed25519PrivateKey,
+    metadata: &[u8],
+    payer: &AccountAddress,
+    amount: u64,
+) -> Vec<u8> {
+    // `SigningKey::sign` prefixes messages with the hash seed of their type, which the
+    // `DualAttestation` module doesn't, so the message is signed with the key's bytes directly.
+    let secret_key = ed25519_dalek::SecretKey::from_bytes(&compliance_key.to_bytes())
+        .expect("Ed25519PrivateKey is a valid secret key");
+    let public_key: ed25519_dalek::PublicKey = (&secret_key).into();
+    ed25519_dalek::ExpandedSecretKey::from(&secret_key)
+        .sign(
+            &dual_attestation_message(metadata, payer, amount),
+            &public_key,
+        )
+        .to_bytes()
+        .to_vec()
+}
+
+/// Check that `metadata_signature` is the signature of a payment of `amount` from `payer` with
+/// `metadata` by the payee's `compliance_public_key`, as the `DualAttestation` module will.
+pub fn verify_dual_attestation_signature(
+    compliance_public_key: &Ed25519PublicKey,
+    metadata_signature: &[u8],
+    metadata: &[u8],
+    payer: &AccountAddress,
+    amount: u64,
+) -> Result<()> {
+    let signature = Ed25519Signature::try_from(metadata_signature)?;
+    signature.verify_arbitrary_msg(
+        &dual_attestation_message(metadata, payer, amount),
+        compliance_public_key,
+    )
+}
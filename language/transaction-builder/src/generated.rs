@@ -11,6 +11,33 @@ use libra_types::{
 };
 use move_core_types::language_storage::TypeTag;
 
+/// The length of an ed25519 signature.
+pub const SIGNATURE_LENGTH: usize = 64;
+
+/// The reason a script builder rejected its arguments.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum BuilderError {
+    /// The named amount argument is zero.
+    ZeroAmount(&'static str),
+    /// The named signature argument is neither empty nor `SIGNATURE_LENGTH` bytes long.
+    InvalidSignatureLength(&'static str, usize),
+}
+
+impl std::fmt::Display for BuilderError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            BuilderError::ZeroAmount(name) => write!(f, "Argument {} must not be zero", name),
+            BuilderError::InvalidSignatureLength(name, length) => write!(
+                f,
+                "Argument {} must be empty or {} bytes long, found {} bytes",
+                name, SIGNATURE_LENGTH, length
+            ),
+        }
+    }
+}
+
+impl std::error::Error for BuilderError {}
+
 /// Add a `Currency` balance to `account`, which will enable `account` to send and receive
 /// `Libra<Currency>`. Aborts with NOT_A_CURRENCY if `Currency` is not an accepted
 /// currency type in the Libra system Aborts with `LibraAccount::ADD_EXISTING_CURRENCY` if
@@ -30,6 +57,18 @@ pub fn encode_add_currency_to_account_script(currency: TypeTag) -> Script {
     )
 }
 
+/// Typed arguments of `encode_add_currency_to_account_script`, checked by `build`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct AddCurrencyToAccountBuilder {
+    pub currency: TypeTag,
+}
+
+impl AddCurrencyToAccountBuilder {
+    pub fn build(self) -> std::result::Result<Script, BuilderError> {
+        Ok(encode_add_currency_to_account_script(self.currency))
+    }
+}
+
 /// Add the `KeyRotationCapability` for `to_recover_account` to the `RecoveryAddress`
 /// resource under `recovery_address`. Aborts if `to_recovery_account` and
 /// `to_recovery_address belong to different VASPs, if `recovery_address` does not have a
@@ -49,6 +88,20 @@ pub fn encode_add_recovery_rotation_capability_script(recovery_address: AccountA
     )
 }
 
+/// Typed arguments of `encode_add_recovery_rotation_capability_script`, checked by `build`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct AddRecoveryRotationCapabilityBuilder {
+    pub recovery_address: AccountAddress,
+}
+
+impl AddRecoveryRotationCapabilityBuilder {
+    pub fn build(self) -> std::result::Result<Script, BuilderError> {
+        Ok(encode_add_recovery_rotation_capability_script(
+            self.recovery_address,
+        ))
+    }
+}
+
 /// Add `new_validator` to the pending validator set. Fails if the `new_validator` address
 /// is already in the validator set or does not have a `ValidatorConfig` resource stored
 /// at the address.
@@ -65,6 +118,18 @@ pub fn encode_add_validator_script(validator_address: AccountAddress) -> Script
     )
 }
 
+/// Typed arguments of `encode_add_validator_script`, checked by `build`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct AddValidatorBuilder {
+    pub validator_address: AccountAddress,
+}
+
+impl AddValidatorBuilder {
+    pub fn build(self) -> std::result::Result<Script, BuilderError> {
+        Ok(encode_add_validator_script(self.validator_address))
+    }
+}
+
 /// Permanently destroy the `Token`s stored in the oldest burn request under the `Preburn`
 /// resource. This will only succeed if `account` has a `MintCapability<Token>`, a
 /// `Preburn<Token>` resource exists under `preburn_address`, and there is a pending burn
@@ -92,6 +157,24 @@ pub fn encode_burn_script(
     )
 }
 
+/// Typed arguments of `encode_burn_script`, checked by `build`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct BurnBuilder {
+    pub token: TypeTag,
+    pub sliding_nonce: u64,
+    pub preburn_address: AccountAddress,
+}
+
+impl BurnBuilder {
+    pub fn build(self) -> std::result::Result<Script, BuilderError> {
+        Ok(encode_burn_script(
+            self.token,
+            self.sliding_nonce,
+            self.preburn_address,
+        ))
+    }
+}
+
 /// Burn transaction fees that have been collected in the given `currency` and relinquish
 /// to the association. The currency must be non-synthetic.
 pub fn encode_burn_txn_fees_script(coin_type: TypeTag) -> Script {
@@ -107,6 +190,18 @@ pub fn encode_burn_txn_fees_script(coin_type: TypeTag) -> Script {
     )
 }
 
+/// Typed arguments of `encode_burn_txn_fees_script`, checked by `build`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct BurnTxnFeesBuilder {
+    pub coin_type: TypeTag,
+}
+
+impl BurnTxnFeesBuilder {
+    pub fn build(self) -> std::result::Result<Script, BuilderError> {
+        Ok(encode_burn_txn_fees_script(self.coin_type))
+    }
+}
+
 /// Cancel the oldest burn request from `preburn_address` and return the funds. Fails if
 /// the sender does not have a published `BurnCapability<Token>`.
 pub fn encode_cancel_burn_script(token: TypeTag, preburn_address: AccountAddress) -> Script {
@@ -122,6 +217,19 @@ pub fn encode_cancel_burn_script(token: TypeTag, preburn_address: AccountAddress
     )
 }
 
+/// Typed arguments of `encode_cancel_burn_script`, checked by `build`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CancelBurnBuilder {
+    pub token: TypeTag,
+    pub preburn_address: AccountAddress,
+}
+
+impl CancelBurnBuilder {
+    pub fn build(self) -> std::result::Result<Script, BuilderError> {
+        Ok(encode_cancel_burn_script(self.token, self.preburn_address))
+    }
+}
+
 /// Create a `ChildVASP` account for sender `parent_vasp` at `child_address` with a
 /// balance of `child_initial_balance` in `CoinType` and an initial authentication_key
 /// `auth_key_prefix | child_address`. If `add_all_currencies` is true, the child address
@@ -161,6 +269,28 @@ pub fn encode_create_child_vasp_account_script(
     )
 }
 
+/// Typed arguments of `encode_create_child_vasp_account_script`, checked by `build`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CreateChildVaspAccountBuilder {
+    pub coin_type: TypeTag,
+    pub child_address: AccountAddress,
+    pub auth_key_prefix: Vec<u8>,
+    pub add_all_currencies: bool,
+    pub child_initial_balance: u64,
+}
+
+impl CreateChildVaspAccountBuilder {
+    pub fn build(self) -> std::result::Result<Script, BuilderError> {
+        Ok(encode_create_child_vasp_account_script(
+            self.coin_type,
+            self.child_address,
+            self.auth_key_prefix,
+            self.add_all_currencies,
+            self.child_initial_balance,
+        ))
+    }
+}
+
 /// Create an account with the DesignatedDealer role at `addr` with authentication key
 /// `auth_key_prefix` | `addr` and a 0 balance of type `Currency`. If `add_all_currencies`
 /// is true, 0 balances for all available currencies in the system will also be added.
@@ -200,6 +330,34 @@ pub fn encode_create_designated_dealer_script(
     )
 }
 
+/// Typed arguments of `encode_create_designated_dealer_script`, checked by `build`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CreateDesignatedDealerBuilder {
+    pub currency: TypeTag,
+    pub sliding_nonce: u64,
+    pub addr: AccountAddress,
+    pub auth_key_prefix: Vec<u8>,
+    pub human_name: Vec<u8>,
+    pub base_url: Vec<u8>,
+    pub compliance_public_key: Vec<u8>,
+    pub add_all_currencies: bool,
+}
+
+impl CreateDesignatedDealerBuilder {
+    pub fn build(self) -> std::result::Result<Script, BuilderError> {
+        Ok(encode_create_designated_dealer_script(
+            self.currency,
+            self.sliding_nonce,
+            self.addr,
+            self.auth_key_prefix,
+            self.human_name,
+            self.base_url,
+            self.compliance_public_key,
+            self.add_all_currencies,
+        ))
+    }
+}
+
 /// Create an account with the ParentVASP role at `address` with authentication key
 /// `auth_key_prefix` | `new_account_address` and a 0 balance of type `currency`. If
 /// `add_all_currencies` is true, 0 balances for all available currencies in the system
@@ -234,6 +392,32 @@ pub fn encode_create_parent_vasp_account_script(
     )
 }
 
+/// Typed arguments of `encode_create_parent_vasp_account_script`, checked by `build`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CreateParentVaspAccountBuilder {
+    pub coin_type: TypeTag,
+    pub new_account_address: AccountAddress,
+    pub auth_key_prefix: Vec<u8>,
+    pub human_name: Vec<u8>,
+    pub base_url: Vec<u8>,
+    pub compliance_public_key: Vec<u8>,
+    pub add_all_currencies: bool,
+}
+
+impl CreateParentVaspAccountBuilder {
+    pub fn build(self) -> std::result::Result<Script, BuilderError> {
+        Ok(encode_create_parent_vasp_account_script(
+            self.coin_type,
+            self.new_account_address,
+            self.auth_key_prefix,
+            self.human_name,
+            self.base_url,
+            self.compliance_public_key,
+            self.add_all_currencies,
+        ))
+    }
+}
+
 /// Extract the `KeyRotationCapability` for `recovery_account` and publish it in a
 /// `RecoveryAddress` resource under `recovery_account`. Aborts if `recovery_account` has
 /// delegated its `KeyRotationCapability`, already has a `RecoveryAddress` resource, or is
@@ -251,6 +435,16 @@ pub fn encode_create_recovery_address_script() -> Script {
     )
 }
 
+/// Typed arguments of `encode_create_recovery_address_script`, checked by `build`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CreateRecoveryAddressBuilder {}
+
+impl CreateRecoveryAddressBuilder {
+    pub fn build(self) -> std::result::Result<Script, BuilderError> {
+        Ok(encode_create_recovery_address_script())
+    }
+}
+
 /// Create an account with the ParentVASP role at `address` with authentication key
 /// `auth_key_prefix` | `new_account_address` and a 0 balance of type `currency`. If
 /// `add_all_currencies` is true, 0 balances for all available currencies in the system
@@ -285,6 +479,26 @@ pub fn encode_create_testing_account_script(
     )
 }
 
+/// Typed arguments of `encode_create_testing_account_script`, checked by `build`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CreateTestingAccountBuilder {
+    pub coin_type: TypeTag,
+    pub new_account_address: AccountAddress,
+    pub auth_key_prefix: Vec<u8>,
+    pub add_all_currencies: bool,
+}
+
+impl CreateTestingAccountBuilder {
+    pub fn build(self) -> std::result::Result<Script, BuilderError> {
+        Ok(encode_create_testing_account_script(
+            self.coin_type,
+            self.new_account_address,
+            self.auth_key_prefix,
+            self.add_all_currencies,
+        ))
+    }
+}
+
 /// Create a validator account at `new_validator_address` with `auth_key_prefix`.
 pub fn encode_create_validator_account_script(
     new_account_address: AccountAddress,
@@ -306,6 +520,22 @@ pub fn encode_create_validator_account_script(
     )
 }
 
+/// Typed arguments of `encode_create_validator_account_script`, checked by `build`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CreateValidatorAccountBuilder {
+    pub new_account_address: AccountAddress,
+    pub auth_key_prefix: Vec<u8>,
+}
+
+impl CreateValidatorAccountBuilder {
+    pub fn build(self) -> std::result::Result<Script, BuilderError> {
+        Ok(encode_create_validator_account_script(
+            self.new_account_address,
+            self.auth_key_prefix,
+        ))
+    }
+}
+
 /// Create a validator operator account at `new_validator_address` with `auth_key_prefix`.
 pub fn encode_create_validator_operator_account_script(
     new_account_address: AccountAddress,
@@ -327,6 +557,22 @@ pub fn encode_create_validator_operator_account_script(
     )
 }
 
+/// Typed arguments of `encode_create_validator_operator_account_script`, checked by `build`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CreateValidatorOperatorAccountBuilder {
+    pub new_account_address: AccountAddress,
+    pub auth_key_prefix: Vec<u8>,
+}
+
+impl CreateValidatorOperatorAccountBuilder {
+    pub fn build(self) -> std::result::Result<Script, BuilderError> {
+        Ok(encode_create_validator_operator_account_script(
+            self.new_account_address,
+            self.auth_key_prefix,
+        ))
+    }
+}
+
 /// Freeze account `address`. Initiator must be authorized. `sliding_nonce` is a unique
 /// nonce for operation, see sliding_nonce.move for details.
 pub fn encode_freeze_account_script(
@@ -351,6 +597,22 @@ pub fn encode_freeze_account_script(
     )
 }
 
+/// Typed arguments of `encode_freeze_account_script`, checked by `build`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct FreezeAccountBuilder {
+    pub sliding_nonce: u64,
+    pub to_freeze_account: AccountAddress,
+}
+
+impl FreezeAccountBuilder {
+    pub fn build(self) -> std::result::Result<Script, BuilderError> {
+        Ok(encode_freeze_account_script(
+            self.sliding_nonce,
+            self.to_freeze_account,
+        ))
+    }
+}
+
 /// Mint `amount_lbr` LBR from the sending account's constituent coins and deposits the
 /// resulting LBR into the sending account.
 pub fn encode_mint_lbr_script(amount_lbr: u64) -> Script {
@@ -372,6 +634,21 @@ pub fn encode_mint_lbr_script(amount_lbr: u64) -> Script {
     )
 }
 
+/// Typed arguments of `encode_mint_lbr_script`, checked by `build`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct MintLbrBuilder {
+    pub amount_lbr: u64,
+}
+
+impl MintLbrBuilder {
+    pub fn build(self) -> std::result::Result<Script, BuilderError> {
+        if self.amount_lbr == 0 {
+            return Err(BuilderError::ZeroAmount("amount_lbr"));
+        }
+        Ok(encode_mint_lbr_script(self.amount_lbr))
+    }
+}
+
 /// Modify publishing options. Takes the LCS bytes of a `VMPublishingOption` object as
 /// input.
 pub fn encode_modify_publishing_option_script(args: Vec<u8>) -> Script {
@@ -388,6 +665,18 @@ pub fn encode_modify_publishing_option_script(args: Vec<u8>) -> Script {
     )
 }
 
+/// Typed arguments of `encode_modify_publishing_option_script`, checked by `build`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ModifyPublishingOptionBuilder {
+    pub args: Vec<u8>,
+}
+
+impl ModifyPublishingOptionBuilder {
+    pub fn build(self) -> std::result::Result<Script, BuilderError> {
+        Ok(encode_modify_publishing_option_script(self.args))
+    }
+}
+
 /// Transfer `amount` coins of type `Currency` from `payer` to `payee` with (optional)
 /// associated `metadata` and an (optional) `metadata_signature` on the message `metadata`
 /// | `Signer::address_of(payer)` | `amount` | `DualAttestation::DOMAIN_SEPARATOR`. The
@@ -447,6 +736,38 @@ pub fn encode_peer_to_peer_with_metadata_script(
     )
 }
 
+/// Typed arguments of `encode_peer_to_peer_with_metadata_script`, checked by `build`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PeerToPeerWithMetadataBuilder {
+    pub currency: TypeTag,
+    pub payee: AccountAddress,
+    pub amount: u64,
+    pub metadata: Vec<u8>,
+    pub metadata_signature: Vec<u8>,
+}
+
+impl PeerToPeerWithMetadataBuilder {
+    pub fn build(self) -> std::result::Result<Script, BuilderError> {
+        if self.amount == 0 {
+            return Err(BuilderError::ZeroAmount("amount"));
+        }
+        if !self.metadata_signature.is_empty() && self.metadata_signature.len() != SIGNATURE_LENGTH
+        {
+            return Err(BuilderError::InvalidSignatureLength(
+                "metadata_signature",
+                self.metadata_signature.len(),
+            ));
+        }
+        Ok(encode_peer_to_peer_with_metadata_script(
+            self.currency,
+            self.payee,
+            self.amount,
+            self.metadata,
+            self.metadata_signature,
+        ))
+    }
+}
+
 /// Preburn `amount` `Token`s from `account`. This will only succeed if `account` already
 /// has a published `Preburn<Token>` resource.
 pub fn encode_preburn_script(token: TypeTag, amount: u64) -> Script {
@@ -468,6 +789,22 @@ pub fn encode_preburn_script(token: TypeTag, amount: u64) -> Script {
     )
 }
 
+/// Typed arguments of `encode_preburn_script`, checked by `build`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PreburnBuilder {
+    pub token: TypeTag,
+    pub amount: u64,
+}
+
+impl PreburnBuilder {
+    pub fn build(self) -> std::result::Result<Script, BuilderError> {
+        if self.amount == 0 {
+            return Err(BuilderError::ZeroAmount("amount"));
+        }
+        Ok(encode_preburn_script(self.token, self.amount))
+    }
+}
+
 /// Publishes an unrestricted `LimitsDefintion<CoinType>` under `account`. Will abort if a
 /// resource with the same type already exists under `account`. No windows will point to
 /// this limit at the time it is published.
@@ -485,6 +822,20 @@ pub fn encode_publish_account_limit_definition_script(coin_type: TypeTag) -> Scr
     )
 }
 
+/// Typed arguments of `encode_publish_account_limit_definition_script`, checked by `build`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PublishAccountLimitDefinitionBuilder {
+    pub coin_type: TypeTag,
+}
+
+impl PublishAccountLimitDefinitionBuilder {
+    pub fn build(self) -> std::result::Result<Script, BuilderError> {
+        Ok(encode_publish_account_limit_definition_script(
+            self.coin_type,
+        ))
+    }
+}
+
 /// (1) Rotate the authentication key of the sender to `public_key` (2) Publish a resource
 /// containing a 32-byte ed25519 public key and the rotation capability of the sender
 /// under the sender's address. Aborts if the sender already has a
@@ -502,6 +853,20 @@ pub fn encode_publish_shared_ed25519_public_key_script(public_key: Vec<u8>) -> S
     )
 }
 
+/// Typed arguments of `encode_publish_shared_ed25519_public_key_script`, checked by `build`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PublishSharedEd25519PublicKeyBuilder {
+    pub public_key: Vec<u8>,
+}
+
+impl PublishSharedEd25519PublicKeyBuilder {
+    pub fn build(self) -> std::result::Result<Script, BuilderError> {
+        Ok(encode_publish_shared_ed25519_public_key_script(
+            self.public_key,
+        ))
+    }
+}
+
 /// Update configs of all the validators and emit reconfiguration event.
 pub fn encode_reconfigure_script() -> Script {
     Script::new(
@@ -517,6 +882,16 @@ pub fn encode_reconfigure_script() -> Script {
     )
 }
 
+/// Typed arguments of `encode_reconfigure_script`, checked by `build`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ReconfigureBuilder {}
+
+impl ReconfigureBuilder {
+    pub fn build(self) -> std::result::Result<Script, BuilderError> {
+        Ok(encode_reconfigure_script())
+    }
+}
+
 /// Adding `to_remove` to the set of pending validator removals. Fails if the `to_remove`
 /// address is already in the validator set or already in the pending removals. Callable
 /// by Validator's operator.
@@ -533,6 +908,18 @@ pub fn encode_remove_validator_script(validator_address: AccountAddress) -> Scri
     )
 }
 
+/// Typed arguments of `encode_remove_validator_script`, checked by `build`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RemoveValidatorBuilder {
+    pub validator_address: AccountAddress,
+}
+
+impl RemoveValidatorBuilder {
+    pub fn build(self) -> std::result::Result<Script, BuilderError> {
+        Ok(encode_remove_validator_script(self.validator_address))
+    }
+}
+
 /// Rotate the sender's authentication key to `new_key`. `new_key` should be a 256 bit
 /// sha3 hash of an ed25519 public key.
 pub fn encode_rotate_authentication_key_script(new_key: Vec<u8>) -> Script {
@@ -555,6 +942,18 @@ pub fn encode_rotate_authentication_key_script(new_key: Vec<u8>) -> Script {
     )
 }
 
+/// Typed arguments of `encode_rotate_authentication_key_script`, checked by `build`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RotateAuthenticationKeyBuilder {
+    pub new_key: Vec<u8>,
+}
+
+impl RotateAuthenticationKeyBuilder {
+    pub fn build(self) -> std::result::Result<Script, BuilderError> {
+        Ok(encode_rotate_authentication_key_script(self.new_key))
+    }
+}
+
 /// Rotate the sender's authentication key to `new_key`. `new_key` should be a 256 bit
 /// sha3 hash of an ed25519 public key. This script also takes `sliding_nonce`, as a
 /// unique nonce for this operation. See sliding_nonce.move for details.
@@ -587,6 +986,22 @@ pub fn encode_rotate_authentication_key_with_nonce_script(
     )
 }
 
+/// Typed arguments of `encode_rotate_authentication_key_with_nonce_script`, checked by `build`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RotateAuthenticationKeyWithNonceBuilder {
+    pub sliding_nonce: u64,
+    pub new_key: Vec<u8>,
+}
+
+impl RotateAuthenticationKeyWithNonceBuilder {
+    pub fn build(self) -> std::result::Result<Script, BuilderError> {
+        Ok(encode_rotate_authentication_key_with_nonce_script(
+            self.sliding_nonce,
+            self.new_key,
+        ))
+    }
+}
+
 /// Rotate the authentication key of `to_recover` to `new_key`. Can be invoked by either
 /// `recovery_address` or `to_recover`. Aborts if `recovery_address` does not have the
 /// `KeyRotationCapability` for `to_recover`.
@@ -612,6 +1027,26 @@ pub fn encode_rotate_authentication_key_with_recovery_address_script(
     )
 }
 
+/// Typed arguments of `encode_rotate_authentication_key_with_recovery_address_script`, checked by `build`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RotateAuthenticationKeyWithRecoveryAddressBuilder {
+    pub recovery_address: AccountAddress,
+    pub to_recover: AccountAddress,
+    pub new_key: Vec<u8>,
+}
+
+impl RotateAuthenticationKeyWithRecoveryAddressBuilder {
+    pub fn build(self) -> std::result::Result<Script, BuilderError> {
+        Ok(
+            encode_rotate_authentication_key_with_recovery_address_script(
+                self.recovery_address,
+                self.to_recover,
+                self.new_key,
+            ),
+        )
+    }
+}
+
 /// Rotate `account`'s base URL to `new_url` and its compliance public key to `new_key`.
 /// Aborts if `account` is not a ParentVASP or DesignatedDealer Aborts if `new_key` is not
 /// a well-formed public key
@@ -634,6 +1069,22 @@ pub fn encode_rotate_dual_attestation_info_script(new_url: Vec<u8>, new_key: Vec
     )
 }
 
+/// Typed arguments of `encode_rotate_dual_attestation_info_script`, checked by `build`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RotateDualAttestationInfoBuilder {
+    pub new_url: Vec<u8>,
+    pub new_key: Vec<u8>,
+}
+
+impl RotateDualAttestationInfoBuilder {
+    pub fn build(self) -> std::result::Result<Script, BuilderError> {
+        Ok(encode_rotate_dual_attestation_info_script(
+            self.new_url,
+            self.new_key,
+        ))
+    }
+}
+
 /// (1) Rotate the public key stored in `account`'s `SharedEd25519PublicKey` resource to
 /// `new_public_key` (2) Rotate the authentication key using the capability stored in
 /// `account`'s `SharedEd25519PublicKey` to a new value derived from `new_public_key`
@@ -653,6 +1104,20 @@ pub fn encode_rotate_shared_ed25519_public_key_script(public_key: Vec<u8>) -> Sc
     )
 }
 
+/// Typed arguments of `encode_rotate_shared_ed25519_public_key_script`, checked by `build`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RotateSharedEd25519PublicKeyBuilder {
+    pub public_key: Vec<u8>,
+}
+
+impl RotateSharedEd25519PublicKeyBuilder {
+    pub fn build(self) -> std::result::Result<Script, BuilderError> {
+        Ok(encode_rotate_shared_ed25519_public_key_script(
+            self.public_key,
+        ))
+    }
+}
+
 /// Set validator's config.
 pub fn encode_set_validator_config_script(
     validator_account: AccountAddress,
@@ -682,6 +1147,30 @@ pub fn encode_set_validator_config_script(
     )
 }
 
+/// Typed arguments of `encode_set_validator_config_script`, checked by `build`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SetValidatorConfigBuilder {
+    pub validator_account: AccountAddress,
+    pub consensus_pubkey: Vec<u8>,
+    pub validator_network_identity_pubkey: Vec<u8>,
+    pub validator_network_address: Vec<u8>,
+    pub fullnodes_network_identity_pubkey: Vec<u8>,
+    pub fullnodes_network_address: Vec<u8>,
+}
+
+impl SetValidatorConfigBuilder {
+    pub fn build(self) -> std::result::Result<Script, BuilderError> {
+        Ok(encode_set_validator_config_script(
+            self.validator_account,
+            self.consensus_pubkey,
+            self.validator_network_identity_pubkey,
+            self.validator_network_address,
+            self.fullnodes_network_identity_pubkey,
+            self.fullnodes_network_address,
+        ))
+    }
+}
+
 /// Set validator's operator
 pub fn encode_set_validator_operator_script(operator_account: AccountAddress) -> Script {
     Script::new(
@@ -696,6 +1185,18 @@ pub fn encode_set_validator_operator_script(operator_account: AccountAddress) ->
     )
 }
 
+/// Typed arguments of `encode_set_validator_operator_script`, checked by `build`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SetValidatorOperatorBuilder {
+    pub operator_account: AccountAddress,
+}
+
+impl SetValidatorOperatorBuilder {
+    pub fn build(self) -> std::result::Result<Script, BuilderError> {
+        Ok(encode_set_validator_operator_script(self.operator_account))
+    }
+}
+
 /// Send `amount` coins of type `Token` to `payee`.
 pub fn encode_testnet_mint_script(token: TypeTag, payee: AccountAddress, amount: u64) -> Script {
     Script::new(
@@ -730,6 +1231,27 @@ pub fn encode_testnet_mint_script(token: TypeTag, payee: AccountAddress, amount:
     )
 }
 
+/// Typed arguments of `encode_testnet_mint_script`, checked by `build`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TestnetMintBuilder {
+    pub token: TypeTag,
+    pub payee: AccountAddress,
+    pub amount: u64,
+}
+
+impl TestnetMintBuilder {
+    pub fn build(self) -> std::result::Result<Script, BuilderError> {
+        if self.amount == 0 {
+            return Err(BuilderError::ZeroAmount("amount"));
+        }
+        Ok(encode_testnet_mint_script(
+            self.token,
+            self.payee,
+            self.amount,
+        ))
+    }
+}
+
 /// Mint 'mint_amount' to 'designated_dealer_address' for 'tier_index' tier. Max valid
 /// tier index is 3 since there are max 4 tiers per DD. Sender should be treasury
 /// compliance account and receiver authorized DD. `sliding_nonce` is a unique nonce for
@@ -762,6 +1284,31 @@ pub fn encode_tiered_mint_script(
     )
 }
 
+/// Typed arguments of `encode_tiered_mint_script`, checked by `build`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TieredMintBuilder {
+    pub coin_type: TypeTag,
+    pub sliding_nonce: u64,
+    pub designated_dealer_address: AccountAddress,
+    pub mint_amount: u64,
+    pub tier_index: u64,
+}
+
+impl TieredMintBuilder {
+    pub fn build(self) -> std::result::Result<Script, BuilderError> {
+        if self.mint_amount == 0 {
+            return Err(BuilderError::ZeroAmount("mint_amount"));
+        }
+        Ok(encode_tiered_mint_script(
+            self.coin_type,
+            self.sliding_nonce,
+            self.designated_dealer_address,
+            self.mint_amount,
+            self.tier_index,
+        ))
+    }
+}
+
 /// Unfreeze account `address`. Initiator must be authorized. `sliding_nonce` is a unique
 /// nonce for operation, see sliding_nonce.move for details.
 pub fn encode_unfreeze_account_script(
@@ -786,6 +1333,22 @@ pub fn encode_unfreeze_account_script(
     )
 }
 
+/// Typed arguments of `encode_unfreeze_account_script`, checked by `build`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct UnfreezeAccountBuilder {
+    pub sliding_nonce: u64,
+    pub to_unfreeze_account: AccountAddress,
+}
+
+impl UnfreezeAccountBuilder {
+    pub fn build(self) -> std::result::Result<Script, BuilderError> {
+        Ok(encode_unfreeze_account_script(
+            self.sliding_nonce,
+            self.to_unfreeze_account,
+        ))
+    }
+}
+
 /// Unmints `amount_lbr` LBR from the sending account into the constituent coins and
 /// deposits the resulting coins into the sending account."
 pub fn encode_unmint_lbr_script(amount_lbr: u64) -> Script {
@@ -807,6 +1370,21 @@ pub fn encode_unmint_lbr_script(amount_lbr: u64) -> Script {
     )
 }
 
+/// Typed arguments of `encode_unmint_lbr_script`, checked by `build`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct UnmintLbrBuilder {
+    pub amount_lbr: u64,
+}
+
+impl UnmintLbrBuilder {
+    pub fn build(self) -> std::result::Result<Script, BuilderError> {
+        if self.amount_lbr == 0 {
+            return Err(BuilderError::ZeroAmount("amount_lbr"));
+        }
+        Ok(encode_unmint_lbr_script(self.amount_lbr))
+    }
+}
+
 /// Optionally update thresholds of max balance, inflow, outflow for any limits-bound
 /// accounts with their limits defined at `limit_address`. Limits are defined in terms of
 /// base (on-chain) currency units for `CoinType`. If a new threshold is 0, that
@@ -845,6 +1423,32 @@ pub fn encode_update_account_limit_definition_script(
     )
 }
 
+/// Typed arguments of `encode_update_account_limit_definition_script`, checked by `build`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct UpdateAccountLimitDefinitionBuilder {
+    pub coin_type: TypeTag,
+    pub limit_address: AccountAddress,
+    pub sliding_nonce: u64,
+    pub new_max_inflow: u64,
+    pub new_max_outflow: u64,
+    pub new_max_holding_balance: u64,
+    pub new_time_period: u64,
+}
+
+impl UpdateAccountLimitDefinitionBuilder {
+    pub fn build(self) -> std::result::Result<Script, BuilderError> {
+        Ok(encode_update_account_limit_definition_script(
+            self.coin_type,
+            self.limit_address,
+            self.sliding_nonce,
+            self.new_max_inflow,
+            self.new_max_outflow,
+            self.new_max_holding_balance,
+            self.new_time_period,
+        ))
+    }
+}
+
 /// * Sets the account limits window `tracking_balance` field for `CoinType` at
 /// `window_address` to `aggregate_balance` if `aggregate_balance != 0`. * Sets the
 /// account limits window `limit_address` field for `CoinType` at `window_address` to
@@ -872,6 +1476,26 @@ pub fn encode_update_account_limit_window_info_script(
     )
 }
 
+/// Typed arguments of `encode_update_account_limit_window_info_script`, checked by `build`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct UpdateAccountLimitWindowInfoBuilder {
+    pub coin_type: TypeTag,
+    pub window_address: AccountAddress,
+    pub aggregate_balance: u64,
+    pub new_limit_address: AccountAddress,
+}
+
+impl UpdateAccountLimitWindowInfoBuilder {
+    pub fn build(self) -> std::result::Result<Script, BuilderError> {
+        Ok(encode_update_account_limit_window_info_script(
+            self.coin_type,
+            self.window_address,
+            self.aggregate_balance,
+            self.new_limit_address,
+        ))
+    }
+}
+
 /// Update the dual attesation limit to `new_micro_lbr_limit`.
 pub fn encode_update_dual_attestation_limit_script(
     sliding_nonce: u64,
@@ -895,6 +1519,22 @@ pub fn encode_update_dual_attestation_limit_script(
     )
 }
 
+/// Typed arguments of `encode_update_dual_attestation_limit_script`, checked by `build`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct UpdateDualAttestationLimitBuilder {
+    pub sliding_nonce: u64,
+    pub new_micro_lbr_limit: u64,
+}
+
+impl UpdateDualAttestationLimitBuilder {
+    pub fn build(self) -> std::result::Result<Script, BuilderError> {
+        Ok(encode_update_dual_attestation_limit_script(
+            self.sliding_nonce,
+            self.new_micro_lbr_limit,
+        ))
+    }
+}
+
 /// Update the on-chain exchange rate to LBR for the given `currency` to be given by
 /// `new_exchange_rate_numerator/new_exchange_rate_denominator`.
 pub fn encode_update_exchange_rate_script(
@@ -926,6 +1566,26 @@ pub fn encode_update_exchange_rate_script(
     )
 }
 
+/// Typed arguments of `encode_update_exchange_rate_script`, checked by `build`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct UpdateExchangeRateBuilder {
+    pub currency: TypeTag,
+    pub sliding_nonce: u64,
+    pub new_exchange_rate_numerator: u64,
+    pub new_exchange_rate_denominator: u64,
+}
+
+impl UpdateExchangeRateBuilder {
+    pub fn build(self) -> std::result::Result<Script, BuilderError> {
+        Ok(encode_update_exchange_rate_script(
+            self.currency,
+            self.sliding_nonce,
+            self.new_exchange_rate_numerator,
+            self.new_exchange_rate_denominator,
+        ))
+    }
+}
+
 /// Update Libra version.
 pub fn encode_update_libra_version_script(major: u64) -> Script {
     Script::new(
@@ -940,6 +1600,18 @@ pub fn encode_update_libra_version_script(major: u64) -> Script {
     )
 }
 
+/// Typed arguments of `encode_update_libra_version_script`, checked by `build`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct UpdateLibraVersionBuilder {
+    pub major: u64,
+}
+
+impl UpdateLibraVersionBuilder {
+    pub fn build(self) -> std::result::Result<Script, BuilderError> {
+        Ok(encode_update_libra_version_script(self.major))
+    }
+}
+
 /// Allows--true--or disallows--false--minting of `currency` based upon `allow_minting`.
 pub fn encode_update_minting_ability_script(currency: TypeTag, allow_minting: bool) -> Script {
     Script::new(
@@ -954,3 +1626,19 @@ pub fn encode_update_minting_ability_script(currency: TypeTag, allow_minting: bo
         vec![TransactionArgument::Bool(allow_minting)],
     )
 }
+
+/// Typed arguments of `encode_update_minting_ability_script`, checked by `build`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct UpdateMintingAbilityBuilder {
+    pub currency: TypeTag,
+    pub allow_minting: bool,
+}
+
+impl UpdateMintingAbilityBuilder {
+    pub fn build(self) -> std::result::Result<Script, BuilderError> {
+        Ok(encode_update_minting_ability_script(
+            self.currency,
+            self.allow_minting,
+        ))
+    }
+}
@@ -17,6 +17,12 @@ use std::convert::TryFrom;
 /// Generated builders.
 mod generated;
 
+/// Construction of full transactions around the scripts of the builders.
+pub mod transaction_factory;
+
+/// Metadata and signatures of payments subject to dual attestation.
+pub mod dual_attestation;
+
 /// Re-export all generated builders unless they are shadowed by custom builders below.
 pub use generated::*;
 
@@ -0,0 +1,204 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Construction of full transactions around the script payloads of the builders.
+//!
+//! A `TransactionFactory` holds the parameters shared by every transaction a client sends, so
+//! that only the sender and the script vary per transaction:
+//!
+//! ```ignore
+//! let factory = TransactionFactory::new(ChainId::test()).with_gas_currency(Currency::Coin1);
+//! let sequence_numbers = LocalSequenceNumbers::new();
+//! sequence_numbers.set(sender, 0);
+//! let txn = factory.signed_script(
+//!     sender,
+//!     encode_peer_to_peer_with_metadata_script(coin1_tag(), payee, 100, vec![], vec![]),
+//!     &sequence_numbers,
+//!     &private_key,
+//! )?;
+//! ```
+
+use anyhow::{format_err, Result};
+use libra_crypto::{ed25519::Ed25519PrivateKey, PrivateKey};
+use libra_types::{
+    account_address::AccountAddress,
+    account_config::Currency,
+    chain_id::ChainId,
+    transaction::{RawTransaction, Script, SignedTransaction, TransactionPayload},
+};
+use std::{
+    collections::HashMap,
+    sync::Mutex,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+/// Where the sequence number of the next transaction of an account comes from, such as a local
+/// counter or the account state on chain.
+pub trait SequenceNumberProvider {
+    /// Return the sequence number to use for the next transaction sent by `address`.
+    fn next_sequence_number(&self, address: &AccountAddress) -> Result<u64>;
+}
+
+/// Hands out consecutive sequence numbers for the accounts it was told the sequence number of.
+#[derive(Debug, Default)]
+pub struct LocalSequenceNumbers {
+    next: Mutex<HashMap<AccountAddress, u64>>,
+}
+
+impl LocalSequenceNumbers {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the sequence number of the next transaction of `address`.
+    pub fn set(&self, address: AccountAddress, sequence_number: u64) {
+        self.next
+            .lock()
+            .expect("sequence number lock poisoned")
+            .insert(address, sequence_number);
+    }
+}
+
+impl SequenceNumberProvider for LocalSequenceNumbers {
+    fn next_sequence_number(&self, address: &AccountAddress) -> Result<u64> {
+        let mut next = self.next.lock().expect("sequence number lock poisoned");
+        let sequence_number = next
+            .get_mut(address)
+            .ok_or_else(|| format_err!("Unknown sequence number for account {}", address))?;
+        *sequence_number += 1;
+        Ok(*sequence_number - 1)
+    }
+}
+
+/// When the transactions built by a factory expire.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ExpirationPolicy {
+    /// Expire at a fixed time, as a duration since the Unix epoch.
+    Absolute(Duration),
+    /// Expire this long after the transaction is built.
+    Relative(Duration),
+}
+
+impl ExpirationPolicy {
+    /// Return the expiration time of a transaction built now, as a duration since the Unix epoch.
+    pub fn expiration_time(&self) -> Duration {
+        match self {
+            ExpirationPolicy::Absolute(time) => *time,
+            ExpirationPolicy::Relative(timeout) => {
+                SystemTime::now()
+                    .duration_since(UNIX_EPOCH)
+                    .expect("System time is before the Unix epoch")
+                    + *timeout
+            }
+        }
+    }
+}
+
+/// Builds the transactions of a client given the chain, gas and expiration parameters they
+/// share.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TransactionFactory {
+    chain_id: ChainId,
+    gas_currency: Currency,
+    max_gas_amount: u64,
+    gas_unit_price: u64,
+    expiration: ExpirationPolicy,
+}
+
+impl TransactionFactory {
+    pub const DEFAULT_MAX_GAS_AMOUNT: u64 = 1_000_000;
+    pub const DEFAULT_GAS_UNIT_PRICE: u64 = 0;
+    pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(100);
+
+    /// Return a factory for transactions on `chain_id`, paying gas in LBR, with the default gas
+    /// parameters and expiring `DEFAULT_TIMEOUT` after they are built.
+    pub fn new(chain_id: ChainId) -> Self {
+        Self {
+            chain_id,
+            gas_currency: Currency::LBR,
+            max_gas_amount: Self::DEFAULT_MAX_GAS_AMOUNT,
+            gas_unit_price: Self::DEFAULT_GAS_UNIT_PRICE,
+            expiration: ExpirationPolicy::Relative(Self::DEFAULT_TIMEOUT),
+        }
+    }
+
+    pub fn with_gas_currency(mut self, gas_currency: Currency) -> Self {
+        self.gas_currency = gas_currency;
+        self
+    }
+
+    pub fn with_max_gas_amount(mut self, max_gas_amount: u64) -> Self {
+        self.max_gas_amount = max_gas_amount;
+        self
+    }
+
+    pub fn with_gas_unit_price(mut self, gas_unit_price: u64) -> Self {
+        self.gas_unit_price = gas_unit_price;
+        self
+    }
+
+    pub fn with_expiration(mut self, expiration: ExpirationPolicy) -> Self {
+        self.expiration = expiration;
+        self
+    }
+
+    pub fn chain_id(&self) -> ChainId {
+        self.chain_id
+    }
+
+    /// Build the transaction sending `payload` from `sender` with `sequence_number`.
+    pub fn payload(
+        &self,
+        sender: AccountAddress,
+        sequence_number: u64,
+        payload: TransactionPayload,
+    ) -> RawTransaction {
+        RawTransaction::new(
+            sender,
+            sequence_number,
+            payload,
+            self.max_gas_amount,
+            self.gas_unit_price,
+            self.gas_currency.into(),
+            self.expiration.expiration_time(),
+            self.chain_id,
+        )
+    }
+
+    /// Build the transaction running `script` from `sender` with `sequence_number`.
+    pub fn script(
+        &self,
+        sender: AccountAddress,
+        sequence_number: u64,
+        script: Script,
+    ) -> RawTransaction {
+        self.payload(sender, sequence_number, TransactionPayload::Script(script))
+    }
+
+    /// Build the transaction running `script` from `sender`, with the next sequence number of
+    /// `sender` from `sequence_numbers`.
+    pub fn script_with_next_sequence_number(
+        &self,
+        sender: AccountAddress,
+        script: Script,
+        sequence_numbers: &dyn SequenceNumberProvider,
+    ) -> Result<RawTransaction> {
+        let sequence_number = sequence_numbers.next_sequence_number(&sender)?;
+        Ok(self.script(sender, sequence_number, script))
+    }
+
+    /// Build and sign the transaction running `script` from `sender`, with the next sequence
+    /// number of `sender` from `sequence_numbers`.
+    pub fn signed_script(
+        &self,
+        sender: AccountAddress,
+        script: Script,
+        sequence_numbers: &dyn SequenceNumberProvider,
+        private_key: &Ed25519PrivateKey,
+    ) -> Result<SignedTransaction> {
+        let raw_txn = self.script_with_next_sequence_number(sender, script, sequence_numbers)?;
+        Ok(raw_txn
+            .sign(private_key, private_key.public_key())?
+            .into_inner())
+    }
+}
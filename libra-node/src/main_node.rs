@@ -1,10 +1,11 @@
 // Copyright (c) The Libra Core Contributors
 // SPDX-License-Identifier: Apache-2.0
 
+use backup_cli::coordinators::fast_sync::FastSyncCoordinator;
 use backup_service::start_backup_service;
 use consensus::{consensus_provider::start_consensus, gen_consensus_reconfig_subscription};
 use debug_interface::node_debug_service::NodeDebugService;
-use executor::{db_bootstrapper::bootstrap_db_if_empty, Executor};
+use executor::{chunk_replayer::ChunkReplayer, db_bootstrapper::bootstrap_db_if_empty, Executor};
 use executor_types::ChunkExecutor;
 use futures::{channel::mpsc::channel, executor::block_on};
 use libra_config::{
@@ -16,10 +17,17 @@ use libra_logger::prelude::*;
 use libra_mempool::gen_mempool_reconfig_subscription;
 use libra_metrics::metric_server;
 use libra_vm::LibraVM;
-use libradb::LibraDB;
+use libradb::{ColdStorageConfig, LibraDB, PrunerConfig};
+use network::peer_reputation::PeerReputations;
 use network_builder::builder::NetworkBuilder;
 use state_synchronizer::StateSynchronizer;
-use std::{boxed::Box, net::ToSocketAddrs, sync::Arc, thread, time::Instant};
+use std::{
+    boxed::Box,
+    net::ToSocketAddrs,
+    sync::Arc,
+    thread,
+    time::{Duration, Instant},
+};
 use storage_interface::DbReaderWriter;
 use storage_service::start_storage_service_with_db;
 use tokio::runtime::Runtime;
@@ -38,10 +46,60 @@ pub struct LibraHandle {
 }
 
 fn setup_chunk_executor(db: DbReaderWriter) -> Box<dyn ChunkExecutor> {
-    Box::new(Executor::<LibraVM>::new(db))
+    let mut chunk_replayer = ChunkReplayer::new(Executor::<LibraVM>::new(db));
+    chunk_replayer.add_progress_callback(|progress| {
+        debug!(
+            "[state sync] Synced transactions [{}, {}].",
+            progress.first_version, progress.last_version,
+        )
+    });
+    Box::new(chunk_replayer)
 }
 
-fn setup_debug_interface(config: &NodeConfig) -> NodeDebugService {
+/// Bootstraps an empty DB at the waypoint from backups if fast sync is configured, so that state
+/// sync doesn't replay the transactions before the waypoint. Panics if the restore fails.
+fn fast_sync_if_empty(node_config: &NodeConfig, libra_db: &LibraDB, db_rw: &DbReaderWriter) {
+    let fast_sync_config = match &node_config.state_sync.fast_sync {
+        Some(fast_sync_config) => fast_sync_config,
+        None => return,
+    };
+    let tree_state = db_rw
+        .reader
+        .get_latest_tree_state()
+        .expect("Reading the latest tree state should work.");
+    if !tree_state.is_empty() {
+        return;
+    }
+
+    let waypoint = node_config.base.waypoint.waypoint();
+    info!("Fast syncing the empty DB from waypoint {}.", waypoint);
+    let mut rt = Runtime::new().expect("Failed to create the fast sync runtime.");
+    let res = rt.block_on(async {
+        FastSyncCoordinator::new_with_config(
+            fast_sync_config,
+            waypoint,
+            node_config.storage.dir(),
+            Arc::new(libra_db.get_restore_handler()),
+        )
+        .await?
+        .run()
+        .await
+    });
+    // A failed restore can leave part of what it wrote behind, e.g. epoch ending LedgerInfos with
+    // no state, which bootstrapping the DB with genesis would then commit under, so don't go on.
+    if let Err(e) = res {
+        panic!(
+            "Fast sync failed. Clear the DB at {:?} before restarting: {:?}",
+            node_config.storage.dir(),
+            e
+        );
+    }
+}
+
+fn setup_debug_interface(
+    config: &NodeConfig,
+    peer_reputations: Vec<PeerReputations>,
+) -> NodeDebugService {
     let addr = format!(
         "{}:{}",
         config.debug_interface.address, config.debug_interface.admission_control_node_debug_port,
@@ -54,7 +112,7 @@ fn setup_debug_interface(config: &NodeConfig) -> NodeDebugService {
     libra_trace::set_libra_trace(&config.debug_interface.libra_trace.sampling)
         .expect("Failed to set libra trace sampling rate.");
 
-    NodeDebugService::new(addr)
+    NodeDebugService::new(addr, peer_reputations)
 }
 
 pub fn setup_environment(node_config: &mut NodeConfig) -> LibraHandle {
@@ -66,14 +124,47 @@ pub fn setup_environment(node_config: &mut NodeConfig) -> LibraHandle {
         .expect("Building rayon global thread pool should work.");
 
     let mut instant = Instant::now();
+    let pruner_config = PrunerConfig {
+        state_prune_window: node_config.storage.prune_window,
+        event_prune_window: node_config.storage.event_prune_window,
+        transaction_prune_window: node_config.storage.transaction_prune_window,
+    };
+    // No pruner runs unless some data is configured to be pruned.
+    let pruner_config = if pruner_config == PrunerConfig::default() {
+        None
+    } else {
+        Some(pruner_config)
+    };
     let (libra_db, db_rw) = DbReaderWriter::wrap(
-        LibraDB::open(
-            &node_config.storage.dir(),
-            false, /* readonly */
-            node_config.storage.prune_window,
-        )
+        match node_config.storage.cold_storage_dir() {
+            Some(path) => LibraDB::open_with_cold_storage(
+                &node_config.storage.dir(),
+                pruner_config,
+                ColdStorageConfig {
+                    path,
+                    hot_window: node_config.storage.hot_window,
+                },
+            ),
+            None => LibraDB::open(
+                &node_config.storage.dir(),
+                false, /* readonly */
+                pruner_config,
+            ),
+        }
         .expect("DB should open."),
     );
+    libra_db.set_slow_read_threshold(
+        node_config
+            .storage
+            .slow_read_threshold_ms
+            .map(Duration::from_millis),
+    );
+    libra_db.set_commit_interval(
+        node_config
+            .storage
+            .commit_interval_ms
+            .map(Duration::from_millis),
+    );
     let _simple_storage_service =
         start_storage_service_with_db(&node_config, Arc::clone(&libra_db));
     let backup_service = start_backup_service(
@@ -81,6 +172,7 @@ pub fn setup_environment(node_config: &mut NodeConfig) -> LibraHandle {
         Arc::clone(&libra_db),
     );
 
+    fast_sync_if_empty(&node_config, &libra_db, &db_rw);
     bootstrap_db_if_empty::<LibraVM>(&db_rw, get_genesis_txn(&node_config).unwrap())
         .expect("Db-bootstrapper should not fail.");
 
@@ -96,6 +188,7 @@ pub fn setup_environment(node_config: &mut NodeConfig) -> LibraHandle {
         instant.elapsed().as_millis()
     );
     let mut network_runtimes = vec![];
+    let mut peer_reputations = vec![];
     let mut state_sync_network_handles = vec![];
     let mut mempool_network_handles = vec![];
     let mut consensus_network_handles = None;
@@ -165,6 +258,7 @@ pub fn setup_environment(node_config: &mut NodeConfig) -> LibraHandle {
         // Start the network and cache the runtime so it does not go out of scope.
         // TODO:  move all 'start' commands to a second phase at the end of setup_environment.  Target is to have one pass to wire the pieces together and a second pass to start processing in an appropriate order.
         let peer_id = network_builder.peer_id();
+        peer_reputations.push(network_builder.peer_reputations());
         let _listen_addr = network_builder.build();
         network_runtimes.push(runtime);
         debug!("Network started for peer_id: {}", peer_id);
@@ -231,7 +325,7 @@ pub fn setup_environment(node_config: &mut NodeConfig) -> LibraHandle {
         debug!("Consensus started in {} ms", instant.elapsed().as_millis());
     }
 
-    let debug_if = setup_debug_interface(&node_config);
+    let debug_if = setup_debug_interface(&node_config, peer_reputations);
 
     let metrics_port = node_config.debug_interface.metrics_server_port;
     let metric_host = node_config.debug_interface.address.clone();
@@ -6,7 +6,7 @@ use crate::core_mempool::transaction::{MempoolTransaction, TimelineState};
 use libra_types::account_address::AccountAddress;
 use std::{
     cmp::Ordering,
-    collections::{btree_set::Iter, BTreeMap, BTreeSet},
+    collections::{btree_set::Iter, BTreeMap, BTreeSet, HashMap},
     iter::Rev,
     ops::Bound,
     time::Duration,
@@ -278,6 +278,56 @@ impl ParkingLotIndex {
     }
 }
 
+/// AccountSizeIndex keeps track of the number of transactions of each account
+/// we keep separate index to be able to efficiently find the account holding the most
+/// transactions to evict from when Mempool is full
+pub struct AccountSizeIndex {
+    sizes: HashMap<AccountAddress, usize>,
+    data: BTreeSet<(usize, AccountAddress)>,
+}
+
+impl AccountSizeIndex {
+    pub(crate) fn new() -> Self {
+        Self {
+            sizes: HashMap::new(),
+            data: BTreeSet::new(),
+        }
+    }
+
+    /// add transaction to index
+    pub(crate) fn insert(&mut self, txn: &MempoolTransaction) {
+        self.update(txn.get_sender(), |size| size + 1);
+    }
+
+    /// remove transaction from index
+    pub(crate) fn remove(&mut self, txn: &MempoolTransaction) {
+        self.update(txn.get_sender(), |size| size.saturating_sub(1));
+    }
+
+    fn update(&mut self, address: AccountAddress, f: impl FnOnce(usize) -> usize) {
+        let size = self.sizes.remove(&address).unwrap_or(0);
+        self.data.remove(&(size, address));
+        let size = f(size);
+        if size > 0 {
+            self.sizes.insert(address, size);
+            self.data.insert((size, address));
+        }
+    }
+
+    /// returns the account holding the most transactions other than `excluded`, along with the
+    /// number of its transactions
+    pub(crate) fn largest_except(
+        &self,
+        excluded: &AccountAddress,
+    ) -> Option<(AccountAddress, usize)> {
+        self.data
+            .iter()
+            .rev()
+            .find(|(_, address)| address != excluded)
+            .map(|(size, address)| (*address, *size))
+    }
+}
+
 /// Logical pointer to `MempoolTransaction`
 /// Includes Account's address and transaction sequence number
 pub type TxnPointer = (AccountAddress, u64);
@@ -6,13 +6,14 @@
 use crate::{
     core_mempool::{
         index::TxnPointer,
-        transaction::{MempoolTransaction, TimelineState},
+        transaction::{transaction_hash, MempoolTransaction, TimelineState},
         transaction_store::TransactionStore,
         ttl_cache::TtlCache,
     },
-    OP_COUNTERS,
+    PendingTransaction, SubmissionStatus, TransactionQuery, TransactionStatus, OP_COUNTERS,
 };
 use libra_config::config::NodeConfig;
+use libra_crypto::HashValue;
 use libra_logger::prelude::*;
 use libra_trace::prelude::*;
 use libra_types::{
@@ -26,6 +27,9 @@ use std::{
     time::{Duration, SystemTime, UNIX_EPOCH},
 };
 
+/// How long the statuses of rejected transactions are kept around for clients to look up
+const REJECTED_TRANSACTION_TTL_SECS: u64 = 600;
+
 pub struct Mempool {
     // stores metadata of all transactions in mempool (of all states)
     transactions: TransactionStore,
@@ -36,6 +40,10 @@ pub struct Mempool {
     // used to measure e2e latency of transaction in system, as well as time it takes to pick it up
     // by consensus
     pub(crate) metrics_cache: TtlCache<(AccountAddress, u64), SystemTime>,
+    // statuses of recently rejected transactions, by hash
+    rejected_transactions: TtlCache<HashValue, SubmissionStatus>,
+    // hash of the latest rejected transaction for each sender and sequence number
+    rejected_transaction_hashes: TtlCache<TxnPointer, HashValue>,
     pub system_transaction_timeout: Duration,
 }
 
@@ -45,6 +53,14 @@ impl Mempool {
             transactions: TransactionStore::new(&config.mempool),
             sequence_number_cache: TtlCache::new(config.mempool.capacity, Duration::from_secs(100)),
             metrics_cache: TtlCache::new(config.mempool.capacity, Duration::from_secs(100)),
+            rejected_transactions: TtlCache::new(
+                config.mempool.capacity,
+                Duration::from_secs(REJECTED_TRANSACTION_TTL_SECS),
+            ),
+            rejected_transaction_hashes: TtlCache::new(
+                config.mempool.capacity,
+                Duration::from_secs(REJECTED_TRANSACTION_TTL_SECS),
+            ),
             system_transaction_timeout: Duration::from_secs(
                 config.mempool.system_transaction_timeout_secs,
             ),
@@ -143,7 +159,7 @@ impl Mempool {
         }
 
         let txn_info = MempoolTransaction::new(
-            txn,
+            txn.clone(),
             expiration_time,
             gas_amount,
             rankin_score,
@@ -153,9 +169,47 @@ impl Mempool {
 
         let status = self.transactions.insert(txn_info, sequence_number);
         OP_COUNTERS.inc(&format!("insert.{:?}", status));
+        if status.code != MempoolStatusCode::Accepted {
+            self.record_rejection(&txn, (status.clone(), None));
+        }
         status
     }
 
+    /// Keeps the status a transaction was rejected with on submission around for a while, for
+    /// clients to look up
+    pub(crate) fn record_rejection(&mut self, txn: &SignedTransaction, status: SubmissionStatus) {
+        let hash = transaction_hash(txn);
+        self.rejected_transactions.insert(hash, status);
+        self.rejected_transaction_hashes
+            .insert((txn.sender(), txn.sequence_number()), hash);
+    }
+
+    /// Removes a transaction that failed validation while in mempool, e.g. after a
+    /// reconfiguration, recording `status` as the reason it was rejected
+    /// Does nothing if the transaction was meanwhile replaced or removed
+    pub(crate) fn remove_invalid_transaction(
+        &mut self,
+        txn: &SignedTransaction,
+        status: SubmissionStatus,
+    ) {
+        let (sender, sequence_number) = (txn.sender(), txn.sequence_number());
+        let is_pending = self
+            .transactions
+            .get_mempool_txn(&sender, sequence_number)
+            .map_or(false, |mempool_txn| mempool_txn.txn == *txn);
+        if !is_pending {
+            return;
+        }
+        debug!(
+            "[Mempool] transaction is no longer valid: {}:{}, status: {:?}",
+            sender, sequence_number, status
+        );
+        self.metrics_cache.remove(&(sender, sequence_number));
+        self.transactions
+            .remove_invalid_transaction(&sender, sequence_number);
+        self.record_rejection(txn, status);
+    }
+
     /// Fetches next block of transactions for consensus
     /// `batch_size` - size of requested block
     /// `seen_txns` - transactions that were sent to Consensus but were not committed yet
@@ -229,6 +283,52 @@ impl Mempool {
         block
     }
 
+    /// Fetches the transaction sent by `sender` with `sequence_number`, if it's in mempool
+    pub(crate) fn get_pending_transaction(
+        &self,
+        sender: &AccountAddress,
+        sequence_number: u64,
+    ) -> Option<PendingTransaction> {
+        self.transactions
+            .get_mempool_txn(sender, sequence_number)
+            .map(pending_transaction)
+    }
+
+    /// Fetches the status of a transaction, if it's in mempool or was recently rejected
+    /// A transaction in mempool takes precedence over a rejected one with the same sender and
+    /// sequence number
+    pub(crate) fn get_transaction_status(
+        &self,
+        query: TransactionQuery,
+    ) -> Option<TransactionStatus> {
+        let (pending_txn, rejected_hash) = match query {
+            TransactionQuery::BySequenceNumber(sender, sequence_number) => (
+                self.transactions.get_mempool_txn(&sender, sequence_number),
+                self.rejected_transaction_hashes
+                    .get(&(sender, sequence_number))
+                    .cloned(),
+            ),
+            TransactionQuery::ByHash(hash) => {
+                (self.transactions.get_mempool_txn_by_hash(&hash), Some(hash))
+            }
+        };
+        if let Some(txn) = pending_txn {
+            let pending = pending_transaction(txn);
+            return Some(if !self.transactions.is_ready(txn) {
+                TransactionStatus::WaitingOnSequenceGap(pending)
+            } else if let TimelineState::Ready(_) = txn.timeline_state {
+                TransactionStatus::ReadyForBroadcast(pending)
+            } else {
+                TransactionStatus::Validated(pending)
+            });
+        }
+        rejected_hash.and_then(|hash| {
+            self.rejected_transactions
+                .get(&hash)
+                .map(|status| TransactionStatus::Rejected(hash, status.clone()))
+        })
+    }
+
     /// periodic core mempool garbage collection
     /// removes all expired transactions
     /// clears expired entries in metrics cache and sequence number cache
@@ -237,6 +337,8 @@ impl Mempool {
         self.transactions.gc_by_system_ttl();
         self.metrics_cache.gc(now);
         self.sequence_number_cache.gc(now);
+        self.rejected_transactions.gc(now);
+        self.rejected_transaction_hashes.gc(now);
     }
 
     /// Garbage collection based on client-specified expiration time
@@ -244,6 +346,11 @@ impl Mempool {
         self.transactions.gc_by_expiration_time(block_time);
     }
 
+    /// Read all transactions in mempool, e.g. to re-validate them
+    pub(crate) fn get_all_transactions(&self) -> Vec<SignedTransaction> {
+        self.transactions.get_all_transactions()
+    }
+
     /// Read `count` transactions from timeline since `timeline_id`
     /// Returns block of transactions and new last_timeline_id
     pub(crate) fn read_timeline(
@@ -251,6 +358,19 @@ impl Mempool {
         timeline_id: u64,
         count: usize,
     ) -> (Vec<(u64, SignedTransaction)>, u64) {
+        let (batch, last_timeline_id) = self.read_timeline_with_metadata(timeline_id, count);
+        (
+            batch.into_iter().map(|(id, txn)| (id, txn.txn)).collect(),
+            last_timeline_id,
+        )
+    }
+
+    /// Like `read_timeline`, with the mempool metadata of the transactions
+    pub(crate) fn read_timeline_with_metadata(
+        &mut self,
+        timeline_id: u64,
+        count: usize,
+    ) -> (Vec<(u64, MempoolTransaction)>, u64) {
         self.transactions.read_timeline(timeline_id, count)
     }
 
@@ -260,6 +380,25 @@ impl Mempool {
         &mut self,
         timeline_ids: Vec<u64>,
     ) -> Vec<(u64, SignedTransaction)> {
+        self.filter_read_timeline_with_metadata(timeline_ids)
+            .into_iter()
+            .map(|(id, txn)| (id, txn.txn))
+            .collect()
+    }
+
+    /// Like `filter_read_timeline`, with the mempool metadata of the transactions
+    pub(crate) fn filter_read_timeline_with_metadata(
+        &mut self,
+        timeline_ids: Vec<u64>,
+    ) -> Vec<(u64, MempoolTransaction)> {
         self.transactions.filter_read_timeline(timeline_ids)
     }
 }
+
+fn pending_transaction(txn: &MempoolTransaction) -> PendingTransaction {
+    PendingTransaction {
+        txn: txn.txn.clone(),
+        ranking_score: txn.ranking_score,
+        expiration_time: txn.expiration_time,
+    }
+}
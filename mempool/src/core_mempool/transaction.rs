@@ -1,7 +1,11 @@
 // Copyright (c) The Libra Core Contributors
 // SPDX-License-Identifier: Apache-2.0
 
-use libra_types::{account_address::AccountAddress, transaction::SignedTransaction};
+use libra_crypto::{hash::CryptoHash, HashValue};
+use libra_types::{
+    account_address::AccountAddress,
+    transaction::{SignedTransaction, Transaction},
+};
 use std::time::Duration;
 
 #[derive(Clone)]
@@ -13,6 +17,10 @@ pub struct MempoolTransaction {
     pub ranking_score: u64,
     pub timeline_state: TimelineState,
     pub is_governance_txn: bool,
+    // size of the raw transaction, counted against Mempool's byte budget
+    pub size_bytes: usize,
+    // hash the transaction is committed under
+    pub hash: HashValue,
 }
 
 impl MempoolTransaction {
@@ -24,6 +32,8 @@ impl MempoolTransaction {
         timeline_state: TimelineState,
         is_governance_txn: bool,
     ) -> Self {
+        let size_bytes = txn.raw_txn_bytes_len();
+        let hash = transaction_hash(&txn);
         Self {
             txn,
             gas_amount,
@@ -31,6 +41,8 @@ impl MempoolTransaction {
             expiration_time,
             timeline_state,
             is_governance_txn,
+            size_bytes,
+            hash,
         }
     }
     pub(crate) fn get_sequence_number(&self) -> u64 {
@@ -44,6 +56,11 @@ impl MempoolTransaction {
     }
 }
 
+/// hash of a user transaction, as it is committed under
+pub(crate) fn transaction_hash(txn: &SignedTransaction) -> HashValue {
+    Transaction::UserTransaction(txn.clone()).hash()
+}
+
 #[derive(Clone, Copy, PartialEq, Eq, Debug, Hash)]
 pub enum TimelineState {
     // transaction is ready for broadcast
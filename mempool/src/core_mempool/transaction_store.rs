@@ -4,15 +4,15 @@
 use crate::{
     core_mempool::{
         index::{
-            AccountTransactions, ParkingLotIndex, PriorityIndex, PriorityQueueIter, TTLIndex,
-            TimelineIndex,
+            AccountSizeIndex, AccountTransactions, ParkingLotIndex, PriorityIndex,
+            PriorityQueueIter, TTLIndex, TimelineIndex, TxnPointer,
         },
         transaction::{MempoolTransaction, TimelineState},
     },
-    OP_COUNTERS,
+    counters, OP_COUNTERS,
 };
-use anyhow::{format_err, Result};
 use libra_config::config::MempoolConfig;
+use libra_crypto::HashValue;
 use libra_logger::prelude::*;
 use libra_types::{
     account_address::AccountAddress,
@@ -41,10 +41,18 @@ pub struct TransactionStore {
     timeline_index: TimelineIndex,
     // keeps track of "non-ready" txns (transactions that can't be included in next block)
     parking_lot_index: ParkingLotIndex,
+    // transactions by hash
+    hash_index: HashMap<HashValue, TxnPointer>,
+    // accounts by number of transactions
+    account_size_index: AccountSizeIndex,
+    // total size of all transactions in storage
+    size_bytes: usize,
 
     // configuration
     capacity: usize,
+    capacity_bytes: usize,
     capacity_per_user: usize,
+    min_gas_price_bump_percentage: u64,
 }
 
 impl TransactionStore {
@@ -61,10 +69,15 @@ impl TransactionStore {
             priority_index: PriorityIndex::new(),
             timeline_index: TimelineIndex::new(),
             parking_lot_index: ParkingLotIndex::new(),
+            hash_index: HashMap::new(),
+            account_size_index: AccountSizeIndex::new(),
+            size_bytes: 0,
 
             // configuration
             capacity: config.capacity,
+            capacity_bytes: config.capacity_bytes,
             capacity_per_user: config.capacity_per_user,
+            min_gas_price_bump_percentage: config.min_gas_price_bump_percentage,
         }
     }
 
@@ -74,14 +87,31 @@ impl TransactionStore {
         address: &AccountAddress,
         sequence_number: u64,
     ) -> Option<SignedTransaction> {
-        if let Some(txn) = self
-            .transactions
+        self.get_mempool_txn(address, sequence_number)
+            .map(|txn| txn.txn.clone())
+    }
+
+    /// fetch transaction, along with its mempool metadata, by account address + sequence_number
+    pub(crate) fn get_mempool_txn(
+        &self,
+        address: &AccountAddress,
+        sequence_number: u64,
+    ) -> Option<&MempoolTransaction> {
+        self.transactions
             .get(&address)
             .and_then(|txns| txns.get(&sequence_number))
-        {
-            return Some(txn.txn.clone());
-        }
-        None
+    }
+
+    /// fetch transaction, along with its mempool metadata, by hash
+    pub(crate) fn get_mempool_txn_by_hash(&self, hash: &HashValue) -> Option<&MempoolTransaction> {
+        self.hash_index
+            .get(hash)
+            .and_then(|(address, sequence_number)| self.get_mempool_txn(address, *sequence_number))
+    }
+
+    /// checks if transaction is ready to be included in next block
+    pub(crate) fn is_ready(&self, txn: &MempoolTransaction) -> bool {
+        self.priority_index.contains(txn)
     }
 
     /// insert transaction into TransactionStore
@@ -91,48 +121,47 @@ impl TransactionStore {
         txn: MempoolTransaction,
         current_sequence_number: u64,
     ) -> MempoolStatus {
-        if self.handle_gas_price_update(&txn).is_err() {
-            return MempoolStatus::new(MempoolStatusCode::InvalidUpdate).with_message(format!(
-                "Failed to update gas price to {}",
-                txn.get_gas_price()
-            ));
+        if let Err(status) = self.handle_gas_price_update(&txn) {
+            return status;
+        }
+
+        let address = txn.get_sender();
+        let sequence_number = txn.get_sequence_number();
+
+        self.clean_committed_transactions(&address, current_sequence_number);
+
+        // capacity check, before anything is evicted to make room for the transaction
+        let txns_len = self.transactions.get(&address).map_or(0, |txns| txns.len());
+        if txns_len >= self.capacity_per_user {
+            return MempoolStatus::new(MempoolStatusCode::TooManyTransactions).with_message(
+                format!(
+                    "txns length: {} capacity per user: {}",
+                    txns_len, self.capacity_per_user,
+                ),
+            );
         }
 
         if self.check_if_full(&txn, current_sequence_number) {
             return MempoolStatus::new(MempoolStatusCode::MempoolIsFull).with_message(format!(
-                "mempool size: {}, capacity: {}",
+                "mempool size: {}, capacity: {}, size in bytes: {}, capacity in bytes: {}",
                 self.system_ttl_index.size(),
                 self.capacity,
+                self.size_bytes,
+                self.capacity_bytes,
             ));
         }
 
-        let address = txn.get_sender();
-        let sequence_number = txn.get_sequence_number();
-
+        // insert into storage and other indexes
+        self.system_ttl_index.insert(&txn);
+        self.expiration_time_index.insert(&txn);
+        self.account_size_index.insert(&txn);
+        self.size_bytes += txn.size_bytes;
+        self.hash_index.insert(txn.hash, (address, sequence_number));
         self.transactions
             .entry(address)
-            .or_insert_with(AccountTransactions::new);
-
-        self.clean_committed_transactions(&address, current_sequence_number);
-
-        if let Some(txns) = self.transactions.get_mut(&address) {
-            // capacity check
-            if txns.len() >= self.capacity_per_user {
-                return MempoolStatus::new(MempoolStatusCode::TooManyTransactions).with_message(
-                    format!(
-                        "txns length: {} capacity per user: {}",
-                        txns.len(),
-                        self.capacity_per_user,
-                    ),
-                );
-            }
-
-            // insert into storage and other indexes
-            self.system_ttl_index.insert(&txn);
-            self.expiration_time_index.insert(&txn);
-            txns.insert(sequence_number, txn);
-            self.track_indices();
-        }
+            .or_insert_with(AccountTransactions::new)
+            .insert(sequence_number, txn);
+        self.track_indices();
         self.process_ready_transactions(&address, current_sequence_number);
         MempoolStatus::new(MempoolStatusCode::Accepted)
     }
@@ -141,27 +170,79 @@ impl TransactionStore {
         OP_COUNTERS.set("txn.system_ttl_index", self.system_ttl_index.size());
         OP_COUNTERS.set("txn.parking_lot_index", self.parking_lot_index.size());
         OP_COUNTERS.set("txn.priority_index", self.priority_index.size());
+        OP_COUNTERS.set("txn.size_bytes", self.size_bytes);
     }
 
     /// checks if Mempool is full
-    /// If it's full, tries to free some space by evicting transactions from ParkingLot
+    /// If it's full, tries to free some space by evicting transactions from ParkingLot first,
+    /// then the latest transactions of the account holding the most transactions
     /// We only evict on attempt to insert a transaction that would be ready for broadcast upon insertion
     fn check_if_full(&mut self, txn: &MempoolTransaction, curr_sequence_number: u64) -> bool {
-        if self.system_ttl_index.size() >= self.capacity
-            && self.check_txn_ready(txn, curr_sequence_number)
-        {
-            // try to free some space in Mempool from ParkingLot
-            if let Some((address, sequence_number)) = self.parking_lot_index.pop() {
-                if let Some(txn) = self
-                    .transactions
-                    .get_mut(&address)
-                    .and_then(|txns| txns.remove(&sequence_number))
+        if self.is_full(txn) && self.check_txn_ready(txn, curr_sequence_number) {
+            while self.is_full(txn) {
+                if !self.evict_from_parking_lot()
+                    && !self.evict_from_largest_account(&txn.get_sender())
                 {
-                    self.index_remove(&txn);
+                    break;
                 }
             }
         }
+        self.is_full(txn)
+    }
+
+    /// checks if there is no room for `txn` either by number of transactions or by size
+    fn is_full(&self, txn: &MempoolTransaction) -> bool {
         self.system_ttl_index.size() >= self.capacity
+            || self.size_bytes + txn.size_bytes > self.capacity_bytes
+    }
+
+    /// evicts a non-ready transaction, returns false if ParkingLot is empty
+    fn evict_from_parking_lot(&mut self) -> bool {
+        while let Some((address, sequence_number)) = self.parking_lot_index.pop() {
+            if let Some(txn) = self
+                .transactions
+                .get_mut(&address)
+                .and_then(|txns| txns.remove(&sequence_number))
+            {
+                counters::CORE_MEMPOOL_EVICTIONS
+                    .with_label_values(&["parking_lot"])
+                    .inc();
+                self.index_remove(&txn);
+                return true;
+            }
+        }
+        false
+    }
+
+    /// evicts the transaction with the highest sequence number of the account holding the most
+    /// transactions, as long as that account would still hold more transactions than `sender`
+    /// after the insertion. Returns false if there is no such account
+    fn evict_from_largest_account(&mut self, sender: &AccountAddress) -> bool {
+        let sender_len = self.transactions.get(sender).map_or(0, |txns| txns.len());
+        let largest = self
+            .account_size_index
+            .largest_except(sender)
+            .filter(|(_, len)| *len > sender_len + 1)
+            .and_then(|(address, _)| {
+                self.transactions
+                    .get(&address)
+                    .and_then(|txns| txns.keys().next_back())
+                    .map(|sequence_number| (address, *sequence_number))
+            });
+        if let Some((address, sequence_number)) = largest {
+            if let Some(txn) = self
+                .transactions
+                .get_mut(&address)
+                .and_then(|txns| txns.remove(&sequence_number))
+            {
+                counters::CORE_MEMPOOL_EVICTIONS
+                    .with_label_values(&["largest_account"])
+                    .inc();
+                self.index_remove(&txn);
+                return true;
+            }
+        }
+        false
     }
 
     /// check if a transaction would be ready for broadcast in mempool upon insertion (without inserting it)
@@ -192,22 +273,38 @@ impl TransactionStore {
 
     /// check if transaction is already present in Mempool
     /// e.g. given request is update
-    /// we allow increase in gas price to speed up process
-    fn handle_gas_price_update(&mut self, txn: &MempoolTransaction) -> Result<()> {
+    /// we allow increase in gas price by at least `min_gas_price_bump_percentage` to speed up process
+    fn handle_gas_price_update(&mut self, txn: &MempoolTransaction) -> Result<(), MempoolStatus> {
         if let Some(txns) = self.transactions.get_mut(&txn.get_sender()) {
             if let Some(current_version) = txns.get_mut(&txn.get_sequence_number()) {
-                if current_version.txn.max_gas_amount() == txn.txn.max_gas_amount()
-                    && current_version.txn.payload() == txn.txn.payload()
-                    && current_version.txn.expiration_time() == txn.txn.expiration_time()
-                    && current_version.get_gas_price() < txn.get_gas_price()
+                if current_version.txn.max_gas_amount() != txn.txn.max_gas_amount()
+                    || current_version.txn.payload() != txn.txn.payload()
+                    || current_version.txn.expiration_time() != txn.txn.expiration_time()
                 {
-                    if let Some(txn) = txns.remove(&txn.get_sequence_number()) {
-                        self.index_remove(&txn);
-                    }
-                } else {
-                    return Err(format_err!("Invalid gas price update. txn gas price: {}, current_version gas price: {}",
+                    return Err(
+                        MempoolStatus::new(MempoolStatusCode::InvalidUpdate).with_message(
+                            "Only gas price of a pending transaction can be updated".to_string(),
+                        ),
+                    );
+                }
+                let min_gas_price = min_replacement_gas_price(
+                    current_version.get_gas_price(),
+                    self.min_gas_price_bump_percentage,
+                );
+                if txn.get_gas_price() < min_gas_price {
+                    return Err(MempoolStatus::new(MempoolStatusCode::GasPriceBumpTooLow)
+                        .with_message(format!(
+                            "txn gas price: {}, current_version gas price: {}, min gas price for update: {}",
                             txn.get_gas_price(),
-                            current_version.get_gas_price()));
+                            current_version.get_gas_price(),
+                            min_gas_price,
+                        )));
+                }
+                if let Some(txn) = txns.remove(&txn.get_sequence_number()) {
+                    counters::CORE_MEMPOOL_EVICTIONS
+                        .with_label_values(&["replaced"])
+                        .inc();
+                    self.index_remove(&txn);
                 }
             }
         }
@@ -280,6 +377,28 @@ impl TransactionStore {
         self.process_ready_transactions(account, account_sequence_number);
     }
 
+    /// removes a transaction that's no longer valid, e.g. after a reconfiguration
+    /// all following transactions of the account are marked as non-ready
+    pub(crate) fn remove_invalid_transaction(
+        &mut self,
+        account: &AccountAddress,
+        sequence_number: u64,
+    ) {
+        if let Some(txns) = self.transactions.get_mut(&account) {
+            for (_, t) in txns.range((Bound::Excluded(sequence_number), Bound::Unbounded)) {
+                self.parking_lot_index.insert(&t);
+                self.priority_index.remove(&t);
+                self.timeline_index.remove(&t);
+            }
+            if let Some(txn) = txns.remove(&sequence_number) {
+                counters::CORE_MEMPOOL_EVICTIONS
+                    .with_label_values(&["revalidation"])
+                    .inc();
+                self.index_remove(&txn);
+            }
+        }
+    }
+
     pub(crate) fn reject_transaction(&mut self, account: &AccountAddress, _sequence_number: u64) {
         if let Some(txns) = self.transactions.remove(&account) {
             for transaction in txns.values() {
@@ -295,16 +414,19 @@ impl TransactionStore {
         self.priority_index.remove(&txn);
         self.timeline_index.remove(&txn);
         self.parking_lot_index.remove(&txn);
+        self.account_size_index.remove(&txn);
+        self.hash_index.remove(&txn.hash);
+        self.size_bytes -= txn.size_bytes;
         self.track_indices();
     }
 
-    /// Read `count` transactions from timeline since `timeline_id`
+    /// Read `count` transactions, along with their mempool metadata, from timeline since `timeline_id`
     /// Returns block of transactions and new last_timeline_id
     pub(crate) fn read_timeline(
         &mut self,
         timeline_id: u64,
         count: usize,
-    ) -> (Vec<(u64, SignedTransaction)>, u64) {
+    ) -> (Vec<(u64, MempoolTransaction)>, u64) {
         let mut batch = vec![];
         let mut last_timeline_id = timeline_id;
         for (id, (address, sequence_number)) in
@@ -315,7 +437,7 @@ impl TransactionStore {
                 .get_mut(&address)
                 .and_then(|txns| txns.get(&sequence_number))
             {
-                batch.push((id, txn.txn.clone()));
+                batch.push((id, txn.clone()));
                 if let TimelineState::Ready(timeline_id) = txn.timeline_state {
                     last_timeline_id = timeline_id;
                 }
@@ -324,12 +446,12 @@ impl TransactionStore {
         (batch, last_timeline_id)
     }
 
-    /// Returns transactions with timeline ID in `timeline_ids`
+    /// Returns transactions, along with their mempool metadata, with timeline ID in `timeline_ids`
     /// as list of (timeline_id, transaction)
     pub(crate) fn filter_read_timeline(
         &mut self,
         timeline_ids: Vec<u64>,
-    ) -> Vec<(u64, SignedTransaction)> {
+    ) -> Vec<(u64, MempoolTransaction)> {
         timeline_ids
             .into_iter()
             .filter_map(|timeline_id| {
@@ -341,7 +463,7 @@ impl TransactionStore {
                         .get_mut(&address)
                         .and_then(|txns| txns.get(&sequence_number))
                     {
-                        return Some((timeline_id, txn.txn.clone()));
+                        return Some((timeline_id, txn.clone()));
                     }
                 }
                 None
@@ -364,10 +486,18 @@ impl TransactionStore {
     }
 
     fn gc(&mut self, now: Duration, by_system_ttl: bool) {
-        let (index_name, index) = if by_system_ttl {
-            ("gc.system_ttl_index", &mut self.system_ttl_index)
+        let (index_name, reason, index) = if by_system_ttl {
+            (
+                "gc.system_ttl_index",
+                "system_ttl",
+                &mut self.system_ttl_index,
+            )
         } else {
-            ("gc.expiration_time_index", &mut self.expiration_time_index)
+            (
+                "gc.expiration_time_index",
+                "expiration_time",
+                &mut self.expiration_time_index,
+            )
         };
         OP_COUNTERS.inc(index_name);
 
@@ -383,6 +513,9 @@ impl TransactionStore {
                     let is_active = self.priority_index.contains(&txn);
                     let status = if is_active { "active" } else { "parked" };
                     OP_COUNTERS.inc(&format!("{}.{}", index_name, status));
+                    counters::CORE_MEMPOOL_EVICTIONS
+                        .with_label_values(&[reason])
+                        .inc();
                     self.index_remove(&txn);
                 }
             }
@@ -390,7 +523,24 @@ impl TransactionStore {
         self.track_indices();
     }
 
+    /// Read all transactions in mempool, ready or not
+    pub(crate) fn get_all_transactions(&self) -> Vec<SignedTransaction> {
+        self.transactions
+            .values()
+            .flat_map(|txns| txns.values())
+            .map(|txn| txn.txn.clone())
+            .collect()
+    }
+
     pub(crate) fn iter_queue(&self) -> PriorityQueueIter {
         self.priority_index.iter()
     }
 }
+
+/// lowest gas price a pending transaction at `gas_price` can be replaced at: higher by at least
+/// `bump_percentage`, rounded up, and by at least one
+fn min_replacement_gas_price(gas_price: u64, bump_percentage: u64) -> u64 {
+    let bumped = (u128::from(gas_price) * (100 + u128::from(bump_percentage)) + 99) / 100;
+    let min_gas_price = std::cmp::max(bumped, u128::from(gas_price) + 1);
+    std::cmp::min(min_gas_price, u128::from(u64::max_value())) as u64
+}
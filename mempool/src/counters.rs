@@ -29,6 +29,18 @@ pub static MEMPOOL_SERVICE: Lazy<IntCounterVec> = Lazy::new(|| {
     .unwrap()
 });
 
+/// Counter of transactions evicted from core mempool before being committed
+pub static CORE_MEMPOOL_EVICTIONS: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "libra_core_mempool_evictions",
+        "Number of transactions evicted from core mempool",
+        // reason of the eviction: "parking_lot", "largest_account", "system_ttl", "expiration_time",
+        // "replaced", "revalidation"
+        &["reason"]
+    )
+    .unwrap()
+});
+
 pub static SHARED_MEMPOOL_EVENTS: Lazy<IntCounterVec> = Lazy::new(|| {
     register_int_counter_vec!(
         "libra_shared_mempool_events",
@@ -38,6 +50,17 @@ pub static SHARED_MEMPOOL_EVENTS: Lazy<IntCounterVec> = Lazy::new(|| {
     .unwrap()
 });
 
+/// Counter of transactions re-validated by shared mempool upon reconfiguration
+pub static SHARED_MEMPOOL_REVALIDATED_TXNS: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "libra_shared_mempool_revalidated_txns",
+        "Number of transactions in mempool re-validated upon reconfiguration",
+        // outcome of the re-validation: "valid", "invalid"
+        &["status"]
+    )
+    .unwrap()
+});
+
 pub static SHARED_MEMPOOL_TRANSACTIONS_PROCESSED: Lazy<IntCounterVec> = Lazy::new(|| {
     register_int_counter_vec!(
         "libra_shared_mempool_transactions_processed",
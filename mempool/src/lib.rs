@@ -63,8 +63,9 @@ pub use shared_mempool::{
     bootstrap, network,
     types::{
         gen_mempool_reconfig_subscription, CommitNotification, CommitResponse,
-        CommittedTransaction, ConsensusRequest, ConsensusResponse, MempoolClientSender,
-        SubmissionStatus, TransactionExclusion,
+        CommittedTransaction, ConsensusRequest, ConsensusResponse, MempoolClientRequest,
+        MempoolClientSender, PendingTransaction, SubmissionStatus, TransactionExclusion,
+        TransactionQuery, TransactionStatus,
     },
 };
 #[cfg(feature = "fuzzing")]
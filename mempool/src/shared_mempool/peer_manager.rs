@@ -11,6 +11,7 @@ use std::{
     collections::{BTreeSet, HashMap, HashSet},
     ops::{Deref, DerefMut},
     sync::Mutex,
+    time::{Duration, Instant},
 };
 
 /// stores only peers that receive txns from this node
@@ -36,10 +37,13 @@ pub(crate) struct PeerManager {
 
 #[derive(Clone)]
 pub struct BroadcastInfo {
-    // broadcasts that have not been ACK'ed for yet
-    pub sent_batches: HashMap<String, Vec<u64>>,
+    // broadcasts that have not been ACK'ed for yet, along with when they were sent
+    pub sent_batches: HashMap<String, (Vec<u64>, Instant)>,
     // timeline IDs of all txns that need to be retried and ACKed for
     pub total_retry_txns: BTreeSet<u64>,
+    // timeline IDs of txns read from timeline but not broadcast yet, in favor of txns with higher
+    // ranking score
+    pub deferred_txns: BTreeSet<u64>,
     // whether broadcasts are in backoff/backpressure mode, e.g. broadcasting at longer intervals
     pub backoff_mode: bool,
 }
@@ -49,6 +53,7 @@ impl BroadcastInfo {
         Self {
             sent_batches: HashMap::new(),
             total_retry_txns: BTreeSet::new(),
+            deferred_txns: BTreeSet::new(),
             backoff_mode: false,
         }
     }
@@ -187,6 +192,8 @@ impl PeerManager {
         batch_id: String,
         // timeline IDs of txns broadcasted
         batch: Vec<u64>,
+        // timeline IDs of txns left for a later broadcast
+        deferred_txns: BTreeSet<u64>,
         // the new timeline ID to read from for next broadcast
         timeline_id: u64,
         // timeline ID of first txn in timeline, used to remove potentially expired retry_txns
@@ -201,10 +208,10 @@ impl PeerManager {
         sync_state
             .broadcast_info
             .sent_batches
-            .insert(batch_id, batch);
+            .insert(batch_id, (batch, Instant::now()));
         sync_state.timeline_id = std::cmp::max(sync_state.timeline_id, timeline_id);
 
-        // clean up expired retriable txns
+        // clean up expired retriable and deferred txns
         let gc_retry_txns = sync_state
             .broadcast_info
             .total_retry_txns
@@ -214,6 +221,34 @@ impl PeerManager {
             .collect::<BTreeSet<_>>();
 
         sync_state.broadcast_info.total_retry_txns = gc_retry_txns;
+        sync_state.broadcast_info.deferred_txns = deferred_txns
+            .into_iter()
+            .filter(|x| *x >= earliest_timeline_id)
+            .collect();
+    }
+
+    // treats broadcasts to `peer` that have not been ACK'ed for within `ack_timeout` as lost,
+    // so that their txns are retried and they don't hold up the backpressure window
+    pub fn expire_broadcasts(&self, peer: &PeerNetworkId, ack_timeout: Duration) {
+        let mut peer_info = self
+            .peer_info
+            .lock()
+            .expect("failed to acquire peer_info lock");
+
+        if let Some(sync_state) = peer_info.get_mut(peer) {
+            let broadcast_info = &mut sync_state.broadcast_info;
+            let expired_batches = broadcast_info
+                .sent_batches
+                .iter()
+                .filter(|(_, (_, sent_at))| sent_at.elapsed() >= ack_timeout)
+                .map(|(batch_id, _)| batch_id.clone())
+                .collect::<Vec<_>>();
+            for batch_id in expired_batches {
+                if let Some((batch, _)) = broadcast_info.sent_batches.remove(&batch_id) {
+                    broadcast_info.total_retry_txns.extend(batch);
+                }
+            }
+        }
     }
 
     pub fn process_broadcast_ack(
@@ -230,7 +265,7 @@ impl PeerManager {
 
         let sync_state = peer_info.get_mut(&peer).expect("missing peer sync state");
 
-        if let Some(batch) = sync_state.broadcast_info.sent_batches.remove(&batch_id) {
+        if let Some((batch, _)) = sync_state.broadcast_info.sent_batches.remove(&batch_id) {
             // convert retry_txns from index within a batch to actual timeline ID of txn
             let retry_timeline_ids = retry_txns
                 .iter()
@@ -11,13 +11,14 @@ use crate::{
         notify_subscribers, ScheduledBroadcast, SharedMempool, SharedMempoolNotification,
     },
     CommitNotification, CommitResponse, CommittedTransaction, ConsensusRequest, ConsensusResponse,
-    SubmissionStatus,
+    PendingTransaction, SubmissionStatus, TransactionQuery, TransactionStatus,
 };
 use anyhow::{format_err, Result};
 use futures::{channel::oneshot, stream::FuturesUnordered};
 use libra_config::config::PeerNetworkId;
 use libra_logger::prelude::*;
 use libra_types::{
+    account_address::AccountAddress,
     mempool_status::{MempoolStatus, MempoolStatusCode},
     on_chain_config::OnChainConfigPayload,
     transaction::SignedTransaction,
@@ -28,10 +29,10 @@ use libra_types::{
     PeerId,
 };
 use std::{
-    cmp,
-    collections::HashSet,
+    cmp::{self, Reverse},
+    collections::{BTreeSet, HashSet},
     ops::Deref,
-    sync::{Arc, Mutex, RwLock},
+    sync::{Arc, Mutex},
     time::{Duration, Instant, SystemTime, UNIX_EPOCH},
 };
 use tokio::runtime::Handle;
@@ -41,6 +42,9 @@ use vm_validator::vm_validator::{get_account_sequence_number, TransactionValidat
 //  broadcast_coordinator tasks  //
 // ============================== //
 
+/// number of txns, relative to the batch size, that are ranked against each other for a broadcast
+const BROADCAST_SCAN_FACTOR: usize = 10;
+
 /// attempts broadcast to `peer` and schedules the next broadcast
 pub(crate) fn execute_broadcast<V>(
     peer: PeerNetworkId,
@@ -75,10 +79,21 @@ where
 {
     let peer_manager = &smp.peer_manager;
 
-    let (timeline_id, retry_txns_id, next_backoff) = if peer_manager.is_picked_peer(&peer) {
-        let state = peer_manager.get_peer_state(&peer);
-        let next_backoff = state.broadcast_info.backoff_mode;
-        if state.is_alive {
+    let (timeline_id, retry_txns_id, deferred_txns_id, next_backoff) =
+        if peer_manager.is_picked_peer(&peer) {
+            peer_manager.expire_broadcasts(
+                &peer,
+                Duration::from_millis(smp.config.shared_mempool_ack_timeout_ms),
+            );
+            let state = peer_manager.get_peer_state(&peer);
+            let next_backoff = state.broadcast_info.backoff_mode;
+            if !state.is_alive
+                // hold off until the peer ACKs some of the broadcasts sent to it so far
+                || state.broadcast_info.sent_batches.len()
+                    >= smp.config.shared_mempool_backpressure_window
+            {
+                return next_backoff;
+            }
             (
                 state.timeline_id,
                 state
@@ -86,14 +101,16 @@ where
                     .total_retry_txns
                     .into_iter()
                     .collect::<Vec<_>>(),
+                state
+                    .broadcast_info
+                    .deferred_txns
+                    .into_iter()
+                    .collect::<Vec<_>>(),
                 next_backoff,
             )
         } else {
-            return next_backoff;
-        }
-    } else {
-        return false;
-    };
+            return false;
+        };
 
     // It is possible that a broadcast was scheduled as non-backoff before an ACK received after the
     // broadcast scheduling turns on backoff mode
@@ -108,19 +125,19 @@ where
         .lock()
         .expect("[shared mempool] failed to acquire mempool lock");
 
-    // first populate batch with retriable txns, to prioritize resending them
-    let retry_txns = mempool.filter_read_timeline(retry_txns_id);
-    // pad the batch with new txns from fresh timeline read, if batch has space
-    let (new_txns, new_timeline_id) = if retry_txns.len() < smp.config.shared_mempool_batch_size {
-        mempool.read_timeline(
-            timeline_id,
-            smp.config.shared_mempool_batch_size - retry_txns.len(),
-        )
+    // retriable txns are prioritized for resending
+    let retry_txns = mempool.filter_read_timeline_with_metadata(retry_txns_id);
+    // the rest of the batch is picked by ranking score from txns deferred by previous broadcasts
+    // and from a fresh timeline read, unless enough txns are deferred already
+    let deferred_txns = mempool.filter_read_timeline_with_metadata(deferred_txns_id);
+    let scan_size = smp.config.shared_mempool_batch_size * BROADCAST_SCAN_FACTOR;
+    let (new_txns, new_timeline_id) = if deferred_txns.len() < scan_size {
+        mempool.read_timeline_with_metadata(timeline_id, scan_size - deferred_txns.len())
     } else {
         (vec![], timeline_id)
     };
 
-    if new_txns.is_empty() && retry_txns.is_empty() {
+    if new_txns.is_empty() && retry_txns.is_empty() && deferred_txns.is_empty() {
         return next_backoff;
     }
 
@@ -134,12 +151,35 @@ where
     // don't hold mempool lock during network send
     drop(mempool);
 
-    // combine retry_txns and new_txns into batch
-    let mut all_txns = retry_txns
+    // fill the batch with retry_txns first, then with the other txns by highest ranking score,
+    // within the batch size and bytes limits
+    let mut candidates = deferred_txns
         .into_iter()
         .chain(new_txns.into_iter())
         .collect::<Vec<_>>();
-    all_txns.truncate(smp.config.shared_mempool_batch_size);
+    candidates.sort_by_key(|(id, txn)| (Reverse(txn.ranking_score), *id));
+    let mut all_txns = vec![];
+    let mut batch_bytes = 0;
+    let mut next_deferred_txns = BTreeSet::new();
+    let num_retry_txns = retry_txns.len();
+    for (idx, (id, txn)) in retry_txns
+        .into_iter()
+        .chain(candidates.into_iter())
+        .enumerate()
+    {
+        let fits = all_txns.len() < smp.config.shared_mempool_batch_size
+            && (all_txns.is_empty()
+                || batch_bytes + txn.size_bytes <= smp.config.shared_mempool_batch_bytes);
+        if fits {
+            batch_bytes += txn.size_bytes;
+            all_txns.push((id, txn.txn));
+        } else if idx >= num_retry_txns {
+            // retry_txns that don't fit are kept in the peer's retry_txns
+            next_deferred_txns.insert(id);
+        }
+    }
+    // broadcast in timeline order, so that txns of an account arrive in order of sequence number
+    all_txns.sort_by_key(|(id, _txn)| *id);
     let batch_timeline_ids = all_txns.iter().map(|(id, _txn)| *id).collect::<Vec<_>>();
     let batch_txns = all_txns
         .into_iter()
@@ -171,6 +211,7 @@ where
             peer,
             request_id,
             batch_timeline_ids,
+            next_deferred_txns,
             new_timeline_id,
             earliest_timeline_id,
         );
@@ -226,6 +267,37 @@ pub(crate) async fn process_client_transaction_submission<V>(
     }
 }
 
+/// looks up a transaction pending in mempool for a client
+pub(crate) fn process_client_pending_transaction_request(
+    mempool: &Mutex<CoreMempool>,
+    sender: AccountAddress,
+    sequence_number: u64,
+    callback: oneshot::Sender<Result<Option<PendingTransaction>>>,
+) {
+    let pending_txn = mempool
+        .lock()
+        .expect("[shared mempool] failed to acquire mempool lock")
+        .get_pending_transaction(&sender, sequence_number);
+    if callback.send(Ok(pending_txn)).is_err() {
+        error!("[shared mempool] failed to send back pending transaction to client endpoint");
+    }
+}
+
+/// looks up the status of a transaction in mempool for a client
+pub(crate) fn process_client_transaction_status_request(
+    mempool: &Mutex<CoreMempool>,
+    query: TransactionQuery,
+    callback: oneshot::Sender<Result<Option<TransactionStatus>>>,
+) {
+    let status = mempool
+        .lock()
+        .expect("[shared mempool] failed to acquire mempool lock")
+        .get_transaction_status(query);
+    if callback.send(Ok(status)).is_err() {
+        error!("[shared mempool] failed to send back transaction status to client endpoint");
+    }
+}
+
 /// processes transactions from other nodes
 pub(crate) async fn process_transaction_broadcast<V>(
     mut smp: SharedMempool<V>,
@@ -376,10 +448,12 @@ where
                         statuses.push((mempool_status, None));
                     }
                     Some(validation_status) => {
-                        statuses.push((
+                        let status = (
                             MempoolStatus::new(MempoolStatusCode::VmError),
                             Some(validation_status.clone()),
-                        ));
+                        );
+                        mempool.record_rejection(&transaction, status.clone());
+                        statuses.push(status);
                     }
                 }
             }
@@ -510,19 +584,92 @@ async fn commit_txns(
     }
 }
 
+/// number of txns re-validated between taking the mempool lock to remove the invalid ones
+const REVALIDATION_BATCH_SIZE: usize = 100;
+
 /// processes on-chain reconfiguration notification
 pub(crate) async fn process_config_update<V>(
     config_update: OnChainConfigPayload,
-    validator: Arc<RwLock<V>>,
+    smp: SharedMempool<V>,
 ) where
     V: TransactionValidation,
 {
     // restart VM validator
-    validator
+    smp.validator
         .write()
         .unwrap()
         .restart(config_update)
         .expect("failed to restart VM validator");
+
+    // validation is CPU-bound, so keep it off the executor's threads
+    if let Err(e) = tokio::task::spawn_blocking(move || revalidate_transactions(&smp)).await {
+        error!(
+            "[shared mempool] failed to re-validate transactions: {:?}",
+            e
+        );
+    }
+}
+
+/// re-runs validation on all txns in mempool against the restarted VM validator and removes the
+/// ones that are no longer valid, so that they aren't proposed to consensus only to be discarded
+fn revalidate_transactions<V>(smp: &SharedMempool<V>)
+where
+    V: TransactionValidation,
+{
+    let transactions = smp
+        .mempool
+        .lock()
+        .expect("[shared mempool] failed to acquire mempool lock")
+        .get_all_transactions();
+
+    for batch in transactions.chunks(REVALIDATION_BATCH_SIZE) {
+        // don't hold mempool lock during validation
+        let invalid_txns = batch
+            .iter()
+            .filter_map(|txn| {
+                let validation_result = smp
+                    .validator
+                    .read()
+                    .unwrap()
+                    .validate_transaction(txn.clone());
+                match validation_result {
+                    Ok(result) => result.status().map(|status| (txn, status)),
+                    Err(e) => {
+                        error!(
+                            "[shared mempool] failed to re-validate transaction {}:{}: {:?}",
+                            txn.sender(),
+                            txn.sequence_number(),
+                            e
+                        );
+                        None
+                    }
+                }
+            })
+            .collect::<Vec<_>>();
+
+        counters::SHARED_MEMPOOL_REVALIDATED_TXNS
+            .with_label_values(&["valid"])
+            .inc_by((batch.len() - invalid_txns.len()) as i64);
+        counters::SHARED_MEMPOOL_REVALIDATED_TXNS
+            .with_label_values(&["invalid"])
+            .inc_by(invalid_txns.len() as i64);
+        if !invalid_txns.is_empty() {
+            let mut mempool = smp
+                .mempool
+                .lock()
+                .expect("[shared mempool] failed to acquire mempool lock");
+            for (txn, vm_status) in invalid_txns {
+                mempool.remove_invalid_transaction(
+                    txn,
+                    (
+                        MempoolStatus::new(MempoolStatusCode::VmError),
+                        Some(vm_status),
+                    ),
+                );
+            }
+        }
+    }
+    notify_subscribers(SharedMempoolNotification::Revalidation, &smp.subscribers);
 }
 
 /// creates uniques request id for the batch in the format "{start_id}_{end_id}"
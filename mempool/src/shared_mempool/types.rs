@@ -18,6 +18,7 @@ use libra_config::{
     config::{MempoolConfig, PeerNetworkId},
     network_id::NetworkId,
 };
+use libra_crypto::HashValue;
 use libra_types::{
     account_address::AccountAddress,
     mempool_status::MempoolStatus,
@@ -30,7 +31,7 @@ use std::{
     pin::Pin,
     sync::{Arc, Mutex, RwLock},
     task::Waker,
-    time::Instant,
+    time::{Duration, Instant},
 };
 use storage_interface::DbReader;
 use subscription_service::ReconfigSubscription;
@@ -58,6 +59,7 @@ pub enum SharedMempoolNotification {
     NewTransactions,
     ACK,
     Broadcast,
+    Revalidation,
 }
 
 pub(crate) fn notify_subscribers(
@@ -122,6 +124,68 @@ impl Future for ScheduledBroadcast {
     }
 }
 
+/// Message sent from client endpoints, such as JSON RPC, to mempool
+pub enum MempoolClientRequest {
+    /// request to submit a transaction to mempool
+    SubmitTransaction(
+        SignedTransaction,
+        // callback to send the submission status back to sender
+        oneshot::Sender<Result<SubmissionStatus>>,
+    ),
+    /// request for the transaction sent by an account with a sequence number, if it's pending in
+    /// mempool
+    GetPendingTransaction(
+        // sender
+        AccountAddress,
+        // sequence number
+        u64,
+        // callback to send response back to sender
+        oneshot::Sender<Result<Option<PendingTransaction>>>,
+    ),
+    /// request for the status of a transaction, if mempool knows about it
+    GetTransactionStatus(
+        // transaction to look up
+        TransactionQuery,
+        // callback to send response back to sender
+        oneshot::Sender<Result<Option<TransactionStatus>>>,
+    ),
+}
+
+/// how a transaction is looked up in mempool
+#[derive(Clone, Copy, Debug)]
+pub enum TransactionQuery {
+    /// by sender and sequence number
+    BySequenceNumber(AccountAddress, u64),
+    /// by hash
+    ByHash(HashValue),
+}
+
+/// status of a transaction in mempool
+#[derive(Clone, Debug)]
+pub enum TransactionStatus {
+    /// validated and ready to be pulled into a block, but not broadcast by this node, e.g.
+    /// because it was received from another node
+    Validated(PendingTransaction),
+    /// validated, but waiting on transactions with lower sequence numbers from the same sender
+    WaitingOnSequenceGap(PendingTransaction),
+    /// validated, ready to be broadcast and pulled into a block
+    ReadyForBroadcast(PendingTransaction),
+    /// rejected on submission, along with the hash of the rejected transaction
+    Rejected(HashValue, SubmissionStatus),
+}
+
+/// transaction in mempool, waiting to be committed
+#[derive(Clone, Debug)]
+pub struct PendingTransaction {
+    /// transaction
+    pub txn: SignedTransaction,
+    /// score the transaction is ranked by when pulling blocks for consensus
+    pub ranking_score: u64,
+    /// system expiration time, since the unix epoch, by which the transaction is removed from
+    /// mempool
+    pub expiration_time: Duration,
+}
+
 /// Message sent from consensus to mempool
 pub enum ConsensusRequest {
     /// request to pull block to submit to consensus
@@ -191,8 +255,7 @@ pub struct TransactionExclusion {
 pub type SubmissionStatus = (MempoolStatus, Option<VMStatus>);
 
 /// sender type: used to enqueue new transactions to shared mempool by client endpoints
-pub type MempoolClientSender =
-    mpsc::Sender<(SignedTransaction, oneshot::Sender<Result<SubmissionStatus>>)>;
+pub type MempoolClientSender = mpsc::Sender<MempoolClientRequest>;
 
 /// On-chain configs that mempool subscribes to for reconfiguration
 const MEMPOOL_SUBSCRIBED_CONFIGS: &[ConfigID] = &[LibraVersion::CONFIG_ID, VMConfig::CONFIG_ID];
@@ -5,11 +5,17 @@ use crate::{
     core_mempool::{CoreMempool, TimelineState, TtlCache},
     tests::common::{
         add_signed_txn, add_txn, add_txns_to_mempool, exist_in_metrics_cache, setup_mempool,
-        TestTransaction,
+        ConsensusMock, TestTransaction,
     },
+    TransactionQuery, TransactionStatus,
 };
 use libra_config::config::NodeConfig;
-use libra_types::transaction::SignedTransaction;
+use libra_crypto::{hash::CryptoHash, HashValue};
+use libra_types::{
+    mempool_status::{MempoolStatus, MempoolStatusCode},
+    transaction::{SignedTransaction, Transaction},
+    vm_status::{StatusCode, VMStatus},
+};
 use std::{
     collections::HashSet,
     time::{Duration, SystemTime},
@@ -154,6 +160,147 @@ fn test_update_invalid_transaction_in_mempool() {
     assert_eq!(next_tnx[0].gas_unit_price(), 1);
 }
 
+#[test]
+fn test_update_transaction_gas_price_bump() {
+    let mut config = NodeConfig::random();
+    config.mempool.min_gas_price_bump_percentage = 50;
+    let mut pool = CoreMempool::new(&config);
+    let mut consensus = ConsensusMock::new();
+    add_txn(&mut pool, TestTransaction::new(0, 0, 10)).unwrap();
+
+    let add = |pool: &mut CoreMempool, gas_price| {
+        let txn = TestTransaction::new(0, 0, gas_price).make_signed_transaction();
+        pool.add_txn(txn, 0, gas_price, 0, TimelineState::NotReady, false)
+            .code
+    };
+    // gas price isn't high enough above the pending transaction's
+    assert_eq!(add(&mut pool, 10), MempoolStatusCode::GasPriceBumpTooLow);
+    assert_eq!(add(&mut pool, 14), MempoolStatusCode::GasPriceBumpTooLow);
+    assert_eq!(add(&mut pool, 15), MempoolStatusCode::Accepted);
+
+    let block = consensus.get_block(&mut pool, 1);
+    assert_eq!(block.len(), 1);
+    assert_eq!(block[0].gas_unit_price(), 15);
+}
+
+#[test]
+fn test_transaction_status() {
+    let (mut pool, _) = setup_mempool();
+    let hash = |txn: &SignedTransaction| Transaction::UserTransaction(txn.clone()).hash();
+    let status = |pool: &CoreMempool, address, sequence_number| {
+        pool.get_transaction_status(TransactionQuery::BySequenceNumber(
+            TestTransaction::get_address(address),
+            sequence_number,
+        ))
+    };
+
+    let ready_txn = TestTransaction::new(0, 0, 1).make_signed_transaction();
+    add_signed_txn(&mut pool, ready_txn.clone()).unwrap();
+    add_txn(&mut pool, TestTransaction::new(0, 2, 1)).unwrap();
+    // received from another node, so not broadcast
+    let txn = TestTransaction::new(1, 0, 1).make_signed_transaction();
+    pool.add_txn(txn, 0, 1, 0, TimelineState::NonQualified, false);
+
+    assert!(matches!(
+        status(&pool, 0, 0),
+        Some(TransactionStatus::ReadyForBroadcast(_))
+    ));
+    assert!(matches!(
+        status(&pool, 0, 2),
+        Some(TransactionStatus::WaitingOnSequenceGap(_))
+    ));
+    assert!(matches!(
+        status(&pool, 1, 0),
+        Some(TransactionStatus::Validated(_))
+    ));
+    assert!(status(&pool, 0, 1).is_none());
+
+    // invalid update is rejected, while the pending transaction is still reported by sequence
+    // number
+    let invalid_update =
+        TestTransaction::new(0, 0, 5).make_signed_transaction_with_max_gas_amount(200);
+    assert!(add_signed_txn(&mut pool, invalid_update.clone()).is_err());
+    match pool.get_transaction_status(TransactionQuery::ByHash(hash(&invalid_update))) {
+        Some(TransactionStatus::Rejected(rejected_hash, (mempool_status, None))) => {
+            assert_eq!(rejected_hash, hash(&invalid_update));
+            assert_eq!(mempool_status.code, MempoolStatusCode::InvalidUpdate);
+        }
+        status => panic!("unexpected status: {:?}", status),
+    }
+    match pool.get_transaction_status(TransactionQuery::ByHash(hash(&ready_txn))) {
+        Some(TransactionStatus::ReadyForBroadcast(pending)) => assert_eq!(pending.txn, ready_txn),
+        status => panic!("unexpected status: {:?}", status),
+    }
+    assert!(matches!(
+        status(&pool, 0, 0),
+        Some(TransactionStatus::ReadyForBroadcast(_))
+    ));
+
+    // rejected by VM validation
+    let invalid_txn = TestTransaction::new(0, 1, 1).make_signed_transaction();
+    pool.record_rejection(
+        &invalid_txn,
+        (
+            MempoolStatus::new(MempoolStatusCode::VmError),
+            Some(VMStatus::Error(StatusCode::INVALID_SIGNATURE)),
+        ),
+    );
+    assert!(matches!(
+        status(&pool, 0, 1),
+        Some(TransactionStatus::Rejected(_, (_, Some(_))))
+    ));
+    assert!(pool
+        .get_transaction_status(TransactionQuery::ByHash(HashValue::zero()))
+        .is_none());
+}
+
+#[test]
+fn test_remove_invalid_transaction() {
+    let (mut pool, mut consensus) = setup_mempool();
+    let txns = add_txns_to_mempool(
+        &mut pool,
+        vec![
+            TestTransaction::new(0, 0, 1),
+            TestTransaction::new(0, 1, 1),
+            TestTransaction::new(0, 2, 1),
+            TestTransaction::new(1, 0, 1),
+        ],
+    );
+    let vm_error = || {
+        (
+            MempoolStatus::new(MempoolStatusCode::VmError),
+            Some(VMStatus::Error(StatusCode::GAS_UNIT_PRICE_BELOW_MIN_BOUND)),
+        )
+    };
+
+    // a replaced transaction is left in mempool
+    let replaced_txn = TestTransaction::new(0, 1, 5).make_signed_transaction();
+    pool.remove_invalid_transaction(&replaced_txn, vm_error());
+    assert_eq!(pool.get_all_transactions().len(), 4);
+
+    pool.remove_invalid_transaction(&txns[1], vm_error());
+    assert_eq!(pool.get_all_transactions().len(), 3);
+    assert!(matches!(
+        pool.get_transaction_status(TransactionQuery::BySequenceNumber(
+            TestTransaction::get_address(0),
+            1
+        )),
+        Some(TransactionStatus::Rejected(_, (_, Some(_))))
+    ));
+    // the following transaction of the account is no longer ready
+    assert!(matches!(
+        pool.get_transaction_status(TransactionQuery::BySequenceNumber(
+            TestTransaction::get_address(0),
+            2
+        )),
+        Some(TransactionStatus::WaitingOnSequenceGap(_))
+    ));
+    let block = consensus.get_block(&mut pool, 10);
+    assert_eq!(block.len(), 2);
+    assert!(block.contains(&txns[0]));
+    assert!(block.contains(&txns[3]));
+}
+
 #[test]
 fn test_remove_transaction() {
     let (mut pool, mut consensus) = setup_mempool();
@@ -175,6 +322,27 @@ fn test_remove_transaction() {
     assert_eq!(consensus.get_block(&mut pool, 1), vec!(new_txns[1].clone()));
 }
 
+#[test]
+fn test_get_pending_transaction() {
+    let (mut pool, _) = setup_mempool();
+    let txns = add_txns_to_mempool(&mut pool, vec![TestTransaction::new(0, 0, 3)]);
+    let txn = &txns[0];
+
+    let pending_txn = pool
+        .get_pending_transaction(&txn.sender(), txn.sequence_number())
+        .unwrap();
+    assert_eq!(&pending_txn.txn, txn);
+    assert_eq!(pending_txn.ranking_score, 3);
+    assert!(pool
+        .get_pending_transaction(&txn.sender(), txn.sequence_number() + 1)
+        .is_none());
+
+    pool.remove_transaction(&txn.sender(), txn.sequence_number(), false);
+    assert!(pool
+        .get_pending_transaction(&txn.sender(), txn.sequence_number())
+        .is_none());
+}
+
 #[test]
 fn test_system_ttl() {
     // created mempool with system_transaction_timeout = 0
@@ -303,6 +471,73 @@ fn test_capacity() {
     assert!(add_txn(&mut pool, TestTransaction::new(1, 2, 1)).is_ok());
 }
 
+#[test]
+fn test_capacity_bytes() {
+    let size_bytes = TestTransaction::new(1, 0, 1)
+        .make_signed_transaction()
+        .raw_txn_bytes_len();
+    let mut config = NodeConfig::random();
+    config.mempool.capacity_bytes = 2 * size_bytes;
+    let mut pool = CoreMempool::new(&config);
+
+    // error on exceeding byte budget
+    add_txn(&mut pool, TestTransaction::new(1, 0, 1)).unwrap();
+    add_txn(&mut pool, TestTransaction::new(1, 1, 1)).unwrap();
+    assert!(add_txn(&mut pool, TestTransaction::new(1, 2, 1)).is_err());
+
+    // commit transaction and free space
+    pool.remove_transaction(&TestTransaction::get_address(1), 0, false);
+    assert!(add_txn(&mut pool, TestTransaction::new(1, 2, 1)).is_ok());
+}
+
+#[test]
+fn test_largest_account_eviction() {
+    let mut config = NodeConfig::random();
+    config.mempool.capacity = 4;
+    let mut pool = CoreMempool::new(&config);
+    // single account fills up Mempool with ready transactions
+    for seq in 0..4 {
+        add_txn(&mut pool, TestTransaction::new(1, seq, 1)).unwrap();
+    }
+    // other account still gets its transactions in, by evicting latest txns of the first one
+    for seq in 0..2 {
+        add_txn(&mut pool, TestTransaction::new(0, seq, 1)).unwrap();
+    }
+    // until both accounts hold the same number of transactions
+    assert!(add_txn(&mut pool, TestTransaction::new(0, 2, 1)).is_err());
+
+    let mut txns: Vec<_> = pool
+        .get_block(4, HashSet::new())
+        .iter()
+        .map(|txn| (txn.sender(), txn.sequence_number()))
+        .collect();
+    txns.sort();
+    let mut expected = vec![
+        (TestTransaction::get_address(0), 0),
+        (TestTransaction::get_address(0), 1),
+        (TestTransaction::get_address(1), 0),
+        (TestTransaction::get_address(1), 1),
+    ];
+    expected.sort();
+    assert_eq!(txns, expected);
+}
+
+#[test]
+fn test_capacity_per_user_checked_before_eviction() {
+    let mut config = NodeConfig::random();
+    config.mempool.capacity = 3;
+    config.mempool.capacity_per_user = 2;
+    let mut pool = CoreMempool::new(&config);
+    add_txn(&mut pool, TestTransaction::new(1, 0, 1)).unwrap();
+    add_txn(&mut pool, TestTransaction::new(1, 1, 1)).unwrap();
+    // non-ready transaction of other account fills up Mempool
+    add_txn(&mut pool, TestTransaction::new(0, 5, 1)).unwrap();
+
+    // a transaction over the capacity per user doesn't evict anything
+    assert!(add_txn(&mut pool, TestTransaction::new(1, 2, 1)).is_err());
+    assert_eq!(pool.get_all_transactions().len(), 3);
+}
+
 #[test]
 fn test_parking_lot_eviction() {
     let mut config = NodeConfig::random();
@@ -604,6 +604,28 @@ fn test_broadcast_updated_transaction() {
     assert_eq!(txn.get(0).unwrap().gas_unit_price(), 5);
 }
 
+#[test]
+fn test_broadcast_by_ranking_score() {
+    let (mut smp, peers) = SharedMempoolNetwork::bootstrap_validator_network(2, 1, None);
+    let (peer_a, peer_b) = (peers.get(0).unwrap(), peers.get(1).unwrap());
+    smp.add_txns(
+        &peer_a,
+        vec![
+            TestTransaction::new(0, 0, 1),
+            TestTransaction::new(1, 0, 5),
+            TestTransaction::new(2, 0, 3),
+        ],
+    );
+
+    smp.send_new_peer_event(peer_a, peer_b);
+
+    // txns are broadcast by highest gas price first, regardless of when they were added
+    for expected_gas_price in &[5, 3, 1] {
+        let txn = smp.deliver_message(&peer_a, 1, true).0;
+        assert_eq!(txn.get(0).unwrap().gas_unit_price(), *expected_gas_price);
+    }
+}
+
 #[test]
 fn test_consensus_events_rejected_txns() {
     let smp = MockSharedMempool::new(None);
@@ -11,7 +11,10 @@
 //! long as the latter is in its trusted peers set.
 use channel::{self, message_queues::QueueStyle};
 use libra_config::{
-    config::{DiscoveryMethod, NetworkConfig, RoleType, HANDSHAKE_VERSION},
+    config::{
+        DiscoveryMethod, NetworkConfig, PeerReputationConfig, ProxyConfig, RoleType,
+        SeedFileConfig, HANDSHAKE_VERSION,
+    },
     network_id::{NetworkContext, NetworkId},
 };
 use libra_crypto::x25519;
@@ -22,13 +25,16 @@ use libra_network_address::{
     NetworkAddress,
 };
 use libra_types::{chain_id::ChainId, PeerId};
+use netcore::transport::proxy::Proxy;
 use network::{
+    bandwidth_throttle::BandwidthLimits,
     connectivity_manager::{builder::ConnectivityManagerBuilder, ConnectivityRequest},
     constants,
     peer_manager::{
         builder::{AuthenticationMode, PeerManagerBuilder},
         conn_notifs_channel, ConnectionRequestSender,
     },
+    peer_reputation::PeerReputations,
     protocols::{
         gossip_discovery::{self, builder::GossipDiscoveryBuilder},
         health_checker::{self, builder::HealthCheckerBuilder},
@@ -38,6 +44,7 @@ use network::{
 };
 use network_simple_onchain_discovery::{
     builder::ConfigurationChangeListenerBuilder, gen_simple_discovery_reconfig_subscription,
+    seed_file::SeedFileListener,
 };
 use std::{
     clone::Clone,
@@ -164,6 +171,11 @@ impl NetworkBuilder {
             .seed_addrs(config.seed_addrs.clone())
             .seed_pubkeys(config.seed_pubkeys.clone())
             .connectivity_check_interval_ms(config.connectivity_check_interval_ms)
+            .peer_reputation_config(config.peer_reputation.clone())
+            .bandwidth_limits(
+                BandwidthLimits::from_config(&config.bandwidth_limits)
+                    .expect("Bandwidth limits must be well-formed"),
+            )
             .add_connection_monitoring(
                 // TODO: Move these values into NetworkConfig
                 constants::PING_INTERVAL_MS,
@@ -171,10 +183,20 @@ impl NetworkBuilder {
                 constants::PING_FAILURES_TOLERATED,
             );
 
+        if let Some(proxy) = &config.proxy {
+            network_builder.proxy(match proxy {
+                ProxyConfig::Socks5(addr) => Proxy::Socks5(addr.clone()),
+                ProxyConfig::HttpConnect(addr) => Proxy::HttpConnect(addr.clone()),
+            });
+        }
+
         // Sanity check seed addresses.
         config
             .verify_seed_addrs()
             .expect("Seed addresses must be well-formed");
+        config
+            .verify_external_addrs()
+            .expect("Proxy and advertised addresses must be well-formed");
 
         // Don't turn on connectivity manager if we're a public-facing server,
         // for example.
@@ -186,18 +208,25 @@ impl NetworkBuilder {
         // 2) networks with a discovery protocol need connmgr to connect to newly
         //    discovered peers.
         // 3) if we have seed peers, then we need connmgr to connect to them.
+        // 4) so do the peers of a seed file.
         // TODO(philiphayes): could probably use a better way to specify these cases
         if config.mutual_authentication
             || config.discovery_method != DiscoveryMethod::None
             || !config.seed_addrs.is_empty()
+            || config.seed_file.is_some()
         {
             network_builder.add_connectivity_manager();
         }
 
         match &config.discovery_method {
             DiscoveryMethod::Gossip(gossip_config) => {
+                // The external address of a node behind a NAT takes precedence.
+                let advertised_address = config
+                    .advertised_address
+                    .clone()
+                    .unwrap_or_else(|| gossip_config.advertised_address.clone());
                 network_builder.add_gossip_discovery(
-                    gossip_config.advertised_address.clone(),
+                    advertised_address,
                     gossip_config.discovery_interval_ms,
                     pubkey,
                 );
@@ -212,6 +241,10 @@ impl NetworkBuilder {
             DiscoveryMethod::None => {}
         }
 
+        if let Some(seed_file) = &config.seed_file {
+            network_builder.add_seed_file_discovery(seed_file.clone());
+        }
+
         (runtime, network_builder)
     }
 
@@ -251,6 +284,29 @@ impl NetworkBuilder {
         self
     }
 
+    /// Set how peers are scored on their behavior.
+    pub fn peer_reputation_config(&mut self, config: PeerReputationConfig) -> &mut Self {
+        self.peer_manager_builder.peer_reputation_config(config);
+        self
+    }
+
+    /// The reputations of the peers of the network, as scored once it's built.
+    pub fn peer_reputations(&self) -> PeerReputations {
+        self.peer_manager_builder.peer_reputations()
+    }
+
+    /// Set the limits on the bandwidth of each protocol over each connection.
+    pub fn bandwidth_limits(&mut self, bandwidth_limits: BandwidthLimits) -> &mut Self {
+        self.peer_manager_builder.bandwidth_limits(bandwidth_limits);
+        self
+    }
+
+    /// Set a proxy to dial outbound connections through.
+    pub fn proxy(&mut self, proxy: Proxy) -> &mut Self {
+        self.peer_manager_builder.proxy(proxy);
+        self
+    }
+
     pub fn conn_mgr_reqs_tx(&self) -> Option<channel::Sender<ConnectivityRequest>> {
         match self.connectivity_manager_builder.as_ref() {
             Some(conn_mgr_builder) => Some(conn_mgr_builder.conn_mgr_reqs_tx()),
@@ -366,6 +422,18 @@ impl NetworkBuilder {
             .start_configuration_change_listener()
     }
 
+    /// Add a [`SeedFileListener`] to the network, which periodically reloads a signed file of seed
+    /// peers and passes them to the [`ConnectivityManager`], along with the other discovered peers.
+    fn add_seed_file_discovery(&mut self, config: SeedFileConfig) -> &mut Self {
+        let conn_mgr_reqs_tx = self
+            .conn_mgr_reqs_tx()
+            .expect("ConnectivityManager not enabled");
+        let listener = SeedFileListener::new(self.network_context(), config, conn_mgr_reqs_tx);
+        self.executor.spawn(listener.start());
+        debug!("{} Started seed file discovery", self.network_context());
+        self
+    }
+
     fn build_configuration_change_listener(&mut self) -> &mut Self {
         if let Some(configuration_change_listener) =
             self.configuration_change_listener_builder.as_mut()
@@ -20,6 +20,7 @@ use std::time::Duration;
 pub mod and_then;
 pub mod boxed;
 pub mod memory;
+pub mod proxy;
 pub mod tcp;
 pub mod timeout;
 
@@ -0,0 +1,348 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Tunneling of outbound TCP connections through a SOCKS5 or HTTP CONNECT proxy, for nodes which
+//! can't reach their peers directly, e.g. behind a corporate NAT or firewall.
+use crate::transport::tcp::resolve_and_connect;
+use libra_network_address::{parse_dns_tcp, parse_ip_tcp, NetworkAddress};
+use std::{io, net::SocketAddr};
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::TcpStream,
+};
+
+const SOCKS5_VERSION: u8 = 5;
+const SOCKS5_NO_AUTH: u8 = 0;
+const SOCKS5_CONNECT: u8 = 1;
+const SOCKS5_SUCCEEDED: u8 = 0;
+const SOCKS5_ATYP_IPV4: u8 = 1;
+const SOCKS5_ATYP_DOMAIN: u8 = 3;
+const SOCKS5_ATYP_IPV6: u8 = 4;
+
+/// Maximum size of the response of an HTTP proxy to a CONNECT request.
+const MAX_HTTP_RESPONSE_LEN: usize = 8 * 1024;
+
+/// A proxy to dial outbound connections through, at the given `/ip4`, `/ip6` or `/dns` address.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Proxy {
+    /// A SOCKS5 proxy, which must accept unauthenticated clients.
+    Socks5(NetworkAddress),
+    /// An HTTP proxy supporting the CONNECT method.
+    HttpConnect(NetworkAddress),
+}
+
+/// Where the proxy is asked to connect to. DNS names are resolved by the proxy, so the IP version
+/// filters of `/dns4` and `/dns6` addresses don't apply.
+enum Target<'a> {
+    Ip(SocketAddr),
+    Domain(&'a str, u16),
+}
+
+impl<'a> Target<'a> {
+    fn authority(&self) -> String {
+        match self {
+            Target::Ip(addr) => addr.to_string(),
+            Target::Domain(name, port) => format!("{}:{}", name, port),
+        }
+    }
+}
+
+/// Connects to `addr` through the proxy.
+pub(crate) async fn connect(proxy: Proxy, addr: NetworkAddress) -> io::Result<TcpStream> {
+    let protos = addr.as_slice();
+    let target = if let Some(((ipaddr, port), _addr_suffix)) = parse_ip_tcp(protos) {
+        Target::Ip(SocketAddr::new(ipaddr, port))
+    } else if let Some(((_ip_filter, dns_name, port), _addr_suffix)) = parse_dns_tcp(protos) {
+        Target::Domain(dns_name.as_ref(), port)
+    } else {
+        return Err(proxy_error(format!("Invalid NetworkAddress: '{}'", addr)));
+    };
+
+    match proxy {
+        Proxy::Socks5(proxy_addr) => {
+            let mut stream = resolve_and_connect(proxy_addr).await?;
+            socks5_handshake(&mut stream, &target).await?;
+            Ok(stream)
+        }
+        Proxy::HttpConnect(proxy_addr) => {
+            let mut stream = resolve_and_connect(proxy_addr).await?;
+            http_connect_handshake(&mut stream, &target).await?;
+            Ok(stream)
+        }
+    }
+}
+
+/// See [RFC 1928](https://tools.ietf.org/html/rfc1928).
+async fn socks5_handshake(stream: &mut TcpStream, target: &Target<'_>) -> io::Result<()> {
+    // Only offer to go unauthenticated.
+    stream
+        .write_all(&[SOCKS5_VERSION, 1, SOCKS5_NO_AUTH])
+        .await?;
+    let mut method = [0u8; 2];
+    stream.read_exact(&mut method).await?;
+    if method != [SOCKS5_VERSION, SOCKS5_NO_AUTH] {
+        return Err(proxy_error(
+            "SOCKS5 proxy doesn't accept unauthenticated clients".to_string(),
+        ));
+    }
+
+    let mut request = vec![SOCKS5_VERSION, SOCKS5_CONNECT, 0];
+    let port = match target {
+        Target::Ip(SocketAddr::V4(addr)) => {
+            request.push(SOCKS5_ATYP_IPV4);
+            request.extend_from_slice(&addr.ip().octets());
+            addr.port()
+        }
+        Target::Ip(SocketAddr::V6(addr)) => {
+            request.push(SOCKS5_ATYP_IPV6);
+            request.extend_from_slice(&addr.ip().octets());
+            addr.port()
+        }
+        Target::Domain(name, port) => {
+            // DnsNames are at most 255 bytes long.
+            request.push(SOCKS5_ATYP_DOMAIN);
+            request.push(name.len() as u8);
+            request.extend_from_slice(name.as_bytes());
+            *port
+        }
+    };
+    request.extend_from_slice(&port.to_be_bytes());
+    stream.write_all(&request).await?;
+
+    let mut reply = [0u8; 4];
+    stream.read_exact(&mut reply).await?;
+    if reply[0] != SOCKS5_VERSION || reply[1] != SOCKS5_SUCCEEDED {
+        return Err(proxy_error(format!(
+            "SOCKS5 proxy failed to connect to {}, reply: {}",
+            target.authority(),
+            reply[1]
+        )));
+    }
+    // Skip the address the proxy bound to, and its port.
+    let bound_addr_len = match reply[3] {
+        SOCKS5_ATYP_IPV4 => 4,
+        SOCKS5_ATYP_IPV6 => 16,
+        SOCKS5_ATYP_DOMAIN => {
+            let mut len = [0u8; 1];
+            stream.read_exact(&mut len).await?;
+            len[0] as usize
+        }
+        atyp => {
+            return Err(proxy_error(format!(
+                "SOCKS5 proxy replied with an unknown address type: {}",
+                atyp
+            )))
+        }
+    };
+    let mut bound_addr = vec![0u8; bound_addr_len + 2];
+    stream.read_exact(&mut bound_addr).await?;
+    Ok(())
+}
+
+async fn http_connect_handshake(stream: &mut TcpStream, target: &Target<'_>) -> io::Result<()> {
+    let authority = target.authority();
+    let request = format!("CONNECT {0} HTTP/1.1\r\nHost: {0}\r\n\r\n", authority);
+    stream.write_all(request.as_bytes()).await?;
+
+    // Read the response a byte at a time, so as not to consume what the peer sends after it.
+    let mut response = Vec::new();
+    while !response.ends_with(b"\r\n\r\n") {
+        if response.len() >= MAX_HTTP_RESPONSE_LEN {
+            return Err(proxy_error(
+                "HTTP proxy response to CONNECT is too long".to_string(),
+            ));
+        }
+        let mut byte = [0u8; 1];
+        stream.read_exact(&mut byte).await?;
+        response.push(byte[0]);
+    }
+
+    let response = String::from_utf8_lossy(&response);
+    let status_line = response.lines().next().unwrap_or_default();
+    let mut status = status_line.split_whitespace();
+    match (status.next(), status.next()) {
+        (Some(version), Some("200")) if version.starts_with("HTTP/1.") => Ok(()),
+        _ => Err(proxy_error(format!(
+            "HTTP proxy failed to connect to {}: '{}'",
+            authority, status_line
+        ))),
+    }
+}
+
+fn proxy_error(msg: String) -> io::Error {
+    io::Error::new(io::ErrorKind::Other, msg)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use futures::future::join;
+    use tokio::net::TcpListener;
+
+    async fn listen() -> (TcpListener, NetworkAddress) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = NetworkAddress::from(listener.local_addr().unwrap());
+        (listener, addr)
+    }
+
+    // A SOCKS5 proxy expecting a connection request for `expected_request`, which then sends
+    // "hello" as the target.
+    async fn serve_socks5(mut listener: TcpListener, expected_request: Vec<u8>, reply: u8) {
+        let (mut socket, _) = listener.accept().await.unwrap();
+        let mut greeting = [0u8; 3];
+        socket.read_exact(&mut greeting).await.unwrap();
+        assert_eq!(greeting, [SOCKS5_VERSION, 1, SOCKS5_NO_AUTH]);
+        socket
+            .write_all(&[SOCKS5_VERSION, SOCKS5_NO_AUTH])
+            .await
+            .unwrap();
+
+        let mut request = vec![0u8; expected_request.len()];
+        socket.read_exact(&mut request).await.unwrap();
+        assert_eq!(request, expected_request);
+        socket
+            .write_all(&[
+                SOCKS5_VERSION,
+                reply,
+                0,
+                SOCKS5_ATYP_IPV4,
+                10,
+                0,
+                0,
+                1,
+                0x1f,
+                0x90,
+            ])
+            .await
+            .unwrap();
+        socket.write_all(b"hello").await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn socks5_ip() {
+        let (listener, proxy_addr) = listen().await;
+        let expected_request = vec![
+            SOCKS5_VERSION,
+            SOCKS5_CONNECT,
+            0,
+            SOCKS5_ATYP_IPV4,
+            1,
+            2,
+            3,
+            4,
+            0x18,
+            0x24,
+        ];
+        let client = async move {
+            let mut stream = connect(
+                Proxy::Socks5(proxy_addr),
+                "/ip4/1.2.3.4/tcp/6180".parse().unwrap(),
+            )
+            .await
+            .unwrap();
+            let mut buf = [0u8; 5];
+            stream.read_exact(&mut buf).await.unwrap();
+            assert_eq!(&buf, b"hello");
+        };
+        join(
+            serve_socks5(listener, expected_request, SOCKS5_SUCCEEDED),
+            client,
+        )
+        .await;
+    }
+
+    #[tokio::test]
+    async fn socks5_domain() {
+        let (listener, proxy_addr) = listen().await;
+        let mut expected_request = vec![SOCKS5_VERSION, SOCKS5_CONNECT, 0, SOCKS5_ATYP_DOMAIN, 11];
+        expected_request.extend_from_slice(b"example.com");
+        expected_request.extend_from_slice(&[0x18, 0x24]);
+        let client = async move {
+            connect(
+                Proxy::Socks5(proxy_addr),
+                "/dns4/example.com/tcp/6180".parse().unwrap(),
+            )
+            .await
+            .unwrap();
+        };
+        join(
+            serve_socks5(listener, expected_request, SOCKS5_SUCCEEDED),
+            client,
+        )
+        .await;
+    }
+
+    #[tokio::test]
+    async fn socks5_refused() {
+        let (listener, proxy_addr) = listen().await;
+        let mut expected_request = vec![SOCKS5_VERSION, SOCKS5_CONNECT, 0, SOCKS5_ATYP_IPV4];
+        expected_request.extend_from_slice(&[1, 2, 3, 4, 0x18, 0x24]);
+        let client = async move {
+            assert!(connect(
+                Proxy::Socks5(proxy_addr),
+                "/ip4/1.2.3.4/tcp/6180".parse().unwrap(),
+            )
+            .await
+            .is_err());
+        };
+        // 5: connection refused.
+        join(serve_socks5(listener, expected_request, 5), client).await;
+    }
+
+    // An HTTP proxy expecting a CONNECT request to `authority`, responding with `response`.
+    async fn serve_http(mut listener: TcpListener, authority: &str, response: &[u8]) {
+        let (mut socket, _) = listener.accept().await.unwrap();
+        let expected_request = format!("CONNECT {0} HTTP/1.1\r\nHost: {0}\r\n\r\n", authority);
+        let mut request = vec![0u8; expected_request.len()];
+        socket.read_exact(&mut request).await.unwrap();
+        assert_eq!(request, expected_request.as_bytes());
+        socket.write_all(response).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn http_connect() {
+        let (listener, proxy_addr) = listen().await;
+        let client = async move {
+            let mut stream = connect(
+                Proxy::HttpConnect(proxy_addr),
+                "/ip6/::1/tcp/6180".parse().unwrap(),
+            )
+            .await
+            .unwrap();
+            let mut buf = [0u8; 5];
+            stream.read_exact(&mut buf).await.unwrap();
+            assert_eq!(&buf, b"hello");
+        };
+        join(
+            serve_http(
+                listener,
+                "[::1]:6180",
+                b"HTTP/1.1 200 Connection established\r\nProxy-Agent: test\r\n\r\nhello",
+            ),
+            client,
+        )
+        .await;
+    }
+
+    #[tokio::test]
+    async fn http_connect_refused() {
+        let (listener, proxy_addr) = listen().await;
+        let client = async move {
+            assert!(connect(
+                Proxy::HttpConnect(proxy_addr),
+                "/dns/example.com/tcp/6180".parse().unwrap(),
+            )
+            .await
+            .is_err());
+        };
+        join(
+            serve_http(
+                listener,
+                "example.com:6180",
+                b"HTTP/1.1 403 Forbidden\r\n\r\n",
+            ),
+            client,
+        )
+        .await;
+    }
+}
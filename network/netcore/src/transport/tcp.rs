@@ -2,7 +2,13 @@
 // SPDX-License-Identifier: Apache-2.0
 
 //! TCP Transport
-use crate::{compat::IoCompat, transport::Transport};
+use crate::{
+    compat::IoCompat,
+    transport::{
+        proxy::{self, Proxy},
+        Transport,
+    },
+};
 use futures::{
     future::{self, Future},
     io::{AsyncRead, AsyncWrite},
@@ -36,6 +42,8 @@ pub struct TcpTransport {
     pub keepalive: Option<Option<Duration>>,
     /// `TCP_NODELAY` to set for opened sockets, or `None` to keep default.
     pub nodelay: Option<bool>,
+    /// Proxy to dial outbound connections through, or `None` to dial peers directly.
+    pub proxy: Option<Proxy>,
 }
 
 impl TcpTransport {
@@ -106,7 +114,10 @@ impl Transport for TcpTransport {
             .ok_or_else(|| invalid_addr_error(&addr))?;
 
         let f: Pin<Box<dyn Future<Output = io::Result<TcpStream>> + Send + 'static>> =
-            Box::pin(resolve_and_connect(addr));
+            match &self.proxy {
+                Some(proxy) => Box::pin(proxy::connect(proxy.clone(), addr)),
+                None => Box::pin(resolve_and_connect(addr)),
+            };
 
         Ok(TcpOutbound {
             inner: f,
@@ -130,7 +141,7 @@ fn resolve_with_filter<'a>(
 
 /// Note: we need to take ownership of this `NetworkAddress` (instead of just
 /// borrowing the `&[Protocol]` slice) so this future can be `Send + 'static`.
-async fn resolve_and_connect(addr: NetworkAddress) -> io::Result<TcpStream> {
+pub(crate) async fn resolve_and_connect(addr: NetworkAddress) -> io::Result<TcpStream> {
     let protos = addr.as_slice();
 
     if let Some(((ipaddr, port), _addr_suffix)) = parse_ip_tcp(protos) {
@@ -24,6 +24,7 @@ use std::{
 use subscription_service::ReconfigSubscription;
 
 pub mod builder;
+pub mod seed_file;
 
 /// Histogram of idle time of spent in event processing loop
 pub static EVENT_PROCESSING_LOOP_IDLE_DURATION_S: Lazy<DurationHistogram> = Lazy::new(|| {
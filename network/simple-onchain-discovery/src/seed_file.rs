@@ -0,0 +1,333 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Discovery of peers from a signed seed peer file, which an operator can update to push peers to
+//! their nodes without a reconfiguration transaction, e.g. when the on-chain addresses of the
+//! validators are unreachable.
+//!
+//! The file is reloaded periodically from disk or a URL, and must be re-signed before it expires.
+//! Its peers are sent to the
+//! ConnectivityManager under [`DiscoverySource::SeedFile`], which merges them with the peers
+//! discovered on-chain: on-chain addresses are dialed first.
+
+use anyhow::{bail, ensure, Context, Result};
+use futures::sink::SinkExt;
+use libra_config::{
+    config::{SeedFileConfig, SeedFileLocation},
+    network_id::NetworkContext,
+};
+use libra_crypto::{
+    ed25519::{Ed25519PrivateKey, Ed25519PublicKey, Ed25519Signature},
+    x25519, Signature, SigningKey,
+};
+use libra_crypto_derive::{CryptoHasher, LCSCryptoHash};
+use libra_logger::prelude::*;
+use libra_metrics::{register_int_counter_vec, IntCounterVec};
+use libra_network_address::NetworkAddress;
+use libra_types::PeerId;
+use network::connectivity_manager::{ConnectivityRequest, DiscoverySource};
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    sync::Arc,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+/// Larger seed files are rejected, so that a URL can't have the node download without bound.
+const MAX_SEED_FILE_BYTES: usize = 1024 * 1024;
+
+/// Counter of the reloads of the seed peer file, by result
+pub static SEED_FILE_RELOADS: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "simple_onchain_discovery_seed_file_reloads",
+        "Counter of the reloads of the seed peer file, by result",
+        &["role_type", "result"]
+    )
+    .unwrap()
+});
+
+/// The peers of a seed file.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize, CryptoHasher, LCSCryptoHash)]
+pub struct SeedPeers {
+    /// Monotonically increasing version, so that an attacker can't replace the peers with those
+    /// of an older file, e.g. when served over plain http.
+    pub version: u64,
+    /// The file is rejected from this time on, in seconds since the Unix epoch. The version of the
+    /// last file loaded isn't persisted, so this bounds how old a file that can be replayed to a
+    /// restarted node is.
+    pub expiration_timestamp_secs: u64,
+    pub peers: BTreeMap<PeerId, Vec<NetworkAddress>>,
+}
+
+/// The content of a seed file: its peers, signed by the operator.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct SignedSeedPeers {
+    pub seed_peers: SeedPeers,
+    pub signature: Ed25519Signature,
+}
+
+impl SignedSeedPeers {
+    pub fn sign(seed_peers: SeedPeers, private_key: &Ed25519PrivateKey) -> Self {
+        let signature = private_key.sign(&seed_peers);
+        Self {
+            seed_peers,
+            signature,
+        }
+    }
+
+    pub fn verify(self, public_key: &Ed25519PublicKey) -> Result<SeedPeers> {
+        self.signature
+            .verify(&self.seed_peers, public_key)
+            .context("Invalid seed file signature")?;
+        Ok(self.seed_peers)
+    }
+}
+
+/// Listener which converts the peers of the seed file into ConnectivityRequests for the
+/// ConnectivityManager, each time a newer version of the file is loaded.
+pub struct SeedFileListener {
+    network_context: Arc<NetworkContext>,
+    config: SeedFileConfig,
+    conn_mgr_reqs_tx: channel::Sender<ConnectivityRequest>,
+    /// Version of the last file sent to the ConnectivityManager.
+    version: Option<u64>,
+}
+
+impl SeedFileListener {
+    pub fn new(
+        network_context: Arc<NetworkContext>,
+        config: SeedFileConfig,
+        conn_mgr_reqs_tx: channel::Sender<ConnectivityRequest>,
+    ) -> Self {
+        Self {
+            network_context,
+            config,
+            conn_mgr_reqs_tx,
+            version: None,
+        }
+    }
+
+    /// Loads the seed file, returning its peers if it is correctly signed and newer than the last
+    /// one loaded.
+    async fn load(&self) -> Result<Option<SeedPeers>> {
+        let bytes = match &self.config.location {
+            SeedFileLocation::Path(path) => {
+                let bytes = tokio::fs::read(path)
+                    .await
+                    .with_context(|| format!("Failed to read seed file {:?}", path))?;
+                ensure!(
+                    bytes.len() <= MAX_SEED_FILE_BYTES,
+                    "Seed file is larger than {} bytes",
+                    MAX_SEED_FILE_BYTES
+                );
+                bytes
+            }
+            SeedFileLocation::Url(url) => fetch(url)
+                .await
+                .with_context(|| format!("Failed to fetch seed file {}", url))?,
+        };
+        let signed: SignedSeedPeers =
+            serde_yaml::from_slice(&bytes).context("Failed to parse seed file")?;
+        let seed_peers = signed.verify(&self.config.signing_public_key)?;
+        let now_secs = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("System time is before the Unix epoch")
+            .as_secs();
+        ensure!(
+            now_secs < seed_peers.expiration_timestamp_secs,
+            "Seed file version {} expired at {}",
+            seed_peers.version,
+            seed_peers.expiration_timestamp_secs
+        );
+
+        match self.version {
+            Some(version) if seed_peers.version == version => Ok(None),
+            Some(version) => {
+                ensure!(
+                    seed_peers.version > version,
+                    "Seed file version {} is older than the version loaded: {}",
+                    seed_peers.version,
+                    version
+                );
+                Ok(Some(seed_peers))
+            }
+            None => Ok(Some(seed_peers)),
+        }
+    }
+
+    async fn reload(&mut self) {
+        let role = self.network_context.role().as_str();
+        let seed_peers = match self.load().await {
+            Ok(Some(seed_peers)) => seed_peers,
+            Ok(None) => {
+                SEED_FILE_RELOADS
+                    .with_label_values(&[role, "unchanged"])
+                    .inc();
+                return;
+            }
+            Err(err) => {
+                // Keep the peers of the last valid file.
+                SEED_FILE_RELOADS.with_label_values(&[role, "failed"]).inc();
+                warn!(
+                    "{} Failed to reload seed file: {:?}",
+                    self.network_context, err
+                );
+                return;
+            }
+        };
+        SEED_FILE_RELOADS
+            .with_label_values(&[role, "updated"])
+            .inc();
+        info!(
+            "{} Loaded seed file version {} with {} peers",
+            self.network_context,
+            seed_peers.version,
+            seed_peers.peers.len()
+        );
+        self.version = Some(seed_peers.version);
+
+        for update in extract_updates(seed_peers) {
+            if let Err(e) = self.conn_mgr_reqs_tx.send(update).await {
+                warn!(
+                    "{} Failed to send update to ConnectivityManager {}",
+                    self.network_context, e
+                );
+            }
+        }
+    }
+
+    /// Starts the listener to reload the seed file periodically.  Creates an infinite loop.
+    pub async fn start(mut self) {
+        let mut interval =
+            tokio::time::interval(Duration::from_millis(self.config.reload_interval_ms));
+        loop {
+            interval.tick().await;
+            self.reload().await;
+        }
+    }
+}
+
+/// GETs the seed file at `url`, reading no more than `MAX_SEED_FILE_BYTES` of the response.
+async fn fetch(url: &str) -> Result<Vec<u8>> {
+    let mut response = reqwest::get(url).await?.error_for_status()?;
+    if let Some(len) = response.content_length() {
+        ensure!(
+            len <= MAX_SEED_FILE_BYTES as u64,
+            "Seed file is larger than {} bytes",
+            MAX_SEED_FILE_BYTES
+        );
+    }
+    let mut bytes = Vec::new();
+    while let Some(chunk) = response.chunk().await? {
+        if bytes.len() + chunk.len() > MAX_SEED_FILE_BYTES {
+            bail!("Seed file is larger than {} bytes", MAX_SEED_FILE_BYTES);
+        }
+        bytes.extend_from_slice(&chunk);
+    }
+    Ok(bytes)
+}
+
+/// Extracts the ConnectivityRequests for the peers of a seed file. Their public keys are parsed
+/// out of their addresses.
+fn extract_updates(seed_peers: SeedPeers) -> Vec<ConnectivityRequest> {
+    let new_peer_pubkeys: HashMap<_, _> = seed_peers
+        .peers
+        .iter()
+        .map(|(peer_id, addrs)| {
+            let pubkeys: HashSet<x25519::PublicKey> = addrs
+                .iter()
+                .filter_map(NetworkAddress::find_noise_proto)
+                .collect();
+            (*peer_id, pubkeys)
+        })
+        .collect();
+    let new_peer_addrs: HashMap<_, _> = seed_peers.peers.into_iter().collect();
+
+    vec![
+        ConnectivityRequest::UpdateAddresses(DiscoverySource::SeedFile, new_peer_addrs),
+        ConnectivityRequest::UpdateEligibleNodes(DiscoverySource::SeedFile, new_peer_pubkeys),
+    ]
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use futures::{FutureExt, StreamExt};
+    use libra_config::network_id::NetworkContext;
+    use libra_crypto::{PrivateKey, Uniform};
+    use libra_temppath::TempPath;
+
+    fn seed_peers(version: u64) -> SeedPeers {
+        let addr: NetworkAddress = "/ip4/1.2.3.4/tcp/6180".parse().unwrap();
+        let mut peers = BTreeMap::new();
+        peers.insert(PeerId::random(), vec![addr]);
+        SeedPeers {
+            version,
+            expiration_timestamp_secs: u64::max_value(),
+            peers,
+        }
+    }
+
+    #[test]
+    fn verify_signature() {
+        let private_key = Ed25519PrivateKey::generate_for_testing();
+        let signed = SignedSeedPeers::sign(seed_peers(1), &private_key);
+        assert!(signed.clone().verify(&private_key.public_key()).is_ok());
+
+        // A tampered file is rejected.
+        let mut tampered = signed;
+        tampered.seed_peers.version = 2;
+        assert!(tampered.verify(&private_key.public_key()).is_err());
+    }
+
+    #[tokio::test]
+    async fn reload_newer_versions() {
+        let private_key = Ed25519PrivateKey::generate_for_testing();
+        let path = TempPath::new();
+        path.create_as_file().unwrap();
+        let write = |seed_peers: SeedPeers| {
+            let signed = SignedSeedPeers::sign(seed_peers, &private_key);
+            std::fs::write(path.path(), serde_yaml::to_vec(&signed).unwrap()).unwrap();
+        };
+
+        let (conn_mgr_reqs_tx, mut conn_mgr_reqs_rx) = channel::new_test(10);
+        let mut listener = SeedFileListener::new(
+            NetworkContext::mock(),
+            SeedFileConfig {
+                location: SeedFileLocation::Path(path.path().to_path_buf()),
+                signing_public_key: private_key.public_key(),
+                reload_interval_ms: 1000,
+            },
+            conn_mgr_reqs_tx,
+        );
+
+        // The peers of the first version are sent to the ConnectivityManager.
+        let peers = seed_peers(2);
+        write(peers.clone());
+        listener.reload().await;
+        match conn_mgr_reqs_rx.next().await.unwrap() {
+            ConnectivityRequest::UpdateAddresses(DiscoverySource::SeedFile, addrs) => {
+                assert_eq!(addrs, peers.peers.into_iter().collect())
+            }
+            request => panic!("Unexpected request: {:?}", request),
+        }
+        conn_mgr_reqs_rx.next().await.unwrap();
+        assert_eq!(listener.version, Some(2));
+
+        // Older versions, expired files, and files signed with another key, are ignored.
+        write(seed_peers(1));
+        listener.reload().await;
+        write(SeedPeers {
+            expiration_timestamp_secs: 1,
+            ..seed_peers(3)
+        });
+        listener.reload().await;
+        let other_key = Ed25519PrivateKey::generate(&mut rand::rngs::OsRng);
+        let signed = SignedSeedPeers::sign(seed_peers(3), &other_key);
+        std::fs::write(path.path(), serde_yaml::to_vec(&signed).unwrap()).unwrap();
+        listener.reload().await;
+        assert_eq!(listener.version, Some(2));
+        assert!(conn_mgr_reqs_rx.next().now_or_never().is_none());
+    }
+}
@@ -0,0 +1,91 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Shapes the outbound traffic of each application protocol over a connection, so that one
+//! protocol, e.g. state sync serving a node catching up, can't starve the others, e.g. consensus,
+//! on the same link.
+//!
+//! The bytes of each protocol with a limit are taken from a token bucket. Its messages are
+//! queued, and each is handed to the [`Rpc`](crate::protocols::rpc::Rpc) or
+//! [`DirectSend`](crate::protocols::direct_send::DirectSend) actor once the bucket holds enough
+//! tokens to pay for it. The messages of the other protocols aren't delayed. Responses to inbound
+//! rpcs aren't throttled.
+
+use crate::ProtocolId;
+use anyhow::{ensure, Result};
+use libra_config::config::TokenBucketConfig;
+use std::{
+    collections::{BTreeMap, HashMap},
+    time::{Duration, Instant},
+};
+
+#[cfg(test)]
+mod test;
+
+/// The bandwidth limit of each throttled protocol.
+#[derive(Clone, Debug, Default)]
+pub struct BandwidthLimits(HashMap<ProtocolId, TokenBucketConfig>);
+
+impl BandwidthLimits {
+    /// Parses the limits of the network config, keyed by protocol name.
+    pub fn from_config(limits: &BTreeMap<String, TokenBucketConfig>) -> Result<Self> {
+        let mut parsed = HashMap::new();
+        for (name, config) in limits {
+            let protocol: ProtocolId = name.parse()?;
+            ensure!(
+                config.refill_per_sec > 0,
+                "Bandwidth limit of {} has to be refilled.",
+                name,
+            );
+            parsed.insert(protocol, *config);
+        }
+        Ok(Self(parsed))
+    }
+
+    pub fn get(&self, protocol: ProtocolId) -> Option<&TokenBucketConfig> {
+        self.0.get(&protocol)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&ProtocolId, &TokenBucketConfig)> {
+        self.0.iter()
+    }
+}
+
+/// A bucket of `capacity` tokens, refilled at `refill_per_sec` tokens a second. A message can take
+/// more tokens than the bucket holds, leaving it in debt: the message then has to wait for the
+/// debt to be refilled before being sent, and so do the messages after it.
+pub struct TokenBucket {
+    config: TokenBucketConfig,
+    tokens: f64,
+    last_refill: Instant,
+}
+
+impl TokenBucket {
+    pub fn full(config: TokenBucketConfig, now: Instant) -> Self {
+        Self {
+            config,
+            tokens: config.capacity as f64,
+            last_refill: now,
+        }
+    }
+
+    fn refill(&mut self, now: Instant) {
+        let elapsed = now
+            .checked_duration_since(self.last_refill)
+            .unwrap_or_else(|| Duration::from_secs(0));
+        self.tokens = (self.tokens + elapsed.as_secs_f64() * self.config.refill_per_sec as f64)
+            .min(self.config.capacity as f64);
+        self.last_refill = std::cmp::max(self.last_refill, now);
+    }
+
+    /// Takes `num_tokens`, returning how long to wait before the message paid by them is sent.
+    pub fn take(&mut self, num_tokens: u64, now: Instant) -> Duration {
+        self.refill(now);
+        self.tokens -= num_tokens as f64;
+        if self.tokens >= 0.0 {
+            Duration::from_secs(0)
+        } else {
+            Duration::from_secs_f64(-self.tokens / self.config.refill_per_sec as f64)
+        }
+    }
+}
@@ -0,0 +1,71 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    bandwidth_throttle::{BandwidthLimits, TokenBucket},
+    ProtocolId,
+};
+use libra_config::config::TokenBucketConfig;
+use std::{
+    collections::BTreeMap,
+    time::{Duration, Instant},
+};
+
+const CONFIG: TokenBucketConfig = TokenBucketConfig {
+    capacity: 1000,
+    refill_per_sec: 100,
+};
+
+#[test]
+fn test_token_bucket() {
+    let now = Instant::now();
+    let mut bucket = TokenBucket::full(CONFIG, now);
+
+    // Messages within the capacity are sent right away.
+    assert_eq!(bucket.take(600, now), Duration::from_secs(0));
+    assert_eq!(bucket.take(400, now), Duration::from_secs(0));
+    // The next ones wait for their bytes to be refilled.
+    assert_eq!(bucket.take(50, now), Duration::from_millis(500));
+    assert_eq!(bucket.take(50, now), Duration::from_secs(1));
+
+    // Once the debt is refilled, messages are sent right away again.
+    let now = now + Duration::from_secs(2);
+    assert_eq!(bucket.take(100, now), Duration::from_secs(0));
+
+    // The bucket doesn't fill past its capacity.
+    let now = now + Duration::from_secs(100);
+    assert_eq!(bucket.take(1000, now), Duration::from_secs(0));
+    assert_eq!(bucket.take(100, now), Duration::from_secs(1));
+}
+
+#[test]
+fn test_oversized_message() {
+    let now = Instant::now();
+    let mut bucket = TokenBucket::full(CONFIG, now);
+    assert_eq!(bucket.take(1500, now), Duration::from_secs(5));
+}
+
+#[test]
+fn test_limits_from_config() {
+    let mut config = BTreeMap::new();
+    config.insert("StateSynchronizerDirectSend".to_string(), CONFIG);
+    let limits = BandwidthLimits::from_config(&config).unwrap();
+    assert_eq!(
+        limits.get(ProtocolId::StateSynchronizerDirectSend),
+        Some(&CONFIG)
+    );
+    assert_eq!(limits.get(ProtocolId::ConsensusRpc), None);
+
+    config.insert("StateSync".to_string(), CONFIG);
+    assert!(BandwidthLimits::from_config(&config).is_err());
+
+    let mut config = BTreeMap::new();
+    config.insert(
+        "MempoolDirectSend".to_string(),
+        TokenBucketConfig {
+            capacity: 1000,
+            refill_per_sec: 0,
+        },
+    );
+    assert!(BandwidthLimits::from_config(&config).is_err());
+}
@@ -105,6 +105,8 @@ pub struct ConnectivityManager<TTicker, TBackoff> {
 pub enum DiscoverySource {
     OnChain,
     Gossip,
+    /// The signed seed peer file periodically reloaded by the node.
+    SeedFile,
     Config,
 }
 
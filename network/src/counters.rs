@@ -87,6 +87,57 @@ pub static LIBRA_NETWORK_DIRECT_SEND_BYTES: Lazy<HistogramVec> = Lazy::new(|| {
     .unwrap()
 });
 
+/// Counter of peer misbehaviors reported, by type.
+pub static LIBRA_NETWORK_PEER_MISBEHAVIORS: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "libra_network_peer_misbehaviors",
+        "Libra network peer misbehaviors counter",
+        &["role_type", "type"]
+    )
+    .unwrap()
+});
+
+/// Counter of peers disconnected or banned for misbehaving.
+pub static LIBRA_NETWORK_PEER_PUNISHMENTS: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "libra_network_peer_punishments",
+        "Libra network counter of peers disconnected or banned for misbehaving",
+        &["role_type", "action"]
+    )
+    .unwrap()
+});
+
+/// Time messages wait for their protocol's bandwidth limit before being sent.
+pub static LIBRA_NETWORK_THROTTLE_DELAY: Lazy<HistogramVec> = Lazy::new(|| {
+    register_histogram_vec!(
+        "libra_network_throttle_delay_seconds",
+        "Libra network bandwidth throttling delay histogram",
+        &["protocol_id"]
+    )
+    .unwrap()
+});
+
+/// Counter of bytes sent by throttled protocols.
+pub static LIBRA_NETWORK_THROTTLED_BYTES: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "libra_network_throttled_bytes",
+        "Libra network counter of bytes sent by protocols with a bandwidth limit",
+        &["protocol_id"]
+    )
+    .unwrap()
+});
+
+/// Counters(queued,dequeued,dropped) related to outbound requests waiting for their protocol's
+/// bandwidth limit.
+pub static PENDING_THROTTLED_NETWORK_REQUESTS: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "libra_network_pending_throttled_requests",
+        "Counters(queued,dequeued,dropped) related to pending outbound requests held by bandwidth limits",
+        &["state"]
+    )
+    .unwrap()
+});
+
 /// Counters(queued,dequeued,dropped) related to inbound network notifications for RPCs and
 /// DirectSends.
 pub static PENDING_NETWORK_NOTIFICATIONS: Lazy<IntCounterVec> = Lazy::new(|| {
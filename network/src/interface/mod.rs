@@ -12,9 +12,11 @@
 //! [`NetworkProvider`] actor. Inbound RPC requests are forwarded to the appropriate
 //! handler, determined using the protocol negotiated on the RPC substream.
 use crate::{
+    bandwidth_throttle::{BandwidthLimits, TokenBucket},
     constants, counters,
     peer::{Peer, PeerHandle, PeerNotification},
     peer_manager::TransportNotification,
+    peer_reputation::PeerReputations,
     protocols::{
         direct_send::{DirectSend, DirectSendNotification, DirectSendRequest, Message},
         rpc::{InboundRpcRequest, OutboundRpcRequest, Rpc, RpcNotification},
@@ -30,7 +32,13 @@ use futures::{
 };
 use libra_logger::prelude::*;
 use libra_types::PeerId;
-use std::{fmt::Debug, marker::PhantomData, num::NonZeroUsize, time::Duration};
+use std::{
+    collections::HashMap,
+    fmt::Debug,
+    marker::PhantomData,
+    num::NonZeroUsize,
+    time::{Duration, Instant},
+};
 use tokio::runtime::Handle;
 
 /// Requests [`NetworkProvider`] receives from the network interface.
@@ -42,6 +50,23 @@ pub enum NetworkRequest {
     SendMessage(Message),
 }
 
+impl NetworkRequest {
+    pub fn protocol(&self) -> ProtocolId {
+        match self {
+            NetworkRequest::SendRpc(req) => req.protocol,
+            NetworkRequest::SendMessage(msg) => msg.protocol,
+        }
+    }
+
+    /// The number of bytes of the request or message sent.
+    pub fn num_bytes(&self) -> usize {
+        match self {
+            NetworkRequest::SendRpc(req) => req.data.len(),
+            NetworkRequest::SendMessage(msg) => msg.mdata.len(),
+        }
+    }
+}
+
 /// Notifications that [`NetworkProvider`] sends to consumers of its API. The
 /// [`NetworkProvider`] in turn receives these notifications from the PeerManager and other
 /// [`protocols`](crate::protocols).
@@ -62,6 +87,7 @@ impl<TSocket> NetworkProvider<TSocket>
 where
     TSocket: AsyncRead + AsyncWrite + Send + 'static,
 {
+    #[allow(clippy::too_many_arguments)]
     pub fn start(
         executor: Handle,
         connection: Connection<TSocket>,
@@ -70,6 +96,8 @@ where
         max_concurrent_notifs: usize,
         channel_size: usize,
         max_frame_size: usize,
+        reputations: PeerReputations,
+        bandwidth_limits: &BandwidthLimits,
     ) -> (
         libra_channel::Sender<ProtocolId, NetworkRequest>,
         libra_channel::Receiver<ProtocolId, NetworkNotification>,
@@ -112,6 +140,7 @@ where
             peer_rpc_notifs_tx,
             peer_ds_notifs_tx,
             max_frame_size,
+            reputations.clone(),
         );
         executor.spawn(peer.start());
 
@@ -134,6 +163,7 @@ where
             Duration::from_millis(constants::INBOUND_RPC_TIMEOUT_MS),
             constants::MAX_CONCURRENT_OUTBOUND_RPCS,
             constants::MAX_CONCURRENT_INBOUND_RPCS,
+            reputations,
         );
         executor.spawn(rpc.start());
 
@@ -195,17 +225,50 @@ where
             }),
         );
 
+        // Queue the requests of throttled protocols, each queue being handled by a task waiting
+        // for the bandwidth limit of its protocol.
+        let mut throttled_reqs_txs = HashMap::new();
+        for (protocol, bucket_config) in bandwidth_limits.iter() {
+            let (throttled_reqs_tx, throttled_reqs_rx) = libra_channel::new(
+                QueueStyle::FIFO,
+                NonZeroUsize::new(channel_size).expect("libra_channel cannot be of size 0"),
+                Some(&counters::PENDING_THROTTLED_NETWORK_REQUESTS),
+            );
+            executor.spawn(Self::handle_throttled_network_requests(
+                peer_id,
+                TokenBucket::full(*bucket_config, Instant::now()),
+                throttled_reqs_rx,
+                rpc_reqs_tx.clone(),
+                ds_reqs_tx.clone(),
+            ));
+            throttled_reqs_txs.insert(*protocol, throttled_reqs_tx);
+        }
+
         // Handle network requests.
         let f = async move {
             let peer_id_str = peer_id.short_str();
             requests_rx
                 .for_each_concurrent(max_concurrent_reqs, move |req| {
-                    Self::handle_network_request(
-                        peer_id,
-                        req,
-                        rpc_reqs_tx.clone(),
-                        ds_reqs_tx.clone(),
-                    )
+                    let throttled_reqs_tx = throttled_reqs_txs.get(&req.protocol()).cloned();
+                    let rpc_reqs_tx = rpc_reqs_tx.clone();
+                    let ds_reqs_tx = ds_reqs_tx.clone();
+                    async move {
+                        match throttled_reqs_tx {
+                            Some(mut throttled_reqs_tx) => {
+                                if let Err(e) = throttled_reqs_tx.push(req.protocol(), req) {
+                                    error!(
+                                        "Failed to throttle request to peer: {}. Error: {:?}",
+                                        peer_id.short_str(),
+                                        e
+                                    );
+                                }
+                            }
+                            None => {
+                                Self::handle_network_request(peer_id, req, rpc_reqs_tx, ds_reqs_tx)
+                                    .await
+                            }
+                        }
+                    }
                 })
                 .then(|_| async move {
                     info!(
@@ -223,6 +286,31 @@ where
         (requests_tx, notifs_rx)
     }
 
+    async fn handle_throttled_network_requests(
+        peer_id: PeerId,
+        mut bucket: TokenBucket,
+        mut throttled_reqs_rx: libra_channel::Receiver<ProtocolId, NetworkRequest>,
+        rpc_reqs_tx: channel::Sender<OutboundRpcRequest>,
+        ds_reqs_tx: channel::Sender<DirectSendRequest>,
+    ) {
+        while let Some(req) = throttled_reqs_rx.next().await {
+            let protocol = req.protocol().as_str();
+            let num_bytes = req.num_bytes() as u64;
+            let delay = bucket.take(num_bytes, Instant::now());
+            counters::LIBRA_NETWORK_THROTTLE_DELAY
+                .with_label_values(&[protocol])
+                .observe(delay.as_secs_f64());
+            if delay > Duration::from_secs(0) {
+                tokio::time::delay_for(delay).await;
+            }
+            counters::LIBRA_NETWORK_THROTTLED_BYTES
+                .with_label_values(&[protocol])
+                .inc_by(num_bytes as i64);
+            Self::handle_network_request(peer_id, req, rpc_reqs_tx.clone(), ds_reqs_tx.clone())
+                .await;
+        }
+    }
+
     async fn handle_network_request(
         peer_id: PeerId,
         req: NetworkRequest,
@@ -8,6 +8,7 @@
 
 pub use interface::NetworkProvider;
 
+pub mod bandwidth_throttle;
 pub mod common;
 pub mod connectivity_manager;
 pub mod constants;
@@ -15,6 +16,7 @@ pub mod error;
 pub mod interface;
 pub mod logging;
 pub mod peer_manager;
+pub mod peer_reputation;
 pub mod protocols;
 
 pub mod counters;
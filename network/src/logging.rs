@@ -40,6 +40,7 @@ pub mod network_events {
     /// Labels
     pub const CONNECTIVITY_MANAGER_LOOP: &str = "connectivity_manager_loop";
     pub const PEER_MANAGER_LOOP: &str = "peer_manager_loop";
+    pub const PEER_REPUTATION: &str = "peer_reputation";
 
     /// Common terms
     pub const TYPE: &str = "type";
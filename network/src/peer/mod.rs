@@ -6,6 +6,7 @@
 use crate::{
     counters,
     peer_manager::PeerManagerError,
+    peer_reputation::{Misbehavior, PeerReputations, ReputationAction},
     protocols::wire::messaging::v1::NetworkMessage,
     transport,
     transport::{Connection, ConnectionMetadata},
@@ -49,6 +50,7 @@ pub enum PeerRequest {
 pub enum DisconnectReason {
     Requested,
     ConnectionLost,
+    Misbehaved,
 }
 
 #[derive(Debug)]
@@ -82,12 +84,15 @@ pub struct Peer<TSocket> {
     /// The maximum size of an inbound or outbound request frame
     /// Currently, requests are only a single frame
     max_frame_size: usize,
+    /// Reputations of the peers, lowered when this one sends invalid messages.
+    reputations: PeerReputations,
 }
 
 impl<TSocket> Peer<TSocket>
 where
     TSocket: AsyncRead + AsyncWrite + Send + 'static,
 {
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         executor: Handle,
         connection: Connection<TSocket>,
@@ -96,6 +101,7 @@ where
         rpc_notifs_tx: channel::Sender<PeerNotification>,
         direct_send_notifs_tx: channel::Sender<PeerNotification>,
         max_frame_size: usize,
+        reputations: PeerReputations,
     ) -> Self {
         let Connection {
             metadata: connection_metadata,
@@ -111,6 +117,7 @@ where
             direct_send_notifs_tx,
             state: State::Connected,
             max_frame_size,
+            reputations,
         }
     }
 
@@ -308,7 +315,13 @@ where
         trace!("Received message from Peer {}", self.peer_id().short_str(),);
         // Read inbound message from stream.
         let message = message.freeze();
-        let message: NetworkMessage = lcs::from_bytes(&message)?;
+        let message: NetworkMessage = match lcs::from_bytes(&message) {
+            Ok(message) => message,
+            Err(err) => {
+                self.report_misbehavior(Misbehavior::InvalidMessage).await;
+                return Err(err.into());
+            }
+        };
         match message {
             NetworkMessage::RpcRequest(_) | NetworkMessage::RpcResponse(_) => {
                 let notif = PeerNotification::NewMessage(message);
@@ -339,7 +352,29 @@ where
                 write_reqs_tx.send((pong, ack_tx)).await?;
                 Ok(())
             }
-            _ => unreachable!("Unhandled"),
+            NetworkMessage::Error(error_code) => {
+                warn!(
+                    "Peer {} reported an error: {:?}",
+                    self.peer_id().short_str(),
+                    error_code
+                );
+                Ok(())
+            }
+            // Pings are never sent, so Pongs aren't expected.
+            NetworkMessage::Pong(_) => {
+                self.report_misbehavior(Misbehavior::ProtocolViolation)
+                    .await;
+                Ok(())
+            }
+        }
+    }
+
+    async fn report_misbehavior(&mut self, misbehavior: Misbehavior) {
+        match self.reputations.report(self.peer_id(), misbehavior) {
+            ReputationAction::None => (),
+            ReputationAction::Disconnect | ReputationAction::Ban => {
+                self.close_connection(DisconnectReason::Misbehaved).await;
+            }
         }
     }
 
@@ -4,6 +4,7 @@
 use crate::{
     constants,
     peer::{DisconnectReason, Peer, PeerHandle, PeerNotification},
+    peer_reputation::PeerReputations,
     protocols::wire::{
         handshake::v1::MessagingProtocolVersion,
         messaging::v1::{DirectSendMsg, NetworkMessage},
@@ -12,6 +13,7 @@ use crate::{
     ProtocolId,
 };
 use futures::{future::join, io::AsyncWriteExt, stream::StreamExt, SinkExt};
+use libra_config::{config::PeerReputationConfig, network_id::NetworkContext};
 use libra_network_address::NetworkAddress;
 use libra_types::PeerId;
 use memsocket::MemorySocket;
@@ -62,6 +64,7 @@ fn build_test_peer(
         peer_rpc_notifs_tx,
         peer_direct_send_notifs_tx,
         constants::MAX_FRAME_SIZE,
+        PeerReputations::new(NetworkContext::mock(), PeerReputationConfig::default()),
     );
     let peer_handle = PeerHandle::new(peer_id, peer_req_tx);
 
@@ -383,3 +386,43 @@ fn peer_terminates_when_request_tx_has_dropped() {
     };
     rt.block_on(join(peer.start(), drop));
 }
+
+#[test]
+fn peer_disconnects_misbehaving_peer() {
+    ::libra_logger::Logger::new().environment_only(true).init();
+    let mut rt = Runtime::new().unwrap();
+    let (
+        mut peer,
+        _peer_handle,
+        connection,
+        mut peer_notifs_rx,
+        _peer_rpc_notifs_rx,
+        _peer_direct_send_notifs_rx,
+    ) = build_test_peer(rt.handle().clone(), ConnectionOrigin::Inbound);
+    let peer_id = peer.peer_id();
+    // A single invalid message is enough to get the remote peer disconnected.
+    let reputations = PeerReputations::new(
+        NetworkContext::mock(),
+        PeerReputationConfig {
+            enabled: true,
+            invalid_message_penalty: 100,
+            ..PeerReputationConfig::default()
+        },
+    );
+    peer.reputations = reputations.clone();
+
+    let server = async move {
+        let mut connection = Framed::new(IoCompat::new(connection), LengthDelimitedCodec::new());
+        connection.send(vec![0xff; 8].into()).await.unwrap();
+        // Keep the connection open, so the peer can only be disconnected for misbehaving.
+        connection
+    };
+    rt.spawn(peer.start());
+    let _connection = rt.block_on(server);
+    rt.block_on(assert_peer_disconnected_event(
+        peer_id,
+        DisconnectReason::Misbehaved,
+        &mut peer_notifs_rx,
+    ));
+    assert_eq!(reputations.get(peer_id).unwrap().invalid_messages, 1);
+}
@@ -2,18 +2,23 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
+    bandwidth_throttle::BandwidthLimits,
     counters,
     noise::stream::NoiseStream,
     peer_manager::{
         conn_notifs_channel, ConnectionRequest, ConnectionRequestSender, PeerManager,
         PeerManagerNotification, PeerManagerRequest, PeerManagerRequestSender,
     },
+    peer_reputation::PeerReputations,
     protocols::wire::handshake::v1::SupportedProtocols,
     transport::{self, Connection, LibraNetTransport, LIBRA_TCP_TRANSPORT},
     ProtocolId,
 };
 use channel::{self, libra_channel, message_queues::QueueStyle};
-use libra_config::{config::HANDSHAKE_VERSION, network_id::NetworkContext};
+use libra_config::{
+    config::{PeerReputationConfig, HANDSHAKE_VERSION},
+    network_id::NetworkContext,
+};
 use libra_crypto::x25519;
 use libra_logger::prelude::*;
 use libra_metrics::IntCounterVec;
@@ -21,6 +26,7 @@ use libra_network_address::NetworkAddress;
 use libra_types::{chain_id::ChainId, PeerId};
 use netcore::transport::{
     memory::MemoryTransport,
+    proxy::Proxy,
     tcp::{TcpSocket, TcpTransport},
     Transport,
 };
@@ -194,6 +200,9 @@ pub struct PeerManagerBuilder {
     listen_address: NetworkAddress,
     state: State,
     max_frame_size: usize,
+    peer_reputations: PeerReputations,
+    bandwidth_limits: BandwidthLimits,
+    proxy: Option<Proxy>,
 }
 
 impl PeerManagerBuilder {
@@ -223,6 +232,10 @@ impl PeerManagerBuilder {
         );
 
         Self {
+            peer_reputations: PeerReputations::new(
+                network_context.clone(),
+                PeerReputationConfig::default(),
+            ),
             network_context,
             transport_context: Some(TransportContext::new(
                 chain_id,
@@ -247,6 +260,8 @@ impl PeerManagerBuilder {
             listen_address,
             state: State::CREATED,
             max_frame_size,
+            bandwidth_limits: BandwidthLimits::default(),
+            proxy: None,
         }
     }
 
@@ -262,6 +277,33 @@ impl PeerManagerBuilder {
             .clone()
     }
 
+    /// Set how peers are scored on their behavior.
+    pub fn peer_reputation_config(&mut self, config: PeerReputationConfig) -> &mut Self {
+        assert_eq!(self.state, State::CREATED);
+        self.peer_reputations = PeerReputations::new(self.network_context.clone(), config);
+        self
+    }
+
+    /// The reputations of the peers, as scored by the PeerManager. They are started over when the
+    /// config is set, so this is to be called after `peer_reputation_config`.
+    pub fn peer_reputations(&self) -> PeerReputations {
+        self.peer_reputations.clone()
+    }
+
+    /// Set the limits on the bandwidth of each protocol over each connection.
+    pub fn bandwidth_limits(&mut self, bandwidth_limits: BandwidthLimits) -> &mut Self {
+        assert_eq!(self.state, State::CREATED);
+        self.bandwidth_limits = bandwidth_limits;
+        self
+    }
+
+    /// Set a proxy to dial outbound TCP connections through.
+    pub fn proxy(&mut self, proxy: Proxy) -> &mut Self {
+        assert_eq!(self.state, State::CREATED);
+        self.proxy = Some(proxy);
+        self
+    }
+
     pub fn add_connection_event_listener(&mut self) -> conn_notifs_channel::Receiver {
         self.peer_manager_context
             .as_mut()
@@ -305,7 +347,10 @@ impl PeerManagerBuilder {
             [Ip4(_), Tcp(_)] | [Ip6(_), Tcp(_)] => {
                 self.tcp_peer_manager = Some(self.build_with_transport(
                     LibraNetTransport::new(
-                        LIBRA_TCP_TRANSPORT.clone(),
+                        TcpTransport {
+                            proxy: self.proxy.clone(),
+                            ..LIBRA_TCP_TRANSPORT
+                        },
                         peer_id,
                         key,
                         maybe_trusted_peers,
@@ -373,6 +418,8 @@ impl PeerManagerBuilder {
             pm_context.max_concurrent_network_notifs,
             pm_context.channel_size,
             self.max_frame_size,
+            self.peer_reputations.clone(),
+            self.bandwidth_limits.clone(),
         );
 
         // PeerManager constructor appends a public key to the listen_address.
@@ -11,10 +11,12 @@
 //!  notification about new/lost Peers to the rest of the network stack.
 //!  * An actor responsible for dialing and listening for new connections.
 use crate::{
+    bandwidth_throttle::BandwidthLimits,
     counters,
     interface::{NetworkNotification, NetworkProvider, NetworkRequest},
     logging::*,
     peer::DisconnectReason,
+    peer_reputation::PeerReputations,
     protocols::{
         direct_send::Message,
         rpc::{error::RpcError, InboundRpcRequest, OutboundRpcRequest},
@@ -266,6 +268,10 @@ where
     channel_size: usize,
     /// Max network frame size
     max_frame_size: usize,
+    /// Reputations of the peers. Banned peers aren't connected to.
+    reputations: PeerReputations,
+    /// Limits on the bandwidth of each protocol over each connection.
+    bandwidth_limits: BandwidthLimits,
 }
 
 impl<TTransport, TSocket> PeerManager<TTransport, TSocket>
@@ -291,6 +297,8 @@ where
         max_concurrent_network_reqs: usize,
         max_concurrent_network_notifs: usize,
         max_frame_size: usize,
+        reputations: PeerReputations,
+        bandwidth_limits: BandwidthLimits,
     ) -> Self {
         let (transport_notifs_tx, transport_notifs_rx) = channel::new(
             channel_size,
@@ -329,6 +337,8 @@ where
             max_concurrent_network_notifs,
             channel_size,
             max_frame_size,
+            reputations,
+            bandwidth_limits,
         }
     }
 
@@ -436,6 +446,7 @@ where
                 // Notify upstream if there's still no active connection. This might be redundant,
                 // but does not affect correctness.
                 if !self.active_peers.contains_key(&peer_id) {
+                    self.reputations.forget(peer_id);
                     let notif = ConnectionNotification::LostPeer(
                         peer_id,
                         lost_conn_metadata.addr().clone(),
@@ -452,8 +463,24 @@ where
         trace!("{} PeerManagerRequest::{:?}", self.network_context, request);
         match request {
             ConnectionRequest::DialPeer(requested_peer_id, addr, response_tx) => {
-                // Only dial peers which we aren't already connected with
-                if let Some((curr_connection, _)) = self.active_peers.get(&requested_peer_id) {
+                // Only dial peers which we aren't already connected with, and which aren't banned
+                if self.reputations.is_banned(requested_peer_id) {
+                    debug!(
+                        "{} Peer {} is banned. Not dialing address {}",
+                        self.network_context,
+                        requested_peer_id.short_str(),
+                        addr
+                    );
+                    let error = PeerManagerError::Banned(requested_peer_id);
+                    if response_tx.send(Err(error)).is_err() {
+                        warn!(
+                            "{} Receiver for DialPeer {} dropped",
+                            self.network_context,
+                            requested_peer_id.short_str()
+                        );
+                    }
+                } else if let Some((curr_connection, _)) = self.active_peers.get(&requested_peer_id)
+                {
                     let error = PeerManagerError::AlreadyConnected(curr_connection.addr().clone());
                     debug!(
                         "{} Already connected with Peer {} using connection {:?}. Not dialing address {}",
@@ -576,6 +603,16 @@ where
         let peer_id = conn_meta.peer_id();
         assert_ne!(self.network_context.peer_id(), peer_id);
 
+        if self.reputations.is_banned(peer_id) {
+            info!(
+                "{} Closing connection with banned Peer {}",
+                self.network_context,
+                peer_id.short_str()
+            );
+            self.close_connection(connection);
+            return;
+        }
+
         let mut send_new_peer_notification = true;
 
         // Check for and handle simultaneous dialing
@@ -602,25 +639,8 @@ where
                     self.network_context,
                     peer_id.short_str()
                 );
-                let network_context = self.network_context.clone();
                 // Drop the new connection and keep the one already stored in active_peers
-                let drop_fut = async move {
-                    let mut connection = connection;
-                    if let Err(e) = tokio::time::timeout(
-                        transport::TRANSPORT_TIMEOUT,
-                        connection.socket.close(),
-                    )
-                    .await
-                    {
-                        error!(
-                            "{} Closing connection with Peer {} failed with error: {}",
-                            network_context,
-                            peer_id.short_str(),
-                            e
-                        );
-                    };
-                };
-                self.executor.spawn(drop_fut);
+                self.close_connection(connection);
                 return;
             }
         }
@@ -634,6 +654,8 @@ where
             self.max_concurrent_network_notifs,
             self.channel_size,
             self.max_frame_size,
+            self.reputations.clone(),
+            &self.bandwidth_limits,
         );
         // Start background task to handle events (RPCs and DirectSend messages) received from
         // peer.
@@ -653,6 +675,26 @@ where
         }
     }
 
+    /// Closes a connection that won't be added to `active_peers`.
+    fn close_connection(&self, connection: Connection<TSocket>) {
+        let network_context = self.network_context.clone();
+        let peer_id = connection.metadata.peer_id();
+        let drop_fut = async move {
+            let mut connection = connection;
+            if let Err(e) =
+                tokio::time::timeout(transport::TRANSPORT_TIMEOUT, connection.socket.close()).await
+            {
+                error!(
+                    "{} Closing connection with Peer {} failed with error: {}",
+                    network_context,
+                    peer_id.short_str(),
+                    e
+                );
+            };
+        };
+        self.executor.spawn(drop_fut);
+    }
+
     /// Sends a `ConnectionNotification` to all event handlers, warns on failures
     fn send_conn_notification(&mut self, peer_id: PeerId, notification: ConnectionNotification) {
         for handler in self.connection_event_handlers.iter_mut() {
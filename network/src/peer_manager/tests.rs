@@ -2,12 +2,14 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
+    bandwidth_throttle::BandwidthLimits,
     constants,
     peer::DisconnectReason,
     peer_manager::{
         conn_notifs_channel, error::PeerManagerError, ConnectionNotification, ConnectionRequest,
         PeerManager, PeerManagerNotification, PeerManagerRequest, TransportNotification,
     },
+    peer_reputation::PeerReputations,
     protocols::wire::{
         handshake::v1::MessagingProtocolVersion,
         messaging::v1::{NetworkMessage, Nonce},
@@ -19,7 +21,7 @@ use crate::{
 use channel::{libra_channel, message_queues::QueueStyle};
 use futures::{channel::oneshot, io::AsyncWriteExt, sink::SinkExt, stream::StreamExt};
 use libra_config::{
-    config::RoleType,
+    config::{PeerReputationConfig, RoleType},
     network_id::{NetworkContext, NetworkId},
 };
 use libra_network_address::NetworkAddress;
@@ -93,14 +95,15 @@ fn build_test_peer_manager(
         libra_channel::new(QueueStyle::FIFO, NonZeroUsize::new(1).unwrap(), None);
     let (conn_status_tx, conn_status_rx) = conn_notifs_channel::new();
 
+    let network_context = Arc::new(NetworkContext::new(
+        NetworkId::Validator,
+        RoleType::Validator,
+        peer_id,
+    ));
     let peer_manager = PeerManager::new(
         executor,
         build_test_transport(),
-        Arc::new(NetworkContext::new(
-            NetworkId::Validator,
-            RoleType::Validator,
-            peer_id,
-        )),
+        network_context.clone(),
         "/memory/0".parse().unwrap(),
         peer_manager_request_rx,
         connection_reqs_rx,
@@ -110,6 +113,8 @@ fn build_test_peer_manager(
         constants::MAX_CONCURRENT_NETWORK_REQS,
         constants::MAX_CONCURRENT_NETWORK_NOTIFS,
         constants::MAX_FRAME_SIZE,
+        PeerReputations::new(network_context, PeerReputationConfig::default()),
+        BandwidthLimits::default(),
     );
 
     (
@@ -0,0 +1,273 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Scores peers on their behavior, so that the misbehaving ones get disconnected and banned.
+//!
+//! The [`Peer`](crate::peer::Peer) actor reports the messages of a peer that fail to deserialize
+//! or that it wasn't supposed to send, and the [`Rpc`](crate::protocols::rpc::Rpc) actor reports
+//! the outbound rpcs that time out or get answered in time. Each report updates the score of the
+//! peer as configured in [`PeerReputationConfig`], and tells the reporter whether to disconnect
+//! the peer. The [`PeerManager`](crate::peer_manager::PeerManager) refuses to connect to banned
+//! peers until their ban expires, and has the reputations of the other peers forgotten once they
+//! disconnect, unless they were disconnected for misbehaving. Scoring is off unless enabled in the
+//! config, as it may disconnect honest peers, e.g. validators answering rpcs slowly.
+
+use crate::{
+    counters,
+    logging::{network_events, network_log},
+};
+use libra_config::{config::PeerReputationConfig, network_id::NetworkContext};
+use libra_logger::prelude::*;
+use libra_types::PeerId;
+use serde::Serialize;
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+#[cfg(test)]
+mod test;
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Misbehavior {
+    /// The peer sent a message that failed to deserialize.
+    InvalidMessage,
+    /// The peer sent a well-formed message it wasn't supposed to, e.g. an unsolicited Pong.
+    ProtocolViolation,
+    /// The peer didn't answer an outbound rpc in time.
+    RpcTimeout,
+}
+
+impl Misbehavior {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Misbehavior::InvalidMessage => "invalid_message",
+            Misbehavior::ProtocolViolation => "protocol_violation",
+            Misbehavior::RpcTimeout => "rpc_timeout",
+        }
+    }
+}
+
+/// What the reporter of a misbehavior should do with the peer.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ReputationAction {
+    /// The peer can stay connected.
+    None,
+    /// The score of the peer fell to the disconnect threshold.
+    Disconnect,
+    /// The score of the peer fell to the ban threshold: it must be disconnected, and it won't be
+    /// connected to again until the ban expires.
+    Ban,
+}
+
+/// The score of a peer and the events it was computed from.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+pub struct PeerReputation {
+    pub score: i64,
+    pub invalid_messages: u64,
+    pub protocol_violations: u64,
+    pub rpc_timeouts: u64,
+    pub rpc_successes: u64,
+    #[serde(skip)]
+    banned_until: Option<Instant>,
+}
+
+impl PeerReputation {
+    fn new(score: i64) -> Self {
+        Self {
+            score,
+            invalid_messages: 0,
+            protocol_violations: 0,
+            rpc_timeouts: 0,
+            rpc_successes: 0,
+            banned_until: None,
+        }
+    }
+
+    /// The fraction of the outbound rpcs to the peer that timed out.
+    pub fn rpc_timeout_rate(&self) -> f64 {
+        let num_rpcs = self.rpc_timeouts + self.rpc_successes;
+        if num_rpcs == 0 {
+            0.0
+        } else {
+            self.rpc_timeouts as f64 / num_rpcs as f64
+        }
+    }
+}
+
+/// The reputations of the peers of a network, shared by the actors of all the connections.
+#[derive(Clone)]
+pub struct PeerReputations {
+    network_context: Arc<NetworkContext>,
+    config: PeerReputationConfig,
+    peers: Arc<Mutex<HashMap<PeerId, PeerReputation>>>,
+}
+
+impl PeerReputations {
+    pub fn new(network_context: Arc<NetworkContext>, config: PeerReputationConfig) -> Self {
+        Self {
+            network_context,
+            config,
+            peers: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    /// Lowers the score of `peer_id` for `misbehavior`, returning what to do with the peer.
+    pub fn report(&self, peer_id: PeerId, misbehavior: Misbehavior) -> ReputationAction {
+        self.report_at(peer_id, misbehavior, Instant::now())
+    }
+
+    fn report_at(
+        &self,
+        peer_id: PeerId,
+        misbehavior: Misbehavior,
+        now: Instant,
+    ) -> ReputationAction {
+        let config = &self.config;
+        let role = self.network_context.role();
+        counters::LIBRA_NETWORK_PEER_MISBEHAVIORS
+            .with_label_values(&[role.as_str(), misbehavior.as_str()])
+            .inc();
+        if !config.enabled {
+            return ReputationAction::None;
+        }
+
+        let mut peers = self.peers.lock().expect("Lock poisoned");
+        let reputation = peers
+            .entry(peer_id)
+            .or_insert_with(|| PeerReputation::new(config.max_score));
+        let penalty = match misbehavior {
+            Misbehavior::InvalidMessage => {
+                reputation.invalid_messages += 1;
+                config.invalid_message_penalty
+            }
+            Misbehavior::ProtocolViolation => {
+                reputation.protocol_violations += 1;
+                config.protocol_violation_penalty
+            }
+            Misbehavior::RpcTimeout => {
+                reputation.rpc_timeouts += 1;
+                config.rpc_timeout_penalty
+            }
+        };
+        reputation.score = reputation.score.saturating_sub(penalty);
+        let score = reputation.score;
+
+        let action = if score <= config.ban_threshold {
+            reputation.banned_until = Some(now + Duration::from_millis(config.ban_duration_ms));
+            // The peer starts over once its ban expires.
+            reputation.score = config.max_score;
+            ReputationAction::Ban
+        } else if score <= config.disconnect_threshold {
+            ReputationAction::Disconnect
+        } else {
+            ReputationAction::None
+        };
+
+        let action_label = match action {
+            ReputationAction::None => {
+                debug!(
+                    "{} Peer {} misbehaved ({}), score: {}",
+                    self.network_context,
+                    peer_id.short_str(),
+                    misbehavior.as_str(),
+                    score
+                );
+                return action;
+            }
+            ReputationAction::Disconnect => "disconnect",
+            ReputationAction::Ban => "ban",
+        };
+        counters::LIBRA_NETWORK_PEER_PUNISHMENTS
+            .with_label_values(&[role.as_str(), action_label])
+            .inc();
+        send_struct_log!(
+            network_log(network_events::PEER_REPUTATION, &self.network_context)
+                .data(network_events::TYPE, action_label)
+                .data("misbehavior", misbehavior.as_str())
+                .data("score", score)
+                .field(network_events::REMOTE_PEER, &peer_id)
+        );
+        warn!(
+            "{} Peer {} misbehaved ({}), score: {}, action: {}",
+            self.network_context,
+            peer_id.short_str(),
+            misbehavior.as_str(),
+            score,
+            action_label
+        );
+        action
+    }
+
+    /// Raises the score of `peer_id` for answering an outbound rpc in time.
+    pub fn record_rpc_success(&self, peer_id: PeerId) {
+        let config = &self.config;
+        if !config.enabled {
+            return;
+        }
+        let mut peers = self.peers.lock().expect("Lock poisoned");
+        let reputation = peers
+            .entry(peer_id)
+            .or_insert_with(|| PeerReputation::new(config.max_score));
+        reputation.rpc_successes += 1;
+        reputation.score = reputation
+            .score
+            .saturating_add(config.rpc_success_reward)
+            .min(config.max_score);
+    }
+
+    /// Whether `peer_id` is banned, i.e. must not be connected to.
+    pub fn is_banned(&self, peer_id: PeerId) -> bool {
+        self.is_banned_at(peer_id, Instant::now())
+    }
+
+    fn is_banned_at(&self, peer_id: PeerId, now: Instant) -> bool {
+        let mut peers = self.peers.lock().expect("Lock poisoned");
+        match peers
+            .get(&peer_id)
+            .and_then(|reputation| reputation.banned_until)
+        {
+            Some(banned_until) if now < banned_until => true,
+            // The peer started over when it was banned, so there's nothing left to remember.
+            Some(_) => {
+                peers.remove(&peer_id);
+                false
+            }
+            None => false,
+        }
+    }
+
+    /// The network the peers are scored on.
+    pub fn network_context(&self) -> &NetworkContext {
+        &self.network_context
+    }
+
+    /// The reputation of `peer_id`, if it was ever scored.
+    pub fn get(&self, peer_id: PeerId) -> Option<PeerReputation> {
+        self.peers
+            .lock()
+            .expect("Lock poisoned")
+            .get(&peer_id)
+            .cloned()
+    }
+
+    /// The reputations of all the peers scored and not yet forgotten.
+    pub fn all(&self) -> HashMap<PeerId, PeerReputation> {
+        self.peers.lock().expect("Lock poisoned").clone()
+    }
+
+    /// Forgets the reputation of `peer_id` once it's disconnected, so that reputations aren't
+    /// kept for every peer ever connected to. Those of peers that are banned or were disconnected
+    /// for misbehaving are kept, so that they don't start over by reconnecting.
+    pub fn forget(&self, peer_id: PeerId) {
+        let disconnect_threshold = self.config.disconnect_threshold;
+        let mut peers = self.peers.lock().expect("Lock poisoned");
+        let punished = peers.get(&peer_id).map_or(false, |reputation| {
+            reputation.banned_until.is_some() || reputation.score <= disconnect_threshold
+        });
+        if !punished {
+            peers.remove(&peer_id);
+        }
+    }
+}
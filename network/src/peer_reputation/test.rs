@@ -0,0 +1,129 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::peer_reputation::{Misbehavior, PeerReputations, ReputationAction};
+use libra_config::{config::PeerReputationConfig, network_id::NetworkContext};
+use libra_types::PeerId;
+use std::time::{Duration, Instant};
+
+fn test_config() -> PeerReputationConfig {
+    PeerReputationConfig {
+        enabled: true,
+        max_score: 100,
+        invalid_message_penalty: 30,
+        protocol_violation_penalty: 50,
+        rpc_timeout_penalty: 10,
+        rpc_success_reward: 5,
+        disconnect_threshold: 0,
+        ban_threshold: -50,
+        ban_duration_ms: 1000,
+    }
+}
+
+#[test]
+fn test_misbehaviors_lower_score() {
+    let reputations = PeerReputations::new(NetworkContext::mock(), test_config());
+    let peer_id = PeerId::random();
+    assert!(reputations.get(peer_id).is_none());
+
+    assert_eq!(
+        reputations.report(peer_id, Misbehavior::InvalidMessage),
+        ReputationAction::None
+    );
+    assert_eq!(
+        reputations.report(peer_id, Misbehavior::RpcTimeout),
+        ReputationAction::None
+    );
+    let reputation = reputations.get(peer_id).unwrap();
+    assert_eq!(reputation.score, 60);
+    assert_eq!(reputation.invalid_messages, 1);
+    assert_eq!(reputation.rpc_timeouts, 1);
+    assert_eq!(reputation.protocol_violations, 0);
+
+    // Other peers aren't affected.
+    assert!(reputations.get(PeerId::random()).is_none());
+}
+
+#[test]
+fn test_rpc_successes_restore_score() {
+    let reputations = PeerReputations::new(NetworkContext::mock(), test_config());
+    let peer_id = PeerId::random();
+    reputations.report(peer_id, Misbehavior::RpcTimeout);
+    reputations.record_rpc_success(peer_id);
+    assert_eq!(reputations.get(peer_id).unwrap().score, 95);
+
+    // The score is capped.
+    reputations.record_rpc_success(peer_id);
+    reputations.record_rpc_success(peer_id);
+    let reputation = reputations.get(peer_id).unwrap();
+    assert_eq!(reputation.score, 100);
+    assert_eq!(reputation.rpc_successes, 3);
+    assert!((reputation.rpc_timeout_rate() - 0.25).abs() < f64::EPSILON);
+}
+
+#[test]
+fn test_disconnect_then_ban() {
+    let reputations = PeerReputations::new(NetworkContext::mock(), test_config());
+    let peer_id = PeerId::random();
+    let now = Instant::now();
+
+    assert_eq!(
+        reputations.report_at(peer_id, Misbehavior::ProtocolViolation, now),
+        ReputationAction::None
+    );
+    assert_eq!(
+        reputations.report_at(peer_id, Misbehavior::ProtocolViolation, now),
+        ReputationAction::Disconnect
+    );
+    assert!(!reputations.is_banned_at(peer_id, now));
+    assert_eq!(
+        reputations.report_at(peer_id, Misbehavior::ProtocolViolation, now),
+        ReputationAction::Ban
+    );
+    assert!(reputations.is_banned_at(peer_id, now));
+    assert!(reputations.is_banned_at(peer_id, now + Duration::from_millis(999)));
+
+    // Banned peers are remembered after disconnecting, until the ban expires.
+    reputations.forget(peer_id);
+    assert!(reputations.is_banned_at(peer_id, now));
+    assert!(!reputations.is_banned_at(peer_id, now + Duration::from_millis(1000)));
+    assert!(reputations.get(peer_id).is_none());
+}
+
+#[test]
+fn test_forget_disconnected_peers() {
+    let reputations = PeerReputations::new(NetworkContext::mock(), test_config());
+    let peer_id = PeerId::random();
+    reputations.report(peer_id, Misbehavior::InvalidMessage);
+    reputations.forget(peer_id);
+    assert!(reputations.get(peer_id).is_none());
+
+    // Peers disconnected for misbehaving don't start over by reconnecting.
+    reputations.report(peer_id, Misbehavior::ProtocolViolation);
+    assert_eq!(
+        reputations.report(peer_id, Misbehavior::ProtocolViolation),
+        ReputationAction::Disconnect
+    );
+    reputations.forget(peer_id);
+    assert_eq!(reputations.get(peer_id).unwrap().score, 0);
+}
+
+#[test]
+fn test_disabled() {
+    let reputations = PeerReputations::new(
+        NetworkContext::mock(),
+        PeerReputationConfig {
+            enabled: false,
+            ..test_config()
+        },
+    );
+    let peer_id = PeerId::random();
+    for _ in 0..10 {
+        assert_eq!(
+            reputations.report(peer_id, Misbehavior::ProtocolViolation),
+            ReputationAction::None
+        );
+    }
+    assert!(!reputations.is_banned(peer_id));
+    assert!(reputations.get(peer_id).is_none());
+}
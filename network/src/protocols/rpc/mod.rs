@@ -49,6 +49,7 @@ use crate::{
         RESPONSE_LABEL, SENT_LABEL,
     },
     peer::{PeerHandle, PeerNotification},
+    peer_reputation::{Misbehavior, PeerReputations, ReputationAction},
     protocols::wire::messaging::v1::{
         NetworkMessage, Priority, RequestId, RpcRequest, RpcResponse,
     },
@@ -190,10 +191,13 @@ pub struct Rpc {
     /// The maximum number of concurrent inbound rpc requests that we will
     /// service before back-pressure kicks in.
     max_concurrent_inbound_rpcs: u32,
+    /// Reputations of the peers, updated on the outcome of each outbound rpc.
+    reputations: PeerReputations,
 }
 
 impl Rpc {
     /// Create a new instance of the [`Rpc`] protocol actor.
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         peer_handle: PeerHandle,
         requests_rx: channel::Receiver<OutboundRpcRequest>,
@@ -202,6 +206,7 @@ impl Rpc {
         inbound_rpc_timeout: Duration,
         max_concurrent_outbound_rpcs: u32,
         max_concurrent_inbound_rpcs: u32,
+        reputations: PeerReputations,
     ) -> Self {
         Self {
             request_id_gen: RequestIdGenerator::new(peer_handle.peer_id()),
@@ -213,6 +218,7 @@ impl Rpc {
             pending_outbound_rpcs: HashMap::new(),
             max_concurrent_outbound_rpcs,
             max_concurrent_inbound_rpcs,
+            reputations,
         }
     }
 
@@ -392,6 +398,8 @@ impl Rpc {
 
         let peer_handle = self.peer_handle.clone();
         let peer_id_str = peer_handle.peer_id().short_str();
+        let mut reputation_peer_handle = self.peer_handle.clone();
+        let reputations = self.reputations.clone();
 
         // Generate and assign request id to this RPC.
         let request_id = self.request_id_gen.next();
@@ -431,6 +439,7 @@ impl Rpc {
                             request_id, peer_id_str, err
                         );
                     }
+                    update_reputation(&reputations, &mut reputation_peer_handle, &res).await;
                     // Propagate the results to the rpc client layer.
                     if res_tx.send(res).is_err() {
                         counters::LIBRA_NETWORK_RPC_MESSAGES
@@ -454,6 +463,24 @@ impl Rpc {
     }
 }
 
+// Scores the peer on the outcome of an outbound rpc, disconnecting it if it times out too often.
+async fn update_reputation(
+    reputations: &PeerReputations,
+    peer_handle: &mut PeerHandle,
+    res: &Result<Bytes, RpcError>,
+) {
+    let peer_id = peer_handle.peer_id();
+    match res {
+        Ok(_) => reputations.record_rpc_success(peer_id),
+        Err(RpcError::TimedOut) => {
+            if reputations.report(peer_id, Misbehavior::RpcTimeout) != ReputationAction::None {
+                peer_handle.disconnect().await;
+            }
+        }
+        Err(_) => (),
+    }
+}
+
 async fn handle_outbound_rpc_inner(
     mut peer_handle: PeerHandle,
     request_id: RequestId,
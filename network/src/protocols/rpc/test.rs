@@ -14,6 +14,7 @@ use crate::{
 };
 use anyhow::anyhow;
 use futures::future::join;
+use libra_config::{config::PeerReputationConfig, network_id::NetworkContext};
 use libra_types::PeerId;
 use serial_test::serial;
 use tokio::runtime::{Handle, Runtime};
@@ -48,6 +49,7 @@ fn start_rpc_actor(
         Duration::from_secs(1), // 1 second inbound rpc timeout.
         10,                     // max_concurrent_outbound_rpcs
         10,                     // max_concurrent_inbound_rpcs
+        PeerReputations::new(NetworkContext::mock(), PeerReputationConfig::default()),
     );
     executor.spawn(rpc.start());
     (rpc_requests_tx, rpc_notifs_rx, peer_reqs_rx, peer_notifs_tx)
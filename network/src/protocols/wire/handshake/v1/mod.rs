@@ -10,10 +10,11 @@
 //! supported over that messaging protocol. On receipt, both ends will determine the highest
 //! intersecting messaging protocol version and use that for the remainder of the session.
 
+use anyhow::format_err;
 use libra_config::network_id::NetworkId;
 use libra_types::chain_id::ChainId;
 use serde::{Deserialize, Serialize};
-use std::{collections::BTreeMap, convert::TryInto, fmt, iter::Iterator};
+use std::{collections::BTreeMap, convert::TryInto, fmt, iter::Iterator, str::FromStr};
 
 #[cfg(test)]
 mod test;
@@ -45,6 +46,23 @@ impl ProtocolId {
     }
 }
 
+impl FromStr for ProtocolId {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> anyhow::Result<Self> {
+        use ProtocolId::*;
+        Ok(match s {
+            "ConsensusRpc" => ConsensusRpc,
+            "ConsensusDirectSend" => ConsensusDirectSend,
+            "MempoolDirectSend" => MempoolDirectSend,
+            "StateSynchronizerDirectSend" => StateSynchronizerDirectSend,
+            "DiscoveryDirectSend" => DiscoveryDirectSend,
+            "HealthCheckerRpc" => HealthCheckerRpc,
+            _ => return Err(format_err!("Unknown protocol: {}", s)),
+        })
+    }
+}
+
 impl fmt::Display for ProtocolId {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         write!(f, "{}", self.as_str())
@@ -27,6 +27,18 @@ fn protocols_to_from_vec() {
     );
 }
 
+#[test]
+fn protocol_names() {
+    // Protocols are numbered from 0, so this covers them all.
+    let mut num_protocols = 0u8;
+    while let Ok(protocol) = lcs::from_bytes::<ProtocolId>(&[num_protocols]) {
+        assert_eq!(protocol.as_str().parse::<ProtocolId>().unwrap(), protocol);
+        num_protocols += 1;
+    }
+    assert!(num_protocols > 0);
+    assert!("ConsensusRPC".parse::<ProtocolId>().is_err());
+}
+
 #[test]
 fn represents_same_network() {
     let network_id = NetworkId::Private("h1".to_string());
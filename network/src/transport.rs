@@ -54,6 +54,8 @@ pub const LIBRA_TCP_TRANSPORT: tcp::TcpTransport = tcp::TcpTransport {
     keepalive: None,
     // Use TCP_NODELAY for libra tcp connections.
     nodelay: Some(true),
+    // Dial peers directly, unless the network config sets a proxy.
+    proxy: None,
 };
 
 /// A trait alias for "socket-like" things.
@@ -259,13 +259,14 @@ mod test {
     use libra_config::utils;
     use libra_crypto::{ed25519::Ed25519PrivateKey, HashValue, PrivateKey, Uniform};
     use libra_json_rpc::test_bootstrap;
+    use libra_mempool::MempoolClientRequest;
     use libra_types::{
         account_address::AccountAddress,
         account_config::{AccountResource, BalanceResource},
         account_state::AccountState,
         account_state_blob::{AccountStateBlob, AccountStateWithProof},
         block_info::BlockInfo,
-        contract_event::ContractEvent,
+        contract_event::{ContractEvent, EventWithProof},
         epoch_change::EpochChangeProof,
         event::{EventHandle, EventKey},
         ledger_info::{LedgerInfo, LedgerInfoWithSignatures},
@@ -407,7 +408,9 @@ mod test {
         if mock_validator {
             // Provide a VMValidator to the runtime.
             server.spawn(async move {
-                while let Some((txn, cb)) = mp_events.next().await {
+                while let Some(MempoolClientRequest::SubmitTransaction(txn, cb)) =
+                    mp_events.next().await
+                {
                     let vm_status = MockVMValidator.validate_transaction(txn).unwrap().status();
                     let result = if vm_status.is_some() {
                         (MempoolStatus::new(MempoolStatusCode::VmError), vm_status)
@@ -527,6 +530,16 @@ mod test {
             unimplemented!()
         }
 
+        fn get_events_with_proofs(
+            &self,
+            _event_key: &EventKey,
+            _start: u64,
+            _limit: u64,
+            _ledger_version: Version,
+        ) -> Result<Vec<EventWithProof>> {
+            unimplemented!()
+        }
+
         fn get_latest_account_state(
             &self,
             _address: AccountAddress,
@@ -31,7 +31,7 @@ use libra_types::{
 };
 use network::protocols::network::Event;
 use std::{
-    collections::{BTreeMap, HashMap},
+    collections::{BTreeMap, BTreeSet, HashMap},
     ops::Bound::Included,
     time::{Duration, SystemTime, UNIX_EPOCH},
 };
@@ -128,6 +128,10 @@ impl PendingLedgerInfos {
     fn target_li(&self) -> Option<LedgerInfoWithSignatures> {
         self.target_li.clone()
     }
+
+    fn highest_li(&self) -> Option<LedgerInfoWithSignatures> {
+        self.pending_li_queue.values().next_back().cloned()
+    }
 }
 
 /// Coordination of synchronization process is driven by SyncCoordinator, which `start()` function
@@ -138,6 +142,8 @@ impl PendingLedgerInfos {
 /// higher within the timeout interval).
 /// * Validator: the ChunkRequests are generated on demand for a specific target LedgerInfo to
 /// synchronize to.
+/// Once a LedgerInfo in the current epoch is known to sync to, the chunks after the next one are
+/// requested from several peers in parallel, and applied in order as they come.
 pub(crate) struct SyncCoordinator<T> {
     // used to process client requests
     client_events: mpsc::UnboundedReceiver<CoordinatorMessage>,
@@ -170,6 +176,10 @@ pub(crate) struct SyncCoordinator<T> {
     // queue of incoming long polling requests
     // peer will be notified about new chunk of transactions if it's available before expiry time
     subscriptions: HashMap<PeerNetworkId, PendingRequestInfo>,
+    // Versions of the chunks requested in parallel, ahead of the next one
+    parallel_requests: BTreeSet<Version>,
+    // Chunks received ahead of the local storage: (k, v) = (first version, (peer, response))
+    pending_chunks: BTreeMap<Version, (PeerNetworkId, GetChunkResponse)>,
     executor_proxy: T,
 }
 
@@ -202,6 +212,8 @@ impl<T: ExecutorProxyTrait> SyncCoordinator<T> {
             network_senders,
             peer_manager: PeerManager::new(upstream_config),
             subscriptions: HashMap::new(),
+            parallel_requests: BTreeSet::new(),
+            pending_chunks: BTreeMap::new(),
             sync_request: None,
             initialization_listener: None,
             executor_proxy,
@@ -283,32 +295,68 @@ impl<T: ExecutorProxyTrait> SyncCoordinator<T> {
                 }
             }
             StateSynchronizerMsg::GetChunkResponse(response) => {
-                if let Err(err) = self
-                    .process_chunk_response(&peer.clone(), *response.clone())
-                    .await
-                {
-                    // security log
-                    send_struct_log!(security_log(security_events::STATE_SYNC_INVALID_CHUNK)
-                        .data("from_peer", &peer)
-                        .data_display("error", &err)
-                        .data("chunk", &response));
-
-                    // TODO update dashboards to ID peers using PeerNetworkID, not just peer ID
-                    counters::APPLY_CHUNK_FAILURE
-                        .with_label_values(&[&*peer.peer_id().to_string()])
-                        .inc();
-                } else {
-                    self.peer_manager
-                        .update_score(&peer, PeerScoreUpdateType::Success);
-                    // TODO update dashboards to ID peers using PeerNetworkID, not just peer ID
-                    counters::APPLY_CHUNK_SUCCESS
-                        .with_label_values(&[&*peer.peer_id().to_string()])
-                        .inc();
-                }
+                self.process_chunk_response(peer, *response).await;
             }
         }
     }
 
+    /// Applies the chunk in the response, or keeps it for later if it's ahead of the local
+    /// storage because it was requested in parallel. Then applies the chunks kept that are next in
+    /// line.
+    async fn process_chunk_response(&mut self, peer: PeerNetworkId, response: GetChunkResponse) {
+        counters::RESPONSES_RECEIVED
+            .with_label_values(&[&*peer.peer_id().to_string()])
+            .inc();
+        if let Some(start_version) = response.txn_list_with_proof.first_transaction_version {
+            self.peer_manager.process_response(start_version, &peer);
+            if start_version > self.local_state.highest_version_in_local_storage() + 1
+                && self.parallel_requests.contains(&start_version)
+                && !self.pending_chunks.contains_key(&start_version)
+            {
+                debug!(
+                    "[state sync] Keeping chunk starting at version {} from {:?} until the chunks before it are applied",
+                    start_version, peer
+                );
+                self.pending_chunks.insert(start_version, (peer, response));
+                counters::PENDING_CHUNKS.set(self.pending_chunks.len() as i64);
+                return;
+            }
+        }
+
+        let mut next_chunk = Some((peer, response));
+        while let Some((peer, response)) = next_chunk.take() {
+            self.apply_chunk_response(peer, response).await;
+            // The chunks kept that are now behind the local storage are of no use anymore.
+            let next_version = self.local_state.highest_version_in_local_storage() + 1;
+            self.pending_chunks = self.pending_chunks.split_off(&next_version);
+            next_chunk = self.pending_chunks.remove(&next_version);
+        }
+        counters::PENDING_CHUNKS.set(self.pending_chunks.len() as i64);
+    }
+
+    /// Applies the chunk in the response, scoring the peer by whether it's valid.
+    async fn apply_chunk_response(&mut self, peer: PeerNetworkId, response: GetChunkResponse) {
+        if let Err(err) = self.apply_chunk(&peer, response.clone()).await {
+            // security log
+            send_struct_log!(security_log(security_events::STATE_SYNC_INVALID_CHUNK)
+                .data("from_peer", &peer)
+                .data_display("error", &err)
+                .data("chunk", &response));
+
+            // TODO update dashboards to ID peers using PeerNetworkID, not just peer ID
+            counters::APPLY_CHUNK_FAILURE
+                .with_label_values(&[&*peer.peer_id().to_string()])
+                .inc();
+        } else {
+            self.peer_manager
+                .update_score(&peer, PeerScoreUpdateType::Success);
+            // TODO update dashboards to ID peers using PeerNetworkID, not just peer ID
+            counters::APPLY_CHUNK_SUCCESS
+                .with_label_values(&[&*peer.peer_id().to_string()])
+                .inc();
+        }
+    }
+
     /// Sync up coordinator state with the local storage
     /// and updates the pending ledger info accordingly
     fn sync_state_with_local_storage(&mut self) -> Result<()> {
@@ -458,6 +506,7 @@ impl<T: ExecutorProxyTrait> SyncCoordinator<T> {
 
         self.check_subscriptions();
         self.peer_manager.remove_requests(local_version);
+        self.parallel_requests = self.parallel_requests.split_off(&(local_version + 1));
 
         if let Some(mut req) = self.sync_request.as_mut() {
             req.last_progress_tst = SystemTime::now();
@@ -696,14 +745,11 @@ impl<T: ExecutorProxyTrait> SyncCoordinator<T> {
     /// * Issue a request for the next chunk.
     /// * Validate and execute the transactions.
     /// * Notify the clients in case a sync request has been completed.
-    async fn process_chunk_response(
+    async fn apply_chunk(
         &mut self,
         peer: &PeerNetworkId,
         response: GetChunkResponse,
     ) -> Result<()> {
-        counters::RESPONSES_RECEIVED
-            .with_label_values(&[&*peer.peer_id().to_string()])
-            .inc();
         debug!("[state sync] Processing chunk response {}", response);
         let txn_list_with_proof = response.txn_list_with_proof.clone();
         let known_version = self.local_state.highest_version_in_local_storage();
@@ -942,11 +988,13 @@ impl<T: ExecutorProxyTrait> SyncCoordinator<T> {
     /// Sends a chunk request with a given `known_version` and `known_epoch`
     /// (might be chosen optimistically).
     /// The request includes a target for Validator and a non-zero timeout for a FullNode.
+    /// The chunks after it are requested in parallel when possible.
     fn send_chunk_request(&mut self, known_version: u64, known_epoch: u64) -> Result<()> {
-        let peer = self
-            .peer_manager
-            .pick_peer()
-            .ok_or_else(|| format_err!("No peers found for chunk request."))?;
+        if self.pending_chunks.contains_key(&(known_version + 1))
+            || self.is_parallel_request_in_flight(known_version + 1)
+        {
+            return self.send_parallel_chunk_requests(known_version);
+        }
 
         let target = if !self.is_initialized() {
             let waypoint_version = self.waypoint.version();
@@ -975,10 +1023,80 @@ impl<T: ExecutorProxyTrait> SyncCoordinator<T> {
         };
 
         let req = GetChunkRequest::new(known_version, known_epoch, self.config.chunk_limit, target);
+        self.send_request(req)?;
+        self.send_parallel_chunk_requests(known_version)
+    }
+
+    /// Requests the chunks after the one following `known_version` from the upstream peers, for
+    /// up to `max_concurrent_chunk_requests` chunks to be in flight at once. That's only done when
+    /// the LedgerInfo to sync to is in the current epoch, for all the chunks to be verifiable
+    /// against it.
+    fn send_parallel_chunk_requests(&mut self, known_version: u64) -> Result<()> {
+        let target_li = match self.parallel_target_li() {
+            Some(target_li) => target_li,
+            None => return Ok(()),
+        };
+        let target_version = target_li.ledger_info().version();
+        let chunk_limit = self.config.chunk_limit;
+        for i in 1..self.config.max_concurrent_chunk_requests {
+            let chunk_known_version = known_version + i * chunk_limit;
+            if chunk_known_version >= target_version {
+                break;
+            }
+            let chunk_start_version = chunk_known_version + 1;
+            if self.pending_chunks.contains_key(&chunk_start_version)
+                || self.is_parallel_request_in_flight(chunk_start_version)
+            {
+                continue;
+            }
+            let req = GetChunkRequest::new(
+                chunk_known_version,
+                self.local_state.epoch(),
+                chunk_limit,
+                TargetType::TargetLedgerInfo(target_li.clone()),
+            );
+            self.send_request(req)?;
+            self.parallel_requests.insert(chunk_start_version);
+        }
+        Ok(())
+    }
+
+    /// The LedgerInfo to request chunks in parallel against, if any.
+    fn parallel_target_li(&self) -> Option<LedgerInfoWithSignatures> {
+        if !self.is_initialized() {
+            return None;
+        }
+        let target_li = match self.sync_request.as_ref() {
+            Some(sync_req) => Some(sync_req.target.clone()),
+            None => self.pending_ledger_infos.highest_li(),
+        }?;
+        if target_li.ledger_info().epoch() == self.local_state.epoch() {
+            Some(target_li)
+        } else {
+            None
+        }
+    }
+
+    fn is_parallel_request_in_flight(&self, version: Version) -> bool {
+        self.parallel_requests.contains(&version)
+            && self
+                .peer_manager
+                .get_last_request_time(version)
+                .and_then(|tst| tst.checked_add(self.retry_timeout))
+                .map_or(false, |tst| SystemTime::now().duration_since(tst).is_err())
+    }
+
+    /// Sends the request to a peer picked by the peer manager.
+    fn send_request(&mut self, req: GetChunkRequest) -> Result<()> {
+        let peer = self
+            .peer_manager
+            .pick_peer()
+            .ok_or_else(|| format_err!("No peers found for chunk request."))?;
         debug!(
             "[state sync] request next chunk. peer_id: {:?}, chunk req: {}",
             peer, req,
         );
+        let known_version = req.known_version;
         let msg = StateSynchronizerMsg::GetChunkRequest(Box::new(req));
         self.peer_manager
             .process_request(known_version + 1, peer.clone());
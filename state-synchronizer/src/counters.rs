@@ -69,6 +69,16 @@ pub static STATE_SYNC_TXN_REPLAYED: Lazy<IntCounter> = Lazy::new(|| {
     .unwrap()
 });
 
+/// Number of chunks received ahead of the local storage, kept until the chunks before them are
+/// applied.
+pub static PENDING_CHUNKS: Lazy<IntGauge> = Lazy::new(|| {
+    register_int_gauge!(
+        "libra_state_sync_pending_chunks",
+        "Number of chunks received ahead of the local storage, waiting to be applied"
+    )
+    .unwrap()
+});
+
 /// Number of peers that are currently active and upstream.
 /// They are the set of nodes a node can make sync requests to
 pub static ACTIVE_UPSTREAM_PEERS: Lazy<IntGauge> = Lazy::new(|| {
@@ -2,7 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::SynchronizerState;
-use anyhow::{format_err, Result};
+use anyhow::{ensure, format_err, Result};
 use executor_types::{ChunkExecutor, ExecutedTrees};
 use itertools::Itertools;
 use libra_types::{
@@ -10,7 +10,10 @@ use libra_types::{
     contract_event::ContractEvent,
     ledger_info::LedgerInfoWithSignatures,
     move_resource::MoveStorage,
-    on_chain_config::{config_address, OnChainConfigPayload, ON_CHAIN_CONFIG_REGISTRY},
+    on_chain_config::{
+        config_address, OnChainConfigPayload, ON_CHAIN_CONFIG_REGISTRY,
+        OPTIONAL_ON_CHAIN_CONFIG_REGISTRY,
+    },
     transaction::TransactionListWithProof,
 };
 use std::{collections::HashSet, convert::TryFrom, sync::Arc};
@@ -92,17 +95,30 @@ impl ExecutorProxy {
             .map(|config_id| config_id.access_path())
             .collect();
         let configs = storage.batch_fetch_resources(access_paths)?;
-        let epoch = storage
+        let config_account = storage
             .get_latest_account_state(config_address())?
-            .map(|blob| {
-                AccountState::try_from(&blob).and_then(|state| {
-                    Ok(state
-                        .get_configuration_resource()?
-                        .ok_or_else(|| format_err!("ConfigurationResource does not exist"))?
-                        .epoch())
-                })
+            .ok_or_else(|| format_err!("Failed to fetch ConfigurationResource"))?;
+        let config_account = AccountState::try_from(&config_account)?;
+        let epoch = config_account
+            .get_configuration_resource()?
+            .ok_or_else(|| format_err!("ConfigurationResource does not exist"))?
+            .epoch();
+        // configs that aren't published on chain are left out, but failing to read them is an error
+        let optional_configs = OPTIONAL_ON_CHAIN_CONFIG_REGISTRY
+            .iter()
+            .map(|config_id| {
+                let access_path = config_id.access_path();
+                ensure!(
+                    access_path.address == config_address(),
+                    "optional on-chain config {:?} is not under the config address",
+                    config_id
+                );
+                Ok(config_account
+                    .get(&access_path.path)
+                    .map(|config| (*config_id, config.clone())))
             })
-            .ok_or_else(|| format_err!("Failed to fetch ConfigurationResource"))??;
+            .filter_map(Result::transpose)
+            .collect::<Result<Vec<_>>>()?;
 
         Ok(OnChainConfigPayload::new(
             epoch,
@@ -111,6 +127,7 @@ impl ExecutorProxy {
                     .iter()
                     .cloned()
                     .zip_eq(configs)
+                    .chain(optional_configs)
                     .collect(),
             ),
         ))
@@ -195,14 +212,8 @@ impl ExecutorProxyTrait for ExecutorProxy {
         let changed_configs = new_configs
             .configs()
             .iter()
-            .filter(|(id, cfg)| {
-                &self
-                    .on_chain_configs
-                    .configs()
-                    .get(id)
-                    .expect("missing on-chain config value in local copy")
-                    != cfg
-            })
+            // optional configs may be missing from the local copy, if they are newly published
+            .filter(|(id, cfg)| self.on_chain_configs.configs().get(id) != Some(cfg))
             .map(|(id, _)| *id)
             .collect::<HashSet<_>>();
 
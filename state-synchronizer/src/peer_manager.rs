@@ -16,16 +16,29 @@ use std::{
 
 const MAX_SCORE: f64 = 100.0;
 const MIN_SCORE: f64 = 1.0;
+// Weight of the latest response in the average response latency of a peer
+const LATENCY_SMOOTHING: f64 = 0.2;
 
 #[derive(Default, Debug, Clone)]
 pub struct PeerInfo {
     is_alive: bool,
     score: f64,
+    // moving average of the time it takes the peer to respond to a chunk request, in seconds
+    latency_s: f64,
 }
 
 impl PeerInfo {
     pub fn new(is_alive: bool, score: f64) -> Self {
-        Self { is_alive, score }
+        Self {
+            is_alive,
+            score,
+            latency_s: 0.0,
+        }
+    }
+
+    // Peers are picked based on their score, and slow peers less so.
+    fn weight(&self) -> f64 {
+        self.score / (1.0 + self.latency_s)
     }
 }
 
@@ -131,9 +144,31 @@ impl PeerManager {
         }
     }
 
+    /// Records how long `peer` took to respond to the request for the chunk starting at `version`,
+    /// if the request was last sent to it.
+    pub fn process_response(&mut self, version: u64, peer: &PeerNetworkId) {
+        let request_time = match self.requests.get(&version) {
+            Some(request) if &request.last_request_peer == peer => request.last_request_time,
+            _ => return,
+        };
+        let latency_s = match SystemTime::now().duration_since(request_time) {
+            Ok(latency) => latency.as_secs_f64(),
+            Err(_) => return,
+        };
+        if let Some(peer_info) = self.peers.get_mut(peer) {
+            peer_info.latency_s = if peer_info.latency_s > 0.0 {
+                (1.0 - LATENCY_SMOOTHING) * peer_info.latency_s + LATENCY_SMOOTHING * latency_s
+            } else {
+                latency_s
+            };
+            self.update_peer_selection_data();
+        }
+    }
+
     // Updates the information used to select a peer to send a chunk request to:
     // * eligible_peers
-    // * weighted_index: the chance that a peer is selected from `eligible_peers` is weighted by its score
+    // * weighted_index: the chance that a peer is selected from `eligible_peers` is weighted by its
+    // score and response latency
     fn update_peer_selection_data(&mut self) {
         let active_peers = self.get_active_upstream_peers();
         counters::ACTIVE_UPSTREAM_PEERS.set(active_peers.len() as i64);
@@ -144,7 +179,7 @@ impl PeerManager {
             .into_iter()
             .map(|(peer, peer_info)| {
                 eligible_peers.push(peer.clone());
-                peer_info.score
+                peer_info.weight()
             })
             .collect();
         self.eligible_peers = eligible_peers;
@@ -252,4 +287,9 @@ impl PeerManager {
     pub fn peer_score(&self, peer: &PeerNetworkId) -> Option<f64> {
         self.peers.get(peer).map(|p| p.score)
     }
+
+    #[cfg(test)]
+    pub fn peer_latency_s(&self, peer: &PeerNetworkId) -> Option<f64> {
+        self.peers.get(peer).map(|p| p.latency_s)
+    }
 }
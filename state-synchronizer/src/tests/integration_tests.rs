@@ -298,6 +298,10 @@ impl SynchronizerEnv {
         let mut config = config_builder::test_config().0;
         config.base.role = role;
         config.state_sync.sync_request_timeout_ms = timeout_ms;
+        if mock_network {
+            // the tests delivering messages one by one expect the chunks to be requested serially
+            config.state_sync.max_concurrent_chunk_requests = 1;
+        }
 
         let network = config.validator_network.unwrap();
         let network_id = if role.is_validator() {
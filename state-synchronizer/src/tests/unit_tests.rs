@@ -3,7 +3,7 @@
 
 use crate::peer_manager::{PeerManager, PeerScoreUpdateType};
 use libra_config::config::{PeerNetworkId, UpstreamConfig};
-use std::collections::HashMap;
+use std::{collections::HashMap, thread, time::Duration};
 
 #[test]
 fn test_peer_manager() {
@@ -83,3 +83,23 @@ fn test_peer_manager_request_metadata() {
             <= peer_manager.get_last_request_time(1).unwrap()
     );
 }
+
+#[test]
+fn test_peer_manager_response_latency() {
+    let peers = vec![
+        PeerNetworkId::random_validator(),
+        PeerNetworkId::random_validator(),
+    ];
+    let mut peer_manager = PeerManager::new(UpstreamConfig::default());
+    for peer in peers.iter() {
+        peer_manager.enable_peer(peer.clone());
+    }
+
+    peer_manager.process_request(1, peers[0].clone());
+    thread::sleep(Duration::from_millis(10));
+    // Responses to requests sent to other peers aren't timed.
+    peer_manager.process_response(1, &peers[1]);
+    assert_eq!(peer_manager.peer_latency_s(&peers[1]), Some(0.0));
+    peer_manager.process_response(1, &peers[0]);
+    assert!(peer_manager.peer_latency_s(&peers[0]).unwrap() >= 0.01);
+}
@@ -3,9 +3,12 @@
 
 use crate::{
     backup_types::epoch_ending::manifest::{EpochEndingBackup, EpochEndingChunk},
+    metadata::{save_metadata, Metadata},
     storage::{BackupHandleRef, BackupStorage, FileHandle, ShellSafeName},
     utils::{
-        backup_service_client::BackupServiceClient, read_record_bytes::ReadRecordBytes,
+        backup_service_client::BackupServiceClient,
+        chunk_codec::{ChunkCodec, ChunkId},
+        read_record_bytes::ReadRecordBytes,
         should_cut_chunk, GlobalBackupOpt,
     },
 };
@@ -34,6 +37,7 @@ pub struct EpochEndingBackupController {
     max_chunk_size: usize,
     client: Arc<BackupServiceClient>,
     storage: Arc<dyn BackupStorage>,
+    chunk_codec: Arc<ChunkCodec>,
 }
 
 impl EpochEndingBackupController {
@@ -42,6 +46,7 @@ impl EpochEndingBackupController {
         global_opt: GlobalBackupOpt,
         client: Arc<BackupServiceClient>,
         storage: Arc<dyn BackupStorage>,
+        chunk_codec: Arc<ChunkCodec>,
     ) -> Self {
         Self {
             start_epoch: opt.start_epoch,
@@ -49,6 +54,7 @@ impl EpochEndingBackupController {
             max_chunk_size: global_opt.max_chunk_size,
             client,
             storage,
+            chunk_codec,
         }
     }
 
@@ -67,7 +73,7 @@ impl EpochEndingBackupController {
         let mut chunk_first_epoch: u64 = self.start_epoch;
 
         while let Some(record_bytes) = ledger_infos_file.read_record_bytes().await? {
-            if should_cut_chunk(&chunk_bytes, &record_bytes, self.max_chunk_size) {
+            if should_cut_chunk(chunk_bytes.len(), &record_bytes, self.max_chunk_size) {
                 println!("New chunk.");
                 let chunk = self
                     .write_chunk(
@@ -144,11 +150,21 @@ impl EpochEndingBackupController {
             .storage
             .create_for_write(backup_handle, &Self::chunk_name(first_epoch))
             .await?;
-        chunk_file.write_all(&chunk_bytes).await?;
+        chunk_file
+            .write_all(&self.chunk_codec.encode(
+                chunk_bytes,
+                &ChunkId::EpochEnding {
+                    first_epoch,
+                    last_epoch,
+                },
+            )?)
+            .await?;
+        chunk_file.shutdown().await?;
         Ok(EpochEndingChunk {
             first_epoch,
             last_epoch,
             ledger_infos: chunk_handle,
+            format: self.chunk_codec.format(),
         })
     }
 
@@ -171,6 +187,14 @@ impl EpochEndingBackupController {
         manifest_file
             .write_all(&serde_json::to_vec(&manifest)?)
             .await?;
+        manifest_file.shutdown().await?;
+
+        let metadata = Metadata::new_epoch_ending_backup(
+            manifest.first_epoch,
+            manifest.last_epoch,
+            manifest_handle.clone(),
+        );
+        save_metadata(&*self.storage, &metadata).await?;
 
         Ok(manifest_handle)
     }
@@ -1,7 +1,10 @@
 // Copyright (c) The Libra Core Contributors
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::storage::FileHandle;
+use crate::{
+    storage::FileHandle,
+    utils::chunk_codec::{ChunkFormat, ChunkId},
+};
 use anyhow::{ensure, Result};
 use libra_types::waypoint::Waypoint;
 use serde::{Deserialize, Serialize};
@@ -13,6 +16,18 @@ pub struct EpochEndingChunk {
     pub first_epoch: u64,
     pub last_epoch: u64,
     pub ledger_infos: FileHandle,
+    /// How `ledger_infos` is compressed and encrypted.
+    #[serde(default)]
+    pub format: ChunkFormat,
+}
+
+impl EpochEndingChunk {
+    pub fn id(&self) -> ChunkId {
+        ChunkId::EpochEnding {
+            first_epoch: self.first_epoch,
+            last_epoch: self.last_epoch,
+        }
+    }
 }
 
 /// Epoch ending backup manifest, representing epoch ending information in the
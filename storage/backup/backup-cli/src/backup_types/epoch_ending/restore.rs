@@ -2,14 +2,21 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
-    backup_types::epoch_ending::manifest::EpochEndingBackup,
+    backup_types::epoch_ending::manifest::{EpochEndingBackup, EpochEndingChunk},
     storage::{BackupStorage, FileHandle},
-    utils::read_record_bytes::ReadRecordBytes,
+    utils::{chunk_codec::ChunkCodec, run_ahead, GlobalRestoreOpt},
 };
 use anyhow::{anyhow, ensure, Result};
-use libra_types::{ledger_info::LedgerInfoWithSignatures, waypoint::Waypoint};
+use futures::TryStreamExt;
+use libra_types::{
+    epoch_change::Verifier,
+    epoch_state::EpochState,
+    ledger_info::{LedgerInfo, LedgerInfoWithSignatures},
+    transaction::Version,
+    waypoint::Waypoint,
+};
 use libradb::backup::restore_handler::RestoreHandler;
-use std::sync::Arc;
+use std::{collections::HashMap, sync::Arc};
 use structopt::StructOpt;
 use tokio::io::AsyncReadExt;
 
@@ -17,28 +24,54 @@ use tokio::io::AsyncReadExt;
 pub struct EpochEndingRestoreOpt {
     #[structopt(long = "epoch-ending-manifest")]
     pub manifest_handle: FileHandle,
+    #[structopt(
+        long = "trust-waypoint",
+        help = "Trusted waypoint of an epoch ending LedgerInfo. Can be repeated."
+    )]
+    pub trust_waypoints: Vec<Waypoint>,
 }
 
 pub struct EpochEndingRestoreController {
     storage: Arc<dyn BackupStorage>,
+    chunk_codec: Arc<ChunkCodec>,
     restore_handler: Arc<RestoreHandler>,
     manifest_handle: FileHandle,
+    trusted_waypoints: HashMap<Version, Waypoint>,
+    target_version: Version,
+    concurrent_downloads: usize,
 }
 
 impl EpochEndingRestoreController {
     pub fn new(
         opt: EpochEndingRestoreOpt,
+        global_opt: GlobalRestoreOpt,
         storage: Arc<dyn BackupStorage>,
+        chunk_codec: Arc<ChunkCodec>,
         restore_handler: Arc<RestoreHandler>,
     ) -> Self {
         Self {
             storage,
+            chunk_codec,
             restore_handler,
             manifest_handle: opt.manifest_handle,
+            trusted_waypoints: opt
+                .trust_waypoints
+                .into_iter()
+                .map(|wp| (wp.version(), wp))
+                .collect(),
+            target_version: global_opt.target_version(),
+            concurrent_downloads: global_opt.concurrent_downloads,
         }
     }
 
-    pub async fn run(self) -> Result<()> {
+    /// Restores the epoch ending LedgerInfos in the backup up to the target version, returning
+    /// all of them in epoch order, including those after the target version, which are verified
+    /// but not saved.
+    ///
+    /// A LedgerInfo is verified by matching a trusted waypoint, or by the signatures of the
+    /// validator set of its epoch, given by the verified LedgerInfo of the previous epoch. Those
+    /// before the first trusted waypoint can't be verified, so they are not saved either.
+    pub async fn run(self) -> Result<Vec<LedgerInfo>> {
         let mut manifest_bytes = Vec::new();
         self.storage
             .open_for_read(&self.manifest_handle)
@@ -50,17 +83,22 @@ impl EpochEndingRestoreController {
 
         let mut next_epoch = manifest.first_epoch;
         let mut waypoint_iter = manifest.waypoints.iter();
+        let mut restored = Vec::new();
+        // validator set of the next epoch, once a LedgerInfo is verified
+        let mut next_epoch_state: Option<EpochState> = None;
+
+        let chunks = run_ahead(manifest.chunks, self.concurrent_downloads, |chunk| {
+            Self::load_chunk(
+                Arc::clone(&self.storage),
+                Arc::clone(&self.chunk_codec),
+                chunk,
+            )
+        });
+        futures::pin_mut!(chunks);
 
-        for chunk in manifest.chunks {
-            let lis = self.read_chunk(chunk.ledger_infos).await?;
-            ensure!(
-                chunk.first_epoch + lis.len() as u64 == chunk.last_epoch + 1,
-                "Number of items in chunks doesn't match that in manifest. first_epoch: {}, last_epoch: {}, items in chunk: {}",
-                chunk.first_epoch,
-                chunk.last_epoch,
-                lis.len(),
-            );
-            // verify
+        while let Some(lis) = chunks.try_next().await? {
+            let mut num_unverified = 0;
+            // verify, in order, since each LedgerInfo is checked against the previous epoch
             for li in lis.iter() {
                 ensure!(
                     li.ledger_info().epoch() == next_epoch,
@@ -72,33 +110,69 @@ impl EpochEndingRestoreController {
                     anyhow!("More LedgerInfo's found than waypoints in manifest.")
                 })?;
                 let wp_li = Waypoint::new_epoch_boundary(li.ledger_info())?;
-                // TODO: verify signature on li
                 ensure!(
                     *wp_manifest == wp_li,
                     "Waypoints don't match. In manifest: {}, In chunk: {}",
                     wp_manifest,
                     wp_li,
                 );
+                if let Some(wp_trusted) = self.trusted_waypoints.get(&wp_li.version()) {
+                    ensure!(
+                        *wp_trusted == wp_li,
+                        "Waypoints don't match. In backup: {}, trusted: {}",
+                        wp_li,
+                        wp_trusted,
+                    );
+                } else if let Some(epoch_state) = &next_epoch_state {
+                    epoch_state.verify(li)?;
+                } else {
+                    num_unverified += 1;
+                    next_epoch += 1;
+                    continue;
+                }
+                next_epoch_state = Some(
+                    li.ledger_info()
+                        .next_epoch_state()
+                        .ok_or_else(|| {
+                            anyhow!("LedgerInfo doesn't end an epoch: {}", li.ledger_info())
+                        })?
+                        .clone(),
+                );
                 next_epoch += 1;
             }
 
             // write to db
-            self.restore_handler.save_ledger_infos(&lis)?;
+            let num_to_save = lis
+                .iter()
+                .take_while(|li| li.ledger_info().version() <= self.target_version)
+                .count();
+            if num_to_save > num_unverified {
+                self.restore_handler
+                    .save_ledger_infos(&lis[num_unverified..num_to_save])?;
+            }
+            restored.extend(lis.into_iter().map(|li| li.ledger_info().clone()));
         }
 
-        Ok(())
+        Ok(restored)
     }
 }
 
 impl EpochEndingRestoreController {
-    async fn read_chunk(&self, file_handle: FileHandle) -> Result<Vec<LedgerInfoWithSignatures>> {
-        let mut file = self.storage.open_for_read(&file_handle).await?;
-        let mut chunk = vec![];
-
-        while let Some(record_bytes) = file.read_record_bytes().await? {
-            chunk.push(lcs::from_bytes(&record_bytes)?);
-        }
-
-        Ok(chunk)
+    async fn load_chunk(
+        storage: Arc<dyn BackupStorage>,
+        chunk_codec: Arc<ChunkCodec>,
+        chunk: EpochEndingChunk,
+    ) -> Result<Vec<LedgerInfoWithSignatures>> {
+        let lis: Vec<LedgerInfoWithSignatures> = chunk_codec
+            .read_records(&*storage, &chunk.ledger_infos, chunk.format, &chunk.id())
+            .await?;
+        ensure!(
+            chunk.first_epoch + lis.len() as u64 == chunk.last_epoch + 1,
+            "Number of items in chunks doesn't match that in manifest. first_epoch: {}, last_epoch: {}, items in chunk: {}",
+            chunk.first_epoch,
+            chunk.last_epoch,
+            lis.len(),
+        );
+        Ok(lis)
     }
 }
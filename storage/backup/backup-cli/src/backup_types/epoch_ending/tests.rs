@@ -9,20 +9,21 @@ use crate::{
     storage::{local_fs::LocalFs, BackupStorage},
     utils::{
         backup_service_client::BackupServiceClient,
+        chunk_codec::{ChunkCodec, ChunkFormat, Compression, Encryption},
         test_utils::{tmp_db_empty, tmp_db_with_random_content},
-        GlobalBackupOpt,
+        GlobalBackupOpt, GlobalRestoreOpt,
     },
 };
 use backup_service::start_backup_service;
 use libra_config::utils::get_available_port;
 use libra_temppath::TempPath;
+use libra_types::waypoint::Waypoint;
 use std::sync::Arc;
-use tokio::time::Duration;
+use tokio::{runtime::Runtime, time::Duration};
 
 #[test]
 fn end_to_end() {
     let (_src_db_dir, src_db, blocks) = tmp_db_with_random_content();
-    let (_tgt_db_dir, tgt_db) = tmp_db_empty();
     let backup_dir = TempPath::new();
     backup_dir.create_as_dir().unwrap();
     let store: Arc<dyn BackupStorage> = Arc::new(LocalFs::new(backup_dir.path().to_path_buf()));
@@ -30,6 +31,9 @@ fn end_to_end() {
     let port = get_available_port();
     let mut rt = start_backup_service(port, src_db);
     let client = Arc::new(BackupServiceClient::new(port));
+    let chunk_codec = Arc::new(
+        ChunkCodec::new(ChunkFormat::new(Compression::Gzip, Encryption::None), None).unwrap(),
+    );
 
     let latest_epoch = blocks.last().unwrap().1.ledger_info().next_block_epoch();
     let manifest_handle = rt
@@ -44,27 +48,46 @@ fn end_to_end() {
                 },
                 client,
                 Arc::clone(&store),
+                Arc::clone(&chunk_codec),
             )
             .run(),
         )
         .unwrap();
 
-    rt.block_on(
-        EpochEndingRestoreController::new(
-            EpochEndingRestoreOpt { manifest_handle },
-            store,
-            Arc::new(tgt_db.get_restore_handler()),
-        )
-        .run(),
-    )
-    .unwrap();
-
     let expected_ledgers_infos = blocks
         .into_iter()
         .map(|(_, li)| li)
         .filter(|li| li.ledger_info().ends_epoch())
         .collect::<Vec<_>>();
+    // the random LedgerInfos aren't signed, so they can only be verified by waypoints
+    let waypoints = expected_ledgers_infos
+        .iter()
+        .map(|li| Waypoint::new_epoch_boundary(li.ledger_info()).unwrap())
+        .collect::<Vec<_>>();
+    let restore = |rt: &mut Runtime, trust_waypoints: Vec<Waypoint>| {
+        let (tgt_db_dir, tgt_db) = tmp_db_empty();
+        rt.block_on(
+            EpochEndingRestoreController::new(
+                EpochEndingRestoreOpt {
+                    manifest_handle: manifest_handle.clone(),
+                    trust_waypoints,
+                },
+                GlobalRestoreOpt {
+                    db_dir: tgt_db_dir.path().to_path_buf(),
+                    target_version: None,
+                    concurrent_downloads: 4,
+                },
+                Arc::clone(&store),
+                Arc::clone(&chunk_codec),
+                Arc::new(tgt_db.get_restore_handler()),
+            )
+            .run(),
+        )
+        .unwrap();
+        (tgt_db_dir, tgt_db)
+    };
 
+    let (_tgt_db_dir, tgt_db) = restore(&mut rt, waypoints.clone());
     assert_eq!(
         tgt_db
             .get_epoch_ending_ledger_infos(0, latest_epoch)
@@ -73,5 +96,18 @@ fn end_to_end() {
         expected_ledgers_infos,
     );
 
+    // LedgerInfos before the first trusted waypoint are not saved.
+    let (_tgt_db_dir, tgt_db) = restore(&mut rt, vec![*waypoints.last().unwrap()]);
+    assert_eq!(
+        tgt_db
+            .get_epoch_ending_ledger_infos(latest_epoch - 1, latest_epoch)
+            .unwrap()
+            .0,
+        vec![expected_ledgers_infos.last().unwrap().clone()],
+    );
+    assert!(tgt_db
+        .get_epoch_ending_ledger_infos(0, latest_epoch)
+        .is_err());
+
     rt.shutdown_timeout(Duration::from_secs(1));
 }
@@ -2,10 +2,16 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
-    backup_types::state_snapshot::manifest::{StateSnapshotBackup, StateSnapshotChunk},
+    backup_types::state_snapshot::{
+        delta::{DeltaBase, DeltaRecord},
+        manifest::{StateSnapshotBackup, StateSnapshotChunk},
+    },
+    metadata::{save_metadata, Metadata},
     storage::{BackupHandleRef, BackupStorage, FileHandle, ShellSafeName},
     utils::{
-        backup_service_client::BackupServiceClient, read_record_bytes::ReadRecordBytes,
+        backup_service_client::BackupServiceClient,
+        chunk_codec::{ChunkCodec, ChunkId},
+        read_record_bytes::ReadRecordBytes,
         should_cut_chunk, GlobalBackupOpt,
     },
 };
@@ -28,13 +34,22 @@ pub struct StateSnapshotBackupOpt {
         help = "Version at which a state snapshot to be taken."
     )]
     pub version: Version,
+
+    #[structopt(
+        long = "base-manifest",
+        help = "Manifest of an older state snapshot backup. If set, only accounts that differ \
+        from it are backed up, and restoring the delta requires the base backup."
+    )]
+    pub base_manifest: Option<FileHandle>,
 }
 
 pub struct StateSnapshotBackupController {
     version: Version,
+    base_manifest: Option<FileHandle>,
     max_chunk_size: usize,
     client: Arc<BackupServiceClient>,
     storage: Arc<dyn BackupStorage>,
+    chunk_codec: Arc<ChunkCodec>,
 }
 
 impl StateSnapshotBackupController {
@@ -43,17 +58,31 @@ impl StateSnapshotBackupController {
         global_opt: GlobalBackupOpt,
         client: Arc<BackupServiceClient>,
         storage: Arc<dyn BackupStorage>,
+        chunk_codec: Arc<ChunkCodec>,
     ) -> Self {
         Self {
             version: opt.version,
+            base_manifest: opt.base_manifest,
             max_chunk_size: global_opt.max_chunk_size,
             client,
             storage,
+            chunk_codec,
         }
     }
 
     pub async fn run(self) -> Result<FileHandle> {
         let backup_handle = self.storage.create_backup(&self.backup_name()).await?;
+        let mut base = match &self.base_manifest {
+            Some(base_manifest) => Some(
+                DeltaBase::open(
+                    Arc::clone(&self.storage),
+                    Arc::clone(&self.chunk_codec),
+                    base_manifest,
+                )
+                .await?,
+            ),
+            None => None,
+        };
 
         let mut chunks = vec![];
 
@@ -62,14 +91,17 @@ impl StateSnapshotBackupController {
             .read_record_bytes()
             .await?
             .ok_or_else(|| anyhow!("State is empty."))?;
-        let mut chunk_bytes = (prev_record_bytes.len() as u32).to_be_bytes().to_vec();
-        chunk_bytes.extend(&prev_record_bytes);
+        // The size of the full chunk decides where chunks are cut, so a delta has the same chunks
+        // as a full backup at the same version, while only the changed accounts are written.
+        let mut chunk_size = 4 + prev_record_bytes.len();
+        let mut chunk_bytes = vec![];
+        Self::add_record(&mut chunk_bytes, &prev_record_bytes, &mut base).await?;
         let mut chunk_first_key = Self::parse_key(&prev_record_bytes)?;
         let mut current_idx: usize = 0;
         let mut chunk_first_idx: usize = 0;
 
         while let Some(record_bytes) = state_snapshot_file.read_record_bytes().await? {
-            if should_cut_chunk(&chunk_bytes, &record_bytes, self.max_chunk_size) {
+            if should_cut_chunk(chunk_size, &record_bytes, self.max_chunk_size) {
                 println!("New Chunk.");
 
                 let chunk = self
@@ -83,18 +115,28 @@ impl StateSnapshotBackupController {
                     )
                     .await?;
                 chunks.push(chunk);
+                chunk_size = 0;
                 chunk_bytes = vec![];
                 chunk_first_idx = current_idx + 1;
                 chunk_first_key = Self::parse_key(&record_bytes)?;
             }
 
             current_idx += 1;
-            chunk_bytes.extend(&(record_bytes.len() as u32).to_be_bytes());
-            chunk_bytes.extend(&record_bytes);
+            chunk_size += 4 + record_bytes.len();
+            Self::add_record(&mut chunk_bytes, &record_bytes, &mut base).await?;
             prev_record_bytes = record_bytes;
         }
 
-        assert!(!chunk_bytes.is_empty());
+        assert!(chunk_size > 0);
+        // Accounts of the base after the last one in the state are gone.
+        if let Some(base) = &mut base {
+            for (key, _) in base.take_until(None).await? {
+                Self::push_record(
+                    &mut chunk_bytes,
+                    &lcs::to_bytes::<DeltaRecord>(&(key, None))?,
+                );
+            }
+        }
         println!("Last chunk.");
         let chunk = self
             .write_chunk(
@@ -139,6 +181,50 @@ impl StateSnapshotBackupController {
             .unwrap()
     }
 
+    /// Appends `record` to `chunk_bytes`. For a delta, the account is only appended if it's not
+    /// the same in `base`, following the accounts of the base before it, which are gone.
+    async fn add_record(
+        chunk_bytes: &mut Vec<u8>,
+        record: &Bytes,
+        base: &mut Option<DeltaBase>,
+    ) -> Result<()> {
+        let base = match base {
+            Some(base) => base,
+            None => {
+                Self::push_record(chunk_bytes, record);
+                return Ok(());
+            }
+        };
+        let (key, blob): (HashValue, AccountStateBlob) = lcs::from_bytes(record)?;
+        let mut base_accounts = base.take_until(Some(key)).await?;
+        let unchanged = match base_accounts.last() {
+            Some((base_key, base_blob)) if *base_key == key => {
+                let unchanged = *base_blob == blob;
+                base_accounts.pop();
+                unchanged
+            }
+            _ => false,
+        };
+        for (gone_key, _) in base_accounts {
+            Self::push_record(
+                chunk_bytes,
+                &lcs::to_bytes::<DeltaRecord>(&(gone_key, None))?,
+            );
+        }
+        if !unchanged {
+            Self::push_record(
+                chunk_bytes,
+                &lcs::to_bytes::<DeltaRecord>(&(key, Some(blob)))?,
+            );
+        }
+        Ok(())
+    }
+
+    fn push_record(chunk_bytes: &mut Vec<u8>, record: &[u8]) {
+        chunk_bytes.extend(&(record.len() as u32).to_be_bytes());
+        chunk_bytes.extend(record);
+    }
+
     fn parse_key(record: &Bytes) -> Result<HashValue> {
         let (key, _): (HashValue, AccountStateBlob) = lcs::from_bytes(record)?;
         Ok(key)
@@ -158,7 +244,17 @@ impl StateSnapshotBackupController {
             .storage
             .create_for_write(backup_handle, &Self::chunk_name(first_idx))
             .await?;
-        chunk_file.write_all(&chunk_bytes).await?;
+        chunk_file
+            .write_all(&self.chunk_codec.encode(
+                chunk_bytes,
+                &ChunkId::StateSnapshot {
+                    version: self.version,
+                    first_idx: first_idx as u64,
+                    last_idx: last_idx as u64,
+                },
+            )?)
+            .await?;
+        chunk_file.shutdown().await?;
         let (proof_handle, mut proof_file) = self
             .storage
             .create_for_write(backup_handle, &Self::chunk_proof_name(first_idx, last_idx))
@@ -171,6 +267,7 @@ impl StateSnapshotBackupController {
             &mut proof_file,
         )
         .await?;
+        proof_file.shutdown().await?;
 
         Ok(StateSnapshotChunk {
             first_idx,
@@ -178,6 +275,7 @@ impl StateSnapshotBackupController {
             first_key,
             last_key,
             blobs: chunk_handle,
+            format: self.chunk_codec.format(),
             proof: proof_handle,
         })
     }
@@ -196,12 +294,14 @@ impl StateSnapshotBackupController {
             .create_for_write(&backup_handle, Self::proof_name())
             .await?;
         proof_file.write_all(&proof_bytes).await?;
+        proof_file.shutdown().await?;
 
         let manifest = StateSnapshotBackup {
             version: self.version,
             root_hash: txn_info.transaction_info().state_root_hash(),
             chunks,
             proof: proof_handle,
+            base_manifest: self.base_manifest.clone(),
         };
 
         let (manifest_handle, mut manifest_file) = self
@@ -211,6 +311,10 @@ impl StateSnapshotBackupController {
         manifest_file
             .write_all(&serde_json::to_vec(&manifest)?)
             .await?;
+        manifest_file.shutdown().await?;
+
+        let metadata = Metadata::new_state_snapshot_backup(self.version, manifest_handle.clone());
+        save_metadata(&*self.storage, &metadata).await?;
 
         Ok(manifest_handle)
     }
@@ -0,0 +1,246 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    backup_types::state_snapshot::manifest::{StateSnapshotBackup, StateSnapshotChunk},
+    storage::{BackupStorage, FileHandleRef},
+    utils::chunk_codec::ChunkCodec,
+};
+use anyhow::{ensure, Result};
+use futures::future::BoxFuture;
+use libra_crypto::HashValue;
+use libra_types::{account_state_blob::AccountStateBlob, transaction::Version};
+use std::{collections::VecDeque, sync::Arc, vec};
+use tokio::io::AsyncReadExt;
+
+/// A record in a chunk of a delta state snapshot backup: an account that is new or changed since
+/// the base, or that is gone if the blob is `None`.
+pub type DeltaRecord = (HashValue, Option<AccountStateBlob>);
+
+/// The accounts read from a chunk of a state snapshot backup: all of them, or only those that
+/// differ from the base if the backup is a delta.
+pub enum ChunkAccounts {
+    Full(Vec<(HashValue, AccountStateBlob)>),
+    Delta(Vec<DeltaRecord>),
+}
+
+/// The full account state a delta state snapshot is layered over, i.e. that of its base, with
+/// the bases of the base resolved. It's read chunk by chunk in key order as the chunks of the
+/// delta are layered over it, so it's never held in memory as a whole.
+pub struct DeltaBase {
+    storage: Arc<dyn BackupStorage>,
+    chunk_codec: Arc<ChunkCodec>,
+    version: Version,
+    chunks: vec::IntoIter<StateSnapshotChunk>,
+    /// The base of this backup, if it's a delta itself.
+    base: Option<Box<DeltaBase>>,
+    /// Accounts read from the backup but not taken yet, in key order.
+    pending: VecDeque<(HashValue, AccountStateBlob)>,
+}
+
+impl DeltaBase {
+    /// Opens the state snapshot backup at `manifest_handle` as a base, following `base_manifest`
+    /// down to a full backup.
+    pub async fn open(
+        storage: Arc<dyn BackupStorage>,
+        chunk_codec: Arc<ChunkCodec>,
+        manifest_handle: &FileHandleRef,
+    ) -> Result<Self> {
+        Self::open_impl(storage, chunk_codec, manifest_handle.to_string(), None).await
+    }
+
+    fn open_impl(
+        storage: Arc<dyn BackupStorage>,
+        chunk_codec: Arc<ChunkCodec>,
+        manifest_handle: String,
+        delta_version: Option<Version>,
+    ) -> BoxFuture<'static, Result<Self>> {
+        Box::pin(async move {
+            let manifest = read_manifest(&*storage, &manifest_handle).await?;
+            manifest.verify()?;
+            if let Some(delta_version) = delta_version {
+                ensure!(
+                    manifest.version < delta_version,
+                    "Base state snapshot at version {} is not older than the delta at version {}.",
+                    manifest.version,
+                    delta_version,
+                );
+            }
+            let base = match manifest.base_manifest {
+                Some(base_manifest) => Some(Box::new(
+                    Self::open_impl(
+                        Arc::clone(&storage),
+                        Arc::clone(&chunk_codec),
+                        base_manifest,
+                        Some(manifest.version),
+                    )
+                    .await?,
+                )),
+                None => None,
+            };
+
+            Ok(Self {
+                storage,
+                chunk_codec,
+                version: manifest.version,
+                chunks: manifest.chunks.into_iter(),
+                base,
+                pending: VecDeque::new(),
+            })
+        })
+    }
+
+    /// Takes the accounts up to `last_key`, inclusive, or all the remaining ones if it's `None`.
+    pub fn take_until(
+        &mut self,
+        last_key: Option<HashValue>,
+    ) -> BoxFuture<'_, Result<Vec<(HashValue, AccountStateBlob)>>> {
+        Box::pin(async move {
+            let mut taken = Vec::new();
+            loop {
+                while let Some((key, _)) = self.pending.front() {
+                    if last_key.map_or(false, |last_key| *key > last_key) {
+                        return Ok(taken);
+                    }
+                    taken.extend(self.pending.pop_front());
+                }
+                let chunk = match self.chunks.next() {
+                    Some(chunk) => chunk,
+                    None => return Ok(taken),
+                };
+                let is_last_chunk = self.chunks.len() == 0;
+                let accounts = read_chunk(
+                    &*self.storage,
+                    &self.chunk_codec,
+                    self.version,
+                    &chunk,
+                    self.base.is_some(),
+                )
+                .await?;
+                let accounts =
+                    layer(self.base.as_deref_mut(), &chunk, is_last_chunk, accounts).await?;
+                self.pending.extend(accounts);
+            }
+        })
+    }
+
+    /// Returns all accounts in the key range of `chunk` of a delta over this base: those in the
+    /// base, overridden or removed by `delta`. The last chunk of the delta also removes the
+    /// accounts after its range, and each chunk those between it and the previous one.
+    pub async fn layer_chunk(
+        &mut self,
+        chunk: &StateSnapshotChunk,
+        is_last_chunk: bool,
+        delta: Vec<DeltaRecord>,
+    ) -> Result<Vec<(HashValue, AccountStateBlob)>> {
+        let last_key = if is_last_chunk {
+            None
+        } else {
+            Some(chunk.last_key)
+        };
+        let mut base = self.take_until(last_key).await?.into_iter().peekable();
+
+        let mut accounts = Vec::with_capacity(chunk.last_idx - chunk.first_idx + 1);
+        let mut prev_key = None;
+        for (key, blob) in delta {
+            ensure!(
+                prev_key.map_or(true, |prev_key| key > prev_key),
+                "Account {:x} out of order in delta chunk.",
+                key,
+            );
+            prev_key = Some(key);
+            while let Some((base_key, _)) = base.peek() {
+                if *base_key >= key {
+                    break;
+                }
+                accounts.extend(base.next());
+            }
+            let in_base = base.peek().map_or(false, |(base_key, _)| *base_key == key);
+            if in_base {
+                base.next();
+            }
+            match blob {
+                Some(blob) => accounts.push((key, blob)),
+                None => ensure!(
+                    in_base,
+                    "Account {:x} removed by the delta is not in the base.",
+                    key,
+                ),
+            }
+        }
+        accounts.extend(base);
+
+        let num_accounts = chunk.last_idx - chunk.first_idx + 1;
+        ensure!(
+            accounts.len() == num_accounts,
+            "Number of accounts after layering ({}) doesn't match that in manifest ({}).",
+            accounts.len(),
+            num_accounts,
+        );
+        ensure!(
+            accounts.first().map(|(key, _)| *key) == Some(chunk.first_key)
+                && accounts.last().map(|(key, _)| *key) == Some(chunk.last_key),
+            "Accounts after layering are out of the key range of chunk [{:x}, {:x}].",
+            chunk.first_key,
+            chunk.last_key,
+        );
+        Ok(accounts)
+    }
+}
+
+/// Returns all accounts in the key range of `chunk`, layering them over `base` if they're of a
+/// delta.
+pub async fn layer(
+    base: Option<&mut DeltaBase>,
+    chunk: &StateSnapshotChunk,
+    is_last_chunk: bool,
+    accounts: ChunkAccounts,
+) -> Result<Vec<(HashValue, AccountStateBlob)>> {
+    match (accounts, base) {
+        (ChunkAccounts::Full(accounts), None) => Ok(accounts),
+        (ChunkAccounts::Delta(delta), Some(base)) => {
+            base.layer_chunk(chunk, is_last_chunk, delta).await
+        }
+        (ChunkAccounts::Full(_), Some(_)) | (ChunkAccounts::Delta(_), None) => {
+            unreachable!("Chunks are read as deltas exactly when there's a base.")
+        }
+    }
+}
+
+pub async fn read_manifest(
+    storage: &dyn BackupStorage,
+    file_handle: &FileHandleRef,
+) -> Result<StateSnapshotBackup> {
+    let mut manifest_bytes = Vec::new();
+    storage
+        .open_for_read(file_handle)
+        .await?
+        .read_to_end(&mut manifest_bytes)
+        .await?;
+    Ok(serde_json::from_slice(&manifest_bytes)?)
+}
+
+/// Reads the accounts in `chunk` of the state snapshot at `version`, as `DeltaRecord`s if it's
+/// of a delta.
+pub async fn read_chunk(
+    storage: &dyn BackupStorage,
+    chunk_codec: &ChunkCodec,
+    version: Version,
+    chunk: &StateSnapshotChunk,
+    is_delta: bool,
+) -> Result<ChunkAccounts> {
+    let chunk_id = chunk.id(version);
+    Ok(if is_delta {
+        ChunkAccounts::Delta(
+            chunk_codec
+                .read_records(storage, &chunk.blobs, chunk.format, &chunk_id)
+                .await?,
+        )
+    } else {
+        ChunkAccounts::Full(
+            chunk_codec
+                .read_records(storage, &chunk.blobs, chunk.format, &chunk_id)
+                .await?,
+        )
+    })
+}
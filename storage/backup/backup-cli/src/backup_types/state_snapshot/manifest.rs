@@ -1,7 +1,11 @@
 // Copyright (c) The Libra Core Contributors
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::storage::FileHandle;
+use crate::{
+    storage::FileHandle,
+    utils::chunk_codec::{ChunkFormat, ChunkId},
+};
+use anyhow::{ensure, Result};
 use libra_crypto::HashValue;
 use libra_types::transaction::Version;
 use serde::{Deserialize, Serialize};
@@ -21,19 +25,36 @@ pub struct StateSnapshotChunk {
     /// Repeated `len(record) + record` where `record` is LCS serialized tuple
     /// `(key, account_state_blob)`
     pub blobs: FileHandle,
+    /// How `blobs` is compressed and encrypted.
+    #[serde(default)]
+    pub format: ChunkFormat,
     /// LCS serialized `SparseMerkleRangeProof` that proves this chunk adds up to the root hash
     /// indicated in the backup (`StateSnapshotBackup::root_hash`).
     pub proof: FileHandle,
 }
 
-/// State snapshot backup manifest, representing a complete state view at specified version.
+impl StateSnapshotChunk {
+    /// The id of this chunk in the state snapshot at `version`.
+    pub fn id(&self, version: Version) -> ChunkId {
+        ChunkId::StateSnapshot {
+            version,
+            first_idx: self.first_idx as u64,
+            last_idx: self.last_idx as u64,
+        }
+    }
+}
+
+/// State snapshot backup manifest, representing a complete state view at specified version,
+/// either by itself or layered over a base state snapshot backup.
 #[derive(Deserialize, Serialize)]
 pub struct StateSnapshotBackup {
     /// Version at which this state snapshot is taken.
     pub version: Version,
     /// Hash of the state tree root.
     pub root_hash: HashValue,
-    /// All account blobs in chunks.
+    /// All account blobs in chunks. For a delta, each chunk still describes the key range,
+    /// indices and proof of the full chunk, but `blobs` only carries the accounts in the range
+    /// that differ from the base.
     pub chunks: Vec<StateSnapshotChunk>,
     /// LCS serialized
     /// `Tuple(TransactionInfoWithProof, LedgerInfoWithSignatures)`.
@@ -46,4 +67,44 @@ pub struct StateSnapshotBackup {
     /// `EpochStateBackup` recovered prior to this to the DB; Requiring it to be in the same epoch
     /// limits the requirement on such `EpochStateBackup` to no older than the same epoch.
     pub proof: FileHandle,
+    /// If set, this is a delta against the state snapshot backup with this manifest, which can
+    /// itself be a delta.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub base_manifest: Option<FileHandle>,
+}
+
+impl StateSnapshotBackup {
+    pub fn verify(&self) -> Result<()> {
+        // check chunk ranges
+        let mut next_idx = 0;
+        let mut prev_last_key = None;
+        for chunk in &self.chunks {
+            ensure!(
+                chunk.first_idx == next_idx,
+                "Chunk ranges not continuous. Expected first index: {}, actual: {}.",
+                next_idx,
+                chunk.first_idx,
+            );
+            ensure!(
+                chunk.last_idx >= chunk.first_idx && chunk.last_key >= chunk.first_key,
+                "Chunk range invalid. [{}, {}], keys [{:x}, {:x}]",
+                chunk.first_idx,
+                chunk.last_idx,
+                chunk.first_key,
+                chunk.last_key,
+            );
+            if let Some(prev_last_key) = prev_last_key {
+                ensure!(
+                    chunk.first_key > prev_last_key,
+                    "Chunk keys not increasing. Previous last key: {:x}, first key: {:x}.",
+                    prev_last_key,
+                    chunk.first_key,
+                );
+            }
+            next_idx = chunk.last_idx + 1;
+            prev_last_key = Some(chunk.last_key);
+        }
+
+        Ok(())
+    }
 }
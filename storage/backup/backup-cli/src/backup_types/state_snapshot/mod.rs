@@ -2,6 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 pub mod backup;
+pub mod delta;
 pub mod manifest;
 pub mod restore;
 
@@ -2,17 +2,23 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
-    backup_types::state_snapshot::manifest::StateSnapshotBackup,
-    storage::{BackupStorage, FileHandle},
-    utils::read_record_bytes::ReadRecordBytes,
-};
-use anyhow::Result;
-use libra_crypto::HashValue;
-use libra_types::{
-    account_state_blob::AccountStateBlob, proof::SparseMerkleRangeProof, transaction::Version,
+    backup_types::state_snapshot::{
+        delta::{layer, read_chunk, read_manifest, ChunkAccounts, DeltaBase},
+        manifest::StateSnapshotChunk,
+    },
+    storage::{BackupStorage, FileHandle, FileHandleRef},
+    utils::{chunk_codec::ChunkCodec, run_ahead, GlobalRestoreOpt},
 };
+use anyhow::{ensure, Result};
+use futures::TryStreamExt;
+use libra_types::{proof::SparseMerkleRangeProof, transaction::Version};
 use libradb::backup::restore_handler::RestoreHandler;
-use std::sync::Arc;
+use serde::{Deserialize, Serialize};
+use std::{
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::Instant,
+};
 use structopt::StructOpt;
 use tokio::io::AsyncReadExt;
 
@@ -22,72 +28,222 @@ pub struct StateSnapshotRestoreOpt {
     pub manifest_handle: FileHandle,
     #[structopt(long = "state-into-version")]
     pub version: Version,
+    #[structopt(
+        long = "checkpoint-file",
+        parse(from_os_str),
+        help = "File to record the last restored chunk in, so that an interrupted restore resumes \
+        from there when run again. Removed once the restore finishes."
+    )]
+    pub checkpoint_file: Option<PathBuf>,
+    #[structopt(
+        long = "progress",
+        help = "Report accounts restored, throughput and ETA after each chunk."
+    )]
+    pub progress: bool,
+}
+
+/// Progress of a state snapshot restore, persisted after each chunk is written to the DB.
+#[derive(Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct Checkpoint {
+    pub manifest_handle: FileHandle,
+    pub version: Version,
+    /// Index in the manifest of the last chunk written to the DB. The last account in a chunk is
+    /// only written along with the next chunk, so a resumed restore starts from this chunk again,
+    /// skipping the accounts already in the DB.
+    pub last_chunk_idx: usize,
+}
+
+impl Checkpoint {
+    pub async fn load(path: &Path) -> Result<Option<Self>> {
+        match tokio::fs::read(path).await {
+            Ok(bytes) => Ok(Some(serde_json::from_slice(&bytes)?)),
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    /// Replaces the checkpoint at `path` with `self`, without ever leaving a partial file.
+    async fn save(&self, path: &Path) -> Result<()> {
+        let tmp_path = path.with_extension("tmp");
+        tokio::fs::write(&tmp_path, serde_json::to_vec(self)?).await?;
+        tokio::fs::rename(&tmp_path, path).await?;
+        Ok(())
+    }
 }
 
 pub struct StateSnapshotRestoreController {
     storage: Arc<dyn BackupStorage>,
+    chunk_codec: Arc<ChunkCodec>,
     restore_handler: Arc<RestoreHandler>,
     version: Version,
     manifest_handle: FileHandle,
+    checkpoint_file: Option<PathBuf>,
+    progress: bool,
+    target_version: Version,
+    concurrent_downloads: usize,
 }
 
 impl StateSnapshotRestoreController {
     pub fn new(
         opt: StateSnapshotRestoreOpt,
+        global_opt: GlobalRestoreOpt,
         storage: Arc<dyn BackupStorage>,
+        chunk_codec: Arc<ChunkCodec>,
         restore_handler: Arc<RestoreHandler>,
     ) -> Self {
         Self {
             storage,
+            chunk_codec,
             restore_handler,
             version: opt.version,
             manifest_handle: opt.manifest_handle,
+            checkpoint_file: opt.checkpoint_file,
+            progress: opt.progress,
+            target_version: global_opt.target_version(),
+            concurrent_downloads: global_opt.concurrent_downloads,
         }
     }
 
     pub async fn run(self) -> Result<()> {
-        let mut manifest_bytes = Vec::new();
-        self.storage
-            .open_for_read(&self.manifest_handle)
-            .await?
-            .read_to_end(&mut manifest_bytes)
-            .await?;
-        let manifest: StateSnapshotBackup = serde_json::from_slice(&manifest_bytes)?;
+        let manifest = read_manifest(&*self.storage, &self.manifest_handle).await?;
+        manifest.verify()?;
+        ensure!(
+            manifest.version <= self.target_version,
+            "State snapshot at version {} is after the target version {}.",
+            manifest.version,
+            self.target_version,
+        );
+        let mut base = match &manifest.base_manifest {
+            Some(base_manifest) => Some(
+                DeltaBase::open(
+                    Arc::clone(&self.storage),
+                    Arc::clone(&self.chunk_codec),
+                    base_manifest,
+                )
+                .await?,
+            ),
+            None => None,
+        };
+
+        let checkpoint = match &self.checkpoint_file {
+            Some(path) => Checkpoint::load(path).await?,
+            None => None,
+        };
+        let first_chunk_idx = match checkpoint {
+            Some(checkpoint) => {
+                ensure!(
+                    checkpoint.manifest_handle == self.manifest_handle
+                        && checkpoint.version == self.version,
+                    "Checkpoint is of restoring {} into version {}, not {} into version {}.",
+                    checkpoint.manifest_handle,
+                    checkpoint.version,
+                    self.manifest_handle,
+                    self.version,
+                );
+                println!(
+                    "Resuming from chunk {} of {}.",
+                    checkpoint.last_chunk_idx,
+                    manifest.chunks.len(),
+                );
+                checkpoint.last_chunk_idx
+            }
+            None => 0,
+        };
 
         let mut receiver = self
             .restore_handler
             .get_state_restore_receiver(self.version, manifest.root_hash)?;
+        let progress = RestoreProgress::new(
+            manifest.chunks.last().map_or(0, |chunk| chunk.last_idx + 1),
+            manifest
+                .chunks
+                .get(first_chunk_idx)
+                .map_or(0, |chunk| chunk.first_idx),
+        );
+
+        // The accounts of the base in the chunks restored already are skipped.
+        if let (Some(base), Some(chunk)) = (
+            &mut base,
+            first_chunk_idx
+                .checked_sub(1)
+                .and_then(|idx| manifest.chunks.get(idx)),
+        ) {
+            base.take_until(Some(chunk.last_key)).await?;
+        }
 
-        for chunk in manifest.chunks {
-            let blobs = self.read_account_state_chunk(chunk.blobs).await?;
-            let proof = self.read_proof(chunk.proof).await?;
+        let num_chunks = manifest.chunks.len();
+        let version = manifest.version;
+        let is_delta = base.is_some();
+        let chunks = run_ahead(
+            manifest.chunks.into_iter().skip(first_chunk_idx),
+            self.concurrent_downloads,
+            |chunk| {
+                Self::load_chunk(
+                    Arc::clone(&self.storage),
+                    Arc::clone(&self.chunk_codec),
+                    version,
+                    is_delta,
+                    chunk,
+                )
+            },
+        );
+        futures::pin_mut!(chunks);
 
-            receiver.add_chunk(blobs, proof)?;
+        let mut chunk_idx = first_chunk_idx;
+        while let Some((chunk, accounts, proof)) = chunks.try_next().await? {
+            // Layered here, in order, since the base is read along.
+            let mut blobs =
+                layer(base.as_mut(), &chunk, chunk_idx + 1 == num_chunks, accounts).await?;
+            // Skip accounts an interrupted restore already wrote.
+            if let Some(previous_key) = receiver.previous_key_hash() {
+                blobs.retain(|(key, _)| *key > previous_key);
+            }
+            if !blobs.is_empty() {
+                receiver.add_chunk(blobs, proof)?;
+            }
+            if let Some(path) = &self.checkpoint_file {
+                Checkpoint {
+                    manifest_handle: self.manifest_handle.clone(),
+                    version: self.version,
+                    last_chunk_idx: chunk_idx,
+                }
+                .save(path)
+                .await?;
+            }
+            if self.progress {
+                progress.report(chunk.last_idx + 1);
+            }
+            chunk_idx += 1;
         }
 
         receiver.finish()?;
+        if let Some(path) = &self.checkpoint_file {
+            tokio::fs::remove_file(path).await?;
+        }
         Ok(())
     }
 }
 
 impl StateSnapshotRestoreController {
-    async fn read_account_state_chunk(
-        &self,
-        file_handle: FileHandle,
-    ) -> Result<Vec<(HashValue, AccountStateBlob)>> {
-        let mut file = self.storage.open_for_read(&file_handle).await?;
-
-        let mut chunk = vec![];
-
-        while let Some(record_bytes) = file.read_record_bytes().await? {
-            chunk.push(lcs::from_bytes(&record_bytes)?);
-        }
-
-        Ok(chunk)
+    /// Reads the accounts in a chunk, only those that differ from the base if it's a delta, and
+    /// its proof. Runs ahead of the DB writes, so it only touches the storage.
+    async fn load_chunk(
+        storage: Arc<dyn BackupStorage>,
+        chunk_codec: Arc<ChunkCodec>,
+        version: Version,
+        is_delta: bool,
+        chunk: StateSnapshotChunk,
+    ) -> Result<(StateSnapshotChunk, ChunkAccounts, SparseMerkleRangeProof)> {
+        let accounts = read_chunk(&*storage, &chunk_codec, version, &chunk, is_delta).await?;
+        let proof = Self::read_proof(&*storage, &chunk.proof).await?;
+        Ok((chunk, accounts, proof))
     }
 
-    async fn read_proof(&self, file_handle: FileHandle) -> Result<SparseMerkleRangeProof> {
-        let mut file = self.storage.open_for_read(&file_handle).await?;
+    async fn read_proof(
+        storage: &dyn BackupStorage,
+        file_handle: &FileHandleRef,
+    ) -> Result<SparseMerkleRangeProof> {
+        let mut file = storage.open_for_read(file_handle).await?;
         let mut buf = Vec::new();
         file.read_to_end(&mut buf).await?;
 
@@ -95,3 +251,40 @@ impl StateSnapshotRestoreController {
         Ok(proof)
     }
 }
+
+struct RestoreProgress {
+    total_accounts: usize,
+    start_accounts: usize,
+    start_time: Instant,
+}
+
+impl RestoreProgress {
+    fn new(total_accounts: usize, start_accounts: usize) -> Self {
+        Self {
+            total_accounts,
+            start_accounts,
+            start_time: Instant::now(),
+        }
+    }
+
+    fn report(&self, restored_accounts: usize) {
+        let elapsed = self.start_time.elapsed().as_secs_f64();
+        let throughput = if elapsed > 0.0 {
+            (restored_accounts - self.start_accounts) as f64 / elapsed
+        } else {
+            0.0
+        };
+        let eta = if throughput > 0.0 {
+            format!(
+                "{:.0}s",
+                (self.total_accounts - restored_accounts) as f64 / throughput
+            )
+        } else {
+            "unknown".to_string()
+        };
+        println!(
+            "Restored {}/{} accounts, {:.0} accounts/s, ETA {}.",
+            restored_accounts, self.total_accounts, throughput, eta,
+        );
+    }
+}
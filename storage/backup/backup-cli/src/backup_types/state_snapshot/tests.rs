@@ -4,27 +4,34 @@
 use crate::{
     backup_types::state_snapshot::{
         backup::{StateSnapshotBackupController, StateSnapshotBackupOpt},
-        restore::{StateSnapshotRestoreController, StateSnapshotRestoreOpt},
+        delta::{read_chunk, read_manifest, ChunkAccounts, DeltaBase, DeltaRecord},
+        manifest::{StateSnapshotBackup, StateSnapshotChunk},
+        restore::{Checkpoint, StateSnapshotRestoreController, StateSnapshotRestoreOpt},
     },
-    storage::{local_fs::LocalFs, BackupStorage},
+    storage::{local_fs::LocalFs, BackupStorage, FileHandle, ShellSafeName},
     utils::{
         backup_service_client::BackupServiceClient,
+        chunk_codec::ChunkCodec,
         test_utils::{tmp_db_empty, tmp_db_with_random_content},
-        GlobalBackupOpt,
+        GlobalBackupOpt, GlobalRestoreOpt,
     },
 };
 use backup_service::start_backup_service;
 use libra_config::utils::get_available_port;
+use libra_crypto::HashValue;
 use libra_temppath::TempPath;
-use libra_types::transaction::PRE_GENESIS_VERSION;
-use std::sync::Arc;
+use libra_types::{
+    account_state_blob::AccountStateBlob,
+    transaction::{Version, PRE_GENESIS_VERSION},
+};
+use std::{path::PathBuf, str::FromStr, sync::Arc};
 use storage_interface::DbReader;
-use tokio::time::Duration;
+use tokio::{io::AsyncWriteExt, runtime::Runtime, time::Duration};
 
 #[test]
 fn end_to_end() {
     let (_src_db_dir, src_db, _blocks) = tmp_db_with_random_content();
-    let (_tgt_db_dir, tgt_db) = tmp_db_empty();
+    let (tgt_db_dir, tgt_db) = tmp_db_empty();
     let backup_dir = TempPath::new();
     backup_dir.create_as_dir().unwrap();
     let store: Arc<dyn BackupStorage> = Arc::new(LocalFs::new(backup_dir.path().to_path_buf()));
@@ -36,12 +43,16 @@ fn end_to_end() {
     let manifest_handle = rt
         .block_on(
             StateSnapshotBackupController::new(
-                StateSnapshotBackupOpt { version },
+                StateSnapshotBackupOpt {
+                    version,
+                    base_manifest: None,
+                },
                 GlobalBackupOpt {
                     max_chunk_size: 500,
                 },
                 client,
                 Arc::clone(&store),
+                Arc::new(ChunkCodec::default()),
             )
             .run(),
         )
@@ -52,8 +63,16 @@ fn end_to_end() {
             StateSnapshotRestoreOpt {
                 manifest_handle,
                 version: PRE_GENESIS_VERSION,
+                checkpoint_file: None,
+                progress: false,
+            },
+            GlobalRestoreOpt {
+                db_dir: tgt_db_dir.path().to_path_buf(),
+                target_version: None,
+                concurrent_downloads: 4,
             },
             store,
+            Arc::new(ChunkCodec::default()),
             Arc::new(tgt_db.get_restore_handler()),
         )
         .run(),
@@ -69,3 +88,340 @@ fn end_to_end() {
 
     rt.shutdown_timeout(Duration::from_secs(1));
 }
+
+#[test]
+fn delta() {
+    let (_src_db_dir, src_db, blocks) = tmp_db_with_random_content();
+    let (tgt_db_dir, tgt_db) = tmp_db_empty();
+    let backup_dir = TempPath::new();
+    backup_dir.create_as_dir().unwrap();
+    let store: Arc<dyn BackupStorage> = Arc::new(LocalFs::new(backup_dir.path().to_path_buf()));
+
+    let port = get_available_port();
+    let mut rt = start_backup_service(port, src_db.clone());
+    let client = Arc::new(BackupServiceClient::new(port));
+    let (latest_version, state_root_hash) = rt.block_on(client.get_latest_state_root()).unwrap();
+    let base_version = blocks[0].0.len() as Version - 1;
+    let middle_version = (base_version + latest_version) / 2;
+    let num_accounts = |version| {
+        src_db
+            .get_backup_handler()
+            .get_account_iter(version)
+            .unwrap()
+            .count()
+    };
+
+    // A full backup, a delta over it and a delta over the delta.
+    let mut base_manifest = None;
+    for version in vec![base_version, middle_version, latest_version] {
+        base_manifest = Some(
+            rt.block_on(
+                StateSnapshotBackupController::new(
+                    StateSnapshotBackupOpt {
+                        version,
+                        base_manifest,
+                    },
+                    GlobalBackupOpt {
+                        max_chunk_size: 500,
+                    },
+                    Arc::clone(&client),
+                    Arc::clone(&store),
+                    Arc::new(ChunkCodec::default()),
+                )
+                .run(),
+            )
+            .unwrap(),
+        );
+    }
+    let manifest_handle = base_manifest.unwrap();
+
+    // The delta only carries accounts that changed, while layering it gives the full state.
+    let manifest = rt
+        .block_on(read_manifest(&*store, &manifest_handle))
+        .unwrap();
+    let mut num_accounts_in_delta = 0;
+    for chunk in &manifest.chunks {
+        match rt
+            .block_on(read_chunk(
+                &*store,
+                &ChunkCodec::default(),
+                manifest.version,
+                chunk,
+                true,
+            ))
+            .unwrap()
+        {
+            ChunkAccounts::Delta(delta) => num_accounts_in_delta += delta.len(),
+            ChunkAccounts::Full(_) => unreachable!(),
+        }
+    }
+    assert!(num_accounts_in_delta <= num_accounts(latest_version));
+    let full_state = rt.block_on(async {
+        DeltaBase::open(
+            Arc::clone(&store),
+            Arc::new(ChunkCodec::default()),
+            &manifest_handle,
+        )
+        .await
+        .unwrap()
+        .take_until(None)
+        .await
+        .unwrap()
+    });
+    assert_eq!(full_state.len(), num_accounts(latest_version));
+
+    rt.block_on(
+        StateSnapshotRestoreController::new(
+            StateSnapshotRestoreOpt {
+                manifest_handle,
+                version: PRE_GENESIS_VERSION,
+                checkpoint_file: None,
+                progress: false,
+            },
+            GlobalRestoreOpt {
+                db_dir: tgt_db_dir.path().to_path_buf(),
+                target_version: None,
+                concurrent_downloads: 4,
+            },
+            store,
+            Arc::new(ChunkCodec::default()),
+            Arc::new(tgt_db.get_restore_handler()),
+        )
+        .run(),
+    )
+    .unwrap();
+    assert_eq!(
+        tgt_db
+            .get_latest_tree_state()
+            .unwrap()
+            .account_state_root_hash,
+        state_root_hash,
+    );
+
+    rt.shutdown_timeout(Duration::from_secs(1));
+}
+
+#[test]
+fn resume() {
+    let (_src_db_dir, src_db, _blocks) = tmp_db_with_random_content();
+    let (tgt_db_dir, tgt_db) = tmp_db_empty();
+    let backup_dir = TempPath::new();
+    backup_dir.create_as_dir().unwrap();
+    let store: Arc<dyn BackupStorage> = Arc::new(LocalFs::new(backup_dir.path().to_path_buf()));
+    let checkpoint_file = backup_dir.path().join("restore.checkpoint");
+
+    let port = get_available_port();
+    let mut rt = start_backup_service(port, src_db);
+    let client = Arc::new(BackupServiceClient::new(port));
+    let (version, state_root_hash) = rt.block_on(client.get_latest_state_root()).unwrap();
+    let manifest_handle = rt
+        .block_on(
+            StateSnapshotBackupController::new(
+                StateSnapshotBackupOpt {
+                    version,
+                    base_manifest: None,
+                },
+                GlobalBackupOpt {
+                    max_chunk_size: 500,
+                },
+                client,
+                Arc::clone(&store),
+                Arc::new(ChunkCodec::default()),
+            )
+            .run(),
+        )
+        .unwrap();
+    let manifest = rt
+        .block_on(read_manifest(&*store, &manifest_handle))
+        .unwrap();
+    let restore = |rt: &mut Runtime| {
+        rt.block_on(
+            StateSnapshotRestoreController::new(
+                StateSnapshotRestoreOpt {
+                    manifest_handle: manifest_handle.clone(),
+                    version: PRE_GENESIS_VERSION,
+                    checkpoint_file: Some(checkpoint_file.clone()),
+                    progress: true,
+                },
+                GlobalRestoreOpt {
+                    db_dir: tgt_db_dir.path().to_path_buf(),
+                    target_version: None,
+                    concurrent_downloads: 4,
+                },
+                Arc::clone(&store),
+                Arc::new(ChunkCodec::default()),
+                Arc::new(tgt_db.get_restore_handler()),
+            )
+            .run(),
+        )
+    };
+
+    // Interrupt the restore by making a chunk in the middle unreadable.
+    let failing_chunk_idx = manifest.chunks.len() / 2;
+    let proof_path = PathBuf::from(&manifest.chunks[failing_chunk_idx].proof);
+    let moved_proof_path = proof_path.with_extension("moved");
+    std::fs::rename(&proof_path, &moved_proof_path).unwrap();
+    assert!(restore(&mut rt).is_err());
+    if failing_chunk_idx > 0 {
+        assert_eq!(
+            rt.block_on(Checkpoint::load(&checkpoint_file)).unwrap(),
+            Some(Checkpoint {
+                manifest_handle: manifest_handle.clone(),
+                version: PRE_GENESIS_VERSION,
+                last_chunk_idx: failing_chunk_idx - 1,
+            }),
+        );
+    }
+
+    std::fs::rename(&moved_proof_path, &proof_path).unwrap();
+    restore(&mut rt).unwrap();
+    assert!(!checkpoint_file.exists());
+    assert_eq!(
+        tgt_db
+            .get_latest_tree_state()
+            .unwrap()
+            .account_state_root_hash,
+        state_root_hash,
+    );
+
+    rt.shutdown_timeout(Duration::from_secs(1));
+}
+
+/// Writes a backup with a single chunk holding `records`, described by `manifest`.
+async fn write_single_chunk_backup<T: serde::Serialize>(
+    store: &dyn BackupStorage,
+    name: &str,
+    records: &[T],
+    mut manifest: StateSnapshotBackup,
+) -> FileHandle {
+    let backup_handle = store
+        .create_backup(&ShellSafeName::from_str(name).unwrap())
+        .await
+        .unwrap();
+    let mut chunk_bytes = Vec::new();
+    for record in records {
+        let record = lcs::to_bytes(record).unwrap();
+        chunk_bytes.extend(&(record.len() as u32).to_be_bytes());
+        chunk_bytes.extend(record);
+    }
+    let (chunk_handle, mut chunk_file) = store
+        .create_for_write(
+            &backup_handle,
+            &ShellSafeName::from_str("0-.chunk").unwrap(),
+        )
+        .await
+        .unwrap();
+    chunk_file
+        .write_all(
+            &ChunkCodec::default()
+                .encode(&chunk_bytes, &manifest.chunks[0].id(manifest.version))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    chunk_file.shutdown().await.unwrap();
+    manifest.chunks[0].blobs = chunk_handle;
+
+    let (manifest_handle, mut manifest_file) = store
+        .create_for_write(
+            &backup_handle,
+            &ShellSafeName::from_str("state.manifest").unwrap(),
+        )
+        .await
+        .unwrap();
+    manifest_file
+        .write_all(&serde_json::to_vec(&manifest).unwrap())
+        .await
+        .unwrap();
+    manifest_file.shutdown().await.unwrap();
+    manifest_handle
+}
+
+fn single_chunk_manifest(
+    version: Version,
+    first_key: HashValue,
+    last_key: HashValue,
+    num_accounts: usize,
+    base_manifest: Option<FileHandle>,
+) -> StateSnapshotBackup {
+    StateSnapshotBackup {
+        version,
+        root_hash: HashValue::zero(),
+        chunks: vec![StateSnapshotChunk {
+            first_idx: 0,
+            last_idx: num_accounts - 1,
+            first_key,
+            last_key,
+            blobs: FileHandle::new(),
+            format: ChunkCodec::default().format(),
+            proof: FileHandle::new(),
+        }],
+        proof: FileHandle::new(),
+        base_manifest,
+    }
+}
+
+#[test]
+fn delta_removes_accounts() {
+    let backup_dir = TempPath::new();
+    backup_dir.create_as_dir().unwrap();
+    let store: Arc<dyn BackupStorage> = Arc::new(LocalFs::new(backup_dir.path().to_path_buf()));
+    let mut keys: Vec<HashValue> = (0..4).map(|_| HashValue::random()).collect();
+    keys.sort();
+    let blob = |byte: u8| AccountStateBlob::from(vec![byte]);
+    let full_state = |manifest_handle: FileHandle| {
+        let store = Arc::clone(&store);
+        async move {
+            DeltaBase::open(store, Arc::new(ChunkCodec::default()), &manifest_handle)
+                .await?
+                .take_until(None)
+                .await
+        }
+    };
+
+    let mut rt = Runtime::new().unwrap();
+    // Base at version 0: [k0, k1, k2].
+    let base = rt.block_on(write_single_chunk_backup(
+        &*store,
+        "base",
+        &[(keys[0], blob(0)), (keys[1], blob(1)), (keys[2], blob(2))],
+        single_chunk_manifest(0, keys[0], keys[2], 3, None),
+    ));
+    // Delta at version 1: k1 removed, k2 changed and k3 added.
+    let delta: Vec<DeltaRecord> = vec![
+        (keys[1], None),
+        (keys[2], Some(blob(20))),
+        (keys[3], Some(blob(3))),
+    ];
+    let delta = rt.block_on(write_single_chunk_backup(
+        &*store,
+        "delta",
+        &delta,
+        single_chunk_manifest(1, keys[0], keys[3], 3, Some(base)),
+    ));
+    assert_eq!(
+        rt.block_on(full_state(delta.clone())).unwrap(),
+        vec![(keys[0], blob(0)), (keys[2], blob(20)), (keys[3], blob(3))],
+    );
+    // Delta of the delta at version 2: k3, after the key range of the last chunk, removed.
+    let delta2: Vec<DeltaRecord> = vec![(keys[3], None)];
+    let delta2 = rt.block_on(write_single_chunk_backup(
+        &*store,
+        "delta2",
+        &delta2,
+        single_chunk_manifest(2, keys[0], keys[2], 2, Some(delta)),
+    ));
+    assert_eq!(
+        rt.block_on(full_state(delta2.clone())).unwrap(),
+        vec![(keys[0], blob(0)), (keys[2], blob(20))],
+    );
+    // Removing an account that's not in the base fails.
+    let bad_delta: Vec<DeltaRecord> = vec![(keys[1], None)];
+    let bad_delta = rt.block_on(write_single_chunk_backup(
+        &*store,
+        "bad_delta",
+        &bad_delta,
+        single_chunk_manifest(3, keys[0], keys[2], 2, Some(delta2)),
+    ));
+    assert!(rt.block_on(full_state(bad_delta)).is_err());
+}
@@ -3,9 +3,12 @@
 
 use crate::{
     backup_types::transaction::manifest::{TransactionBackup, TransactionChunk},
+    metadata::{save_metadata, Metadata},
     storage::{BackupHandleRef, BackupStorage, FileHandle, ShellSafeName},
     utils::{
-        backup_service_client::BackupServiceClient, read_record_bytes::ReadRecordBytes,
+        backup_service_client::BackupServiceClient,
+        chunk_codec::{ChunkCodec, ChunkId},
+        read_record_bytes::ReadRecordBytes,
         should_cut_chunk, GlobalBackupOpt,
     },
 };
@@ -31,6 +34,7 @@ pub struct TransactionBackupController {
     max_chunk_size: usize,
     client: Arc<BackupServiceClient>,
     storage: Arc<dyn BackupStorage>,
+    chunk_codec: Arc<ChunkCodec>,
 }
 
 impl TransactionBackupController {
@@ -39,6 +43,7 @@ impl TransactionBackupController {
         global_opt: GlobalBackupOpt,
         client: Arc<BackupServiceClient>,
         storage: Arc<dyn BackupStorage>,
+        chunk_codec: Arc<ChunkCodec>,
     ) -> Self {
         Self {
             start_version: opt.start_version,
@@ -46,6 +51,7 @@ impl TransactionBackupController {
             max_chunk_size: global_opt.max_chunk_size,
             client,
             storage,
+            chunk_codec,
         }
     }
 
@@ -63,7 +69,7 @@ impl TransactionBackupController {
         let mut chunk_first_ver: u64 = self.start_version;
 
         while let Some(record_bytes) = transactions_file.read_record_bytes().await? {
-            if should_cut_chunk(&chunk_bytes, &record_bytes, self.max_chunk_size) {
+            if should_cut_chunk(chunk_bytes.len(), &record_bytes, self.max_chunk_size) {
                 println!("New chunk.");
 
                 let chunk = self
@@ -150,17 +156,28 @@ impl TransactionBackupController {
             &mut proof_file,
         )
         .await?;
+        proof_file.shutdown().await?;
 
         let (chunk_handle, mut chunk_file) = self
             .storage
             .create_for_write(backup_handle, &Self::chunk_name(first_version))
             .await?;
-        chunk_file.write_all(&chunk_bytes).await?;
+        chunk_file
+            .write_all(&self.chunk_codec.encode(
+                chunk_bytes,
+                &ChunkId::Transaction {
+                    first_version,
+                    last_version,
+                },
+            )?)
+            .await?;
+        chunk_file.shutdown().await?;
 
         Ok(TransactionChunk {
             first_version,
             last_version,
             transactions: chunk_handle,
+            format: self.chunk_codec.format(),
             proof: proof_handle,
         })
     }
@@ -184,6 +201,11 @@ impl TransactionBackupController {
         manifest_file
             .write_all(&serde_json::to_vec(&manifest)?)
             .await?;
+        manifest_file.shutdown().await?;
+
+        let metadata =
+            Metadata::new_transaction_backup(first_version, last_version, manifest_handle.clone());
+        save_metadata(&*self.storage, &metadata).await?;
 
         Ok(manifest_handle)
     }
@@ -1,7 +1,10 @@
 // Copyright (c) The Libra Core Contributors
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::storage::FileHandle;
+use crate::{
+    storage::FileHandle,
+    utils::chunk_codec::{ChunkFormat, ChunkId},
+};
 use anyhow::{ensure, Result};
 use libra_types::transaction::Version;
 use serde::{Deserialize, Serialize};
@@ -15,6 +18,9 @@ pub struct TransactionChunk {
     /// Repeated `len(record) + record`, where `record` is LCS serialized tuple
     /// `(Transaction, TransactionInfo)`
     pub transactions: FileHandle,
+    /// How `transactions` is compressed and encrypted.
+    #[serde(default)]
+    pub format: ChunkFormat,
     /// LCS serialized `(TransactionAccumulatorRangeProof, LedgerInfoWithSignatures)`.
     /// The `TransactionAccumulatorRangeProof` links the transactions to the
     /// `LedgerInfoWithSignatures`, and the `LedgerInfoWithSignatures` can be verified by the
@@ -23,6 +29,15 @@ pub struct TransactionChunk {
     pub proof: FileHandle,
 }
 
+impl TransactionChunk {
+    pub fn id(&self) -> ChunkId {
+        ChunkId::Transaction {
+            first_version: self.first_version,
+            last_version: self.last_version,
+        }
+    }
+}
+
 /// Transaction backup manifest, representing transactions in the
 /// [`first_version`, `last_version`] range (right side inclusive).
 #[derive(Deserialize, Serialize)]
@@ -2,11 +2,13 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
-    backup_types::transaction::manifest::TransactionBackup,
-    storage::{BackupStorage, FileHandle},
-    utils::read_record_bytes::ReadRecordBytes,
+    backup_types::transaction::manifest::{TransactionBackup, TransactionChunk},
+    storage::{BackupStorage, FileHandle, FileHandleRef},
+    utils::{chunk_codec::ChunkCodec, run_ahead, GlobalRestoreOpt},
 };
 use anyhow::{ensure, Result};
+use futures::TryStreamExt;
+use libra_crypto::hash::CryptoHash;
 use libra_types::{
     ledger_info::LedgerInfoWithSignatures,
     proof::{TransactionAccumulatorRangeProof, TransactionListProof},
@@ -25,20 +27,28 @@ pub struct TransactionRestoreOpt {
 
 pub struct TransactionRestoreController {
     storage: Arc<dyn BackupStorage>,
+    chunk_codec: Arc<ChunkCodec>,
     restore_handler: Arc<RestoreHandler>,
     manifest_handle: FileHandle,
+    target_version: Version,
+    concurrent_downloads: usize,
 }
 
 impl TransactionRestoreController {
     pub fn new(
         opt: TransactionRestoreOpt,
+        global_opt: GlobalRestoreOpt,
         storage: Arc<dyn BackupStorage>,
+        chunk_codec: Arc<ChunkCodec>,
         restore_handler: Arc<RestoreHandler>,
     ) -> Self {
         Self {
             storage,
+            chunk_codec,
             restore_handler,
             manifest_handle: opt.manifest_handle,
+            target_version: global_opt.target_version(),
+            concurrent_downloads: global_opt.concurrent_downloads,
         }
     }
 
@@ -52,26 +62,26 @@ impl TransactionRestoreController {
         let manifest: TransactionBackup = serde_json::from_slice(&manifest_bytes)?;
         manifest.verify()?;
 
-        let mut first_chunk = true;
-        for chunk in manifest.chunks {
-            let (txns, txn_infos) = self.read_chunk(chunk.transactions).await?;
-            let (proof, ledger_info) = self.read_proof(chunk.proof).await?;
-            ensure!(
-                chunk.first_version + (txns.len() as Version) == chunk.last_version + 1,
-                "Number of items in chunks doesn't match that in manifest. first_version: {}, last_version: {}, items in chunk: {}",
-                chunk.first_version,
-                chunk.last_version,
-                txns.len(),
-            );
-
-            let txn_list_with_proof = TransactionListWithProof::new(
-                txns,
-                None,
-                Some(chunk.first_version),
-                TransactionListProof::new(proof, txn_infos),
-            );
-            txn_list_with_proof.verify(ledger_info.ledger_info(), Some(chunk.first_version))?;
+        let target_version = self.target_version;
+        let chunks = run_ahead(
+            manifest
+                .chunks
+                .into_iter()
+                .take_while(|chunk| chunk.first_version <= target_version),
+            self.concurrent_downloads,
+            |chunk| {
+                LoadedChunk::load(
+                    Arc::clone(&self.storage),
+                    Arc::clone(&self.chunk_codec),
+                    chunk,
+                )
+            },
+        );
+        futures::pin_mut!(chunks);
 
+        let mut first_chunk = true;
+        while let Some(chunk) = chunks.try_next().await? {
+            let (txn_list_with_proof, ledger_info) = chunk.into_txn_list_with_proof(target_version);
             // write to db
             self.restore_handler.save_transactions(
                 &txn_list_with_proof,
@@ -85,29 +95,86 @@ impl TransactionRestoreController {
     }
 }
 
-impl TransactionRestoreController {
-    async fn read_chunk(
-        &self,
-        file_handle: FileHandle,
-    ) -> Result<(Vec<Transaction>, Vec<TransactionInfo>)> {
-        let mut file = self.storage.open_for_read(&file_handle).await?;
-        let mut txns = Vec::new();
-        let mut txn_infos = Vec::new();
+/// A chunk of a transaction backup, read from the storage and verified against the LedgerInfo
+/// in its proof.
+#[derive(Clone)]
+pub(crate) struct LoadedChunk {
+    pub first_version: Version,
+    pub txns: Vec<Transaction>,
+    pub txn_infos: Vec<TransactionInfo>,
+    pub range_proof: TransactionAccumulatorRangeProof,
+    pub ledger_info: LedgerInfoWithSignatures,
+}
 
-        while let Some(record_bytes) = file.read_record_bytes().await? {
-            let (txn, txn_info) = lcs::from_bytes(&record_bytes)?;
-            txns.push(txn);
-            txn_infos.push(txn_info);
-        }
+impl LoadedChunk {
+    /// Runs ahead of the DB writes, so it only touches the storage.
+    pub async fn load(
+        storage: Arc<dyn BackupStorage>,
+        chunk_codec: Arc<ChunkCodec>,
+        chunk: TransactionChunk,
+    ) -> Result<Self> {
+        let (txns, txn_infos): (Vec<Transaction>, Vec<TransactionInfo>) = chunk_codec
+            .read_records::<(Transaction, TransactionInfo)>(
+                &*storage,
+                &chunk.transactions,
+                chunk.format,
+                &chunk.id(),
+            )
+            .await?
+            .into_iter()
+            .unzip();
+        let (range_proof, ledger_info) = Self::read_proof(&*storage, &chunk.proof).await?;
+        ensure!(
+            chunk.first_version + (txns.len() as Version) == chunk.last_version + 1,
+            "Number of items in chunks doesn't match that in manifest. first_version: {}, last_version: {}, items in chunk: {}",
+            chunk.first_version,
+            chunk.last_version,
+            txns.len(),
+        );
 
-        Ok((txns, txn_infos))
+        let txn_hashes: Vec<_> = txns.iter().map(CryptoHash::hash).collect();
+        TransactionListProof::new(range_proof.clone(), txn_infos.clone()).verify(
+            ledger_info.ledger_info(),
+            Some(chunk.first_version),
+            &txn_hashes,
+        )?;
+
+        Ok(Self {
+            first_version: chunk.first_version,
+            txns,
+            txn_infos,
+            range_proof,
+            ledger_info,
+        })
+    }
+
+    /// Leaves out transactions after `last_version`. The left siblings in the proof, which is all
+    /// that gets saved of it, still apply.
+    pub fn into_txn_list_with_proof(
+        mut self,
+        last_version: Version,
+    ) -> (TransactionListWithProof, LedgerInfoWithSignatures) {
+        if last_version < self.first_version + self.txns.len() as Version {
+            let num_txns = (last_version + 1).saturating_sub(self.first_version) as usize;
+            self.txns.truncate(num_txns);
+            self.txn_infos.truncate(num_txns);
+        }
+        (
+            TransactionListWithProof::new(
+                self.txns,
+                None,
+                Some(self.first_version),
+                TransactionListProof::new(self.range_proof, self.txn_infos),
+            ),
+            self.ledger_info,
+        )
     }
 
     async fn read_proof(
-        &self,
-        file_handle: FileHandle,
+        storage: &dyn BackupStorage,
+        file_handle: &FileHandleRef,
     ) -> Result<(TransactionAccumulatorRangeProof, LedgerInfoWithSignatures)> {
-        let mut file = self.storage.open_for_read(&file_handle).await?;
+        let mut file = storage.open_for_read(file_handle).await?;
         let mut bytes = Vec::new();
         file.read_to_end(&mut bytes).await?;
         Ok(lcs::from_bytes(&bytes)?)
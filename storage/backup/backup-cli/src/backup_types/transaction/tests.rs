@@ -9,8 +9,9 @@ use crate::{
     storage::{local_fs::LocalFs, BackupStorage},
     utils::{
         backup_service_client::BackupServiceClient,
+        chunk_codec::{ChunkCodec, ChunkFormat, Compression, Encryption, Key},
         test_utils::{tmp_db_empty, tmp_db_with_random_content},
-        GlobalBackupOpt,
+        GlobalBackupOpt, GlobalRestoreOpt,
     },
 };
 use backup_service::start_backup_service;
@@ -24,7 +25,7 @@ use tokio::time::Duration;
 #[test]
 fn end_to_end() {
     let (_src_db_dir, src_db, blocks) = tmp_db_with_random_content();
-    let (_tgt_db_dir, tgt_db) = tmp_db_empty();
+    let (tgt_db_dir, tgt_db) = tmp_db_empty();
     let backup_dir = TempPath::new();
     backup_dir.create_as_dir().unwrap();
     let store: Arc<dyn BackupStorage> = Arc::new(LocalFs::new(backup_dir.path().to_path_buf()));
@@ -52,6 +53,7 @@ fn end_to_end() {
     let first_ver_to_backup = (total_txns / 2) as Version;
     let num_txns_to_backup = total_txns - first_ver_to_backup as usize;
 
+    let key: Key = rand::random();
     let manifest_handle = rt
         .block_on(
             TransactionBackupController::new(
@@ -62,6 +64,13 @@ fn end_to_end() {
                 GlobalBackupOpt { max_chunk_size },
                 client,
                 Arc::clone(&store),
+                Arc::new(
+                    ChunkCodec::new(
+                        ChunkFormat::new(Compression::Gzip, Encryption::Aes256Gcm),
+                        Some(key),
+                    )
+                    .unwrap(),
+                ),
             )
             .run(),
         )
@@ -70,7 +79,13 @@ fn end_to_end() {
     rt.block_on(
         TransactionRestoreController::new(
             TransactionRestoreOpt { manifest_handle },
+            GlobalRestoreOpt {
+                db_dir: tgt_db_dir.path().to_path_buf(),
+                target_version: None,
+                concurrent_downloads: 4,
+            },
             store,
+            Arc::new(ChunkCodec::new_for_restore(Some(key))),
             Arc::new(tgt_db.get_restore_handler()),
         )
         .run(),
@@ -0,0 +1,40 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use anyhow::{Context, Result};
+use backup_cli::{
+    coordinators::maintenance::{MaintenanceCoordinator, MaintenanceCoordinatorOpt},
+    storage::StorageOpt,
+};
+use structopt::StructOpt;
+
+#[derive(StructOpt)]
+#[structopt(
+    about = "Delete backups falling out of the retention policies, and files not referenced by \
+    any backup kept. Must not run concurrently with backups."
+)]
+struct Opt {
+    #[structopt(flatten)]
+    opt: MaintenanceCoordinatorOpt,
+
+    #[structopt(subcommand)]
+    storage: StorageOpt,
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    let opt = Opt::from_args();
+
+    let dry_run = opt.opt.dry_run;
+    MaintenanceCoordinator::new(opt.opt, opt.storage.init_storage().await?)
+        .run()
+        .await
+        .context("Failed maintaining backups.")?;
+    if dry_run {
+        println!("Finished dry run, nothing deleted.");
+    } else {
+        println!("Finished maintaining backups.");
+    }
+
+    Ok(())
+}
@@ -0,0 +1,46 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use anyhow::{bail, Context, Result};
+use backup_cli::{
+    coordinators::verify::{VerifyCoordinator, VerifyCoordinatorOpt},
+    storage::StorageOpt,
+    utils::chunk_codec::ChunkDecodeOpt,
+};
+use std::sync::Arc;
+use structopt::StructOpt;
+
+#[derive(StructOpt)]
+#[structopt(
+    about = "Verify all backups in the storage against trusted waypoints, without a LibraDB."
+)]
+struct Opt {
+    #[structopt(flatten)]
+    opt: VerifyCoordinatorOpt,
+
+    #[structopt(flatten)]
+    chunk_codec: ChunkDecodeOpt,
+
+    #[structopt(subcommand)]
+    storage: StorageOpt,
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    let opt = Opt::from_args();
+
+    let chunk_codec = Arc::new(opt.chunk_codec.codec()?);
+    let report = VerifyCoordinator::new(opt.opt, opt.storage.init_storage().await?, chunk_codec)
+        .run()
+        .await
+        .context("Failed verifying backups.")?;
+    if !report.is_ok() {
+        bail!(
+            "Verification failed, {} corrupted or missing pieces of backups found.",
+            report.failures.len()
+        );
+    }
+    println!("Finished verifying backups.");
+
+    Ok(())
+}
@@ -4,9 +4,12 @@
 use anyhow::{Context, Result};
 use backup_cli::{
     backup_types::state_snapshot::backup::{StateSnapshotBackupController, StateSnapshotBackupOpt},
+    coordinators::list::{CatalogEntry, ListCoordinator, ListCoordinatorOpt},
+    metadata::Metadata,
     storage::StorageOpt,
     utils::{
         backup_service_client::{BackupServiceClient, BackupServiceClientOpt},
+        chunk_codec::{ChunkCodecOpt, ChunkDecodeOpt},
         GlobalBackupOpt,
     },
 };
@@ -18,6 +21,8 @@ use structopt::StructOpt;
 enum Command {
     #[structopt(about = "Manually run one shot commands.")]
     OneShot(OneShotCommand),
+    #[structopt(about = "List the backups in the storage, to tell what can be restored.")]
+    List(ListOpt),
 }
 
 #[derive(StructOpt)]
@@ -44,6 +49,9 @@ struct OneShotBackupOpt {
     #[structopt(flatten)]
     global: GlobalBackupOpt,
 
+    #[structopt(flatten)]
+    chunk_codec: ChunkCodecOpt,
+
     #[structopt(flatten)]
     state_snapshot: StateSnapshotBackupOpt,
 
@@ -54,6 +62,18 @@ struct OneShotBackupOpt {
     storage: StorageOpt,
 }
 
+#[derive(StructOpt)]
+struct ListOpt {
+    #[structopt(flatten)]
+    opt: ListCoordinatorOpt,
+
+    #[structopt(flatten)]
+    chunk_codec: ChunkDecodeOpt,
+
+    #[structopt(subcommand)]
+    storage: StorageOpt,
+}
+
 #[tokio::main]
 async fn main() -> Result<()> {
     let cmd = Command::from_args();
@@ -69,12 +89,14 @@ async fn main() -> Result<()> {
             OneShotCommand::Backup(opt) => {
                 let client = Arc::new(BackupServiceClient::new_with_opt(opt.client));
                 let storage = opt.storage.init_storage().await?;
+                let chunk_codec = Arc::new(opt.chunk_codec.codec()?);
 
                 let manifest = StateSnapshotBackupController::new(
                     opt.state_snapshot,
                     opt.global,
                     client,
                     storage,
+                    chunk_codec,
                 )
                 .run()
                 .await
@@ -83,6 +105,44 @@ async fn main() -> Result<()> {
                 println!("Success. Manifest saved to {}", &manifest);
             }
         },
+        Command::List(opt) => {
+            let chunk_codec = Arc::new(opt.chunk_codec.codec()?);
+            let entries =
+                ListCoordinator::new(opt.opt, opt.storage.init_storage().await?, chunk_codec)
+                    .run()
+                    .await
+                    .context("Failed listing backups.")?;
+            for entry in &entries {
+                print_entry(entry);
+            }
+        }
     }
     Ok(())
 }
+
+fn print_entry(entry: &CatalogEntry) {
+    let what = match &entry.metadata {
+        Metadata::EpochEndingBackup(e) => format!(
+            "epoch_ending   epochs [{}, {}]",
+            e.first_epoch, e.last_epoch
+        ),
+        Metadata::StateSnapshotBackup(s) => format!(
+            "state_snapshot version {} root_hash {}",
+            s.version,
+            entry
+                .root_hash
+                .map_or_else(|| "unknown".to_string(), |h| h.to_hex()),
+        ),
+        Metadata::TransactionBackup(t) => format!(
+            "transaction    versions [{}, {}]",
+            t.first_version, t.last_version,
+        ),
+    };
+    println!(
+        "{} size {} timestamp_usecs {} manifest {}",
+        what,
+        entry.size,
+        entry.timestamp_usecs,
+        entry.metadata.manifest(),
+    );
+}
@@ -0,0 +1,66 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use anyhow::{Context, Result};
+use backup_cli::{
+    coordinators::replay_verify::{ReplayVerifyCoordinator, ReplayVerifyCoordinatorOpt},
+    storage::StorageOpt,
+    utils::{chunk_codec::ChunkDecodeOpt, GlobalRestoreOpt},
+};
+use libradb::LibraDB;
+use std::sync::Arc;
+use storage_interface::DbReaderWriter;
+use structopt::StructOpt;
+
+#[derive(StructOpt)]
+#[structopt(
+    about = "Replay transaction backups through the executor from a state snapshot, checking the \
+    results against the backups. The DB only holds the state snapshot and what the replayed \
+    transactions write, and can be removed afterwards."
+)]
+struct Opt {
+    #[structopt(flatten)]
+    global: GlobalRestoreOpt,
+
+    #[structopt(flatten)]
+    opt: ReplayVerifyCoordinatorOpt,
+
+    #[structopt(flatten)]
+    chunk_codec: ChunkDecodeOpt,
+
+    #[structopt(subcommand)]
+    storage: StorageOpt,
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    let opt = Opt::from_args();
+
+    let (db, db_rw) = DbReaderWriter::wrap(
+        LibraDB::open(
+            &opt.global.db_dir,
+            false, /* read_only */
+            None,  /* pruner */
+        )
+        .expect("Failed opening DB."),
+    );
+    let restore_handler = Arc::new(db.get_restore_handler());
+    let chunk_codec = Arc::new(opt.chunk_codec.codec()?);
+    let num_replayed = ReplayVerifyCoordinator::new(
+        opt.opt,
+        opt.global,
+        opt.storage.init_storage().await?,
+        chunk_codec,
+        restore_handler,
+        db_rw,
+    )
+    .run()
+    .await
+    .context("Failed replay verifying backups.")?;
+    println!(
+        "Finished replay verifying backups, {} transactions replayed.",
+        num_replayed
+    );
+
+    Ok(())
+}
@@ -3,11 +3,17 @@
 
 use anyhow::{Context, Result};
 use backup_cli::{
-    backup_types::state_snapshot::restore::{
-        StateSnapshotRestoreController, StateSnapshotRestoreOpt,
+    backup_types::{
+        epoch_ending::restore::{EpochEndingRestoreController, EpochEndingRestoreOpt},
+        state_snapshot::restore::{StateSnapshotRestoreController, StateSnapshotRestoreOpt},
+        transaction::restore::{TransactionRestoreController, TransactionRestoreOpt},
+    },
+    coordinators::{
+        dry_run::{DryRunReport, RestoreDryRun},
+        restore::{RestoreCoordinator, RestoreCoordinatorOpt},
     },
     storage::StorageOpt,
-    utils::GlobalRestoreOpt,
+    utils::{chunk_codec::ChunkDecodeOpt, GlobalRestoreOpt},
 };
 use libradb::LibraDB;
 use std::sync::Arc;
@@ -19,32 +25,165 @@ struct Opt {
     global: GlobalRestoreOpt,
 
     #[structopt(flatten)]
-    state_snapshot: StateSnapshotRestoreOpt,
+    chunk_codec: ChunkDecodeOpt,
+
+    #[structopt(
+        long = "dry-run",
+        help = "List the manifests and files the restore would fetch with their sizes, and \
+        validate the manifests, without opening the DB."
+    )]
+    dry_run: bool,
 
     #[structopt(subcommand)]
-    storage: StorageOpt,
+    restore_type: RestoreType,
+}
+
+#[derive(StructOpt)]
+enum RestoreType {
+    #[structopt(about = "Restore epoch ending LedgerInfos from a single backup.")]
+    EpochEnding {
+        #[structopt(flatten)]
+        opt: EpochEndingRestoreOpt,
+        #[structopt(subcommand)]
+        storage: StorageOpt,
+    },
+    #[structopt(about = "Restore account state from a single state snapshot backup.")]
+    StateSnapshot {
+        #[structopt(flatten)]
+        opt: StateSnapshotRestoreOpt,
+        #[structopt(subcommand)]
+        storage: StorageOpt,
+    },
+    #[structopt(about = "Restore transactions from a single backup.")]
+    Transaction {
+        #[structopt(flatten)]
+        opt: TransactionRestoreOpt,
+        #[structopt(subcommand)]
+        storage: StorageOpt,
+    },
+    #[structopt(
+        about = "Discover the latest backups in the storage and restore epoch endings, state \
+        snapshot and transactions in order."
+    )]
+    Auto {
+        #[structopt(flatten)]
+        opt: RestoreCoordinatorOpt,
+        #[structopt(subcommand)]
+        storage: StorageOpt,
+    },
 }
 
 #[tokio::main]
 async fn main() -> Result<()> {
     let opt = Opt::from_args();
+    let global_opt = opt.global;
+
+    if opt.dry_run {
+        let report = dry_run(global_opt, opt.restore_type)
+            .await
+            .context("Failed dry running restore.")?;
+        for (file_handle, size) in report.manifests.iter().chain(&report.files) {
+            println!("{} {}", size, file_handle);
+        }
+        println!(
+            "Dry run finished: {} manifests, {} files, {} bytes in total.",
+            report.manifests.len(),
+            report.files.len(),
+            report.total_bytes(),
+        );
+        return Ok(());
+    }
 
     let db = Arc::new(
         LibraDB::open(
-            opt.global.db_dir,
+            &global_opt.db_dir,
             false, /* read_only */
             None,  /* pruner */
         )
         .expect("Failed opening DB."),
     );
-    let storage = opt.storage.init_storage().await?;
     let restore_handler = Arc::new(db.get_restore_handler());
-    StateSnapshotRestoreController::new(opt.state_snapshot, storage, restore_handler)
-        .run()
-        .await
-        .context("Failed restoring state_snapshot.")?;
+    let chunk_codec = Arc::new(opt.chunk_codec.codec()?);
 
-    println!("Finished restoring account state.");
+    match opt.restore_type {
+        RestoreType::EpochEnding { opt, storage } => {
+            EpochEndingRestoreController::new(
+                opt,
+                global_opt,
+                storage.init_storage().await?,
+                chunk_codec,
+                restore_handler,
+            )
+            .run()
+            .await
+            .context("Failed restoring epoch ending backup.")?;
+            println!("Finished restoring epoch ending LedgerInfos.");
+        }
+        RestoreType::StateSnapshot { opt, storage } => {
+            StateSnapshotRestoreController::new(
+                opt,
+                global_opt,
+                storage.init_storage().await?,
+                chunk_codec,
+                restore_handler,
+            )
+            .run()
+            .await
+            .context("Failed restoring state_snapshot.")?;
+            println!("Finished restoring account state.");
+        }
+        RestoreType::Transaction { opt, storage } => {
+            TransactionRestoreController::new(
+                opt,
+                global_opt,
+                storage.init_storage().await?,
+                chunk_codec,
+                restore_handler,
+            )
+            .run()
+            .await
+            .context("Failed restoring transactions.")?;
+            println!("Finished restoring transactions.");
+        }
+        RestoreType::Auto { opt, storage } => {
+            RestoreCoordinator::new(
+                opt,
+                global_opt,
+                storage.init_storage().await?,
+                chunk_codec,
+                restore_handler,
+            )
+            .run()
+            .await
+            .context("Failed restoring from backups.")?;
+            println!("Finished restoring from backups.");
+        }
+    }
 
     Ok(())
 }
+
+async fn dry_run(global_opt: GlobalRestoreOpt, restore_type: RestoreType) -> Result<DryRunReport> {
+    match restore_type {
+        RestoreType::EpochEnding { opt, storage } => {
+            let mut dry_run = RestoreDryRun::new(global_opt, storage.init_storage().await?);
+            dry_run.add_epoch_ending(&opt.manifest_handle).await?;
+            Ok(dry_run.finish())
+        }
+        RestoreType::StateSnapshot { opt, storage } => {
+            let mut dry_run = RestoreDryRun::new(global_opt, storage.init_storage().await?);
+            dry_run.add_state_snapshot(&opt.manifest_handle).await?;
+            Ok(dry_run.finish())
+        }
+        RestoreType::Transaction { opt, storage } => {
+            let mut dry_run = RestoreDryRun::new(global_opt, storage.init_storage().await?);
+            dry_run.add_transaction(&opt.manifest_handle).await?;
+            Ok(dry_run.finish())
+        }
+        RestoreType::Auto { storage, .. } => {
+            let mut dry_run = RestoreDryRun::new(global_opt, storage.init_storage().await?);
+            dry_run.add_all().await?;
+            Ok(dry_run.finish())
+        }
+    }
+}
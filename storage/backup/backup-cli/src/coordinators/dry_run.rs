@@ -0,0 +1,161 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    backup_types::{
+        epoch_ending::manifest::EpochEndingBackup, state_snapshot::manifest::StateSnapshotBackup,
+        transaction::manifest::TransactionBackup,
+    },
+    coordinators::restore::RestoreSelection,
+    storage::{BackupStorage, FileHandle, FileHandleRef},
+    utils::{file_size, GlobalRestoreOpt},
+};
+use anyhow::{ensure, Result};
+use libra_types::transaction::Version;
+use serde::de::DeserializeOwned;
+use std::sync::Arc;
+use tokio::io::AsyncReadExt;
+
+/// What a restore would fetch from the storage.
+#[derive(Debug, Default)]
+pub struct DryRunReport {
+    /// Manifests with their sizes in bytes, in the order they are restored.
+    pub manifests: Vec<(FileHandle, u64)>,
+    /// Chunk and proof files with their sizes in bytes, in the order they are restored.
+    pub files: Vec<(FileHandle, u64)>,
+}
+
+impl DryRunReport {
+    pub fn total_bytes(&self) -> u64 {
+        self.manifests
+            .iter()
+            .chain(&self.files)
+            .map(|(_, size)| size)
+            .sum()
+    }
+}
+
+/// Goes through the manifests a restore would, validating them and measuring the files they
+/// reference, without touching any DB. Files are read through to measure them, but not kept in
+/// memory.
+pub struct RestoreDryRun {
+    storage: Arc<dyn BackupStorage>,
+    global_opt: GlobalRestoreOpt,
+    report: DryRunReport,
+}
+
+impl RestoreDryRun {
+    pub fn new(global_opt: GlobalRestoreOpt, storage: Arc<dyn BackupStorage>) -> Self {
+        Self {
+            storage,
+            global_opt,
+            report: DryRunReport::default(),
+        }
+    }
+
+    pub fn finish(self) -> DryRunReport {
+        self.report
+    }
+
+    /// Adds what `RestoreCoordinator` would restore.
+    pub async fn add_all(&mut self) -> Result<()> {
+        let RestoreSelection {
+            epoch_endings,
+            state_snapshot,
+            transactions,
+        } = RestoreSelection::select(&*self.storage, &self.global_opt).await?;
+
+        for backup in epoch_endings {
+            self.add_epoch_ending(&backup.manifest).await?;
+        }
+        self.add_state_snapshot(&state_snapshot.manifest).await?;
+        for backup in transactions {
+            self.add_transaction(&backup.manifest).await?;
+        }
+        Ok(())
+    }
+
+    pub async fn add_epoch_ending(&mut self, manifest_handle: &FileHandleRef) -> Result<()> {
+        let manifest: EpochEndingBackup = self.load_manifest(manifest_handle).await?;
+        manifest.verify()?;
+        for chunk in manifest.chunks {
+            self.add_file(chunk.ledger_infos).await?;
+        }
+        Ok(())
+    }
+
+    /// Adds the state snapshot backup and, if it's a delta, the backups it's layered over.
+    pub async fn add_state_snapshot(&mut self, manifest_handle: &FileHandleRef) -> Result<()> {
+        let mut manifest: StateSnapshotBackup = self.load_manifest(manifest_handle).await?;
+        ensure!(
+            manifest.version <= self.target_version(),
+            "State snapshot at version {} is after the target version {}.",
+            manifest.version,
+            self.target_version(),
+        );
+        loop {
+            manifest.verify()?;
+            self.add_file(manifest.proof).await?;
+            for chunk in manifest.chunks {
+                self.add_file(chunk.blobs).await?;
+                self.add_file(chunk.proof).await?;
+            }
+            let base_manifest = match manifest.base_manifest {
+                Some(base_manifest) => base_manifest,
+                None => break,
+            };
+            let delta_version = manifest.version;
+            manifest = self.load_manifest(&base_manifest).await?;
+            ensure!(
+                manifest.version < delta_version,
+                "Base state snapshot at version {} is not older than the delta at version {}.",
+                manifest.version,
+                delta_version,
+            );
+        }
+        Ok(())
+    }
+
+    pub async fn add_transaction(&mut self, manifest_handle: &FileHandleRef) -> Result<()> {
+        let manifest: TransactionBackup = self.load_manifest(manifest_handle).await?;
+        manifest.verify()?;
+        let target_version = self.target_version();
+        for chunk in manifest
+            .chunks
+            .into_iter()
+            .take_while(|chunk| chunk.first_version <= target_version)
+        {
+            self.add_file(chunk.transactions).await?;
+            self.add_file(chunk.proof).await?;
+        }
+        Ok(())
+    }
+}
+
+impl RestoreDryRun {
+    fn target_version(&self) -> Version {
+        self.global_opt.target_version()
+    }
+
+    async fn load_manifest<T: DeserializeOwned>(
+        &mut self,
+        manifest_handle: &FileHandleRef,
+    ) -> Result<T> {
+        let mut bytes = Vec::new();
+        self.storage
+            .open_for_read(manifest_handle)
+            .await?
+            .read_to_end(&mut bytes)
+            .await?;
+        self.report
+            .manifests
+            .push((manifest_handle.to_string(), bytes.len() as u64));
+        Ok(serde_json::from_slice(&bytes)?)
+    }
+
+    async fn add_file(&mut self, file_handle: FileHandle) -> Result<()> {
+        let size = file_size(&*self.storage, &file_handle).await?;
+        self.report.files.push((file_handle, size));
+        Ok(())
+    }
+}
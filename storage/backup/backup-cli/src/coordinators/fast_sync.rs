@@ -0,0 +1,125 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    coordinators::restore::{RestoreCoordinator, RestoreCoordinatorOpt, RestoreSelection},
+    storage::{
+        command_adapter::{config::CommandAdapterConfig, CommandAdapter},
+        local_fs::LocalFs,
+        BackupStorage,
+    },
+    utils::{
+        chunk_codec::{ChunkCodec, EncryptionKeyOpt},
+        GlobalRestoreOpt,
+    },
+};
+use anyhow::{ensure, Result};
+use libra_config::config::{BackupStorageConfig, FastSyncConfig};
+use libra_types::waypoint::Waypoint;
+use libradb::backup::restore_handler::RestoreHandler;
+use std::{path::PathBuf, sync::Arc};
+
+/// Bootstraps an empty DB from the latest state snapshot at or after the version of a waypoint, so
+/// that a new node doesn't replay all the transactions since genesis: the epoch ending LedgerInfos
+/// from the waypoint on, the state snapshot, and the transactions up to it are restored. The
+/// waypoint is trusted, so the epoch ending LedgerInfo at its version must match it, those after
+/// it must be signed by the validators of their epochs, and the state snapshot must be proven by
+/// a LedgerInfo verified that way. The epoch ending LedgerInfos before the waypoint can't be
+/// verified, so they aren't restored.
+pub struct FastSyncCoordinator {
+    waypoint: Waypoint,
+    global_opt: GlobalRestoreOpt,
+    storage: Arc<dyn BackupStorage>,
+    chunk_codec: Arc<ChunkCodec>,
+    restore_handler: Arc<RestoreHandler>,
+}
+
+impl FastSyncCoordinator {
+    pub fn new(
+        waypoint: Waypoint,
+        global_opt: GlobalRestoreOpt,
+        storage: Arc<dyn BackupStorage>,
+        chunk_codec: Arc<ChunkCodec>,
+        restore_handler: Arc<RestoreHandler>,
+    ) -> Self {
+        Self {
+            waypoint,
+            global_opt: GlobalRestoreOpt {
+                target_version: None,
+                ..global_opt
+            },
+            storage,
+            chunk_codec,
+            restore_handler,
+        }
+    }
+
+    /// Builds the coordinator bootstrapping the DB of a node under `db_dir`, from the backup
+    /// storage in the fast sync config of the node.
+    pub async fn new_with_config(
+        config: &FastSyncConfig,
+        waypoint: Waypoint,
+        db_dir: PathBuf,
+        restore_handler: Arc<RestoreHandler>,
+    ) -> Result<Self> {
+        let storage: Arc<dyn BackupStorage> = match &config.backup_storage {
+            BackupStorageConfig::LocalFs { dir } => Arc::new(LocalFs::new(dir.clone())),
+            BackupStorageConfig::CommandAdapter { config } => Arc::new(CommandAdapter::new(
+                CommandAdapterConfig::load_from_file(config).await?,
+            )),
+        };
+        let key = EncryptionKeyOpt {
+            storage_config: config.encryption_key_storage.clone(),
+            key_name: config.encryption_key_name.clone(),
+        }
+        .key()?;
+        Ok(Self::new(
+            waypoint,
+            GlobalRestoreOpt {
+                db_dir,
+                target_version: None,
+                concurrent_downloads: config.concurrent_downloads,
+            },
+            storage,
+            Arc::new(ChunkCodec::new_for_restore(key)),
+            restore_handler,
+        ))
+    }
+
+    pub async fn run(self) -> Result<()> {
+        let latest_snapshot_version = RestoreSelection::select(&*self.storage, &self.global_opt)
+            .await?
+            .state_snapshot
+            .version;
+        ensure!(
+            latest_snapshot_version >= self.waypoint.version(),
+            "No state snapshot backup at or after the version of waypoint {}, the latest one is \
+             at version {}.",
+            self.waypoint,
+            latest_snapshot_version,
+        );
+        // the DB is brought up as of the snapshot, and state sync takes it from there
+        let global_opt = GlobalRestoreOpt {
+            target_version: Some(latest_snapshot_version),
+            ..self.global_opt
+        };
+        let selection = RestoreSelection::select(&*self.storage, &global_opt).await?;
+
+        println!(
+            "Fast syncing to version {}, from waypoint {}.",
+            latest_snapshot_version, self.waypoint
+        );
+        RestoreCoordinator::new(
+            RestoreCoordinatorOpt {
+                trust_waypoints: vec![self.waypoint],
+                genesis_waypoint: None,
+            },
+            global_opt,
+            self.storage,
+            self.chunk_codec,
+            self.restore_handler,
+        )
+        .run_selection(selection)
+        .await
+    }
+}
@@ -0,0 +1,193 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    backup_types::{
+        epoch_ending::manifest::EpochEndingBackup, state_snapshot::manifest::StateSnapshotBackup,
+        transaction::manifest::TransactionBackup,
+    },
+    metadata::{
+        cache::{MetadataCache, MetadataCacheOpt},
+        view::MetadataView,
+        Metadata,
+    },
+    storage::{BackupStorage, FileHandleRef},
+    utils::{chunk_codec::ChunkCodec, file_size},
+};
+use anyhow::{anyhow, Result};
+use libra_crypto::HashValue;
+use libra_types::{
+    ledger_info::LedgerInfoWithSignatures,
+    proof::{TransactionAccumulatorRangeProof, TransactionInfoWithProof},
+};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use std::sync::Arc;
+use structopt::StructOpt;
+use tokio::io::AsyncReadExt;
+
+#[derive(StructOpt)]
+pub struct ListCoordinatorOpt {
+    #[structopt(flatten)]
+    pub metadata_cache: MetadataCacheOpt,
+}
+
+/// A backup available in the storage, along with what's learned from its manifest.
+#[derive(Clone, Debug, Deserialize, Serialize, Eq, PartialEq)]
+pub struct CatalogEntry {
+    pub metadata: Metadata,
+    /// Total size in bytes of the manifest and the files it references. For a delta state
+    /// snapshot, that's without the backups it's layered over.
+    pub size: u64,
+    /// Timestamp of the last LedgerInfo in an epoch ending backup, or of the LedgerInfo a state
+    /// snapshot or the last chunk of transactions is proven against.
+    pub timestamp_usecs: u64,
+    /// Root hash of a state snapshot.
+    pub root_hash: Option<HashValue>,
+}
+
+/// Lists all backups in a storage, in the order a restore would consider them. Files are read
+/// through to tell their sizes, so listing a backup for the first time is expensive; with a
+/// metadata cache, that's done only once per backup.
+pub struct ListCoordinator {
+    storage: Arc<dyn BackupStorage>,
+    chunk_codec: Arc<ChunkCodec>,
+    metadata_cache: MetadataCache,
+}
+
+impl ListCoordinator {
+    pub fn new(
+        opt: ListCoordinatorOpt,
+        storage: Arc<dyn BackupStorage>,
+        chunk_codec: Arc<ChunkCodec>,
+    ) -> Self {
+        Self {
+            storage,
+            chunk_codec,
+            metadata_cache: MetadataCache::new(opt.metadata_cache),
+        }
+    }
+
+    pub async fn run(self) -> Result<Vec<CatalogEntry>> {
+        let view: MetadataView = self
+            .metadata_cache
+            .sync_and_load(&*self.storage)
+            .await?
+            .into();
+        let metadata = view
+            .epoch_ending_backups()
+            .iter()
+            .cloned()
+            .map(Metadata::EpochEndingBackup)
+            .chain(
+                view.state_snapshot_backups()
+                    .iter()
+                    .cloned()
+                    .map(Metadata::StateSnapshotBackup),
+            )
+            .chain(
+                view.transaction_backups()
+                    .iter()
+                    .cloned()
+                    .map(Metadata::TransactionBackup),
+            );
+
+        let mut entries = Vec::new();
+        let mut manifests = Vec::new();
+        for metadata in metadata {
+            let manifest_handle = metadata.manifest().clone();
+            let entry = match self.metadata_cache.load_details(&manifest_handle).await? {
+                Some(entry) => entry,
+                None => {
+                    let entry = self.new_entry(metadata).await?;
+                    self.metadata_cache
+                        .save_details(&manifest_handle, &entry)
+                        .await?;
+                    entry
+                }
+            };
+            entries.push(entry);
+            manifests.push(manifest_handle);
+        }
+        self.metadata_cache.retain_details(&manifests).await?;
+
+        Ok(entries)
+    }
+}
+
+impl ListCoordinator {
+    async fn new_entry(&self, metadata: Metadata) -> Result<CatalogEntry> {
+        let manifest_handle = metadata.manifest();
+        let manifest_bytes = self.read_all(manifest_handle).await?;
+        let mut size = manifest_bytes.len() as u64;
+
+        let (timestamp_usecs, root_hash) = match &metadata {
+            Metadata::EpochEndingBackup(_) => {
+                let manifest: EpochEndingBackup = serde_json::from_slice(&manifest_bytes)?;
+                for chunk in &manifest.chunks {
+                    size += file_size(&*self.storage, &chunk.ledger_infos).await?;
+                }
+                let last_chunk = manifest.chunks.last().ok_or_else(|| {
+                    anyhow!("No chunks in epoch ending backup {}.", manifest_handle)
+                })?;
+                let lis: Vec<LedgerInfoWithSignatures> = self
+                    .chunk_codec
+                    .read_records(
+                        &*self.storage,
+                        &last_chunk.ledger_infos,
+                        last_chunk.format,
+                        &last_chunk.id(),
+                    )
+                    .await?;
+                let last_li = lis
+                    .last()
+                    .ok_or_else(|| anyhow!("Empty chunk {}.", last_chunk.ledger_infos))?;
+                (last_li.ledger_info().timestamp_usecs(), None)
+            }
+            Metadata::StateSnapshotBackup(_) => {
+                let manifest: StateSnapshotBackup = serde_json::from_slice(&manifest_bytes)?;
+                size += file_size(&*self.storage, &manifest.proof).await?;
+                for chunk in &manifest.chunks {
+                    size += file_size(&*self.storage, &chunk.blobs).await?;
+                    size += file_size(&*self.storage, &chunk.proof).await?;
+                }
+                let (_, li): (TransactionInfoWithProof, LedgerInfoWithSignatures) =
+                    self.load_lcs(&manifest.proof).await?;
+                (li.ledger_info().timestamp_usecs(), Some(manifest.root_hash))
+            }
+            Metadata::TransactionBackup(_) => {
+                let manifest: TransactionBackup = serde_json::from_slice(&manifest_bytes)?;
+                for chunk in &manifest.chunks {
+                    size += file_size(&*self.storage, &chunk.transactions).await?;
+                    size += file_size(&*self.storage, &chunk.proof).await?;
+                }
+                let last_chunk = manifest.chunks.last().ok_or_else(|| {
+                    anyhow!("No chunks in transaction backup {}.", manifest_handle)
+                })?;
+                let (_, li): (TransactionAccumulatorRangeProof, LedgerInfoWithSignatures) =
+                    self.load_lcs(&last_chunk.proof).await?;
+                (li.ledger_info().timestamp_usecs(), None)
+            }
+        };
+
+        Ok(CatalogEntry {
+            metadata,
+            size,
+            timestamp_usecs,
+            root_hash,
+        })
+    }
+
+    async fn read_all(&self, file_handle: &FileHandleRef) -> Result<Vec<u8>> {
+        let mut bytes = Vec::new();
+        self.storage
+            .open_for_read(file_handle)
+            .await?
+            .read_to_end(&mut bytes)
+            .await?;
+        Ok(bytes)
+    }
+
+    async fn load_lcs<T: DeserializeOwned>(&self, file_handle: &FileHandleRef) -> Result<T> {
+        Ok(lcs::from_bytes(&self.read_all(file_handle).await?)?)
+    }
+}
@@ -0,0 +1,317 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    backup_types::{
+        epoch_ending::manifest::EpochEndingBackup, state_snapshot::delta::read_manifest,
+        transaction::manifest::TransactionBackup,
+    },
+    metadata::{load_metadata_files, view::MetadataView, Metadata},
+    storage::{BackupStorage, FileHandle, FileHandleRef},
+};
+use anyhow::{anyhow, ensure, Result};
+use libra_types::{ledger_info::LedgerInfoWithSignatures, proof::TransactionAccumulatorRangeProof};
+use serde::de::DeserializeOwned;
+use std::{
+    cmp::Reverse,
+    collections::{HashMap, HashSet},
+    sync::Arc,
+    time::{SystemTime, UNIX_EPOCH},
+};
+use structopt::StructOpt;
+use tokio::io::AsyncReadExt;
+
+const USECS_PER_DAY: u64 = 24 * 3600 * 1_000_000;
+
+#[derive(StructOpt)]
+pub struct MaintenanceCoordinatorOpt {
+    #[structopt(
+        long = "keep-epoch-ending-backups",
+        help = "Number of epoch ending backups to keep, those reaching the latest epochs. N.B. a \
+        restore from scratch needs epoch endings all the way from epoch 0."
+    )]
+    pub keep_epoch_ending_backups: usize,
+    #[structopt(
+        long = "keep-transaction-days",
+        help = "Keep transaction backups committed within this many days, judged by the \
+        LedgerInfo they are proven against. The latest transaction backup is always kept."
+    )]
+    pub keep_transaction_days: u64,
+    #[structopt(
+        long = "dry-run",
+        help = "Print what would be deleted, without deleting anything."
+    )]
+    pub dry_run: bool,
+}
+
+/// What a maintenance run deletes from the storage: the backups falling out of the retention
+/// policy, and files not referenced by any backup kept, e.g. those of interrupted backups.
+#[derive(Debug, Default)]
+pub struct MaintenancePlan {
+    /// Metadata of all backups kept.
+    pub retained: Vec<Metadata>,
+    /// Metadata files to delete, deleted first so that the backups are no longer discovered.
+    pub metadata_files_to_delete: Vec<FileHandle>,
+    /// Files not referenced by the manifest of any backup kept, including the manifests.
+    pub files_to_delete: Vec<FileHandle>,
+}
+
+#[derive(Clone, Copy)]
+enum BackupType {
+    EpochEnding,
+    StateSnapshot,
+    Transaction,
+}
+
+/// Enforces retention policies on a backup storage: keeps the latest epoch ending backups, the
+/// transaction backups of the last days, the state snapshots that can be restored with those and
+/// the bases of delta state snapshots kept, and deletes everything else.
+///
+/// Files of a backup being taken are not referenced by any metadata yet, so this must not run
+/// concurrently with backups.
+pub struct MaintenanceCoordinator {
+    storage: Arc<dyn BackupStorage>,
+    keep_epoch_ending_backups: usize,
+    keep_transaction_usecs: u64,
+    dry_run: bool,
+}
+
+impl MaintenanceCoordinator {
+    pub fn new(opt: MaintenanceCoordinatorOpt, storage: Arc<dyn BackupStorage>) -> Self {
+        Self {
+            storage,
+            keep_epoch_ending_backups: opt.keep_epoch_ending_backups,
+            keep_transaction_usecs: opt.keep_transaction_days.saturating_mul(USECS_PER_DAY),
+            dry_run: opt.dry_run,
+        }
+    }
+
+    pub async fn run(self) -> Result<MaintenancePlan> {
+        let now_usecs = SystemTime::now().duration_since(UNIX_EPOCH)?.as_micros() as u64;
+        let plan = self.plan(now_usecs).await?;
+        println!(
+            "Keeping {} backups, deleting {} metadata files and {} backup files.",
+            plan.retained.len(),
+            plan.metadata_files_to_delete.len(),
+            plan.files_to_delete.len(),
+        );
+
+        if self.dry_run {
+            for file_handle in plan
+                .metadata_files_to_delete
+                .iter()
+                .chain(&plan.files_to_delete)
+            {
+                println!("Would delete {}", file_handle);
+            }
+        } else {
+            self.apply(&plan).await?;
+        }
+        Ok(plan)
+    }
+
+    /// Decides what to keep and what to delete as of `now_usecs`. Fails without deciding anything
+    /// if a manifest of a backup to keep can't be read, so that no file it references is
+    /// mistaken as unreferenced.
+    pub async fn plan(&self, now_usecs: u64) -> Result<MaintenancePlan> {
+        ensure!(
+            self.keep_epoch_ending_backups > 0,
+            "At least one epoch ending backup must be kept.",
+        );
+        let metadata_files = load_metadata_files(&*self.storage).await?;
+        let view: MetadataView = metadata_files
+            .iter()
+            .flat_map(|(_, metadata)| metadata.iter().cloned())
+            .collect::<Vec<_>>()
+            .into();
+        let mut retained = HashMap::new();
+
+        let mut epoch_endings = view.epoch_ending_backups().to_vec();
+        epoch_endings.sort_by_key(|e| (e.last_epoch, Reverse(e.first_epoch)));
+        for backup in epoch_endings
+            .iter()
+            .rev()
+            .take(self.keep_epoch_ending_backups)
+        {
+            retained.insert(backup.manifest.clone(), BackupType::EpochEnding);
+        }
+
+        let latest_transactions = view
+            .transaction_backups()
+            .iter()
+            .max_by_key(|t| (t.last_version, Reverse(t.first_version)));
+        let mut oldest_retained_version = None;
+        for backup in view.transaction_backups() {
+            let timestamp_usecs = self.transaction_timestamp_usecs(&backup.manifest).await?;
+            if Some(backup) == latest_transactions
+                || now_usecs.saturating_sub(timestamp_usecs) <= self.keep_transaction_usecs
+            {
+                retained.insert(backup.manifest.clone(), BackupType::Transaction);
+                oldest_retained_version = oldest_retained_version
+                    .map(|v: u64| v.min(backup.first_version))
+                    .or(Some(backup.first_version));
+            }
+        }
+
+        // A state snapshot is kept if it's the latest, or the transactions after it are kept.
+        let latest_state_snapshot = view.state_snapshot_backups().last();
+        let mut to_follow = Vec::new();
+        for backup in view.state_snapshot_backups() {
+            if Some(backup) == latest_state_snapshot
+                || oldest_retained_version.map_or(false, |v| backup.version + 1 >= v)
+            {
+                retained.insert(backup.manifest.clone(), BackupType::StateSnapshot);
+                to_follow.push(backup.manifest.clone());
+            }
+        }
+
+        // Keep the bases of delta state snapshots kept, and keep metadata files whole, until
+        // neither adds anything.
+        loop {
+            while let Some(manifest_handle) = to_follow.pop() {
+                let manifest = read_manifest(&*self.storage, &manifest_handle).await?;
+                if let Some(base_manifest) = manifest.base_manifest {
+                    if !retained.contains_key(&base_manifest) {
+                        retained.insert(base_manifest.clone(), BackupType::StateSnapshot);
+                        to_follow.push(base_manifest);
+                    }
+                }
+            }
+            let num_retained = retained.len();
+            for (_, metadata) in &metadata_files {
+                if metadata
+                    .iter()
+                    .any(|m| retained.contains_key(manifest_of(m).0))
+                {
+                    for m in metadata {
+                        let (manifest_handle, backup_type) = manifest_of(m);
+                        if !retained.contains_key(manifest_handle) {
+                            retained.insert(manifest_handle.clone(), backup_type);
+                            if let BackupType::StateSnapshot = backup_type {
+                                to_follow.push(manifest_handle.clone());
+                            }
+                        }
+                    }
+                }
+            }
+            if to_follow.is_empty() && retained.len() == num_retained {
+                break;
+            }
+        }
+
+        let mut referenced = HashSet::new();
+        for (manifest_handle, backup_type) in &retained {
+            referenced.insert(manifest_handle.clone());
+            referenced.extend(self.files_in_backup(manifest_handle, *backup_type).await?);
+        }
+
+        let mut plan = MaintenancePlan::default();
+        for (file_handle, metadata) in metadata_files {
+            if metadata
+                .iter()
+                .any(|m| retained.contains_key(manifest_of(m).0))
+            {
+                plan.retained.extend(metadata);
+            } else {
+                plan.metadata_files_to_delete.push(file_handle);
+            }
+        }
+        plan.files_to_delete = self
+            .storage
+            .list_backup_files()
+            .await?
+            .into_iter()
+            .filter(|file_handle| !referenced.contains(file_handle))
+            .collect();
+        plan.metadata_files_to_delete.sort();
+        plan.files_to_delete.sort();
+
+        Ok(plan)
+    }
+
+    /// Deletes the metadata files in `plan` before the backup files, so that an interrupted run
+    /// never leaves metadata pointing to deleted files.
+    pub async fn apply(&self, plan: &MaintenancePlan) -> Result<()> {
+        for file_handle in plan
+            .metadata_files_to_delete
+            .iter()
+            .chain(&plan.files_to_delete)
+        {
+            println!("Deleting {}", file_handle);
+            self.storage.delete_file(file_handle).await?;
+        }
+        Ok(())
+    }
+}
+
+impl MaintenanceCoordinator {
+    /// Timestamp of the LedgerInfo the last chunk of a transaction backup is proven against.
+    async fn transaction_timestamp_usecs(&self, manifest_handle: &FileHandleRef) -> Result<u64> {
+        let manifest: TransactionBackup = self.load_json(manifest_handle).await?;
+        let last_chunk = manifest
+            .chunks
+            .last()
+            .ok_or_else(|| anyhow!("No chunks in transaction backup {}.", manifest_handle))?;
+        let (_, li): (TransactionAccumulatorRangeProof, LedgerInfoWithSignatures) =
+            lcs::from_bytes(&self.read_all(&last_chunk.proof).await?)?;
+        Ok(li.ledger_info().timestamp_usecs())
+    }
+
+    /// All files the manifest at `manifest_handle` references.
+    async fn files_in_backup(
+        &self,
+        manifest_handle: &FileHandleRef,
+        backup_type: BackupType,
+    ) -> Result<Vec<FileHandle>> {
+        Ok(match backup_type {
+            BackupType::EpochEnding => {
+                let manifest: EpochEndingBackup = self.load_json(manifest_handle).await?;
+                manifest
+                    .chunks
+                    .into_iter()
+                    .map(|chunk| chunk.ledger_infos)
+                    .collect()
+            }
+            BackupType::StateSnapshot => {
+                let manifest = read_manifest(&*self.storage, manifest_handle).await?;
+                let mut files = vec![manifest.proof];
+                for chunk in manifest.chunks {
+                    files.push(chunk.blobs);
+                    files.push(chunk.proof);
+                }
+                files
+            }
+            BackupType::Transaction => {
+                let manifest: TransactionBackup = self.load_json(manifest_handle).await?;
+                let mut files = Vec::new();
+                for chunk in manifest.chunks {
+                    files.push(chunk.transactions);
+                    files.push(chunk.proof);
+                }
+                files
+            }
+        })
+    }
+
+    async fn read_all(&self, file_handle: &FileHandleRef) -> Result<Vec<u8>> {
+        let mut bytes = Vec::new();
+        self.storage
+            .open_for_read(file_handle)
+            .await?
+            .read_to_end(&mut bytes)
+            .await?;
+        Ok(bytes)
+    }
+
+    async fn load_json<T: DeserializeOwned>(&self, file_handle: &FileHandleRef) -> Result<T> {
+        Ok(serde_json::from_slice(&self.read_all(file_handle).await?)?)
+    }
+}
+
+fn manifest_of(metadata: &Metadata) -> (&FileHandle, BackupType) {
+    match metadata {
+        Metadata::EpochEndingBackup(e) => (&e.manifest, BackupType::EpochEnding),
+        Metadata::StateSnapshotBackup(s) => (&s.manifest, BackupType::StateSnapshot),
+        Metadata::TransactionBackup(t) => (&t.manifest, BackupType::Transaction),
+    }
+}
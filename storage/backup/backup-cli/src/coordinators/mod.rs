@@ -0,0 +1,13 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+pub mod dry_run;
+pub mod fast_sync;
+pub mod list;
+pub mod maintenance;
+pub mod replay_verify;
+pub mod restore;
+pub mod verify;
+
+#[cfg(test)]
+mod tests;
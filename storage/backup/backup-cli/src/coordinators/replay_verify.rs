@@ -0,0 +1,279 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    backup_types::{
+        state_snapshot::{
+            manifest::StateSnapshotBackup,
+            restore::{StateSnapshotRestoreController, StateSnapshotRestoreOpt},
+        },
+        transaction::{manifest::TransactionBackup, restore::LoadedChunk},
+    },
+    coordinators::verify::{EpochHistory, VerifyCoordinator, VerifyCoordinatorOpt},
+    metadata::{load_all_metadata, view::MetadataView, StateSnapshotBackupMeta},
+    storage::{BackupStorage, FileHandleRef},
+    utils::{chunk_codec::ChunkCodec, run_ahead, GlobalRestoreOpt},
+};
+use anyhow::{ensure, Context, Result};
+use executor::{chunk_replayer::ChunkReplayer, Executor};
+use futures::TryStreamExt;
+use libra_types::{
+    ledger_info::LedgerInfoWithSignatures, proof::TransactionInfoWithProof, transaction::Version,
+    waypoint::Waypoint,
+};
+use libra_vm::LibraVM;
+use libradb::backup::restore_handler::RestoreHandler;
+use std::{sync::Arc, time::Instant};
+use storage_interface::DbReaderWriter;
+use structopt::StructOpt;
+use tokio::io::AsyncReadExt;
+
+#[derive(StructOpt)]
+pub struct ReplayVerifyCoordinatorOpt {
+    #[structopt(
+        long = "trust-waypoint",
+        help = "Trusted waypoint of an epoch ending LedgerInfo, where verification of the epoch \
+        ending backups starts. Can be repeated."
+    )]
+    pub trust_waypoints: Vec<Waypoint>,
+    #[structopt(
+        long = "start-version",
+        default_value = "0",
+        help = "Replay from the latest state snapshot no later than this version."
+    )]
+    pub start_version: Version,
+}
+
+/// Replays transaction backups through the executor, checking that re-executing every
+/// transaction produces the TransactionInfo in the backup, and every LedgerInfo the transactions
+/// are proven by against the verified epoch history. This checks the backups end to end, and
+/// that the executor is deterministic, without restoring a full DB: the executor needs the state
+/// to execute against, so the latest state snapshot no later than the start version is restored
+/// into the DB given, which only grows by the state the replayed transactions write, and can be
+/// thrown away afterwards. Since a chunk of transactions is only proven as a whole, the chunk
+/// holding the target version is replayed to its end.
+pub struct ReplayVerifyCoordinator {
+    global_opt: GlobalRestoreOpt,
+    storage: Arc<dyn BackupStorage>,
+    chunk_codec: Arc<ChunkCodec>,
+    restore_handler: Arc<RestoreHandler>,
+    db: DbReaderWriter,
+    trust_waypoints: Vec<Waypoint>,
+    start_version: Version,
+}
+
+impl ReplayVerifyCoordinator {
+    pub fn new(
+        opt: ReplayVerifyCoordinatorOpt,
+        global_opt: GlobalRestoreOpt,
+        storage: Arc<dyn BackupStorage>,
+        chunk_codec: Arc<ChunkCodec>,
+        restore_handler: Arc<RestoreHandler>,
+        db: DbReaderWriter,
+    ) -> Self {
+        Self {
+            global_opt,
+            storage,
+            chunk_codec,
+            restore_handler,
+            db,
+            trust_waypoints: opt.trust_waypoints,
+            start_version: opt.start_version,
+        }
+    }
+
+    /// Returns the number of transactions replayed.
+    pub async fn run(self) -> Result<usize> {
+        let epoch_history = VerifyCoordinator::new(
+            VerifyCoordinatorOpt {
+                trust_waypoints: self.trust_waypoints.clone(),
+            },
+            Arc::clone(&self.storage),
+            Arc::clone(&self.chunk_codec),
+        )
+        .verify_epoch_history()
+        .await
+        .context("Failed verifying epoch ending backups.")?;
+
+        let metadata_view: MetadataView = load_all_metadata(&*self.storage).await?.into();
+        let target_version = self.global_opt.target_version();
+        let state_snapshot = metadata_view.select_state_snapshot(self.start_version)?;
+        let transactions =
+            metadata_view.select_transaction_backups(state_snapshot.version, target_version)?;
+        if let Some(target_version) = self.global_opt.target_version {
+            let last_version = transactions.last().map_or(0, |t| t.last_version);
+            ensure!(
+                last_version >= target_version,
+                "Transaction backups stop at version {}, before the target version {}.",
+                last_version,
+                target_version,
+            );
+        }
+
+        let snapshot_version = state_snapshot.version;
+        println!(
+            "Restoring state snapshot at version {} to replay from.",
+            snapshot_version
+        );
+        self.verify_state_snapshot(&state_snapshot, &epoch_history)
+            .await?;
+        StateSnapshotRestoreController::new(
+            StateSnapshotRestoreOpt {
+                manifest_handle: state_snapshot.manifest,
+                version: snapshot_version,
+                checkpoint_file: None,
+                progress: false,
+            },
+            self.global_opt.clone(),
+            Arc::clone(&self.storage),
+            Arc::clone(&self.chunk_codec),
+            Arc::clone(&self.restore_handler),
+        )
+        .run()
+        .await
+        .context("Failed restoring state snapshot.")?;
+
+        // The transactions up to the snapshot version are only saved, so that the executor can
+        // pick up the transaction accumulator where the snapshot is.
+        let mut replayer = None;
+        let mut db_version = snapshot_version;
+        let mut num_replayed = 0;
+        for backup in transactions {
+            println!(
+                "Replaying transaction backup [{}, {}].",
+                backup.first_version, backup.last_version,
+            );
+            let manifest: TransactionBackup =
+                serde_json::from_slice(&self.read_all(&backup.manifest).await?)?;
+            manifest.verify()?;
+            let skip_before = db_version;
+            let chunks = run_ahead(
+                manifest
+                    .chunks
+                    .into_iter()
+                    .skip_while(|chunk| chunk.last_version < skip_before)
+                    .take_while(|chunk| chunk.first_version <= target_version),
+                self.global_opt.concurrent_downloads,
+                |chunk| {
+                    LoadedChunk::load(
+                        Arc::clone(&self.storage),
+                        Arc::clone(&self.chunk_codec),
+                        chunk,
+                    )
+                },
+            );
+            futures::pin_mut!(chunks);
+
+            while let Some(chunk) = chunks.try_next().await? {
+                epoch_history
+                    .verify_ledger_info(&chunk.ledger_info)
+                    .with_context(|| {
+                        format!(
+                            "Failed verifying LedgerInfo for transactions from version {}.",
+                            chunk.first_version
+                        )
+                    })?;
+
+                if replayer.is_none() {
+                    let (txn_list_with_proof, ledger_info) =
+                        chunk.clone().into_txn_list_with_proof(db_version);
+                    self.restore_handler.save_transactions(
+                        &txn_list_with_proof,
+                        &ledger_info,
+                        true,
+                    )?;
+                    replayer = Some(self.new_replayer(db_version)?);
+                }
+                // Backups can overlap, and so can the first chunk with the state snapshot. The
+                // transactions synced already are skipped by the executor.
+                let last_version = chunk.first_version + chunk.txns.len() as Version - 1;
+                if last_version <= db_version {
+                    continue;
+                }
+                let first_version = db_version + 1;
+                let (txn_list_with_proof, ledger_info) =
+                    chunk.into_txn_list_with_proof(last_version);
+
+                num_replayed += replayer
+                    .as_mut()
+                    .expect("The replayer is created along with the first chunk.")
+                    .replay(txn_list_with_proof, ledger_info, |version| {
+                        epoch_history.epoch_ending_ledger_info(version).cloned()
+                    })
+                    .with_context(|| {
+                        format!(
+                            "Failed replaying transactions from version {}.",
+                            first_version,
+                        )
+                    })?;
+                db_version = last_version;
+            }
+        }
+
+        Ok(num_replayed)
+    }
+}
+
+impl ReplayVerifyCoordinator {
+    /// Creates the replayer picking up after `db_version`, reporting the replay throughput.
+    fn new_replayer(&self, db_version: Version) -> Result<ChunkReplayer<Executor<LibraVM>>> {
+        let mut replayer = ChunkReplayer::new(Executor::<LibraVM>::new_for_replay(
+            self.db.clone(),
+            self.restore_handler.get_tree_state(db_version + 1)?,
+        ));
+        let start_time = Instant::now();
+        let mut num_replayed = 0;
+        replayer.add_progress_callback(move |progress| {
+            num_replayed += progress.num_txns();
+            let elapsed = start_time.elapsed().as_secs_f64();
+            let throughput = if elapsed > 0.0 {
+                num_replayed as f64 / elapsed
+            } else {
+                0.0
+            };
+            println!(
+                "Replayed transactions up to version {}, {:.0} transactions/s.",
+                progress.last_version, throughput,
+            );
+        });
+        Ok(replayer)
+    }
+
+    /// Checks that the state snapshot is proven by a LedgerInfo in the verified epoch history.
+    async fn verify_state_snapshot(
+        &self,
+        backup: &StateSnapshotBackupMeta,
+        epoch_history: &EpochHistory,
+    ) -> Result<()> {
+        let manifest: StateSnapshotBackup =
+            serde_json::from_slice(&self.read_all(&backup.manifest).await?)?;
+        ensure!(
+            manifest.version == backup.version,
+            "State snapshot version in manifest: {}, in metadata: {}",
+            manifest.version,
+            backup.version,
+        );
+
+        let (txn_info_with_proof, li): (TransactionInfoWithProof, LedgerInfoWithSignatures) =
+            lcs::from_bytes(&self.read_all(&manifest.proof).await?)?;
+        epoch_history.verify_ledger_info(&li)?;
+        txn_info_with_proof.verify(li.ledger_info(), manifest.version)?;
+        ensure!(
+            txn_info_with_proof.transaction_info().state_root_hash() == manifest.root_hash,
+            "Root hash mismatch with that in proof. root hash: {}, expected: {}",
+            manifest.root_hash,
+            txn_info_with_proof.transaction_info().state_root_hash(),
+        );
+        Ok(())
+    }
+
+    async fn read_all(&self, file_handle: &FileHandleRef) -> Result<Vec<u8>> {
+        let mut bytes = Vec::new();
+        self.storage
+            .open_for_read(file_handle)
+            .await?
+            .read_to_end(&mut bytes)
+            .await?;
+        Ok(bytes)
+    }
+}
@@ -0,0 +1,294 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    backup_types::{
+        epoch_ending::restore::{EpochEndingRestoreController, EpochEndingRestoreOpt},
+        state_snapshot::{
+            manifest::StateSnapshotBackup,
+            restore::{StateSnapshotRestoreController, StateSnapshotRestoreOpt},
+        },
+        transaction::restore::{TransactionRestoreController, TransactionRestoreOpt},
+    },
+    coordinators::verify::{EpochHistory, VerifyCoordinator, VerifyCoordinatorOpt},
+    metadata::{
+        load_all_metadata, view::MetadataView, EpochEndingBackupMeta, StateSnapshotBackupMeta,
+        TransactionBackupMeta,
+    },
+    storage::{BackupStorage, FileHandleRef},
+    utils::{chunk_codec::ChunkCodec, GlobalRestoreOpt},
+};
+use anyhow::{ensure, Context, Result};
+use libra_types::{
+    ledger_info::{LedgerInfo, LedgerInfoWithSignatures},
+    proof::TransactionInfoWithProof,
+    waypoint::Waypoint,
+};
+use libradb::backup::restore_handler::RestoreHandler;
+use std::sync::Arc;
+use structopt::StructOpt;
+use tokio::io::AsyncReadExt;
+
+#[derive(StructOpt)]
+pub struct RestoreCoordinatorOpt {
+    #[structopt(
+        long = "trust-waypoint",
+        help = "Trusted waypoint of an epoch ending LedgerInfo. Can be repeated. At least one of \
+        --trust-waypoint and --genesis-waypoint is required."
+    )]
+    pub trust_waypoints: Vec<Waypoint>,
+    #[structopt(
+        long = "genesis-waypoint",
+        help = "Waypoint of the genesis LedgerInfo, trusted along with those given by \
+        --trust-waypoint, so that the epoch ending backups are verified from genesis on."
+    )]
+    pub genesis_waypoint: Option<Waypoint>,
+}
+
+/// The backups a `RestoreCoordinator` restores from, in the order they are restored.
+pub struct RestoreSelection {
+    pub epoch_endings: Vec<EpochEndingBackupMeta>,
+    pub state_snapshot: StateSnapshotBackupMeta,
+    pub transactions: Vec<TransactionBackupMeta>,
+}
+
+impl RestoreSelection {
+    /// Selects from all backups in `storage`, as of the target version in `global_opt`.
+    pub async fn select(
+        storage: &dyn BackupStorage,
+        global_opt: &GlobalRestoreOpt,
+    ) -> Result<Self> {
+        let metadata_view: MetadataView = load_all_metadata(storage).await?.into();
+        let target_version = global_opt.target_version();
+        let state_snapshot = metadata_view.select_state_snapshot(target_version)?;
+        let transactions =
+            metadata_view.select_transaction_backups(state_snapshot.version, target_version)?;
+        if let Some(target_version) = global_opt.target_version {
+            let last_version = transactions.last().map_or(0, |t| t.last_version);
+            ensure!(
+                last_version >= target_version,
+                "Transaction backups stop at version {}, before the target version {}.",
+                last_version,
+                target_version,
+            );
+        }
+        let epoch_endings = metadata_view.select_epoch_ending_backups()?;
+
+        Ok(Self {
+            epoch_endings,
+            state_snapshot,
+            transactions,
+        })
+    }
+}
+
+/// Restores everything needed to bring up a DB from a backup storage in one go: all epoch ending
+/// LedgerInfos, the latest state snapshot, and the transactions since the snapshot, in that order
+/// so that what's restored later can be checked against what's restored earlier. With a target
+/// version, the DB is brought up as of it instead, from the latest state snapshot no later than
+/// it.
+///
+/// Nothing is restored without a trusted waypoint: the epoch ending LedgerInfos are verified from
+/// the earliest one on through the signatures of the validator set of each epoch, and the
+/// LedgerInfo proving the state snapshot is verified against the validator set of its epoch.
+pub struct RestoreCoordinator {
+    global_opt: GlobalRestoreOpt,
+    storage: Arc<dyn BackupStorage>,
+    chunk_codec: Arc<ChunkCodec>,
+    restore_handler: Arc<RestoreHandler>,
+    trust_waypoints: Vec<Waypoint>,
+    genesis_waypoint: Option<Waypoint>,
+}
+
+impl RestoreCoordinator {
+    pub fn new(
+        opt: RestoreCoordinatorOpt,
+        global_opt: GlobalRestoreOpt,
+        storage: Arc<dyn BackupStorage>,
+        chunk_codec: Arc<ChunkCodec>,
+        restore_handler: Arc<RestoreHandler>,
+    ) -> Self {
+        Self {
+            global_opt,
+            storage,
+            chunk_codec,
+            restore_handler,
+            trust_waypoints: opt.trust_waypoints,
+            genesis_waypoint: opt.genesis_waypoint,
+        }
+    }
+
+    pub async fn run(self) -> Result<()> {
+        let selection = RestoreSelection::select(&*self.storage, &self.global_opt).await?;
+        self.run_selection(selection).await
+    }
+
+    /// Restores the backups selected, which must have been selected as of the target version.
+    pub async fn run_selection(self, selection: RestoreSelection) -> Result<()> {
+        let RestoreSelection {
+            epoch_endings,
+            state_snapshot,
+            transactions,
+        } = selection;
+
+        let verified_epoch_history = self.verify_epoch_history().await?;
+        let trust_waypoints = verified_epoch_history.waypoints()?;
+
+        let mut epoch_history = Vec::new();
+        for backup in epoch_endings {
+            println!(
+                "Restoring epoch ending backup [{}, {}].",
+                backup.first_epoch, backup.last_epoch,
+            );
+            let lis = EpochEndingRestoreController::new(
+                EpochEndingRestoreOpt {
+                    manifest_handle: backup.manifest,
+                    trust_waypoints: trust_waypoints.clone(),
+                },
+                self.global_opt.clone(),
+                Arc::clone(&self.storage),
+                Arc::clone(&self.chunk_codec),
+                Arc::clone(&self.restore_handler),
+            )
+            .run()
+            .await
+            .context("Failed restoring epoch ending backup.")?;
+            // Backups can overlap.
+            let next_epoch = epoch_history.len() as u64;
+            epoch_history.extend(lis.into_iter().filter(|li| li.epoch() >= next_epoch));
+        }
+        self.verify_trust_waypoints_used(&epoch_history)?;
+
+        println!(
+            "Restoring state snapshot at version {}.",
+            state_snapshot.version
+        );
+        self.verify_state_snapshot(&state_snapshot, &verified_epoch_history)
+            .await?;
+        StateSnapshotRestoreController::new(
+            StateSnapshotRestoreOpt {
+                manifest_handle: state_snapshot.manifest,
+                version: state_snapshot.version,
+                checkpoint_file: None,
+                progress: false,
+            },
+            self.global_opt.clone(),
+            Arc::clone(&self.storage),
+            Arc::clone(&self.chunk_codec),
+            Arc::clone(&self.restore_handler),
+        )
+        .run()
+        .await
+        .context("Failed restoring state snapshot.")?;
+
+        for backup in transactions {
+            println!(
+                "Restoring transaction backup [{}, {}].",
+                backup.first_version, backup.last_version,
+            );
+            TransactionRestoreController::new(
+                TransactionRestoreOpt {
+                    manifest_handle: backup.manifest,
+                },
+                self.global_opt.clone(),
+                Arc::clone(&self.storage),
+                Arc::clone(&self.chunk_codec),
+                Arc::clone(&self.restore_handler),
+            )
+            .run()
+            .await
+            .context("Failed restoring transaction backup.")?;
+        }
+
+        Ok(())
+    }
+}
+
+impl RestoreCoordinator {
+    /// Verifies the epoch ending backups from the earliest trusted waypoint on, through the
+    /// signatures of the validator set of each epoch, so that the waypoints of all later epoch
+    /// endings can be trusted without obtaining them elsewhere.
+    async fn verify_epoch_history(&self) -> Result<EpochHistory> {
+        let trust_waypoints: Vec<Waypoint> = self
+            .genesis_waypoint
+            .iter()
+            .chain(&self.trust_waypoints)
+            .cloned()
+            .collect();
+        ensure!(
+            !trust_waypoints.is_empty(),
+            "Refusing to restore without a trusted waypoint, pass --trust-waypoint or \
+            --genesis-waypoint.",
+        );
+        if let Some(genesis_waypoint) = self.genesis_waypoint {
+            println!(
+                "Deriving trusted waypoints from genesis waypoint {}.",
+                genesis_waypoint
+            );
+        }
+        VerifyCoordinator::new(
+            VerifyCoordinatorOpt { trust_waypoints },
+            Arc::clone(&self.storage),
+            Arc::clone(&self.chunk_codec),
+        )
+        .verify_epoch_history()
+        .await
+        .context("Failed verifying epoch ending backups against the trusted waypoints.")
+    }
+
+    /// Every trusted waypoint must have been checked against an epoch ending LedgerInfo in the
+    /// backups, otherwise trusting it had no effect.
+    fn verify_trust_waypoints_used(&self, epoch_history: &[LedgerInfo]) -> Result<()> {
+        for wp in &self.trust_waypoints {
+            ensure!(
+                epoch_history.iter().any(|li| li.version() == wp.version()),
+                "Trusted waypoint {} is not covered by the epoch ending backups.",
+                wp,
+            );
+        }
+        Ok(())
+    }
+
+    /// Checks that the state snapshot is proven by a LedgerInfo that agrees with the verified
+    /// epoch ending LedgerInfo of its epoch if it ends one, and is signed by the validator set of
+    /// its epoch otherwise.
+    async fn verify_state_snapshot(
+        &self,
+        backup: &StateSnapshotBackupMeta,
+        epoch_history: &EpochHistory,
+    ) -> Result<()> {
+        let manifest: StateSnapshotBackup =
+            serde_json::from_slice(&self.read_all(&backup.manifest).await?)?;
+        ensure!(
+            manifest.version == backup.version,
+            "State snapshot version in manifest: {}, in metadata: {}",
+            manifest.version,
+            backup.version,
+        );
+
+        let (txn_info_with_proof, li): (TransactionInfoWithProof, LedgerInfoWithSignatures) =
+            lcs::from_bytes(&self.read_all(&manifest.proof).await?)?;
+        txn_info_with_proof.verify(li.ledger_info(), manifest.version)?;
+        epoch_history
+            .verify_ledger_info(&li)
+            .context("Failed verifying the LedgerInfo proving the state snapshot.")?;
+        ensure!(
+            txn_info_with_proof.transaction_info().state_root_hash() == manifest.root_hash,
+            "Root hash mismatch with that in proof. root hash: {}, expected: {}",
+            manifest.root_hash,
+            txn_info_with_proof.transaction_info().state_root_hash(),
+        );
+
+        Ok(())
+    }
+
+    async fn read_all(&self, file_handle: &FileHandleRef) -> Result<Vec<u8>> {
+        let mut bytes = Vec::new();
+        self.storage
+            .open_for_read(file_handle)
+            .await?
+            .read_to_end(&mut bytes)
+            .await?;
+        Ok(bytes)
+    }
+}
@@ -0,0 +1,879 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    backup_types::{
+        epoch_ending::backup::{EpochEndingBackupController, EpochEndingBackupOpt},
+        state_snapshot::backup::{StateSnapshotBackupController, StateSnapshotBackupOpt},
+        transaction::backup::{TransactionBackupController, TransactionBackupOpt},
+    },
+    coordinators::{
+        dry_run::RestoreDryRun,
+        fast_sync::FastSyncCoordinator,
+        list::{ListCoordinator, ListCoordinatorOpt},
+        maintenance::{MaintenanceCoordinator, MaintenanceCoordinatorOpt},
+        replay_verify::{ReplayVerifyCoordinator, ReplayVerifyCoordinatorOpt},
+        restore::{RestoreCoordinator, RestoreCoordinatorOpt},
+        verify::{VerifyCoordinator, VerifyCoordinatorOpt},
+    },
+    metadata::{cache::MetadataCacheOpt, Metadata},
+    storage::{local_fs::LocalFs, BackupStorage, ShellSafeName},
+    utils::{
+        backup_service_client::BackupServiceClient,
+        chunk_codec::{ChunkCodec, ChunkFormat, Compression, Encryption, Key},
+        test_utils::{tmp_db_empty, tmp_db_with_random_content, tmp_db_with_signed_content},
+        GlobalBackupOpt, GlobalRestoreOpt,
+    },
+};
+use backup_service::start_backup_service;
+use libra_config::utils::get_available_port;
+use libra_crypto::HashValue;
+use libra_temppath::TempPath;
+use libra_types::{
+    ledger_info::LedgerInfoWithSignatures,
+    transaction::{TransactionToCommit, Version},
+    waypoint::Waypoint,
+};
+use std::{str::FromStr, sync::Arc};
+use storage_interface::{DbReader, DbReaderWriter};
+use tokio::{io::AsyncWriteExt, runtime::Runtime, time::Duration};
+
+/// Backs up epoch endings, the latest state snapshot and all transactions in `blocks`, returning
+/// the version and root hash of the state snapshot.
+fn backup_all(
+    rt: &mut Runtime,
+    port: u16,
+    store: &Arc<dyn BackupStorage>,
+    chunk_codec: &Arc<ChunkCodec>,
+    blocks: &[(Vec<TransactionToCommit>, LedgerInfoWithSignatures)],
+) -> (Version, HashValue) {
+    let client = Arc::new(BackupServiceClient::new(port));
+    let global_opt = || GlobalBackupOpt {
+        max_chunk_size: 1024,
+    };
+
+    let latest_epoch = blocks.last().unwrap().1.ledger_info().next_block_epoch();
+    let total_txns = blocks.iter().fold(0, |x, b| x + b.0.len());
+    let (state_version, state_root_hash) = rt.block_on(client.get_latest_state_root()).unwrap();
+
+    rt.block_on(
+        EpochEndingBackupController::new(
+            EpochEndingBackupOpt {
+                start_epoch: 0,
+                end_epoch: latest_epoch,
+            },
+            global_opt(),
+            Arc::clone(&client),
+            Arc::clone(store),
+            Arc::clone(chunk_codec),
+        )
+        .run(),
+    )
+    .unwrap();
+    rt.block_on(
+        StateSnapshotBackupController::new(
+            StateSnapshotBackupOpt {
+                version: state_version,
+                base_manifest: None,
+            },
+            global_opt(),
+            Arc::clone(&client),
+            Arc::clone(store),
+            Arc::clone(chunk_codec),
+        )
+        .run(),
+    )
+    .unwrap();
+    rt.block_on(
+        TransactionBackupController::new(
+            TransactionBackupOpt {
+                start_version: 0,
+                num_transactions: total_txns,
+            },
+            global_opt(),
+            client,
+            Arc::clone(store),
+            Arc::clone(chunk_codec),
+        )
+        .run(),
+    )
+    .unwrap();
+
+    (state_version, state_root_hash)
+}
+
+#[test]
+fn end_to_end() {
+    let (_src_db_dir, src_db, blocks) = tmp_db_with_signed_content();
+    let backup_dir = TempPath::new();
+    backup_dir.create_as_dir().unwrap();
+    let store: Arc<dyn BackupStorage> = Arc::new(LocalFs::new(backup_dir.path().to_path_buf()));
+
+    let port = get_available_port();
+    let mut rt = start_backup_service(port, src_db);
+    let key: Key = rand::random();
+    let chunk_codec = Arc::new(
+        ChunkCodec::new(
+            ChunkFormat::new(Compression::Gzip, Encryption::Aes256Gcm),
+            Some(key),
+        )
+        .unwrap(),
+    );
+    let (_, state_root_hash) = backup_all(&mut rt, port, &store, &chunk_codec, &blocks);
+    let chunk_codec = Arc::new(ChunkCodec::new_for_restore(Some(key)));
+
+    let latest_li = blocks.last().unwrap().1.ledger_info().clone();
+    let latest_epoch = latest_li.next_block_epoch();
+    let latest_version = latest_li.version();
+    let total_txns = blocks.iter().fold(0, |x, b| x + b.0.len());
+
+    let first_epoch_ending_li = blocks
+        .iter()
+        .map(|(_, li)| li.ledger_info())
+        .find(|li| li.ends_epoch())
+        .unwrap();
+    let trusted_waypoint = Waypoint::new_epoch_boundary(first_epoch_ending_li).unwrap();
+
+    let restore = |rt: &mut Runtime, trust_waypoints: Vec<Waypoint>| {
+        let (tgt_db_dir, tgt_db) = tmp_db_empty();
+        let res = rt.block_on(
+            RestoreCoordinator::new(
+                RestoreCoordinatorOpt {
+                    trust_waypoints,
+                    genesis_waypoint: None,
+                },
+                GlobalRestoreOpt {
+                    db_dir: tgt_db_dir.path().to_path_buf(),
+                    target_version: None,
+                    concurrent_downloads: 4,
+                },
+                Arc::clone(&store),
+                Arc::clone(&chunk_codec),
+                Arc::new(tgt_db.get_restore_handler()),
+            )
+            .run(),
+        );
+        (tgt_db_dir, tgt_db, res)
+    };
+
+    // Nothing is restored without a trusted waypoint.
+    assert!(restore(&mut rt, Vec::new()).2.is_err());
+
+    // A trusted waypoint that doesn't match the backup fails the restore.
+    let bad_waypoint: Waypoint = format!(
+        "{}:{}",
+        first_epoch_ending_li.version(),
+        HashValue::zero().to_hex()
+    )
+    .parse()
+    .unwrap();
+    assert!(restore(&mut rt, vec![bad_waypoint]).2.is_err());
+
+    let (_tgt_db_dir, tgt_db, res) = restore(&mut rt, vec![trusted_waypoint]);
+    res.unwrap();
+
+    assert_eq!(
+        tgt_db
+            .get_latest_tree_state()
+            .unwrap()
+            .account_state_root_hash,
+        state_root_hash,
+    );
+    assert_eq!(
+        tgt_db
+            .get_epoch_ending_ledger_infos(0, latest_epoch)
+            .unwrap()
+            .0,
+        blocks
+            .iter()
+            .map(|(_, li)| li)
+            .filter(|li| li.ledger_info().ends_epoch())
+            .cloned()
+            .collect::<Vec<_>>(),
+    );
+    assert_eq!(
+        tgt_db
+            .get_transactions(0, total_txns as u64, latest_version, false)
+            .unwrap()
+            .transactions,
+        blocks
+            .iter()
+            .map(|(txns, _)| txns)
+            .flatten()
+            .map(|txn_to_commit| txn_to_commit.transaction().clone())
+            .collect::<Vec<_>>(),
+    );
+
+    rt.shutdown_timeout(Duration::from_secs(1));
+}
+
+#[test]
+fn auto_trust() {
+    let (_src_db_dir, src_db, blocks) = tmp_db_with_signed_content();
+    let backup_dir = TempPath::new();
+    backup_dir.create_as_dir().unwrap();
+    let store: Arc<dyn BackupStorage> = Arc::new(LocalFs::new(backup_dir.path().to_path_buf()));
+
+    let port = get_available_port();
+    let mut rt = start_backup_service(port, src_db);
+    let chunk_codec = Arc::new(ChunkCodec::default());
+    let (_, state_root_hash) = backup_all(&mut rt, port, &store, &chunk_codec, &blocks);
+
+    let latest_epoch = blocks.last().unwrap().1.ledger_info().next_block_epoch();
+    let genesis_li = blocks
+        .iter()
+        .map(|(_, li)| li.ledger_info())
+        .find(|li| li.ends_epoch())
+        .unwrap();
+    let genesis_waypoint = Waypoint::new_epoch_boundary(genesis_li).unwrap();
+    let restore = |rt: &mut Runtime, genesis_waypoint: Waypoint| {
+        let (tgt_db_dir, tgt_db) = tmp_db_empty();
+        let res = rt.block_on(
+            RestoreCoordinator::new(
+                RestoreCoordinatorOpt {
+                    trust_waypoints: Vec::new(),
+                    genesis_waypoint: Some(genesis_waypoint),
+                },
+                GlobalRestoreOpt {
+                    db_dir: tgt_db_dir.path().to_path_buf(),
+                    target_version: None,
+                    concurrent_downloads: 4,
+                },
+                Arc::clone(&store),
+                Arc::clone(&chunk_codec),
+                Arc::new(tgt_db.get_restore_handler()),
+            )
+            .run(),
+        );
+        (tgt_db_dir, tgt_db, res)
+    };
+
+    // A genesis waypoint that doesn't match the backup fails the restore.
+    let bad_waypoint: Waypoint = format!(
+        "{}:{}",
+        genesis_waypoint.version(),
+        HashValue::zero().to_hex()
+    )
+    .parse()
+    .unwrap();
+    assert!(restore(&mut rt, bad_waypoint).2.is_err());
+
+    let (_tgt_db_dir, tgt_db, res) = restore(&mut rt, genesis_waypoint);
+    res.unwrap();
+    assert_eq!(
+        tgt_db
+            .get_latest_tree_state()
+            .unwrap()
+            .account_state_root_hash,
+        state_root_hash,
+    );
+    assert_eq!(
+        tgt_db
+            .get_epoch_ending_ledger_infos(0, latest_epoch)
+            .unwrap()
+            .0,
+        blocks
+            .iter()
+            .map(|(_, li)| li)
+            .filter(|li| li.ledger_info().ends_epoch())
+            .cloned()
+            .collect::<Vec<_>>(),
+    );
+
+    rt.shutdown_timeout(Duration::from_secs(1));
+}
+
+#[test]
+fn verify() {
+    let (_src_db_dir, src_db, blocks) = tmp_db_with_signed_content();
+    let backup_dir = TempPath::new();
+    backup_dir.create_as_dir().unwrap();
+    let store: Arc<dyn BackupStorage> = Arc::new(LocalFs::new(backup_dir.path().to_path_buf()));
+
+    let port = get_available_port();
+    let mut rt = start_backup_service(port, src_db);
+    let chunk_codec = Arc::new(ChunkCodec::default());
+    backup_all(&mut rt, port, &store, &chunk_codec, &blocks);
+
+    let first_epoch_ending_li = blocks
+        .iter()
+        .map(|(_, li)| li.ledger_info())
+        .find(|li| li.ends_epoch())
+        .unwrap();
+    let trusted_waypoint = Waypoint::new_epoch_boundary(first_epoch_ending_li).unwrap();
+    let verify = |rt: &mut Runtime| {
+        rt.block_on(
+            VerifyCoordinator::new(
+                VerifyCoordinatorOpt {
+                    trust_waypoints: vec![trusted_waypoint],
+                },
+                Arc::clone(&store),
+                Arc::clone(&chunk_codec),
+            )
+            .run(),
+        )
+        .unwrap()
+    };
+
+    let report = verify(&mut rt);
+    assert!(report.is_ok(), "{:?}", report.failures);
+
+    // Corrupt the first transaction chunk.
+    let chunk_path = backup_dir.path().join("transaction_0-").join("0-.chunk");
+    let mut chunk = std::fs::read(&chunk_path).unwrap();
+    let last = chunk.len() - 1;
+    chunk[last] ^= 0xff;
+    std::fs::write(&chunk_path, chunk).unwrap();
+
+    let report = verify(&mut rt);
+    assert_eq!(report.failures.len(), 1);
+    assert!(report.failures[0].contains("0-.chunk"));
+
+    // Remove it.
+    std::fs::remove_file(&chunk_path).unwrap();
+
+    let report = verify(&mut rt);
+    assert_eq!(report.failures.len(), 1);
+    assert!(report.failures[0].contains("0-.chunk"));
+
+    rt.shutdown_timeout(Duration::from_secs(1));
+}
+
+#[test]
+fn maintenance() {
+    // At least two transactions, so that there can be an older transaction backup.
+    let (_src_db_dir, src_db, blocks) = loop {
+        let content = tmp_db_with_signed_content();
+        if content.2.iter().map(|(txns, _)| txns.len()).sum::<usize>() > 1 {
+            break content;
+        }
+    };
+    let backup_dir = TempPath::new();
+    backup_dir.create_as_dir().unwrap();
+    let store: Arc<dyn BackupStorage> = Arc::new(LocalFs::new(backup_dir.path().to_path_buf()));
+
+    let port = get_available_port();
+    let mut rt = start_backup_service(port, src_db);
+    let chunk_codec = Arc::new(ChunkCodec::default());
+    let (_, state_root_hash) = backup_all(&mut rt, port, &store, &chunk_codec, &blocks);
+
+    // A transaction backup older than the full one, and the leftover of an interrupted backup.
+    rt.block_on(
+        TransactionBackupController::new(
+            TransactionBackupOpt {
+                start_version: 1,
+                num_transactions: 1,
+            },
+            GlobalBackupOpt {
+                max_chunk_size: 1024,
+            },
+            Arc::new(BackupServiceClient::new(port)),
+            Arc::clone(&store),
+            Arc::clone(&chunk_codec),
+        )
+        .run(),
+    )
+    .unwrap();
+    let orphan_file = rt.block_on(async {
+        let backup_handle = store
+            .create_backup(&ShellSafeName::from_str("interrupted").unwrap())
+            .await
+            .unwrap();
+        let (file_handle, mut file) = store
+            .create_for_write(
+                &backup_handle,
+                &ShellSafeName::from_str("0-.chunk").unwrap(),
+            )
+            .await
+            .unwrap();
+        file.write_all(b"partial").await.unwrap();
+        file_handle
+    });
+
+    let coordinator = |keep_transaction_days, dry_run| {
+        MaintenanceCoordinator::new(
+            MaintenanceCoordinatorOpt {
+                keep_epoch_ending_backups: 1,
+                keep_transaction_days,
+                dry_run,
+            },
+            Arc::clone(&store),
+        )
+    };
+    let path_of = |backup: &str, file: &str| {
+        backup_dir
+            .path()
+            .join(backup)
+            .join(file)
+            .into_os_string()
+            .into_string()
+            .unwrap()
+    };
+
+    // Everything is recent enough, only the orphan goes.
+    let plan = rt.block_on(coordinator(u64::MAX, false).plan(0)).unwrap();
+    assert_eq!(plan.retained.len(), 4);
+    assert!(plan.metadata_files_to_delete.is_empty());
+    assert_eq!(plan.files_to_delete, vec![orphan_file.clone()]);
+
+    // Nothing is recent enough, the older transaction backup goes too.
+    let mut stale_files: Vec<_> = std::fs::read_dir(backup_dir.path().join("transaction_1-"))
+        .unwrap()
+        .map(|entry| {
+            entry
+                .unwrap()
+                .path()
+                .into_os_string()
+                .into_string()
+                .unwrap()
+        })
+        .collect();
+    stale_files.push(orphan_file.clone());
+    stale_files.sort();
+    let plan = rt.block_on(coordinator(0, false).plan(u64::MAX)).unwrap();
+    assert_eq!(plan.retained.len(), 3);
+    assert_eq!(
+        plan.metadata_files_to_delete,
+        vec![path_of("metadata", "transaction_1-1.meta")],
+    );
+    assert_eq!(plan.files_to_delete, stale_files);
+
+    // A dry run deletes nothing.
+    rt.block_on(coordinator(0, true).run()).unwrap();
+    assert!(backup_dir.path().join("transaction_1-").exists());
+
+    rt.block_on(coordinator(0, false).run()).unwrap();
+    assert!(!backup_dir.path().join("transaction_1-").exists());
+    assert!(!backup_dir.path().join("interrupted").exists());
+    assert!(rt
+        .block_on(coordinator(0, false).plan(u64::MAX))
+        .unwrap()
+        .files_to_delete
+        .is_empty());
+
+    // What's kept still restores.
+    let (tgt_db_dir, tgt_db) = tmp_db_empty();
+    let first_epoch_ending_li = blocks
+        .iter()
+        .map(|(_, li)| li.ledger_info())
+        .find(|li| li.ends_epoch())
+        .unwrap();
+    rt.block_on(
+        RestoreCoordinator::new(
+            RestoreCoordinatorOpt {
+                trust_waypoints: vec![Waypoint::new_epoch_boundary(first_epoch_ending_li).unwrap()],
+                genesis_waypoint: None,
+            },
+            GlobalRestoreOpt {
+                db_dir: tgt_db_dir.path().to_path_buf(),
+                target_version: None,
+                concurrent_downloads: 4,
+            },
+            Arc::clone(&store),
+            chunk_codec,
+            Arc::new(tgt_db.get_restore_handler()),
+        )
+        .run(),
+    )
+    .unwrap();
+    assert_eq!(
+        tgt_db
+            .get_latest_tree_state()
+            .unwrap()
+            .account_state_root_hash,
+        state_root_hash,
+    );
+
+    rt.shutdown_timeout(Duration::from_secs(1));
+}
+
+#[test]
+fn point_in_time() {
+    // At least three transactions, so that there's a target version before the latest one with a
+    // state snapshot before it.
+    let (_src_db_dir, src_db, blocks) = loop {
+        let content = tmp_db_with_signed_content();
+        if content.2.iter().map(|(txns, _)| txns.len()).sum::<usize>() > 2 {
+            break content;
+        }
+    };
+    let (tgt_db_dir, tgt_db) = tmp_db_empty();
+    let backup_dir = TempPath::new();
+    backup_dir.create_as_dir().unwrap();
+    let store: Arc<dyn BackupStorage> = Arc::new(LocalFs::new(backup_dir.path().to_path_buf()));
+
+    let port = get_available_port();
+    let mut rt = start_backup_service(port, src_db);
+    let chunk_codec = Arc::new(ChunkCodec::default());
+    backup_all(&mut rt, port, &store, &chunk_codec, &blocks);
+
+    let total_txns = blocks.iter().fold(0, |x, b| x + b.0.len());
+    let target_version = (total_txns - 2) as Version;
+    rt.block_on(
+        StateSnapshotBackupController::new(
+            StateSnapshotBackupOpt {
+                version: target_version / 2,
+                base_manifest: None,
+            },
+            GlobalBackupOpt {
+                max_chunk_size: 1024,
+            },
+            Arc::new(BackupServiceClient::new(port)),
+            Arc::clone(&store),
+            Arc::clone(&chunk_codec),
+        )
+        .run(),
+    )
+    .unwrap();
+
+    let first_epoch_ending_li = blocks
+        .iter()
+        .map(|(_, li)| li.ledger_info())
+        .find(|li| li.ends_epoch())
+        .unwrap();
+    rt.block_on(
+        RestoreCoordinator::new(
+            RestoreCoordinatorOpt {
+                trust_waypoints: vec![Waypoint::new_epoch_boundary(first_epoch_ending_li).unwrap()],
+                genesis_waypoint: None,
+            },
+            GlobalRestoreOpt {
+                db_dir: tgt_db_dir.path().to_path_buf(),
+                target_version: Some(target_version),
+                concurrent_downloads: 4,
+            },
+            store,
+            chunk_codec,
+            Arc::new(tgt_db.get_restore_handler()),
+        )
+        .run(),
+    )
+    .unwrap();
+
+    assert_eq!(
+        tgt_db.get_latest_tree_state().unwrap().num_transactions,
+        target_version + 1,
+    );
+    assert_eq!(
+        tgt_db
+            .get_transactions(0, target_version + 1, target_version, false)
+            .unwrap()
+            .transactions,
+        blocks
+            .iter()
+            .map(|(txns, _)| txns)
+            .flatten()
+            .take(target_version as usize + 1)
+            .map(|txn_to_commit| txn_to_commit.transaction().clone())
+            .collect::<Vec<_>>(),
+    );
+    // No epoch ending LedgerInfo after the target version is restored.
+    assert!(tgt_db
+        .get_latest_ledger_info()
+        .map_or(true, |li| li.ledger_info().version() <= target_version));
+
+    rt.shutdown_timeout(Duration::from_secs(1));
+}
+
+#[test]
+fn fast_sync() {
+    let (_src_db_dir, src_db, blocks) = tmp_db_with_signed_content();
+    let backup_dir = TempPath::new();
+    backup_dir.create_as_dir().unwrap();
+    let store: Arc<dyn BackupStorage> = Arc::new(LocalFs::new(backup_dir.path().to_path_buf()));
+
+    let port = get_available_port();
+    let mut rt = start_backup_service(port, src_db);
+    let chunk_codec = Arc::new(ChunkCodec::default());
+    let (state_version, state_root_hash) = backup_all(&mut rt, port, &store, &chunk_codec, &blocks);
+
+    let waypoint_li = blocks
+        .iter()
+        .map(|(_, li)| li.ledger_info())
+        .filter(|li| li.ends_epoch())
+        .last()
+        .unwrap();
+    let waypoint = Waypoint::new_epoch_boundary(waypoint_li).unwrap();
+    let fast_sync = |rt: &mut Runtime, waypoint: Waypoint| {
+        let (tgt_db_dir, tgt_db) = tmp_db_empty();
+        let res = rt.block_on(
+            FastSyncCoordinator::new(
+                waypoint,
+                GlobalRestoreOpt {
+                    db_dir: tgt_db_dir.path().to_path_buf(),
+                    target_version: None,
+                    concurrent_downloads: 4,
+                },
+                Arc::clone(&store),
+                Arc::clone(&chunk_codec),
+                Arc::new(tgt_db.get_restore_handler()),
+            )
+            .run(),
+        );
+        (tgt_db_dir, tgt_db, res)
+    };
+
+    rt.block_on(
+        StateSnapshotBackupController::new(
+            StateSnapshotBackupOpt {
+                version: waypoint.version(),
+                base_manifest: None,
+            },
+            GlobalBackupOpt {
+                max_chunk_size: 1024,
+            },
+            Arc::new(BackupServiceClient::new(port)),
+            Arc::clone(&store),
+            Arc::clone(&chunk_codec),
+        )
+        .run(),
+    )
+    .unwrap();
+
+    // A waypoint that doesn't match the backup fails the fast sync.
+    let bad_waypoint: Waypoint = format!("{}:{}", waypoint.version(), HashValue::zero().to_hex())
+        .parse()
+        .unwrap();
+    assert!(fast_sync(&mut rt, bad_waypoint).2.is_err());
+
+    // The latest state snapshot is restored, rather than the one at the waypoint.
+    let (_tgt_db_dir, tgt_db, res) = fast_sync(&mut rt, waypoint);
+    res.unwrap();
+    let tree_state = tgt_db.get_latest_tree_state().unwrap();
+    assert_eq!(tree_state.num_transactions, state_version + 1);
+    assert_eq!(tree_state.account_state_root_hash, state_root_hash);
+    waypoint
+        .verify(tgt_db.get_latest_ledger_info().unwrap().ledger_info())
+        .unwrap();
+
+    rt.shutdown_timeout(Duration::from_secs(1));
+}
+
+#[test]
+fn dry_run() {
+    let (_src_db_dir, src_db, blocks) = tmp_db_with_random_content();
+    let backup_dir = TempPath::new();
+    backup_dir.create_as_dir().unwrap();
+    let store: Arc<dyn BackupStorage> = Arc::new(LocalFs::new(backup_dir.path().to_path_buf()));
+
+    let port = get_available_port();
+    let mut rt = start_backup_service(port, src_db);
+    let chunk_codec = Arc::new(ChunkCodec::default());
+    backup_all(&mut rt, port, &store, &chunk_codec, &blocks);
+
+    let global_opt = GlobalRestoreOpt {
+        db_dir: backup_dir.path().join("db_not_opened"),
+        target_version: None,
+        concurrent_downloads: 4,
+    };
+    let dry_run = |rt: &mut Runtime| {
+        let mut dry_run = RestoreDryRun::new(global_opt.clone(), Arc::clone(&store));
+        rt.block_on(dry_run.add_all()).map(|_| dry_run.finish())
+    };
+    let report = dry_run(&mut rt).unwrap();
+
+    // One backup of each type, all of whose files are needed.
+    assert_eq!(report.manifests.len(), 3);
+    let mut expected_files = Vec::new();
+    for backup in std::fs::read_dir(backup_dir.path()).unwrap() {
+        let backup = backup.unwrap();
+        if backup.file_name() == "metadata" {
+            continue;
+        }
+        for file in std::fs::read_dir(backup.path()).unwrap() {
+            let file = file.unwrap();
+            expected_files.push((
+                file.path().into_os_string().into_string().unwrap(),
+                file.metadata().unwrap().len(),
+            ));
+        }
+    }
+    let mut reported_files: Vec<_> = report
+        .manifests
+        .iter()
+        .chain(&report.files)
+        .cloned()
+        .collect();
+    reported_files.sort();
+    expected_files.sort();
+    assert_eq!(reported_files, expected_files);
+    assert_eq!(
+        report.total_bytes(),
+        expected_files.iter().map(|(_, size)| size).sum::<u64>(),
+    );
+    assert!(!backup_dir.path().join("db_not_opened").exists());
+
+    // A malformed manifest fails the dry run.
+    let manifest_path = backup_dir
+        .path()
+        .join("transaction_0-")
+        .join("transaction.manifest");
+    let mut manifest: serde_json::Value =
+        serde_json::from_slice(&std::fs::read(&manifest_path).unwrap()).unwrap();
+    manifest["last_version"] = serde_json::json!(u64::max_value());
+    std::fs::write(&manifest_path, serde_json::to_vec(&manifest).unwrap()).unwrap();
+    assert!(dry_run(&mut rt).is_err());
+
+    rt.shutdown_timeout(Duration::from_secs(1));
+}
+
+#[test]
+fn list() {
+    let (_src_db_dir, src_db, blocks) = tmp_db_with_random_content();
+    let backup_dir = TempPath::new();
+    backup_dir.create_as_dir().unwrap();
+    let store: Arc<dyn BackupStorage> = Arc::new(LocalFs::new(backup_dir.path().to_path_buf()));
+    let cache_dir = TempPath::new();
+
+    let port = get_available_port();
+    let mut rt = start_backup_service(port, src_db);
+    let chunk_codec = Arc::new(ChunkCodec::default());
+    let (state_version, state_root_hash) = backup_all(&mut rt, port, &store, &chunk_codec, &blocks);
+
+    let list = |rt: &mut Runtime| {
+        rt.block_on(
+            ListCoordinator::new(
+                ListCoordinatorOpt {
+                    metadata_cache: MetadataCacheOpt {
+                        dir: Some(cache_dir.path().to_path_buf()),
+                    },
+                },
+                Arc::clone(&store),
+                Arc::clone(&chunk_codec),
+            )
+            .run(),
+        )
+        .unwrap()
+    };
+    let entries = list(&mut rt);
+
+    // Epoch endings first, then state snapshots, then transactions.
+    assert_eq!(entries.len(), 3);
+    assert!(matches!(
+        entries[0].metadata,
+        Metadata::EpochEndingBackup(_)
+    ));
+    match &entries[1].metadata {
+        Metadata::StateSnapshotBackup(s) => assert_eq!(s.version, state_version),
+        _ => panic!("Expecting a state snapshot backup."),
+    }
+    assert_eq!(entries[1].root_hash, Some(state_root_hash));
+    match &entries[2].metadata {
+        Metadata::TransactionBackup(t) => assert_eq!(
+            t.last_version + 1,
+            blocks.iter().fold(0, |x, b| x + b.0.len()) as Version,
+        ),
+        _ => panic!("Expecting a transaction backup."),
+    }
+    let mut backup_size = 0;
+    for backup in std::fs::read_dir(backup_dir.path()).unwrap() {
+        let backup = backup.unwrap();
+        if backup.file_name() == "metadata" {
+            continue;
+        }
+        for file in std::fs::read_dir(backup.path()).unwrap() {
+            backup_size += file.unwrap().metadata().unwrap().len();
+        }
+    }
+    assert_eq!(entries.iter().map(|e| e.size).sum::<u64>(), backup_size);
+
+    // Once cached, backups are listed without reading them again.
+    for backup in std::fs::read_dir(backup_dir.path()).unwrap() {
+        let backup = backup.unwrap();
+        if backup.file_name() != "metadata" {
+            std::fs::remove_dir_all(backup.path()).unwrap();
+        }
+    }
+    assert_eq!(list(&mut rt), entries);
+
+    // Backups whose metadata is gone from the storage are gone from the list.
+    for file in std::fs::read_dir(backup_dir.path().join("metadata")).unwrap() {
+        let file = file.unwrap();
+        if file
+            .file_name()
+            .to_str()
+            .unwrap()
+            .starts_with("transaction_")
+        {
+            std::fs::remove_file(file.path()).unwrap();
+        }
+    }
+    assert_eq!(list(&mut rt), entries[..2].to_vec());
+
+    rt.shutdown_timeout(Duration::from_secs(1));
+}
+
+#[test]
+fn replay_verify() {
+    let (_src_db_dir, src_db, blocks) = tmp_db_with_signed_content();
+    let backup_dir = TempPath::new();
+    backup_dir.create_as_dir().unwrap();
+    let store: Arc<dyn BackupStorage> = Arc::new(LocalFs::new(backup_dir.path().to_path_buf()));
+
+    let port = get_available_port();
+    let mut rt = start_backup_service(port, src_db);
+    let chunk_codec = Arc::new(ChunkCodec::default());
+    let (state_version, _) = backup_all(&mut rt, port, &store, &chunk_codec, &blocks);
+
+    let first_epoch_ending_li = blocks
+        .iter()
+        .map(|(_, li)| li.ledger_info())
+        .find(|li| li.ends_epoch())
+        .unwrap();
+    let trusted_waypoint = Waypoint::new_epoch_boundary(first_epoch_ending_li).unwrap();
+    let replay_verify = |rt: &mut Runtime, start_version: Version| {
+        let (tgt_db_dir, tgt_db) = tmp_db_empty();
+        rt.block_on(
+            ReplayVerifyCoordinator::new(
+                ReplayVerifyCoordinatorOpt {
+                    trust_waypoints: vec![trusted_waypoint],
+                    start_version,
+                },
+                GlobalRestoreOpt {
+                    db_dir: tgt_db_dir.path().to_path_buf(),
+                    target_version: None,
+                    concurrent_downloads: 4,
+                },
+                Arc::clone(&store),
+                Arc::clone(&chunk_codec),
+                Arc::new(tgt_db.get_restore_handler()),
+                DbReaderWriter {
+                    reader: tgt_db.clone(),
+                    writer: tgt_db,
+                },
+            )
+            .run(),
+        )
+    };
+
+    // Nothing to replay after the latest state snapshot.
+    assert_eq!(replay_verify(&mut rt, state_version).unwrap(), 0);
+
+    // Random transactions don't produce their TransactionInfos when replayed.
+    rt.block_on(
+        StateSnapshotBackupController::new(
+            StateSnapshotBackupOpt {
+                version: 0,
+                base_manifest: None,
+            },
+            GlobalBackupOpt {
+                max_chunk_size: 1024,
+            },
+            Arc::new(BackupServiceClient::new(port)),
+            Arc::clone(&store),
+            Arc::clone(&chunk_codec),
+        )
+        .run(),
+    )
+    .unwrap();
+    if state_version > 0 {
+        let err = replay_verify(&mut rt, 0).unwrap_err();
+        assert!(
+            format!("{:#}", err).contains("Failed replaying transactions from version 1"),
+            "{:#}",
+            err
+        );
+    }
+
+    rt.shutdown_timeout(Duration::from_secs(1));
+}
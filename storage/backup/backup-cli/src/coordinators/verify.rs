@@ -0,0 +1,501 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    backup_types::{
+        epoch_ending::manifest::EpochEndingBackup,
+        state_snapshot::{
+            delta::{layer, read_chunk, DeltaBase},
+            manifest::StateSnapshotBackup,
+        },
+        transaction::manifest::TransactionBackup,
+    },
+    metadata::{
+        load_all_metadata, view::MetadataView, EpochEndingBackupMeta, StateSnapshotBackupMeta,
+        TransactionBackupMeta,
+    },
+    storage::{BackupStorage, FileHandleRef},
+    utils::chunk_codec::{ChunkCodec, ChunkFormat, ChunkId},
+};
+use anyhow::{anyhow, bail, ensure, Result};
+use jellyfish_merkle::{
+    node_type::{LeafNode, Node, NodeKey},
+    restore::JellyfishMerkleRestore,
+    NodeBatch, TreeReader, TreeWriter,
+};
+use libra_types::{
+    epoch_change::Verifier,
+    ledger_info::LedgerInfoWithSignatures,
+    proof::{
+        SparseMerkleRangeProof, TransactionAccumulatorRangeProof, TransactionInfoWithProof,
+        TransactionListProof,
+    },
+    transaction::{Transaction, TransactionInfo, TransactionListWithProof, Version},
+    waypoint::Waypoint,
+};
+use serde::de::DeserializeOwned;
+use std::{collections::BTreeMap, fmt::Display, sync::Arc};
+use structopt::StructOpt;
+use tokio::io::AsyncReadExt;
+
+#[derive(StructOpt)]
+pub struct VerifyCoordinatorOpt {
+    #[structopt(
+        long = "trust-waypoint",
+        help = "Trusted waypoint of an epoch ending LedgerInfo, where verification of the epoch \
+        ending backups starts. Can be repeated."
+    )]
+    pub trust_waypoints: Vec<Waypoint>,
+}
+
+/// Verifies all backups in a storage without restoring them: every manifest, chunk and proof is
+/// read and checked, and every LedgerInfo is checked against the trusted waypoints, directly or
+/// through the signatures of the validator set of its epoch.
+pub struct VerifyCoordinator {
+    storage: Arc<dyn BackupStorage>,
+    chunk_codec: Arc<ChunkCodec>,
+    trust_waypoints: Vec<Waypoint>,
+}
+
+/// Problems found by the `VerifyCoordinator`, one per corrupted or missing piece of a backup.
+#[derive(Default)]
+pub struct VerificationReport {
+    pub failures: Vec<String>,
+}
+
+impl VerificationReport {
+    fn fail(&mut self, what: impl Display, err: anyhow::Error) {
+        let failure = format!("{}: {:#}", what, err);
+        println!("FAILED {}", failure);
+        self.failures.push(failure);
+    }
+
+    pub fn is_ok(&self) -> bool {
+        self.failures.is_empty()
+    }
+}
+
+impl VerifyCoordinator {
+    pub fn new(
+        opt: VerifyCoordinatorOpt,
+        storage: Arc<dyn BackupStorage>,
+        chunk_codec: Arc<ChunkCodec>,
+    ) -> Self {
+        Self {
+            storage,
+            chunk_codec,
+            trust_waypoints: opt.trust_waypoints,
+        }
+    }
+
+    pub async fn run(self) -> Result<VerificationReport> {
+        ensure!(
+            !self.trust_waypoints.is_empty(),
+            "At least one trusted waypoint is required."
+        );
+        let metadata_view: MetadataView = load_all_metadata(&*self.storage).await?.into();
+        let mut report = VerificationReport::default();
+
+        let epoch_history = self
+            .verify_epoch_ending_backups(metadata_view.epoch_ending_backups(), &mut report)
+            .await;
+        for backup in metadata_view.state_snapshot_backups() {
+            if let Err(err) = self
+                .verify_state_snapshot_backup(backup, &epoch_history)
+                .await
+            {
+                report.fail(
+                    format!("State snapshot backup at version {}", backup.version),
+                    err,
+                );
+            }
+        }
+        for backup in metadata_view.transaction_backups() {
+            self.verify_transaction_backup(backup, &epoch_history, &mut report)
+                .await;
+        }
+
+        Ok(report)
+    }
+
+    /// Verifies only the epoch ending backups, failing on the first problem found.
+    pub async fn verify_epoch_history(&self) -> Result<EpochHistory> {
+        ensure!(
+            !self.trust_waypoints.is_empty(),
+            "At least one trusted waypoint is required."
+        );
+        let metadata_view: MetadataView = load_all_metadata(&*self.storage).await?.into();
+        let mut report = VerificationReport::default();
+        let epoch_history = self
+            .verify_epoch_ending_backups(metadata_view.epoch_ending_backups(), &mut report)
+            .await;
+        if let Some(failure) = report.failures.into_iter().next() {
+            bail!(failure);
+        }
+        Ok(epoch_history)
+    }
+}
+
+impl VerifyCoordinator {
+    async fn verify_epoch_ending_backups(
+        &self,
+        backups: &[EpochEndingBackupMeta],
+        report: &mut VerificationReport,
+    ) -> EpochHistory {
+        let mut lis = BTreeMap::new();
+        for backup in backups {
+            let what = format!(
+                "Epoch ending backup [{}, {}]",
+                backup.first_epoch, backup.last_epoch
+            );
+            let manifest: EpochEndingBackup = match self.load_json(&backup.manifest).await {
+                Ok(manifest) => manifest,
+                Err(err) => {
+                    report.fail(what, err);
+                    continue;
+                }
+            };
+            if let Err(err) = manifest.verify() {
+                report.fail(what, err);
+                continue;
+            }
+
+            for chunk in &manifest.chunks {
+                let res = async {
+                    let chunk_lis: Vec<LedgerInfoWithSignatures> = self
+                        .load_records(&chunk.ledger_infos, chunk.format, &chunk.id())
+                        .await?;
+                    ensure!(
+                        chunk.first_epoch + chunk_lis.len() as u64 == chunk.last_epoch + 1,
+                        "Number of items in chunk doesn't match that in manifest: {}",
+                        chunk_lis.len(),
+                    );
+                    for (li, wp_manifest) in chunk_lis.iter().zip(
+                        &manifest.waypoints[(chunk.first_epoch - manifest.first_epoch) as usize..],
+                    ) {
+                        let wp_li = Waypoint::new_epoch_boundary(li.ledger_info())?;
+                        ensure!(
+                            *wp_manifest == wp_li,
+                            "Waypoints don't match. In manifest: {}, In chunk: {}",
+                            wp_manifest,
+                            wp_li,
+                        );
+                    }
+                    Ok(chunk_lis)
+                }
+                .await;
+                match res {
+                    Ok(chunk_lis) => {
+                        for li in chunk_lis {
+                            lis.insert(li.ledger_info().epoch(), li);
+                        }
+                    }
+                    Err(err) => report.fail(
+                        format!(
+                            "{}, chunk [{}, {}] at {}",
+                            what, chunk.first_epoch, chunk.last_epoch, chunk.ledger_infos,
+                        ),
+                        err,
+                    ),
+                }
+            }
+        }
+
+        match EpochHistory::verify(lis, &self.trust_waypoints) {
+            Ok(epoch_history) => epoch_history,
+            Err(err) => {
+                report.fail("Epoch ending LedgerInfos", err);
+                EpochHistory::default()
+            }
+        }
+    }
+
+    async fn verify_state_snapshot_backup(
+        &self,
+        backup: &StateSnapshotBackupMeta,
+        epoch_history: &EpochHistory,
+    ) -> Result<()> {
+        let manifest: StateSnapshotBackup = self.load_json(&backup.manifest).await?;
+        ensure!(
+            manifest.version == backup.version,
+            "Version in manifest: {}, in metadata: {}",
+            manifest.version,
+            backup.version,
+        );
+        let (txn_info_with_proof, li): (TransactionInfoWithProof, LedgerInfoWithSignatures) =
+            self.load_lcs(&manifest.proof).await?;
+        epoch_history.verify_ledger_info(&li)?;
+        txn_info_with_proof.verify(li.ledger_info(), manifest.version)?;
+        ensure!(
+            txn_info_with_proof.transaction_info().state_root_hash() == manifest.root_hash,
+            "Root hash mismatch with that in proof. root hash: {}, expected: {}",
+            manifest.root_hash,
+            txn_info_with_proof.transaction_info().state_root_hash(),
+        );
+
+        // Each chunk is proven relative to the ones before it, so stop at the first bad one.
+        let mut base = match &manifest.base_manifest {
+            Some(base_manifest) => Some(
+                DeltaBase::open(
+                    Arc::clone(&self.storage),
+                    Arc::clone(&self.chunk_codec),
+                    base_manifest,
+                )
+                .await?,
+            ),
+            None => None,
+        };
+        let mut receiver =
+            JellyfishMerkleRestore::new(&NullTreeStore, manifest.version, manifest.root_hash)?;
+        let version = manifest.version;
+        let num_chunks = manifest.chunks.len();
+        for (chunk_idx, chunk) in manifest.chunks.into_iter().enumerate() {
+            let res = async {
+                let accounts = read_chunk(
+                    &*self.storage,
+                    &self.chunk_codec,
+                    version,
+                    &chunk,
+                    base.is_some(),
+                )
+                .await?;
+                let blobs =
+                    layer(base.as_mut(), &chunk, chunk_idx + 1 == num_chunks, accounts).await?;
+                let proof: SparseMerkleRangeProof = self.load_lcs(&chunk.proof).await?;
+                receiver.add_chunk(blobs, proof)
+            }
+            .await;
+            if let Err(err) = res {
+                bail!(
+                    "Chunk [{}, {}] at {}: {:#}",
+                    chunk.first_idx,
+                    chunk.last_idx,
+                    chunk.blobs,
+                    err,
+                );
+            }
+        }
+        receiver.finish()
+    }
+
+    async fn verify_transaction_backup(
+        &self,
+        backup: &TransactionBackupMeta,
+        epoch_history: &EpochHistory,
+        report: &mut VerificationReport,
+    ) {
+        let what = format!(
+            "Transaction backup [{}, {}]",
+            backup.first_version, backup.last_version
+        );
+        let manifest: TransactionBackup = match self.load_json(&backup.manifest).await {
+            Ok(manifest) => manifest,
+            Err(err) => return report.fail(what, err),
+        };
+        if let Err(err) = manifest.verify() {
+            return report.fail(what, err);
+        }
+
+        for chunk in manifest.chunks {
+            let res = async {
+                let (txns, txn_infos): (Vec<Transaction>, Vec<TransactionInfo>) = self
+                    .load_records::<(Transaction, TransactionInfo)>(
+                        &chunk.transactions,
+                        chunk.format,
+                        &chunk.id(),
+                    )
+                    .await?
+                    .into_iter()
+                    .unzip();
+                let (proof, li): (TransactionAccumulatorRangeProof, LedgerInfoWithSignatures) =
+                    self.load_lcs(&chunk.proof).await?;
+                ensure!(
+                    chunk.first_version + (txns.len() as Version) == chunk.last_version + 1,
+                    "Number of items in chunk doesn't match that in manifest: {}",
+                    txns.len(),
+                );
+                epoch_history.verify_ledger_info(&li)?;
+                TransactionListWithProof::new(
+                    txns,
+                    None,
+                    Some(chunk.first_version),
+                    TransactionListProof::new(proof, txn_infos),
+                )
+                .verify(li.ledger_info(), Some(chunk.first_version))
+            }
+            .await;
+            if let Err(err) = res {
+                report.fail(
+                    format!(
+                        "{}, chunk [{}, {}] at {}",
+                        what, chunk.first_version, chunk.last_version, chunk.transactions,
+                    ),
+                    err,
+                );
+            }
+        }
+    }
+
+    async fn read_all(&self, file_handle: &FileHandleRef) -> Result<Vec<u8>> {
+        let mut bytes = Vec::new();
+        self.storage
+            .open_for_read(file_handle)
+            .await?
+            .read_to_end(&mut bytes)
+            .await?;
+        Ok(bytes)
+    }
+
+    async fn load_json<T: DeserializeOwned>(&self, file_handle: &FileHandleRef) -> Result<T> {
+        Ok(serde_json::from_slice(&self.read_all(file_handle).await?)?)
+    }
+
+    async fn load_lcs<T: DeserializeOwned>(&self, file_handle: &FileHandleRef) -> Result<T> {
+        Ok(lcs::from_bytes(&self.read_all(file_handle).await?)?)
+    }
+
+    async fn load_records<T: DeserializeOwned>(
+        &self,
+        file_handle: &FileHandleRef,
+        format: ChunkFormat,
+        chunk_id: &ChunkId,
+    ) -> Result<Vec<T>> {
+        self.chunk_codec
+            .read_records(&*self.storage, file_handle, format, chunk_id)
+            .await
+    }
+}
+
+/// Epoch ending LedgerInfos verified against the trusted waypoints: the one at the earliest
+/// trusted waypoint, and those following it that are signed by the validator set of the previous
+/// epoch.
+#[derive(Default)]
+pub struct EpochHistory {
+    epoch_endings: BTreeMap<u64, LedgerInfoWithSignatures>,
+}
+
+impl EpochHistory {
+    fn verify(
+        lis: BTreeMap<u64, LedgerInfoWithSignatures>,
+        trust_waypoints: &[Waypoint],
+    ) -> Result<Self> {
+        let first_trusted = trust_waypoints
+            .iter()
+            .min_by_key(|wp| wp.version())
+            .ok_or_else(|| anyhow!("No trusted waypoints."))?;
+        let mut iter = lis
+            .into_iter()
+            .skip_while(|(_, li)| li.ledger_info().version() < first_trusted.version());
+        let (epoch, li) = iter.next().ok_or_else(|| {
+            anyhow!(
+                "No epoch ending LedgerInfo found at trusted waypoint {}.",
+                first_trusted,
+            )
+        })?;
+        first_trusted.verify(li.ledger_info())?;
+
+        let mut epoch_endings = BTreeMap::new();
+        epoch_endings.insert(epoch, li);
+        let mut next_epoch = epoch + 1;
+        for (epoch, li) in iter {
+            ensure!(
+                epoch == next_epoch,
+                "Epoch ending LedgerInfo missing for epoch {}.",
+                next_epoch,
+            );
+            Self::verify_with_previous(&epoch_endings[&(epoch - 1)], &li)?;
+            epoch_endings.insert(epoch, li);
+            next_epoch += 1;
+        }
+
+        for wp in trust_waypoints {
+            let li = epoch_endings
+                .values()
+                .find(|li| li.ledger_info().version() == wp.version())
+                .ok_or_else(|| {
+                    anyhow!(
+                        "No verified epoch ending LedgerInfo at trusted waypoint {}.",
+                        wp
+                    )
+                })?;
+            wp.verify(li.ledger_info())?;
+        }
+
+        Ok(Self { epoch_endings })
+    }
+
+    fn verify_with_previous(
+        previous_epoch_ending: &LedgerInfoWithSignatures,
+        li: &LedgerInfoWithSignatures,
+    ) -> Result<()> {
+        previous_epoch_ending
+            .ledger_info()
+            .next_epoch_state()
+            .ok_or_else(|| anyhow!("Epoch ending LedgerInfo carries no next epoch state."))?
+            .verify(li)
+    }
+
+    /// Verifies `li` against the epoch ending LedgerInfo of its epoch if it ends an epoch, or by
+    /// its signatures otherwise.
+    pub fn verify_ledger_info(&self, li: &LedgerInfoWithSignatures) -> Result<()> {
+        let epoch = li.ledger_info().epoch();
+        if let Some(epoch_ending) = self.epoch_endings.get(&epoch) {
+            if li.ledger_info().ends_epoch() {
+                let wp_li = Waypoint::new_epoch_boundary(li.ledger_info())?;
+                let wp_epoch_ending = Waypoint::new_epoch_boundary(epoch_ending.ledger_info())?;
+                ensure!(
+                    wp_li == wp_epoch_ending,
+                    "Waypoints don't match. LedgerInfo: {}, epoch ending: {}",
+                    wp_li,
+                    wp_epoch_ending,
+                );
+                return Ok(());
+            }
+        }
+        let previous_epoch_ending = epoch
+            .checked_sub(1)
+            .and_then(|previous_epoch| self.epoch_endings.get(&previous_epoch))
+            .ok_or_else(|| {
+                anyhow!(
+                    "No verified validator set for epoch {} to verify LedgerInfo at version {}.",
+                    epoch,
+                    li.ledger_info().version(),
+                )
+            })?;
+        Self::verify_with_previous(previous_epoch_ending, li)
+    }
+
+    /// The verified LedgerInfo ending the epoch the transaction at `version` is in, if any.
+    pub fn epoch_ending_ledger_info(&self, version: Version) -> Option<&LedgerInfoWithSignatures> {
+        self.epoch_endings
+            .values()
+            .find(|li| li.ledger_info().version() >= version)
+    }
+
+    /// Waypoints of all the verified epoch ending LedgerInfos, in epoch order.
+    pub fn waypoints(&self) -> Result<Vec<Waypoint>> {
+        self.epoch_endings
+            .values()
+            .map(|li| Waypoint::new_epoch_boundary(li.ledger_info()))
+            .collect()
+    }
+}
+
+/// Discards what `JellyfishMerkleRestore` writes, so that it only verifies the chunks.
+struct NullTreeStore;
+
+impl TreeReader for NullTreeStore {
+    fn get_node_option(&self, _node_key: &NodeKey) -> Result<Option<Node>> {
+        Ok(None)
+    }
+
+    fn get_rightmost_leaf(&self) -> Result<Option<(NodeKey, LeafNode)>> {
+        Ok(None)
+    }
+}
+
+impl TreeWriter for NullTreeStore {
+    fn write_node_batch(&self, _node_batch: &NodeBatch) -> Result<()> {
+        Ok(())
+    }
+}
@@ -0,0 +1,166 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    metadata::{load_all_metadata, parse_metadata_file, Metadata},
+    storage::{BackupStorage, FileHandle, FileHandleRef},
+};
+use anyhow::Result;
+use libra_crypto::HashValue;
+use serde::{de::DeserializeOwned, Serialize};
+use std::{
+    collections::HashSet,
+    path::{Path, PathBuf},
+};
+use structopt::StructOpt;
+use tokio::io::AsyncReadExt;
+
+#[derive(StructOpt)]
+pub struct MetadataCacheOpt {
+    #[structopt(
+        long = "metadata-cache-dir",
+        parse(from_os_str),
+        help = "Local directory to cache metadata files, and what's learned from the manifests, \
+        in. Nothing is cached if not specified."
+    )]
+    pub dir: Option<PathBuf>,
+}
+
+/// Local cache of what's read from a backup storage, so that it's not read again the next time.
+/// Metadata files and backups never change once written, so an entry cached under a file handle
+/// stays valid until the file is deleted from the storage.
+pub struct MetadataCache {
+    dir: Option<PathBuf>,
+}
+
+impl MetadataCache {
+    const METADATA_DIR: &'static str = "metadata";
+    const DETAILS_DIR: &'static str = "details";
+
+    pub fn new(opt: MetadataCacheOpt) -> Self {
+        Self { dir: opt.dir }
+    }
+
+    /// Reads all metadata saved to `storage`, only downloading the metadata files not cached yet,
+    /// and evicting those no longer in the storage.
+    pub async fn sync_and_load(&self, storage: &dyn BackupStorage) -> Result<Vec<Metadata>> {
+        let dir = match &self.dir {
+            Some(dir) => dir.join(Self::METADATA_DIR),
+            None => return load_all_metadata(storage).await,
+        };
+        tokio::fs::create_dir_all(&dir).await?;
+
+        let mut cached = HashSet::new();
+        let mut res = Vec::new();
+        for file_handle in storage.list_metadata_files().await? {
+            let name = Self::cache_file_name(&file_handle);
+            let path = dir.join(&name);
+            let content = match read_if_exists(&path).await? {
+                Some(content) => content,
+                None => {
+                    let mut content = Vec::new();
+                    storage
+                        .open_for_read(&file_handle)
+                        .await?
+                        .read_to_end(&mut content)
+                        .await?;
+                    write_atomically(&path, &content).await?;
+                    content
+                }
+            };
+            res.extend(parse_metadata_file(std::str::from_utf8(&content)?)?);
+            cached.insert(name);
+        }
+        evict_except(&dir, &cached).await?;
+
+        Ok(res)
+    }
+
+    /// Returns what's been cached by `save_details` for `file_handle`, if anything.
+    pub async fn load_details<T: DeserializeOwned>(
+        &self,
+        file_handle: &FileHandleRef,
+    ) -> Result<Option<T>> {
+        let dir = match &self.dir {
+            Some(dir) => dir.join(Self::DETAILS_DIR),
+            None => return Ok(None),
+        };
+        match read_if_exists(&dir.join(Self::cache_file_name(file_handle))).await? {
+            Some(bytes) => Ok(Some(serde_json::from_slice(&bytes)?)),
+            None => Ok(None),
+        }
+    }
+
+    /// Caches what's learned from the file at `file_handle`, e.g. a manifest.
+    pub async fn save_details<T: Serialize>(
+        &self,
+        file_handle: &FileHandleRef,
+        details: &T,
+    ) -> Result<()> {
+        let dir = match &self.dir {
+            Some(dir) => dir.join(Self::DETAILS_DIR),
+            None => return Ok(()),
+        };
+        tokio::fs::create_dir_all(&dir).await?;
+        write_atomically(
+            &dir.join(Self::cache_file_name(file_handle)),
+            &serde_json::to_vec(details)?,
+        )
+        .await
+    }
+
+    /// Evicts what's cached by `save_details` for files other than `file_handles`.
+    pub async fn retain_details(&self, file_handles: &[FileHandle]) -> Result<()> {
+        let dir = match &self.dir {
+            Some(dir) => dir.join(Self::DETAILS_DIR),
+            None => return Ok(()),
+        };
+        if !dir.exists() {
+            return Ok(());
+        }
+        let names: HashSet<_> = file_handles
+            .iter()
+            .map(|file_handle| Self::cache_file_name(file_handle))
+            .collect();
+        evict_except(&dir, &names).await
+    }
+}
+
+impl MetadataCache {
+    /// File handles can be any string, so they are hashed into names safe for the local file
+    /// system.
+    fn cache_file_name(file_handle: &FileHandleRef) -> String {
+        HashValue::sha3_256_of(file_handle.as_bytes()).to_hex()
+    }
+}
+
+async fn read_if_exists(path: &Path) -> Result<Option<Vec<u8>>> {
+    match tokio::fs::read(path).await {
+        Ok(bytes) => Ok(Some(bytes)),
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(None),
+        Err(err) => Err(err.into()),
+    }
+}
+
+/// Writes `path` without ever leaving a partial file, so that an interrupted run doesn't poison
+/// the cache.
+async fn write_atomically(path: &Path, bytes: &[u8]) -> Result<()> {
+    let tmp_path = path.with_extension("tmp");
+    tokio::fs::write(&tmp_path, bytes).await?;
+    tokio::fs::rename(&tmp_path, path).await?;
+    Ok(())
+}
+
+async fn evict_except(dir: &Path, names: &HashSet<String>) -> Result<()> {
+    let mut entries = tokio::fs::read_dir(dir).await?;
+    while let Some(entry) = entries.next_entry().await? {
+        let keep = entry
+            .file_name()
+            .to_str()
+            .map_or(false, |name| names.contains(name));
+        if !keep {
+            tokio::fs::remove_file(entry.path()).await?;
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,147 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+pub mod cache;
+pub mod view;
+
+#[cfg(test)]
+mod tests;
+
+use crate::storage::{BackupStorage, FileHandle, ShellSafeName, TextLine};
+use anyhow::Result;
+use libra_types::transaction::Version;
+use serde::{Deserialize, Serialize};
+use std::convert::TryInto;
+use tokio::io::AsyncReadExt;
+
+/// One line of a metadata file, describing a backup that has been fully written to the
+/// storage. A restore discovers what is available in a storage by reading all the metadata.
+#[derive(Clone, Debug, Deserialize, Serialize, Eq, PartialEq)]
+pub enum Metadata {
+    EpochEndingBackup(EpochEndingBackupMeta),
+    StateSnapshotBackup(StateSnapshotBackupMeta),
+    TransactionBackup(TransactionBackupMeta),
+}
+
+impl Metadata {
+    pub fn new_epoch_ending_backup(
+        first_epoch: u64,
+        last_epoch: u64,
+        manifest: FileHandle,
+    ) -> Self {
+        Self::EpochEndingBackup(EpochEndingBackupMeta {
+            first_epoch,
+            last_epoch,
+            manifest,
+        })
+    }
+
+    pub fn new_state_snapshot_backup(version: Version, manifest: FileHandle) -> Self {
+        Self::StateSnapshotBackup(StateSnapshotBackupMeta { version, manifest })
+    }
+
+    pub fn new_transaction_backup(
+        first_version: Version,
+        last_version: Version,
+        manifest: FileHandle,
+    ) -> Self {
+        Self::TransactionBackup(TransactionBackupMeta {
+            first_version,
+            last_version,
+            manifest,
+        })
+    }
+
+    /// Name of the metadata file, unique to the backup described.
+    pub fn name(&self) -> ShellSafeName {
+        match self {
+            Self::EpochEndingBackup(e) => {
+                format!("epoch_ending_{}-{}.meta", e.first_epoch, e.last_epoch)
+            }
+            Self::StateSnapshotBackup(s) => format!("state_snapshot_ver_{}.meta", s.version),
+            Self::TransactionBackup(t) => {
+                format!("transaction_{}-{}.meta", t.first_version, t.last_version)
+            }
+        }
+        .try_into()
+        .unwrap()
+    }
+
+    /// Manifest of the backup described.
+    pub fn manifest(&self) -> &FileHandle {
+        match self {
+            Self::EpochEndingBackup(e) => &e.manifest,
+            Self::StateSnapshotBackup(s) => &s.manifest,
+            Self::TransactionBackup(t) => &t.manifest,
+        }
+    }
+
+    pub fn to_text_line(&self) -> Result<TextLine> {
+        TextLine::new(&serde_json::to_string(self)?)
+    }
+}
+
+/// An epoch ending backup covering the [`first_epoch`, `last_epoch`] range (right side inclusive).
+#[derive(Clone, Debug, Deserialize, Serialize, Eq, PartialEq)]
+pub struct EpochEndingBackupMeta {
+    pub first_epoch: u64,
+    pub last_epoch: u64,
+    pub manifest: FileHandle,
+}
+
+/// A state snapshot backup taken at `version`.
+#[derive(Clone, Debug, Deserialize, Serialize, Eq, PartialEq)]
+pub struct StateSnapshotBackupMeta {
+    pub version: Version,
+    pub manifest: FileHandle,
+}
+
+/// A transaction backup covering the [`first_version`, `last_version`] range (right side
+/// inclusive).
+#[derive(Clone, Debug, Deserialize, Serialize, Eq, PartialEq)]
+pub struct TransactionBackupMeta {
+    pub first_version: Version,
+    pub last_version: Version,
+    pub manifest: FileHandle,
+}
+
+/// Saves `metadata` to `storage`, to be called after the backup it describes is fully written.
+pub async fn save_metadata(storage: &dyn BackupStorage, metadata: &Metadata) -> Result<FileHandle> {
+    storage
+        .save_metadata_line(&metadata.name(), &metadata.to_text_line()?)
+        .await
+}
+
+/// Reads all metadata saved to `storage`.
+pub async fn load_all_metadata(storage: &dyn BackupStorage) -> Result<Vec<Metadata>> {
+    Ok(load_metadata_files(storage)
+        .await?
+        .into_iter()
+        .flat_map(|(_, metadata)| metadata)
+        .collect())
+}
+
+/// Reads all metadata saved to `storage`, along with the metadata file each line is in.
+pub async fn load_metadata_files(
+    storage: &dyn BackupStorage,
+) -> Result<Vec<(FileHandle, Vec<Metadata>)>> {
+    let mut res = Vec::new();
+    for file_handle in storage.list_metadata_files().await? {
+        let mut content = String::new();
+        storage
+            .open_for_read(&file_handle)
+            .await?
+            .read_to_string(&mut content)
+            .await?;
+        res.push((file_handle, parse_metadata_file(&content)?));
+    }
+    Ok(res)
+}
+
+fn parse_metadata_file(content: &str) -> Result<Vec<Metadata>> {
+    Ok(content
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(serde_json::from_str)
+        .collect::<Result<_, _>>()?)
+}
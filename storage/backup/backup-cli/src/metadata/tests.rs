@@ -0,0 +1,79 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::metadata::{view::MetadataView, Metadata};
+use libra_types::transaction::Version;
+
+#[test]
+fn test_select_backups() {
+    let view = MetadataView::from(vec![
+        Metadata::new_epoch_ending_backup(3, 5, "e3".to_string()),
+        Metadata::new_epoch_ending_backup(0, 2, "e0".to_string()),
+        Metadata::new_epoch_ending_backup(1, 2, "e1".to_string()),
+        Metadata::new_epoch_ending_backup(7, 8, "e7".to_string()),
+        Metadata::new_state_snapshot_backup(20, "s20".to_string()),
+        Metadata::new_state_snapshot_backup(10, "s10".to_string()),
+        Metadata::new_transaction_backup(0, 9, "t0".to_string()),
+        Metadata::new_transaction_backup(10, 19, "t10".to_string()),
+        Metadata::new_transaction_backup(15, 29, "t15".to_string()),
+        Metadata::new_transaction_backup(40, 49, "t40".to_string()),
+    ]);
+
+    assert_eq!(
+        view.select_state_snapshot(Version::max_value())
+            .unwrap()
+            .version,
+        20
+    );
+    assert_eq!(view.select_state_snapshot(19).unwrap().version, 10);
+    assert!(view.select_state_snapshot(9).is_err());
+    assert_eq!(
+        view.select_transaction_backups(20, Version::max_value())
+            .unwrap()
+            .into_iter()
+            .map(|t| t.manifest)
+            .collect::<Vec<_>>(),
+        vec!["t15".to_string()],
+    );
+    assert_eq!(
+        view.select_transaction_backups(5, Version::max_value())
+            .unwrap()
+            .into_iter()
+            .map(|t| t.manifest)
+            .collect::<Vec<_>>(),
+        vec!["t0".to_string(), "t10".to_string(), "t15".to_string()],
+    );
+    assert_eq!(
+        view.select_transaction_backups(5, 12)
+            .unwrap()
+            .into_iter()
+            .map(|t| t.manifest)
+            .collect::<Vec<_>>(),
+        vec!["t0".to_string(), "t10".to_string()],
+    );
+    assert!(view
+        .select_transaction_backups(30, Version::max_value())
+        .is_err());
+    assert_eq!(
+        view.select_epoch_ending_backups()
+            .unwrap()
+            .into_iter()
+            .map(|e| e.manifest)
+            .collect::<Vec<_>>(),
+        vec!["e0".to_string(), "e3".to_string()],
+    );
+
+    let empty = MetadataView::from(vec![]);
+    assert!(empty.select_state_snapshot(Version::max_value()).is_err());
+    assert!(empty.select_epoch_ending_backups().is_err());
+}
+
+#[test]
+fn test_text_line_round_trip() {
+    let metadata = Metadata::new_transaction_backup(1, 2, "/backups/t1/manifest".to_string());
+    let line = metadata.to_text_line().unwrap();
+    assert_eq!(
+        serde_json::from_str::<Metadata>(line.as_ref()).unwrap(),
+        metadata,
+    );
+}
@@ -0,0 +1,122 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::metadata::{
+    EpochEndingBackupMeta, Metadata, StateSnapshotBackupMeta, TransactionBackupMeta,
+};
+use anyhow::{anyhow, ensure, Result};
+use libra_types::transaction::Version;
+
+/// All the backups available in a storage, sorted so that the ones needed for a restore can be
+/// selected.
+pub struct MetadataView {
+    epoch_ending_backups: Vec<EpochEndingBackupMeta>,
+    state_snapshot_backups: Vec<StateSnapshotBackupMeta>,
+    transaction_backups: Vec<TransactionBackupMeta>,
+}
+
+impl From<Vec<Metadata>> for MetadataView {
+    fn from(metadata_vec: Vec<Metadata>) -> Self {
+        let mut epoch_ending_backups = Vec::new();
+        let mut state_snapshot_backups = Vec::new();
+        let mut transaction_backups = Vec::new();
+
+        for meta in metadata_vec {
+            match meta {
+                Metadata::EpochEndingBackup(e) => epoch_ending_backups.push(e),
+                Metadata::StateSnapshotBackup(s) => state_snapshot_backups.push(s),
+                Metadata::TransactionBackup(t) => transaction_backups.push(t),
+            }
+        }
+        epoch_ending_backups.sort_by_key(|e| (e.first_epoch, e.last_epoch));
+        state_snapshot_backups.sort_by_key(|s| s.version);
+        transaction_backups.sort_by_key(|t| (t.first_version, t.last_version));
+
+        Self {
+            epoch_ending_backups,
+            state_snapshot_backups,
+            transaction_backups,
+        }
+    }
+}
+
+impl MetadataView {
+    pub fn epoch_ending_backups(&self) -> &[EpochEndingBackupMeta] {
+        &self.epoch_ending_backups
+    }
+
+    pub fn state_snapshot_backups(&self) -> &[StateSnapshotBackupMeta] {
+        &self.state_snapshot_backups
+    }
+
+    pub fn transaction_backups(&self) -> &[TransactionBackupMeta] {
+        &self.transaction_backups
+    }
+
+    /// Returns the state snapshot backup with the highest version no higher than
+    /// `target_version`.
+    pub fn select_state_snapshot(
+        &self,
+        target_version: Version,
+    ) -> Result<StateSnapshotBackupMeta> {
+        self.state_snapshot_backups
+            .iter()
+            .rev()
+            .find(|s| s.version <= target_version)
+            .cloned()
+            .ok_or_else(|| {
+                anyhow!(
+                    "No state snapshot backup found at or before version {}.",
+                    target_version
+                )
+            })
+    }
+
+    /// Returns transaction backups that continuously cover from `start_version` to as far as the
+    /// backups go, but no further than needed to reach `target_version`.
+    pub fn select_transaction_backups(
+        &self,
+        start_version: Version,
+        target_version: Version,
+    ) -> Result<Vec<TransactionBackupMeta>> {
+        let mut res = Vec::new();
+        let mut next_version = start_version;
+        for backup in &self.transaction_backups {
+            if backup.last_version < next_version {
+                continue;
+            }
+            if backup.first_version > next_version || next_version > target_version {
+                break;
+            }
+            next_version = backup.last_version + 1;
+            res.push(backup.clone());
+        }
+
+        ensure!(
+            !res.is_empty(),
+            "No transaction backup found containing version {}.",
+            start_version,
+        );
+        Ok(res)
+    }
+
+    /// Returns epoch ending backups that continuously cover from epoch 0 to as far as the backups
+    /// go.
+    pub fn select_epoch_ending_backups(&self) -> Result<Vec<EpochEndingBackupMeta>> {
+        let mut res = Vec::new();
+        let mut next_epoch = 0;
+        for backup in &self.epoch_ending_backups {
+            if backup.last_epoch < next_epoch {
+                continue;
+            }
+            if backup.first_epoch > next_epoch {
+                break;
+            }
+            next_epoch = backup.last_epoch + 1;
+            res.push(backup.clone());
+        }
+
+        ensure!(!res.is_empty(), "No epoch ending backup found for epoch 0.");
+        Ok(res)
+    }
+}
@@ -3,8 +3,9 @@
 
 use crate::storage::{BackupHandle, FileHandle};
 use anyhow::Result;
+use libra_retrier::exp_retry_strategy;
 use serde::Deserialize;
-use std::path::PathBuf;
+use std::{path::PathBuf, time::Duration};
 use tokio::io::AsyncReadExt;
 
 #[derive(Clone, Debug, Deserialize)]
@@ -50,12 +51,71 @@ pub struct Commands {
     /// stdin will be fed with byte stream.
     /// expected output on stdout:
     ///     FileHandle, trailing newline
+    /// On failure, it's run again with the whole file fed to stdin, and must output the same
+    /// FileHandle.
     pub create_for_write: String,
     /// Command line to open a file for reading.
     /// input env vars:
     ///     $FILE_NAME
     /// expected stdout to stream out bytes of the file.
+    /// On failure, it's run again and what's been read already is skipped.
     pub open_for_read: String,
+    /// Command line to save a line of metadata
+    /// input env vars:
+    ///     $FILE_NAME
+    /// stdin will be fed with byte stream.
+    /// expected output on stdout:
+    ///     FileHandle, trailing newline is trimmed
+    pub save_metadata_line: String,
+    /// Command line to list all existing metadata file handles.
+    /// input env vars: none
+    /// expected output on stdout:
+    ///     One FileHandle per line.
+    pub list_metadata_files: String,
+    /// Command line to list all existing file handles returned by `create_for_write`, excluding
+    /// metadata files.
+    /// input env vars: none
+    /// expected output on stdout:
+    ///     One FileHandle per line.
+    pub list_backup_files: String,
+    /// Command line to delete a file, succeeding if it doesn't exist.
+    /// input env vars:
+    ///     $FILE_HANDLE
+    pub delete_file: String,
+}
+
+/// How failed commands are retried. `open_for_read` is run again to read the rest of the file,
+/// and `create_for_write` is run again with all that's written to the file.
+#[derive(Clone, Debug, Deserialize)]
+#[serde(default)]
+pub struct RetryConfig {
+    /// Number of retries after the first failed attempt.
+    pub max_retries: usize,
+    /// Delay before the first retry, doubled on each of the following retries.
+    pub initial_backoff_ms: u64,
+    /// Upper bound of the delay between retries.
+    pub max_backoff_ms: u64,
+}
+
+impl Default for RetryConfig {
+    fn default() -> Self {
+        Self {
+            max_retries: 0,
+            initial_backoff_ms: 1000,
+            max_backoff_ms: 30_000,
+        }
+    }
+}
+
+impl RetryConfig {
+    /// Delays before each of the retries.
+    pub fn delays(&self) -> impl Iterator<Item = Duration> {
+        exp_retry_strategy(
+            self.initial_backoff_ms,
+            self.max_backoff_ms,
+            self.max_retries,
+        )
+    }
 }
 
 #[derive(Deserialize)]
@@ -64,6 +124,9 @@ pub struct CommandAdapterConfig {
     pub commands: Commands,
     /// Additional environment variables to be set when command lines are spawned.
     pub env_vars: Vec<EnvVar>,
+    /// Retries of failed commands, no retries if not specified.
+    #[serde(default)]
+    pub retry: RetryConfig,
 }
 
 impl CommandAdapterConfig {
@@ -1,20 +1,40 @@
 // Copyright (c) The Libra Core Contributors
 // SPDX-License-Identifier: Apache-2.0
 
-mod config;
+pub mod config;
+pub mod presets;
 
 #[cfg(test)]
 mod tests;
 
 use crate::storage::{
-    command_adapter::config::{CommandAdapterConfig, EnvVar},
+    command_adapter::config::{CommandAdapterConfig, EnvVar, RetryConfig},
     BackupHandle, BackupHandleRef, BackupStorage, FileHandle, FileHandleRef, ShellSafeName,
+    TextLine,
 };
-use anyhow::{anyhow, ensure, Result};
+use anyhow::{anyhow, bail, ensure, Result};
 use async_trait::async_trait;
-use std::{path::PathBuf, process::Stdio};
+use bytes::Bytes;
+use futures::{
+    future::BoxFuture,
+    stream::{self, StreamExt, TryStreamExt},
+    Future,
+};
+use libra_retrier::retry_async;
+use std::{
+    io,
+    path::PathBuf,
+    pin::Pin,
+    process::Stdio,
+    task::{Context, Poll},
+    time::Duration,
+};
 use structopt::StructOpt;
-use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite};
+use tokio::{
+    io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt},
+    process::{Child, ChildStdin, ChildStdout},
+};
+use tokio_util::compat::FuturesAsyncReadCompatExt;
 
 #[derive(StructOpt)]
 pub struct CommandAdapterOpt {
@@ -25,8 +45,39 @@ pub struct CommandAdapterOpt {
     config: PathBuf,
 }
 
+/// Retry options shared by the presets of `CommandAdapter`.
+#[derive(StructOpt)]
+pub struct RetryOpt {
+    #[structopt(
+        long = "max-retries",
+        default_value = "5",
+        help = "Max number of retries of a failed storage operation."
+    )]
+    pub max_retries: usize,
+    #[structopt(
+        long = "initial-backoff-ms",
+        default_value = "1000",
+        help = "Delay before the first retry, doubled on each of the following retries."
+    )]
+    pub initial_backoff_ms: u64,
+}
+
+impl RetryOpt {
+    pub fn config(&self) -> RetryConfig {
+        RetryConfig {
+            max_retries: self.max_retries,
+            initial_backoff_ms: self.initial_backoff_ms,
+            ..Default::default()
+        }
+    }
+}
+
 /// A BackupStorage that delegates required APIs to configured command lines.
 /// see `CommandAdapterConfig`.
+///
+/// Failed commands are retried according to the config. A file being read is read again from
+/// where the failed attempt stopped, and a file being written is kept in memory until it's fully
+/// written, so that it can be written again.
 pub struct CommandAdapter {
     config: CommandAdapterConfig,
 }
@@ -46,23 +97,30 @@ impl CommandAdapter {
         env_vars.extend_from_slice(&self.config.env_vars);
         Command::new(cmd_str.to_string(), env_vars)
     }
+
+    /// Runs a command to completion, retrying on failure according to the config, and returns
+    /// its stdout.
+    async fn run_with_retry(
+        &self,
+        cmd_str: &str,
+        env_vars: Vec<EnvVar>,
+        stdin: &[u8],
+    ) -> Result<String> {
+        let cmd = self.cmd(cmd_str, env_vars);
+        retry_async(self.config.retry.delays(), || Box::pin(cmd.run(stdin))).await
+    }
 }
 
 #[async_trait]
 impl BackupStorage for CommandAdapter {
     async fn create_backup(&self, name: &ShellSafeName) -> Result<BackupHandle> {
-        let mut cmd = self.cmd(
-            &self.config.commands.create_backup,
-            vec![EnvVar::backup_name(name.to_string())],
-        );
-        let output = cmd.spawn().await?.wait_with_output().await?;
-        ensure!(
-            output.status.success(),
-            "Failed running command: {:?}, Exit code: {:?}",
-            cmd,
-            output.status.code(),
-        );
-        let mut backup_handle = BackupHandle::from_utf8(output.stdout)?;
+        let mut backup_handle = self
+            .run_with_retry(
+                &self.config.commands.create_backup,
+                vec![EnvVar::backup_name(name.to_string())],
+                &[],
+            )
+            .await?;
         backup_handle.truncate(backup_handle.trim_end().len());
         Ok(backup_handle)
     }
@@ -72,16 +130,14 @@ impl BackupStorage for CommandAdapter {
         backup_handle: &BackupHandleRef,
         name: &ShellSafeName,
     ) -> Result<(FileHandle, Box<dyn AsyncWrite + Send + Unpin>)> {
-        let mut child = self
-            .cmd(
-                &self.config.commands.create_for_write,
-                vec![
-                    EnvVar::backup_handle(backup_handle.to_string()),
-                    EnvVar::file_name(name.to_string()),
-                ],
-            )
-            .spawn()
-            .await?;
+        let cmd = self.cmd(
+            &self.config.commands.create_for_write,
+            vec![
+                EnvVar::backup_handle(backup_handle.to_string()),
+                EnvVar::file_name(name.to_string()),
+            ],
+        );
+        let mut child = cmd.spawn()?;
         let stdin = child
             .stdin
             .take()
@@ -93,29 +149,93 @@ impl BackupStorage for CommandAdapter {
         let mut file_handle = FileHandle::new();
         stdout.read_to_string(&mut file_handle).await?;
         file_handle.truncate(file_handle.trim_end().len());
-        Ok((file_handle, Box::new(stdin)))
+        let file = FileWriter {
+            cmd,
+            delays: Box::new(self.config.retry.delays()),
+            file_handle: file_handle.clone(),
+            child: Some((child, stdin)),
+            content: Vec::new(),
+            shutdown: None,
+        };
+        Ok((file_handle, Box::new(file)))
     }
 
     async fn open_for_read(
         &self,
         file_handle: &FileHandleRef,
     ) -> Result<Box<dyn AsyncRead + Send + Unpin>> {
-        let mut child = self
-            .cmd(
+        let mut reader = FileReader {
+            cmd: self.cmd(
                 &self.config.commands.open_for_read,
                 vec![EnvVar::file_handle(file_handle.to_string())],
+            ),
+            delays: Box::new(self.config.retry.delays()),
+            child: None,
+            offset: 0,
+        };
+        // Fail early if the command can't be started at all.
+        reader.start().await?;
+        let chunks = stream::unfold(Some(reader), |reader| async move {
+            let mut reader = reader?;
+            match reader.next_chunk().await {
+                Ok(Some(chunk)) => Some((Ok(chunk), Some(reader))),
+                Ok(None) => None,
+                Err(err) => Some((Err(io::Error::new(io::ErrorKind::Other, err)), None)),
+            }
+        });
+        Ok(Box::new(chunks.boxed().into_async_read().compat()))
+    }
+
+    async fn save_metadata_line(
+        &self,
+        name: &ShellSafeName,
+        content: &TextLine,
+    ) -> Result<FileHandle> {
+        let mut file_handle = self
+            .run_with_retry(
+                &self.config.commands.save_metadata_line,
+                vec![EnvVar::file_name(name.to_string())],
+                content.as_ref().as_bytes(),
             )
-            .spawn()
             .await?;
-        let stdout = child
-            .stdout
-            .take()
-            .ok_or_else(|| anyhow!("Child process stdout is None."))?;
-        Ok(Box::new(stdout))
+        file_handle.truncate(file_handle.trim_end().len());
+        Ok(file_handle)
+    }
+
+    async fn list_metadata_files(&self) -> Result<Vec<FileHandle>> {
+        Ok(self
+            .run_with_retry(&self.config.commands.list_metadata_files, vec![], &[])
+            .await?
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .map(str::to_string)
+            .collect())
+    }
+
+    async fn list_backup_files(&self) -> Result<Vec<FileHandle>> {
+        Ok(self
+            .run_with_retry(&self.config.commands.list_backup_files, vec![], &[])
+            .await?
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .map(str::to_string)
+            .collect())
+    }
+
+    async fn delete_file(&self, file_handle: &FileHandleRef) -> Result<()> {
+        self.run_with_retry(
+            &self.config.commands.delete_file,
+            vec![EnvVar::file_handle(file_handle.to_string())],
+            &[],
+        )
+        .await?;
+        Ok(())
     }
 }
 
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 struct Command {
     cmd_str: String,
     env_vars: Vec<EnvVar>,
@@ -140,8 +260,216 @@ impl Command {
         cmd
     }
 
-    async fn spawn(&mut self) -> Result<tokio::process::Child> {
+    fn spawn(&self) -> Result<Child> {
         println!("Spawning {:?}", self);
         Ok(self.tokio_cmd(&self.cmd_str).spawn()?)
     }
+
+    /// Runs the command to completion feeding it `stdin`, and returns its stdout.
+    async fn run(&self, stdin: &[u8]) -> Result<String> {
+        let mut child = self.spawn()?;
+        let mut child_stdin = child
+            .stdin
+            .take()
+            .ok_or_else(|| anyhow!("Child process stdin is None."))?;
+        child_stdin.write_all(stdin).await?;
+        // Close stdin so the command sees EOF.
+        drop(child_stdin);
+        let output = child.wait_with_output().await?;
+        ensure!(
+            output.status.success(),
+            "Failed running command: {:?}, Exit code: {:?}",
+            self,
+            output.status.code(),
+        );
+        Ok(String::from_utf8(output.stdout)?)
+    }
+}
+
+/// Reads the stdout of the `open_for_read` command. If the command fails midway, it's run again
+/// after a delay and what's been read already is skipped.
+struct FileReader {
+    cmd: Command,
+    delays: Box<dyn Iterator<Item = Duration> + Send>,
+    child: Option<(Child, ChildStdout)>,
+    offset: u64,
+}
+
+impl FileReader {
+    const CHUNK_SIZE: usize = 64 * 1024;
+
+    async fn start(&mut self) -> Result<()> {
+        let mut child = self.cmd.spawn()?;
+        let mut stdout = child
+            .stdout
+            .take()
+            .ok_or_else(|| anyhow!("Child process stdout is None."))?;
+        let skipped =
+            tokio::io::copy(&mut (&mut stdout).take(self.offset), &mut tokio::io::sink()).await?;
+        ensure!(
+            skipped == self.offset,
+            "File is shorter than what's been read from it: {} < {}",
+            skipped,
+            self.offset,
+        );
+        self.child = Some((child, stdout));
+        Ok(())
+    }
+
+    async fn read_chunk(&mut self) -> Result<Option<Bytes>> {
+        if self.child.is_none() {
+            self.start().await?;
+        }
+        let (_, stdout) = self.child.as_mut().expect("Started above.");
+        let mut buf = vec![0; Self::CHUNK_SIZE];
+        let len = stdout.read(&mut buf).await?;
+        if len == 0 {
+            let (child, _) = self.child.take().expect("Started above.");
+            let status = child.await?;
+            ensure!(
+                status.success(),
+                "Failed running command: {:?}, Exit code: {:?}",
+                self.cmd,
+                status.code(),
+            );
+            return Ok(None);
+        }
+        buf.truncate(len);
+        self.offset += len as u64;
+        Ok(Some(buf.into()))
+    }
+
+    async fn next_chunk(&mut self) -> Result<Option<Bytes>> {
+        loop {
+            match self.read_chunk().await {
+                Ok(chunk) => return Ok(chunk),
+                Err(err) => match self.delays.next() {
+                    Some(delay) => {
+                        println!("{:#}. Retrying in {:?}.", err, delay);
+                        self.child = None;
+                        tokio::time::delay_for(delay).await;
+                    }
+                    None => return Err(err),
+                },
+            }
+        }
+    }
+}
+
+/// Streams what's written to the stdin of the `create_for_write` command, keeping a copy of it.
+/// Shutting down waits for the command to finish, and if it failed, runs it again with the copy
+/// until it succeeds or runs out of retries.
+struct FileWriter {
+    cmd: Command,
+    delays: Box<dyn Iterator<Item = Duration> + Send>,
+    file_handle: FileHandle,
+    child: Option<(Child, ChildStdin)>,
+    content: Vec<u8>,
+    shutdown: Option<BoxFuture<'static, io::Result<()>>>,
+}
+
+impl FileWriter {
+    async fn finish(
+        cmd: Command,
+        mut delays: Box<dyn Iterator<Item = Duration> + Send>,
+        file_handle: FileHandle,
+        child: Option<(Child, ChildStdin)>,
+        content: Vec<u8>,
+    ) -> Result<()> {
+        let mut err = match child {
+            Some((child, stdin)) => {
+                // Close stdin so the command sees EOF.
+                drop(stdin);
+                let status = child.await?;
+                if status.success() {
+                    return Ok(());
+                }
+                anyhow!(
+                    "Failed running command: {:?}, Exit code: {:?}",
+                    cmd,
+                    status.code(),
+                )
+            }
+            None => anyhow!("Failed writing to command: {:?}", cmd),
+        };
+        loop {
+            let delay = match delays.next() {
+                Some(delay) => delay,
+                None => return Err(err),
+            };
+            println!("{:#}. Retrying in {:?}.", err, delay);
+            tokio::time::delay_for(delay).await;
+            match cmd.run(&content).await {
+                Ok(output) => {
+                    if output.trim_end() != file_handle {
+                        bail!(
+                            "File handle changed on retry. Was: {}, now: {}",
+                            file_handle,
+                            output.trim_end(),
+                        );
+                    }
+                    return Ok(());
+                }
+                Err(e) => err = e,
+            }
+        }
+    }
+}
+
+impl AsyncWrite for FileWriter {
+    fn poll_write(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        let this = &mut *self;
+        if let Some((_, stdin)) = &mut this.child {
+            match Pin::new(stdin).poll_write(cx, buf) {
+                Poll::Pending => return Poll::Pending,
+                Poll::Ready(Ok(len)) => {
+                    this.content.extend_from_slice(&buf[..len]);
+                    return Poll::Ready(Ok(len));
+                }
+                // The command failed, what's written is only kept for the retries.
+                Poll::Ready(Err(_)) => this.child = None,
+            }
+        }
+        this.content.extend_from_slice(buf);
+        Poll::Ready(Ok(buf.len()))
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        let this = &mut *self;
+        if let Some((_, stdin)) = &mut this.child {
+            match Pin::new(stdin).poll_flush(cx) {
+                Poll::Pending => return Poll::Pending,
+                Poll::Ready(Ok(())) => (),
+                Poll::Ready(Err(_)) => this.child = None,
+            }
+        }
+        Poll::Ready(Ok(()))
+    }
+
+    fn poll_shutdown(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        let this = &mut *self;
+        if this.shutdown.is_none() {
+            let finish = Self::finish(
+                this.cmd.clone(),
+                std::mem::replace(&mut this.delays, Box::new(std::iter::empty())),
+                this.file_handle.clone(),
+                this.child.take(),
+                std::mem::take(&mut this.content),
+            );
+            this.shutdown = Some(Box::pin(async move {
+                finish
+                    .await
+                    .map_err(|err| io::Error::new(io::ErrorKind::Other, err))
+            }));
+        }
+        this.shutdown
+            .as_mut()
+            .expect("Set above.")
+            .as_mut()
+            .poll(cx)
+    }
 }
@@ -0,0 +1,88 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+#[cfg(test)]
+mod tests;
+
+use crate::storage::command_adapter::{
+    config::{CommandAdapterConfig, Commands, EnvVar},
+    CommandAdapter, RetryOpt,
+};
+use structopt::StructOpt;
+
+#[derive(StructOpt)]
+pub struct AzureBlobOpt {
+    #[structopt(long = "account-name", help = "Azure storage account.")]
+    pub account_name: String,
+    #[structopt(long = "container", help = "Blob container to hold backups.")]
+    pub container: String,
+    #[structopt(
+        long = "sub-dir",
+        default_value = "",
+        help = "Directory in the container to hold backups, the container root if not specified."
+    )]
+    pub sub_dir: String,
+    #[structopt(
+        long = "encryption-scope",
+        help = "Encryption scope to encrypt uploaded blobs with, instead of the account default."
+    )]
+    pub encryption_scope: Option<String>,
+    #[structopt(flatten)]
+    pub retry: RetryOpt,
+}
+
+impl AzureBlobOpt {
+    /// Commands built on the `az` cli, which uploads large files as blocks in parallel and
+    /// retries requests by itself. File handles are blob names in the container.
+    pub fn config(&self) -> CommandAdapterConfig {
+        let mut env_vars = vec![
+            EnvVar::new(
+                "AZURE_STORAGE_ACCOUNT".to_string(),
+                self.account_name.clone(),
+            ),
+            EnvVar::new("CONTAINER".to_string(), self.container.clone()),
+            EnvVar::new("PREFIX".to_string(), self.prefix()),
+        ];
+        let mut upload_args = String::new();
+        if let Some(scope) = &self.encryption_scope {
+            env_vars.push(EnvVar::new("ENCRYPTION_SCOPE".to_string(), scope.clone()));
+            upload_args.push_str(r#" --encryption-scope "$ENCRYPTION_SCOPE""#);
+        }
+
+        CommandAdapterConfig {
+            commands: Commands {
+                create_backup: r#"echo "$BACKUP_NAME""#.to_string(),
+                create_for_write: format!(
+                    r#"echo "$PREFIX$BACKUP_HANDLE/$FILE_NAME" && exec >&- && az storage blob upload --only-show-errors -o none --container-name "$CONTAINER" --name "$PREFIX$BACKUP_HANDLE/$FILE_NAME" --file /dev/stdin{}"#,
+                    upload_args,
+                ),
+                open_for_read: r#"az storage blob download --only-show-errors -o none --container-name "$CONTAINER" --name "$FILE_HANDLE" --file /dev/stdout"#.to_string(),
+                save_metadata_line: format!(
+                    r#"az storage blob upload --only-show-errors -o none --container-name "$CONTAINER" --name "${{PREFIX}}metadata/$FILE_NAME" --file /dev/stdin{} && echo "${{PREFIX}}metadata/$FILE_NAME""#,
+                    upload_args,
+                ),
+                list_metadata_files: r#"az storage blob list --only-show-errors --container-name "$CONTAINER" --prefix "${PREFIX}metadata/" --query "[].name" -o tsv"#.to_string(),
+                list_backup_files: r#"az storage blob list --only-show-errors --num-results "*" --container-name "$CONTAINER" --prefix "$PREFIX" --query "[].name" -o tsv | sed "\#^${PREFIX}metadata/#d""#.to_string(),
+                // `az storage blob delete` fails if the blob is gone, e.g. deleted by a failed
+                // attempt.
+                delete_file: r#"EXISTS=$(az storage blob exists --only-show-errors --container-name "$CONTAINER" --name "$FILE_HANDLE" --query exists -o tsv) && if [ "$EXISTS" = true ]; then az storage blob delete --only-show-errors --container-name "$CONTAINER" --name "$FILE_HANDLE"; fi"#.to_string(),
+            },
+            env_vars,
+            retry: self.retry.config(),
+        }
+    }
+
+    pub fn new_storage(&self) -> CommandAdapter {
+        CommandAdapter::new(self.config())
+    }
+
+    /// Prefix of all blob names, empty or ending with a slash.
+    fn prefix(&self) -> String {
+        let sub_dir = self.sub_dir.trim_matches('/');
+        if sub_dir.is_empty() {
+            String::new()
+        } else {
+            format!("{}/", sub_dir)
+        }
+    }
+}
@@ -0,0 +1,33 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use super::*;
+
+#[test]
+fn test_config() {
+    let opt = AzureBlobOpt::from_iter(&[
+        "azure-blob",
+        "--account-name",
+        "libra",
+        "--container",
+        "backup",
+        "--sub-dir",
+        "testnet",
+        "--encryption-scope",
+        "scope",
+    ]);
+    let config = opt.config();
+
+    assert!(config
+        .env_vars
+        .iter()
+        .any(|v| v.key == "PREFIX" && v.value == "testnet/"));
+    assert!(config
+        .commands
+        .create_for_write
+        .ends_with(r#"--encryption-scope "$ENCRYPTION_SCOPE""#));
+    assert!(config
+        .commands
+        .save_metadata_line
+        .contains(r#"--name "${PREFIX}metadata/$FILE_NAME""#));
+}
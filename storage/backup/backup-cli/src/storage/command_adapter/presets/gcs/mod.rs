@@ -0,0 +1,90 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+#[cfg(test)]
+mod tests;
+
+use crate::storage::command_adapter::{
+    config::{CommandAdapterConfig, Commands, EnvVar},
+    CommandAdapter, RetryOpt,
+};
+use structopt::StructOpt;
+
+#[derive(StructOpt)]
+pub struct GcsOpt {
+    #[structopt(long = "bucket", help = "GCS bucket to hold backups.")]
+    pub bucket: String,
+    #[structopt(
+        long = "sub-dir",
+        default_value = "",
+        help = "Directory in the bucket to hold backups, the bucket root if not specified."
+    )]
+    pub sub_dir: String,
+    #[structopt(
+        long = "encryption-key",
+        help = "Cloud KMS key name or customer supplied key to encrypt uploaded files with."
+    )]
+    pub encryption_key: Option<String>,
+    #[structopt(flatten)]
+    pub retry: RetryOpt,
+}
+
+impl GcsOpt {
+    /// Commands built on `gsutil`, which uploads streamed files with resumable uploads and
+    /// retries requests with backoff by itself, up to `Boto:num_retries` times.
+    pub fn config(&self) -> CommandAdapterConfig {
+        let mut env_vars = vec![
+            EnvVar::new("ROOT".to_string(), self.root()),
+            EnvVar::new(
+                "NUM_RETRIES".to_string(),
+                self.retry.max_retries.to_string(),
+            ),
+        ];
+        let mut gsutil = r#"gsutil -q -o "Boto:num_retries=$NUM_RETRIES""#.to_string();
+        if let Some(key) = &self.encryption_key {
+            env_vars.push(EnvVar::new("ENCRYPTION_KEY".to_string(), key.clone()));
+            gsutil.push_str(r#" -o "GSUtil:encryption_key=$ENCRYPTION_KEY""#);
+        }
+
+        CommandAdapterConfig {
+            commands: Commands {
+                create_backup: r#"echo "$BACKUP_NAME""#.to_string(),
+                create_for_write: format!(
+                    r#"echo "$ROOT/$BACKUP_HANDLE/$FILE_NAME" && exec >&- && {} cp - "$ROOT/$BACKUP_HANDLE/$FILE_NAME""#,
+                    gsutil,
+                ),
+                open_for_read: format!(r#"{} cp "$FILE_HANDLE" -"#, gsutil),
+                save_metadata_line: format!(
+                    r#"{} cp - "$ROOT/metadata/$FILE_NAME" && echo "$ROOT/metadata/$FILE_NAME""#,
+                    gsutil,
+                ),
+                // `gsutil ls` fails if nothing matches.
+                list_metadata_files: format!(r#"{} ls "$ROOT/metadata/" || true"#, gsutil),
+                list_backup_files: format!(
+                    r#"({} ls "$ROOT/**" || true) | sed "\#^$ROOT/metadata/#d""#,
+                    gsutil,
+                ),
+                // `gsutil rm` fails if the file is gone, e.g. deleted by a failed attempt.
+                delete_file: format!(
+                    r#"if {0} stat "$FILE_HANDLE" > /dev/null; then {0} rm "$FILE_HANDLE"; fi"#,
+                    gsutil,
+                ),
+            },
+            env_vars,
+            retry: self.retry.config(),
+        }
+    }
+
+    pub fn new_storage(&self) -> CommandAdapter {
+        CommandAdapter::new(self.config())
+    }
+
+    fn root(&self) -> String {
+        let sub_dir = self.sub_dir.trim_matches('/');
+        if sub_dir.is_empty() {
+            format!("gs://{}", self.bucket)
+        } else {
+            format!("gs://{}/{}", self.bucket, sub_dir)
+        }
+    }
+}
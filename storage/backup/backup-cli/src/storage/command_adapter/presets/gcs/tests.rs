@@ -0,0 +1,33 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use super::*;
+
+#[test]
+fn test_config() {
+    let opt = GcsOpt::from_iter(&[
+        "gcs",
+        "--bucket",
+        "libra-backup",
+        "--encryption-key",
+        "projects/p/locations/l/keyRings/r/cryptoKeys/k",
+    ]);
+    let config = opt.config();
+
+    assert!(config
+        .env_vars
+        .iter()
+        .any(|v| v.key == "ROOT" && v.value == "gs://libra-backup"));
+    assert!(config
+        .env_vars
+        .iter()
+        .any(|v| v.key == "NUM_RETRIES" && v.value == "5"));
+    assert!(config
+        .commands
+        .create_for_write
+        .contains(r#"-o "GSUtil:encryption_key=$ENCRYPTION_KEY""#));
+    assert!(config
+        .commands
+        .open_for_read
+        .contains(r#"-o "GSUtil:encryption_key=$ENCRYPTION_KEY""#));
+}
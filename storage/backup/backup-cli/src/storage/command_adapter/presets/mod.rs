@@ -0,0 +1,10 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Command lines of `CommandAdapter` preset for common object stores, so that backups can be
+//! stored in them without writing a config file. Each preset runs the cli of its store, which
+//! must be installed and authenticated where the backup tools run.
+
+pub mod azure_blob;
+pub mod gcs;
+pub mod s3;
@@ -0,0 +1,99 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+#[cfg(test)]
+mod tests;
+
+use crate::storage::command_adapter::{
+    config::{CommandAdapterConfig, Commands, EnvVar},
+    CommandAdapter, RetryOpt,
+};
+use structopt::StructOpt;
+
+#[derive(StructOpt)]
+pub struct S3Opt {
+    #[structopt(long = "bucket", help = "S3 bucket to hold backups.")]
+    pub bucket: String,
+    #[structopt(
+        long = "sub-dir",
+        default_value = "",
+        help = "Directory in the bucket to hold backups, the bucket root if not specified."
+    )]
+    pub sub_dir: String,
+    #[structopt(
+        long = "sse",
+        help = "Server side encryption of uploaded files, AES256 or aws:kms."
+    )]
+    pub sse: Option<String>,
+    #[structopt(
+        long = "sse-kms-key-id",
+        help = "KMS key to encrypt uploaded files with, when --sse is aws:kms."
+    )]
+    pub sse_kms_key_id: Option<String>,
+    #[structopt(flatten)]
+    pub retry: RetryOpt,
+}
+
+impl S3Opt {
+    /// Commands built on the `aws` cli, which uploads streamed files in multipart and retries
+    /// requests with backoff by itself, up to `AWS_MAX_ATTEMPTS` times.
+    pub fn config(&self) -> CommandAdapterConfig {
+        let mut env_vars = vec![
+            EnvVar::new("ROOT".to_string(), self.root()),
+            EnvVar::new("BUCKET_URL".to_string(), format!("s3://{}", self.bucket)),
+            EnvVar::new("AWS_RETRY_MODE".to_string(), "standard".to_string()),
+            EnvVar::new(
+                "AWS_MAX_ATTEMPTS".to_string(),
+                (self.retry.max_retries + 1).to_string(),
+            ),
+        ];
+        let mut upload_args = String::new();
+        if let Some(sse) = &self.sse {
+            env_vars.push(EnvVar::new("SSE".to_string(), sse.clone()));
+            upload_args.push_str(r#" --sse "$SSE""#);
+        }
+        if let Some(key_id) = &self.sse_kms_key_id {
+            env_vars.push(EnvVar::new("SSE_KMS_KEY_ID".to_string(), key_id.clone()));
+            upload_args.push_str(r#" --sse-kms-key-id "$SSE_KMS_KEY_ID""#);
+        }
+
+        CommandAdapterConfig {
+            commands: Commands {
+                create_backup: r#"echo "$BACKUP_NAME""#.to_string(),
+                create_for_write: format!(
+                    r#"echo "$ROOT/$BACKUP_HANDLE/$FILE_NAME" && exec >&- && aws s3 cp --quiet{} - "$ROOT/$BACKUP_HANDLE/$FILE_NAME""#,
+                    upload_args,
+                ),
+                open_for_read: r#"aws s3 cp --quiet "$FILE_HANDLE" -"#.to_string(),
+                save_metadata_line: format!(
+                    r#"aws s3 cp --quiet{} - "$ROOT/metadata/$FILE_NAME" && echo "$ROOT/metadata/$FILE_NAME""#,
+                    upload_args,
+                ),
+                // `aws s3 ls` fails if nothing is under the prefix.
+                list_metadata_files:
+                    r#"(aws s3 ls "$ROOT/metadata/" || true) | awk '{print ENVIRON["ROOT"] "/metadata/" $4}'"#
+                        .to_string(),
+                // `aws s3 ls --recursive` prints keys relative to the bucket.
+                list_backup_files:
+                    r#"(aws s3 ls --recursive "$ROOT/" || true) | sed -E -n "s#.* ([^ ]+)\$#$BUCKET_URL/\1#p" | sed "\#^$ROOT/metadata/#d""#
+                        .to_string(),
+                delete_file: r#"aws s3 rm --quiet "$FILE_HANDLE""#.to_string(),
+            },
+            env_vars,
+            retry: self.retry.config(),
+        }
+    }
+
+    pub fn new_storage(&self) -> CommandAdapter {
+        CommandAdapter::new(self.config())
+    }
+
+    fn root(&self) -> String {
+        let sub_dir = self.sub_dir.trim_matches('/');
+        if sub_dir.is_empty() {
+            format!("s3://{}", self.bucket)
+        } else {
+            format!("s3://{}/{}", self.bucket, sub_dir)
+        }
+    }
+}
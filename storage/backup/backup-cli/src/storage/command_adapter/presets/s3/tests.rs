@@ -0,0 +1,51 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use super::*;
+
+#[test]
+fn test_config() {
+    let opt = S3Opt::from_iter(&[
+        "s3",
+        "--bucket",
+        "libra-backup",
+        "--sub-dir",
+        "/testnet/",
+        "--sse",
+        "aws:kms",
+        "--sse-kms-key-id",
+        "key-id",
+        "--max-retries",
+        "2",
+    ]);
+    let config = opt.config();
+
+    let env = |key: &str| {
+        config
+            .env_vars
+            .iter()
+            .find(|v| v.key == key)
+            .map(|v| v.value.clone())
+    };
+    assert_eq!(env("ROOT").unwrap(), "s3://libra-backup/testnet");
+    assert_eq!(env("AWS_MAX_ATTEMPTS").unwrap(), "3");
+    assert_eq!(env("SSE").unwrap(), "aws:kms");
+    assert_eq!(env("SSE_KMS_KEY_ID").unwrap(), "key-id");
+    assert!(config
+        .commands
+        .create_for_write
+        .contains(r#"--sse "$SSE" --sse-kms-key-id "$SSE_KMS_KEY_ID""#));
+    assert!(config
+        .commands
+        .save_metadata_line
+        .contains(r#"--sse "$SSE" --sse-kms-key-id "$SSE_KMS_KEY_ID""#));
+    assert_eq!(config.retry.max_retries, 2);
+
+    let opt = S3Opt::from_iter(&["s3", "--bucket", "libra-backup"]);
+    let config = opt.config();
+    assert!(!config.commands.create_for_write.contains("--sse"));
+    assert!(config
+        .env_vars
+        .iter()
+        .any(|v| v.key == "ROOT" && v.value == "s3://libra-backup"));
+}
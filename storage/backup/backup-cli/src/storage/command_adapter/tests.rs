@@ -2,10 +2,105 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use super::*;
-use crate::storage::test_util::{arb_backups, test_write_and_read_impl};
+use crate::storage::test_util::{
+    arb_backups, arb_metadata_files, test_list_and_delete_files_impl,
+    test_save_and_list_metadata_files_impl, test_write_and_read_impl,
+};
 use libra_temppath::TempPath;
 use proptest::prelude::*;
-use tokio::runtime::Runtime;
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    runtime::Runtime,
+};
+
+fn get_store(tmpdir: &TempPath) -> CommandAdapter {
+    let config = CommandAdapterConfig::load_from_str(
+        &format!(r#"
+            [[env_vars]]
+            key = "FOLDER"
+            value = "{}"
+
+            [commands]
+            create_backup = 'cd "$FOLDER" && mkdir $BACKUP_NAME && echo $BACKUP_NAME'
+            create_for_write = 'cd "$FOLDER" && cd "$BACKUP_HANDLE" && test ! -f $FILE_NAME && touch $FILE_NAME && echo `pwd`/$FILE_NAME && exec >&- && cat > $FILE_NAME'
+            open_for_read = 'cat "$FILE_HANDLE"'
+            save_metadata_line = 'cd "$FOLDER" && mkdir -p metadata && cd metadata && test ! -f $FILE_NAME && cat > $FILE_NAME && echo `pwd`/$FILE_NAME'
+            list_metadata_files = 'cd "$FOLDER" && (test -d metadata && cd metadata && ls | sed "s#^#`pwd`/#" || true)'
+            list_backup_files = 'cd "$FOLDER" && find "$PWD" -mindepth 2 -maxdepth 2 -type f ! -path "$PWD/metadata/*"'
+            delete_file = 'rm -f "$FILE_HANDLE"'
+        "#, tmpdir.path().to_str().unwrap()),
+    ).unwrap();
+
+    CommandAdapter::new(config)
+}
+
+/// A store whose commands fail the first time they're run for each file, after reading or
+/// writing part of it.
+fn get_flaky_store(tmpdir: &TempPath, max_retries: usize) -> CommandAdapter {
+    let config = CommandAdapterConfig::load_from_str(
+        &format!(r#"
+            [[env_vars]]
+            key = "FOLDER"
+            value = "{}"
+
+            [commands]
+            create_backup = 'echo $BACKUP_NAME'
+            create_for_write = 'echo "$FOLDER/$FILE_NAME" && exec >&- && if mkdir "$FOLDER/$FILE_NAME.write_failed" 2>/dev/null; then head -c 3 > /dev/null; exit 1; else cat > "$FOLDER/$FILE_NAME"; fi'
+            open_for_read = 'if mkdir "$FILE_HANDLE.read_failed" 2>/dev/null; then head -c 3 "$FILE_HANDLE"; exit 1; else cat "$FILE_HANDLE"; fi'
+            save_metadata_line = 'exit 1'
+            list_metadata_files = 'exit 1'
+            list_backup_files = 'exit 1'
+            delete_file = 'exit 1'
+
+            [retry]
+            max_retries = {}
+            initial_backoff_ms = 10
+        "#, tmpdir.path().to_str().unwrap(), max_retries),
+    ).unwrap();
+
+    CommandAdapter::new(config)
+}
+
+#[test]
+fn test_retry_streaming_commands() {
+    let mut rt = Runtime::new().unwrap();
+    let tmpdir = TempPath::new();
+    tmpdir.create_as_dir().unwrap();
+    let content: Vec<u8> = (0..1_000_000u32).map(|i| i as u8).collect();
+
+    rt.block_on(async {
+        let store = get_flaky_store(&tmpdir, 1);
+        let backup_handle = store
+            .create_backup(&"backup".parse().unwrap())
+            .await
+            .unwrap();
+        let (file_handle, mut file) = store
+            .create_for_write(&backup_handle, &"file".parse().unwrap())
+            .await
+            .unwrap();
+        file.write_all(&content).await.unwrap();
+        file.shutdown().await.unwrap();
+
+        let mut read = Vec::new();
+        store
+            .open_for_read(&file_handle)
+            .await
+            .unwrap()
+            .read_to_end(&mut read)
+            .await
+            .unwrap();
+        assert_eq!(read, content);
+
+        // Without retries, the failure is seen when the file is shut down.
+        let store = get_flaky_store(&tmpdir, 0);
+        let (_, mut file) = store
+            .create_for_write(&backup_handle, &"no_retry".parse().unwrap())
+            .await
+            .unwrap();
+        file.write_all(&content).await.unwrap();
+        assert!(file.shutdown().await.is_err());
+    });
+}
 
 proptest! {
     #![proptest_config(ProptestConfig::with_cases(10))]
@@ -18,20 +113,32 @@ proptest! {
         let tmpdir = TempPath::new();
         tmpdir.create_as_dir().unwrap();
 
-        let config = CommandAdapterConfig::load_from_str(
-            &format!(r#"
-                [[env_vars]]
-                key = "FOLDER"
-                value = "{}"
+        let store = get_store(&tmpdir);
+        rt.block_on(test_write_and_read_impl(Box::new(store), &tmpdir, backups));
+    }
+
+    #[test]
+    fn test_save_and_list_metadata_files(
+        input in arb_metadata_files()
+    ) {
+        let mut rt = Runtime::new().unwrap();
+        let tmpdir = TempPath::new();
+        tmpdir.create_as_dir().unwrap();
+
+        let store = get_store(&tmpdir);
+        rt.block_on(test_save_and_list_metadata_files_impl(Box::new(store), input));
+    }
 
-                [commands]
-                create_backup = 'cd "$FOLDER" && mkdir $BACKUP_NAME && echo $BACKUP_NAME'
-                create_for_write = 'cd "$FOLDER" && cd "$BACKUP_HANDLE" && test ! -f $FILE_NAME && touch $FILE_NAME && echo `pwd`/$FILE_NAME && exec >&- && cat > $FILE_NAME'
-                open_for_read = 'cat "$FILE_HANDLE"'
-            "#, tmpdir.path().to_str().unwrap()),
-        ).unwrap();
+    #[test]
+    fn test_list_and_delete_files(
+        backups in arb_backups(),
+        metadata_files in arb_metadata_files(),
+    ) {
+        let mut rt = Runtime::new().unwrap();
+        let tmpdir = TempPath::new();
+        tmpdir.create_as_dir().unwrap();
 
-        let store = CommandAdapter::new(config);
-        rt.block_on(test_write_and_read_impl(Box::new(store), &tmpdir, backups));
+        let store = get_store(&tmpdir);
+        rt.block_on(test_list_and_delete_files_impl(Box::new(store), backups, metadata_files));
     }
 }
@@ -6,14 +6,14 @@ mod tests;
 
 use super::{BackupHandle, BackupHandleRef, FileHandle, FileHandleRef};
 
-use crate::storage::{BackupStorage, ShellSafeName};
+use crate::storage::{BackupStorage, ShellSafeName, TextLine};
 use anyhow::{anyhow, Result};
 use async_trait::async_trait;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use structopt::StructOpt;
 use tokio::{
-    fs::{create_dir, OpenOptions},
-    io::{AsyncRead, AsyncWrite},
+    fs::{create_dir, create_dir_all, read_dir, remove_dir, remove_file, OpenOptions},
+    io::{AsyncRead, AsyncWrite, AsyncWriteExt},
 };
 
 #[derive(StructOpt)]
@@ -33,6 +33,8 @@ pub struct LocalFs {
 }
 
 impl LocalFs {
+    const METADATA_DIR: &'static str = "metadata";
+
     pub fn new(dir: PathBuf) -> Self {
         Self { dir }
     }
@@ -40,6 +42,17 @@ impl LocalFs {
     pub fn new_with_opt(opt: LocalFsOpt) -> Self {
         Self::new(opt.dir)
     }
+
+    fn metadata_dir(&self) -> PathBuf {
+        self.dir.join(Self::METADATA_DIR)
+    }
+
+    fn to_file_handle(path: &Path) -> Result<FileHandle> {
+        path.to_path_buf()
+            .into_os_string()
+            .into_string()
+            .map_err(|s| anyhow!("into_string failed for OsString '{:?}'", s))
+    }
 }
 
 #[async_trait]
@@ -54,13 +67,7 @@ impl BackupStorage for LocalFs {
         backup_handle: &BackupHandleRef,
         name: &ShellSafeName,
     ) -> Result<(FileHandle, Box<dyn AsyncWrite + Send + Unpin>)> {
-        let file_handle = self
-            .dir
-            .join(backup_handle)
-            .join(name.as_ref())
-            .into_os_string()
-            .into_string()
-            .map_err(|s| anyhow!("into_string failed for OsString '{:?}'", s))?;
+        let file_handle = Self::to_file_handle(&self.dir.join(backup_handle).join(name.as_ref()))?;
         let file = OpenOptions::new()
             .write(true)
             .create_new(true)
@@ -77,4 +84,67 @@ impl BackupStorage for LocalFs {
         let file = OpenOptions::new().read(true).open(file_handle).await?;
         Ok(Box::new(file))
     }
+
+    async fn save_metadata_line(
+        &self,
+        name: &ShellSafeName,
+        content: &TextLine,
+    ) -> Result<FileHandle> {
+        let dir = self.metadata_dir();
+        create_dir_all(&dir).await?;
+        let path = dir.join(name.as_ref());
+        let mut file = OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(&path)
+            .await?;
+        file.write_all(content.as_ref().as_bytes()).await?;
+
+        Self::to_file_handle(&path)
+    }
+
+    async fn list_metadata_files(&self) -> Result<Vec<FileHandle>> {
+        let dir = self.metadata_dir();
+        if !dir.exists() {
+            return Ok(Vec::new());
+        }
+
+        let mut res = Vec::new();
+        let mut entries = read_dir(dir).await?;
+        while let Some(entry) = entries.next_entry().await? {
+            res.push(Self::to_file_handle(&entry.path())?);
+        }
+        Ok(res)
+    }
+
+    async fn list_backup_files(&self) -> Result<Vec<FileHandle>> {
+        let mut res = Vec::new();
+        let mut backups = read_dir(&self.dir).await?;
+        while let Some(backup) = backups.next_entry().await? {
+            if !backup.file_type().await?.is_dir() || backup.file_name() == Self::METADATA_DIR {
+                continue;
+            }
+            let mut entries = read_dir(backup.path()).await?;
+            while let Some(entry) = entries.next_entry().await? {
+                res.push(Self::to_file_handle(&entry.path())?);
+            }
+        }
+        Ok(res)
+    }
+
+    async fn delete_file(&self, file_handle: &FileHandleRef) -> Result<()> {
+        let path = Path::new(file_handle);
+        match remove_file(path).await {
+            Ok(()) => (),
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => (),
+            Err(err) => return Err(err.into()),
+        }
+        // Remove the backup folder once it's emptied, which fails harmlessly if it's not.
+        if let Some(dir) = path.parent() {
+            if dir != self.metadata_dir() {
+                let _ = remove_dir(dir).await;
+            }
+        }
+        Ok(())
+    }
 }
@@ -2,7 +2,10 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use super::*;
-use crate::storage::test_util::{arb_backups, test_write_and_read_impl};
+use crate::storage::test_util::{
+    arb_backups, arb_metadata_files, test_list_and_delete_files_impl,
+    test_save_and_list_metadata_files_impl, test_write_and_read_impl,
+};
 use libra_temppath::TempPath;
 use proptest::prelude::*;
 use tokio::runtime::Runtime;
@@ -21,4 +24,29 @@ proptest! {
         let mut rt = Runtime::new().unwrap();
         rt.block_on(test_write_and_read_impl(Box::new(store), &tmpdir, backups));
     }
+
+    #[test]
+    fn test_save_and_list_metadata_files(
+        input in arb_metadata_files()
+    ) {
+        let tmpdir = TempPath::new();
+        tmpdir.create_as_dir().unwrap();
+        let store = LocalFs::new(tmpdir.path().to_path_buf());
+
+        let mut rt = Runtime::new().unwrap();
+        rt.block_on(test_save_and_list_metadata_files_impl(Box::new(store), input));
+    }
+
+    #[test]
+    fn test_list_and_delete_files(
+        backups in arb_backups(),
+        metadata_files in arb_metadata_files(),
+    ) {
+        let tmpdir = TempPath::new();
+        tmpdir.create_as_dir().unwrap();
+        let store = LocalFs::new(tmpdir.path().to_path_buf());
+
+        let mut rt = Runtime::new().unwrap();
+        rt.block_on(test_list_and_delete_files_impl(Box::new(store), backups, metadata_files));
+    }
 }
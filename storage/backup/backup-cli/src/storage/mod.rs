@@ -10,7 +10,10 @@ mod test_util;
 mod tests;
 
 use crate::storage::{
-    command_adapter::{CommandAdapter, CommandAdapterOpt},
+    command_adapter::{
+        presets::{azure_blob::AzureBlobOpt, gcs::GcsOpt, s3::S3Opt},
+        CommandAdapter, CommandAdapterOpt,
+    },
     local_fs::{LocalFs, LocalFsOpt},
 };
 use anyhow::{ensure, Result};
@@ -85,8 +88,28 @@ impl Arbitrary for ShellSafeName {
     }
 }
 
+/// A single line of text, terminated by a newline, fit to be appended to a metadata file.
+#[cfg_attr(test, derive(Debug, Eq, PartialEq))]
+pub struct TextLine(String);
+
+impl TextLine {
+    pub fn new(value: &str) -> Result<Self> {
+        let newlines: &[_] = &['\n', '\r'];
+        ensure!(value.find(newlines).is_none(), "Newline not allowed.");
+        let mut ret = value.to_string();
+        ret.push('\n');
+        Ok(Self(ret))
+    }
+}
+
+impl AsRef<str> for TextLine {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
 #[async_trait]
-pub trait BackupStorage {
+pub trait BackupStorage: Send + Sync {
     /// Hint that a bunch of files are gonna be created related to a backup identified by `name`,
     /// which is unique to the content of the backup, i.e. it won't be the same name unless you are
     /// backing up exactly the same thing.
@@ -105,6 +128,21 @@ pub trait BackupStorage {
         &self,
         file_handle: &FileHandleRef,
     ) -> Result<Box<dyn AsyncRead + Send + Unpin>>;
+    /// Asks to save a line of metadata, under a file named `name`, which is unique to the content
+    /// of the line. Metadata files are how a restore discovers the backups that exist in the
+    /// storage, so this is called after the backup it describes is fully written.
+    async fn save_metadata_line(
+        &self,
+        name: &ShellSafeName,
+        content: &TextLine,
+    ) -> Result<FileHandle>;
+    /// Lists all the metadata files saved by `save_metadata_line`.
+    async fn list_metadata_files(&self) -> Result<Vec<FileHandle>>;
+    /// Lists all the files created by `create_for_write`, across all backups.
+    async fn list_backup_files(&self) -> Result<Vec<FileHandle>>;
+    /// Deletes a file created by `create_for_write` or `save_metadata_line`. Deleting a file that
+    /// doesn't exist is not an error, so that an interrupted deletion can be retried.
+    async fn delete_file(&self, file_handle: &FileHandleRef) -> Result<()>;
 }
 
 #[derive(StructOpt)]
@@ -113,6 +151,14 @@ pub enum StorageOpt {
     LocalFs(LocalFsOpt),
     #[structopt(about = "Select the CommandAdapter backup store.")]
     CommandAdapter(CommandAdapterOpt),
+    #[structopt(about = "Select the CommandAdapter backup store, preset for S3 with the aws cli.")]
+    S3(S3Opt),
+    #[structopt(about = "Select the CommandAdapter backup store, preset for GCS with gsutil.")]
+    Gcs(GcsOpt),
+    #[structopt(
+        about = "Select the CommandAdapter backup store, preset for Azure Blob with the az cli."
+    )]
+    AzureBlob(AzureBlobOpt),
 }
 
 impl StorageOpt {
@@ -120,6 +166,9 @@ impl StorageOpt {
         Ok(match self {
             StorageOpt::LocalFs(opt) => Arc::new(LocalFs::new_with_opt(opt)),
             StorageOpt::CommandAdapter(opt) => Arc::new(CommandAdapter::new_with_opt(opt).await?),
+            StorageOpt::S3(opt) => Arc::new(opt.new_storage()),
+            StorageOpt::Gcs(opt) => Arc::new(opt.new_storage()),
+            StorageOpt::AzureBlob(opt) => Arc::new(opt.new_storage()),
         })
     }
 }
@@ -1,7 +1,7 @@
 // Copyright (c) The Libra Core Contributors
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::storage::{BackupStorage, ShellSafeName};
+use crate::storage::{BackupStorage, ShellSafeName, TextLine};
 use libra_temppath::TempPath;
 use proptest::{
     collection::{hash_map, vec},
@@ -33,6 +33,7 @@ pub async fn test_write_and_read_impl(
             let (handle, mut file) = store.create_for_write(&backup_handle, name).await.unwrap();
             assert_eq!(handle, to_file_name(&tmpdir, backup_name, name));
             file.write_all(content).await.unwrap();
+            file.shutdown().await.unwrap();
         }
     }
 
@@ -47,6 +48,112 @@ pub async fn test_write_and_read_impl(
     }
 }
 
+pub async fn test_save_and_list_metadata_files_impl(
+    store: Box<dyn BackupStorage>,
+    input: Vec<(ShellSafeName, TextLine)>,
+) {
+    assert!(store.list_metadata_files().await.unwrap().is_empty());
+
+    let mut file_handles = Vec::new();
+    for (name, content) in &input {
+        file_handles.push(store.save_metadata_line(name, content).await.unwrap());
+    }
+
+    let mut listed = store.list_metadata_files().await.unwrap();
+    listed.sort();
+    file_handles.sort();
+    assert_eq!(listed, file_handles);
+
+    let mut contents = Vec::new();
+    for file_handle in &listed {
+        let mut buf = String::new();
+        store
+            .open_for_read(file_handle)
+            .await
+            .unwrap()
+            .read_to_string(&mut buf)
+            .await
+            .unwrap();
+        contents.push(buf);
+    }
+    contents.sort();
+    let mut expected = input
+        .iter()
+        .map(|(_, line)| line.as_ref().to_string())
+        .collect::<Vec<_>>();
+    expected.sort();
+    assert_eq!(contents, expected);
+}
+
+pub async fn test_list_and_delete_files_impl(
+    store: Box<dyn BackupStorage>,
+    backups: HashMap<ShellSafeName, HashMap<ShellSafeName, Vec<u8>>>,
+    metadata_files: Vec<(ShellSafeName, TextLine)>,
+) {
+    let mut file_handles = Vec::new();
+    for (backup_name, files) in &backups {
+        let backup_handle = store.create_backup(backup_name).await.unwrap();
+        for (name, content) in files {
+            let (handle, mut file) = store.create_for_write(&backup_handle, name).await.unwrap();
+            file.write_all(content).await.unwrap();
+            file.shutdown().await.unwrap();
+            file_handles.push(handle);
+        }
+    }
+    let mut metadata_handles = Vec::new();
+    for (name, content) in &metadata_files {
+        metadata_handles.push(store.save_metadata_line(name, content).await.unwrap());
+    }
+    file_handles.sort();
+    metadata_handles.sort();
+
+    // Metadata files are not listed as backup files.
+    let mut listed = store.list_backup_files().await.unwrap();
+    listed.sort();
+    assert_eq!(listed, file_handles);
+
+    // Delete every other file, twice to check deleting is idempotent.
+    let (deleted, kept): (Vec<_>, Vec<_>) = file_handles
+        .into_iter()
+        .enumerate()
+        .partition(|(i, _)| i % 2 == 0);
+    for _ in 0..2 {
+        for (_, handle) in &deleted {
+            store.delete_file(handle).await.unwrap();
+        }
+    }
+    let mut listed = store.list_backup_files().await.unwrap();
+    listed.sort();
+    assert_eq!(
+        listed,
+        kept.into_iter()
+            .map(|(_, handle)| handle)
+            .collect::<Vec<_>>()
+    );
+
+    let mut listed = store.list_metadata_files().await.unwrap();
+    listed.sort();
+    assert_eq!(listed, metadata_handles);
+    for handle in &metadata_handles {
+        store.delete_file(handle).await.unwrap();
+    }
+    assert!(store.list_metadata_files().await.unwrap().is_empty());
+}
+
+pub fn arb_metadata_files() -> impl Strategy<Value = Vec<(ShellSafeName, TextLine)>> {
+    hash_map(
+        any::<ShellSafeName>(), // file name
+        "[a-zA-Z0-9 ]{1,100}",  // line content
+        0..10,
+    )
+    .prop_map(|files| {
+        files
+            .into_iter()
+            .map(|(name, content)| (name, TextLine::new(&content).unwrap()))
+            .collect()
+    })
+}
+
 pub fn arb_backups(
 ) -> impl Strategy<Value = HashMap<ShellSafeName, HashMap<ShellSafeName, Vec<u8>>>> {
     hash_map(
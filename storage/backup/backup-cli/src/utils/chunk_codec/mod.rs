@@ -0,0 +1,417 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+#[cfg(test)]
+mod tests;
+
+use crate::{
+    storage::{BackupStorage, FileHandleRef},
+    utils::read_record_bytes::ReadRecordBytes,
+};
+use aes_gcm::{
+    aead::{generic_array::GenericArray, Aead, NewAead, Payload},
+    Aes256Gcm,
+};
+use anyhow::{anyhow, bail, ensure, Result};
+use flate2::{read::GzDecoder, write::GzEncoder};
+use libra_config::config::SecureBackend;
+use libra_secure_storage::{KVStorage, Storage, Value};
+use libra_types::transaction::Version;
+use rand::{rngs::OsRng, RngCore};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use std::{
+    convert::{TryFrom, TryInto},
+    io::{Read, Write},
+    path::PathBuf,
+    str::FromStr,
+};
+use structopt::StructOpt;
+use tokio::io::AsyncReadExt;
+
+/// The default of `--max-chunk-size` on restore, the largest chunk a codec decodes.
+pub const DEFAULT_MAX_CHUNK_SIZE: usize = 1 << 30;
+
+/// The length in bytes of the AES-256-GCM key.
+pub const KEY_LEN: usize = 32;
+
+/// The length in bytes of the AES-256-GCM nonce, which is prepended to each encrypted chunk.
+const NONCE_LEN: usize = 12;
+
+pub type Key = [u8; KEY_LEN];
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Compression {
+    None,
+    Gzip,
+}
+
+impl FromStr for Compression {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Ok(match s {
+            "none" => Compression::None,
+            "gzip" => Compression::Gzip,
+            _ => bail!("Unknown compression: {}, expecting none or gzip.", s),
+        })
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Encryption {
+    None,
+    Aes256Gcm,
+}
+
+impl FromStr for Encryption {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Ok(match s {
+            "none" => Encryption::None,
+            "aes-256-gcm" => Encryption::Aes256Gcm,
+            _ => bail!("Unknown encryption: {}, expecting none or aes-256-gcm.", s),
+        })
+    }
+}
+
+/// How a chunk file is encoded, recorded in the manifest along with the chunk so that backups
+/// taken with different settings stay restorable.
+///
+/// Serialized as a single byte: compression in the lower four bits, encryption in the upper
+/// four, so that 0, the default for manifests without it, is a chunk stored as is.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(into = "u8", try_from = "u8")]
+pub struct ChunkFormat {
+    pub compression: Compression,
+    pub encryption: Encryption,
+}
+
+impl ChunkFormat {
+    pub fn new(compression: Compression, encryption: Encryption) -> Self {
+        Self {
+            compression,
+            encryption,
+        }
+    }
+}
+
+impl Default for ChunkFormat {
+    fn default() -> Self {
+        Self::new(Compression::None, Encryption::None)
+    }
+}
+
+impl From<ChunkFormat> for u8 {
+    fn from(format: ChunkFormat) -> Self {
+        let compression = match format.compression {
+            Compression::None => 0,
+            Compression::Gzip => 1,
+        };
+        let encryption = match format.encryption {
+            Encryption::None => 0,
+            Encryption::Aes256Gcm => 1,
+        };
+        encryption << 4 | compression
+    }
+}
+
+impl TryFrom<u8> for ChunkFormat {
+    type Error = anyhow::Error;
+
+    fn try_from(byte: u8) -> Result<Self> {
+        let compression = match byte & 0x0f {
+            0 => Compression::None,
+            1 => Compression::Gzip,
+            _ => bail!("Unknown compression in chunk format {:#04x}.", byte),
+        };
+        let encryption = match byte >> 4 {
+            0 => Encryption::None,
+            1 => Encryption::Aes256Gcm,
+            _ => bail!("Unknown encryption in chunk format {:#04x}.", byte),
+        };
+        Ok(Self::new(compression, encryption))
+    }
+}
+
+#[derive(Clone, StructOpt)]
+pub struct EncryptionKeyOpt {
+    #[structopt(
+        long = "encryption-key-storage",
+        parse(from_os_str),
+        help = "YAML file with the config of the secure storage holding the AES-256-GCM key \
+        chunks are encrypted with, e.g. `type: vault` and the vault config."
+    )]
+    pub storage_config: Option<PathBuf>,
+    #[structopt(
+        long = "encryption-key-name",
+        default_value = "backup_encryption_key",
+        help = "Name of the key in the secure storage, holding 32 bytes or their hex encoding."
+    )]
+    pub key_name: String,
+}
+
+impl EncryptionKeyOpt {
+    /// Reads the key from the secure storage, or returns None if no storage is configured.
+    pub fn key(&self) -> Result<Option<Key>> {
+        let config_path = match &self.storage_config {
+            Some(config_path) => config_path,
+            None => return Ok(None),
+        };
+        let backend: SecureBackend = serde_yaml::from_slice(&std::fs::read(config_path)?)?;
+        let key = match Storage::from(&backend).get(&self.key_name)?.value {
+            Value::Bytes(bytes) => bytes,
+            Value::String(hex_key) => hex::decode(hex_key.trim())?,
+            _ => bail!("Encryption key must be stored as bytes or a hex string."),
+        };
+        Ok(Some(key.as_slice().try_into().map_err(|_| {
+            anyhow!(
+                "Encryption key must be {} bytes, got {}.",
+                KEY_LEN,
+                key.len()
+            )
+        })?))
+    }
+}
+
+#[derive(Clone, StructOpt)]
+pub struct ChunkDecodeOpt {
+    #[structopt(flatten)]
+    pub key: EncryptionKeyOpt,
+    #[structopt(
+        long = "max-chunk-size",
+        default_value = "1073741824",
+        help = "Maximum size in bytes of a chunk file after decoding, which must be no smaller \
+        than the --max-chunk-size the backups were taken with."
+    )]
+    pub max_chunk_size: usize,
+}
+
+impl ChunkDecodeOpt {
+    pub fn codec(&self) -> Result<ChunkCodec> {
+        Ok(ChunkCodec::new_for_restore(self.key.key()?).with_max_chunk_size(self.max_chunk_size))
+    }
+}
+
+#[derive(Clone, StructOpt)]
+pub struct ChunkCodecOpt {
+    #[structopt(
+        long = "compression",
+        default_value = "none",
+        help = "Compression of chunk files: none or gzip."
+    )]
+    pub compression: Compression,
+    #[structopt(
+        long = "encryption",
+        default_value = "none",
+        help = "Encryption of chunk files: none or aes-256-gcm, which requires \
+        --encryption-key-storage."
+    )]
+    pub encryption: Encryption,
+    #[structopt(flatten)]
+    pub key: EncryptionKeyOpt,
+}
+
+impl ChunkCodecOpt {
+    pub fn codec(&self) -> Result<ChunkCodec> {
+        ChunkCodec::new(
+            ChunkFormat::new(self.compression, self.encryption),
+            self.key.key()?,
+        )
+    }
+}
+
+/// Which chunk of which backup a chunk file is. It's authenticated along with the content of an
+/// encrypted chunk, so that the chunk can't be passed off as another one encrypted with the same
+/// key.
+#[derive(Serialize)]
+pub enum ChunkId {
+    EpochEnding {
+        first_epoch: u64,
+        last_epoch: u64,
+    },
+    StateSnapshot {
+        version: Version,
+        first_idx: u64,
+        last_idx: u64,
+    },
+    Transaction {
+        first_version: Version,
+        last_version: Version,
+    },
+}
+
+/// Encodes chunk files being backed up in the configured format, and decodes chunk files being
+/// restored from whatever format their manifest says.
+#[derive(Clone)]
+pub struct ChunkCodec {
+    format: ChunkFormat,
+    key: Option<Key>,
+    /// The largest chunk that's decoded, which bounds what a corrupted or malicious chunk can
+    /// make a restore allocate.
+    max_chunk_size: usize,
+}
+
+impl Default for ChunkCodec {
+    fn default() -> Self {
+        Self::new_for_restore(None)
+    }
+}
+
+impl ChunkCodec {
+    pub fn new(format: ChunkFormat, key: Option<Key>) -> Result<Self> {
+        ensure!(
+            format.encryption == Encryption::None || key.is_some(),
+            "Encrypting chunks requires an encryption key.",
+        );
+        Ok(Self {
+            format,
+            key,
+            max_chunk_size: DEFAULT_MAX_CHUNK_SIZE,
+        })
+    }
+
+    /// A codec reading chunks in any format with `key` if encrypted, and writing them as is.
+    pub fn new_for_restore(key: Option<Key>) -> Self {
+        Self {
+            format: ChunkFormat::default(),
+            key,
+            max_chunk_size: DEFAULT_MAX_CHUNK_SIZE,
+        }
+    }
+
+    /// Refuses to decode chunks larger than `max_chunk_size` bytes, instead of the default.
+    pub fn with_max_chunk_size(mut self, max_chunk_size: usize) -> Self {
+        self.max_chunk_size = max_chunk_size;
+        self
+    }
+
+    /// The format `encode` encodes in, to be recorded in the manifest.
+    pub fn format(&self) -> ChunkFormat {
+        self.format
+    }
+
+    pub fn encode(&self, bytes: &[u8], chunk_id: &ChunkId) -> Result<Vec<u8>> {
+        let compressed = match self.format.compression {
+            Compression::None => bytes.to_vec(),
+            Compression::Gzip => {
+                let mut encoder = GzEncoder::new(Vec::new(), flate2::Compression::default());
+                encoder.write_all(bytes)?;
+                encoder.finish()?
+            }
+        };
+        match self.format.encryption {
+            Encryption::None => Ok(compressed),
+            Encryption::Aes256Gcm => {
+                let mut nonce = [0u8; NONCE_LEN];
+                OsRng.fill_bytes(&mut nonce);
+                let mut encrypted = nonce.to_vec();
+                encrypted.extend(
+                    self.aead()?
+                        .encrypt(
+                            GenericArray::from_slice(&nonce),
+                            Payload {
+                                msg: &compressed,
+                                aad: &Self::aad(self.format, chunk_id)?,
+                            },
+                        )
+                        .map_err(|_| anyhow!("Failed encrypting chunk."))?,
+                );
+                Ok(encrypted)
+            }
+        }
+    }
+
+    pub fn decode(
+        &self,
+        format: ChunkFormat,
+        bytes: Vec<u8>,
+        chunk_id: &ChunkId,
+    ) -> Result<Vec<u8>> {
+        ensure!(
+            bytes.len() <= self.max_encoded_size(),
+            "Chunk file of {} bytes is too large to hold a chunk of at most {} bytes.",
+            bytes.len(),
+            self.max_chunk_size,
+        );
+        let compressed = match format.encryption {
+            Encryption::None => bytes,
+            Encryption::Aes256Gcm => {
+                ensure!(bytes.len() >= NONCE_LEN, "Encrypted chunk too short.");
+                let (nonce, encrypted) = bytes.split_at(NONCE_LEN);
+                self.aead()?
+                    .decrypt(
+                        GenericArray::from_slice(nonce),
+                        Payload {
+                            msg: encrypted,
+                            aad: &Self::aad(format, chunk_id)?,
+                        },
+                    )
+                    .map_err(|_| anyhow!("Failed decrypting chunk, wrong key or corrupted."))?
+            }
+        };
+        let decompressed = match format.compression {
+            Compression::None => compressed,
+            Compression::Gzip => {
+                let mut decompressed = Vec::new();
+                GzDecoder::new(compressed.as_slice())
+                    .take(self.max_chunk_size as u64 + 1)
+                    .read_to_end(&mut decompressed)?;
+                decompressed
+            }
+        };
+        ensure!(
+            decompressed.len() <= self.max_chunk_size,
+            "Chunk larger than the maximum chunk size of {} bytes.",
+            self.max_chunk_size,
+        );
+        Ok(decompressed)
+    }
+
+    /// Reads the chunk file at `file_handle`, which is `chunk_id` in `format`, and deserializes
+    /// its records.
+    pub async fn read_records<T: DeserializeOwned>(
+        &self,
+        storage: &dyn BackupStorage,
+        file_handle: &FileHandleRef,
+        format: ChunkFormat,
+        chunk_id: &ChunkId,
+    ) -> Result<Vec<T>> {
+        let mut bytes = Vec::new();
+        storage
+            .open_for_read(file_handle)
+            .await?
+            .take(self.max_encoded_size() as u64 + 1)
+            .read_to_end(&mut bytes)
+            .await?;
+        let bytes = self.decode(format, bytes, chunk_id)?;
+
+        let mut file = bytes.as_slice();
+        let mut records = Vec::new();
+        while let Some(record_bytes) = file.read_record_bytes().await? {
+            records.push(lcs::from_bytes(&record_bytes)?);
+        }
+        Ok(records)
+    }
+
+    /// The most bytes a chunk of at most `max_chunk_size` bytes takes encoded: compression can
+    /// grow incompressible chunks a little, and encryption adds the nonce and the tag.
+    fn max_encoded_size(&self) -> usize {
+        self.max_chunk_size
+            .saturating_add(self.max_chunk_size / 64)
+            .saturating_add(1024)
+    }
+
+    /// The associated data of an encrypted chunk: its format and identity.
+    fn aad(format: ChunkFormat, chunk_id: &ChunkId) -> Result<Vec<u8>> {
+        Ok(lcs::to_bytes(&(u8::from(format), chunk_id))?)
+    }
+
+    fn aead(&self) -> Result<Aes256Gcm> {
+        let key = self
+            .key
+            .as_ref()
+            .ok_or_else(|| anyhow!("Chunk is encrypted but no encryption key is provided."))?;
+        Ok(Aes256Gcm::new(GenericArray::from_slice(key)))
+    }
+}
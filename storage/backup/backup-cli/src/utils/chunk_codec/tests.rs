@@ -0,0 +1,93 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use super::*;
+use proptest::{collection::vec, prelude::*};
+
+const CHUNK_ID: ChunkId = ChunkId::Transaction {
+    first_version: 10,
+    last_version: 19,
+};
+
+fn arb_format() -> impl Strategy<Value = ChunkFormat> {
+    (
+        prop_oneof![Just(Compression::None), Just(Compression::Gzip)],
+        prop_oneof![Just(Encryption::None), Just(Encryption::Aes256Gcm)],
+    )
+        .prop_map(|(compression, encryption)| ChunkFormat::new(compression, encryption))
+}
+
+#[test]
+fn test_plain_format_is_zero() {
+    assert_eq!(u8::from(ChunkFormat::default()), 0);
+    assert!(ChunkFormat::try_from(0x20).is_err());
+    assert!(ChunkFormat::try_from(0x02).is_err());
+}
+
+proptest! {
+    #[test]
+    fn test_format_byte(format in arb_format()) {
+        prop_assert_eq!(ChunkFormat::try_from(u8::from(format)).unwrap(), format);
+    }
+
+    #[test]
+    fn test_encode_decode(
+        format in arb_format(),
+        key in any::<Key>(),
+        bytes in vec(any::<u8>(), 0..10000),
+    ) {
+        let encoded = ChunkCodec::new(format, Some(key))
+            .unwrap()
+            .encode(&bytes, &CHUNK_ID)
+            .unwrap();
+        let restore_codec = ChunkCodec::new_for_restore(Some(key));
+        prop_assert_eq!(
+            restore_codec.decode(format, encoded.clone(), &CHUNK_ID).unwrap(),
+            bytes
+        );
+
+        if format.encryption == Encryption::Aes256Gcm {
+            let mut wrong_key = key;
+            wrong_key[0] ^= 1;
+            prop_assert!(ChunkCodec::new_for_restore(Some(wrong_key))
+                .decode(format, encoded.clone(), &CHUNK_ID)
+                .is_err());
+            prop_assert!(ChunkCodec::new_for_restore(None)
+                .decode(format, encoded.clone(), &CHUNK_ID)
+                .is_err());
+            let other_chunk = ChunkId::Transaction {
+                first_version: 20,
+                last_version: 29,
+            };
+            prop_assert!(restore_codec.decode(format, encoded, &other_chunk).is_err());
+        }
+    }
+}
+
+#[test]
+fn test_encryption_requires_key() {
+    assert!(ChunkCodec::new(
+        ChunkFormat::new(Compression::Gzip, Encryption::Aes256Gcm),
+        None
+    )
+    .is_err());
+}
+
+#[test]
+fn test_max_chunk_size() {
+    let bytes = vec![0u8; 10000];
+    for compression in &[Compression::None, Compression::Gzip] {
+        let format = ChunkFormat::new(*compression, Encryption::None);
+        let encoded = ChunkCodec::new(format, None)
+            .unwrap()
+            .encode(&bytes, &CHUNK_ID)
+            .unwrap();
+        let decode = |max_chunk_size| {
+            ChunkCodec::new_for_restore(None)
+                .with_max_chunk_size(max_chunk_size)
+                .decode(format, encoded.clone(), &CHUNK_ID)
+        };
+        assert_eq!(decode(bytes.len()).unwrap(), bytes);
+        assert!(decode(bytes.len() - 1).is_err());
+    }
+}
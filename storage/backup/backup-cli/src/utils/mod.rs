@@ -2,11 +2,16 @@
 // SPDX-License-Identifier: Apache-2.0
 
 pub mod backup_service_client;
+pub mod chunk_codec;
 pub mod read_record_bytes;
 
 #[cfg(test)]
 pub mod test_utils;
 
+use crate::storage::{BackupStorage, FileHandleRef};
+use anyhow::Result;
+use futures::{stream, Future, Stream, StreamExt};
+use libra_types::transaction::Version;
 use std::{mem::size_of, path::PathBuf};
 use structopt::StructOpt;
 
@@ -16,12 +21,61 @@ pub struct GlobalBackupOpt {
     pub max_chunk_size: usize,
 }
 
-#[derive(StructOpt)]
+#[derive(Clone, StructOpt)]
 pub struct GlobalRestoreOpt {
     #[structopt(long = "target-db-dir", parse(from_os_str))]
     pub db_dir: PathBuf,
+    #[structopt(
+        long = "target-version",
+        help = "Restore the DB as of this version, leaving out transactions and epoch ending \
+        LedgerInfos after it. Restores everything in the backups if not specified."
+    )]
+    pub target_version: Option<Version>,
+    #[structopt(
+        long = "concurrent-downloads",
+        default_value = "8",
+        help = "Number of chunks to download and verify concurrently, ahead of writing them to \
+        the DB."
+    )]
+    pub concurrent_downloads: usize,
+}
+
+impl GlobalRestoreOpt {
+    pub fn target_version(&self) -> Version {
+        self.target_version.unwrap_or(Version::max_value())
+    }
+}
+
+/// Runs `f` on each of `items` in a task of its own, keeping up to `concurrency` tasks running
+/// ahead of the consumer of the returned stream, which yields the results in the order of `items`.
+pub(crate) fn run_ahead<I, F, Fut, T>(
+    items: I,
+    concurrency: usize,
+    f: F,
+) -> impl Stream<Item = Result<T>>
+where
+    I: IntoIterator,
+    F: FnMut(I::Item) -> Fut,
+    Fut: Future<Output = Result<T>> + Send + 'static,
+    T: Send + 'static,
+{
+    stream::iter(items)
+        .map(f)
+        .map(tokio::spawn)
+        .buffered(concurrency.max(1))
+        .map(|res| res?)
+}
+
+/// Size in bytes of a file in the storage, which is read through to tell, since the storage
+/// doesn't keep sizes.
+pub(crate) async fn file_size(
+    storage: &dyn BackupStorage,
+    file_handle: &FileHandleRef,
+) -> Result<u64> {
+    let mut file = storage.open_for_read(file_handle).await?;
+    Ok(tokio::io::copy(&mut file, &mut tokio::io::sink()).await?)
 }
 
-pub(crate) fn should_cut_chunk(chunk: &[u8], record: &[u8], max_chunk_size: usize) -> bool {
-    !chunk.is_empty() && chunk.len() + record.len() + size_of::<u32>() > max_chunk_size
+pub(crate) fn should_cut_chunk(chunk_size: usize, record: &[u8], max_chunk_size: usize) -> bool {
+    chunk_size != 0 && chunk_size + record.len() + size_of::<u32>() > max_chunk_size
 }
@@ -3,9 +3,16 @@
 
 use libra_proptest_helpers::ValueGenerator;
 use libra_temppath::TempPath;
-use libra_types::{ledger_info::LedgerInfoWithSignatures, transaction::TransactionToCommit};
+use libra_types::{
+    block_info::BlockInfo,
+    epoch_state::EpochState,
+    ledger_info::{LedgerInfo, LedgerInfoWithSignatures},
+    transaction::TransactionToCommit,
+    validator_signer::ValidatorSigner,
+    validator_verifier::ValidatorVerifier,
+};
 use libradb::{test_helper::arb_blocks_to_commit, LibraDB};
-use std::sync::Arc;
+use std::{collections::BTreeMap, sync::Arc};
 use storage_interface::DbWriter;
 
 pub fn tmp_db_empty() -> (TempPath, Arc<LibraDB>) {
@@ -19,10 +26,58 @@ pub fn tmp_db_with_random_content() -> (
     TempPath,
     Arc<LibraDB>,
     Vec<(Vec<TransactionToCommit>, LedgerInfoWithSignatures)>,
+) {
+    tmp_db_with_blocks(ValueGenerator::new().generate(arb_blocks_to_commit()))
+}
+
+/// Like `tmp_db_with_random_content`, but every LedgerInfo is signed by a single validator which
+/// is also the validator set of every epoch, so that signatures verify.
+pub fn tmp_db_with_signed_content() -> (
+    TempPath,
+    Arc<LibraDB>,
+    Vec<(Vec<TransactionToCommit>, LedgerInfoWithSignatures)>,
+) {
+    let signer = ValidatorSigner::random(None);
+    let verifier = ValidatorVerifier::new_single(signer.author(), signer.public_key());
+    let blocks = ValueGenerator::new()
+        .generate(arb_blocks_to_commit())
+        .into_iter()
+        .map(|(txns_to_commit, li_with_sigs)| {
+            let li = li_with_sigs.ledger_info();
+            let block_info = BlockInfo::new(
+                li.epoch(),
+                li.round(),
+                li.consensus_block_id(),
+                li.transaction_accumulator_hash(),
+                li.version(),
+                li.timestamp_usecs(),
+                li.next_epoch_state().map(|epoch_state| EpochState {
+                    epoch: epoch_state.epoch,
+                    verifier: verifier.clone(),
+                }),
+            );
+            let li = LedgerInfo::new(block_info, li.consensus_data_hash());
+            let mut signatures = BTreeMap::new();
+            signatures.insert(signer.author(), signer.sign(&li));
+            (
+                txns_to_commit,
+                LedgerInfoWithSignatures::new(li, signatures),
+            )
+        })
+        .collect();
+
+    tmp_db_with_blocks(blocks)
+}
+
+fn tmp_db_with_blocks(
+    blocks: Vec<(Vec<TransactionToCommit>, LedgerInfoWithSignatures)>,
+) -> (
+    TempPath,
+    Arc<LibraDB>,
+    Vec<(Vec<TransactionToCommit>, LedgerInfoWithSignatures)>,
 ) {
     let (tmpdir, db) = tmp_db_empty();
     let mut cur_ver = 0;
-    let blocks = ValueGenerator::new().generate(arb_blocks_to_commit());
     for (txns_to_commit, ledger_info_with_sigs) in &blocks {
         db.save_transactions(
             txns_to_commit,
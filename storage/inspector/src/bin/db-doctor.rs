@@ -0,0 +1,113 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+#![forbid(unsafe_code)]
+
+use anyhow::{ensure, Result};
+use libra_types::transaction::Version;
+use libradb::{verify::DbVerifier, LibraDB, PrunedData};
+use std::path::{Path, PathBuf};
+use storage_interface::DbReader;
+use structopt::StructOpt;
+
+#[derive(Debug, StructOpt)]
+#[structopt(about = "Check a DB for corruption, reading through everything stored in it.")]
+struct Opt {
+    #[structopt(long, parse(from_os_str))]
+    db: PathBuf,
+
+    #[structopt(
+        long,
+        parse(from_os_str),
+        help = "File to keep the next version to verify in, so that an interrupted run resumes \
+        from where it stopped. Not advanced once inconsistencies are found, and removed once \
+        the whole DB is verified."
+    )]
+    cursor_file: Option<PathBuf>,
+
+    #[structopt(long, default_value = "10000")]
+    batch_size: u64,
+
+    #[structopt(long, help = "Skip verifying the latest state tree.")]
+    skip_state: bool,
+}
+
+fn read_cursor(cursor_file: &Option<PathBuf>) -> Result<Version> {
+    match cursor_file {
+        Some(path) if path.exists() => Ok(std::fs::read_to_string(path)?.trim().parse()?),
+        _ => Ok(0),
+    }
+}
+
+fn write_cursor(path: &Path, next_version: Version) -> Result<()> {
+    let tmp_path = path.with_extension("tmp");
+    std::fs::write(&tmp_path, next_version.to_string())?;
+    std::fs::rename(&tmp_path, path)?;
+    Ok(())
+}
+
+fn main() -> Result<()> {
+    let opt = Opt::from_args();
+    ensure!(opt.batch_size > 0, "Batch size must be positive.");
+
+    let db = LibraDB::open(&opt.db, true /* readonly */, None /* pruner */)?;
+    let latest_version = db.get_latest_ledger_info()?.ledger_info().version();
+    let mut verifier = DbVerifier::new(&db);
+
+    let least_readable_version = db.least_readable_version(PrunedData::Transactions)?;
+    let cursor = read_cursor(&opt.cursor_file)?;
+    if cursor > 0 {
+        println!("Resuming from version {}.", cursor);
+    }
+    let mut next_version = std::cmp::max(cursor, least_readable_version);
+    if next_version > cursor {
+        println!(
+            "Transactions before version {} are pruned, starting from there.",
+            next_version
+        );
+    }
+    while next_version <= latest_version {
+        let end_version = std::cmp::min(
+            next_version.saturating_add(opt.batch_size),
+            latest_version + 1,
+        );
+        verifier.verify_transactions(next_version, end_version)?;
+        next_version = end_version;
+        if let Some(path) = &opt.cursor_file {
+            if verifier.inconsistencies().is_empty() {
+                write_cursor(path, next_version)?;
+            }
+        }
+        println!(
+            "Verified transactions up to version {} of {}, {} inconsistencies found.",
+            next_version - 1,
+            latest_version,
+            verifier.inconsistencies().len(),
+        );
+    }
+
+    if !opt.skip_state {
+        let num_nodes = verifier.verify_state(latest_version)?;
+        println!(
+            "Verified {} nodes in the state tree at version {}.",
+            num_nodes, latest_version,
+        );
+    }
+
+    for inconsistency in verifier.inconsistencies() {
+        println!("{}", inconsistency);
+    }
+    ensure!(
+        verifier.inconsistencies().is_empty(),
+        "{} inconsistencies found.",
+        verifier.inconsistencies().len(),
+    );
+    if let Some(path) = &opt.cursor_file {
+        if path.exists() {
+            std::fs::remove_file(path)?;
+        }
+    }
+    println!("No inconsistencies found.");
+
+    Ok(())
+}
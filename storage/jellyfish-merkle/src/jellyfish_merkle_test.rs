@@ -670,6 +670,27 @@ proptest! {
         test_existent_keys_impl(&tree, version, &kvs);
     }
 
+    #[test]
+    fn test_batch_get(
+        kvs in hash_map(any::<HashValue>(), any::<AccountStateBlob>(), 1..1000),
+        nonexistent_keys in vec(any::<HashValue>(), 0..100),
+    ) {
+        let (db, version) = init_mock_db(&kvs);
+        let tree = JellyfishMerkleTree::new(&db);
+
+        // Existent keys interleaved with nonexistent ones, and a key repeated.
+        let mut keys: Vec<HashValue> = kvs.keys().cloned().collect();
+        keys.extend(nonexistent_keys);
+        keys.push(keys[0]);
+        let blobs = tree.batch_get(&keys, version).unwrap();
+        prop_assert_eq!(blobs.len(), keys.len());
+        for (key, blob) in keys.iter().zip(blobs) {
+            prop_assert_eq!(blob, tree.get(*key, version).unwrap());
+        }
+
+        prop_assert!(tree.batch_get(&[], version).unwrap().is_empty());
+    }
+
     #[test]
     fn test_get_range_proof(
         (btree, n) in btree_map(any::<HashValue>(), any::<AccountStateBlob>(), 1..1000)
@@ -110,6 +110,15 @@ pub trait TreeReader {
     /// Gets node given a node key. Returns `None` if the node does not exist.
     fn get_node_option(&self, node_key: &NodeKey) -> Result<Option<Node>>;
 
+    /// Gets nodes given a list of node keys, in the same order. Storage that can read many keys
+    /// at once should override this.
+    fn get_node_options(&self, node_keys: &[NodeKey]) -> Result<Vec<Option<Node>>> {
+        node_keys
+            .iter()
+            .map(|node_key| self.get_node_option(node_key))
+            .collect()
+    }
+
     /// Gets the rightmost leaf. Note that this assumes we are in the process of restoring the tree
     /// and all nodes are at the same version.
     fn get_rightmost_leaf(&self) -> Result<Option<(NodeKey, LeafNode)>>;
@@ -562,6 +571,80 @@ where
         bail!("Jellyfish Merkle tree has cyclic graph inside.");
     }
 
+    /// Gets the account state blobs of many keys at `version`, in the same order as `keys`.
+    ///
+    /// Instead of walking down the tree for each key, all keys are walked down together, level by
+    /// level, reading the nodes at each level with one `TreeReader::get_node_options` call, and
+    /// nodes shared by several keys only once.
+    pub fn batch_get(
+        &self,
+        keys: &[HashValue],
+        version: Version,
+    ) -> Result<Vec<Option<AccountStateBlob>>> {
+        let mut blobs = vec![None; keys.len()];
+        if keys.is_empty() {
+            return Ok(blobs);
+        }
+        // (index of the key, key of the node to visit next)
+        let mut pending: Vec<(usize, NodeKey)> = (0..keys.len())
+            .map(|i| (i, NodeKey::new_empty_path(version)))
+            .collect();
+
+        // We limit the number of loops here deliberately to avoid potential cyclic graph bugs
+        // in the tree structure.
+        for nibble_depth in 0..=ROOT_NIBBLE_HEIGHT {
+            let mut node_keys: Vec<NodeKey> = pending.iter().map(|(_, k)| k.clone()).collect();
+            node_keys.sort();
+            node_keys.dedup();
+            let nodes = self.reader.get_node_options(&node_keys)?;
+            ensure!(
+                nodes.len() == node_keys.len(),
+                "Expected {} nodes, got {}.",
+                node_keys.len(),
+                nodes.len(),
+            );
+
+            let mut next_pending = Vec::new();
+            for (i, node_key) in pending {
+                let idx = node_keys
+                    .binary_search(&node_key)
+                    .expect("All node keys are fetched.");
+                let node = nodes[idx]
+                    .as_ref()
+                    .ok_or_else(|| format_err!("Missing node at {:?}.", node_key))?;
+                match node {
+                    Node::Internal(internal_node) => {
+                        ensure!(nibble_depth < ROOT_NIBBLE_HEIGHT, "ran out of nibbles");
+                        let queried_child_index = keys[i].get_nibble(nibble_depth);
+                        if let Some(child) = internal_node.child(queried_child_index) {
+                            next_pending.push((
+                                i,
+                                node_key.gen_child_node_key(child.version, queried_child_index),
+                            ));
+                        }
+                    }
+                    Node::Leaf(leaf_node) => {
+                        if leaf_node.account_key() == keys[i] {
+                            blobs[i] = Some(leaf_node.blob().clone());
+                        }
+                    }
+                    Node::Null => {
+                        ensure!(
+                            nibble_depth == 0,
+                            "Non-root null node exists with node key {:?}",
+                            node_key
+                        );
+                    }
+                }
+            }
+            if next_pending.is_empty() {
+                return Ok(blobs);
+            }
+            pending = next_pending;
+        }
+        bail!("Jellyfish Merkle tree has cyclic graph inside.");
+    }
+
     /// Gets the proof that shows a list of keys up to `rightmost_key_to_prove` exist at `version`.
     pub fn get_range_proof(
         &self,
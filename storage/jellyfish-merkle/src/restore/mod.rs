@@ -252,6 +252,12 @@ where
         Ok(partial_nodes)
     }
 
+    /// Returns the key of the most recently added account, which after a restart is the rightmost
+    /// account found in storage. Accounts up to and including it must not be added again.
+    pub fn previous_key_hash(&self) -> Option<HashValue> {
+        self.previous_leaf.as_ref().map(|leaf| leaf.account_key())
+    }
+
     /// Restores a chunk of accounts. This function will verify that the given chunk is correct
     /// using the proof and root hash, then write things to storage. If the chunk is invalid, an
     /// error will be returned and nothing will be written to storage.
@@ -76,6 +76,7 @@ proptest! {
 
             let mut restore =
                 JellyfishMerkleRestore::new(&restore_db, version, expected_root_hash).unwrap();
+            prop_assert_eq!(restore.previous_key_hash(), Some(rightmost_key));
             let proof = tree
                 .get_range_proof(
                     remaining_accounts.last().map(|(key, _value)| *key).unwrap(),
@@ -18,6 +18,7 @@ use libra_types::{
 };
 use schemadb::DB;
 use std::{borrow::Borrow, sync::Arc};
+use storage_interface::TreeState;
 
 /// Provides functionalities for LibraDB data restore.
 #[derive(Clone)]
@@ -128,4 +129,14 @@ impl RestoreHandler {
 
         self.db.write_schemas(cs.batch)
     }
+
+    /// Returns the tree state after the first `num_transactions` transactions, which must have
+    /// been restored, for replaying the transactions after them.
+    pub fn get_tree_state(&self, num_transactions: LeafCount) -> Result<TreeState> {
+        ensure!(num_transactions > 0, "No transactions restored.");
+        let txn_info = self
+            .ledger_store
+            .get_transaction_info(num_transactions - 1)?;
+        self.ledger_store.get_tree_state(num_transactions, txn_info)
+    }
 }
@@ -2,7 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::ledger_counters::LedgerCounterBumps;
-use schemadb::SchemaBatch;
+use schemadb::{PreparedBatch, SchemaBatch};
 
 /// Structure that collects changes to be made to the DB in one transaction.
 ///
@@ -28,8 +28,10 @@ impl ChangeSet {
 
 /// ChangeSet that's ready to be committed to the DB.
 ///
-/// This is a wrapper type just to make sure `ChangeSet` to be committed is sealed properly.
+/// This is a wrapper type just to make sure `ChangeSet` to be committed is sealed properly. The
+/// batch is serialized when sealing, before the commit lock is taken, so that commits only wait
+/// on each other's DB writes.
 pub(crate) struct SealedChangeSet {
     /// A batch of db alternations.
-    pub batch: SchemaBatch,
+    pub batch: PreparedBatch,
 }
@@ -0,0 +1,181 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! This module provides `CommitSyncer`, which writes commits to the DB and groups the fsyncs of
+//! those within a commit interval, syncing what's left unsynced from a background thread once the
+//! interval passes, and when it's dropped.
+
+use crate::LIBRA_STORAGE_COMMITS;
+use anyhow::Result;
+use libra_logger::prelude::*;
+use schemadb::{PreparedBatch, SchemaBatch, DB};
+use std::{
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        mpsc::{channel, RecvTimeoutError, Sender},
+        Arc, Mutex, MutexGuard,
+    },
+    thread::JoinHandle,
+    time::{Duration, Instant},
+};
+
+struct SyncStatus {
+    last_synced_at: Instant,
+    /// Whether anything's written without being synced since `last_synced_at`.
+    has_unsynced_commits: bool,
+}
+
+/// Shared between the `CommitSyncer` and its worker thread.
+struct SyncerState {
+    db: Arc<DB>,
+    /// 0 for syncing every commit.
+    commit_interval_ms: AtomicU64,
+    /// Held while writing, so that commits and syncs are ordered.
+    status: Mutex<SyncStatus>,
+}
+
+impl SyncerState {
+    fn commit_interval(&self) -> Duration {
+        Duration::from_millis(self.commit_interval_ms.load(Ordering::Relaxed))
+    }
+
+    fn lock_status(&self) -> MutexGuard<SyncStatus> {
+        self.status
+            .lock()
+            .expect("Locking sync status should not fail.")
+    }
+
+    fn commit(&self, batch: PreparedBatch, force_sync: bool) -> Result<()> {
+        let mut status = self.lock_status();
+        let sync = force_sync || status.last_synced_at.elapsed() >= self.commit_interval();
+        self.db.write_prepared(batch, sync)?;
+        if sync {
+            status.last_synced_at = Instant::now();
+        }
+        status.has_unsynced_commits = !sync;
+        LIBRA_STORAGE_COMMITS
+            .with_label_values(&[if sync { "synced" } else { "unsynced" }])
+            .inc();
+        Ok(())
+    }
+
+    /// Syncs what's unsynced, if `due` says it's time to given how long ago the last sync was.
+    fn sync_if(&self, due: impl FnOnce(Duration) -> bool) -> Result<()> {
+        let mut status = self.lock_status();
+        if !status.has_unsynced_commits || !due(status.last_synced_at.elapsed()) {
+            return Ok(());
+        }
+        // An empty synced write syncs the write-ahead log, including all written before it.
+        self.db.write_prepared(
+            self.db.prepare_batch(SchemaBatch::new())?,
+            true, /* sync */
+        )?;
+        status.last_synced_at = Instant::now();
+        status.has_unsynced_commits = false;
+        Ok(())
+    }
+}
+
+enum Command {
+    Quit,
+    /// The commit interval has changed.
+    Wake,
+}
+
+pub(crate) struct CommitSyncer {
+    state: Arc<SyncerState>,
+    /// Joined upon destruction, after which it's `None`.
+    worker_thread: Option<JoinHandle<()>>,
+    command_sender: Mutex<Sender<Command>>,
+}
+
+impl CommitSyncer {
+    /// Creates the worker thread, syncing every commit until an interval is set.
+    pub fn new(db: Arc<DB>) -> Self {
+        let state = Arc::new(SyncerState {
+            db,
+            commit_interval_ms: AtomicU64::new(0),
+            status: Mutex::new(SyncStatus {
+                last_synced_at: Instant::now(),
+                has_unsynced_commits: false,
+            }),
+        });
+        let (command_sender, command_receiver) = channel();
+        let worker_state = Arc::clone(&state);
+        let worker_thread = std::thread::Builder::new()
+            .name("libradb_syncer".into())
+            .spawn(move || loop {
+                let interval = worker_state.commit_interval();
+                let command = if interval == Duration::from_millis(0) {
+                    command_receiver
+                        .recv()
+                        .map_err(|_| RecvTimeoutError::Disconnected)
+                } else {
+                    command_receiver.recv_timeout(interval)
+                };
+                match command {
+                    Ok(Command::Quit) | Err(RecvTimeoutError::Disconnected) => break,
+                    Ok(Command::Wake) => (),
+                    Err(RecvTimeoutError::Timeout) => {
+                        if let Err(e) = worker_state.sync_if(|elapsed| elapsed >= interval) {
+                            crit!("Failed syncing commits, will retry. {:?}", e);
+                        }
+                    }
+                }
+            })
+            .expect("Creating syncer thread should succeed.");
+
+        Self {
+            state,
+            worker_thread: Some(worker_thread),
+            command_sender: Mutex::new(command_sender),
+        }
+    }
+
+    pub fn set_commit_interval(&self, interval: Option<Duration>) {
+        self.state.commit_interval_ms.store(
+            interval.map_or(0, |interval| interval.as_millis() as u64),
+            Ordering::Relaxed,
+        );
+        self.send(Command::Wake);
+    }
+
+    /// Writes `batch`, synced to disk unless the last sync is within the commit interval, in
+    /// which case it's synced along with a later commit or by the worker thread once the interval
+    /// passes. `force_sync` syncs it regardless.
+    pub fn commit(&self, batch: PreparedBatch, force_sync: bool) -> Result<()> {
+        self.state.commit(batch, force_sync)
+    }
+
+    /// Syncs what's committed so far to disk.
+    pub fn sync(&self) -> Result<()> {
+        self.state.sync_if(|_| true)
+    }
+
+    #[cfg(test)]
+    pub fn has_unsynced_commits(&self) -> bool {
+        self.state.lock_status().has_unsynced_commits
+    }
+
+    fn send(&self, command: Command) {
+        self.command_sender
+            .lock()
+            .expect("command_sender to syncer thread should lock.")
+            .send(command)
+            .expect("Receiver should not destruct prematurely.");
+    }
+}
+
+impl Drop for CommitSyncer {
+    fn drop(&mut self) {
+        self.send(Command::Quit);
+        self.worker_thread
+            .take()
+            .expect("Worker thread must exist.")
+            .join()
+            .expect("Worker thread should join peacefully.");
+        if let Err(e) = self.sync() {
+            crit!("Failed syncing commits on close. {:?}", e);
+        }
+    }
+}
@@ -11,9 +11,12 @@ use crate::{
     errors::LibraDbError,
     ledger_counters::LedgerCounter,
     schema::{
-        event::EventSchema, event_accumulator::EventAccumulatorSchema,
+        event::EventSchema,
+        event_accumulator::EventAccumulatorSchema,
         event_by_key::EventByKeySchema,
+        event_by_type::{hash_type_tag, EventByTypeSchema},
     },
+    tiering::{self, ColdStore},
 };
 use accumulator::{HashReader, MerkleAccumulator};
 use anyhow::{ensure, format_err, Result};
@@ -28,16 +31,19 @@ use libra_types::{
     proof::{position::Position, EventAccumulatorProof, EventProof},
     transaction::Version,
 };
-use schemadb::{schema::ValueCodec, ReadOptions, DB};
+use move_core_types::language_storage::TypeTag;
+use schemadb::{schema::ValueCodec, ReadOptions, SchemaIterator, DB};
 use std::{convert::TryFrom, sync::Arc};
 
 pub(crate) struct EventStore {
     db: Arc<DB>,
+    /// Where historical events are moved to, if tiering is on.
+    cold_store: Option<Arc<ColdStore>>,
 }
 
 impl EventStore {
-    pub fn new(db: Arc<DB>) -> Self {
-        Self { db }
+    pub fn new(db: Arc<DB>, cold_store: Option<Arc<ColdStore>>) -> Self {
+        Self { db, cold_store }
     }
 
     /// Get all of the events given a transaction version.
@@ -46,7 +52,8 @@ impl EventStore {
     pub fn get_events_by_version(&self, version: Version) -> Result<Vec<ContractEvent>> {
         let mut events = vec![];
 
-        let mut iter = self.db.iter::<EventSchema>(ReadOptions::default())?;
+        let mut iter =
+            tiering::iter_at_version::<EventSchema>(&self.db, self.cold_store.as_deref(), version)?;
         // Grab the first event and then iterate until we get all events for this version.
         iter.seek(&version)?;
         while let Some(((ver, index), event)) = iter.next().transpose()? {
@@ -60,19 +67,32 @@ impl EventStore {
     }
 
     /// Get the event raw data given transaction version and the index of the event queried.
+    pub fn get_event_by_version_and_index(
+        &self,
+        version: Version,
+        index: u64,
+    ) -> Result<ContractEvent> {
+        tiering::get_falling_through::<EventSchema>(
+            &self.db,
+            self.cold_store.as_deref(),
+            &(version, index),
+        )?
+        .ok_or_else(|| LibraDbError::NotFound(format!("Event {} of Txn {}", index, version)).into())
+    }
+
+    /// Get the event raw data given transaction version and the index of the event queried, with
+    /// proof towards the event accumulator root of the transaction.
     pub fn get_event_with_proof_by_version_and_index(
         &self,
         version: Version,
         index: u64,
     ) -> Result<(ContractEvent, EventAccumulatorProof)> {
         // Get event content.
-        let event = self
-            .db
-            .get::<EventSchema>(&(version, index))?
-            .ok_or_else(|| LibraDbError::NotFound(format!("Event {} of Txn {}", index, version)))?;
+        let event = self.get_event_by_version_and_index(version, index)?;
 
         // Get the number of events in total for the transaction at `version`.
-        let mut iter = self.db.iter::<EventSchema>(ReadOptions::default())?;
+        let mut iter =
+            tiering::iter_at_version::<EventSchema>(&self.db, self.cold_store.as_deref(), version)?;
         iter.seek_for_prev(&(version + 1))?;
         let num_events = match iter.next().transpose()? {
             Some(((ver, index), _)) if ver == version => (index + 1),
@@ -158,27 +178,58 @@ impl EventStore {
             u64,     // index among events for the same transaction
         )>,
     > {
+        self.get_events_by_key_iter(event_key, start_seq_num, ledger_version)?
+            .take(limit as usize)
+            .collect()
+    }
+
+    /// Gets an iterator that yields what `lookup_events_by_key` returns, without a limit.
+    pub fn get_events_by_key_iter(
+        &self,
+        event_key: &EventKey,
+        start_seq_num: u64,
+        ledger_version: Version,
+    ) -> Result<EventsByKeyIter> {
         let mut iter = self.db.iter::<EventByKeySchema>(ReadOptions::default())?;
         iter.seek(&(*event_key, start_seq_num))?;
+        Ok(EventsByKeyIter {
+            inner: iter,
+            event_key: *event_key,
+            expected_next_seq_num: start_seq_num,
+            ledger_version,
+        })
+    }
+
+    /// Given `address` and `type_tag`, returns the events of that type emitted on event keys
+    /// created by `address`, identified by transaction version and index among all events emitted
+    /// by the same transaction, starting from `start_version`. Result won't contain records with a
+    /// transaction version > `ledger_version` and is in ascending order.
+    pub fn lookup_events_by_type(
+        &self,
+        address: AccountAddress,
+        type_tag: &TypeTag,
+        start_version: Version,
+        limit: u64,
+        ledger_version: Version,
+    ) -> Result<
+        Vec<(
+            Version, // transaction version it belongs to
+            u64,     // index among events for the same transaction
+        )>,
+    > {
+        let type_tag_hash = hash_type_tag(type_tag)?;
+        let mut iter = self.db.iter::<EventByTypeSchema>(ReadOptions::default())?;
+        iter.seek(&(address, type_tag_hash, start_version))?;
 
-        let mut result = Vec::new();
-        let mut cur_seq = start_seq_num;
+        let mut events = Vec::new();
         for res in iter.take(limit as usize) {
-            let ((path, seq), (ver, idx)) = res?;
-            if path != *event_key || ver > ledger_version {
+            let ((addr, hash, ver, idx), ()) = res?;
+            if addr != address || hash != type_tag_hash || ver > ledger_version {
                 break;
             }
-            ensure!(
-                seq == cur_seq,
-                "DB corrupt: Sequence number not continuous, expected: {}, actual: {}.",
-                cur_seq,
-                seq
-            );
-            result.push((seq, ver, idx));
-            cur_seq += 1;
+            events.push((ver, idx));
         }
-
-        Ok(result)
+        Ok(events)
     }
 
     /// Save contract events yielded by the transaction at `version` and return root hash of the
@@ -192,7 +243,7 @@ impl EventStore {
         cs.counter_bumps
             .bump(LedgerCounter::EventsCreated, events.len());
 
-        // EventSchema and EventByKeySchema updates
+        // EventSchema, EventByKeySchema and EventByTypeSchema updates
         events
             .iter()
             .enumerate()
@@ -202,6 +253,15 @@ impl EventStore {
                     &(*event.key(), event.sequence_number()),
                     &(version, idx as u64),
                 )?;
+                cs.batch.put::<EventByTypeSchema>(
+                    &(
+                        event.key().get_creator_address(),
+                        hash_type_tag(event.type_tag())?,
+                        version,
+                        idx as u64,
+                    ),
+                    &(),
+                )?;
                 Ok(())
             })
             .collect::<Result<()>>()?;
@@ -223,6 +283,48 @@ impl EventStore {
 
 type Accumulator<'a> = MerkleAccumulator<EventHashReader<'a>, EventAccumulatorHasher>;
 
+pub struct EventsByKeyIter<'a> {
+    inner: SchemaIterator<'a, EventByKeySchema>,
+    event_key: EventKey,
+    expected_next_seq_num: u64,
+    ledger_version: Version,
+}
+
+impl<'a> EventsByKeyIter<'a> {
+    fn next_impl(&mut self) -> Result<Option<(u64, Version, u64)>> {
+        let ret = match self.inner.next().transpose()? {
+            Some(((path, seq), (ver, idx))) => {
+                if path != self.event_key || ver > self.ledger_version {
+                    return Ok(None);
+                }
+                ensure!(
+                    seq == self.expected_next_seq_num,
+                    "DB corrupt: Sequence number not continuous, expected: {}, actual: {}.",
+                    self.expected_next_seq_num,
+                    seq
+                );
+                self.expected_next_seq_num += 1;
+                Some((seq, ver, idx))
+            }
+            None => None,
+        };
+
+        Ok(ret)
+    }
+}
+
+impl<'a> Iterator for EventsByKeyIter<'a> {
+    type Item = Result<(
+        u64,     // sequence number
+        Version, // transaction version it belongs to
+        u64,     // index among events for the same transaction
+    )>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next_impl().transpose()
+    }
+}
+
 struct EventHashReader<'a> {
     store: &'a EventStore,
     version: Version,
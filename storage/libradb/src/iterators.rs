@@ -0,0 +1,48 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! This file defines the items yielded by the iterator APIs of `LibraDB`, which walk through
+//! transactions, events and account states without the caller reading the column families
+//! directly.
+
+use libra_crypto::HashValue;
+use libra_types::{
+    account_state_blob::AccountStateBlob,
+    contract_event::ContractEvent,
+    proof::{EventProof, SparseMerkleProof, TransactionAccumulatorProof},
+    transaction::{Transaction, TransactionInfo, Version},
+};
+
+/// A transaction yielded by `LibraDB::iter_transactions`.
+#[derive(Clone, Debug)]
+pub struct TransactionItem {
+    pub version: Version,
+    pub transaction: Transaction,
+    pub info: TransactionInfo,
+    /// Events emitted by the transaction, if requested.
+    pub events: Option<Vec<ContractEvent>>,
+    /// Proof of `info` towards the root of the ledger at the ledger version requested, if
+    /// requested.
+    pub proof: Option<TransactionAccumulatorProof>,
+}
+
+/// An event yielded by `LibraDB::iter_events_by_key`.
+#[derive(Clone, Debug)]
+pub struct EventItem {
+    pub transaction_version: Version,
+    pub event_index: u64,
+    pub event: ContractEvent,
+    /// Proof of the event towards the root of the ledger at the ledger version requested, if
+    /// requested.
+    pub proof: Option<EventProof>,
+}
+
+/// An account state yielded by `LibraDB::iter_account_states_at_version`.
+#[derive(Clone, Debug)]
+pub struct AccountStateItem {
+    /// Hash of the account address, which is what the state tree is keyed by.
+    pub key: HashValue,
+    pub blob: AccountStateBlob,
+    /// Proof of the account state towards the state root at the version requested, if requested.
+    pub proof: Option<SparseMerkleProof>,
+}
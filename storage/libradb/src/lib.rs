@@ -15,15 +15,21 @@ pub mod test_helper;
 
 pub mod backup;
 pub mod errors;
+pub mod iterators;
 pub mod schema;
+pub mod state_snapshot;
+pub mod verify;
 
 mod change_set;
+mod commit_syncer;
 mod event_store;
 mod ledger_counters;
 mod ledger_store;
 mod pruner;
+mod secondary;
 mod state_store;
 mod system_store;
+mod tiering;
 mod transaction_store;
 
 #[cfg(any(test, feature = "fuzzing"))]
@@ -32,27 +38,38 @@ mod libradb_test;
 
 #[cfg(feature = "fuzzing")]
 pub use libradb_test::test_save_blocks_impl;
+pub use pruner::{PrunedData, PrunerConfig, PrunerHandle};
+pub use secondary::LibraDBSecondary;
+pub use tiering::{ColdStorageConfig, TieringProgress};
 
 use crate::{
     backup::{backup_handler::BackupHandler, restore_handler::RestoreHandler},
     change_set::{ChangeSet, SealedChangeSet},
+    commit_syncer::CommitSyncer,
     errors::LibraDbError,
     event_store::EventStore,
+    iterators::{AccountStateItem, EventItem, TransactionItem},
     ledger_counters::LedgerCounters,
     ledger_store::LedgerStore,
     pruner::Pruner,
     schema::*,
+    state_snapshot::{
+        StateSnapshotChunk, StateSnapshotHeader, StateSnapshotReader, StateSnapshotWriter,
+    },
     state_store::StateStore,
     system_store::SystemStore,
+    tiering::{ColdStore, Migrator},
     transaction_store::TransactionStore,
 };
 use anyhow::{ensure, Result};
 use itertools::{izip, zip_eq};
+use jellyfish_merkle::{iterator::JellyfishMerkleIterator, restore::JellyfishMerkleRestore};
 use libra_crypto::hash::{CryptoHash, HashValue, SPARSE_MERKLE_PLACEHOLDER_HASH};
 use libra_logger::prelude::*;
 use libra_metrics::{
-    register_int_counter, register_int_gauge, register_int_gauge_vec, IntCounter, IntGauge,
-    IntGaugeVec, OpMetrics,
+    register_histogram_vec, register_int_counter, register_int_counter_vec, register_int_gauge,
+    register_int_gauge_vec, HistogramVec, IntCounter, IntCounterVec, IntGauge, IntGaugeVec,
+    OpMetrics,
 };
 use libra_types::{
     account_address::AccountAddress,
@@ -70,9 +87,20 @@ use libra_types::{
         Version, PRE_GENESIS_VERSION,
     },
 };
+use move_core_types::language_storage::TypeTag;
 use once_cell::sync::Lazy;
-use schemadb::{DB, DEFAULT_CF_NAME};
-use std::{iter::Iterator, path::Path, sync::Arc, time::Instant};
+use schemadb::{ColumnFamilyName, DB, DEFAULT_CF_NAME};
+use std::{
+    io::{Read, Write},
+    iter::Iterator,
+    ops::Range,
+    path::Path,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+    time::{Duration, Instant},
+};
 use storage_interface::{DbReader, DbWriter, StartupInfo, TreeState};
 
 static OP_COUNTER: Lazy<OpMetrics> = Lazy::new(|| OpMetrics::new_and_registered("storage"));
@@ -105,12 +133,111 @@ pub static LIBRA_STORAGE_LATEST_TXN_VERSION: Lazy<IntGauge> = Lazy::new(|| {
     .unwrap()
 });
 
+pub static LIBRA_STORAGE_COMMITS: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        // metric name
+        "libra_storage_commits",
+        // metric description
+        "Libra storage commits, by whether they are synced to disk on their own",
+        // metric labels (dimensions)
+        &["sync"]
+    )
+    .unwrap()
+});
+
+pub static LIBRA_STORAGE_API_LATENCY_SECONDS: Lazy<HistogramVec> = Lazy::new(|| {
+    register_histogram_vec!(
+        // metric name
+        "libra_storage_api_latency_seconds",
+        // metric description
+        "Libra storage read API latency in seconds",
+        // metric labels (dimensions)
+        &["api_name"]
+    )
+    .unwrap()
+});
+
+pub static LIBRA_STORAGE_API_RECORDS_READ: Lazy<HistogramVec> = Lazy::new(|| {
+    register_histogram_vec!(
+        // metric name
+        "libra_storage_api_records_read",
+        // metric description
+        "Number of records read from RocksDB to serve a call to a Libra storage read API",
+        // metric labels (dimensions)
+        &["api_name"],
+        vec![1.0, 2.0, 5.0, 10.0, 20.0, 50.0, 100.0, 200.0, 500.0, 1000.0, 2000.0, 5000.0]
+    )
+    .unwrap()
+});
+
+pub static LIBRA_STORAGE_SLOW_READS: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        // metric name
+        "libra_storage_slow_reads",
+        // metric description
+        "Number of calls to Libra storage read APIs slower than the slow read threshold",
+        // metric labels (dimensions)
+        &["api_name"]
+    )
+    .unwrap()
+});
+
+pub static LIBRA_STORAGE_TIERING_MIGRATED_BEFORE_VERSION: Lazy<IntGauge> = Lazy::new(|| {
+    register_int_gauge!(
+        // metric name
+        "libra_storage_tiering_migrated_before_version",
+        // metric description
+        "Libra storage version before which data is migrated to the cold storage"
+    )
+    .unwrap()
+});
+
+pub static LIBRA_STORAGE_PRUNER_LEAST_READABLE_VERSION: Lazy<IntGaugeVec> = Lazy::new(|| {
+    register_int_gauge_vec!(
+        // metric name
+        "libra_storage_pruner_least_readable_version",
+        // metric description
+        "Libra storage pruner least readable version",
+        // metric labels (dimensions)
+        &["data"]
+    )
+    .unwrap()
+});
+
+pub static LIBRA_STORAGE_PRUNER_PRUNED_VERSIONS: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        // metric name
+        "libra_storage_pruner_pruned_versions",
+        // metric description
+        "Libra storage pruner pruned versions",
+        // metric labels (dimensions)
+        &["data"]
+    )
+    .unwrap()
+});
+
+pub static LIBRA_STORAGE_PRUNER_RECLAIMED_BYTES: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        // metric name
+        "libra_storage_pruner_reclaimed_bytes",
+        // metric description
+        "Libra storage pruner bytes of keys and values deleted, not counting the values of state \
+         tree nodes, which aren't read to be deleted",
+        // metric labels (dimensions)
+        &["data"]
+    )
+    .unwrap()
+});
+
 const MAX_LIMIT: u64 = 1000;
 
 // TODO: Either implement an iteration API to allow a very old client to loop through a long history
 // or guarantee that there is always a recent enough waypoint and client knows to boot from there.
 const MAX_NUM_EPOCH_ENDING_LEDGER_INFO: usize = 100;
 
+// Number of accounts per chunk in an exported state snapshot.
+const STATE_SNAPSHOT_CHUNK_SIZE: usize = 10_000;
+
 fn error_if_too_many_requested(num_requested: u64, max_allowed: u64) -> Result<()> {
     if num_requested > max_allowed {
         Err(LibraDbError::TooManyRequested(num_requested, max_allowed).into())
@@ -129,36 +256,73 @@ pub struct LibraDB {
     event_store: EventStore,
     system_store: SystemStore,
     pruner: Option<Pruner>,
+    /// Moves historical data to the cold storage, if it's configured.
+    migrator: Option<Migrator>,
+    /// Reads slower than this many milliseconds are logged, `u64::max_value()` for none.
+    slow_read_threshold_ms: AtomicU64,
+    /// Writes commits, grouping the fsyncs of those within the commit interval.
+    commit_syncer: CommitSyncer,
 }
 
 impl LibraDB {
-    pub fn open<P: AsRef<Path> + Clone>(
-        db_root_path: P,
-        readonly: bool,
-        prune_window: Option<u64>,
-    ) -> Result<Self> {
-        let column_families = vec![
+    fn column_families() -> Vec<ColumnFamilyName> {
+        vec![
             /* LedgerInfo CF = */ DEFAULT_CF_NAME,
             EPOCH_BY_VERSION_CF_NAME,
             EVENT_ACCUMULATOR_CF_NAME,
             EVENT_BY_KEY_CF_NAME,
+            EVENT_BY_TYPE_CF_NAME,
             EVENT_CF_NAME,
             JELLYFISH_MERKLE_NODE_CF_NAME,
             LEDGER_COUNTERS_CF_NAME,
+            PRUNER_PROGRESS_CF_NAME,
             STALE_NODE_INDEX_CF_NAME,
             TRANSACTION_CF_NAME,
             TRANSACTION_ACCUMULATOR_CF_NAME,
             TRANSACTION_BY_ACCOUNT_CF_NAME,
             TRANSACTION_INFO_CF_NAME,
-        ];
+        ]
+    }
+
+    fn new_with_db(
+        db: Arc<DB>,
+        pruner_config: Option<PrunerConfig>,
+        migrator: Option<Migrator>,
+    ) -> Result<Self> {
+        let cold_store = migrator.as_ref().map(|m| Arc::clone(m.cold_store()));
+        let libra_db = LibraDB {
+            db: Arc::clone(&db),
+            event_store: EventStore::new(Arc::clone(&db), cold_store.clone()),
+            ledger_store: Arc::new(LedgerStore::new(Arc::clone(&db))),
+            state_store: Arc::new(StateStore::new(Arc::clone(&db), cold_store.clone())),
+            transaction_store: Arc::new(TransactionStore::new(Arc::clone(&db), cold_store)),
+            system_store: SystemStore::new(Arc::clone(&db)),
+            pruner: pruner_config
+                .map(|config| Pruner::new(Arc::clone(&db), config))
+                .transpose()?,
+            migrator,
+            slow_read_threshold_ms: AtomicU64::new(u64::max_value()),
+            commit_syncer: CommitSyncer::new(Arc::clone(&db)),
+        };
+        // Catch up with what's been committed before the DB is opened.
+        if let Some(ledger_info) = libra_db.ledger_store.get_latest_ledger_info_option() {
+            libra_db.wake_migrator(ledger_info.ledger_info().version());
+        }
+        Ok(libra_db)
+    }
 
+    pub fn open<P: AsRef<Path> + Clone>(
+        db_root_path: P,
+        readonly: bool,
+        pruner_config: Option<PrunerConfig>,
+    ) -> Result<Self> {
         let path = db_root_path.as_ref().join("libradb");
         let instant = Instant::now();
 
         let db = Arc::new(if readonly {
-            DB::open_readonly(path.clone(), "libradb_ro", column_families)?
+            DB::open_readonly(path.clone(), "libradb_ro", Self::column_families())?
         } else {
-            DB::open(path.clone(), "libradb", column_families)?
+            DB::open(path.clone(), "libradb", Self::column_families())?
         });
 
         info!(
@@ -167,15 +331,36 @@ impl LibraDB {
             instant.elapsed().as_millis()
         );
 
-        Ok(LibraDB {
-            db: Arc::clone(&db),
-            event_store: EventStore::new(Arc::clone(&db)),
-            ledger_store: Arc::new(LedgerStore::new(Arc::clone(&db))),
-            state_store: Arc::new(StateStore::new(Arc::clone(&db))),
-            transaction_store: Arc::new(TransactionStore::new(Arc::clone(&db))),
-            system_store: SystemStore::new(Arc::clone(&db)),
-            pruner: prune_window.map(|n| Pruner::new(Arc::clone(&db), n)),
-        })
+        Self::new_with_db(db, pruner_config, None /* migrator */)
+    }
+
+    /// Opens the DB like `open` does in non-readonly mode, keeping only the latest versions of
+    /// data in it, and moving the rest in the background to a cold DB under
+    /// `cold_storage_config.path`, which can be on cheaper disk. Reads of what's been moved fall
+    /// through to the cold DB.
+    pub fn open_with_cold_storage<P: AsRef<Path> + Clone>(
+        db_root_path: P,
+        pruner_config: Option<PrunerConfig>,
+        cold_storage_config: ColdStorageConfig,
+    ) -> Result<Self> {
+        let path = db_root_path.as_ref().join("libradb");
+        let instant = Instant::now();
+
+        let db = Arc::new(DB::open(path.clone(), "libradb", Self::column_families())?);
+        let cold_store = Arc::new(ColdStore::open(&cold_storage_config.path, Arc::clone(&db))?);
+
+        info!(
+            "Opened LibraDB at {:?} with cold storage at {:?} in {} ms",
+            path,
+            cold_storage_config.path,
+            instant.elapsed().as_millis()
+        );
+
+        Self::new_with_db(
+            db,
+            pruner_config,
+            Some(Migrator::new(cold_store, cold_storage_config.hot_window)),
+        )
     }
 
     /// This opens db in non-readonly mode, without the pruner.
@@ -253,12 +438,102 @@ impl LibraDB {
         Ok((lis, more))
     }
 
+    /// Returns up to `limit` events of type `type_tag` emitted on event keys created by `address`,
+    /// with proofs towards `ledger_version`, in ascending order of the transaction version and
+    /// starting from `start_version`.
+    pub fn get_events_by_type(
+        &self,
+        address: AccountAddress,
+        type_tag: &TypeTag,
+        start_version: Version,
+        limit: u64,
+        ledger_version: Version,
+    ) -> Result<Vec<EventWithProof>> {
+        self.monitor_read(
+            "get_events_by_type",
+            || {
+                format!(
+                    "address: {}, type_tag: {}, start_version: {}, limit: {}, ledger_version: {}",
+                    address, type_tag, start_version, limit, ledger_version
+                )
+            },
+            || {
+                error_if_too_many_requested(limit, MAX_LIMIT)?;
+                self.error_if_pruned(PrunedData::Events, start_version)?;
+
+                self.event_store
+                    .lookup_events_by_type(address, type_tag, start_version, limit, ledger_version)?
+                    .into_iter()
+                    .map(|(ver, idx)| {
+                        let (event, event_proof) = self
+                            .event_store
+                            .get_event_with_proof_by_version_and_index(ver, idx)?;
+                        let txn_info_with_proof = self
+                            .ledger_store
+                            .get_transaction_info_with_proof(ver, ledger_version)?;
+                        let proof = EventProof::new(txn_info_with_proof, event_proof);
+                        Ok(EventWithProof::new(ver, idx, event, proof))
+                    })
+                    .collect()
+            },
+        )
+    }
+
+    /// Versions of `data` before the returned one are pruned, whether or not the DB is opened with
+    /// the pruner.
+    pub fn least_readable_version(&self, data: PrunedData) -> Result<Version> {
+        match self.pruner.as_ref() {
+            Some(pruner) => Ok(pruner.least_readable_version(data)),
+            None => pruner::get_least_readable_version(&self.db, data.progress_tag()),
+        }
+    }
+
+    /// Gets a handle to adjust the pruner with, if the DB is opened with one.
+    pub fn pruner_handle(&self) -> Option<PrunerHandle> {
+        self.pruner.as_ref().map(Pruner::handle)
+    }
+
+    /// Tells how far historical data has been moved to the cold storage, if the DB is opened
+    /// with one.
+    pub fn tiering_progress(&self) -> Option<TieringProgress> {
+        self.migrator.as_ref().map(Migrator::progress)
+    }
+
+    /// Sets the latency above which calls to the instrumented read APIs are logged, with what's
+    /// asked for and how many records are read. None logs no calls, which is the default.
+    pub fn set_slow_read_threshold(&self, threshold: Option<Duration>) {
+        self.slow_read_threshold_ms.store(
+            threshold.map_or(u64::max_value(), |threshold| threshold.as_millis() as u64),
+            Ordering::Relaxed,
+        );
+    }
+
+    /// Sets the interval to group commits in when syncing them to disk: a commit within it since
+    /// the last synced one is written without waiting for the disk, and synced along with the
+    /// next commit after the interval, in the background once the interval passes without one,
+    /// when the DB is closed, or by `sync_commits`. That saves an fsync per commit when
+    /// committing many small chunks, e.g. when state sync is catching up, at the cost of losing
+    /// the commits not yet synced if the machine, not just the process, crashes. Commits ending
+    /// an epoch are always synced. None syncs every commit, which is the default.
+    pub fn set_commit_interval(&self, interval: Option<Duration>) {
+        self.commit_syncer.set_commit_interval(interval)
+    }
+
+    /// Syncs what's committed so far to disk.
+    pub fn sync_commits(&self) -> Result<()> {
+        self.commit_syncer.sync()
+    }
+
     pub fn get_transaction_with_proof(
         &self,
         version: Version,
         ledger_version: Version,
         fetch_events: bool,
     ) -> Result<TransactionWithProof> {
+        self.error_if_pruned(PrunedData::Transactions, version)?;
+        if fetch_events {
+            self.error_if_pruned(PrunedData::Events, version)?;
+        }
         let proof = self
             .ledger_store
             .get_transaction_info_with_proof(version, ledger_version)?;
@@ -279,6 +554,191 @@ impl LibraDB {
         })
     }
 
+    // ================================= Iterator APIs ==================================
+
+    /// Gets an iterator that yields the transactions at `versions`, up to `ledger_version`, with
+    /// their events if `fetch_events`, and proofs towards the root of the ledger at
+    /// `ledger_version` if `with_proof`.
+    pub fn iter_transactions(
+        &self,
+        versions: Range<Version>,
+        ledger_version: Version,
+        fetch_events: bool,
+        with_proof: bool,
+    ) -> Result<impl Iterator<Item = Result<TransactionItem>> + '_> {
+        let start_version = versions.start;
+        let end_version = std::cmp::min(versions.end, ledger_version.saturating_add(1));
+        let num_transactions = end_version.saturating_sub(start_version) as usize;
+        if num_transactions > 0 {
+            self.error_if_pruned(PrunedData::Transactions, start_version)?;
+            if fetch_events {
+                self.error_if_pruned(PrunedData::Events, start_version)?;
+            }
+        }
+
+        let txn_iter = self
+            .transaction_store
+            .get_transaction_iter(start_version, num_transactions)?;
+        let txn_info_iter = self
+            .ledger_store
+            .get_transaction_info_iter(start_version, num_transactions)?;
+        Ok(zip_eq(txn_iter, txn_info_iter).zip(start_version..).map(
+            move |((txn_res, txn_info_res), version)| {
+                let events = if fetch_events {
+                    Some(self.event_store.get_events_by_version(version)?)
+                } else {
+                    None
+                };
+                let proof = if with_proof {
+                    Some(
+                        self.ledger_store
+                            .get_transaction_proof(version, ledger_version)?,
+                    )
+                } else {
+                    None
+                };
+                Ok(TransactionItem {
+                    version,
+                    transaction: txn_res?,
+                    info: txn_info_res?,
+                    events,
+                    proof,
+                })
+            },
+        ))
+    }
+
+    /// Gets an iterator that yields the events on `event_key` from `start_seq_num` in ascending
+    /// order, emitted by transactions up to `ledger_version`, with proofs towards the root of the
+    /// ledger at `ledger_version` if `with_proof`.
+    pub fn iter_events_by_key(
+        &self,
+        event_key: &EventKey,
+        start_seq_num: u64,
+        ledger_version: Version,
+        with_proof: bool,
+    ) -> Result<impl Iterator<Item = Result<EventItem>> + '_> {
+        let iter =
+            self.event_store
+                .get_events_by_key_iter(event_key, start_seq_num, ledger_version)?;
+        Ok(iter.map(move |res| {
+            let (seq, ver, idx) = res?;
+            self.error_if_pruned(PrunedData::Events, ver)?;
+            let (event, proof) = if with_proof {
+                let (event, event_proof) = self
+                    .event_store
+                    .get_event_with_proof_by_version_and_index(ver, idx)?;
+                let txn_info_with_proof = self
+                    .ledger_store
+                    .get_transaction_info_with_proof(ver, ledger_version)?;
+                (
+                    event,
+                    Some(EventProof::new(txn_info_with_proof, event_proof)),
+                )
+            } else {
+                (
+                    self.event_store.get_event_by_version_and_index(ver, idx)?,
+                    None,
+                )
+            };
+            ensure!(
+                seq == event.sequence_number(),
+                "Index broken, expected seq:{}, actual:{}",
+                seq,
+                event.sequence_number()
+            );
+            Ok(EventItem {
+                transaction_version: ver,
+                event_index: idx,
+                event,
+                proof,
+            })
+        }))
+    }
+
+    /// Gets an iterator that yields all account states at `version` in the order of the hashes of
+    /// their addresses, with proofs towards the state root at `version` if `with_proof`.
+    pub fn iter_account_states_at_version(
+        &self,
+        version: Version,
+        with_proof: bool,
+    ) -> Result<impl Iterator<Item = Result<AccountStateItem>> + '_> {
+        self.error_if_pruned(PrunedData::State, version)?;
+        let iter = JellyfishMerkleIterator::new(
+            Arc::clone(&self.state_store),
+            version,
+            HashValue::zero(),
+        )?;
+        Ok(iter.map(move |res| {
+            let (key, blob) = res?;
+            let proof = if with_proof {
+                let (_, proof) = self
+                    .state_store
+                    .get_account_state_with_proof_by_key(key, version)?;
+                Some(proof)
+            } else {
+                None
+            };
+            Ok(AccountStateItem { key, blob, proof })
+        }))
+    }
+
+    // ============================= State Snapshot APIs ================================
+
+    /// Writes all account states at `version` to `writer`, in the format described in
+    /// `state_snapshot`. Returns the number of accounts written.
+    pub fn export_state_snapshot<W: Write>(&self, version: Version, writer: W) -> Result<u64> {
+        let header = StateSnapshotHeader {
+            version,
+            root_hash: self.state_store.get_root_hash(version)?,
+        };
+        let mut snapshot_writer = StateSnapshotWriter::new(writer, &header)?;
+
+        let mut num_accounts = 0;
+        let mut accounts = Vec::with_capacity(STATE_SNAPSHOT_CHUNK_SIZE);
+        let mut iter = self
+            .iter_account_states_at_version(version, false /* with_proof */)?
+            .peekable();
+        while let Some(item) = iter.next() {
+            let item = item?;
+            accounts.push((item.key, item.blob));
+            if accounts.len() == STATE_SNAPSHOT_CHUNK_SIZE || iter.peek().is_none() {
+                let proof = self
+                    .state_store
+                    .get_account_state_range_proof(item.key, version)?;
+                num_accounts += accounts.len() as u64;
+                snapshot_writer.write_chunk(&StateSnapshotChunk {
+                    accounts: std::mem::take(&mut accounts),
+                    proof,
+                })?;
+            }
+        }
+        snapshot_writer.finish()?;
+
+        Ok(num_accounts)
+    }
+
+    /// Reads a state snapshot written by `export_state_snapshot` from `reader`, verifying each
+    /// chunk against the root hash in its header, and saves the account states at the version in
+    /// the header. Only the state tree is saved, so it's meant for an otherwise empty DB, to be
+    /// followed by restoring or replaying transactions from the version. Returns the header.
+    pub fn import_state_snapshot<R: Read>(&self, reader: R) -> Result<StateSnapshotHeader> {
+        let mut snapshot_reader = StateSnapshotReader::new(reader)?;
+        let header = *snapshot_reader.header();
+        let mut receiver =
+            JellyfishMerkleRestore::new(&*self.state_store, header.version, header.root_hash)?;
+        while let Some(chunk) = snapshot_reader.next_chunk()? {
+            receiver.add_chunk(chunk.accounts, chunk.proof)?;
+        }
+        receiver.finish()?;
+
+        info!(
+            "Imported state snapshot at version {} with root hash {}.",
+            header.version, header.root_hash
+        );
+        Ok(header)
+    }
+
     // ================================== Backup APIs ===================================
 
     /// Gets an instance of `BackupHandler` for data backup purpose.
@@ -395,7 +855,12 @@ impl LibraDB {
             None
         };
 
-        Ok((SealedChangeSet { batch: cs.batch }, counters))
+        Ok((
+            SealedChangeSet {
+                batch: self.db.prepare_batch(cs.batch)?,
+            },
+            counters,
+        ))
     }
 
     fn save_transactions_impl(
@@ -455,8 +920,11 @@ impl LibraDB {
     /// Write the whole schema batch including all data necessary to mutate the ledger
     /// state of some transaction by leveraging rocksdb atomicity support. Also committed are the
     /// LedgerCounters.
-    fn commit(&self, sealed_cs: SealedChangeSet) -> Result<()> {
-        self.db.write_schemas(sealed_cs.batch)?;
+    ///
+    /// The write is synced to disk unless the last sync is within the commit interval, in which
+    /// case it's synced later, see `set_commit_interval`. `force_sync` syncs it regardless.
+    fn commit(&self, sealed_cs: SealedChangeSet, force_sync: bool) -> Result<()> {
+        self.commit_syncer.commit(sealed_cs.batch, force_sync)?;
 
         match self.db.get_approximate_sizes_cf() {
             Ok(cf_sizes) => {
@@ -476,11 +944,62 @@ impl LibraDB {
         Ok(())
     }
 
+    /// Runs `f` implementing the read API `api_name`, observing the time it takes and the number
+    /// of records it reads, and logging the call with `describe_args()` if it's slow.
+    fn monitor_read<T>(
+        &self,
+        api_name: &'static str,
+        describe_args: impl FnOnce() -> String,
+        f: impl FnOnce() -> Result<T>,
+    ) -> Result<T> {
+        let instant = Instant::now();
+        let num_records_read_before = schemadb::num_records_read_by_thread();
+        let res = f();
+        let elapsed = instant.elapsed();
+        let num_records_read = schemadb::num_records_read_by_thread() - num_records_read_before;
+
+        LIBRA_STORAGE_API_LATENCY_SECONDS
+            .with_label_values(&[api_name])
+            .observe(elapsed.as_secs_f64());
+        LIBRA_STORAGE_API_RECORDS_READ
+            .with_label_values(&[api_name])
+            .observe(num_records_read as f64);
+        if elapsed.as_millis() as u64 >= self.slow_read_threshold_ms.load(Ordering::Relaxed) {
+            LIBRA_STORAGE_SLOW_READS
+                .with_label_values(&[api_name])
+                .inc();
+            warn!(
+                "Slow storage read: {}({}) took {} ms, reading {} records, {}.",
+                api_name,
+                describe_args(),
+                elapsed.as_millis(),
+                num_records_read,
+                if res.is_ok() { "succeeded" } else { "failed" },
+            );
+        }
+        res
+    }
+
+    fn error_if_pruned(&self, data: PrunedData, version: Version) -> Result<()> {
+        match self.pruner.as_ref() {
+            Some(pruner) if version < pruner.least_readable_version(data) => {
+                Err(LibraDbError::Pruned(format!("{} at version {}", data.name(), version)).into())
+            }
+            _ => Ok(()),
+        }
+    }
+
     fn wake_pruner(&self, latest_version: Version) {
         if let Some(pruner) = self.pruner.as_ref() {
             pruner.wake(latest_version)
         }
     }
+
+    fn wake_migrator(&self, latest_version: Version) {
+        if let Some(migrator) = self.migrator.as_ref() {
+            migrator.wake(latest_version)
+        }
+    }
 }
 
 impl DbReader for LibraDB {
@@ -536,44 +1055,59 @@ impl DbReader for LibraDB {
         ledger_version: Version,
         fetch_events: bool,
     ) -> Result<TransactionListWithProof> {
-        error_if_too_many_requested(limit, MAX_LIMIT)?;
-
-        if start_version > ledger_version || limit == 0 {
-            return Ok(TransactionListWithProof::new_empty());
-        }
+        self.monitor_read(
+            "get_transactions",
+            || {
+                format!(
+                    "start_version: {}, limit: {}, ledger_version: {}, fetch_events: {}",
+                    start_version, limit, ledger_version, fetch_events
+                )
+            },
+            || {
+                error_if_too_many_requested(limit, MAX_LIMIT)?;
+
+                if start_version > ledger_version || limit == 0 {
+                    return Ok(TransactionListWithProof::new_empty());
+                }
 
-        let limit = std::cmp::min(limit, ledger_version - start_version + 1);
+                let limit = std::cmp::min(limit, ledger_version - start_version + 1);
+                self.error_if_pruned(PrunedData::Transactions, start_version)?;
+                if fetch_events {
+                    self.error_if_pruned(PrunedData::Events, start_version)?;
+                }
 
-        let txns = (start_version..start_version + limit)
-            .map(|version| Ok(self.transaction_store.get_transaction(version)?))
-            .collect::<Result<Vec<_>>>()?;
-        let txn_infos = (start_version..start_version + limit)
-            .map(|version| Ok(self.ledger_store.get_transaction_info(version)?))
-            .collect::<Result<Vec<_>>>()?;
-        let events = if fetch_events {
-            Some(
-                (start_version..start_version + limit)
-                    .map(|version| Ok(self.event_store.get_events_by_version(version)?))
-                    .collect::<Result<Vec<_>>>()?,
-            )
-        } else {
-            None
-        };
-        let proof = TransactionListProof::new(
-            self.ledger_store.get_transaction_range_proof(
-                Some(start_version),
-                limit,
-                ledger_version,
-            )?,
-            txn_infos,
-        );
+                let txns = (start_version..start_version + limit)
+                    .map(|version| Ok(self.transaction_store.get_transaction(version)?))
+                    .collect::<Result<Vec<_>>>()?;
+                let txn_infos = (start_version..start_version + limit)
+                    .map(|version| Ok(self.ledger_store.get_transaction_info(version)?))
+                    .collect::<Result<Vec<_>>>()?;
+                let events = if fetch_events {
+                    Some(
+                        (start_version..start_version + limit)
+                            .map(|version| Ok(self.event_store.get_events_by_version(version)?))
+                            .collect::<Result<Vec<_>>>()?,
+                    )
+                } else {
+                    None
+                };
+                let proof = TransactionListProof::new(
+                    self.ledger_store.get_transaction_range_proof(
+                        Some(start_version),
+                        limit,
+                        ledger_version,
+                    )?,
+                    txn_infos,
+                );
 
-        Ok(TransactionListWithProof::new(
-            txns,
-            events,
-            Some(start_version),
-            proof,
-        ))
+                Ok(TransactionListWithProof::new(
+                    txns,
+                    events,
+                    Some(start_version),
+                    proof,
+                ))
+            },
+        )
     }
 
     fn get_events(
@@ -583,17 +1117,57 @@ impl DbReader for LibraDB {
         ascending: bool,
         limit: u64,
     ) -> Result<Vec<(u64, ContractEvent)>> {
-        let version = self
-            .ledger_store
-            .get_latest_ledger_info()?
-            .ledger_info()
-            .version();
-        let events = self
-            .get_events_by_event_key(event_key, start, ascending, limit, version)?
-            .into_iter()
-            .map(|e| (e.transaction_version, e.event))
-            .collect();
-        Ok(events)
+        self.monitor_read(
+            "get_events",
+            || {
+                format!(
+                    "event_key: {}, start: {}, ascending: {}, limit: {}",
+                    event_key, start, ascending, limit
+                )
+            },
+            || {
+                let version = self
+                    .ledger_store
+                    .get_latest_ledger_info()?
+                    .ledger_info()
+                    .version();
+                let events = self
+                    .get_events_by_event_key(event_key, start, ascending, limit, version)?
+                    .into_iter()
+                    .map(|e| (e.transaction_version, e.event))
+                    .collect();
+                Ok(events)
+            },
+        )
+    }
+
+    /// Gets the events of the stream with `event_key`, with proofs towards `ledger_version`, in
+    /// order and starting from sequence number `start`.
+    fn get_events_with_proofs(
+        &self,
+        event_key: &EventKey,
+        start: u64,
+        limit: u64,
+        ledger_version: Version,
+    ) -> Result<Vec<EventWithProof>> {
+        self.monitor_read(
+            "get_events_with_proofs",
+            || {
+                format!(
+                    "event_key: {}, start: {}, limit: {}, ledger_version: {}",
+                    event_key, start, limit, ledger_version
+                )
+            },
+            || {
+                self.get_events_by_event_key(
+                    event_key,
+                    start,
+                    true, /* ascending */
+                    limit,
+                    ledger_version,
+                )
+            },
+        )
     }
 
     /// Gets ledger info at specified version and ensures it's an epoch change.
@@ -646,31 +1220,42 @@ impl DbReader for LibraDB {
         version: Version,
         ledger_version: Version,
     ) -> Result<AccountStateWithProof> {
-        ensure!(
-            version <= ledger_version,
-            "The queried version {} should be equal to or older than ledger version {}.",
-            version,
-            ledger_version
-        );
-        let latest_version = self.get_latest_version()?;
-        ensure!(
+        self.monitor_read(
+            "get_account_state_with_proof",
+            || {
+                format!(
+                    "address: {}, version: {}, ledger_version: {}",
+                    address, version, ledger_version
+                )
+            },
+            || {
+                ensure!(
+                    version <= ledger_version,
+                    "The queried version {} should be equal to or older than ledger version {}.",
+                    version,
+                    ledger_version
+                );
+                let latest_version = self.get_latest_version()?;
+                ensure!(
             ledger_version <= latest_version,
             "The ledger version {} is greater than the latest version currently in ledger: {}",
             ledger_version,
             latest_version
         );
 
-        let txn_info_with_proof = self
-            .ledger_store
-            .get_transaction_info_with_proof(version, ledger_version)?;
-        let (account_state_blob, sparse_merkle_proof) = self
-            .state_store
-            .get_account_state_with_proof_by_version(address, version)?;
-        Ok(AccountStateWithProof::new(
-            version,
-            account_state_blob,
-            AccountStateProof::new(txn_info_with_proof, sparse_merkle_proof),
-        ))
+                let txn_info_with_proof = self
+                    .ledger_store
+                    .get_transaction_info_with_proof(version, ledger_version)?;
+                let (account_state_blob, sparse_merkle_proof) = self
+                    .state_store
+                    .get_account_state_with_proof_by_version(address, version)?;
+                Ok(AccountStateWithProof::new(
+                    version,
+                    account_state_blob,
+                    AccountStateProof::new(txn_info_with_proof, sparse_merkle_proof),
+                ))
+            },
+        )
     }
 
     fn get_startup_info(&self) -> Result<Option<StartupInfo>> {
@@ -686,6 +1271,34 @@ impl DbReader for LibraDB {
             .get_account_state_with_proof_by_version(address, version)
     }
 
+    /// Gets the account states of many accounts at `version` walking the state tree down once for
+    /// all of them, reading the nodes each level of the tree with one multi-get, rather than
+    /// looking each account up on its own.
+    fn get_account_states_batch(
+        &self,
+        addresses: &[AccountAddress],
+        version: Version,
+    ) -> Result<Vec<Option<AccountStateBlob>>> {
+        self.monitor_read(
+            "get_account_states_batch",
+            || format!("num_addresses: {}, version: {}", addresses.len(), version),
+            || {
+                let latest_version = self.get_latest_version()?;
+                ensure!(
+                    version <= latest_version,
+                    "The queried version {} is greater than the latest version currently in \
+                     ledger: {}",
+                    version,
+                    latest_version
+                );
+                self.error_if_pruned(PrunedData::State, version)?;
+
+                self.state_store
+                    .get_account_states_by_version(addresses, version)
+            },
+        )
+    }
+
     fn get_latest_state_root(&self) -> Result<(Version, HashValue)> {
         let (version, txn_info) = self.ledger_store.get_latest_transaction_info()?;
         Ok((version, txn_info.state_root_hash()))
@@ -767,14 +1380,16 @@ impl DbWriter for LibraDB {
 
         // Persist.
         let (sealed_cs, counters) = self.seal_change_set(first_version, num_txns, cs)?;
-        self.commit(sealed_cs)?;
+        // Epoch changes are synced right away, since waypoints are taken from them.
+        let ends_epoch = ledger_info_with_sigs.map_or(false, |x| x.ledger_info().ends_epoch());
+        self.commit(sealed_cs, ends_epoch)?;
         // Once everything is successfully persisted, update the latest in-memory ledger info.
         if let Some(x) = ledger_info_with_sigs {
             self.ledger_store.set_latest_ledger_info(x.clone());
         }
 
         // Only increment counter if commit succeeds and there are at least one transaction written
-        // to the storage. That's also when we'd inform the pruner thread to work.
+        // to the storage. That's also when we'd inform the pruner and migrator threads to work.
         if num_txns > 0 {
             let last_version = first_version + num_txns - 1;
             OP_COUNTER.inc_by("committed_txns", num_txns as usize);
@@ -786,6 +1401,7 @@ impl DbWriter for LibraDB {
                 .bump_op_counters();
 
             self.wake_pruner(last_version);
+            self.wake_migrator(last_version);
         }
 
         Ok(())
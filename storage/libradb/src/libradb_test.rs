@@ -140,6 +140,312 @@ fn test_sync_transactions_impl(input: Vec<(Vec<TransactionToCommit>, LedgerInfoW
     }
 }
 
+fn test_group_commit_impl(input: Vec<(Vec<TransactionToCommit>, LedgerInfoWithSignatures)>) {
+    let tmp_dir = TempPath::new();
+    {
+        let db = LibraDB::new_for_test(&tmp_dir);
+        db.set_commit_interval(Some(Duration::from_secs(3600)));
+
+        // Commits are readable right away, synced or not.
+        let mut cur_ver = 0;
+        for (txns_to_commit, ledger_info_with_sigs) in &input {
+            db.save_transactions(&txns_to_commit, cur_ver, Some(ledger_info_with_sigs))
+                .unwrap();
+            verify_committed_transactions(
+                &db,
+                &txns_to_commit,
+                cur_ver,
+                &ledger_info_with_sigs,
+                true, /* is_latest */
+            );
+            cur_ver += txns_to_commit.len() as u64;
+        }
+        // What's left unsynced is synced in the background once the interval passes.
+        db.set_commit_interval(Some(Duration::from_millis(10)));
+        let deadline = Instant::now() + Duration::from_secs(10);
+        while db.commit_syncer.has_unsynced_commits() && Instant::now() < deadline {
+            std::thread::sleep(Duration::from_millis(1));
+        }
+        assert!(!db.commit_syncer.has_unsynced_commits());
+        db.sync_commits().unwrap();
+    }
+
+    let db = LibraDB::new_for_test(&tmp_dir);
+    let num_batches = input.len();
+    let mut cur_ver = 0;
+    for (batch_idx, (txns_to_commit, ledger_info_with_sigs)) in input.iter().enumerate() {
+        verify_committed_transactions(
+            &db,
+            &txns_to_commit,
+            cur_ver,
+            &ledger_info_with_sigs,
+            batch_idx + 1 == num_batches, /* is_latest */
+        );
+        cur_ver += txns_to_commit.len() as u64;
+    }
+}
+
+fn test_secondary_catch_up_impl(input: Vec<(Vec<TransactionToCommit>, LedgerInfoWithSignatures)>) {
+    let tmp_dir = TempPath::new();
+    let db = LibraDB::new_for_test(&tmp_dir);
+
+    let (first_txns, first_ledger_info) = input.first().unwrap();
+    db.save_transactions(
+        &first_txns,
+        0, /* first_version */
+        Some(first_ledger_info),
+    )
+    .unwrap();
+    let secondary = LibraDBSecondary::open(&tmp_dir).unwrap();
+    assert_eq!(
+        secondary.get_latest_ledger_info().unwrap(),
+        *first_ledger_info
+    );
+
+    let mut cur_ver = first_txns.len() as u64;
+    for (txns_to_commit, ledger_info_with_sigs) in input.iter().skip(1) {
+        db.save_transactions(&txns_to_commit, cur_ver, Some(ledger_info_with_sigs))
+            .unwrap();
+        cur_ver += txns_to_commit.len() as u64;
+    }
+    // Not seen by the secondary until it catches up.
+    assert_eq!(
+        secondary.get_latest_ledger_info().unwrap(),
+        *first_ledger_info
+    );
+
+    secondary.try_catch_up_with_primary().unwrap();
+    let (last_txns, last_ledger_info) = input.last().unwrap();
+    assert_eq!(
+        secondary.get_latest_ledger_info().unwrap(),
+        *last_ledger_info
+    );
+    verify_committed_transactions(
+        &secondary.db(),
+        &last_txns,
+        cur_ver - last_txns.len() as u64,
+        last_ledger_info,
+        true, /* is_latest */
+    );
+
+    // The secondary is read-only.
+    assert!(secondary
+        .db()
+        .db
+        .put::<JellyfishMerkleNodeSchema>(&NodeKey::new_empty_path(0), &Node::new_null())
+        .is_err());
+}
+
+fn test_iterators_impl(input: Vec<(Vec<TransactionToCommit>, LedgerInfoWithSignatures)>) {
+    let tmp_dir = TempPath::new();
+    let db = LibraDB::new_for_test(&tmp_dir);
+
+    let mut cur_ver = 0;
+    for (txns_to_commit, ledger_info_with_sigs) in &input {
+        db.save_transactions(&txns_to_commit, cur_ver, Some(ledger_info_with_sigs))
+            .unwrap();
+        cur_ver += txns_to_commit.len() as u64;
+    }
+    let ledger_info = input.last().unwrap().1.ledger_info();
+    let ledger_version = ledger_info.version();
+    let all_txns: Vec<_> = input
+        .iter()
+        .flat_map(|(txns_to_commit, _)| txns_to_commit.iter().cloned())
+        .collect();
+
+    // Transactions, with the range clipped to the ledger version.
+    let items = db
+        .iter_transactions(0..ledger_version + 10, ledger_version, true, true)
+        .unwrap()
+        .collect::<Result<Vec<_>>>()
+        .unwrap();
+    assert_eq!(items.len(), all_txns.len());
+    for (item, txn_to_commit) in itertools::zip_eq(&items, &all_txns) {
+        assert_eq!(&item.transaction, txn_to_commit.transaction());
+        assert_eq!(item.events.as_ref().unwrap(), txn_to_commit.events());
+        item.proof
+            .as_ref()
+            .unwrap()
+            .verify(
+                ledger_info.transaction_accumulator_hash(),
+                item.info.hash(),
+                item.version,
+            )
+            .unwrap();
+    }
+    let items = db
+        .iter_transactions(1..2, ledger_version, false, false)
+        .unwrap()
+        .collect::<Result<Vec<_>>>()
+        .unwrap();
+    assert_eq!(items.len(), std::cmp::min(all_txns.len() - 1, 1));
+    assert!(items
+        .iter()
+        .all(|item| item.events.is_none() && item.proof.is_none()));
+
+    // Events, for each event key.
+    for (event_key, events) in group_events_by_event_key(&all_txns) {
+        let first_seq = events.first().unwrap().sequence_number();
+        let items = db
+            .iter_events_by_key(&event_key, first_seq, ledger_version, true)
+            .unwrap()
+            .collect::<Result<Vec<_>>>()
+            .unwrap();
+        assert_eq!(
+            items
+                .iter()
+                .map(|item| item.event.clone())
+                .collect::<Vec<_>>(),
+            events
+        );
+        for item in &items {
+            item.proof
+                .as_ref()
+                .unwrap()
+                .verify(
+                    ledger_info,
+                    item.event.hash(),
+                    item.transaction_version,
+                    item.event_index,
+                )
+                .unwrap();
+        }
+        let num_items = db
+            .iter_events_by_key(&event_key, first_seq + 1, ledger_version, false)
+            .unwrap()
+            .count();
+        assert_eq!(num_items, events.len() - 1);
+    }
+
+    // Account states, as of the latest writes to each account.
+    let mut expected_states = HashMap::new();
+    for txn_to_commit in &all_txns {
+        for (address, blob) in txn_to_commit.account_states() {
+            expected_states.insert(address.hash(), blob.clone());
+        }
+    }
+    let root_hash = db.state_store.get_root_hash(ledger_version).unwrap();
+    let items = db
+        .iter_account_states_at_version(ledger_version, true)
+        .unwrap()
+        .collect::<Result<Vec<_>>>()
+        .unwrap();
+    assert_eq!(items.len(), expected_states.len());
+    for item in &items {
+        assert_eq!(&item.blob, expected_states.get(&item.key).unwrap());
+        item.proof
+            .as_ref()
+            .unwrap()
+            .verify(root_hash, item.key, Some(&item.blob))
+            .unwrap();
+    }
+}
+
+fn test_account_states_batch_impl(
+    input: Vec<(Vec<TransactionToCommit>, LedgerInfoWithSignatures)>,
+) {
+    let tmp_dir = TempPath::new();
+    let db = LibraDB::new_for_test(&tmp_dir);
+
+    let mut cur_ver = 0;
+    for (txns_to_commit, ledger_info_with_sigs) in &input {
+        db.save_transactions(&txns_to_commit, cur_ver, Some(ledger_info_with_sigs))
+            .unwrap();
+        cur_ver += txns_to_commit.len() as u64;
+    }
+    let ledger_version = input.last().unwrap().1.ledger_info().version();
+
+    let mut addresses: Vec<AccountAddress> = input
+        .iter()
+        .flat_map(|(txns_to_commit, _)| txns_to_commit.iter())
+        .flat_map(|txn_to_commit| txn_to_commit.account_states().keys().cloned())
+        .collect();
+    addresses.push(AccountAddress::random());
+    for version in 0..=ledger_version {
+        let blobs = db.get_account_states_batch(&addresses, version).unwrap();
+        assert_eq!(blobs.len(), addresses.len());
+        for (address, blob) in addresses.iter().zip(blobs) {
+            assert_eq!(
+                blob,
+                db.get_account_state_with_proof_by_version(*address, version)
+                    .unwrap()
+                    .0
+            );
+        }
+    }
+    assert!(db
+        .get_account_states_batch(&addresses, ledger_version + 1)
+        .is_err());
+}
+
+fn test_events_by_type_impl(input: Vec<(Vec<TransactionToCommit>, LedgerInfoWithSignatures)>) {
+    let tmp_dir = TempPath::new();
+    let db = LibraDB::new_for_test(&tmp_dir);
+
+    let mut expected: HashMap<(AccountAddress, TypeTag), Vec<(Version, u64, ContractEvent)>> =
+        HashMap::new();
+    let mut cur_ver = 0;
+    for (txns_to_commit, ledger_info_with_sigs) in &input {
+        db.save_transactions(&txns_to_commit, cur_ver, Some(ledger_info_with_sigs))
+            .unwrap();
+        for txn_to_commit in txns_to_commit {
+            for (idx, event) in txn_to_commit.events().iter().enumerate() {
+                expected
+                    .entry((event.key().get_creator_address(), event.type_tag().clone()))
+                    .or_default()
+                    .push((cur_ver, idx as u64, event.clone()));
+            }
+            cur_ver += 1;
+        }
+    }
+    let ledger_info = input.last().unwrap().1.ledger_info();
+    let ledger_version = ledger_info.version();
+
+    for ((address, type_tag), events) in expected {
+        let events_with_proof = db
+            .get_events_by_type(address, &type_tag, 0, MAX_LIMIT, ledger_version)
+            .unwrap();
+        assert_eq!(events_with_proof.len(), events.len());
+        for (event_with_proof, (ver, idx, event)) in events_with_proof.iter().zip(&events) {
+            assert_eq!(event_with_proof.event, *event);
+            event_with_proof
+                .verify(
+                    ledger_info,
+                    event.key(),
+                    event.sequence_number(),
+                    *ver,
+                    *idx,
+                )
+                .unwrap();
+        }
+
+        // Starting from a later version, and bounded by the ledger version.
+        let (mid_ver, _, _) = events[events.len() / 2];
+        let versions: Vec<_> = db
+            .get_events_by_type(address, &type_tag, mid_ver, MAX_LIMIT, mid_ver)
+            .unwrap()
+            .into_iter()
+            .map(|e| (e.transaction_version, e.event_index))
+            .collect();
+        let expected_versions: Vec<_> = events
+            .iter()
+            .filter(|(ver, _, _)| *ver == mid_ver)
+            .map(|(ver, idx, _)| (*ver, *idx))
+            .collect();
+        assert_eq!(versions, expected_versions);
+    }
+    assert!(db
+        .get_events_by_type(
+            AccountAddress::random(),
+            &TypeTag::Bool,
+            0,
+            MAX_LIMIT,
+            ledger_version
+        )
+        .unwrap()
+        .is_empty());
+}
+
 fn get_events_by_event_key(
     db: &LibraDB,
     ledger_info: &LedgerInfo,
@@ -369,6 +675,31 @@ proptest! {
     fn test_sync_transactions(input in arb_blocks_to_commit()) {
         test_sync_transactions_impl(input);
     }
+
+    #[test]
+    fn test_group_commit(input in arb_blocks_to_commit()) {
+        test_group_commit_impl(input);
+    }
+
+    #[test]
+    fn test_secondary_catch_up(input in arb_blocks_to_commit()) {
+        test_secondary_catch_up_impl(input);
+    }
+
+    #[test]
+    fn test_iterators(input in arb_blocks_to_commit()) {
+        test_iterators_impl(input);
+    }
+
+    #[test]
+    fn test_account_states_batch(input in arb_blocks_to_commit()) {
+        test_account_states_batch_impl(input);
+    }
+
+    #[test]
+    fn test_events_by_type(input in arb_blocks_to_commit()) {
+        test_events_by_type_impl(input);
+    }
 }
 
 #[test]
@@ -2,19 +2,35 @@
 // SPDX-License-Identifier: Apache-2.0
 
 //! This module provides `Pruner` which manages a thread pruning old data in the background and is
-//! meant to be triggered by other threads as they commit new data to the DB.
+//! meant to be triggered by other threads as they commit new data to the DB. State, events and
+//! transactions are each kept for their own retention window, which can be adjusted at runtime
+//! through a `PrunerHandle`. The pruning progress is saved along with each batch pruned, so that
+//! what's pruned is still known after the DB is reopened.
 
 use crate::{
     schema::{
-        jellyfish_merkle_node::JellyfishMerkleNodeSchema, stale_node_index::StaleNodeIndexSchema,
+        event::EventSchema,
+        event_accumulator::EventAccumulatorSchema,
+        event_by_key::EventByKeySchema,
+        event_by_type::{hash_type_tag, EventByTypeSchema},
+        jellyfish_merkle_node::JellyfishMerkleNodeSchema,
+        pruner_progress::{PrunerProgressSchema, PrunerProgressTag},
+        stale_node_index::StaleNodeIndexSchema,
+        transaction::TransactionSchema,
+        transaction_by_account::TransactionByAccountSchema,
+        transaction_info::TransactionInfoSchema,
     },
-    OP_COUNTER,
+    LIBRA_STORAGE_PRUNER_LEAST_READABLE_VERSION, LIBRA_STORAGE_PRUNER_PRUNED_VERSIONS,
+    LIBRA_STORAGE_PRUNER_RECLAIMED_BYTES, OP_COUNTER,
 };
 use anyhow::Result;
 use jellyfish_merkle::StaleNodeIndex;
 use libra_logger::prelude::*;
-use libra_types::transaction::Version;
-use schemadb::{ReadOptions, SchemaBatch, SchemaIterator, DB};
+use libra_types::transaction::{Transaction, Version};
+use schemadb::{
+    schema::{KeyCodec, Schema, ValueCodec},
+    ReadOptions, SchemaBatch, SchemaIterator, DB,
+};
 #[cfg(test)]
 use std::thread::sleep;
 use std::{
@@ -28,81 +44,257 @@ use std::{
     time::{Duration, Instant},
 };
 
+/// Retention windows of the pruner. Other than the latest version, each is how many historical
+/// versions of a kind of data to keep being readable. For example, 0 means keep only the latest
+/// version, and `None` means never prune that kind of data.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct PrunerConfig {
+    pub state_prune_window: Option<u64>,
+    pub event_prune_window: Option<u64>,
+    pub transaction_prune_window: Option<u64>,
+}
+
+impl PrunerConfig {
+    pub fn prune_window(&self, data: PrunedData) -> Option<u64> {
+        match data {
+            PrunedData::State => self.state_prune_window,
+            PrunedData::Events => self.event_prune_window,
+            PrunedData::Transactions => self.transaction_prune_window,
+        }
+    }
+}
+
+/// The kinds of data pruned independently of each other.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum PrunedData {
+    /// Account state, i.e. the stale nodes of the Jellyfish Merkle tree.
+    State,
+    /// Events along with their indices and accumulators.
+    Events,
+    /// Transactions along with the by-account index. Their `TransactionInfo`s are kept until the
+    /// state and events are pruned past them as well, since the proofs of those need them, and
+    /// the transaction accumulator is kept for good, since proofs of later transactions need it.
+    Transactions,
+}
+
+impl PrunedData {
+    const ALL: [PrunedData; 3] = [Self::State, Self::Events, Self::Transactions];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::State => "state",
+            Self::Events => "events",
+            Self::Transactions => "transactions",
+        }
+    }
+
+    pub(crate) fn progress_tag(self) -> PrunerProgressTag {
+        match self {
+            Self::State => PrunerProgressTag::State,
+            Self::Events => PrunerProgressTag::Events,
+            Self::Transactions => PrunerProgressTag::Transactions,
+        }
+    }
+}
+
+/// Versions before the returned one are pruned of what `tag` is the progress of, as saved in `db`.
+pub(crate) fn get_least_readable_version(db: &DB, tag: PrunerProgressTag) -> Result<Version> {
+    Ok(db.get::<PrunerProgressSchema>(&tag)?.unwrap_or(0))
+}
+
+/// Shared between the `Pruner`, its worker thread and `PrunerHandle`s, indexed by `PrunedData`.
+struct PrunerState {
+    /// Retention windows, with `u64::max_value()` standing for no pruning.
+    prune_windows: [AtomicU64; 3],
+    /// The pruning progress. If it's `V` for a kind of data, versions of it before `V` can no
+    /// longer be accessed.
+    least_readable_versions: [AtomicU64; 3],
+    /// Like `least_readable_versions`, for the `TransactionInfo`s.
+    least_readable_transaction_info_version: AtomicU64,
+}
+
+impl PrunerState {
+    fn new(config: PrunerConfig) -> Self {
+        let state = Self {
+            prune_windows: Default::default(),
+            least_readable_versions: Default::default(),
+            least_readable_transaction_info_version: AtomicU64::new(0),
+        };
+        state.set_config(config);
+        state
+    }
+
+    /// Picks up the pruning progress saved in `db`.
+    fn load(db: &DB, config: PrunerConfig) -> Result<Self> {
+        let state = Self::new(config);
+        for data in PrunedData::ALL.iter() {
+            state.least_readable_versions[*data as usize].store(
+                get_least_readable_version(db, data.progress_tag())?,
+                Ordering::Relaxed,
+            );
+        }
+        state.least_readable_transaction_info_version.store(
+            get_least_readable_version(db, PrunerProgressTag::TransactionInfos)?,
+            Ordering::Relaxed,
+        );
+        Ok(state)
+    }
+
+    fn config(&self) -> PrunerConfig {
+        let prune_window = |data: PrunedData| {
+            let window = self.prune_windows[data as usize].load(Ordering::Relaxed);
+            if window == u64::max_value() {
+                None
+            } else {
+                Some(window)
+            }
+        };
+        PrunerConfig {
+            state_prune_window: prune_window(PrunedData::State),
+            event_prune_window: prune_window(PrunedData::Events),
+            transaction_prune_window: prune_window(PrunedData::Transactions),
+        }
+    }
+
+    fn set_config(&self, config: PrunerConfig) {
+        for data in PrunedData::ALL.iter() {
+            self.prune_windows[*data as usize].store(
+                config.prune_window(*data).unwrap_or_else(u64::max_value),
+                Ordering::Relaxed,
+            );
+        }
+    }
+
+    /// Least readable version `data` should be pruned to, given the latest version.
+    fn target_least_readable_version(&self, data: PrunedData, latest_version: Version) -> Version {
+        let prune_window = self.prune_windows[data as usize].load(Ordering::Relaxed);
+        if latest_version > prune_window {
+            latest_version - prune_window
+        } else {
+            0
+        }
+    }
+
+    fn least_readable_version(&self, data: PrunedData) -> Version {
+        self.least_readable_versions[data as usize].load(Ordering::Relaxed)
+    }
+
+    /// `TransactionInfo`s are pruned only as far as all the other data is.
+    fn target_least_readable_transaction_info_version(&self) -> Version {
+        PrunedData::ALL
+            .iter()
+            .map(|data| self.least_readable_version(*data))
+            .min()
+            .expect("Not empty.")
+    }
+
+    fn least_readable_transaction_info_version(&self) -> Version {
+        self.least_readable_transaction_info_version
+            .load(Ordering::Relaxed)
+    }
+}
+
+/// Adjusts the retention windows of a running `Pruner` and tells its progress. Cheap to clone.
+#[derive(Clone)]
+pub struct PrunerHandle {
+    state: Arc<PrunerState>,
+}
+
+impl PrunerHandle {
+    pub fn config(&self) -> PrunerConfig {
+        self.state.config()
+    }
+
+    /// Takes effect as new data is committed. Widening a window doesn't bring back what's already
+    /// been pruned, it only holds off the pruner until the new window is filled.
+    pub fn set_config(&self, config: PrunerConfig) {
+        self.state.set_config(config)
+    }
+
+    /// Versions of `data` before the returned one are pruned.
+    pub fn least_readable_version(&self, data: PrunedData) -> Version {
+        self.state.least_readable_version(data)
+    }
+}
+
 /// The `Pruner` is meant to be part of a `LibraDB` instance and runs in the background to prune old
 /// data.
 ///
 /// It creates a worker thread on construction and joins it on destruction. When destructed, it
 /// quits the worker thread eagerly without waiting for all pending work to be done.
 pub(crate) struct Pruner {
-    /// Other than the latest version, how many historical versions to keep being readable. For
-    /// example, this being 0 means keep only the latest version.
-    historical_versions_to_keep: u64,
+    /// Retention windows and pruning progress, shared with the worker thread and handles.
+    state: Arc<PrunerState>,
     /// The worker thread handle, created upon Pruner instance construction and joined upon its
     /// destruction. It only becomes `None` after joined in `drop()`.
     worker_thread: Option<JoinHandle<()>>,
     /// The sender side of the channel talking to the worker thread.
     command_sender: Mutex<Sender<Command>>,
-    /// (For tests) A way for the worker thread to inform the `Pruner` the pruning progress. If it
-    /// sets this atomic value to `V`, all versions before `V` can no longer be accessed.
-    #[allow(dead_code)]
-    worker_progress: Arc<AtomicU64>,
 }
 
 impl Pruner {
-    /// Creates a worker thread that waits on a channel for pruning commands.
-    pub fn new(db: Arc<DB>, historical_versions_to_keep: u64) -> Self {
+    /// Creates a worker thread that waits on a channel for pruning commands, picking up the
+    /// pruning progress saved in `db`.
+    pub fn new(db: Arc<DB>, config: PrunerConfig) -> Result<Self> {
         let (command_sender, command_receiver) = channel();
-        let worker_progress = Arc::new(AtomicU64::new(0));
-        let worker_progress_clone = Arc::clone(&worker_progress);
+        let state = Arc::new(PrunerState::load(&db, config)?);
+        let state_clone = Arc::clone(&state);
 
         let worker_thread = std::thread::Builder::new()
             .name("libradb_pruner".into())
-            .spawn(move || Worker::new(db, command_receiver, worker_progress_clone).work_loop())
+            .spawn(move || Worker::new(db, command_receiver, state_clone).work_loop())
             .expect("Creating pruner thread should succeed.");
 
-        Self {
-            historical_versions_to_keep,
+        Ok(Self {
+            state,
             worker_thread: Some(worker_thread),
             command_sender: Mutex::new(command_sender),
-            worker_progress,
+        })
+    }
+
+    pub fn handle(&self) -> PrunerHandle {
+        PrunerHandle {
+            state: Arc::clone(&self.state),
         }
     }
 
-    /// Sends pruning command to the worker thread when necessary.
+    pub fn least_readable_version(&self, data: PrunedData) -> Version {
+        self.state.least_readable_version(data)
+    }
+
+    /// Sends pruning command to the worker thread, which works out what's to be pruned.
     pub fn wake(&self, latest_version: Version) {
-        if latest_version > self.historical_versions_to_keep {
-            let least_readable_version = latest_version - self.historical_versions_to_keep;
-            self.command_sender
-                .lock()
-                .expect("command_sender to pruner thread should lock.")
-                .send(Command::Prune {
-                    least_readable_version,
-                })
-                .expect("Receiver should not destruct prematurely.");
-        }
+        self.command_sender
+            .lock()
+            .expect("command_sender to pruner thread should lock.")
+            .send(Command::Prune { latest_version })
+            .expect("Receiver should not destruct prematurely.");
     }
 
     /// (For tests only.) Notifies the worker thread and waits for it to finish its job by polling
-    /// an internal counter.
+    /// the pruning progress.
     #[cfg(test)]
     pub fn wake_and_wait(&self, latest_version: Version) -> Result<()> {
         self.wake(latest_version);
 
-        if latest_version > self.historical_versions_to_keep {
-            let least_readable_version = latest_version - self.historical_versions_to_keep;
-            // Assuming no big pruning chunks will be issued by a test.
-            const TIMEOUT: Duration = Duration::from_secs(10);
-            let end = Instant::now() + TIMEOUT;
-
-            while Instant::now() < end {
-                if self.worker_progress.load(Ordering::Relaxed) >= least_readable_version {
-                    return Ok(());
-                }
-                sleep(Duration::from_millis(1));
+        // Assuming no big pruning chunks will be issued by a test.
+        const TIMEOUT: Duration = Duration::from_secs(10);
+        let end = Instant::now() + TIMEOUT;
+
+        while Instant::now() < end {
+            if PrunedData::ALL.iter().all(|data| {
+                self.state.least_readable_version(*data)
+                    >= self
+                        .state
+                        .target_least_readable_version(*data, latest_version)
+            }) && self.state.least_readable_transaction_info_version()
+                >= self.state.target_least_readable_transaction_info_version()
+            {
+                return Ok(());
             }
-            anyhow::bail!("Timeout waiting for pruner worker.");
+            sleep(Duration::from_millis(1));
         }
-        Ok(())
+        anyhow::bail!("Timeout waiting for pruner worker.");
     }
 }
 
@@ -123,17 +315,17 @@ impl Drop for Pruner {
 
 enum Command {
     Quit,
-    Prune { least_readable_version: Version },
+    Prune { latest_version: Version },
 }
 
 struct Worker {
     db: Arc<DB>,
     command_receiver: Receiver<Command>,
-    target_least_readable_version: Version,
-    /// Keeps a record of the pruning progress. If this equals to version `V`, we know versions
-    /// smaller than `V` are no longer readable.
-    /// This being an atomic value is to communicate the info with the Pruner thread (for tests).
-    least_readable_version: Arc<AtomicU64>,
+    /// Retention windows to prune by, and where the pruning progress is recorded for the `Pruner`
+    /// and handles to tell.
+    state: Arc<PrunerState>,
+    /// Latest version committed, as far as the worker's been told.
+    latest_version: Version,
     /// Indicates if there's NOT any pending work to do currently, to hint
     /// `Self::receive_commands()` to `recv()` blocking-ly.
     blocking_recv: bool,
@@ -144,16 +336,12 @@ struct Worker {
 impl Worker {
     const MAX_VERSIONS_TO_PRUNE_PER_BATCH: usize = 100;
 
-    fn new(
-        db: Arc<DB>,
-        command_receiver: Receiver<Command>,
-        least_readable_version: Arc<AtomicU64>,
-    ) -> Self {
+    fn new(db: Arc<DB>, command_receiver: Receiver<Command>, state: Arc<PrunerState>) -> Self {
         Self {
             db,
             command_receiver,
-            least_readable_version,
-            target_least_readable_version: 0,
+            state,
+            latest_version: 0,
             blocking_recv: true,
             index_min_nonpurged_version: 0,
             index_purged_at: Instant::now(),
@@ -162,38 +350,78 @@ impl Worker {
 
     fn work_loop(mut self) {
         while self.receive_commands() {
-            // Process a reasonably small batch of work before trying to receive commands again,
-            // in case `Command::Quit` is received (that's when we should quit.)
-            match prune_state(
-                Arc::clone(&self.db),
-                self.least_readable_version.load(Ordering::Relaxed),
-                self.target_least_readable_version,
-                Self::MAX_VERSIONS_TO_PRUNE_PER_BATCH,
-            ) {
-                Ok(least_readable_version) => {
-                    // Make next recv() blocking if all done.
-                    self.blocking_recv =
-                        least_readable_version == self.target_least_readable_version;
-
-                    // Log the progress.
-                    self.least_readable_version
-                        .store(least_readable_version, Ordering::Relaxed);
-                    OP_COUNTER.set(
-                        "pruner.least_readable_state_version",
-                        least_readable_version as usize,
-                    );
-
-                    // Try to purge the log.
-                    if let Err(e) = self.maybe_purge_index() {
-                        crit!("Failed purging state state node index, ignored. Err: {}", e);
+            // Process a reasonably small batch of work for each kind of data before trying to
+            // receive commands again, in case `Command::Quit` is received (that's when we should
+            // quit.)
+            let mut all_done = true;
+            for data in PrunedData::ALL.iter().copied() {
+                let least_readable_version = self.state.least_readable_version(data);
+                let target_least_readable_version = self
+                    .state
+                    .target_least_readable_version(data, self.latest_version);
+                if least_readable_version >= target_least_readable_version {
+                    continue;
+                }
+
+                match prune(
+                    data,
+                    Arc::clone(&self.db),
+                    least_readable_version,
+                    target_least_readable_version,
+                    Self::MAX_VERSIONS_TO_PRUNE_PER_BATCH,
+                ) {
+                    Ok((new_least_readable_version, reclaimed_bytes)) => {
+                        all_done &= new_least_readable_version == target_least_readable_version;
+                        self.state.least_readable_versions[data as usize]
+                            .store(new_least_readable_version, Ordering::Relaxed);
+                        log_progress(
+                            data.name(),
+                            least_readable_version,
+                            new_least_readable_version,
+                            reclaimed_bytes,
+                        );
+                    }
+                    Err(e) => {
+                        // On error, stop retrying vigorously by not counting it as pending work.
+                        crit!("Error pruning {}. {:?}", data.name(), e);
                     }
                 }
-                Err(e) => {
-                    crit!("Error pruning stale state nodes. {:?}", e);
-                    // On error, stop retrying vigorously by making next recv() blocking.
-                    self.blocking_recv = true;
+            }
+
+            let least_readable_version = self.state.least_readable_transaction_info_version();
+            let target_least_readable_version =
+                self.state.target_least_readable_transaction_info_version();
+            if least_readable_version < target_least_readable_version {
+                match prune_transaction_infos(
+                    &self.db,
+                    least_readable_version,
+                    target_least_readable_version,
+                    Self::MAX_VERSIONS_TO_PRUNE_PER_BATCH,
+                ) {
+                    Ok((new_least_readable_version, reclaimed_bytes)) => {
+                        all_done &= new_least_readable_version == target_least_readable_version;
+                        self.state
+                            .least_readable_transaction_info_version
+                            .store(new_least_readable_version, Ordering::Relaxed);
+                        log_progress(
+                            "transaction_infos",
+                            least_readable_version,
+                            new_least_readable_version,
+                            reclaimed_bytes,
+                        );
+                    }
+                    Err(e) => {
+                        crit!("Error pruning transaction_infos. {:?}", e);
+                    }
                 }
             }
+            // Make next recv() blocking if all done.
+            self.blocking_recv = all_done;
+
+            // Try to purge the log.
+            if let Err(e) = self.maybe_purge_index() {
+                crit!("Failed purging state state node index, ignored. Err: {}", e);
+            }
         }
     }
 
@@ -222,11 +450,9 @@ impl Worker {
             match command {
                 // On `Command::Quit` inform the outer loop to quit by returning `false`.
                 Command::Quit => return false,
-                Command::Prune {
-                    least_readable_version,
-                } => {
-                    if least_readable_version > self.target_least_readable_version {
-                        self.target_least_readable_version = least_readable_version;
+                Command::Prune { latest_version } => {
+                    if latest_version > self.latest_version {
+                        self.latest_version = latest_version;
                         // Switch to non-blocking to allow some work to be done after the
                         // channel has drained.
                         self.blocking_recv = false;
@@ -250,7 +476,7 @@ impl Worker {
         // this imposes at most one minute of work in vain after restarting.)
         let now = Instant::now();
         if now - self.index_purged_at > MIN_INTERVAL {
-            let least_readable_version = self.least_readable_version.load(Ordering::Relaxed);
+            let least_readable_version = self.state.least_readable_version(PrunedData::State);
 
             if least_readable_version - self.index_min_nonpurged_version + 1 > MIN_VERSIONS {
                 let new_min_non_purged_version = least_readable_version + 1;
@@ -267,6 +493,69 @@ impl Worker {
     }
 }
 
+fn log_progress(
+    name: &str,
+    least_readable_version: Version,
+    new_least_readable_version: Version,
+    reclaimed_bytes: usize,
+) {
+    OP_COUNTER.set(
+        &format!("pruner.least_readable_{}_version", name),
+        new_least_readable_version as usize,
+    );
+    LIBRA_STORAGE_PRUNER_LEAST_READABLE_VERSION
+        .with_label_values(&[name])
+        .set(new_least_readable_version as i64);
+    LIBRA_STORAGE_PRUNER_PRUNED_VERSIONS
+        .with_label_values(&[name])
+        .inc_by((new_least_readable_version - least_readable_version) as i64);
+    LIBRA_STORAGE_PRUNER_RECLAIMED_BYTES
+        .with_label_values(&[name])
+        .inc_by(reclaimed_bytes as i64);
+}
+
+/// Deletions to be written in one go along with the pruning progress, keeping count of the bytes
+/// the deleted records take as far as they are known without reading them.
+struct PruneBatch {
+    batch: SchemaBatch,
+    bytes: usize,
+}
+
+impl PruneBatch {
+    fn new() -> Self {
+        Self {
+            batch: SchemaBatch::new(),
+            bytes: 0,
+        }
+    }
+
+    /// Deletes a record read already, counting both its key and value.
+    fn delete<S: Schema>(&mut self, key: &S::Key, value: &S::Value) -> Result<()> {
+        self.bytes += <S::Value as ValueCodec<S>>::encode_value(value)?.len();
+        self.delete_key::<S>(key)
+    }
+
+    /// Deletes a record by key, counting only the key, since the value isn't worth reading.
+    fn delete_key<S: Schema>(&mut self, key: &S::Key) -> Result<()> {
+        self.bytes += <S::Key as KeyCodec<S>>::encode_key(key)?.len();
+        self.batch.delete::<S>(key)
+    }
+
+    /// Saves `least_readable_version` as the progress of `tag` along with the deletions, and
+    /// returns it with the number of bytes deleted.
+    fn write(
+        mut self,
+        db: &DB,
+        tag: PrunerProgressTag,
+        least_readable_version: Version,
+    ) -> Result<(Version, usize)> {
+        self.batch
+            .put::<PrunerProgressSchema>(&tag, &least_readable_version)?;
+        db.write_schemas(self.batch)?;
+        Ok((least_readable_version, self.bytes))
+    }
+}
+
 struct StaleNodeIndicesByVersionIterator<'a> {
     inner: Peekable<SchemaIterator<'a, StaleNodeIndexSchema>>,
     target_least_readable_version: Version,
@@ -322,35 +611,188 @@ impl<'a> Iterator for StaleNodeIndicesByVersionIterator<'a> {
     }
 }
 
+/// Prunes `data` from `least_readable_version` towards `target_least_readable_version`, by at
+/// most `max_versions` versions. Returns the new least readable version and the number of bytes
+/// deleted.
+fn prune(
+    data: PrunedData,
+    db: Arc<DB>,
+    least_readable_version: Version,
+    target_least_readable_version: Version,
+    max_versions: usize,
+) -> Result<(Version, usize)> {
+    match data {
+        PrunedData::State => prune_state(
+            db,
+            least_readable_version,
+            target_least_readable_version,
+            max_versions,
+        ),
+        PrunedData::Events => prune_events(
+            &db,
+            least_readable_version,
+            target_least_readable_version,
+            max_versions,
+        ),
+        PrunedData::Transactions => prune_transactions(
+            &db,
+            least_readable_version,
+            target_least_readable_version,
+            max_versions,
+        ),
+    }
+}
+
 pub fn prune_state(
     db: Arc<DB>,
     least_readable_version: Version,
     target_least_readable_version: Version,
     max_versions: usize,
-) -> Result<Version> {
-    let indices = StaleNodeIndicesByVersionIterator::new(
+) -> Result<(Version, usize)> {
+    let indices_by_version = StaleNodeIndicesByVersionIterator::new(
         &db,
         least_readable_version,
         target_least_readable_version,
     )?
     .take(max_versions) // Iterator<Item = Result<Vec<StaleNodeIndex>>>
-    .collect::<Result<Vec<_>>>()? // now Vec<Vec<StaleNodeIndex>>
-    .into_iter()
-    .flatten()
-    .collect::<Vec<_>>();
+    .collect::<Result<Vec<_>>>()?; // now Vec<Vec<StaleNodeIndex>>
 
-    if indices.is_empty() {
-        Ok(least_readable_version)
+    // Unless stopped by `max_versions`, nothing else is stale up to the target.
+    let new_least_readable_version = if indices_by_version.len() < max_versions {
+        target_least_readable_version
     } else {
-        let new_least_readable_version = indices.last().expect("Should exist.").stale_since_version;
-        let mut batch = SchemaBatch::new();
-        indices
-            .into_iter()
-            .map(|index| batch.delete::<JellyfishMerkleNodeSchema>(&index.node_key))
-            .collect::<Result<_>>()?;
-        db.write_schemas(batch)?;
-        Ok(new_least_readable_version)
+        indices_by_version
+            .last()
+            .and_then(|indices| indices.last())
+            .expect("Should exist.")
+            .stale_since_version
+    };
+    let mut batch = PruneBatch::new();
+    for index in indices_by_version.into_iter().flatten() {
+        batch.delete_key::<JellyfishMerkleNodeSchema>(&index.node_key)?;
+    }
+    batch.write(&db, PrunerProgressTag::State, new_least_readable_version)
+}
+
+fn prune_events(
+    db: &DB,
+    least_readable_version: Version,
+    target_least_readable_version: Version,
+    max_versions: usize,
+) -> Result<(Version, usize)> {
+    let mut new_least_readable_version = target_least_readable_version;
+    let mut batch = PruneBatch::new();
+
+    let mut iter = db.iter::<EventSchema>(ReadOptions::default())?;
+    iter.seek(&least_readable_version)?;
+    let mut num_versions = 0;
+    let mut last_version = None;
+    for res in iter {
+        let ((version, index), event) = res?;
+        if version >= target_least_readable_version {
+            break;
+        }
+        if last_version != Some(version) {
+            if num_versions == max_versions {
+                new_least_readable_version = version;
+                break;
+            }
+            num_versions += 1;
+            last_version = Some(version);
+        }
+        batch.delete::<EventByKeySchema>(
+            &(*event.key(), event.sequence_number()),
+            &(version, index),
+        )?;
+        batch.delete::<EventByTypeSchema>(
+            &(
+                event.key().get_creator_address(),
+                hash_type_tag(event.type_tag())?,
+                version,
+                index,
+            ),
+            &(),
+        )?;
+        batch.delete::<EventSchema>(&(version, index), &event)?;
+    }
+
+    let mut iter = db.iter::<EventAccumulatorSchema>(ReadOptions::default())?;
+    iter.seek(&least_readable_version)?;
+    for res in iter {
+        let (key, hash) = res?;
+        if key.0 >= new_least_readable_version {
+            break;
+        }
+        batch.delete::<EventAccumulatorSchema>(&key, &hash)?;
+    }
+
+    batch.write(db, PrunerProgressTag::Events, new_least_readable_version)
+}
+
+fn prune_transactions(
+    db: &DB,
+    least_readable_version: Version,
+    target_least_readable_version: Version,
+    max_versions: usize,
+) -> Result<(Version, usize)> {
+    let mut new_least_readable_version = target_least_readable_version;
+    let mut batch = PruneBatch::new();
+
+    let mut iter = db.iter::<TransactionSchema>(ReadOptions::default())?;
+    iter.seek(&least_readable_version)?;
+    for (num_versions, res) in iter.enumerate() {
+        let (version, txn) = res?;
+        if version >= target_least_readable_version {
+            break;
+        }
+        if num_versions == max_versions {
+            new_least_readable_version = version;
+            break;
+        }
+        if let Transaction::UserTransaction(signed_txn) = &txn {
+            batch.delete::<TransactionByAccountSchema>(
+                &(signed_txn.sender(), signed_txn.sequence_number()),
+                &version,
+            )?;
+        }
+        batch.delete::<TransactionSchema>(&version, &txn)?;
+    }
+
+    batch.write(
+        db,
+        PrunerProgressTag::Transactions,
+        new_least_readable_version,
+    )
+}
+
+fn prune_transaction_infos(
+    db: &DB,
+    least_readable_version: Version,
+    target_least_readable_version: Version,
+    max_versions: usize,
+) -> Result<(Version, usize)> {
+    let mut new_least_readable_version = target_least_readable_version;
+    let mut batch = PruneBatch::new();
+
+    let mut iter = db.iter::<TransactionInfoSchema>(ReadOptions::default())?;
+    iter.seek(&least_readable_version)?;
+    for (num_versions, res) in iter.enumerate() {
+        let (version, txn_info) = res?;
+        if version >= target_least_readable_version {
+            break;
+        }
+        if num_versions == max_versions {
+            new_least_readable_version = version;
+            break;
+        }
+        batch.delete::<TransactionInfoSchema>(&version, &txn_info)?;
     }
+
+    batch.write(
+        db,
+        PrunerProgressTag::TransactionInfos,
+        new_least_readable_version,
+    )
 }
 
 #[cfg(test)]
@@ -2,11 +2,16 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use super::*;
-use crate::{change_set::ChangeSet, state_store::StateStore, LibraDB};
+use crate::{
+    change_set::ChangeSet, errors::LibraDbError, state_store::StateStore,
+    test_helper::arb_blocks_to_commit, LibraDB,
+};
 use libra_crypto::HashValue;
+use libra_proptest_helpers::ValueGenerator;
 use libra_temppath::TempPath;
 use libra_types::{account_address::AccountAddress, account_state_blob::AccountStateBlob};
 use std::collections::HashMap;
+use storage_interface::DbReader;
 
 fn put_account_state_set(
     db: &DB,
@@ -48,8 +53,15 @@ fn test_pruner() {
 
     let tmp_dir = TempPath::new();
     let db = LibraDB::new_for_test(&tmp_dir).db;
-    let state_store = &StateStore::new(Arc::clone(&db));
-    let pruner = Pruner::new(Arc::clone(&db), 0 /* historical_versions_to_keep */);
+    let state_store = &StateStore::new(Arc::clone(&db), None /* cold_store */);
+    let pruner = Pruner::new(
+        Arc::clone(&db),
+        PrunerConfig {
+            state_prune_window: Some(0),
+            ..Default::default()
+        },
+    )
+    .unwrap();
 
     let _root0 = put_account_state_set(
         &db,
@@ -109,7 +121,7 @@ fn test_worker_quit_eagerly() {
 
     let tmp_dir = TempPath::new();
     let db = LibraDB::new_for_test(&tmp_dir).db;
-    let state_store = &StateStore::new(Arc::clone(&db));
+    let state_store = &StateStore::new(Arc::clone(&db), None /* cold_store */);
 
     let _root0 = put_account_state_set(
         &db,
@@ -135,17 +147,16 @@ fn test_worker_quit_eagerly() {
         let worker = Worker::new(
             Arc::clone(&db),
             command_receiver,
-            Arc::new(AtomicU64::new(0)), /* progress */
+            Arc::new(PrunerState::new(PrunerConfig {
+                state_prune_window: Some(0),
+                ..Default::default()
+            })),
         );
         command_sender
-            .send(Command::Prune {
-                least_readable_version: 1,
-            })
+            .send(Command::Prune { latest_version: 1 })
             .unwrap();
         command_sender
-            .send(Command::Prune {
-                least_readable_version: 2,
-            })
+            .send(Command::Prune { latest_version: 2 })
             .unwrap();
         command_sender.send(Command::Quit).unwrap();
         // Worker quits immediately although `Command::Quit` is not the first command sent.
@@ -155,3 +166,154 @@ fn test_worker_quit_eagerly() {
         verify_state_in_store(state_store, address, Some(&value2), 2);
     }
 }
+
+#[test]
+fn test_prune_events_and_transactions() {
+    let tmp_dir = TempPath::new();
+    let db = LibraDB::open(
+        &tmp_dir,
+        false, /* readonly */
+        Some(PrunerConfig {
+            event_prune_window: Some(0),
+            transaction_prune_window: Some(1),
+            ..Default::default()
+        }),
+    )
+    .unwrap();
+    let blocks = ValueGenerator::new().generate(arb_blocks_to_commit());
+    let mut num_txns = 0;
+    for (txns_to_commit, ledger_info_with_sigs) in &blocks {
+        db.save_transactions(txns_to_commit, num_txns, Some(ledger_info_with_sigs))
+            .unwrap();
+        num_txns += txns_to_commit.len() as Version;
+    }
+    let latest_version = num_txns - 1;
+    let pruner = db.pruner.as_ref().unwrap();
+    pruner.wake_and_wait(latest_version).unwrap();
+
+    let txns = blocks.iter().flat_map(|(txns, _)| txns);
+    for (version, txn_to_commit) in (0..=latest_version).zip(txns) {
+        let events = db.event_store.get_events_by_version(version).unwrap();
+        if version < latest_version {
+            assert!(events.is_empty());
+        } else {
+            assert_eq!(events, txn_to_commit.events());
+        }
+
+        let lookup_by_account =
+            txn_to_commit
+                .transaction()
+                .as_signed_user_txn()
+                .ok()
+                .map(|signed_txn| {
+                    db.transaction_store
+                        .lookup_transaction_by_account(
+                            signed_txn.sender(),
+                            signed_txn.sequence_number(),
+                            latest_version,
+                        )
+                        .unwrap()
+                });
+        if version + 1 < latest_version {
+            let err = db
+                .get_transactions(version, 1, latest_version, false)
+                .unwrap_err();
+            assert!(matches!(
+                err.downcast_ref::<LibraDbError>(),
+                Some(LibraDbError::Pruned(_))
+            ));
+            assert!(db.transaction_store.get_transaction(version).is_err());
+            // Kept for the proofs of state, which isn't pruned.
+            assert!(db.ledger_store.get_transaction_info(version).is_ok());
+            assert!(lookup_by_account.map_or(true, |found| found.is_none()));
+        } else {
+            assert_eq!(
+                db.transaction_store.get_transaction(version).unwrap(),
+                *txn_to_commit.transaction()
+            );
+            assert!(lookup_by_account.map_or(true, |found| found == Some(version)));
+        }
+    }
+    assert_eq!(
+        pruner.least_readable_version(PrunedData::Transactions),
+        latest_version.saturating_sub(1),
+    );
+    assert_eq!(pruner.least_readable_version(PrunedData::State), 0);
+}
+
+#[test]
+fn test_prune_transaction_infos_and_reopen() {
+    let tmp_dir = TempPath::new();
+    let config = PrunerConfig {
+        state_prune_window: Some(2),
+        event_prune_window: Some(0),
+        transaction_prune_window: Some(1),
+    };
+    let db = LibraDB::open(&tmp_dir, false /* readonly */, Some(config)).unwrap();
+    let blocks = ValueGenerator::new().generate(arb_blocks_to_commit());
+    let mut num_txns = 0;
+    for (txns_to_commit, ledger_info_with_sigs) in &blocks {
+        db.save_transactions(txns_to_commit, num_txns, Some(ledger_info_with_sigs))
+            .unwrap();
+        num_txns += txns_to_commit.len() as Version;
+    }
+    let latest_version = num_txns - 1;
+    db.pruner
+        .as_ref()
+        .unwrap()
+        .wake_and_wait(latest_version)
+        .unwrap();
+
+    // TransactionInfos are pruned as far as the data pruned the least, i.e. state.
+    let least_readable_version = latest_version.saturating_sub(2);
+    for version in 0..=latest_version {
+        assert_eq!(
+            db.ledger_store.get_transaction_info(version).is_ok(),
+            version >= least_readable_version,
+        );
+    }
+
+    // The progress is picked up after reopening, with or without the pruner.
+    drop(db);
+    for pruner_config in &[Some(config), None] {
+        let db = LibraDB::open(&tmp_dir, false /* readonly */, *pruner_config).unwrap();
+        assert_eq!(
+            db.least_readable_version(PrunedData::State).unwrap(),
+            least_readable_version,
+        );
+        assert_eq!(
+            db.least_readable_version(PrunedData::Transactions).unwrap(),
+            latest_version.saturating_sub(1),
+        );
+        assert_eq!(
+            db.least_readable_version(PrunedData::Events).unwrap(),
+            latest_version,
+        );
+    }
+}
+
+#[test]
+fn test_pruner_handle() {
+    let tmp_dir = TempPath::new();
+    let db = LibraDB::open(
+        &tmp_dir,
+        false, /* readonly */
+        Some(PrunerConfig::default()),
+    )
+    .unwrap();
+    let handle = db.pruner_handle().unwrap();
+    assert_eq!(handle.config(), PrunerConfig::default());
+
+    let config = PrunerConfig {
+        state_prune_window: Some(10),
+        event_prune_window: None,
+        transaction_prune_window: Some(0),
+    };
+    handle.set_config(config);
+    assert_eq!(handle.config(), config);
+    assert_eq!(db.pruner_handle().unwrap().config(), config);
+
+    // No pruner to adjust without a config.
+    let tmp_dir = TempPath::new();
+    assert!(LibraDB::new_for_test(&tmp_dir).pruner_handle().is_none());
+}
@@ -17,7 +17,7 @@ use libra_crypto::hash::HashValue;
 use libra_types::{proof::position::Position, transaction::Version};
 use schemadb::{
     define_schema,
-    schema::{KeyCodec, ValueCodec},
+    schema::{KeyCodec, SeekKeyCodec, ValueCodec},
 };
 use std::mem::size_of;
 
@@ -61,5 +61,11 @@ impl ValueCodec<EventAccumulatorSchema> for HashValue {
     }
 }
 
+impl SeekKeyCodec<EventAccumulatorSchema> for Version {
+    fn encode_seek_key(&self) -> Result<Vec<u8>> {
+        Ok(self.to_be_bytes().to_vec())
+    }
+}
+
 #[cfg(test)]
 mod test;
@@ -0,0 +1,95 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! This module defines physical storage schema for an event index via which the ContractEvents
+//! (represented by <txn_version, event_idx> tuples so that they can be fetched from
+//! `EventSchema`) of a certain type emitted on an account can be found in order of the
+//! transaction version.
+//!
+//! ```text
+//! |<--------------------key------------------->|
+//! | address | type_tag_hash | txn_ver | idx |
+//! ```
+//!
+//! `address` is that of the account creating the event key, and `type_tag_hash` is the sha3 hash
+//! of the LCS bytes of the event type, so that keys are of fixed length. Versions are serialized
+//! in big endian so that records in RocksDB will be in order of their numeric value.
+
+use crate::schema::{ensure_slice_len_eq, EVENT_BY_TYPE_CF_NAME};
+use anyhow::Result;
+use byteorder::{BigEndian, ReadBytesExt, WriteBytesExt};
+use libra_crypto::HashValue;
+use libra_types::{account_address::AccountAddress, transaction::Version};
+use move_core_types::language_storage::TypeTag;
+use schemadb::{
+    define_schema,
+    schema::{KeyCodec, SeekKeyCodec, ValueCodec},
+};
+use std::{convert::TryFrom, mem::size_of};
+
+define_schema!(EventByTypeSchema, Key, (), EVENT_BY_TYPE_CF_NAME);
+
+type Index = u64;
+type Key = (AccountAddress, HashValue, Version, Index);
+
+/// Hashes `type_tag` into what's stored in the keys of `EventByTypeSchema`.
+pub(crate) fn hash_type_tag(type_tag: &TypeTag) -> Result<HashValue> {
+    Ok(HashValue::sha3_256_of(&lcs::to_bytes(type_tag)?))
+}
+
+const PREFIX_LEN: usize = AccountAddress::LENGTH + HashValue::LENGTH;
+
+fn encode_prefix(address: &AccountAddress, type_tag_hash: &HashValue) -> Vec<u8> {
+    let mut encoded = Vec::with_capacity(PREFIX_LEN + size_of::<Version>() + size_of::<Index>());
+    encoded.extend_from_slice(address.as_ref());
+    encoded.extend_from_slice(type_tag_hash.as_ref());
+    encoded
+}
+
+impl KeyCodec<EventByTypeSchema> for Key {
+    fn encode_key(&self) -> Result<Vec<u8>> {
+        let (ref address, ref type_tag_hash, version, index) = *self;
+
+        let mut encoded = encode_prefix(address, type_tag_hash);
+        encoded.write_u64::<BigEndian>(version)?;
+        encoded.write_u64::<BigEndian>(index)?;
+
+        Ok(encoded)
+    }
+
+    fn decode_key(data: &[u8]) -> Result<Self> {
+        ensure_slice_len_eq(data, PREFIX_LEN + size_of::<Version>() + size_of::<Index>())?;
+
+        let address = AccountAddress::try_from(&data[..AccountAddress::LENGTH])?;
+        let type_tag_hash = HashValue::from_slice(&data[AccountAddress::LENGTH..PREFIX_LEN])?;
+        let version = (&data[PREFIX_LEN..]).read_u64::<BigEndian>()?;
+        let index = (&data[PREFIX_LEN + size_of::<Version>()..]).read_u64::<BigEndian>()?;
+
+        Ok((address, type_tag_hash, version, index))
+    }
+}
+
+impl ValueCodec<EventByTypeSchema> for () {
+    fn encode_value(&self) -> Result<Vec<u8>> {
+        Ok(Vec::new())
+    }
+
+    fn decode_value(data: &[u8]) -> Result<Self> {
+        ensure_slice_len_eq(data, 0)?;
+        Ok(())
+    }
+}
+
+impl SeekKeyCodec<EventByTypeSchema> for (AccountAddress, HashValue, Version) {
+    fn encode_seek_key(&self) -> Result<Vec<u8>> {
+        let (ref address, ref type_tag_hash, version) = *self;
+
+        let mut encoded = encode_prefix(address, type_tag_hash);
+        encoded.write_u64::<BigEndian>(version)?;
+
+        Ok(encoded)
+    }
+}
+
+#[cfg(test)]
+mod test;
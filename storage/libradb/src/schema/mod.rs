@@ -10,10 +10,13 @@ pub(crate) mod epoch_by_version;
 pub(crate) mod event;
 pub(crate) mod event_accumulator;
 pub(crate) mod event_by_key;
+pub(crate) mod event_by_type;
 pub(crate) mod jellyfish_merkle_node;
 pub(crate) mod ledger_counters;
 pub(crate) mod ledger_info;
+pub(crate) mod pruner_progress;
 pub(crate) mod stale_node_index;
+pub(crate) mod tiering_progress;
 pub(crate) mod transaction;
 pub(crate) mod transaction_accumulator;
 pub(crate) mod transaction_by_account;
@@ -25,10 +28,13 @@ use schemadb::ColumnFamilyName;
 pub(super) const EPOCH_BY_VERSION_CF_NAME: ColumnFamilyName = "epoch_by_version";
 pub(super) const EVENT_ACCUMULATOR_CF_NAME: ColumnFamilyName = "event_accumulator";
 pub(super) const EVENT_BY_KEY_CF_NAME: ColumnFamilyName = "event_by_key";
+pub(super) const EVENT_BY_TYPE_CF_NAME: ColumnFamilyName = "event_by_type";
 pub(super) const EVENT_CF_NAME: ColumnFamilyName = "event";
 pub(super) const JELLYFISH_MERKLE_NODE_CF_NAME: ColumnFamilyName = "jellyfish_merkle_node";
 pub(super) const LEDGER_COUNTERS_CF_NAME: ColumnFamilyName = "ledger_counters";
+pub(super) const PRUNER_PROGRESS_CF_NAME: ColumnFamilyName = "pruner_progress";
 pub(super) const STALE_NODE_INDEX_CF_NAME: ColumnFamilyName = "stale_node_index";
+pub(super) const TIERING_PROGRESS_CF_NAME: ColumnFamilyName = "tiering_progress";
 pub(super) const TRANSACTION_CF_NAME: ColumnFamilyName = "transaction";
 pub(super) const TRANSACTION_ACCUMULATOR_CF_NAME: ColumnFamilyName = "transaction_accumulator";
 pub(super) const TRANSACTION_BY_ACCOUNT_CF_NAME: ColumnFamilyName = "transaction_by_account";
@@ -72,13 +78,16 @@ pub mod fuzzing {
             decode_key_value!(super::event::EventSchema, data);
             decode_key_value!(super::event_accumulator::EventAccumulatorSchema, data);
             decode_key_value!(super::event_by_key::EventByKeySchema, data);
+            decode_key_value!(super::event_by_type::EventByTypeSchema, data);
             decode_key_value!(
                 super::jellyfish_merkle_node::JellyfishMerkleNodeSchema,
                 data
             );
             decode_key_value!(super::ledger_counters::LedgerCountersSchema, data);
             decode_key_value!(super::ledger_info::LedgerInfoSchema, data);
+            decode_key_value!(super::pruner_progress::PrunerProgressSchema, data);
             decode_key_value!(super::stale_node_index::StaleNodeIndexSchema, data);
+            decode_key_value!(super::tiering_progress::TieringProgressSchema, data);
             decode_key_value!(super::transaction::TransactionSchema, data);
             decode_key_value!(
                 super::transaction_accumulator::TransactionAccumulatorSchema,
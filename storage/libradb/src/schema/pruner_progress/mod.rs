@@ -0,0 +1,68 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! This module defines the physical storage schema for the progress of the pruner, so that what's
+//! pruned is known after the DB is reopened.
+//!
+//! ```text
+//! |<--key-->|<-value->|
+//! |   tag   | version |
+//! ```
+
+use crate::schema::{ensure_slice_len_eq, PRUNER_PROGRESS_CF_NAME};
+use anyhow::{format_err, Result};
+use byteorder::{BigEndian, ReadBytesExt};
+use libra_types::transaction::Version;
+use num_derive::{FromPrimitive, ToPrimitive};
+use num_traits::{FromPrimitive, ToPrimitive};
+#[cfg(test)]
+use proptest_derive::Arbitrary;
+use schemadb::{
+    define_schema,
+    schema::{KeyCodec, ValueCodec},
+};
+use std::mem::size_of;
+
+define_schema!(
+    PrunerProgressSchema,
+    PrunerProgressTag,
+    Version,
+    PRUNER_PROGRESS_CF_NAME
+);
+
+/// For each, data of versions before the version stored is pruned.
+#[derive(Clone, Copy, Debug, Eq, FromPrimitive, PartialEq, ToPrimitive)]
+#[cfg_attr(test, derive(Arbitrary))]
+pub(crate) enum PrunerProgressTag {
+    State = 0,
+    Events = 1,
+    Transactions = 2,
+    /// `TransactionInfo`s are needed by the proofs of state and events as well, so they are
+    /// pruned only once all the others are.
+    TransactionInfos = 3,
+}
+
+impl KeyCodec<PrunerProgressSchema> for PrunerProgressTag {
+    fn encode_key(&self) -> Result<Vec<u8>> {
+        Ok(vec![self.to_u8().expect("8 bits are enough.")])
+    }
+
+    fn decode_key(data: &[u8]) -> Result<Self> {
+        ensure_slice_len_eq(data, size_of::<u8>())?;
+        Self::from_u8(data[0]).ok_or_else(|| format_err!("Unknown pruner progress tag."))
+    }
+}
+
+impl ValueCodec<PrunerProgressSchema> for Version {
+    fn encode_value(&self) -> Result<Vec<u8>> {
+        Ok(self.to_be_bytes().to_vec())
+    }
+
+    fn decode_value(data: &[u8]) -> Result<Self> {
+        ensure_slice_len_eq(data, size_of::<Version>())?;
+        Ok((&data[..]).read_u64::<BigEndian>()?)
+    }
+}
+
+#[cfg(test)]
+mod test;
@@ -0,0 +1,66 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! This module defines the physical storage schema for the progress of migrating historical data
+//! to the cold storage. It lives in the cold DB, next to the data migrated.
+//!
+//! ```text
+//! |<--key-->|<-value->|
+//! |   tag   | version |
+//! ```
+
+use crate::schema::{ensure_slice_len_eq, TIERING_PROGRESS_CF_NAME};
+use anyhow::{format_err, Result};
+use byteorder::{BigEndian, ReadBytesExt};
+use libra_types::transaction::Version;
+use num_derive::{FromPrimitive, ToPrimitive};
+use num_traits::{FromPrimitive, ToPrimitive};
+#[cfg(test)]
+use proptest_derive::Arbitrary;
+use schemadb::{
+    define_schema,
+    schema::{KeyCodec, ValueCodec},
+};
+use std::mem::size_of;
+
+define_schema!(
+    TieringProgressSchema,
+    TieringProgressTag,
+    Version,
+    TIERING_PROGRESS_CF_NAME
+);
+
+#[derive(Clone, Copy, Debug, Eq, FromPrimitive, PartialEq, ToPrimitive)]
+#[cfg_attr(test, derive(Arbitrary))]
+pub(crate) enum TieringProgressTag {
+    /// Data of versions before this one is migrated.
+    MigratedBeforeVersion = 0,
+    /// Where the last migration batch started, so that what's left in the hot DB by a batch
+    /// interrupted after being written to the cold DB can be cleaned up after restart.
+    LastBatchStartVersion = 1,
+}
+
+impl KeyCodec<TieringProgressSchema> for TieringProgressTag {
+    fn encode_key(&self) -> Result<Vec<u8>> {
+        Ok(vec![self.to_u8().expect("8 bits are enough.")])
+    }
+
+    fn decode_key(data: &[u8]) -> Result<Self> {
+        ensure_slice_len_eq(data, size_of::<u8>())?;
+        Self::from_u8(data[0]).ok_or_else(|| format_err!("Unknown tiering progress tag."))
+    }
+}
+
+impl ValueCodec<TieringProgressSchema> for Version {
+    fn encode_value(&self) -> Result<Vec<u8>> {
+        Ok(self.to_be_bytes().to_vec())
+    }
+
+    fn decode_value(data: &[u8]) -> Result<Self> {
+        ensure_slice_len_eq(data, size_of::<Version>())?;
+        Ok((&data[..]).read_u64::<BigEndian>()?)
+    }
+}
+
+#[cfg(test)]
+mod test;
@@ -0,0 +1,209 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! This module provides [`LibraDBSecondary`], a read-only view of a [`LibraDB`] that another
+//! process keeps writing to.
+
+use crate::LibraDB;
+use anyhow::Result;
+use arc_swap::ArcSwap;
+use libra_crypto::HashValue;
+use libra_logger::prelude::*;
+use libra_types::{
+    account_address::AccountAddress,
+    account_state_blob::{AccountStateBlob, AccountStateWithProof},
+    contract_event::{ContractEvent, EventWithProof},
+    epoch_change::EpochChangeProof,
+    event::EventKey,
+    ledger_info::LedgerInfoWithSignatures,
+    proof::{AccumulatorConsistencyProof, SparseMerkleProof},
+    transaction::{TransactionListWithProof, TransactionWithProof, Version},
+};
+use std::{
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::Instant,
+};
+use storage_interface::{DbReader, StartupInfo, TreeState};
+
+/// A read-only instance of the DB at a path that another process keeps writing to as the
+/// primary, e.g. to serve reads off the DB of a running validator without stopping it.
+///
+/// RocksDB only shows a read-only instance what was written before it was opened, so what the
+/// primary writes later is only seen after `try_catch_up_with_primary`, which reopens the DB.
+/// Readers holding on to the previous instance keep reading a consistent, older view.
+pub struct LibraDBSecondary {
+    db_root_path: PathBuf,
+    db: ArcSwap<LibraDB>,
+}
+
+impl LibraDBSecondary {
+    pub fn open<P: AsRef<Path>>(db_root_path: P) -> Result<Self> {
+        let db_root_path = db_root_path.as_ref().to_path_buf();
+        let db = Self::open_readonly(&db_root_path)?;
+        Ok(Self {
+            db_root_path,
+            db: ArcSwap::from_pointee(db),
+        })
+    }
+
+    fn open_readonly(db_root_path: &Path) -> Result<LibraDB> {
+        LibraDB::open(
+            db_root_path,
+            true, /* readonly */
+            None, /* pruner */
+        )
+    }
+
+    /// Makes what the primary has written so far visible. Opening can fail if the primary
+    /// deletes files while they are being opened, in which case the current view is kept and
+    /// catching up can be retried.
+    pub fn try_catch_up_with_primary(&self) -> Result<()> {
+        let instant = Instant::now();
+        self.db
+            .store(Arc::new(Self::open_readonly(&self.db_root_path)?));
+        debug!(
+            "Caught up with primary at {:?} in {} ms",
+            self.db_root_path,
+            instant.elapsed().as_millis()
+        );
+        Ok(())
+    }
+
+    /// The view of the DB as of the last time it caught up with the primary.
+    pub fn db(&self) -> Arc<LibraDB> {
+        self.db.load_full()
+    }
+}
+
+impl DbReader for LibraDBSecondary {
+    fn get_epoch_ending_ledger_infos(
+        &self,
+        start_epoch: u64,
+        end_epoch: u64,
+    ) -> Result<EpochChangeProof> {
+        // Not the inherent `LibraDB::get_epoch_ending_ledger_infos`, which returns no proof.
+        DbReader::get_epoch_ending_ledger_infos(&*self.db(), start_epoch, end_epoch)
+    }
+
+    fn get_transactions(
+        &self,
+        start_version: Version,
+        batch_size: u64,
+        ledger_version: Version,
+        fetch_events: bool,
+    ) -> Result<TransactionListWithProof> {
+        self.db()
+            .get_transactions(start_version, batch_size, ledger_version, fetch_events)
+    }
+
+    fn get_events(
+        &self,
+        event_key: &EventKey,
+        start: u64,
+        ascending: bool,
+        limit: u64,
+    ) -> Result<Vec<(u64, ContractEvent)>> {
+        self.db().get_events(event_key, start, ascending, limit)
+    }
+
+    fn get_events_with_proofs(
+        &self,
+        event_key: &EventKey,
+        start: u64,
+        limit: u64,
+        ledger_version: Version,
+    ) -> Result<Vec<EventWithProof>> {
+        self.db()
+            .get_events_with_proofs(event_key, start, limit, ledger_version)
+    }
+
+    fn get_block_timestamp(&self, version: u64) -> Result<u64> {
+        self.db().get_block_timestamp(version)
+    }
+
+    fn get_latest_account_state(
+        &self,
+        address: AccountAddress,
+    ) -> Result<Option<AccountStateBlob>> {
+        self.db().get_latest_account_state(address)
+    }
+
+    fn get_latest_ledger_info(&self) -> Result<LedgerInfoWithSignatures> {
+        self.db().get_latest_ledger_info()
+    }
+
+    fn get_startup_info(&self) -> Result<Option<StartupInfo>> {
+        self.db().get_startup_info()
+    }
+
+    fn get_txn_by_account(
+        &self,
+        address: AccountAddress,
+        seq_num: u64,
+        ledger_version: Version,
+        fetch_events: bool,
+    ) -> Result<Option<TransactionWithProof>> {
+        self.db()
+            .get_txn_by_account(address, seq_num, ledger_version, fetch_events)
+    }
+
+    fn get_state_proof_with_ledger_info(
+        &self,
+        known_version: u64,
+        ledger_info: LedgerInfoWithSignatures,
+    ) -> Result<(EpochChangeProof, AccumulatorConsistencyProof)> {
+        self.db()
+            .get_state_proof_with_ledger_info(known_version, ledger_info)
+    }
+
+    fn get_state_proof(
+        &self,
+        known_version: u64,
+    ) -> Result<(
+        LedgerInfoWithSignatures,
+        EpochChangeProof,
+        AccumulatorConsistencyProof,
+    )> {
+        self.db().get_state_proof(known_version)
+    }
+
+    fn get_account_state_with_proof(
+        &self,
+        address: AccountAddress,
+        version: Version,
+        ledger_version: Version,
+    ) -> Result<AccountStateWithProof> {
+        self.db()
+            .get_account_state_with_proof(address, version, ledger_version)
+    }
+
+    fn get_account_state_with_proof_by_version(
+        &self,
+        address: AccountAddress,
+        version: Version,
+    ) -> Result<(Option<AccountStateBlob>, SparseMerkleProof)> {
+        self.db()
+            .get_account_state_with_proof_by_version(address, version)
+    }
+
+    fn get_account_states_batch(
+        &self,
+        addresses: &[AccountAddress],
+        version: Version,
+    ) -> Result<Vec<Option<AccountStateBlob>>> {
+        self.db().get_account_states_batch(addresses, version)
+    }
+
+    fn get_latest_state_root(&self) -> Result<(Version, HashValue)> {
+        self.db().get_latest_state_root()
+    }
+
+    fn get_latest_tree_state(&self) -> Result<TreeState> {
+        self.db().get_latest_tree_state()
+    }
+
+    fn get_epoch_ending_ledger_info(&self, known_version: u64) -> Result<LedgerInfoWithSignatures> {
+        self.db().get_epoch_ending_ledger_info(known_version)
+    }
+}
@@ -0,0 +1,194 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! This file defines a portable format for the full account state at a version, written by
+//! `LibraDB::export_state_snapshot` and read by `LibraDB::import_state_snapshot`. Unlike backups,
+//! a snapshot is a single self-contained stream that can be produced and consumed in one pass,
+//! so that tools outside of Libra can read it with nothing but this description.
+//!
+//! All integers are little-endian. A snapshot is laid out as:
+//!
+//! ```text
+//! header:
+//!     magic:           8 bytes, "LBRSTATE"
+//!     format version:  u32, currently 1
+//!     version:         u64, the version of the state
+//!     root hash:       32 bytes, root hash of the sparse Merkle tree at the version
+//! chunks, each:
+//!     num accounts:    u32, greater than 0
+//!     accounts, each:
+//!         key:         32 bytes, SHA3-256 hash of the account address
+//!         blob length: u32
+//!         blob:        the account state blob
+//!     proof length:    u32
+//!     proof:           LCS of a `SparseMerkleRangeProof` of all accounts so far
+//! end:
+//!     num accounts:    u32, 0
+//!     total accounts:  u64, the number of accounts in all chunks
+//! ```
+//!
+//! Accounts come in strictly increasing order of their keys across chunks. A reader not
+//! interested in verifying the state can skip the proofs.
+
+use anyhow::{ensure, Result};
+use byteorder::{LittleEndian, ReadBytesExt, WriteBytesExt};
+use libra_crypto::HashValue;
+use libra_types::{
+    account_state_blob::AccountStateBlob, proof::SparseMerkleRangeProof, transaction::Version,
+};
+use std::io::{Read, Write};
+
+const MAGIC: &[u8; 8] = b"LBRSTATE";
+const FORMAT_VERSION: u32 = 1;
+
+/// What a snapshot starts with.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct StateSnapshotHeader {
+    pub version: Version,
+    pub root_hash: HashValue,
+}
+
+/// A chunk of accounts, with the proof of all accounts up to the last one in the chunk.
+#[derive(Clone, Debug)]
+pub struct StateSnapshotChunk {
+    pub accounts: Vec<(HashValue, AccountStateBlob)>,
+    pub proof: SparseMerkleRangeProof,
+}
+
+/// Writes a snapshot to a stream.
+pub struct StateSnapshotWriter<W> {
+    writer: W,
+    num_accounts: u64,
+}
+
+impl<W: Write> StateSnapshotWriter<W> {
+    /// Writes the header.
+    pub fn new(mut writer: W, header: &StateSnapshotHeader) -> Result<Self> {
+        writer.write_all(MAGIC)?;
+        writer.write_u32::<LittleEndian>(FORMAT_VERSION)?;
+        writer.write_u64::<LittleEndian>(header.version)?;
+        writer.write_all(header.root_hash.as_ref())?;
+        Ok(Self {
+            writer,
+            num_accounts: 0,
+        })
+    }
+
+    pub fn write_chunk(&mut self, chunk: &StateSnapshotChunk) -> Result<()> {
+        ensure!(!chunk.accounts.is_empty(), "Should not write empty chunks.");
+
+        self.writer
+            .write_u32::<LittleEndian>(chunk.accounts.len() as u32)?;
+        for (key, blob) in &chunk.accounts {
+            self.writer.write_all(key.as_ref())?;
+            write_bytes(&mut self.writer, blob.as_ref())?;
+        }
+        write_bytes(&mut self.writer, &lcs::to_bytes(&chunk.proof)?)?;
+        self.num_accounts += chunk.accounts.len() as u64;
+        Ok(())
+    }
+
+    /// Writes the end of the snapshot and returns the underlying stream.
+    pub fn finish(mut self) -> Result<W> {
+        self.writer.write_u32::<LittleEndian>(0)?;
+        self.writer.write_u64::<LittleEndian>(self.num_accounts)?;
+        self.writer.flush()?;
+        Ok(self.writer)
+    }
+}
+
+/// Reads a snapshot from a stream. Besides what's in the format, the stream is not validated: the
+/// proofs are to be verified by the caller.
+pub struct StateSnapshotReader<R> {
+    reader: R,
+    header: StateSnapshotHeader,
+    num_accounts: u64,
+    finished: bool,
+}
+
+impl<R: Read> StateSnapshotReader<R> {
+    /// Reads the header.
+    pub fn new(mut reader: R) -> Result<Self> {
+        let mut magic = [0u8; 8];
+        reader.read_exact(&mut magic)?;
+        ensure!(&magic == MAGIC, "Not a state snapshot.");
+        let format_version = reader.read_u32::<LittleEndian>()?;
+        ensure!(
+            format_version == FORMAT_VERSION,
+            "Unsupported state snapshot format version: {}",
+            format_version,
+        );
+        let version = reader.read_u64::<LittleEndian>()?;
+        let root_hash = read_hash(&mut reader)?;
+        Ok(Self {
+            reader,
+            header: StateSnapshotHeader { version, root_hash },
+            num_accounts: 0,
+            finished: false,
+        })
+    }
+
+    pub fn header(&self) -> &StateSnapshotHeader {
+        &self.header
+    }
+
+    /// Returns the next chunk, or None at the end of the snapshot. Errors if the snapshot is
+    /// truncated.
+    pub fn next_chunk(&mut self) -> Result<Option<StateSnapshotChunk>> {
+        if self.finished {
+            return Ok(None);
+        }
+
+        let num_accounts = self.reader.read_u32::<LittleEndian>()?;
+        if num_accounts == 0 {
+            let total_accounts = self.reader.read_u64::<LittleEndian>()?;
+            ensure!(
+                total_accounts == self.num_accounts,
+                "State snapshot claims {} accounts, but {} were read.",
+                total_accounts,
+                self.num_accounts,
+            );
+            self.finished = true;
+            return Ok(None);
+        }
+
+        let mut accounts = Vec::new();
+        for _ in 0..num_accounts {
+            let key = read_hash(&mut self.reader)?;
+            let blob = AccountStateBlob::from(read_bytes(&mut self.reader)?);
+            accounts.push((key, blob));
+        }
+        let proof = lcs::from_bytes(&read_bytes(&mut self.reader)?)?;
+        self.num_accounts += u64::from(num_accounts);
+        Ok(Some(StateSnapshotChunk { accounts, proof }))
+    }
+}
+
+fn write_bytes<W: Write>(writer: &mut W, bytes: &[u8]) -> Result<()> {
+    writer.write_u32::<LittleEndian>(bytes.len() as u32)?;
+    writer.write_all(bytes)?;
+    Ok(())
+}
+
+fn read_bytes<R: Read>(reader: &mut R) -> Result<Vec<u8>> {
+    let len = reader.read_u32::<LittleEndian>()?;
+    // Not allocating `len` bytes upfront, so that a corrupt length doesn't exhaust the memory.
+    let mut bytes = Vec::new();
+    reader.take(u64::from(len)).read_to_end(&mut bytes)?;
+    ensure!(
+        bytes.len() == len as usize,
+        "State snapshot truncated, expected {} bytes, got {}.",
+        len,
+        bytes.len(),
+    );
+    Ok(bytes)
+}
+
+fn read_hash<R: Read>(reader: &mut R) -> Result<HashValue> {
+    let mut bytes = [0u8; HashValue::LENGTH];
+    reader.read_exact(&mut bytes)?;
+    Ok(HashValue::new(bytes))
+}
+
+#[cfg(test)]
+mod test;
@@ -0,0 +1,111 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use super::*;
+use crate::{iterators::AccountStateItem, test_helper::arb_blocks_to_commit, LibraDB};
+use libra_temppath::TempPath;
+use libra_types::{ledger_info::LedgerInfoWithSignatures, transaction::TransactionToCommit};
+use proptest::prelude::*;
+
+// Offset of the first byte of the first account state blob.
+const FIRST_BLOB_OFFSET: usize = 8 + 4 + 8 + 32 + 4 + 32 + 4;
+
+fn save_and_export(
+    input: &[(Vec<TransactionToCommit>, LedgerInfoWithSignatures)],
+) -> (TempPath, LibraDB, Version, Vec<u8>) {
+    let tmp_dir = TempPath::new();
+    let db = LibraDB::new_for_test(&tmp_dir);
+    let mut cur_ver = 0;
+    for (txns_to_commit, ledger_info_with_sigs) in input {
+        db.save_transactions(&txns_to_commit, cur_ver, Some(ledger_info_with_sigs))
+            .unwrap();
+        cur_ver += txns_to_commit.len() as u64;
+    }
+    let version = cur_ver - 1;
+
+    let mut snapshot = Vec::new();
+    db.export_state_snapshot(version, &mut snapshot).unwrap();
+    (tmp_dir, db, version, snapshot)
+}
+
+fn account_states(db: &LibraDB, version: Version) -> Vec<(HashValue, AccountStateBlob)> {
+    db.iter_account_states_at_version(version, false /* with_proof */)
+        .unwrap()
+        .map(|res| res.map(|AccountStateItem { key, blob, .. }| (key, blob)))
+        .collect::<Result<Vec<_>>>()
+        .unwrap()
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(10))]
+
+    #[test]
+    fn test_export_and_import(input in arb_blocks_to_commit()) {
+        let (_tmp_dir, db, version, snapshot) = save_and_export(&input);
+
+        let tmp_dir = TempPath::new();
+        let imported_db = LibraDB::new_for_test(&tmp_dir);
+        let header = imported_db.import_state_snapshot(snapshot.as_slice()).unwrap();
+        prop_assert_eq!(header.version, version);
+        prop_assert_eq!(header.root_hash, db.state_store.get_root_hash(version).unwrap());
+        prop_assert_eq!(
+            imported_db.state_store.get_root_hash(version).unwrap(),
+            header.root_hash
+        );
+        prop_assert_eq!(account_states(&imported_db, version), account_states(&db, version));
+    }
+
+    #[test]
+    fn test_import_truncated(input in arb_blocks_to_commit(), cut in 1usize..100) {
+        let (_tmp_dir, _db, _version, mut snapshot) = save_and_export(&input);
+        snapshot.truncate(snapshot.len().saturating_sub(cut));
+
+        let tmp_dir = TempPath::new();
+        let imported_db = LibraDB::new_for_test(&tmp_dir);
+        prop_assert!(imported_db.import_state_snapshot(snapshot.as_slice()).is_err());
+    }
+
+    #[test]
+    fn test_import_tampered(input in arb_blocks_to_commit()) {
+        let (_tmp_dir, _db, _version, mut snapshot) = save_and_export(&input);
+        snapshot[FIRST_BLOB_OFFSET] ^= 0xff;
+
+        let tmp_dir = TempPath::new();
+        let imported_db = LibraDB::new_for_test(&tmp_dir);
+        prop_assert!(imported_db.import_state_snapshot(snapshot.as_slice()).is_err());
+    }
+}
+
+#[test]
+fn test_read_across_chunks() {
+    let header = StateSnapshotHeader {
+        version: 10,
+        root_hash: HashValue::random(),
+    };
+    let chunks: Vec<_> = (0..3u8)
+        .map(|i| StateSnapshotChunk {
+            accounts: (0..=i)
+                .map(|j| (HashValue::random(), AccountStateBlob::from(vec![i, j])))
+                .collect(),
+            proof: SparseMerkleRangeProof::new(vec![HashValue::random()]),
+        })
+        .collect();
+
+    let mut writer = StateSnapshotWriter::new(Vec::new(), &header).unwrap();
+    for chunk in &chunks {
+        writer.write_chunk(chunk).unwrap();
+    }
+    let bytes = writer.finish().unwrap();
+
+    let mut reader = StateSnapshotReader::new(bytes.as_slice()).unwrap();
+    assert_eq!(*reader.header(), header);
+    for chunk in &chunks {
+        let read = reader.next_chunk().unwrap().unwrap();
+        assert_eq!(read.accounts, chunk.accounts);
+        assert_eq!(read.proof, chunk.proof);
+    }
+    assert!(reader.next_chunk().unwrap().is_none());
+    assert!(reader.next_chunk().unwrap().is_none());
+
+    assert!(StateSnapshotReader::new(&bytes[1..]).is_err());
+}
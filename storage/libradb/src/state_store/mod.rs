@@ -12,6 +12,7 @@ use crate::{
     schema::{
         jellyfish_merkle_node::JellyfishMerkleNodeSchema, stale_node_index::StaleNodeIndexSchema,
     },
+    tiering::{self, ColdStore},
 };
 use anyhow::Result;
 use jellyfish_merkle::{
@@ -30,11 +31,13 @@ use std::{collections::HashMap, sync::Arc};
 
 pub(crate) struct StateStore {
     db: Arc<DB>,
+    /// Where stale nodes are moved to, if tiering is on.
+    cold_store: Option<Arc<ColdStore>>,
 }
 
 impl StateStore {
-    pub fn new(db: Arc<DB>) -> Self {
-        Self { db }
+    pub fn new(db: Arc<DB>, cold_store: Option<Arc<ColdStore>>) -> Self {
+        Self { db, cold_store }
     }
 
     /// Get the account state blob given account address and root hash of state Merkle tree
@@ -43,7 +46,27 @@ impl StateStore {
         address: AccountAddress,
         version: Version,
     ) -> Result<(Option<AccountStateBlob>, SparseMerkleProof)> {
-        JellyfishMerkleTree::new(self).get_with_proof(address.hash(), version)
+        self.get_account_state_with_proof_by_key(address.hash(), version)
+    }
+
+    /// Get the account state blob given the hash of the account address and version.
+    pub fn get_account_state_with_proof_by_key(
+        &self,
+        key: HashValue,
+        version: Version,
+    ) -> Result<(Option<AccountStateBlob>, SparseMerkleProof)> {
+        JellyfishMerkleTree::new(self).get_with_proof(key, version)
+    }
+
+    /// Get the account state blobs of many accounts at the same version, in the same order as
+    /// `addresses`.
+    pub fn get_account_states_by_version(
+        &self,
+        addresses: &[AccountAddress],
+        version: Version,
+    ) -> Result<Vec<Option<AccountStateBlob>>> {
+        let keys: Vec<_> = addresses.iter().map(CryptoHash::hash).collect();
+        JellyfishMerkleTree::new(self).batch_get(&keys, version)
     }
 
     /// Gets the proof that proves a range of accounts.
@@ -141,7 +164,34 @@ impl StateStore {
 
 impl TreeReader for StateStore {
     fn get_node_option(&self, node_key: &NodeKey) -> Result<Option<Node>> {
-        Ok(self.db.get::<JellyfishMerkleNodeSchema>(node_key)?)
+        tiering::get_falling_through::<JellyfishMerkleNodeSchema>(
+            &self.db,
+            self.cold_store.as_deref(),
+            node_key,
+        )
+    }
+
+    fn get_node_options(&self, node_keys: &[NodeKey]) -> Result<Vec<Option<Node>>> {
+        // Encoded nodes are never empty, so these are safe to read with a multi-get.
+        let mut nodes = self.db.multi_get::<JellyfishMerkleNodeSchema>(node_keys)?;
+        if let Some(cold_store) = &self.cold_store {
+            let (missing_indices, missing_keys): (Vec<_>, Vec<_>) = node_keys
+                .iter()
+                .zip(&nodes)
+                .enumerate()
+                .filter(|(_, (_, node))| node.is_none())
+                .map(|(i, (node_key, _))| (i, node_key.clone()))
+                .unzip();
+            if !missing_keys.is_empty() {
+                let cold_nodes = cold_store
+                    .db()
+                    .multi_get::<JellyfishMerkleNodeSchema>(&missing_keys)?;
+                for (i, node) in missing_indices.into_iter().zip(cold_nodes) {
+                    nodes[i] = node;
+                }
+            }
+        }
+        Ok(nodes)
     }
 
     fn get_rightmost_leaf(&self) -> Result<Option<(NodeKey, LeafNode)>> {
@@ -0,0 +1,402 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! This module provides `ColdStore`, which keeps historical transactions, events and state tree
+//! nodes in a second RocksDB instance, typically on a cheaper and slower disk, and `Migrator`,
+//! which manages a thread moving data out of the window of recent versions to it in the
+//! background. Reads of what's been moved fall through to the cold DB transparently.
+//!
+//! Only the bulk of the data is moved. Indices, accumulators and `TransactionInfo`s stay in the
+//! hot DB, so that looking things up and proving them costs the same as before, and only reading
+//! the data itself hits the cold DB.
+//!
+//! Data is written to the cold DB before it's deleted from the hot one, so that it can always be
+//! found in one of them. What the pruner prunes from the hot DB afterwards is not pruned from the
+//! cold DB, but can't be read any more.
+
+use crate::{
+    schema::{
+        event::EventSchema,
+        jellyfish_merkle_node::JellyfishMerkleNodeSchema,
+        stale_node_index::StaleNodeIndexSchema,
+        tiering_progress::{TieringProgressSchema, TieringProgressTag},
+        transaction::TransactionSchema,
+        EVENT_CF_NAME, JELLYFISH_MERKLE_NODE_CF_NAME, TIERING_PROGRESS_CF_NAME,
+        TRANSACTION_CF_NAME,
+    },
+    LIBRA_STORAGE_TIERING_MIGRATED_BEFORE_VERSION,
+};
+use anyhow::Result;
+use libra_logger::prelude::*;
+use libra_types::transaction::Version;
+use schemadb::{
+    schema::{Schema, SeekKeyCodec},
+    ColumnFamilyName, ReadOptions, SchemaBatch, SchemaIterator, DB, DEFAULT_CF_NAME,
+};
+use std::{
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        mpsc::{channel, Receiver, Sender},
+        Arc, Mutex,
+    },
+    thread::JoinHandle,
+};
+#[cfg(test)]
+use std::{
+    thread::sleep,
+    time::{Duration, Instant},
+};
+
+/// Where to keep historical data, and how much of the recent data to keep on fast disk.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ColdStorageConfig {
+    /// Root of the cold DB, which is created under it.
+    pub path: PathBuf,
+    /// Other than the latest version, how many versions to keep in the hot DB. For example, 0
+    /// means moving everything but the latest version to the cold DB.
+    pub hot_window: u64,
+}
+
+/// How far the migration to the cold DB has gone.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct TieringProgress {
+    /// Data of versions before this one is in the cold DB.
+    pub migrated_before_version: Version,
+    /// Data of versions before this one is to be moved to the cold DB, given the latest version
+    /// committed and the hot window.
+    pub target_before_version: Version,
+}
+
+/// The cold DB, sharing the layout of the hot DB for the data moved to it.
+pub(crate) struct ColdStore {
+    hot_db: Arc<DB>,
+    db: DB,
+    /// Data of versions before this one is read from the cold DB. Advanced as soon as it's been
+    /// written to the cold DB, before it's deleted from the hot DB.
+    migrated_before_version: AtomicU64,
+}
+
+impl ColdStore {
+    fn column_families() -> Vec<ColumnFamilyName> {
+        vec![
+            DEFAULT_CF_NAME,
+            EVENT_CF_NAME,
+            JELLYFISH_MERKLE_NODE_CF_NAME,
+            TIERING_PROGRESS_CF_NAME,
+            TRANSACTION_CF_NAME,
+        ]
+    }
+
+    /// Opens the cold DB under `path`, and picks up the migration where it stopped.
+    pub fn open(path: &Path, hot_db: Arc<DB>) -> Result<Self> {
+        let db = DB::open(
+            path.join("libradb_cold"),
+            "libradb_cold",
+            Self::column_families(),
+        )?;
+        let migrated_before_version = db
+            .get::<TieringProgressSchema>(&TieringProgressTag::MigratedBeforeVersion)?
+            .unwrap_or(0);
+        let last_batch_start_version =
+            db.get::<TieringProgressSchema>(&TieringProgressTag::LastBatchStartVersion)?;
+
+        let cold_store = Self {
+            hot_db,
+            db,
+            migrated_before_version: AtomicU64::new(migrated_before_version),
+        };
+        // The last batch might not have been deleted from the hot DB before a restart.
+        if let Some(start_version) = last_batch_start_version {
+            cold_store.delete_from_hot(start_version, migrated_before_version)?;
+        }
+        LIBRA_STORAGE_TIERING_MIGRATED_BEFORE_VERSION.set(migrated_before_version as i64);
+        Ok(cold_store)
+    }
+
+    pub fn db(&self) -> &DB {
+        &self.db
+    }
+
+    pub fn migrated_before_version(&self) -> Version {
+        self.migrated_before_version.load(Ordering::Relaxed)
+    }
+
+    /// Moves the data of at most `max_versions` versions, from where the migration is on, to the
+    /// cold DB, stopping before `target_before_version`. Returns the new migration progress.
+    ///
+    /// State tree nodes are moved once they are stale, i.e. only needed by versions moved.
+    pub fn migrate(&self, target_before_version: Version, max_versions: u64) -> Result<Version> {
+        let start_version = self.migrated_before_version();
+        if start_version >= target_before_version {
+            return Ok(start_version);
+        }
+        let end_version = std::cmp::min(
+            target_before_version,
+            start_version.saturating_add(max_versions),
+        );
+
+        let mut batch = SchemaBatch::new();
+        for res in self.hot_iter::<TransactionSchema>(start_version)? {
+            let (version, txn) = res?;
+            if version >= end_version {
+                break;
+            }
+            batch.put::<TransactionSchema>(&version, &txn)?;
+        }
+        for res in self.hot_iter::<EventSchema>(start_version)? {
+            let (key, event) = res?;
+            if key.0 >= end_version {
+                break;
+            }
+            batch.put::<EventSchema>(&key, &event)?;
+        }
+        for res in self.hot_iter::<StaleNodeIndexSchema>(start_version)? {
+            let (index, _) = res?;
+            if index.stale_since_version >= end_version {
+                break;
+            }
+            // Nodes might have been pruned already.
+            if let Some(node) = self
+                .hot_db
+                .get::<JellyfishMerkleNodeSchema>(&index.node_key)?
+            {
+                batch.put::<JellyfishMerkleNodeSchema>(&index.node_key, &node)?;
+            }
+        }
+        batch.put::<TieringProgressSchema>(
+            &TieringProgressTag::MigratedBeforeVersion,
+            &end_version,
+        )?;
+        batch.put::<TieringProgressSchema>(
+            &TieringProgressTag::LastBatchStartVersion,
+            &start_version,
+        )?;
+        self.db.write_schemas(batch)?;
+
+        self.migrated_before_version
+            .store(end_version, Ordering::Relaxed);
+        self.delete_from_hot(start_version, end_version)?;
+        LIBRA_STORAGE_TIERING_MIGRATED_BEFORE_VERSION.set(end_version as i64);
+        Ok(end_version)
+    }
+
+    /// Deletes what's migrated of versions [`start_version`, `end_version`) from the hot DB.
+    fn delete_from_hot(&self, start_version: Version, end_version: Version) -> Result<()> {
+        let mut batch = SchemaBatch::new();
+        for res in self.hot_iter::<TransactionSchema>(start_version)? {
+            let (version, _) = res?;
+            if version >= end_version {
+                break;
+            }
+            batch.delete::<TransactionSchema>(&version)?;
+        }
+        for res in self.hot_iter::<EventSchema>(start_version)? {
+            let (key, _) = res?;
+            if key.0 >= end_version {
+                break;
+            }
+            batch.delete::<EventSchema>(&key)?;
+        }
+        // The index is kept, for the pruner.
+        for res in self.hot_iter::<StaleNodeIndexSchema>(start_version)? {
+            let (index, _) = res?;
+            if index.stale_since_version >= end_version {
+                break;
+            }
+            batch.delete::<JellyfishMerkleNodeSchema>(&index.node_key)?;
+        }
+        self.hot_db.write_schemas(batch)
+    }
+
+    fn hot_iter<S: Schema>(&self, start_version: Version) -> Result<SchemaIterator<S>>
+    where
+        Version: SeekKeyCodec<S>,
+    {
+        let mut iter = self.hot_db.iter::<S>(ReadOptions::default())?;
+        iter.seek(&start_version)?;
+        Ok(iter)
+    }
+}
+
+/// Reads `key` from the hot DB, falling through to the cold DB if it's not there.
+pub(crate) fn get_falling_through<S: Schema>(
+    hot_db: &DB,
+    cold_store: Option<&ColdStore>,
+    key: &S::Key,
+) -> Result<Option<S::Value>> {
+    match (hot_db.get::<S>(key)?, cold_store) {
+        (None, Some(cold_store)) => cold_store.db.get::<S>(key),
+        (value, _) => Ok(value),
+    }
+}
+
+/// Gets an iterator over the DB holding the data of `version`. The hot iterator is created before
+/// the migration progress is checked, so that what it sees can't be deleted by a migration in
+/// between.
+pub(crate) fn iter_at_version<'a, S: Schema>(
+    hot_db: &'a DB,
+    cold_store: Option<&'a ColdStore>,
+    version: Version,
+) -> Result<SchemaIterator<'a, S>> {
+    let hot_iter = hot_db.iter::<S>(ReadOptions::default())?;
+    match cold_store {
+        Some(cold_store) if version < cold_store.migrated_before_version() => {
+            cold_store.db.iter::<S>(ReadOptions::default())
+        }
+        _ => Ok(hot_iter),
+    }
+}
+
+/// Manages a thread migrating data to the cold DB in the background, triggered by other threads
+/// as they commit new data to the DB.
+///
+/// It creates the worker thread on construction and joins it on destruction, without waiting for
+/// pending work to be done.
+pub(crate) struct Migrator {
+    cold_store: Arc<ColdStore>,
+    hot_window: u64,
+    /// Shared with the worker thread.
+    target_before_version: Arc<AtomicU64>,
+    /// It only becomes `None` after joined in `drop()`.
+    worker_thread: Option<JoinHandle<()>>,
+    command_sender: Mutex<Sender<Command>>,
+}
+
+impl Migrator {
+    const MAX_VERSIONS_PER_BATCH: u64 = 1000;
+
+    pub fn new(cold_store: Arc<ColdStore>, hot_window: u64) -> Self {
+        let (command_sender, command_receiver) = channel();
+        let target_before_version = Arc::new(AtomicU64::new(0));
+        let worker = Worker {
+            cold_store: Arc::clone(&cold_store),
+            target_before_version: Arc::clone(&target_before_version),
+            command_receiver,
+        };
+        let worker_thread = std::thread::Builder::new()
+            .name("libradb_migrator".into())
+            .spawn(move || worker.work_loop())
+            .expect("Creating migrator thread should succeed.");
+
+        Self {
+            cold_store,
+            hot_window,
+            target_before_version,
+            worker_thread: Some(worker_thread),
+            command_sender: Mutex::new(command_sender),
+        }
+    }
+
+    pub fn cold_store(&self) -> &Arc<ColdStore> {
+        &self.cold_store
+    }
+
+    pub fn progress(&self) -> TieringProgress {
+        TieringProgress {
+            migrated_before_version: self.cold_store.migrated_before_version(),
+            target_before_version: self.target_before_version.load(Ordering::Relaxed),
+        }
+    }
+
+    /// Moves the migration target along with the latest version, and wakes the worker thread up.
+    /// Only called by the thread committing to the DB.
+    pub fn wake(&self, latest_version: Version) {
+        let target = latest_version.saturating_sub(self.hot_window);
+        if target > self.target_before_version.load(Ordering::Relaxed) {
+            self.target_before_version.store(target, Ordering::Relaxed);
+        }
+        self.command_sender
+            .lock()
+            .expect("command_sender to migrator thread should lock.")
+            .send(Command::Migrate)
+            .expect("Receiver should not destruct prematurely.");
+    }
+
+    /// (For tests only.) Notifies the worker thread and waits for it to finish its job by polling
+    /// the migration progress.
+    #[cfg(test)]
+    pub fn wake_and_wait(&self, latest_version: Version) -> Result<()> {
+        self.wake(latest_version);
+
+        const TIMEOUT: Duration = Duration::from_secs(10);
+        let end = Instant::now() + TIMEOUT;
+        while Instant::now() < end {
+            let progress = self.progress();
+            if progress.migrated_before_version >= progress.target_before_version {
+                return Ok(());
+            }
+            sleep(Duration::from_millis(1));
+        }
+        anyhow::bail!("Timeout waiting for migrator worker.");
+    }
+}
+
+impl Drop for Migrator {
+    fn drop(&mut self) {
+        self.command_sender
+            .lock()
+            .expect("Locking command_sender should not fail.")
+            .send(Command::Quit)
+            .expect("Receiver should not destruct.");
+        self.worker_thread
+            .take()
+            .expect("Worker thread must exist.")
+            .join()
+            .expect("Worker thread should join peacefully.");
+    }
+}
+
+enum Command {
+    Quit,
+    Migrate,
+}
+
+struct Worker {
+    cold_store: Arc<ColdStore>,
+    target_before_version: Arc<AtomicU64>,
+    command_receiver: Receiver<Command>,
+}
+
+impl Worker {
+    fn work_loop(self) {
+        // Whether the migration stopped short of the target on an error, in which case it's not
+        // retried until being woken again.
+        let mut failed = false;
+        loop {
+            let target_before_version = self.target_before_version.load(Ordering::Relaxed);
+            let pending =
+                !failed && self.cold_store.migrated_before_version() < target_before_version;
+            // Migrate a batch before checking for commands again, in case `Command::Quit` is
+            // received; wait for a command if there's nothing to migrate.
+            let command = if pending {
+                self.command_receiver.try_recv().ok()
+            } else {
+                Some(
+                    self.command_receiver
+                        .recv()
+                        .expect("Sender should not destruct prematurely."),
+                )
+            };
+            match command {
+                Some(Command::Quit) => return,
+                Some(Command::Migrate) => {
+                    failed = false;
+                    continue;
+                }
+                None => (),
+            }
+
+            if let Err(e) = self
+                .cold_store
+                .migrate(target_before_version, Migrator::MAX_VERSIONS_PER_BATCH)
+            {
+                crit!("Error migrating to the cold storage. {:?}", e);
+                failed = true;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test;
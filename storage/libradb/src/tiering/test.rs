@@ -0,0 +1,158 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use super::*;
+use crate::{test_helper::arb_blocks_to_commit, LibraDB};
+use libra_temppath::TempPath;
+use libra_types::{ledger_info::LedgerInfoWithSignatures, transaction::TransactionToCommit};
+use proptest::prelude::*;
+use storage_interface::DbReader;
+
+fn open_tiered(tmp_dir: &TempPath, hot_window: u64) -> LibraDB {
+    LibraDB::open_with_cold_storage(
+        tmp_dir.path().join("hot"),
+        None, /* pruner */
+        ColdStorageConfig {
+            path: tmp_dir.path().join("cold"),
+            hot_window,
+        },
+    )
+    .unwrap()
+}
+
+fn save(db: &LibraDB, input: &[(Vec<TransactionToCommit>, LedgerInfoWithSignatures)]) -> Version {
+    let mut cur_ver = 0;
+    for (txns_to_commit, ledger_info_with_sigs) in input {
+        db.save_transactions(&txns_to_commit, cur_ver, Some(ledger_info_with_sigs))
+            .unwrap();
+        cur_ver += txns_to_commit.len() as u64;
+    }
+    cur_ver - 1
+}
+
+/// Checks that everything reads the same from `db` as from `expected_db`, which isn't tiered.
+fn verify_reads(
+    db: &LibraDB,
+    expected_db: &LibraDB,
+    input: &[(Vec<TransactionToCommit>, LedgerInfoWithSignatures)],
+    latest_version: Version,
+) {
+    assert_eq!(
+        db.get_transactions(0, latest_version + 1, latest_version, true)
+            .unwrap(),
+        expected_db
+            .get_transactions(0, latest_version + 1, latest_version, true)
+            .unwrap(),
+    );
+    let addresses: Vec<_> = input
+        .iter()
+        .flat_map(|(txns_to_commit, _)| txns_to_commit)
+        .flat_map(|txn_to_commit| txn_to_commit.account_states().keys().cloned())
+        .collect();
+    for version in 0..=latest_version {
+        assert_eq!(
+            db.transaction_store.get_transaction(version).unwrap(),
+            expected_db
+                .transaction_store
+                .get_transaction(version)
+                .unwrap(),
+        );
+        assert_eq!(
+            db.event_store.get_events_by_version(version).unwrap(),
+            expected_db
+                .event_store
+                .get_events_by_version(version)
+                .unwrap(),
+        );
+        assert_eq!(
+            db.get_account_states_batch(&addresses, version).unwrap(),
+            expected_db
+                .get_account_states_batch(&addresses, version)
+                .unwrap(),
+        );
+        assert_eq!(
+            db.state_store.get_root_hash(version).unwrap(),
+            expected_db.state_store.get_root_hash(version).unwrap(),
+        );
+    }
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(10))]
+
+    #[test]
+    fn test_migrate_and_read(input in arb_blocks_to_commit(), hot_window in 0u64..5) {
+        let expected_tmp_dir = TempPath::new();
+        let expected_db = LibraDB::new_for_test(&expected_tmp_dir);
+        let latest_version = save(&expected_db, &input);
+
+        let tmp_dir = TempPath::new();
+        {
+            let db = open_tiered(&tmp_dir, hot_window);
+            save(&db, &input);
+            db.migrator.as_ref().unwrap().wake_and_wait(latest_version).unwrap();
+
+            let target_before_version = latest_version.saturating_sub(hot_window);
+            prop_assert_eq!(
+                db.tiering_progress().unwrap(),
+                TieringProgress {
+                    migrated_before_version: target_before_version,
+                    target_before_version,
+                }
+            );
+            // What's migrated is gone from the hot DB.
+            for version in 0..=latest_version {
+                prop_assert_eq!(
+                    db.db.get::<TransactionSchema>(&version).unwrap().is_some(),
+                    version >= target_before_version
+                );
+            }
+            verify_reads(&db, &expected_db, &input, latest_version);
+        }
+
+        // The progress is picked up after reopening.
+        let db = open_tiered(&tmp_dir, hot_window);
+        prop_assert_eq!(
+            db.tiering_progress().unwrap().migrated_before_version,
+            latest_version.saturating_sub(hot_window)
+        );
+        verify_reads(&db, &expected_db, &input, latest_version);
+    }
+}
+
+#[test]
+fn test_get_falling_through() {
+    let tmp_dir = TempPath::new();
+    let hot_db = Arc::new(
+        DB::open(
+            tmp_dir.path().join("hot"),
+            "hot",
+            ColdStore::column_families(),
+        )
+        .unwrap(),
+    );
+    let cold_store = ColdStore::open(&tmp_dir.path().join("cold"), Arc::clone(&hot_db)).unwrap();
+    assert_eq!(cold_store.migrated_before_version(), 0);
+
+    hot_db
+        .put::<TieringProgressSchema>(&TieringProgressTag::MigratedBeforeVersion, &1)
+        .unwrap();
+    cold_store
+        .db()
+        .put::<TieringProgressSchema>(&TieringProgressTag::LastBatchStartVersion, &2)
+        .unwrap();
+    let get = |tag| {
+        get_falling_through::<TieringProgressSchema>(&hot_db, Some(&cold_store), &tag).unwrap()
+    };
+    assert_eq!(get(TieringProgressTag::MigratedBeforeVersion), Some(1));
+    assert_eq!(get(TieringProgressTag::LastBatchStartVersion), Some(2));
+    assert_eq!(
+        get_falling_through::<TieringProgressSchema>(
+            &hot_db,
+            None,
+            &TieringProgressTag::LastBatchStartVersion
+        )
+        .unwrap(),
+        None
+    );
+}
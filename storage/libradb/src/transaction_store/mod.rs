@@ -7,6 +7,7 @@ use crate::{
     change_set::ChangeSet,
     errors::LibraDbError,
     schema::{transaction::TransactionSchema, transaction_by_account::TransactionByAccountSchema},
+    tiering::{self, ColdStore},
 };
 use anyhow::{ensure, format_err, Result};
 use libra_types::{
@@ -19,11 +20,13 @@ use std::sync::Arc;
 
 pub(crate) struct TransactionStore {
     db: Arc<DB>,
+    /// Where historical transactions are moved to, if tiering is on.
+    cold_store: Option<Arc<ColdStore>>,
 }
 
 impl TransactionStore {
-    pub fn new(db: Arc<DB>) -> Self {
-        Self { db }
+    pub fn new(db: Arc<DB>, cold_store: Option<Arc<ColdStore>>) -> Self {
+        Self { db, cold_store }
     }
 
     /// Gets the version of a transaction by the sender `address` and `sequence_number`.
@@ -47,9 +50,12 @@ impl TransactionStore {
 
     /// Get signed transaction given `version`
     pub fn get_transaction(&self, version: Version) -> Result<Transaction> {
-        self.db
-            .get::<TransactionSchema>(&version)?
-            .ok_or_else(|| LibraDbError::NotFound(format!("Txn {}", version)).into())
+        tiering::get_falling_through::<TransactionSchema>(
+            &self.db,
+            self.cold_store.as_deref(),
+            &version,
+        )?
+        .ok_or_else(|| LibraDbError::NotFound(format!("Txn {}", version)).into())
     }
 
     /// Gets an iterator that yields `num_transactions` transactions starting from `start_version`.
@@ -60,8 +66,26 @@ impl TransactionStore {
     ) -> Result<TransactionIter> {
         let mut iter = self.db.iter::<TransactionSchema>(Default::default())?;
         iter.seek(&start_version)?;
+        // The hot iterator is created before the migration progress is checked, so that what it
+        // sees can't be deleted by a migration in between.
+        let mut hot_iter = None;
+        if let Some(cold_store) = &self.cold_store {
+            let migrated_before_version = cold_store.migrated_before_version();
+            if start_version < migrated_before_version {
+                let mut cold_iter = cold_store
+                    .db()
+                    .iter::<TransactionSchema>(Default::default())?;
+                cold_iter.seek(&start_version)?;
+                iter.seek(&migrated_before_version)?;
+                hot_iter = Some((
+                    migrated_before_version,
+                    std::mem::replace(&mut iter, cold_iter),
+                ));
+            }
+        }
         Ok(TransactionIter {
             inner: iter,
+            hot_inner: hot_iter,
             expected_next_version: start_version,
             end_version: start_version
                 .checked_add(num_transactions as u64)
@@ -82,7 +106,21 @@ impl TransactionStore {
         // each block.
         let mut iter = self.db.rev_iter::<TransactionSchema>(Default::default())?;
         iter.seek(&version)?;
-        for res in iter.take(MAX_VERSIONS_TO_SEARCH) {
+        // Going back past what's in the hot DB, carry on in the cold DB.
+        let cold_iter = match &self.cold_store {
+            Some(cold_store) => {
+                let mut cold_iter = cold_store
+                    .db()
+                    .rev_iter::<TransactionSchema>(Default::default())?;
+                cold_iter.seek(&version)?;
+                Some(cold_iter)
+            }
+            None => None,
+        };
+        for res in iter
+            .chain(cold_iter.into_iter().flatten())
+            .take(MAX_VERSIONS_TO_SEARCH)
+        {
             let (v, txn) = res?;
             if let Transaction::BlockMetadata(block_meta) = txn {
                 return Ok(Some((v, block_meta)));
@@ -115,6 +153,9 @@ impl TransactionStore {
 
 pub struct TransactionIter<'a> {
     inner: SchemaIterator<'a, TransactionSchema>,
+    /// When the range starts in the cold DB, `inner` iterates it, and this is switched to from
+    /// the version in it on.
+    hot_inner: Option<(Version, SchemaIterator<'a, TransactionSchema>)>,
     expected_next_version: Version,
     end_version: Version,
 }
@@ -124,6 +165,12 @@ impl<'a> TransactionIter<'a> {
         if self.expected_next_version >= self.end_version {
             return Ok(None);
         }
+        match self.hot_inner.take() {
+            Some((switch_version, hot_inner)) if self.expected_next_version >= switch_version => {
+                self.inner = hot_inner;
+            }
+            hot_inner => self.hot_inner = hot_inner,
+        }
 
         let ret = match self.inner.next().transpose()? {
             Some((version, transaction)) => {
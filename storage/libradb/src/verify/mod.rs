@@ -0,0 +1,330 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! This file defines `DbVerifier`, which reads through the DB checking that what's stored is
+//! consistent with the hashes committing to it, to detect corruption on disk that would otherwise
+//! go unnoticed until the data is read.
+
+use crate::{
+    ledger_store::Accumulator, schema::ledger_info::LedgerInfoSchema, LibraDB, PrunedData,
+};
+use anyhow::Result;
+use jellyfish_merkle::{
+    node_type::{Node, NodeKey},
+    TreeReader,
+};
+use libra_crypto::{
+    hash::{CryptoHash, EventAccumulatorHasher, TransactionAccumulatorHasher},
+    HashValue,
+};
+use libra_types::{
+    ledger_info::LedgerInfoWithSignatures, proof::accumulator::InMemoryAccumulator,
+    transaction::Version,
+};
+use schemadb::ReadOptions;
+use std::{collections::BTreeMap, fmt};
+
+/// Something in the DB that doesn't agree with what commits to it.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Inconsistency {
+    /// The version of the transaction, or of the state tree, it's found at.
+    pub version: Version,
+    pub description: String,
+}
+
+impl fmt::Display for Inconsistency {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "At version {}: {}", self.version, self.description)
+    }
+}
+
+/// Checks the internal consistency of a `LibraDB`:
+///   - transactions and events against the hashes in their `TransactionInfo`s,
+///   - the transaction accumulator against the root hashes in the stored LedgerInfos,
+///   - the state tree, node by node, against the state root hashes in the `TransactionInfo`s.
+///
+/// Transactions are verified range by range, so that a long verification can be resumed from
+/// where it stopped. Problems found are collected instead of failing the verification.
+pub struct DbVerifier<'a> {
+    db: &'a LibraDB,
+    inconsistencies: Vec<Inconsistency>,
+}
+
+impl<'a> DbVerifier<'a> {
+    pub fn new(db: &'a LibraDB) -> Self {
+        Self {
+            db,
+            inconsistencies: Vec::new(),
+        }
+    }
+
+    pub fn inconsistencies(&self) -> &[Inconsistency] {
+        &self.inconsistencies
+    }
+
+    /// Verifies the transactions at versions [`start_version`, `end_version`), along with their
+    /// events, the state root hashes at these versions, and the transaction accumulator up to
+    /// them.
+    ///
+    /// The accumulator is picked up from what's stored at `start_version`, which is trusted to be
+    /// verified by an earlier call if not 0. What's been pruned is skipped, and what's missing
+    /// otherwise is reported.
+    pub fn verify_transactions(
+        &mut self,
+        start_version: Version,
+        end_version: Version,
+    ) -> Result<()> {
+        let start_version = std::cmp::max(
+            start_version,
+            self.db.least_readable_version(PrunedData::Transactions)?,
+        );
+        if start_version >= end_version {
+            return Ok(());
+        }
+        let least_readable_event_version = self.db.least_readable_version(PrunedData::Events)?;
+
+        let ledger_infos = match self.get_ledger_infos(start_version, end_version) {
+            Ok(ledger_infos) => ledger_infos,
+            Err(err) => {
+                self.report(
+                    start_version,
+                    format!("failed reading LedgerInfos: {}", err),
+                );
+                BTreeMap::new()
+            }
+        };
+        let mut accumulator = self.stored_accumulator(start_version);
+        for version in start_version..end_version {
+            let txn_info = match self.db.ledger_store.get_transaction_info(version) {
+                Ok(txn_info) => txn_info,
+                Err(err) => {
+                    // Without the TransactionInfo, the accumulator can only be checked after it.
+                    self.report(version, format!("failed reading TransactionInfo: {}", err));
+                    accumulator = self.stored_accumulator(version + 1);
+                    continue;
+                }
+            };
+
+            let res = self.db.transaction_store.get_transaction(version);
+            self.check(
+                version,
+                "transaction",
+                res.map(|txn| txn.hash()),
+                txn_info.transaction_hash(),
+            );
+
+            if version >= least_readable_event_version {
+                let res = self
+                    .db
+                    .event_store
+                    .get_events_by_version(version)
+                    .map(|events| {
+                        let event_hashes: Vec<_> = events.iter().map(CryptoHash::hash).collect();
+                        InMemoryAccumulator::<EventAccumulatorHasher>::from_leaves(&event_hashes)
+                            .root_hash()
+                    });
+                self.check(version, "event root", res, txn_info.event_root_hash());
+            }
+
+            // The state tree at old versions can be pruned.
+            match self.db.state_store.get_root_hash_option(version) {
+                Ok(None) => (),
+                res => self.check(
+                    version,
+                    "state root",
+                    res.map(|hash| hash.expect("Checked above.")),
+                    txn_info.state_root_hash(),
+                ),
+            }
+
+            accumulator = accumulator.map(|accumulator| accumulator.append(&[txn_info.hash()]));
+            if let (Some(accumulator), Some(ledger_info)) =
+                (&accumulator, ledger_infos.get(&version))
+            {
+                let root_hash = accumulator.root_hash();
+                self.check(
+                    version,
+                    "transaction accumulator root",
+                    Ok(root_hash),
+                    ledger_info.ledger_info().transaction_accumulator_hash(),
+                );
+            }
+        }
+
+        if let Some(accumulator) = accumulator {
+            let stored_accumulator = self
+                .get_stored_accumulator(end_version)
+                .map(|stored| stored.root_hash());
+            self.check(
+                end_version - 1,
+                "stored transaction accumulator root",
+                stored_accumulator,
+                accumulator.root_hash(),
+            );
+        }
+
+        Ok(())
+    }
+
+    /// Verifies every node of the state tree at `version`, and its root hash against the
+    /// `TransactionInfo` at `version`. Returns the number of nodes visited.
+    pub fn verify_state(&mut self, version: Version) -> Result<usize> {
+        let expected_root_hash = match self.db.ledger_store.get_transaction_info(version) {
+            Ok(txn_info) => txn_info.state_root_hash(),
+            Err(err) => {
+                self.report(version, format!("failed reading TransactionInfo: {}", err));
+                return Ok(0);
+            }
+        };
+
+        let mut num_nodes = 0;
+        let mut to_visit = vec![(NodeKey::new_empty_path(version), expected_root_hash, None)];
+        while let Some((node_key, expected_hash, expected_is_leaf)) = to_visit.pop() {
+            num_nodes += 1;
+            let node = match self.db.state_store.get_node(&node_key) {
+                Ok(node) => node,
+                Err(err) => {
+                    self.report(
+                        version,
+                        format!("failed reading node {:?}: {}", node_key, err),
+                    );
+                    continue;
+                }
+            };
+            if node.hash() != expected_hash {
+                self.report(
+                    version,
+                    format!(
+                        "node {:?} hash mismatch, stored: {}, expected: {}",
+                        node_key,
+                        node.hash(),
+                        expected_hash
+                    ),
+                );
+            }
+            if expected_is_leaf.map_or(false, |is_leaf| is_leaf != node.is_leaf()) {
+                self.report(version, format!("node {:?} leaf flag mismatch", node_key));
+            }
+
+            match node {
+                Node::Null => (),
+                Node::Internal(internal_node) => {
+                    for i in (0..16u8).rev() {
+                        if let Some(child) = internal_node.child(i.into()) {
+                            to_visit.push((
+                                node_key.gen_child_node_key(child.version, i.into()),
+                                child.hash,
+                                Some(child.is_leaf),
+                            ));
+                        }
+                    }
+                }
+                Node::Leaf(leaf_node) => {
+                    let key = leaf_node.account_key();
+                    let on_path = node_key
+                        .nibble_path()
+                        .nibbles()
+                        .enumerate()
+                        .all(|(i, nibble)| key.get_nibble(i) == nibble);
+                    if !on_path {
+                        self.report(
+                            version,
+                            format!("leaf {:?} with key {} off its path", node_key, key),
+                        );
+                    }
+                }
+            }
+        }
+
+        Ok(num_nodes)
+    }
+}
+
+impl<'a> DbVerifier<'a> {
+    /// Returns the stored LedgerInfos with versions in [`start_version`, `end_version`).
+    fn get_ledger_infos(
+        &self,
+        start_version: Version,
+        end_version: Version,
+    ) -> Result<BTreeMap<Version, LedgerInfoWithSignatures>> {
+        let mut iter = self
+            .db
+            .db
+            .iter::<LedgerInfoSchema>(ReadOptions::default())?;
+        iter.seek(&self.db.ledger_store.get_epoch(start_version)?)?;
+
+        let mut ledger_infos = BTreeMap::new();
+        for res in iter {
+            let (_epoch, ledger_info) = res?;
+            let version = ledger_info.ledger_info().version();
+            if version >= end_version {
+                break;
+            }
+            if version >= start_version {
+                ledger_infos.insert(version, ledger_info);
+            }
+        }
+        Ok(ledger_infos)
+    }
+
+    /// The transaction accumulator of the first `num_leaves` `TransactionInfo`s, as stored.
+    fn get_stored_accumulator(
+        &self,
+        num_leaves: u64,
+    ) -> Result<InMemoryAccumulator<TransactionAccumulatorHasher>> {
+        InMemoryAccumulator::new(
+            Accumulator::get_frozen_subtree_hashes(&self.db.ledger_store, num_leaves)?,
+            num_leaves,
+        )
+    }
+
+    /// Like `get_stored_accumulator`, reporting the failure to read it.
+    fn stored_accumulator(
+        &mut self,
+        num_leaves: u64,
+    ) -> Option<InMemoryAccumulator<TransactionAccumulatorHasher>> {
+        match self.get_stored_accumulator(num_leaves) {
+            Ok(accumulator) => Some(accumulator),
+            Err(err) => {
+                self.report(
+                    num_leaves,
+                    format!("failed reading transaction accumulator: {}", err),
+                );
+                None
+            }
+        }
+    }
+
+    fn check(
+        &mut self,
+        version: Version,
+        what: &str,
+        actual: Result<HashValue>,
+        expected: HashValue,
+    ) {
+        match actual {
+            Ok(actual) => {
+                if actual != expected {
+                    self.report(
+                        version,
+                        format!(
+                            "{} hash mismatch, stored: {}, expected: {}",
+                            what, actual, expected
+                        ),
+                    );
+                }
+            }
+            Err(err) => self.report(version, format!("failed reading {}: {}", what, err)),
+        }
+    }
+
+    fn report(&mut self, version: Version, description: String) {
+        self.inconsistencies.push(Inconsistency {
+            version,
+            description,
+        });
+    }
+}
+
+#[cfg(test)]
+mod test;
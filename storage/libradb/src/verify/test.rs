@@ -0,0 +1,117 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use super::*;
+use crate::{
+    schema::{
+        jellyfish_merkle_node::JellyfishMerkleNodeSchema, transaction::TransactionSchema,
+        transaction_info::TransactionInfoSchema,
+    },
+    test_helper::arb_blocks_to_commit,
+};
+use libra_temppath::TempPath;
+use libra_types::{
+    account_address::AccountAddress,
+    account_state_blob::AccountStateBlob,
+    block_metadata::BlockMetadata,
+    transaction::{Transaction, TransactionToCommit},
+};
+use proptest::prelude::*;
+use schemadb::SchemaBatch;
+
+fn save(db: &LibraDB, input: &[(Vec<TransactionToCommit>, LedgerInfoWithSignatures)]) -> Version {
+    let mut cur_ver = 0;
+    for (txns_to_commit, ledger_info_with_sigs) in input {
+        db.save_transactions(&txns_to_commit, cur_ver, Some(ledger_info_with_sigs))
+            .unwrap();
+        cur_ver += txns_to_commit.len() as u64;
+    }
+    cur_ver
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(10))]
+
+    #[test]
+    fn test_verify_consistent(input in arb_blocks_to_commit(), split in any::<prop::sample::Index>()) {
+        let tmp_dir = TempPath::new();
+        let db = LibraDB::new_for_test(&tmp_dir);
+        let num_txns = save(&db, &input);
+
+        let mut verifier = DbVerifier::new(&db);
+        verifier.verify_transactions(0, num_txns).unwrap();
+        prop_assert!(verifier.verify_state(num_txns - 1).unwrap() > 0);
+        prop_assert_eq!(verifier.inconsistencies(), &[]);
+
+        // Resuming from anywhere.
+        let split = split.index(num_txns as usize) as Version;
+        let mut verifier = DbVerifier::new(&db);
+        verifier.verify_transactions(0, split).unwrap();
+        verifier.verify_transactions(split, num_txns).unwrap();
+        prop_assert_eq!(verifier.inconsistencies(), &[]);
+    }
+
+    #[test]
+    fn test_verify_corrupt_transaction(input in arb_blocks_to_commit()) {
+        let tmp_dir = TempPath::new();
+        let db = LibraDB::new_for_test(&tmp_dir);
+        let num_txns = save(&db, &input);
+        let other_txn = Transaction::BlockMetadata(BlockMetadata::new(
+            HashValue::random(),
+            1,
+            1,
+            vec![],
+            AccountAddress::random(),
+        ));
+        db.db.put::<TransactionSchema>(&0, &other_txn).unwrap();
+
+        let mut verifier = DbVerifier::new(&db);
+        verifier.verify_transactions(0, num_txns).unwrap();
+        let inconsistencies = verifier.inconsistencies();
+        prop_assert_eq!(inconsistencies.len(), 1);
+        prop_assert_eq!(inconsistencies[0].version, 0);
+    }
+
+    #[test]
+    fn test_verify_missing_transaction_info(input in arb_blocks_to_commit()) {
+        let tmp_dir = TempPath::new();
+        let db = LibraDB::new_for_test(&tmp_dir);
+        let num_txns = save(&db, &input);
+        let mut batch = SchemaBatch::new();
+        batch.delete::<TransactionInfoSchema>(&0).unwrap();
+        db.db.write_schemas(batch).unwrap();
+
+        // Reported, and the accumulator is checked from the next version on.
+        let mut verifier = DbVerifier::new(&db);
+        verifier.verify_transactions(0, num_txns).unwrap();
+        let inconsistencies = verifier.inconsistencies();
+        prop_assert_eq!(inconsistencies.len(), 1);
+        prop_assert_eq!(inconsistencies[0].version, 0);
+
+        let mut verifier = DbVerifier::new(&db);
+        prop_assert_eq!(verifier.verify_state(0).unwrap(), 0);
+        prop_assert_eq!(verifier.inconsistencies().len(), 1);
+    }
+
+    #[test]
+    fn test_verify_corrupt_state(input in arb_blocks_to_commit()) {
+        let tmp_dir = TempPath::new();
+        let db = LibraDB::new_for_test(&tmp_dir);
+        let version = save(&db, &input) - 1;
+        db.db
+            .put::<JellyfishMerkleNodeSchema>(
+                &NodeKey::new_empty_path(version),
+                &Node::new_leaf(HashValue::random(), AccountStateBlob::from(vec![1])),
+            )
+            .unwrap();
+
+        let mut verifier = DbVerifier::new(&db);
+        verifier.verify_transactions(0, version + 1).unwrap();
+        prop_assert!(!verifier.inconsistencies().is_empty());
+        prop_assert!(verifier.inconsistencies().iter().all(|i| i.version == version));
+
+        let mut verifier = DbVerifier::new(&db);
+        verifier.verify_state(version).unwrap();
+        prop_assert_eq!(verifier.inconsistencies().len(), 1);
+    }
+}
@@ -21,6 +21,7 @@ use anyhow::{ensure, format_err, Result};
 use libra_metrics::OpMetrics;
 use once_cell::sync::Lazy;
 use std::{
+    cell::Cell,
     collections::{BTreeMap, HashMap, HashSet},
     iter::Iterator,
     marker::PhantomData,
@@ -29,6 +30,21 @@ use std::{
 
 static OP_COUNTER: Lazy<OpMetrics> = Lazy::new(|| OpMetrics::new_and_registered("schemadb"));
 
+thread_local! {
+    static NUM_RECORDS_READ: Cell<u64> = Cell::new(0);
+}
+
+/// Returns the number of records read by the current thread so far, through `DB::get` and
+/// iterators of any DB. The difference between two calls tells how many records the thread read
+/// in between.
+pub fn num_records_read_by_thread() -> u64 {
+    NUM_RECORDS_READ.with(|num| num.get())
+}
+
+fn inc_num_records_read() {
+    NUM_RECORDS_READ.with(|num| num.set(num.get() + 1));
+}
+
 /// Type alias to `rocksdb::ReadOptions`. See [`rocksdb doc`](https://github.com/pingcap/rust-rocksdb/blob/master/src/rocksdb_options.rs)
 pub type ReadOptions = rocksdb::ReadOptions;
 
@@ -82,6 +98,21 @@ impl SchemaBatch {
     }
 }
 
+/// A [`SchemaBatch`] serialized into a RocksDB write batch against the column families of a `DB`,
+/// so that the work is done ahead of writing it with [`DB::write_prepared`].
+pub struct PreparedBatch {
+    inner: rocksdb::WriteBatch,
+    /// For each row, the column family and the size of key and value, or `None` for a deletion.
+    row_stats: Vec<(ColumnFamilyName, Option<usize>)>,
+}
+
+impl PreparedBatch {
+    /// Number of bytes the batch takes serialized.
+    pub fn size_in_bytes(&self) -> usize {
+        self.inner.size_in_bytes()
+    }
+}
+
 pub enum ScanDirection {
     Forward,
     Backward,
@@ -150,6 +181,7 @@ where
 
         let raw_key = self.db_iter.key().expect("Iterator must be valid.");
         let raw_value = self.db_iter.value().expect("Iterator must be valid.");
+        inc_num_records_read();
         OP_COUNTER.observe(
             &format!("db_iter_bytes_{}", S::COLUMN_FAMILY_NAME),
             (raw_key.len() + raw_value.len()) as f64,
@@ -278,6 +310,7 @@ impl DB {
         let cf_handle = self.get_cf_handle(S::COLUMN_FAMILY_NAME)?;
 
         let result = self.inner.get_cf(cf_handle, &k)?;
+        inc_num_records_read();
         OP_COUNTER.observe(
             &format!("db_get_bytes_{}", S::COLUMN_FAMILY_NAME),
             result.as_ref().map_or(0.0, |v| v.len() as f64),
@@ -287,6 +320,45 @@ impl DB {
             .transpose()
     }
 
+    /// Reads the values of many keys with one RocksDB multi-get, in the same order as
+    /// `schema_keys`.
+    ///
+    /// RocksDB doesn't tell a missing key from an empty value here, so both come back as `None`.
+    /// Only use this with schemas whose encoded values are never empty.
+    pub fn multi_get<S: Schema>(&self, schema_keys: &[S::Key]) -> Result<Vec<Option<S::Value>>> {
+        let _timer = OP_COUNTER.timer(&format!("db_multi_get_time_{}", S::COLUMN_FAMILY_NAME));
+        let cf_handle = self.get_cf_handle(S::COLUMN_FAMILY_NAME)?;
+        let keys = schema_keys
+            .iter()
+            .map(|key| <S::Key as KeyCodec<S>>::encode_key(key))
+            .collect::<Result<Vec<_>>>()?;
+
+        let results = self
+            .inner
+            .multi_get_cf(keys.iter().map(|key| (cf_handle, key)))?;
+        ensure!(
+            results.len() == keys.len(),
+            "Expected {} values, got {}.",
+            keys.len(),
+            results.len(),
+        );
+        results
+            .into_iter()
+            .map(|raw_value| {
+                inc_num_records_read();
+                OP_COUNTER.observe(
+                    &format!("db_get_bytes_{}", S::COLUMN_FAMILY_NAME),
+                    raw_value.len() as f64,
+                );
+                if raw_value.is_empty() {
+                    Ok(None)
+                } else {
+                    <S::Value as ValueCodec<S>>::decode_value(&raw_value).map(Some)
+                }
+            })
+            .collect()
+    }
+
     /// Writes single record.
     pub fn put<S: Schema>(&self, key: &S::Key, value: &S::Value) -> Result<()> {
         // Not necessary to use a batch, but we'd like a central place to bump OP_COUNTERS.
@@ -338,31 +410,55 @@ impl DB {
 
     /// Writes a group of records wrapped in a [`SchemaBatch`].
     pub fn write_schemas(&self, batch: SchemaBatch) -> Result<()> {
-        let _timer = OP_COUNTER.timer(&format!("db_batch_commit_time_{}", self.name));
+        self.write_prepared(self.prepare_batch(batch)?, true /* sync */)
+    }
+
+    /// Serializes `batch` for [`DB::write_prepared`], which can be done without holding up
+    /// other writes.
+    pub fn prepare_batch(&self, batch: SchemaBatch) -> Result<PreparedBatch> {
         let mut db_batch = rocksdb::WriteBatch::default();
+        let mut row_stats = Vec::new();
         for (cf_name, rows) in &batch.rows {
             let cf_handle = self.get_cf_handle(cf_name)?;
             for (key, write_op) in rows {
                 match write_op {
-                    WriteOp::Value(value) => db_batch.put_cf(cf_handle, key, value),
-                    WriteOp::Deletion => db_batch.delete_cf(cf_handle, key),
+                    WriteOp::Value(value) => {
+                        db_batch.put_cf(cf_handle, key, value);
+                        row_stats.push((*cf_name, Some(key.len() + value.len())));
+                    }
+                    WriteOp::Deletion => {
+                        db_batch.delete_cf(cf_handle, key);
+                        row_stats.push((*cf_name, None));
+                    }
                 }
             }
         }
-        let serialized_size = db_batch.size_in_bytes();
+        Ok(PreparedBatch {
+            inner: db_batch,
+            row_stats,
+        })
+    }
+
+    /// Writes a batch prepared by [`DB::prepare_batch`].
+    ///
+    /// Without `sync`, the write goes to the write-ahead log without waiting for it to be synced
+    /// to disk: it survives the process crashing, but not the machine, until a later write with
+    /// `sync` syncs the log, including everything written before.
+    pub fn write_prepared(&self, batch: PreparedBatch, sync: bool) -> Result<()> {
+        let _timer = OP_COUNTER.timer(&format!("db_batch_commit_time_{}", self.name));
+        let serialized_size = batch.size_in_bytes();
 
-        self.inner.write_opt(db_batch, &default_write_options())?;
+        let mut write_options = default_write_options();
+        write_options.set_sync(sync);
+        self.inner.write_opt(batch.inner, &write_options)?;
 
         // Bump counters only after DB write succeeds.
-        for (cf_name, rows) in &batch.rows {
-            for (key, write_op) in rows {
-                match write_op {
-                    WriteOp::Value(value) => OP_COUNTER.observe(
-                        &format!("db_put_bytes_{}", cf_name),
-                        (key.len() + value.len()) as f64,
-                    ),
-                    WriteOp::Deletion => OP_COUNTER.inc(&format!("db_delete_{}", cf_name)),
+        for (cf_name, put_size) in &batch.row_stats {
+            match put_size {
+                Some(size) => {
+                    OP_COUNTER.observe(&format!("db_put_bytes_{}", cf_name), *size as f64)
                 }
+                None => OP_COUNTER.inc(&format!("db_delete_{}", cf_name)),
             }
         }
         OP_COUNTER.observe(
@@ -414,9 +510,9 @@ impl DB {
     }
 }
 
-/// For now we always use synchronous writes. This makes sure that once the operation returns
-/// `Ok(())` the data is persisted even if the machine crashes. In the future we might consider
-/// selectively turning this off for some non-critical writes to improve performance.
+/// By default we use synchronous writes. This makes sure that once the operation returns `Ok(())`
+/// the data is persisted even if the machine crashes. `DB::write_prepared` can turn it off for
+/// writes that can be synced later to improve performance.
 fn default_write_options() -> rocksdb::WriteOptions {
     let mut opts = rocksdb::WriteOptions::default();
     opts.set_sync(true);
@@ -338,3 +338,91 @@ fn test_report_size() {
     assert!(*cf_sizes.get("TestCF2").unwrap() > 0);
     assert_eq!(*cf_sizes.get("default").unwrap(), 0);
 }
+
+#[test]
+fn test_num_records_read() {
+    let db = TestDB::new();
+    for i in 0..3 {
+        db.put::<TestSchema1>(&TestField(i), &TestField(i)).unwrap();
+    }
+
+    let before = schemadb::num_records_read_by_thread();
+    db.get::<TestSchema1>(&TestField(0)).unwrap();
+    db.get::<TestSchema1>(&TestField(3)).unwrap();
+    assert_eq!(schemadb::num_records_read_by_thread() - before, 2);
+
+    let before = schemadb::num_records_read_by_thread();
+    let mut iter = db.iter::<TestSchema1>(Default::default()).unwrap();
+    iter.seek_to_first();
+    assert_eq!(iter.count(), 3);
+    assert_eq!(schemadb::num_records_read_by_thread() - before, 3);
+}
+
+#[test]
+fn test_multi_get() {
+    let db = TestDB::new();
+    for i in 0..3 {
+        db.put::<TestSchema1>(&TestField(i), &TestField(i + 10))
+            .unwrap();
+    }
+
+    let before = schemadb::num_records_read_by_thread();
+    let values = db
+        .multi_get::<TestSchema1>(&[TestField(2), TestField(5), TestField(0), TestField(2)])
+        .unwrap();
+    assert_eq!(
+        values,
+        vec![
+            Some(TestField(12)),
+            None,
+            Some(TestField(10)),
+            Some(TestField(12)),
+        ]
+    );
+    assert_eq!(schemadb::num_records_read_by_thread() - before, 4);
+
+    assert!(db.multi_get::<TestSchema1>(&[]).unwrap().is_empty());
+    assert_eq!(
+        db.multi_get::<TestSchema2>(&[TestField(0)]).unwrap(),
+        vec![None]
+    );
+}
+
+#[test]
+fn test_write_prepared() {
+    let db = TestDB::new();
+
+    let mut batch1 = SchemaBatch::new();
+    batch1
+        .put::<TestSchema1>(&TestField(0), &TestField(0))
+        .unwrap();
+    batch1
+        .put::<TestSchema2>(&TestField(1), &TestField(1))
+        .unwrap();
+    let mut batch2 = SchemaBatch::new();
+    batch2.delete::<TestSchema2>(&TestField(1)).unwrap();
+    batch2
+        .put::<TestSchema2>(&TestField(2), &TestField(2))
+        .unwrap();
+
+    let prepared1 = db.prepare_batch(batch1).unwrap();
+    let prepared2 = db.prepare_batch(batch2).unwrap();
+    assert!(prepared1.size_in_bytes() > 0);
+    // Nothing is written until the prepared batches are.
+    assert_eq!(collect_values::<TestSchema1>(&db), vec![]);
+
+    db.write_prepared(prepared1, false /* sync */).unwrap();
+    assert_eq!(
+        collect_values::<TestSchema2>(&db),
+        gen_expected_values(&[(1, 1)]),
+    );
+    db.write_prepared(prepared2, true /* sync */).unwrap();
+    assert_eq!(
+        collect_values::<TestSchema1>(&db),
+        gen_expected_values(&[(0, 0)]),
+    );
+    assert_eq!(
+        collect_values::<TestSchema2>(&db),
+        gen_expected_values(&[(2, 2)]),
+    );
+}
@@ -9,7 +9,7 @@ use libra_secure_net::NetworkClient;
 use libra_types::{
     account_address::AccountAddress,
     account_state_blob::{AccountStateBlob, AccountStateWithProof},
-    contract_event::ContractEvent,
+    contract_event::{ContractEvent, EventWithProof},
     epoch_change::EpochChangeProof,
     event::EventKey,
     ledger_info::LedgerInfoWithSignatures,
@@ -126,6 +126,16 @@ impl DbReader for StorageClient {
         unimplemented!()
     }
 
+    fn get_events_with_proofs(
+        &self,
+        _key: &EventKey,
+        _start: u64,
+        _limit: u64,
+        _ledger_version: Version,
+    ) -> Result<Vec<EventWithProof>> {
+        unimplemented!()
+    }
+
     fn get_state_proof(
         &self,
         _known_version: u64,
@@ -9,7 +9,7 @@ use libra_types::{
     account_address::AccountAddress,
     account_state::AccountState,
     account_state_blob::{AccountStateBlob, AccountStateWithProof},
-    contract_event::ContractEvent,
+    contract_event::{ContractEvent, EventWithProof},
     epoch_change::EpochChangeProof,
     epoch_state::EpochState,
     event::EventKey,
@@ -158,6 +158,18 @@ pub trait DbReader: Send + Sync {
         limit: u64,
     ) -> Result<Vec<(u64, ContractEvent)>>;
 
+    /// See [`LibraDB::get_events_with_proofs`].
+    ///
+    /// [`LibraDB::get_events_with_proofs`]:
+    /// ../libradb/struct.LibraDB.html#method.get_events_with_proofs
+    fn get_events_with_proofs(
+        &self,
+        event_key: &EventKey,
+        start: u64,
+        limit: u64,
+        ledger_version: Version,
+    ) -> Result<Vec<EventWithProof>>;
+
     /// See [`LibraDB::get_block_timestamp`].
     ///
     /// [`LibraDB::get_block_timestamp`]:
@@ -242,6 +254,26 @@ pub trait DbReader: Send + Sync {
         version: Version,
     ) -> Result<(Option<AccountStateBlob>, SparseMerkleProof)>;
 
+    /// Gets the account states of many accounts at the same version, without proofs, in the same
+    /// order as `addresses`. See [`LibraDB::get_account_states_batch`].
+    ///
+    /// [`LibraDB::get_account_states_batch`]:
+    /// ../libradb/struct.LibraDB.html#method.get_account_states_batch
+    fn get_account_states_batch(
+        &self,
+        addresses: &[AccountAddress],
+        version: Version,
+    ) -> Result<Vec<Option<AccountStateBlob>>> {
+        addresses
+            .iter()
+            .map(|address| {
+                Ok(self
+                    .get_account_state_with_proof_by_version(*address, version)?
+                    .0)
+            })
+            .collect()
+    }
+
     /// See [`LibraDB::get_latest_state_root`].
     ///
     /// [`LibraDB::get_latest_state_root`]:
@@ -273,17 +305,14 @@ impl MoveStorage for &dyn DbReader {
             .map(|path| path.address)
             .collect();
 
-        let results = addresses
-            .iter()
-            .map(|addr| self.get_account_state_with_proof(*addr, version, version))
-            .collect::<Result<Vec<_>>>()?;
+        let results = self.get_account_states_batch(&addresses, version)?;
 
         // Account address --> AccountState
         let account_states = addresses
             .iter()
             .zip_eq(results)
             .map(|(addr, result)| {
-                let account_state = AccountState::try_from(&result.blob.ok_or_else(|| {
+                let account_state = AccountState::try_from(&result.ok_or_else(|| {
                     format_err!("missing blob in account state/account does not exist")
                 })?)?;
                 Ok((addr, account_state))
@@ -11,7 +11,7 @@ use libra_types::{
     account_config::AccountResource,
     account_state::AccountState,
     account_state_blob::{AccountStateBlob, AccountStateWithProof},
-    contract_event::ContractEvent,
+    contract_event::{ContractEvent, EventWithProof},
     epoch_change::EpochChangeProof,
     event::{EventHandle, EventKey},
     ledger_info::LedgerInfoWithSignatures,
@@ -54,6 +54,16 @@ impl DbReader for MockDbReader {
         unimplemented!()
     }
 
+    fn get_events_with_proofs(
+        &self,
+        _event_key: &EventKey,
+        _start: u64,
+        _limit: u64,
+        _ledger_version: Version,
+    ) -> Result<Vec<EventWithProof>> {
+        unimplemented!()
+    }
+
     fn get_block_timestamp(&self, _version: u64) -> Result<u64> {
         unimplemented!()
     }
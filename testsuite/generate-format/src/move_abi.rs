@@ -18,6 +18,7 @@ pub fn get_registry() -> Result<Registry> {
 
     // 2. Trace the main entry point(s) + every enum separately.
     tracer.trace_type::<transaction::ScriptABI>(&samples)?;
+    tracer.trace_type::<transaction::ScriptFunctionABI>(&samples)?;
     tracer.trace_type::<language_storage::TypeTag>(&samples)?;
     tracer.registry()
 }
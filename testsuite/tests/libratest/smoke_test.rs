@@ -40,6 +40,7 @@ use libra_types::{
 };
 use num_traits::cast::FromPrimitive;
 use rust_decimal::Decimal;
+use serde_json::json;
 use std::{
     collections::BTreeMap,
     convert::{TryFrom, TryInto},
@@ -837,6 +838,7 @@ fn test_external_transaction_signer() {
             gas_currency: p_gas_currency,
             max_gas_amount: p_max_gas_amount,
             script,
+            decoded_script,
             ..
         } => {
             assert_eq!(p_sender, sender_address.to_string());
@@ -870,6 +872,22 @@ fn test_external_transaction_signer() {
                 }
                 _ => panic!("Expected peer-to-peer script for user txn"),
             }
+            let decoded_script = decoded_script.expect("Expected decoded script for user txn");
+            assert_eq!(decoded_script.name, "peer_to_peer_with_metadata");
+            let arguments: Vec<_> = decoded_script
+                .arguments
+                .iter()
+                .map(|arg| (arg.name.as_str(), arg.value.clone()))
+                .collect();
+            assert_eq!(
+                arguments,
+                vec![
+                    ("payee", json!(receiver_address.to_string())),
+                    ("amount", json!(amount)),
+                    ("metadata", json!("")),
+                    ("metadata_signature", json!("")),
+                ]
+            );
         }
         _ => panic!("Query should get user transaction"),
     }
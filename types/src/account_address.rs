@@ -1,10 +1,262 @@
 // Copyright (c) The Libra Core Contributors
 // SPDX-License-Identifier: Apache-2.0
-use crate::transaction::authenticator::AuthenticationKey;
+use crate::{
+    chain_id::{ChainId, NamedChain},
+    transaction::authenticator::AuthenticationKey,
+};
+use anyhow::{bail, ensure, format_err, Error, Result};
 use libra_crypto::ed25519::Ed25519PublicKey;
+use std::{convert::TryFrom, fmt, iter, str::FromStr};
 
 pub use move_core_types::account_address::AccountAddress;
 
 pub fn from_public_key(public_key: &Ed25519PublicKey) -> AccountAddress {
     AuthenticationKey::ed25519(public_key).derived_address()
 }
+
+/// An account address together with the chain it belongs to, displayed in a checksummed bech32
+/// encoding whose human readable part identifies the chain, e.g. `tlb1...` on TESTNET. Unlike a
+/// raw hex address, an address encoded for one chain cannot be mistaken for an address of
+/// another chain.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct Bech32Address {
+    chain_id: ChainId,
+    address: AccountAddress,
+}
+
+impl Bech32Address {
+    pub fn new(chain_id: ChainId, address: AccountAddress) -> Self {
+        Self { chain_id, address }
+    }
+
+    pub fn chain_id(&self) -> ChainId {
+        self.chain_id
+    }
+
+    pub fn address(&self) -> AccountAddress {
+        self.address
+    }
+
+    /// Return the human readable part of the addresses of `chain_id`: a fixed prefix for each
+    /// reserved chain, and `lb<id>` for other chains.
+    pub fn human_readable_part(chain_id: ChainId) -> String {
+        match NamedChain::from_chain_id(&chain_id) {
+            Ok(NamedChain::MAINNET) => "lbr".to_string(),
+            Ok(NamedChain::PREMAINNET) => "plb".to_string(),
+            Ok(NamedChain::TESTNET) => "tlb".to_string(),
+            Ok(NamedChain::DEVNET) => "dlb".to_string(),
+            Ok(NamedChain::TESTING) => "xlb".to_string(),
+            Err(_) => format!("lb{}", chain_id.id()),
+        }
+    }
+
+    fn chain_id_of_human_readable_part(hrp: &str) -> Result<ChainId> {
+        let chain_id = match hrp {
+            "lbr" => ChainId::new(NamedChain::MAINNET.id()),
+            "plb" => ChainId::new(NamedChain::PREMAINNET.id()),
+            "tlb" => ChainId::new(NamedChain::TESTNET.id()),
+            "dlb" => ChainId::new(NamedChain::DEVNET.id()),
+            "xlb" => ChainId::new(NamedChain::TESTING.id()),
+            _ if hrp.starts_with("lb") => ChainId::new(
+                hrp[2..]
+                    .parse::<u8>()
+                    .map_err(|_| format_err!("Unknown address prefix {:?}", hrp))?,
+            ),
+            _ => bail!("Unknown address prefix {:?}", hrp),
+        };
+        // Reserved chains only have their own prefix, so that each address has one encoding.
+        ensure!(
+            Self::human_readable_part(chain_id) == hrp,
+            "Unknown address prefix {:?}",
+            hrp
+        );
+        Ok(chain_id)
+    }
+}
+
+impl fmt::Display for Bech32Address {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let data = regroup_bits(&self.address.to_vec(), 8, 5, true).map_err(|_| fmt::Error)?;
+        write!(
+            f,
+            "{}",
+            bech32_encode(&Self::human_readable_part(self.chain_id), &data)
+        )
+    }
+}
+
+impl FromStr for Bech32Address {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let (hrp, data) = bech32_decode(s)?;
+        let chain_id = Self::chain_id_of_human_readable_part(&hrp)?;
+        let bytes = regroup_bits(&data, 5, 8, false)?;
+        Ok(Self::new(
+            chain_id,
+            AccountAddress::try_from(bytes.as_slice())?,
+        ))
+    }
+}
+
+/// Parse an address of `chain_id`, given either as raw hex or in the bech32 encoding of
+/// `Bech32Address`. A bech32 address encoded for another chain is rejected.
+pub fn parse_address_for_chain(s: &str, chain_id: ChainId) -> Result<AccountAddress> {
+    ensure!(!s.is_empty(), "Empty address");
+    if let Ok(address) = AccountAddress::from_hex_literal(s).or_else(|_| s.parse()) {
+        return Ok(address);
+    }
+    let address = Bech32Address::from_str(s)?;
+    if address.chain_id() != chain_id {
+        bail!(
+            "Address {} is encoded for chain {}, not {}",
+            s,
+            address.chain_id(),
+            chain_id
+        );
+    }
+    Ok(address.address())
+}
+
+/// The characters of the bech32 encoding, indexed by the 5 bits they encode.
+const BECH32_CHARSET: &[u8; 32] = b"qpzry9x8gf2tvdw0s3jn54khce6mua7l";
+
+/// The length of the bech32 checksum, in characters.
+const BECH32_CHECKSUM_LEN: usize = 6;
+
+/// The maximum length of a bech32 string, see BIP 173.
+const BECH32_MAX_LEN: usize = 90;
+
+fn bech32_polymod(values: impl IntoIterator<Item = u8>) -> u32 {
+    const GENERATORS: [u32; 5] = [
+        0x3b6a_57b2,
+        0x2650_8e6d,
+        0x1ea1_19fa,
+        0x3d42_33dd,
+        0x2a14_62b3,
+    ];
+    let mut checksum = 1u32;
+    for value in values {
+        let top = checksum >> 25;
+        checksum = (checksum & 0x01ff_ffff) << 5 ^ u32::from(value);
+        for (i, generator) in GENERATORS.iter().enumerate() {
+            if (top >> i) & 1 == 1 {
+                checksum ^= generator;
+            }
+        }
+    }
+    checksum
+}
+
+/// The human readable part as it enters the checksum: the high bits of each character, a zero,
+/// then the low bits of each character.
+fn bech32_expand_hrp(hrp: &str) -> impl Iterator<Item = u8> + '_ {
+    hrp.bytes()
+        .map(|b| b >> 5)
+        .chain(iter::once(0))
+        .chain(hrp.bytes().map(|b| b & 0x1f))
+}
+
+/// Encodes `data`, given as 5-bit groups, under the lowercase human readable part `hrp`.
+pub(crate) fn bech32_encode(hrp: &str, data: &[u8]) -> String {
+    let polymod = bech32_polymod(
+        bech32_expand_hrp(hrp)
+            .chain(data.iter().copied())
+            .chain(iter::repeat(0).take(BECH32_CHECKSUM_LEN)),
+    ) ^ 1;
+    let checksum = (0..BECH32_CHECKSUM_LEN)
+        .map(|i| ((polymod >> (5 * (BECH32_CHECKSUM_LEN - 1 - i))) & 0x1f) as u8);
+    let mut encoded = format!("{}1", hrp);
+    encoded.extend(
+        data.iter()
+            .copied()
+            .chain(checksum)
+            .map(|b| BECH32_CHARSET[b as usize] as char),
+    );
+    encoded
+}
+
+/// Decodes a bech32 string into its lowercase human readable part and its data as 5-bit groups,
+/// checking the checksum.
+pub(crate) fn bech32_decode(s: &str) -> Result<(String, Vec<u8>)> {
+    ensure!(
+        s.len() <= BECH32_MAX_LEN,
+        "Bech32 string longer than {} characters",
+        BECH32_MAX_LEN
+    );
+    ensure!(
+        s.to_lowercase() == s || s.to_uppercase() == s,
+        "Bech32 string mixes upper and lower case"
+    );
+    let s = s.to_lowercase();
+    let separator = s
+        .rfind('1')
+        .ok_or_else(|| format_err!("Bech32 string has no separator"))?;
+    ensure!(
+        separator >= 1 && separator + 1 + BECH32_CHECKSUM_LEN <= s.len(),
+        "Bech32 string too short"
+    );
+    let (hrp, data) = (&s[..separator], &s[separator + 1..]);
+    ensure!(
+        hrp.bytes().all(|b| b >= 33 && b <= 126),
+        "Invalid character in bech32 human readable part"
+    );
+    let data = data
+        .bytes()
+        .map(|b| {
+            BECH32_CHARSET
+                .iter()
+                .position(|c| *c == b)
+                .map(|i| i as u8)
+                .ok_or_else(|| format_err!("Invalid character {:?} in bech32 data", b as char))
+        })
+        .collect::<Result<Vec<_>>>()?;
+    ensure!(
+        bech32_polymod(bech32_expand_hrp(hrp).chain(data.iter().copied())) == 1,
+        "Invalid bech32 checksum"
+    );
+    Ok((
+        hrp.to_string(),
+        data[..data.len() - BECH32_CHECKSUM_LEN].to_vec(),
+    ))
+}
+
+/// Regroups `data` from groups of `from_bits` into groups of `to_bits`, padding the last group
+/// with zeros if `pad` and otherwise rejecting leftover bits that are not zero padding.
+pub(crate) fn regroup_bits(
+    data: &[u8],
+    from_bits: u32,
+    to_bits: u32,
+    pad: bool,
+) -> Result<Vec<u8>> {
+    let max_value = (1u32 << to_bits) - 1;
+    let max_acc = (1u32 << (from_bits + to_bits - 1)) - 1;
+    let mut acc = 0u32;
+    let mut bits = 0u32;
+    let mut regrouped = Vec::with_capacity(data.len() * from_bits as usize / to_bits as usize + 1);
+    for value in data {
+        ensure!(
+            u32::from(*value) >> from_bits == 0,
+            "Value {} has more than {} bits",
+            value,
+            from_bits
+        );
+        acc = (acc << from_bits | u32::from(*value)) & max_acc;
+        bits += from_bits;
+        while bits >= to_bits {
+            bits -= to_bits;
+            regrouped.push(((acc >> bits) & max_value) as u8);
+        }
+    }
+    if pad {
+        if bits > 0 {
+            regrouped.push(((acc << (to_bits - bits)) & max_value) as u8);
+        }
+    } else {
+        ensure!(
+            bits < from_bits && (acc << (to_bits - bits)) & max_value == 0,
+            "Invalid padding"
+        );
+    }
+    Ok(regrouped)
+}
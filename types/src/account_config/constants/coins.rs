@@ -1,12 +1,17 @@
 // Copyright (c) The Libra Core Contributors
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::account_config::constants::{from_currency_code_string, CORE_CODE_ADDRESS};
+use crate::account_config::constants::{
+    from_currency_code_string, type_tag_for_currency_code, CORE_CODE_ADDRESS,
+};
+use anyhow::{bail, Error, Result};
 use move_core_types::{
     identifier::Identifier,
     language_storage::{ModuleId, StructTag, TypeTag},
 };
 use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use std::{convert::TryFrom, fmt, str::FromStr};
 
 pub const LBR_NAME: &str = "LBR";
 pub const COIN1_NAME: &str = "Coin1";
@@ -33,3 +38,75 @@ pub fn lbr_type_tag() -> TypeTag {
         type_params: vec![],
     })
 }
+
+/// The currencies registered at genesis. Prefer this to currency code strings, so that a misspelled
+/// currency is rejected when it is parsed rather than when a transaction using it is executed.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, Hash, Ord, PartialEq, PartialOrd, Serialize)]
+pub enum Currency {
+    LBR,
+    Coin1,
+    Coin2,
+}
+
+impl Currency {
+    /// All registered currencies.
+    pub const ALL: [Currency; 3] = [Currency::LBR, Currency::Coin1, Currency::Coin2];
+
+    /// Return the currency code, which is also the name of its module and struct.
+    pub fn code(self) -> &'static str {
+        match self {
+            Currency::LBR => LBR_NAME,
+            Currency::Coin1 => COIN1_NAME,
+            Currency::Coin2 => COIN2_NAME,
+        }
+    }
+
+    pub fn currency_code(self) -> Identifier {
+        from_currency_code_string(self.code()).expect("currency codes are valid identifiers")
+    }
+
+    pub fn type_tag(self) -> TypeTag {
+        type_tag_for_currency_code(self.currency_code())
+    }
+}
+
+impl fmt::Display for Currency {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.code())
+    }
+}
+
+impl FromStr for Currency {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match Currency::ALL.iter().find(|currency| currency.code() == s) {
+            Some(currency) => Ok(*currency),
+            None => bail!(
+                "Unknown currency {:?}, expected one of {:?}",
+                s,
+                Currency::ALL
+            ),
+        }
+    }
+}
+
+impl TryFrom<&TypeTag> for Currency {
+    type Error = Error;
+
+    fn try_from(type_tag: &TypeTag) -> Result<Self> {
+        match Currency::ALL
+            .iter()
+            .find(|currency| &currency.type_tag() == type_tag)
+        {
+            Some(currency) => Ok(*currency),
+            None => bail!("Not a currency type: {}", type_tag),
+        }
+    }
+}
+
+impl From<Currency> for String {
+    fn from(currency: Currency) -> Self {
+        currency.code().to_string()
+    }
+}
@@ -66,6 +66,10 @@ impl BlockMetadata {
     pub fn proposer(&self) -> AccountAddress {
         self.proposer
     }
+
+    pub fn timestamp_usec(&self) -> u64 {
+        self.timestamp_usecs
+    }
 }
 
 pub fn new_block_event_key() -> EventKey {
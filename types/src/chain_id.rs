@@ -1,17 +1,21 @@
 // Copyright (c) The Libra Core Contributors
 // SPDX-License-Identifier: Apache-2.0
-use anyhow::{format_err, Error, Result};
+use anyhow::{ensure, format_err, Error, Result};
+use once_cell::sync::Lazy;
 use serde::{de::Visitor, Deserialize, Deserializer, Serialize};
 use std::{
+    collections::BTreeMap,
     convert::TryFrom,
     fmt::{Display, Formatter},
     str::FromStr,
+    sync::RwLock,
 };
 
 /// A registry of named chain IDs
 /// Its main purpose is to improve human readability of reserved chain IDs in config files and CLI
 /// When signing transactions for such chains, the numerical chain ID should still be used
 /// (e.g. MAINNET has numeric chain ID 0, PREMAINNET has chain ID 1, etc)
+/// Private deployments can name their own chains with `register_custom_chain`
 #[repr(u8)]
 #[derive(Copy, Clone, Debug)]
 pub enum NamedChain {
@@ -25,26 +29,105 @@ pub enum NamedChain {
 }
 
 impl NamedChain {
+    /// All reserved chains, in the order of their chain IDs.
+    pub const ALL: [NamedChain; 5] = [
+        NamedChain::MAINNET,
+        NamedChain::PREMAINNET,
+        NamedChain::TESTNET,
+        NamedChain::DEVNET,
+        NamedChain::TESTING,
+    ];
+
     fn str_to_chain_id(s: &str) -> Result<ChainId> {
-        // TODO implement custom macro that derives FromStr impl for enum (similar to libra/common/num-variants)
-        let reserved_chain = match s {
-            "MAINNET" => NamedChain::MAINNET,
-            "PREMAINNET" => NamedChain::PREMAINNET,
-            "TESTNET" => NamedChain::TESTNET,
-            "DEVNET" => NamedChain::DEVNET,
-            "TESTING" => NamedChain::TESTING,
-            _ => {
-                return Err(format_err!("Not a reserved chain: {:?}", s));
-            }
-        };
-        Ok(ChainId::new(reserved_chain.id()))
+        if let Some(reserved_chain) = NamedChain::ALL
+            .iter()
+            .find(|reserved_chain| reserved_chain.name() == s)
+        {
+            return Ok(ChainId::new(reserved_chain.id()));
+        }
+        CUSTOM_CHAINS
+            .read()
+            .expect("custom chain lock poisoned")
+            .iter()
+            .find(|(_, name)| name.as_str() == s)
+            .map(|(id, _)| ChainId::new(*id))
+            .ok_or_else(|| format_err!("Not a named chain: {:?}", s))
+    }
+
+    /// Return the reserved chain with chain ID `chain_id`.
+    pub fn from_chain_id(chain_id: &ChainId) -> Result<NamedChain> {
+        NamedChain::ALL
+            .iter()
+            .find(|reserved_chain| reserved_chain.id() == chain_id.id())
+            .copied()
+            .ok_or_else(|| format_err!("Not a reserved chain: {}", chain_id.id()))
     }
 
-    fn id(&self) -> u8 {
+    pub fn name(&self) -> &'static str {
+        match self {
+            NamedChain::MAINNET => "MAINNET",
+            NamedChain::PREMAINNET => "PREMAINNET",
+            NamedChain::TESTNET => "TESTNET",
+            NamedChain::DEVNET => "DEVNET",
+            NamedChain::TESTING => "TESTING",
+        }
+    }
+
+    pub fn id(&self) -> u8 {
         *self as u8
     }
 }
 
+impl Display for NamedChain {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+/// The names of the chains registered with `register_custom_chain`, by chain ID.
+static CUSTOM_CHAINS: Lazy<RwLock<BTreeMap<u8, String>>> =
+    Lazy::new(|| RwLock::new(BTreeMap::new()));
+
+/// Register `name` as the name of the chain with ID `id`, for private deployments that want to
+/// refer to their chain by name in config files and logs. This should be called at process start,
+/// before any config is loaded.
+///
+/// The name must be upper case like the reserved names, and neither the name nor the ID may
+/// already be taken by a reserved or previously registered chain.
+pub fn register_custom_chain(name: &str, id: u8) -> Result<()> {
+    ensure!(
+        !name.is_empty()
+            && name
+                .chars()
+                .all(|c| c.is_ascii_uppercase() || c.is_ascii_digit() || c == '_'),
+        "Invalid chain name: {:?}",
+        name
+    );
+    ensure!(
+        NamedChain::from_chain_id(&ChainId::new(id)).is_err(),
+        "Chain ID {} is reserved",
+        id
+    );
+    let mut custom_chains = CUSTOM_CHAINS.write().expect("custom chain lock poisoned");
+    ensure!(
+        NamedChain::ALL
+            .iter()
+            .all(|reserved_chain| reserved_chain.name() != name)
+            && custom_chains
+                .values()
+                .all(|custom_name| custom_name != name),
+        "Chain name {:?} is already registered",
+        name
+    );
+    ensure!(
+        !custom_chains.contains_key(&id),
+        "Chain ID {} is already registered",
+        id
+    );
+    custom_chains.insert(id, name.to_string());
+    Ok(())
+}
+
 /// Note: u7 in a u8 is uleb-compatible, and any usage of this should be aware
 /// that this field maybe updated to be uleb64 in the future
 #[derive(Clone, Copy, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
@@ -85,10 +168,14 @@ where
     deserializer.deserialize_any(ChainIdVisitor)
 }
 
+/// Chains with a name are displayed as `NAME(id)`, e.g. `TESTNET(2)`, and other chains as their
+/// numeric ID. Both forms are accepted by `FromStr`.
 impl Display for ChainId {
     fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
-        // TODO add pretty printing for NamedChain
-        write!(f, "ChainId {:?}", self.0)
+        match self.name() {
+            Some(name) => write!(f, "{}({})", name, self.0),
+            None => write!(f, "{}", self.0),
+        }
     }
 }
 
@@ -102,7 +189,20 @@ impl FromStr for ChainId {
     type Err = Error;
 
     fn from_str(s: &str) -> Result<Self> {
-        assert!(!s.is_empty());
+        ensure!(!s.is_empty(), "Empty chain ID");
+        // Accept the `NAME(id)` form of `Display`, as long as the name and ID agree.
+        if let (Some(open), true) = (s.find('('), s.ends_with(')')) {
+            let chain_id = NamedChain::str_to_chain_id(&s[..open])?;
+            let id = s[open + 1..s.len() - 1].parse::<u8>()?;
+            ensure!(
+                chain_id.id() == id,
+                "Chain {} has ID {}, not {}",
+                &s[..open],
+                chain_id.id(),
+                id
+            );
+            return Ok(chain_id);
+        }
         NamedChain::str_to_chain_id(s).or_else(|_err| Ok(ChainId::new(s.parse::<u8>()?)))
     }
 }
@@ -116,6 +216,18 @@ impl ChainId {
         self.0
     }
 
+    /// Return the name of the chain, if it is a reserved chain or a registered custom chain.
+    pub fn name(&self) -> Option<String> {
+        match NamedChain::from_chain_id(self) {
+            Ok(reserved_chain) => Some(reserved_chain.name().to_string()),
+            Err(_) => CUSTOM_CHAINS
+                .read()
+                .expect("custom chain lock poisoned")
+                .get(&self.0)
+                .cloned(),
+        }
+    }
+
     pub fn test() -> Self {
         ChainId::new(NamedChain::TESTING.id())
     }
@@ -18,7 +18,7 @@ use move_core_types::{language_storage::TypeTag, move_resource::MoveResource};
 
 #[cfg(any(test, feature = "fuzzing"))]
 use proptest_derive::Arbitrary;
-use serde::{Deserialize, Serialize};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use std::{convert::TryFrom, ops::Deref};
 
 /// Support versioning of the data structure.
@@ -211,6 +211,60 @@ impl TryFrom<&ContractEvent> for NewEpochEvent {
     }
 }
 
+/// Decode `event` as a `T` event, checking that the event has the type of `T`.
+pub fn decode_event<T: MoveResource + DeserializeOwned>(event: &ContractEvent) -> Result<T> {
+    ensure!(
+        event.type_tag == TypeTag::Struct(T::struct_tag()),
+        "Expected {}, got an event of type {}",
+        T::STRUCT_NAME,
+        event.type_tag
+    );
+    lcs::from_bytes(&event.event_data).map_err(Into::into)
+}
+
+/// Declares `LibraEvent` and `decode_any_event` for the given event types, so that the registry
+/// of event types and the decoding dispatch cannot get out of sync.
+macro_rules! libra_events {
+    ($($variant:ident($event:ty)),* $(,)?) => {
+        /// An event emitted by the Libra framework, decoded according to its type.
+        #[derive(Debug)]
+        pub enum LibraEvent {
+            $($variant($event),)*
+        }
+
+        impl LibraEvent {
+            /// Return the types of all the events of the Libra framework.
+            pub fn type_tags() -> Vec<TypeTag> {
+                vec![$(TypeTag::Struct(<$event>::struct_tag()),)*]
+            }
+        }
+
+        /// Decode `event` according to its type. Returns `None` if it is not an event of the
+        /// Libra framework, and an error if its data does not match its type.
+        pub fn decode_any_event(event: &ContractEvent) -> Result<Option<LibraEvent>> {
+            $(
+                if event.type_tag == TypeTag::Struct(<$event>::struct_tag()) {
+                    return Ok(Some(LibraEvent::$variant(decode_event(event)?)));
+                }
+            )*
+            Ok(None)
+        }
+    };
+}
+
+libra_events! {
+    SentPayment(SentPaymentEvent),
+    ReceivedPayment(ReceivedPaymentEvent),
+    ToLBRExchangeRateUpdate(ToLBRExchangeRateUpdateEvent),
+    Mint(MintEvent),
+    Burn(BurnEvent),
+    Preburn(PreburnEvent),
+    CancelBurn(CancelBurnEvent),
+    Upgrade(UpgradeEvent),
+    NewBlock(NewBlockEvent),
+    NewEpoch(NewEpochEvent),
+}
+
 impl std::fmt::Debug for ContractEvent {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(
@@ -53,6 +53,8 @@ pub enum MempoolStatusCode {
     // transaction didn't pass vm_validation
     VmError = 5,
     UnknownStatus = 6,
+    // Replacing transaction's gas price isn't high enough above the pending one's
+    GasPriceBumpTooLow = 7,
 }
 
 impl TryFrom<u64> for MempoolStatusCode {
@@ -67,6 +69,7 @@ impl TryFrom<u64> for MempoolStatusCode {
             4 => Ok(MempoolStatusCode::InvalidUpdate),
             5 => Ok(MempoolStatusCode::VmError),
             6 => Ok(MempoolStatusCode::UnknownStatus),
+            7 => Ok(MempoolStatusCode::GasPriceBumpTooLow),
             _ => Err("invalid StatusCode"),
         }
     }
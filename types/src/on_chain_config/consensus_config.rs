@@ -0,0 +1,49 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::on_chain_config::OnChainConfig;
+use anyhow::{format_err, Result};
+use serde::{Deserialize, Serialize};
+
+/// How the proposer of each round is elected among the validators of an epoch.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub enum ProposerElectionType {
+    /// The smallest validator address is the proposer of every round
+    FixedProposer,
+    /// Round robin rotation of proposers
+    RotatingProposer,
+    /// Proposers are picked based on their commit history
+    LeaderReputation {
+        active_weights: u64,
+        inactive_weights: u64,
+    },
+}
+
+/// Defines the on chain configuration consensus takes over its local one.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct OnChainConsensusConfig {
+    pub proposer_election_type: ProposerElectionType,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+struct OnChainConsensusConfigInner {
+    pub proposer_election_type: Vec<u8>,
+}
+
+impl OnChainConfig for OnChainConsensusConfig {
+    const IDENTIFIER: &'static str = "LibraConsensusConfig";
+
+    fn deserialize_into_config(bytes: &[u8]) -> Result<Self> {
+        let raw_consensus_config =
+            lcs::from_bytes::<OnChainConsensusConfigInner>(&bytes).map_err(|e| {
+                format_err!(
+                    "Failed first round of deserialization for OnChainConsensusConfigInner: {}",
+                    e
+                )
+            })?;
+        let proposer_election_type = lcs::from_bytes(&raw_consensus_config.proposer_election_type)?;
+        Ok(OnChainConsensusConfig {
+            proposer_election_type,
+        })
+    }
+}
@@ -14,23 +14,31 @@ use move_core_types::{
     move_resource::MoveResource,
 };
 use serde::{de::DeserializeOwned, Deserialize, Serialize};
-use std::{collections::HashMap, sync::Arc};
+use std::{
+    collections::{HashMap, HashSet},
+    sync::Arc,
+};
 
+mod consensus_config;
 mod libra_version;
 mod registered_currencies;
 mod validator_set;
 mod vm_config;
+mod write_set_limits;
 
 pub use self::{
+    consensus_config::{OnChainConsensusConfig, ProposerElectionType},
     libra_version::LibraVersion,
     registered_currencies::RegisteredCurrencies,
     validator_set::ValidatorSet,
     vm_config::{ModulePublishingOption, ScriptPublishingOption, VMConfig, VMPublishingOption},
+    write_set_limits::OnChainWriteSetLimits,
 };
 
 /// To register an on-chain config in Rust:
 /// 1. Implement the `OnChainConfig` trait for the Rust representation of the config
-/// 2. Add the config's `ConfigID` to `ON_CHAIN_CONFIG_REGISTRY`
+/// 2. Add the config's `ConfigID` to `ON_CHAIN_CONFIG_REGISTRY`, or to
+///    `OPTIONAL_ON_CHAIN_CONFIG_REGISTRY` if it may not be published on chain
 
 #[derive(Copy, Clone, Debug, Eq, Hash, PartialEq)]
 pub struct ConfigID(&'static str, &'static str);
@@ -58,6 +66,12 @@ pub const ON_CHAIN_CONFIG_REGISTRY: &[ConfigID] = &[
     RegisteredCurrencies::CONFIG_ID,
 ];
 
+/// Configs that may not be published on chain, which are left out of the payload until they are
+pub const OPTIONAL_ON_CHAIN_CONFIG_REGISTRY: &[ConfigID] = &[
+    OnChainConsensusConfig::CONFIG_ID,
+    OnChainWriteSetLimits::CONFIG_ID,
+];
+
 #[derive(Clone, Debug, PartialEq)]
 pub struct OnChainConfigPayload {
     epoch: u64,
@@ -81,9 +95,74 @@ impl OnChainConfigPayload {
         T::deserialize_into_config(bytes)
     }
 
+    /// Like `get`, but `None` if the config isn't in the payload, as optional configs aren't
+    /// while they're not published on chain.
+    pub fn get_optional<T: OnChainConfig>(&self) -> Result<Option<T>> {
+        self.configs
+            .get(&T::CONFIG_ID)
+            .map(|bytes| T::deserialize_into_config(bytes))
+            .transpose()
+    }
+
     pub fn configs(&self) -> &HashMap<ConfigID, Vec<u8>> {
         &self.configs
     }
+
+    /// Return the configs whose value differs from their value in `previous`, including the
+    /// configs that are only in one of the payloads.
+    pub fn delta(&self, previous: &OnChainConfigPayload) -> ConfigDelta {
+        let changed = self
+            .configs
+            .iter()
+            .filter(|(id, bytes)| previous.configs.get(id) != Some(bytes))
+            .map(|(id, _)| *id)
+            .chain(
+                previous
+                    .configs
+                    .keys()
+                    .filter(|id| !self.configs.contains_key(id))
+                    .copied(),
+            )
+            .collect();
+        ConfigDelta {
+            epoch: self.epoch,
+            changed,
+        }
+    }
+}
+
+/// The configs changed by a reconfiguration, so that a component only reloads the configs it
+/// uses when they change:
+///
+/// ```ignore
+/// let delta = payload.delta(&previous_payload);
+/// if delta.is_changed::<VMConfig>() {
+///     vm_config = payload.get::<VMConfig>()?;
+/// }
+/// ```
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ConfigDelta {
+    epoch: u64,
+    changed: HashSet<ConfigID>,
+}
+
+impl ConfigDelta {
+    /// The epoch the changed configs take effect in.
+    pub fn epoch(&self) -> u64 {
+        self.epoch
+    }
+
+    pub fn is_changed<T: OnChainConfig>(&self) -> bool {
+        self.changed.contains(&T::CONFIG_ID)
+    }
+
+    pub fn changed(&self) -> impl Iterator<Item = &ConfigID> {
+        self.changed.iter()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.changed.is_empty()
+    }
 }
 
 /// Trait to be implemented by a storage type from which to read on-chain configs
@@ -119,12 +198,23 @@ pub trait OnChainConfig: Send + Sync + DeserializeOwned {
         Self::deserialize_default_impl(bytes)
     }
 
+    /// The access path the config is stored at.
+    fn access_path() -> AccessPath {
+        Self::CONFIG_ID.access_path()
+    }
+
+    /// The key of the event emitted by reconfigurations, after which the config may have a new
+    /// value. All configs currently change with the epoch.
+    fn reconfiguration_event_key() -> EventKey {
+        new_epoch_event_key()
+    }
+
     fn fetch_config<T>(storage: &T) -> Option<Self>
     where
         T: ConfigStorage,
     {
         storage
-            .fetch_config(Self::CONFIG_ID.access_path())
+            .fetch_config(Self::access_path())
             .and_then(|bytes| Self::deserialize_into_config(&bytes).ok())
     }
 }
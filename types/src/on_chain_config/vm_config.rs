@@ -60,6 +60,13 @@ impl VMPublishingOption {
         }
     }
 
+    pub fn is_open_script(&self) -> bool {
+        match &self.script_option {
+            ScriptPublishingOption::CustomScripts => true,
+            _ => false,
+        }
+    }
+
     pub fn is_open_module(&self) -> bool {
         match &self.module_option {
             ModulePublishingOption::Open => true,
@@ -0,0 +1,17 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::on_chain_config::OnChainConfig;
+use serde::{Deserialize, Serialize};
+
+/// Defines the bounds on what a single transaction can write to storage.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct OnChainWriteSetLimits {
+    pub max_write_ops: u64,
+    pub max_write_bytes: u64,
+    pub max_events: u64,
+}
+
+impl OnChainConfig for OnChainWriteSetLimits {
+    const IDENTIFIER: &'static str = "LibraWriteSetLimits";
+}
@@ -308,6 +308,16 @@ fn new_raw_transaction(
             Duration::from_secs(expiration_time_secs),
             chain_id,
         ),
+        TransactionPayload::ScriptFunction(script_function) => RawTransaction::new_script_function(
+            sender,
+            sequence_number,
+            script_function,
+            max_gas_amount,
+            gas_unit_price,
+            gas_currency_code,
+            Duration::from_secs(expiration_time_secs),
+            chain_id,
+        ),
         TransactionPayload::WriteSet(write_set) => {
             // It's a bit unfortunate that max_gas_amount etc is generated but
             // not used, but it isn't a huge deal.
@@ -5,9 +5,13 @@ use crate::{
     account_address::AccountAddress,
     account_config::LBR_NAME,
     chain_id::ChainId,
-    transaction::{Module, RawTransaction, Script, SignatureCheckedTransaction, SignedTransaction},
+    transaction::{
+        Module, RawTransaction, Script, SignatureCheckedTransaction, SignedTransaction,
+        TransactionPayload,
+    },
     write_set::WriteSet,
 };
+use anyhow::{ensure, Result};
 use libra_crypto::{ed25519::*, traits::*};
 use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
@@ -165,3 +169,81 @@ pub fn get_write_set_txn(
         .sign(&private_key, public_key)
         .unwrap()
 }
+
+/// Builds and signs test transactions for a single chain. Signing a transaction for any other
+/// chain fails, so a test cannot accidentally build a transaction for a chain it was not meant for.
+///
+/// This only guards how tests build transactions: validation and execution do not check the chain
+/// ID of a transaction.
+#[derive(Clone, Copy, Debug)]
+pub struct ChainAwareTransactionBuilder {
+    chain_id: ChainId,
+}
+
+impl ChainAwareTransactionBuilder {
+    pub fn new(chain_id: ChainId) -> Self {
+        Self { chain_id }
+    }
+
+    pub fn chain_id(&self) -> ChainId {
+        self.chain_id
+    }
+
+    /// Build the transaction sending `payload` from `sender` with the test gas parameters,
+    /// expiring 10 seconds from now.
+    pub fn raw_transaction(
+        &self,
+        sender: AccountAddress,
+        sequence_number: u64,
+        payload: TransactionPayload,
+    ) -> RawTransaction {
+        let expiration_time = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_secs()
+            + 10;
+        RawTransaction::new(
+            sender,
+            sequence_number,
+            payload,
+            MAX_GAS_AMOUNT,
+            TEST_GAS_PRICE,
+            LBR_NAME.to_owned(),
+            Duration::from_secs(expiration_time),
+            self.chain_id,
+        )
+    }
+
+    /// Sign `raw_txn`, which must be meant for the chain of the builder.
+    pub fn sign(
+        &self,
+        raw_txn: RawTransaction,
+        private_key: &Ed25519PrivateKey,
+        public_key: Ed25519PublicKey,
+    ) -> Result<SignedTransaction> {
+        ensure!(
+            raw_txn.chain_id() == self.chain_id,
+            "Refusing to sign a transaction for chain {} with a builder for chain {}",
+            raw_txn.chain_id(),
+            self.chain_id
+        );
+        Ok(raw_txn.sign(private_key, public_key)?.into_inner())
+    }
+
+    /// Build and sign the transaction running `script` from `sender`.
+    pub fn signed_script(
+        &self,
+        sender: AccountAddress,
+        sequence_number: u64,
+        script: Script,
+        private_key: &Ed25519PrivateKey,
+        public_key: Ed25519PublicKey,
+    ) -> SignedTransaction {
+        self.sign(
+            self.raw_transaction(sender, sequence_number, TransactionPayload::Script(script)),
+            private_key,
+            public_key,
+        )
+        .expect("transaction is built for the chain of the builder")
+    }
+}
@@ -0,0 +1,136 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Conversion between transaction arguments and Move values, and validation of script arguments
+//! against the ABI of the script. Checking a script locally catches the argument errors that the
+//! VM would otherwise only report as a `TYPE_MISMATCH` when executing the transaction.
+
+use crate::transaction::{Script, ScriptABI, TransactionArgument};
+use move_core_types::{
+    language_storage::TypeTag, transaction_argument::is_argument_of_type, value::MoveValue,
+};
+use thiserror::Error;
+
+/// Errors found when validating script arguments against an ABI.
+#[derive(Clone, Debug, Eq, Error, PartialEq)]
+pub enum ArgumentError {
+    #[error("The code of the script is not the code of {}", script)]
+    /// The script is not the script described by the ABI.
+    CodeMismatch { script: String },
+    #[error(
+        "Script {} expects {} type arguments, got {}",
+        script,
+        expected,
+        actual
+    )]
+    TypeArgumentCountMismatch {
+        script: String,
+        expected: usize,
+        actual: usize,
+    },
+    #[error("Script {} expects {} arguments, got {}", script, expected, actual)]
+    ArgumentCountMismatch {
+        script: String,
+        expected: usize,
+        actual: usize,
+    },
+    #[error(
+        "Argument {} ({}) of script {} must have type {}, got {:?}",
+        position,
+        name,
+        script,
+        expected,
+        actual
+    )]
+    ArgumentTypeMismatch {
+        script: String,
+        position: usize,
+        name: String,
+        expected: TypeTag,
+        actual: TransactionArgument,
+    },
+    #[error("Move value {:?} cannot be passed in a transaction", value)]
+    UnsupportedMoveValue { value: MoveValue },
+}
+
+/// Return the Move value passed to a script for `arg`.
+pub fn argument_to_move_value(arg: &TransactionArgument) -> MoveValue {
+    match arg {
+        TransactionArgument::U8(value) => MoveValue::U8(*value),
+        TransactionArgument::U64(value) => MoveValue::U64(*value),
+        TransactionArgument::U128(value) => MoveValue::U128(*value),
+        TransactionArgument::Address(address) => MoveValue::Address(*address),
+        TransactionArgument::U8Vector(bytes) => MoveValue::vector_u8(bytes.clone()),
+        TransactionArgument::Bool(value) => MoveValue::Bool(*value),
+    }
+}
+
+/// Return the transaction argument passing `value` to a script, if there is one.
+pub fn move_value_to_argument(value: &MoveValue) -> Result<TransactionArgument, ArgumentError> {
+    let unsupported = || ArgumentError::UnsupportedMoveValue {
+        value: value.clone(),
+    };
+    Ok(match value {
+        MoveValue::U8(value) => TransactionArgument::U8(*value),
+        MoveValue::U64(value) => TransactionArgument::U64(*value),
+        MoveValue::U128(value) => TransactionArgument::U128(*value),
+        MoveValue::Address(address) => TransactionArgument::Address(*address),
+        MoveValue::Bool(value) => TransactionArgument::Bool(*value),
+        MoveValue::Vector(elements) => TransactionArgument::U8Vector(
+            elements
+                .iter()
+                .map(|element| match element {
+                    MoveValue::U8(byte) => Ok(*byte),
+                    _ => Err(unsupported()),
+                })
+                .collect::<Result<_, _>>()?,
+        ),
+        MoveValue::Struct(_) | MoveValue::Signer(_) => return Err(unsupported()),
+    })
+}
+
+/// Check that `ty_args` and `args` can be passed to the script described by `abi`: the number of
+/// type arguments and arguments match, and each argument has the type of the parameter.
+pub fn validate_arguments(
+    abi: &ScriptABI,
+    ty_args: &[TypeTag],
+    args: &[TransactionArgument],
+) -> Result<(), ArgumentError> {
+    if ty_args.len() != abi.ty_args().len() {
+        return Err(ArgumentError::TypeArgumentCountMismatch {
+            script: abi.name().to_string(),
+            expected: abi.ty_args().len(),
+            actual: ty_args.len(),
+        });
+    }
+    if args.len() != abi.args().len() {
+        return Err(ArgumentError::ArgumentCountMismatch {
+            script: abi.name().to_string(),
+            expected: abi.args().len(),
+            actual: args.len(),
+        });
+    }
+    for (position, (arg, arg_abi)) in args.iter().zip(abi.args()).enumerate() {
+        let expected = arg_abi.type_tag();
+        if !is_argument_of_type(arg, expected) {
+            return Err(ArgumentError::ArgumentTypeMismatch {
+                script: abi.name().to_string(),
+                position,
+                name: arg_abi.name().to_string(),
+                expected: expected.clone(),
+                actual: arg.clone(),
+            });
+        }
+    }
+    Ok(())
+}
+
+/// Check that `script` is the script described by `abi` and that its arguments are valid.
+pub fn validate_script(abi: &ScriptABI, script: &Script) -> Result<(), ArgumentError> {
+    if script.code() != abi.code() {
+        return Err(ArgumentError::CodeMismatch {
+            script: abi.name().to_string(),
+        });
+    }
+    validate_arguments(abi, script.ty_args(), script.args())
+}
@@ -2,7 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::account_address::AccountAddress;
-use anyhow::{Error, Result};
+use anyhow::{ensure, Error, Result};
 use libra_crypto::{
     ed25519::{Ed25519PublicKey, Ed25519Signature},
     hash::CryptoHash,
@@ -60,10 +60,164 @@ pub enum TransactionAuthenticator {
         public_key: MultiEd25519PublicKey,
         signature: MultiEd25519Signature,
     },
+    /// A transaction signed by its sender and by the secondary signers at
+    /// `secondary_signer_addresses`, in order. Every signature is on the
+    /// `RawTransactionWithData::MultiAgent` of the transaction.
+    MultiAgent {
+        sender: AccountAuthenticator,
+        secondary_signer_addresses: Vec<AccountAddress>,
+        secondary_signers: Vec<AccountAuthenticator>,
+    },
     // ... add more schemes here
 }
 
 impl TransactionAuthenticator {
+    /// Unique identifier for the signature scheme. For multi-agent transactions, this is the
+    /// scheme of the sender.
+    pub fn scheme(&self) -> Scheme {
+        match self {
+            Self::Ed25519 { .. } => Scheme::Ed25519,
+            Self::MultiEd25519 { .. } => Scheme::MultiEd25519,
+            Self::MultiAgent { sender, .. } => sender.scheme(),
+        }
+    }
+
+    /// Create a single-signature ed25519 authenticator
+    pub fn ed25519(public_key: Ed25519PublicKey, signature: Ed25519Signature) -> Self {
+        Self::Ed25519 {
+            public_key,
+            signature,
+        }
+    }
+
+    /// Create a multisignature ed25519 authenticator
+    pub fn multi_ed25519(
+        public_key: MultiEd25519PublicKey,
+        signature: MultiEd25519Signature,
+    ) -> Self {
+        Self::MultiEd25519 {
+            public_key,
+            signature,
+        }
+    }
+
+    /// Create a multi-agent authenticator
+    pub fn multi_agent(
+        sender: AccountAuthenticator,
+        secondary_signer_addresses: Vec<AccountAddress>,
+        secondary_signers: Vec<AccountAuthenticator>,
+    ) -> Self {
+        Self::MultiAgent {
+            sender,
+            secondary_signer_addresses,
+            secondary_signers,
+        }
+    }
+
+    /// Return Ok if the authenticator's public key matches its signature, Err otherwise. For
+    /// multi-agent transactions, every signer's public key must match its signature.
+    pub fn verify<T: Serialize + CryptoHash>(&self, message: &T) -> Result<()> {
+        match self {
+            Self::Ed25519 {
+                public_key,
+                signature,
+            } => signature.verify(message, public_key),
+            Self::MultiEd25519 {
+                public_key,
+                signature,
+            } => signature.verify(message, public_key),
+            Self::MultiAgent {
+                sender,
+                secondary_signer_addresses,
+                secondary_signers,
+            } => {
+                ensure!(
+                    secondary_signer_addresses.len() == secondary_signers.len(),
+                    "{} secondary signer addresses but {} secondary signatures",
+                    secondary_signer_addresses.len(),
+                    secondary_signers.len()
+                );
+                sender.verify(message)?;
+                for signer in secondary_signers {
+                    signer.verify(message)?;
+                }
+                Ok(())
+            }
+        }
+    }
+
+    /// Return the raw bytes of `self.public_key`. For multi-agent transactions, this is the
+    /// public key of the sender.
+    pub fn public_key_bytes(&self) -> Vec<u8> {
+        match self {
+            Self::Ed25519 { public_key, .. } => public_key.to_bytes().to_vec(),
+            Self::MultiEd25519 { public_key, .. } => public_key.to_bytes().to_vec(),
+            Self::MultiAgent { sender, .. } => sender.public_key_bytes(),
+        }
+    }
+
+    /// Return the raw bytes of `self.signature`. For multi-agent transactions, this is the
+    /// signature of the sender.
+    pub fn signature_bytes(&self) -> Vec<u8> {
+        match self {
+            Self::Ed25519 { signature, .. } => signature.to_bytes().to_vec(),
+            Self::MultiEd25519 { signature, .. } => signature.to_bytes().to_vec(),
+            Self::MultiAgent { sender, .. } => sender.signature_bytes(),
+        }
+    }
+
+    /// Return the addresses of the secondary signers, empty unless this is a multi-agent
+    /// authenticator
+    pub fn secondary_signer_addresses(&self) -> Vec<AccountAddress> {
+        match self {
+            Self::Ed25519 { .. } | Self::MultiEd25519 { .. } => vec![],
+            Self::MultiAgent {
+                secondary_signer_addresses,
+                ..
+            } => secondary_signer_addresses.clone(),
+        }
+    }
+
+    /// Return the authenticators of the secondary signers, empty unless this is a multi-agent
+    /// authenticator
+    pub fn secondary_signers(&self) -> Vec<AccountAuthenticator> {
+        match self {
+            Self::Ed25519 { .. } | Self::MultiEd25519 { .. } => vec![],
+            Self::MultiAgent {
+                secondary_signers, ..
+            } => secondary_signers.clone(),
+        }
+    }
+
+    /// Return an authentication key preimage derived from `self`'s public key and scheme id
+    pub fn authentication_key_preimage(&self) -> AuthenticationKeyPreimage {
+        AuthenticationKeyPreimage::new(self.public_key_bytes(), self.scheme())
+    }
+
+    /// Return an authentication key derived from `self`'s public key and scheme id
+    pub fn authentication_key(&self) -> AuthenticationKey {
+        AuthenticationKey::from_preimage(&self.authentication_key_preimage())
+    }
+}
+
+/// An `AccountAuthenticator` authenticates a single signer of a multi-agent transaction. It
+/// supports the same schemes as a single-signer `TransactionAuthenticator`.
+#[derive(Clone, Debug, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub enum AccountAuthenticator {
+    /// Single signature
+    Ed25519 {
+        public_key: Ed25519PublicKey,
+        signature: Ed25519Signature,
+    },
+    /// K-of-N multisignature
+    MultiEd25519 {
+        public_key: MultiEd25519PublicKey,
+        signature: MultiEd25519Signature,
+    },
+    // ... add more schemes here
+}
+
+impl AccountAuthenticator {
     /// Unique identifier for the signature scheme
     pub fn scheme(&self) -> Scheme {
         match self {
@@ -57,6 +57,18 @@ pub struct TravelRuleMetadataV0 {
     off_chain_reference_id: Option<String>,
 }
 
+impl TravelRuleMetadataV0 {
+    pub fn new(off_chain_reference_id: Option<String>) -> Self {
+        Self {
+            off_chain_reference_id,
+        }
+    }
+
+    pub fn off_chain_reference_id(&self) -> Option<&str> {
+        self.off_chain_reference_id.as_deref()
+    }
+}
+
 /// Opaque binary transaction metadata
 #[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
 pub struct UnstructuredBytesMetadata {
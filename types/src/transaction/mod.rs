@@ -10,7 +10,7 @@ use crate::{
     contract_event::ContractEvent,
     ledger_info::LedgerInfo,
     proof::{accumulator::InMemoryAccumulator, TransactionInfoWithProof, TransactionListProof},
-    transaction::authenticator::TransactionAuthenticator,
+    transaction::authenticator::{AccountAuthenticator, TransactionAuthenticator},
     vm_status::{StatusCode, StatusType, VMStatus},
     write_set::WriteSet,
 };
@@ -23,6 +23,12 @@ use libra_crypto::{
     HashValue,
 };
 use libra_crypto_derive::{CryptoHasher, LCSCryptoHash};
+use move_core_types::{
+    gas_schedule::{
+        calculate_intrinsic_gas, AbstractMemorySize, GasAlgebra, GasCarrier, GasConstants,
+    },
+    identifier::Identifier,
+};
 #[cfg(any(test, feature = "fuzzing"))]
 use proptest_derive::Arbitrary;
 use serde::{de, ser, Deserialize, Serialize};
@@ -31,9 +37,10 @@ use std::{
     convert::TryFrom,
     fmt,
     fmt::{Display, Formatter},
-    time::Duration,
+    time::{Duration, SystemTime, UNIX_EPOCH},
 };
 
+pub mod argument_validation;
 pub mod authenticator;
 mod change_set;
 pub mod helpers;
@@ -44,7 +51,10 @@ mod transaction_argument;
 
 pub use change_set::ChangeSet;
 pub use module::Module;
-pub use script::{ArgumentABI, Script, ScriptABI, TypeArgumentABI, SCRIPT_HASH_LENGTH};
+pub use script::{
+    ArgumentABI, Script, ScriptABI, ScriptFunction, ScriptFunctionABI, TypeArgumentABI,
+    SCRIPT_HASH_LENGTH,
+};
 
 use std::ops::Deref;
 pub use transaction_argument::{parse_transaction_argument, TransactionArgument};
@@ -170,6 +180,29 @@ impl RawTransaction {
         }
     }
 
+    /// Create a new `RawTransaction` calling a script function.
+    pub fn new_script_function(
+        sender: AccountAddress,
+        sequence_number: u64,
+        script_function: ScriptFunction,
+        max_gas_amount: u64,
+        gas_unit_price: u64,
+        gas_currency_code: String,
+        expiration_time: Duration,
+        chain_id: ChainId,
+    ) -> Self {
+        RawTransaction {
+            sender,
+            sequence_number,
+            payload: TransactionPayload::ScriptFunction(script_function),
+            max_gas_amount,
+            gas_unit_price,
+            gas_currency_code,
+            expiration_time,
+            chain_id,
+        }
+    }
+
     /// Create a new `RawTransaction` with a module to publish.
     ///
     /// A module transaction is the only way to publish code. Only one module per transaction
@@ -275,6 +308,14 @@ impl RawTransaction {
                 (get_transaction_name(script.code()), script.args())
             }
             TransactionPayload::Module(_) => ("module publishing".to_string(), &empty_vec[..]),
+            TransactionPayload::ScriptFunction(script_function) => (
+                format!(
+                    "{}::{}",
+                    script_function.module().name(),
+                    script_function.function()
+                ),
+                script_function.args(),
+            ),
         };
         let mut f_args: String = "".to_string();
         for arg in args {
@@ -310,6 +351,34 @@ impl RawTransaction {
     pub fn sender(&self) -> AccountAddress {
         self.sender
     }
+
+    /// Return the chain this transaction is meant for.
+    pub fn chain_id(&self) -> ChainId {
+        self.chain_id
+    }
+}
+
+/// The message signed by every signer of a multi-agent transaction: the raw transaction along
+/// with the ordered addresses of the secondary signers, so that no signature can be reused with a
+/// different set of signers.
+#[derive(Clone, Debug, Hash, Eq, PartialEq, Serialize, Deserialize, CryptoHasher, LCSCryptoHash)]
+pub enum RawTransactionWithData {
+    MultiAgent {
+        raw_txn: RawTransaction,
+        secondary_signer_addresses: Vec<AccountAddress>,
+    },
+}
+
+impl RawTransactionWithData {
+    pub fn new_multi_agent(
+        raw_txn: RawTransaction,
+        secondary_signer_addresses: Vec<AccountAddress>,
+    ) -> Self {
+        Self::MultiAgent {
+            raw_txn,
+            secondary_signer_addresses,
+        }
+    }
 }
 
 #[derive(Clone, Debug, Hash, Eq, PartialEq, Serialize, Deserialize)]
@@ -319,6 +388,8 @@ pub enum TransactionPayload {
     Script(Script),
     /// A transaction that publishes code.
     Module(Module),
+    /// A transaction that calls a function of a published module.
+    ScriptFunction(ScriptFunction),
 }
 
 /// A transaction that has been signed.
@@ -338,6 +409,23 @@ pub struct SignedTransaction {
     authenticator: TransactionAuthenticator,
 }
 
+/// Why a transaction fails [`SignedTransaction::pre_validate`].
+#[derive(Clone, Copy, Debug, Eq, PartialEq, thiserror::Error)]
+pub enum PreValidationError {
+    #[error("The transaction would be discarded with {:?}", _0)]
+    /// The status validation would discard the transaction with.
+    Status(StatusCode),
+    #[error("The transaction is for chain {}, not chain {}", actual, expected)]
+    /// The transaction was signed for another chain than the one it is validated for.
+    BadChainId { expected: ChainId, actual: ChainId },
+}
+
+impl From<StatusCode> for PreValidationError {
+    fn from(status: StatusCode) -> Self {
+        PreValidationError::Status(status)
+    }
+}
+
 /// A transaction for which the signature has been verified. Created by
 /// [`SignedTransaction::check_signature`] and [`RawTransaction::sign`].
 #[derive(Clone, Debug, Eq, PartialEq, Hash)]
@@ -402,10 +490,35 @@ impl SignedTransaction {
         }
     }
 
+    /// Creates a multi-agent transaction. Every authenticator must sign the
+    /// `RawTransactionWithData::MultiAgent` of `raw_txn` and `secondary_signer_addresses`.
+    pub fn new_multi_agent(
+        raw_txn: RawTransaction,
+        sender: AccountAuthenticator,
+        secondary_signer_addresses: Vec<AccountAddress>,
+        secondary_signers: Vec<AccountAuthenticator>,
+    ) -> SignedTransaction {
+        let authenticator = TransactionAuthenticator::multi_agent(
+            sender,
+            secondary_signer_addresses,
+            secondary_signers,
+        );
+        SignedTransaction {
+            raw_txn,
+            authenticator,
+        }
+    }
+
     pub fn authenticator(&self) -> TransactionAuthenticator {
         self.authenticator.clone()
     }
 
+    /// Returns the addresses of the secondary signers of a multi-agent transaction, in order.
+    /// Empty for transactions with a single signer.
+    pub fn secondary_signer_addresses(&self) -> Vec<AccountAddress> {
+        self.authenticator.secondary_signer_addresses()
+    }
+
     pub fn sender(&self) -> AccountAddress {
         self.raw_txn.sender
     }
@@ -448,13 +561,79 @@ impl SignedTransaction {
             .len()
     }
 
+    /// Check the parts of the transaction that do not depend on the state of the chain the way
+    /// validation does: its size, gas bounds, gas currency code and expiration time, and that it
+    /// is for the chain `chain_id`. Returns why the transaction would be rejected, mostly the status
+    /// it would be discarded with, so that a client can fail before submitting the transaction.
+    pub fn pre_validate(
+        &self,
+        gas_constants: &GasConstants,
+        chain_id: &ChainId,
+    ) -> std::result::Result<(), PreValidationError> {
+        let raw_bytes_len = self.raw_txn_bytes_len() as GasCarrier;
+        if raw_bytes_len > gas_constants.max_transaction_size_in_bytes {
+            return Err(StatusCode::EXCEEDED_MAX_TRANSACTION_SIZE.into());
+        }
+        if self.max_gas_amount() > gas_constants.maximum_number_of_gas_units.get() {
+            return Err(StatusCode::MAX_GAS_UNITS_EXCEEDS_MAX_GAS_UNITS_BOUND.into());
+        }
+        let intrinsic_gas =
+            calculate_intrinsic_gas(AbstractMemorySize::new(raw_bytes_len), gas_constants);
+        if self.max_gas_amount() < intrinsic_gas.get() {
+            return Err(StatusCode::MAX_GAS_UNITS_BELOW_MIN_TRANSACTION_GAS_UNITS.into());
+        }
+        if self.gas_unit_price() < gas_constants.min_price_per_gas_unit.get() {
+            return Err(StatusCode::GAS_UNIT_PRICE_BELOW_MIN_BOUND.into());
+        }
+        if self.gas_unit_price() > gas_constants.max_price_per_gas_unit.get() {
+            return Err(StatusCode::GAS_UNIT_PRICE_ABOVE_MAX_BOUND.into());
+        }
+        if !Identifier::is_valid(self.gas_currency_code()) {
+            return Err(StatusCode::INVALID_GAS_SPECIFIER.into());
+        }
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("System time is before the Unix epoch");
+        if self.expiration_time().as_secs() <= now.as_secs() {
+            return Err(StatusCode::TRANSACTION_EXPIRED.into());
+        }
+        if self.chain_id() != *chain_id {
+            return Err(PreValidationError::BadChainId {
+                expected: *chain_id,
+                actual: self.chain_id(),
+            });
+        }
+        Ok(())
+    }
+
     /// Checks that the signature of given transaction. Returns `Ok(SignatureCheckedTransaction)` if
     /// the signature is valid.
     pub fn check_signature(self) -> Result<SignatureCheckedTransaction> {
-        self.authenticator.verify(&self.raw_txn)?;
+        match &self.authenticator {
+            TransactionAuthenticator::MultiAgent {
+                secondary_signer_addresses,
+                ..
+            } => {
+                let message = RawTransactionWithData::new_multi_agent(
+                    self.raw_txn.clone(),
+                    secondary_signer_addresses.clone(),
+                );
+                self.authenticator.verify(&message)?;
+            }
+            _ => self.authenticator.verify(&self.raw_txn)?,
+        }
         Ok(SignatureCheckedTransaction(self))
     }
 
+    /// Returns a `SignatureCheckedTransaction` without checking the signature of the transaction.
+    ///
+    /// Only meant for simulating transactions, e.g. to see their effects before signing them: the
+    /// output of executing the returned transaction must never be committed.
+    #[cfg(feature = "simulation")]
+    pub fn skip_signature_check(self) -> SignatureCheckedTransaction {
+        SignatureCheckedTransaction(self)
+    }
+
     pub fn format_for_client(&self, get_transaction_name: impl Fn(&[u8]) -> String) -> String {
         format!(
             "SignedTransaction {{ \n \
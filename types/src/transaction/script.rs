@@ -2,7 +2,10 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::transaction::transaction_argument::TransactionArgument;
-use move_core_types::language_storage::TypeTag;
+use move_core_types::{
+    identifier::{IdentStr, Identifier},
+    language_storage::{ModuleId, TypeTag},
+};
 use serde::{Deserialize, Serialize};
 use std::fmt;
 
@@ -53,6 +56,53 @@ impl fmt::Debug for Script {
     }
 }
 
+/// Call a public function of a published module by name, without shipping any code.
+///
+/// The function is passed the signers of the transaction first, the same way a script is.
+#[derive(Clone, Debug, Hash, Eq, PartialEq, Serialize, Deserialize)]
+pub struct ScriptFunction {
+    module: ModuleId,
+    function: Identifier,
+    ty_args: Vec<TypeTag>,
+    args: Vec<TransactionArgument>,
+}
+
+impl ScriptFunction {
+    pub fn new(
+        module: ModuleId,
+        function: Identifier,
+        ty_args: Vec<TypeTag>,
+        args: Vec<TransactionArgument>,
+    ) -> Self {
+        ScriptFunction {
+            module,
+            function,
+            ty_args,
+            args,
+        }
+    }
+
+    pub fn module(&self) -> &ModuleId {
+        &self.module
+    }
+
+    pub fn function(&self) -> &IdentStr {
+        &self.function
+    }
+
+    pub fn ty_args(&self) -> &[TypeTag] {
+        &self.ty_args
+    }
+
+    pub fn args(&self) -> &[TransactionArgument] {
+        &self.args
+    }
+
+    pub fn into_inner(self) -> (ModuleId, Identifier, Vec<TypeTag>, Vec<TransactionArgument>) {
+        (self.module, self.function, self.ty_args, self.args)
+    }
+}
+
 /// How to call a particular Move script (aka. an "ABI").
 #[derive(Clone, Debug, Hash, Eq, PartialEq, Serialize, Deserialize)]
 pub struct ScriptABI {
@@ -69,6 +119,21 @@ pub struct ScriptABI {
     args: Vec<ArgumentABI>,
 }
 
+/// How to call a particular Move script function.
+#[derive(Clone, Debug, Hash, Eq, PartialEq, Serialize, Deserialize)]
+pub struct ScriptFunctionABI {
+    /// The name of the function.
+    name: String,
+    /// The module the function is declared in.
+    module_name: ModuleId,
+    /// Some text comment.
+    doc: String,
+    /// The names of the type arguments.
+    ty_args: Vec<TypeArgumentABI>,
+    /// The description of regular arguments, not including the leading signers.
+    args: Vec<ArgumentABI>,
+}
+
 /// The description of a (regular) argument in a script.
 #[derive(Clone, Debug, Hash, Eq, PartialEq, Serialize, Deserialize)]
 pub struct ArgumentABI {
@@ -124,6 +189,44 @@ impl ScriptABI {
     }
 }
 
+impl ScriptFunctionABI {
+    pub fn new(
+        name: String,
+        module_name: ModuleId,
+        doc: String,
+        ty_args: Vec<TypeArgumentABI>,
+        args: Vec<ArgumentABI>,
+    ) -> Self {
+        Self {
+            name,
+            module_name,
+            doc,
+            ty_args,
+            args,
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn module_name(&self) -> &ModuleId {
+        &self.module_name
+    }
+
+    pub fn doc(&self) -> &str {
+        &self.doc
+    }
+
+    pub fn ty_args(&self) -> &[TypeArgumentABI] {
+        &self.ty_args
+    }
+
+    pub fn args(&self) -> &[ArgumentABI] {
+        &self.args
+    }
+}
+
 impl ArgumentABI {
     pub fn new(name: String, type_tag: TypeTag) -> Self {
         Self { name, type_tag }
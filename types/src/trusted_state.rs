@@ -2,13 +2,16 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
+    account_address::AccountAddress,
+    account_state_blob::AccountStateWithProof,
     epoch_change::{EpochChangeProof, Verifier},
     ledger_info::{LedgerInfo, LedgerInfoWithSignatures},
-    transaction::Version,
+    transaction::{TransactionWithProof, Version},
     waypoint::Waypoint,
 };
 use anyhow::{ensure, format_err, Result};
 use std::{convert::TryFrom, sync::Arc};
+use thiserror::Error;
 
 /// `TrustedState` keeps track of our latest trusted state, including the latest
 /// verified version and the latest verified validator set.
@@ -139,6 +142,83 @@ impl TrustedState {
     pub fn latest_version(&self) -> Version {
         self.verified_state.version()
     }
+
+    /// Verify the state of the account at `address` at `version` in the ledger of `latest_li`, and
+    /// ratchet as `verify_and_ratchet` would. This is everything a light client needs to check
+    /// an account state returned with its proof, e.g. by the JSON-RPC `get_account_state_with_proof`.
+    pub fn verify_account_state<'a>(
+        &self,
+        latest_li: &'a LedgerInfoWithSignatures,
+        epoch_change_proof: &'a EpochChangeProof,
+        account_state: &AccountStateWithProof,
+        version: Version,
+        address: AccountAddress,
+    ) -> std::result::Result<TrustedStateChange<'a>, ProofVerificationError> {
+        let change = self.verify_ledger_version(latest_li, epoch_change_proof, version)?;
+        account_state
+            .verify(latest_li.ledger_info(), version, address)
+            .map_err(ProofVerificationError::InvalidAccountStateProof)?;
+        Ok(change)
+    }
+
+    /// Verify the user transaction sent by `sender` with `sequence_number` at `version` in the
+    /// ledger of `latest_li`, and ratchet as `verify_and_ratchet` would.
+    pub fn verify_transaction_with_proof<'a>(
+        &self,
+        latest_li: &'a LedgerInfoWithSignatures,
+        epoch_change_proof: &'a EpochChangeProof,
+        transaction: &TransactionWithProof,
+        version: Version,
+        sender: AccountAddress,
+        sequence_number: u64,
+    ) -> std::result::Result<TrustedStateChange<'a>, ProofVerificationError> {
+        let change = self.verify_ledger_version(latest_li, epoch_change_proof, version)?;
+        transaction
+            .verify_user_txn(latest_li.ledger_info(), version, sender, sequence_number)
+            .map_err(ProofVerificationError::InvalidTransactionProof)?;
+        Ok(change)
+    }
+
+    fn verify_ledger_version<'a>(
+        &self,
+        latest_li: &'a LedgerInfoWithSignatures,
+        epoch_change_proof: &'a EpochChangeProof,
+        version: Version,
+    ) -> std::result::Result<TrustedStateChange<'a>, ProofVerificationError> {
+        let change = self
+            .verify_and_ratchet(latest_li, epoch_change_proof)
+            .map_err(ProofVerificationError::InvalidLedgerInfo)?;
+        let ledger_version = latest_li.ledger_info().version();
+        if version > ledger_version {
+            return Err(ProofVerificationError::VersionAfterLedgerInfo {
+                version,
+                ledger_version,
+            });
+        }
+        Ok(change)
+    }
+}
+
+/// Why a response could not be verified against a `TrustedState`.
+#[derive(Debug, Error)]
+pub enum ProofVerificationError {
+    #[error("The ledger info does not verify against the trusted state: {}", _0)]
+    /// The ledger info is stale, or is not signed by the validators of its epoch, or the epoch
+    /// change proof leading to its epoch is invalid.
+    InvalidLedgerInfo(anyhow::Error),
+    #[error(
+        "Version {} is after the version of the ledger info ({})",
+        version,
+        ledger_version
+    )]
+    VersionAfterLedgerInfo {
+        version: Version,
+        ledger_version: Version,
+    },
+    #[error("The account state does not verify against the ledger info: {}", _0)]
+    InvalidAccountStateProof(anyhow::Error),
+    #[error("The transaction does not verify against the ledger info: {}", _0)]
+    InvalidTransactionProof(anyhow::Error),
 }
 
 impl From<Waypoint> for TrustedState {
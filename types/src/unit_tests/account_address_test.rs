@@ -0,0 +1,88 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    account_address::{
+        bech32_decode, bech32_encode, parse_address_for_chain, regroup_bits, AccountAddress,
+        Bech32Address,
+    },
+    chain_id::ChainId,
+};
+use std::str::FromStr;
+
+#[test]
+fn test_bech32_test_vectors() {
+    // Valid strings from BIP 173.
+    for valid in &[
+        "A12UEL5L",
+        "abcdef1qpzry9x8gf2tvdw0s3jn54khce6mua7lmqqqxw",
+        "split1checkupstagehandshakeupstreamerranterredcaperred2y9e3w",
+    ] {
+        let (hrp, data) = bech32_decode(valid).unwrap();
+        assert_eq!(bech32_encode(&hrp, &data), valid.to_lowercase());
+    }
+    for invalid in &[
+        "pzry9x0s0muk",
+        "1pzry9x0s0muk",
+        "x1b4n0q5v",
+        "li1dgmt3",
+        "A1G7SGD8",
+    ] {
+        assert!(bech32_decode(invalid).is_err());
+    }
+}
+
+#[test]
+fn test_regroup_bits() {
+    let bytes = vec![0x00, 0x14, 0x75, 0x1e, 0x76, 0xe8, 0x19, 0x91, 0x96, 0xd4];
+    let groups = regroup_bits(&bytes, 8, 5, true).unwrap();
+    assert!(groups.iter().all(|group| *group < 32));
+    assert_eq!(regroup_bits(&groups, 5, 8, false).unwrap(), bytes);
+    // Non-zero padding bits are rejected.
+    assert!(regroup_bits(&[31, 29], 5, 8, false).is_err());
+}
+
+#[test]
+fn test_bech32_address_round_trip() {
+    let address = AccountAddress::random();
+    for id in &[0, 1, 2, 3, 4, 42] {
+        let encoded = Bech32Address::new(ChainId::new(*id), address);
+        let displayed = encoded.to_string();
+        assert!(displayed.starts_with(&Bech32Address::human_readable_part(ChainId::new(*id))));
+        assert_eq!(Bech32Address::from_str(&displayed).unwrap(), encoded);
+    }
+    assert!(Bech32Address::new(ChainId::new(0), address)
+        .to_string()
+        .starts_with("lbr1"));
+    assert!(Bech32Address::new(ChainId::new(2), address)
+        .to_string()
+        .starts_with("tlb1"));
+}
+
+#[test]
+fn test_bech32_address_checksum() {
+    let encoded = Bech32Address::new(ChainId::new(2), AccountAddress::random()).to_string();
+    let last = encoded.chars().last().unwrap();
+    let typo = if last == 'q' { 'p' } else { 'q' };
+    let corrupted = format!("{}{}", &encoded[..encoded.len() - 1], typo);
+    assert!(Bech32Address::from_str(&corrupted).is_err());
+    // Reserved chains can only be encoded with their own prefix.
+    assert!(Bech32Address::from_str(&encoded.replacen("tlb", "lb2", 1)).is_err());
+}
+
+#[test]
+fn test_parse_address_for_chain() {
+    let address = AccountAddress::random();
+    let testnet = ChainId::new(2);
+    let mainnet = ChainId::new(0);
+    let encoded = Bech32Address::new(testnet, address).to_string();
+
+    assert_eq!(parse_address_for_chain(&encoded, testnet).unwrap(), address);
+    assert!(parse_address_for_chain(&encoded, mainnet).is_err());
+    // Raw hex addresses do not name a chain.
+    assert_eq!(
+        parse_address_for_chain(&address.to_string(), mainnet).unwrap(),
+        address
+    );
+    assert!(parse_address_for_chain("", mainnet).is_err());
+}
@@ -0,0 +1,116 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    account_address::AccountAddress,
+    transaction::{
+        argument_validation::{
+            argument_to_move_value, move_value_to_argument, validate_arguments, validate_script,
+            ArgumentError,
+        },
+        ArgumentABI, Script, ScriptABI, TransactionArgument, TypeArgumentABI,
+    },
+};
+use move_core_types::{language_storage::TypeTag, value::MoveValue};
+
+fn payment_abi() -> ScriptABI {
+    ScriptABI::new(
+        "payment".to_string(),
+        String::new(),
+        vec![1, 2, 3],
+        vec![TypeArgumentABI::new("currency".to_string())],
+        vec![
+            ArgumentABI::new("payee".to_string(), TypeTag::Address),
+            ArgumentABI::new("amount".to_string(), TypeTag::U64),
+            ArgumentABI::new(
+                "metadata".to_string(),
+                TypeTag::Vector(Box::new(TypeTag::U8)),
+            ),
+        ],
+    )
+}
+
+fn payment_args() -> Vec<TransactionArgument> {
+    vec![
+        TransactionArgument::Address(AccountAddress::random()),
+        TransactionArgument::U64(10),
+        TransactionArgument::U8Vector(vec![]),
+    ]
+}
+
+#[test]
+fn test_argument_move_value_round_trip() {
+    for arg in payment_args().into_iter().chain(vec![
+        TransactionArgument::U8(1),
+        TransactionArgument::U128(2),
+        TransactionArgument::Bool(true),
+    ]) {
+        assert_eq!(
+            move_value_to_argument(&argument_to_move_value(&arg)).unwrap(),
+            arg
+        );
+    }
+    assert!(move_value_to_argument(&MoveValue::Vector(vec![MoveValue::U64(1)])).is_err());
+    assert!(move_value_to_argument(&MoveValue::Signer(AccountAddress::random())).is_err());
+}
+
+#[test]
+fn test_validate_arguments() {
+    let abi = payment_abi();
+    assert_eq!(
+        validate_arguments(&abi, &[TypeTag::U8], &payment_args()),
+        Ok(())
+    );
+    assert_eq!(
+        validate_script(
+            &abi,
+            &Script::new(vec![1, 2, 3], vec![TypeTag::U8], payment_args())
+        ),
+        Ok(())
+    );
+
+    assert!(matches!(
+        validate_arguments(&abi, &[], &payment_args()),
+        Err(ArgumentError::TypeArgumentCountMismatch {
+            expected: 1,
+            actual: 0,
+            ..
+        })
+    ));
+    assert!(matches!(
+        validate_arguments(&abi, &[TypeTag::U8], &payment_args()[..2]),
+        Err(ArgumentError::ArgumentCountMismatch {
+            expected: 3,
+            actual: 2,
+            ..
+        })
+    ));
+    let mut args = payment_args();
+    args[1] = TransactionArgument::U128(10);
+    assert!(matches!(
+        validate_arguments(&abi, &[TypeTag::U8], &args),
+        Err(ArgumentError::ArgumentTypeMismatch { position: 1, .. })
+    ));
+    // Only `vector<u8>` parameters take byte vectors.
+    let batch_abi = ScriptABI::new(
+        "batch".to_string(),
+        String::new(),
+        vec![],
+        vec![],
+        vec![ArgumentABI::new(
+            "amounts".to_string(),
+            TypeTag::Vector(Box::new(TypeTag::U64)),
+        )],
+    );
+    assert!(matches!(
+        validate_arguments(&batch_abi, &[], &[TransactionArgument::U8Vector(vec![1])]),
+        Err(ArgumentError::ArgumentTypeMismatch { position: 0, .. })
+    ));
+    assert!(matches!(
+        validate_script(
+            &abi,
+            &Script::new(vec![4], vec![TypeTag::U8], payment_args())
+        ),
+        Err(ArgumentError::CodeMismatch { .. })
+    ));
+}
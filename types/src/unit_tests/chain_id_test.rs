@@ -0,0 +1,46 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::chain_id::{register_custom_chain, ChainId, NamedChain};
+use std::str::FromStr;
+
+#[test]
+fn test_reserved_chain_display_round_trip() {
+    for reserved_chain in NamedChain::ALL.iter() {
+        let chain_id = ChainId::from_str(reserved_chain.name()).unwrap();
+        assert_eq!(
+            NamedChain::from_chain_id(&chain_id).unwrap().name(),
+            reserved_chain.name()
+        );
+        assert_eq!(ChainId::from_str(&chain_id.to_string()).unwrap(), chain_id);
+    }
+    assert_eq!(ChainId::new(2).to_string(), "TESTNET(2)");
+    assert_eq!(ChainId::new(200).to_string(), "200");
+    assert_eq!(ChainId::from_str("200").unwrap(), ChainId::new(200));
+    assert!(NamedChain::from_chain_id(&ChainId::new(200)).is_err());
+}
+
+#[test]
+fn test_display_form_must_be_consistent() {
+    assert!(ChainId::from_str("TESTNET(3)").is_err());
+    assert!(ChainId::from_str("UNKNOWN(3)").is_err());
+    assert!(ChainId::from_str("").is_err());
+}
+
+#[test]
+fn test_custom_chain() {
+    register_custom_chain("PRIVATENET", 42).unwrap();
+    let chain_id = ChainId::from_str("PRIVATENET").unwrap();
+    assert_eq!(chain_id, ChainId::new(42));
+    assert_eq!(chain_id.to_string(), "PRIVATENET(42)");
+    assert_eq!(ChainId::from_str("PRIVATENET(42)").unwrap(), chain_id);
+    // Custom chains are not reserved chains.
+    assert!(NamedChain::from_chain_id(&chain_id).is_err());
+
+    // Names and IDs can only be taken once, and reserved ones never.
+    assert!(register_custom_chain("PRIVATENET", 43).is_err());
+    assert!(register_custom_chain("OTHERNET", 42).is_err());
+    assert!(register_custom_chain("TESTNET", 44).is_err());
+    assert!(register_custom_chain("OTHERNET", 2).is_err());
+    assert!(register_custom_chain("othernet", 45).is_err());
+}
@@ -2,11 +2,15 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
-    contract_event::{ContractEvent, EventWithProof},
+    account_address::AccountAddress,
+    account_config::{ReceivedPaymentEvent, SentPaymentEvent},
+    contract_event::{decode_any_event, decode_event, ContractEvent, EventWithProof, LibraEvent},
     event::EventKey,
 };
 use lcs::test_helpers::assert_canonical_encode_decode;
-use move_core_types::language_storage::TypeTag;
+use move_core_types::{
+    identifier::Identifier, language_storage::TypeTag, move_resource::MoveResource,
+};
 use proptest::prelude::*;
 
 proptest! {
@@ -30,3 +34,37 @@ fn test_event_json_serialize() {
     let contract_event2: ContractEvent = serde_json::from_str(contract_json.as_str()).unwrap();
     assert_eq!(contract_event, contract_event2)
 }
+
+#[test]
+fn test_decode_events() {
+    let receiver = AccountAddress::random();
+    let payment =
+        SentPaymentEvent::new(10, Identifier::new("Coin1").unwrap(), receiver, vec![1, 2]);
+    let event = ContractEvent::new(
+        EventKey::random(),
+        0,
+        TypeTag::Struct(SentPaymentEvent::struct_tag()),
+        lcs::to_bytes(&payment).unwrap(),
+    );
+
+    let decoded: SentPaymentEvent = decode_event(&event).unwrap();
+    assert_eq!(decoded.receiver(), receiver);
+    assert_eq!(decoded.amount(), 10);
+    assert!(decode_event::<ReceivedPaymentEvent>(&event).is_err());
+    match decode_any_event(&event).unwrap() {
+        Some(LibraEvent::SentPayment(decoded)) => assert_eq!(decoded.metadata(), &vec![1, 2]),
+        other => panic!("Unexpected event {:?}", other),
+    }
+    assert!(LibraEvent::type_tags().contains(event.type_tag()));
+
+    // Events of other types are not decoded, and events with bad data are errors.
+    let other_event = ContractEvent::new(EventKey::random(), 0, TypeTag::Address, vec![0u8]);
+    assert!(decode_any_event(&other_event).unwrap().is_none());
+    let bad_event = ContractEvent::new(
+        EventKey::random(),
+        0,
+        TypeTag::Struct(SentPaymentEvent::struct_tag()),
+        vec![0u8],
+    );
+    assert!(decode_any_event(&bad_event).is_err());
+}
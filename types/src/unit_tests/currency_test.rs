@@ -0,0 +1,25 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::account_config::{coin1_tag, lbr_type_tag, Currency};
+use move_core_types::language_storage::TypeTag;
+use std::{convert::TryFrom, str::FromStr};
+
+#[test]
+fn test_currency_round_trip() {
+    for currency in Currency::ALL.iter() {
+        assert_eq!(&Currency::from_str(currency.code()).unwrap(), currency);
+        assert_eq!(&Currency::try_from(&currency.type_tag()).unwrap(), currency);
+        assert_eq!(currency.currency_code().as_str(), currency.code());
+    }
+    assert_eq!(Currency::LBR.type_tag(), lbr_type_tag());
+    assert_eq!(Currency::Coin1.type_tag(), coin1_tag());
+    assert_eq!(String::from(Currency::Coin2), "Coin2");
+}
+
+#[test]
+fn test_unknown_currency() {
+    assert!(Currency::from_str("Coin3").is_err());
+    assert!(Currency::from_str("lbr").is_err());
+    assert!(Currency::try_from(&TypeTag::U64).is_err());
+}
@@ -2,10 +2,15 @@
 // SPDX-License-Identifier: Apache-2.0
 
 mod access_path_test;
+mod account_address_test;
+mod argument_validation_test;
 mod block_metadata_test;
 mod canonical_serialization_examples;
+mod chain_id_test;
 mod code_debug_fmt_test;
 mod contract_event_test;
+mod currency_test;
+mod on_chain_config_test;
 mod transaction_test;
 mod trusted_state_test;
 mod validator_set_test;
@@ -0,0 +1,77 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::on_chain_config::{
+    new_epoch_event_key, ConfigID, LibraVersion, OnChainConfig, OnChainConfigPayload,
+    OnChainConsensusConfig, ProposerElectionType, VMConfig, ValidatorSet,
+};
+use std::{collections::HashMap, sync::Arc};
+
+fn payload(epoch: u64, configs: Vec<(ConfigID, Vec<u8>)>) -> OnChainConfigPayload {
+    OnChainConfigPayload::new(
+        epoch,
+        Arc::new(configs.into_iter().collect::<HashMap<_, _>>()),
+    )
+}
+
+#[test]
+fn test_config_metadata() {
+    assert_eq!(
+        LibraVersion::access_path(),
+        LibraVersion::CONFIG_ID.access_path()
+    );
+    assert_eq!(VMConfig::reconfiguration_event_key(), new_epoch_event_key());
+}
+
+#[test]
+fn test_config_delta() {
+    let previous = payload(
+        1,
+        vec![
+            (LibraVersion::CONFIG_ID, vec![1]),
+            (VMConfig::CONFIG_ID, vec![2]),
+        ],
+    );
+    let current = payload(
+        2,
+        vec![
+            (LibraVersion::CONFIG_ID, vec![3]),
+            (VMConfig::CONFIG_ID, vec![2]),
+            (ValidatorSet::CONFIG_ID, vec![4]),
+        ],
+    );
+
+    let delta = current.delta(&previous);
+    assert_eq!(delta.epoch(), 2);
+    assert!(delta.is_changed::<LibraVersion>());
+    assert!(!delta.is_changed::<VMConfig>());
+    assert!(delta.is_changed::<ValidatorSet>());
+    assert_eq!(delta.changed().count(), 2);
+
+    // Removed configs are changes too.
+    let delta = previous.delta(&current);
+    assert!(delta.is_changed::<ValidatorSet>());
+    assert!(current.delta(&current).is_empty());
+}
+
+#[test]
+fn test_consensus_config_deserialization() {
+    let proposer_election_type = ProposerElectionType::LeaderReputation {
+        active_weights: 99,
+        inactive_weights: 1,
+    };
+    // stored on chain as the serialized proposer election type
+    let bytes = lcs::to_bytes(&lcs::to_bytes(&proposer_election_type).unwrap()).unwrap();
+    let payload = payload(1, vec![(OnChainConsensusConfig::CONFIG_ID, bytes)]);
+    assert_eq!(
+        payload.get::<OnChainConsensusConfig>().unwrap(),
+        OnChainConsensusConfig {
+            proposer_election_type
+        }
+    );
+    assert!(payload.get::<VMConfig>().is_err());
+    assert_eq!(payload.get_optional::<VMConfig>().unwrap(), None);
+
+    let malformed = self::payload(1, vec![(OnChainConsensusConfig::CONFIG_ID, vec![1, 2])]);
+    assert!(malformed.get_optional::<OnChainConsensusConfig>().is_err());
+}
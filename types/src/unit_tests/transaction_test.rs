@@ -5,18 +5,145 @@ use crate::{
     account_address::AccountAddress,
     account_config::LBR_NAME,
     chain_id::ChainId,
+    test_helpers::transaction_test_helpers::{
+        get_test_signed_transaction, ChainAwareTransactionBuilder,
+    },
     transaction::{
-        RawTransaction, Script, SignedTransaction, Transaction, TransactionInfo,
-        TransactionListWithProof, TransactionPayload, TransactionWithProof,
+        PreValidationError, RawTransaction, Script, SignedTransaction, Transaction,
+        TransactionInfo, TransactionListWithProof, TransactionPayload, TransactionWithProof,
     },
+    vm_status::StatusCode,
 };
 use lcs::test_helpers::assert_canonical_encode_decode;
 use libra_crypto::{
     ed25519::{self, Ed25519PrivateKey, Ed25519Signature},
     PrivateKey, Uniform,
 };
+use move_core_types::gas_schedule::{GasAlgebra, GasConstants};
 use proptest::prelude::*;
-use std::convert::TryFrom;
+use std::{
+    convert::TryFrom,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+#[test]
+fn test_pre_validate() {
+    let private_key = Ed25519PrivateKey::generate_for_testing();
+    let gas_constants = GasConstants::default();
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_secs();
+    let txn = |expiration_time, gas_unit_price, gas_currency_code: &str, max_gas_amount| {
+        get_test_signed_transaction(
+            AccountAddress::random(),
+            0,
+            &private_key,
+            private_key.public_key(),
+            None,
+            expiration_time,
+            gas_unit_price,
+            gas_currency_code.to_owned(),
+            Some(max_gas_amount),
+        )
+    };
+    let pre_validate = |txn: SignedTransaction| txn.pre_validate(&gas_constants, &ChainId::test());
+
+    assert_eq!(pre_validate(txn(now + 100, 0, LBR_NAME, 1_000_000)), Ok(()));
+    assert_eq!(
+        pre_validate(txn(
+            now + 100,
+            0,
+            LBR_NAME,
+            gas_constants.maximum_number_of_gas_units.get() + 1
+        )),
+        Err(PreValidationError::Status(
+            StatusCode::MAX_GAS_UNITS_EXCEEDS_MAX_GAS_UNITS_BOUND
+        ))
+    );
+    assert_eq!(
+        pre_validate(txn(now + 100, 0, LBR_NAME, 1)),
+        Err(PreValidationError::Status(
+            StatusCode::MAX_GAS_UNITS_BELOW_MIN_TRANSACTION_GAS_UNITS
+        ))
+    );
+    assert_eq!(
+        pre_validate(txn(
+            now + 100,
+            gas_constants.max_price_per_gas_unit.get() + 1,
+            LBR_NAME,
+            1_000_000
+        )),
+        Err(PreValidationError::Status(
+            StatusCode::GAS_UNIT_PRICE_ABOVE_MAX_BOUND
+        ))
+    );
+    assert_eq!(
+        pre_validate(txn(now + 100, 0, "not a currency", 1_000_000)),
+        Err(PreValidationError::Status(
+            StatusCode::INVALID_GAS_SPECIFIER
+        ))
+    );
+    assert_eq!(
+        pre_validate(txn(now - 1, 0, LBR_NAME, 1_000_000)),
+        Err(PreValidationError::Status(StatusCode::TRANSACTION_EXPIRED))
+    );
+
+    let large_script = Script::new(vec![0; 5000], vec![], vec![]);
+    let large_txn = get_test_signed_transaction(
+        AccountAddress::random(),
+        0,
+        &private_key,
+        private_key.public_key(),
+        Some(large_script),
+        now + 100,
+        0,
+        LBR_NAME.to_owned(),
+        None,
+    );
+    assert_eq!(
+        pre_validate(large_txn),
+        Err(PreValidationError::Status(
+            StatusCode::EXCEEDED_MAX_TRANSACTION_SIZE
+        ))
+    );
+
+    let other_chain_id = ChainId::new(ChainId::test().id() + 1);
+    assert_eq!(
+        txn(now + 100, 0, LBR_NAME, 1_000_000).pre_validate(&gas_constants, &other_chain_id),
+        Err(PreValidationError::BadChainId {
+            expected: other_chain_id,
+            actual: ChainId::test(),
+        })
+    );
+}
+
+#[test]
+fn test_chain_aware_builder_rejects_other_chains() {
+    let private_key = Ed25519PrivateKey::generate_for_testing();
+    let sender = AccountAddress::random();
+    let payload = TransactionPayload::Script(Script::new(vec![], vec![], vec![]));
+    let testnet = ChainAwareTransactionBuilder::new(ChainId::new(2));
+    let devnet = ChainAwareTransactionBuilder::new(ChainId::new(3));
+
+    let txn = testnet
+        .sign(
+            testnet.raw_transaction(sender, 0, payload.clone()),
+            &private_key,
+            private_key.public_key(),
+        )
+        .unwrap();
+    assert_eq!(txn.chain_id(), ChainId::new(2));
+    assert!(txn.check_signature().is_ok());
+
+    assert!(devnet
+        .sign(
+            testnet.raw_transaction(sender, 0, payload),
+            &private_key,
+            private_key.public_key(),
+        )
+        .is_err());
+}
 
 #[test]
 fn test_invalid_signature() {
@@ -3,12 +3,13 @@
 
 use crate::{
     account_address::AccountAddress,
+    account_state_blob::AccountStateWithProof,
     block_info::BlockInfo,
     epoch_change::EpochChangeProof,
     epoch_state::EpochState,
     ledger_info::{LedgerInfo, LedgerInfoWithSignatures},
     transaction::Version,
-    trusted_state::{TrustedState, TrustedStateChange},
+    trusted_state::{ProofVerificationError, TrustedState, TrustedStateChange},
     validator_signer::ValidatorSigner,
     validator_verifier::{random_validator_verifier, ValidatorConsensusInfo, ValidatorVerifier},
     waypoint::Waypoint,
@@ -286,6 +287,61 @@ proptest! {
         };
     }
 
+    #[test]
+    fn test_verify_account_state_reports_reason(
+        (_vsets, lis_with_sigs, latest_li) in arb_update_proof(
+            10,   /* start epoch */
+            123,  /* start version */
+            1..3, /* version delta */
+            1..3, /* epoch changes */
+            1..5, /* validators per epoch */
+        ),
+        account_state in any::<AccountStateWithProof>(),
+    ) {
+        let waypoint = Waypoint::new_epoch_boundary(lis_with_sigs.first().unwrap().ledger_info())
+            .unwrap();
+        let trusted_state = TrustedState::from(waypoint);
+        let change_proof = EpochChangeProof::new(lis_with_sigs, false /* more */);
+        let ledger_version = latest_li.ledger_info().version();
+        let address = AccountAddress::random();
+
+        let result = trusted_state.verify_account_state(
+            &latest_li,
+            &change_proof,
+            &account_state,
+            ledger_version + 1,
+            address,
+        );
+        prop_assert!(matches!(
+            result,
+            Err(ProofVerificationError::VersionAfterLedgerInfo { .. })
+        ));
+        // A proof for some other ledger does not verify.
+        let result = trusted_state.verify_account_state(
+            &latest_li,
+            &change_proof,
+            &account_state,
+            ledger_version,
+            address,
+        );
+        prop_assert!(matches!(
+            result,
+            Err(ProofVerificationError::InvalidAccountStateProof(_))
+        ));
+        // Nor does anything against a ledger info that does not verify.
+        let result = trusted_state.verify_account_state(
+            &latest_li,
+            &EpochChangeProof::new(vec![], false /* more */),
+            &account_state,
+            ledger_version,
+            address,
+        );
+        prop_assert!(matches!(
+            result,
+            Err(ProofVerificationError::InvalidLedgerInfo(_))
+        ));
+    }
+
     #[test]
     fn test_ratchet_with_partial_trusted_prefix(
         (_vsets, lis_with_sigs, latest_li) in arb_update_proof(
@@ -9,7 +9,7 @@ use libra_types::{
     on_chain_config::{LibraVersion, OnChainConfigPayload, VMConfig},
     transaction::{SignedTransaction, VMValidatorResult},
 };
-use libra_vm::LibraVMValidator;
+use libra_vm::{LibraVMValidator, ValidationMode};
 use scratchpad::SparseMerkleTree;
 use std::{convert::TryFrom, sync::Arc};
 use storage_interface::{state_view::VerifiedStateView, DbReader};
@@ -49,6 +49,12 @@ impl VMValidator {
         let vm = LibraVMValidator::new(&state_view);
         VMValidator { db_reader, vm }
     }
+
+    /// Sets how thoroughly transactions are validated. The mode is kept across restarts.
+    pub fn with_validation_mode(mut self, mode: ValidationMode) -> Self {
+        self.vm = self.vm.with_validation_mode(mode);
+        self
+    }
 }
 
 impl TransactionValidation for VMValidator {
@@ -79,7 +85,8 @@ impl TransactionValidation for VMValidator {
         let vm_config = config.get::<VMConfig>()?;
         let version = config.get::<LibraVersion>()?;
 
-        self.vm = LibraVMValidator::init_with_config(version, vm_config);
+        self.vm = LibraVMValidator::init_with_config(version, vm_config)
+            .with_validation_mode(self.vm.validation_mode());
         Ok(())
     }
 }